@@ -1,10 +1,15 @@
-use clap::{Args, Parser, Subcommand};
+use std::time::Duration;
+
+use clap::{ArgMatches, Args, Parser, Subcommand};
 use clap_complete::Shell;
+use melos_core::runner::RetryPolicy;
 
 use crate::commands::{
-    analyze::AnalyzeArgs, build::BuildArgs, exec::ExecArgs, format::FormatArgs, health::HealthArgs,
-    init::InitArgs, list::ListArgs, pub_cmds::PubArgs, publish::PublishArgs, run::RunArgs,
-    test::TestArgs, version::VersionArgs,
+    analyze::AnalyzeArgs, build::BuildArgs, cache::CacheArgs,
+    check_constraints::CheckConstraintsArgs, doctor::DoctorArgs, exec::ExecArgs,
+    format::FormatArgs, graph::GraphArgs, health::HealthArgs, init::InitArgs, list::ListArgs,
+    pub_cmds::PubArgs, publish::PublishArgs, run::RunArgs, selftest::SelftestArgs, test::TestArgs,
+    version::VersionArgs,
 };
 
 /// melos-rs: A Rust CLI for Flutter/Dart monorepo management
@@ -25,6 +30,56 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub sdk_path: Option<String>,
 
+    /// Path to the workspace directory (defaults to the current directory)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub workspace: Option<String>,
+
+    /// Load this config file instead of searching for one; its directory is the workspace root
+    #[arg(long, global = true, value_name = "PATH", conflicts_with = "workspace")]
+    pub config: Option<String>,
+
+    /// Maximum width of `[package]` output prefixes; longer names are truncated with `…`
+    #[arg(long, global = true, default_value_t = crate::render::DEFAULT_MAX_NAME_WIDTH)]
+    pub max_name_width: usize,
+
+    /// Replace matches of this regex in command output with `***` (can be repeated)
+    #[arg(long, global = true, value_name = "REGEX")]
+    pub redact: Vec<String>,
+
+    /// Redact common token shapes by preset name (comma-separated: aws, github)
+    #[arg(long, global = true, value_delimiter = ',', value_name = "PRESETS")]
+    pub redact_preset: Vec<String>,
+
+    /// How concurrent package output is printed: live and interleaved, or
+    /// grouped per package once it finishes
+    #[arg(long, global = true, value_enum, default_value_t = crate::render::StreamMode::Interleaved)]
+    pub stream_mode: crate::render::StreamMode,
+
+    /// With `--stream-mode buffered`, keep only the last N lines of each
+    /// package's output (older lines are dropped with a marker); 0 keeps all
+    #[arg(long, global = true, value_name = "N", default_value_t = crate::render::DEFAULT_MAX_LOG_LINES)]
+    pub max_log_lines: usize,
+
+    /// Print nothing for packages that succeed; dump the full output of
+    /// packages that fail once they finish (the summary is still printed)
+    #[arg(long, global = true)]
+    pub quiet_failures: bool,
+
+    /// When to use colors; `always` also sets FORCE_COLOR/CLICOLOR_FORCE (and
+    /// passes `--color` to `dart test`) so captured child output keeps colors
+    #[arg(long, global = true, value_enum, default_value_t = crate::render::ColorChoice::Auto)]
+    pub color: crate::render::ColorChoice,
+
+    /// Stable, color-free output for scripts: one `name<TAB>status<TAB>duration_ms`
+    /// record per package on stdout; package output goes to stderr
+    #[arg(long, global = true)]
+    pub porcelain: bool,
+
+    /// Do not take the workspace lock that bootstrap, clean, version and
+    /// publish hold to keep concurrent invocations from racing
+    #[arg(long, global = true)]
+    pub no_lock: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -57,11 +112,13 @@ impl Cli {
 /// <https://melos.invertase.dev/~melos-latest/filters>
 #[derive(Args, Debug, Clone, Default)]
 pub struct GlobalFilterArgs {
-    /// Include only packages with names matching the glob pattern (can be repeated)
+    /// Include only packages with names matching the glob pattern (can be repeated;
+    /// brace groups expand, e.g. `app_{ios,android}`)
     #[arg(long, global = true)]
     pub scope: Vec<String>,
 
-    /// Exclude packages with names matching the glob pattern (can be repeated)
+    /// Exclude packages with names matching the glob pattern (can be repeated;
+    /// brace groups expand, e.g. `{example,demo}_*`)
     #[arg(long, global = true)]
     pub ignore: Vec<String>,
 
@@ -73,20 +130,30 @@ pub struct GlobalFilterArgs {
     #[arg(long, global = true, conflicts_with = "diff")]
     pub since: Option<String>,
 
-    /// Only include packages where the given directory exists
-    #[arg(long = "dir-exists", global = true)]
-    pub dir_exists: Option<String>,
+    /// Only include packages changed since the most recent git tag
+    /// (--diff/--since becomes the fallback when no tags exist)
+    #[arg(long, global = true)]
+    pub only_changed_since_tag: bool,
+
+    /// Only include packages where the given directory exists (can be repeated; all must exist)
+    #[arg(long = "dir-exists", global = true, value_name = "PATH")]
+    pub dir_exists: Vec<String>,
 
-    /// Only include packages where the given file exists
-    #[arg(long = "file-exists", global = true)]
-    pub file_exists: Option<String>,
+    /// Only include packages where the given file exists (can be repeated; all must exist)
+    #[arg(long = "file-exists", global = true, value_name = "PATH")]
+    pub file_exists: Vec<String>,
 
     /// Only include Flutter packages
     #[arg(long, global = true)]
     pub flutter: bool,
 
     /// Only include pure Dart packages (exclude Flutter)
-    #[arg(long, global = true)]
+    #[arg(
+        long,
+        global = true,
+        visible_alias = "dart",
+        conflicts_with = "flutter"
+    )]
     pub no_flutter: bool,
 
     /// Only include packages that depend on the given package (can be repeated)
@@ -109,6 +176,11 @@ pub struct GlobalFilterArgs {
     #[arg(long, global = true)]
     pub include_dependencies: bool,
 
+    /// With --include-dependencies, add only direct dependencies of matched
+    /// packages, not the dependencies those pull in transitively
+    #[arg(long, global = true, requires = "include_dependencies")]
+    pub exclude_dependencies: bool,
+
     /// Also include transitive dependents of matched packages
     #[arg(long, global = true)]
     pub include_dependents: bool,
@@ -120,6 +192,10 @@ pub struct GlobalFilterArgs {
     /// Only include non-published/private packages
     #[arg(long, global = true, conflicts_with = "published")]
     pub no_published: bool,
+
+    /// Only include private packages (publish_to: none)
+    #[arg(long, global = true, conflicts_with_all = ["no_private", "published"])]
+    pub private_only: bool,
 }
 
 impl GlobalFilterArgs {
@@ -128,7 +204,8 @@ impl GlobalFilterArgs {
         self.diff.as_deref().or(self.since.as_deref())
     }
 
-    /// Returns the flutter filter: Some(true) for --flutter, Some(false) for --no-flutter, None if neither
+    /// Returns the flutter filter: Some(true) for --flutter, Some(false) for
+    /// --no-flutter/--dart, None if neither
     pub fn flutter_filter(&self) -> Option<bool> {
         match (self.flutter, self.no_flutter) {
             (true, _) => Some(true),
@@ -137,9 +214,10 @@ impl GlobalFilterArgs {
         }
     }
 
-    /// Returns the published filter: Some(true) for --published, Some(false) for --no-published, None if neither
+    /// Returns the published filter: Some(true) for --published, Some(false) for
+    /// --no-published or --private-only, None if neither
     pub fn published_filter(&self) -> Option<bool> {
-        match (self.published, self.no_published) {
+        match (self.published, self.no_published || self.private_only) {
             (true, _) => Some(true),
             (_, true) => Some(false),
             _ => None,
@@ -147,6 +225,71 @@ impl GlobalFilterArgs {
     }
 }
 
+/// Retry and deadline flags for commands that run a process in each package.
+#[derive(Args, Debug, Clone, Default)]
+pub struct RetryArgs {
+    /// Retry a failed package command up to N more times
+    #[arg(long, default_value_t = 0)]
+    pub retries: usize,
+
+    /// Delay in milliseconds before the first retry (doubles on each attempt)
+    #[arg(long, default_value_t = 1000)]
+    pub retry_backoff_ms: u64,
+
+    /// Randomize retry delays so packages don't retry in lockstep
+    #[arg(long)]
+    pub retry_jitter: bool,
+
+    /// Wall-time budget in seconds for the whole command; packages not yet
+    /// finished when it elapses are killed or skipped
+    #[arg(long, value_name = "SECS")]
+    pub deadline: Option<u64>,
+}
+
+impl RetryArgs {
+    /// Build the runner retry policy from the flags
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.retries,
+            backoff: Duration::from_millis(self.retry_backoff_ms),
+            jitter: self.retry_jitter,
+        }
+    }
+
+    /// The command deadline, if one was requested (0 = no deadline)
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Whether any retry/deadline flag differs from its default
+    pub fn is_set(&self) -> bool {
+        self.retries > 0 || self.deadline.is_some()
+    }
+}
+
+/// Clap value parser for `-c/--concurrency`: a number, `auto` or `auto/N`.
+pub fn parse_concurrency_arg(value: &str) -> Result<usize, String> {
+    melos_core::runner::parse_concurrency(value)
+}
+
+/// The `auto` concurrency spec passed to the invoked (sub)command and the
+/// value it resolves to, if any.
+pub fn auto_concurrency(matches: &ArgMatches) -> Option<(String, usize)> {
+    let mut level = Some(matches);
+    let mut spec = None;
+    while let Some(m) = level {
+        if let Ok(Some(mut raw)) = m.try_get_raw("concurrency") {
+            spec = raw.next().and_then(|v| v.to_str()).map(str::trim);
+        }
+        level = m.subcommand().map(|(_, sub)| sub);
+    }
+    let spec = spec.filter(|s| s.starts_with("auto"))?;
+    let concurrency = melos_core::runner::parse_concurrency(spec).ok()?;
+    Some((spec.to_string(), concurrency))
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Run static analysis across packages using `dart analyze`
@@ -159,18 +302,30 @@ pub enum Commands {
     /// Build Flutter apps for Android and/or iOS with declarative config
     Build(BuildArgs),
 
+    /// Inspect or clear melos-rs's own cache files (`.dart_tool/melos_*`)
+    Cache(CacheArgs),
+
+    /// Check that workspace dependency constraints match the packages' actual versions
+    CheckConstraints(CheckConstraintsArgs),
+
     /// Clean all packages (runs `flutter clean` in each)
     Clean(CleanArgs),
 
     /// Generate shell completion scripts
     Completion(CompletionArgs),
 
+    /// Check the environment: SDKs, git, config and pubspec_overrides.yaml
+    Doctor(DoctorArgs),
+
     /// Execute a command in each package
     Exec(ExecArgs),
 
     /// Format Dart code across packages using `dart format`
     Format(FormatArgs),
 
+    /// Print the workspace dependency graph as Graphviz DOT or JSON
+    Graph(GraphArgs),
+
     /// Run workspace health checks (version drift, missing fields, SDK consistency)
     Health(HealthArgs),
 
@@ -189,6 +344,9 @@ pub enum Commands {
     /// Run a script defined in melos.yaml
     Run(RunArgs),
 
+    /// Print environment diagnostics (versions, OS, workspace) for bug reports
+    Selftest(SelftestArgs),
+
     /// Run tests across packages using `dart test` / `flutter test`
     Test(TestArgs),
 
@@ -196,23 +354,20 @@ pub enum Commands {
     Version(VersionArgs),
 
     /// Launch the interactive terminal UI (requires melos-tui binary)
-    Tui(TuiArgs),
-}
+    Tui,
 
-/// Arguments for the `tui` command
-#[derive(Args, Debug)]
-pub struct TuiArgs {
-    /// Path to workspace directory (defaults to current directory)
-    #[arg(long)]
-    pub workspace: Option<String>,
+    /// Any other name: a workspace script, or a `melos-rs-<name>` plugin on PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 /// Arguments for the `bootstrap` command
 #[derive(Args, Debug)]
 pub struct BootstrapArgs {
     /// Number of concurrent pub get processes
-    #[arg(short = 'c', long, default_value_t = 5)]
-    pub concurrency: usize,
+    /// (default: `command.bootstrap.concurrency`, else 5)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
 
     /// Enforce the pubspec.lock file (pass --enforce-lockfile to pub get)
     #[arg(long)]
@@ -234,6 +389,24 @@ pub struct BootstrapArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Skip pub get in packages whose pubspec.yaml and workspace config are
+    /// unchanged since their last bootstrap
+    #[arg(long)]
+    pub skip_if_unchanged: bool,
+
+    /// Run pub get in every package even if --skip-if-unchanged finds it unchanged
+    #[arg(long)]
+    pub force: bool,
+
+    /// Only check that each pubspec_overrides.yaml matches what bootstrap
+    /// would generate; print the differences and exit non-zero if any are
+    /// stale. Writes nothing and skips pub get
+    #[arg(long, conflicts_with = "dry_run")]
+    pub validate_only: bool,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
@@ -245,7 +418,8 @@ pub struct CleanArgs {
     #[arg(long)]
     pub deep: bool,
 
-    /// Show what would be cleaned without actually removing anything
+    /// List the absolute paths that would be removed, per package, without
+    /// removing anything (hooks still run with MELOS_CLEAN_DRY_RUN=true)
     #[arg(long)]
     pub dry_run: bool,
 
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -7,20 +8,34 @@ use tokio::sync::Semaphore;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
-use crate::render::{create_progress_bar, spawn_renderer};
+use crate::render::{RenderSettings, create_progress_bar, spawn_renderer};
+use melos_core::commands::PackageResults;
 use melos_core::commands::analyze::{
-    AnalyzeOpts, assemble_dry_run_scan, build_fix_command, format_conflict_warnings,
-    parse_dry_run_output,
+    AnalyzeCache, AnalyzeOpts, DryRunScan, DryRunSummary, analyze_cache_key, assemble_dry_run_scan,
+    build_fix_command, dart_sdk_version, format_checkstyle, format_conflict_warnings,
+    package_cache_hashes, parse_analyzer_output, parse_dry_run_output, parse_dry_run_summary,
 };
+use melos_core::events::Event;
+use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
 use melos_core::runner::shell_command;
 use melos_core::workspace::Workspace;
 
+/// Output format for the analyze command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum AnalyzeFormat {
+    /// Live per-package analyzer output
+    #[default]
+    Default,
+    /// Checkstyle XML report on stdout, for CI annotations
+    Checkstyle,
+}
+
 /// Arguments for the `analyze` command
 #[derive(Args, Debug)]
 pub struct AnalyzeArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     /// Report fatal warnings as errors
@@ -36,23 +51,33 @@ pub struct AnalyzeArgs {
     pub no_fatal: bool,
 
     /// Run `dart fix --apply` in each package before analyzing
-    #[arg(long, conflicts_with = "dry_run")]
+    /// (with --dry-run: preview per-package fix counts instead)
+    #[arg(long)]
     pub fix: bool,
 
     /// Preview fixes with `dart fix --dry-run` (no changes applied, skips analysis)
-    #[arg(long, conflicts_with = "fix")]
+    #[arg(long)]
     pub dry_run: bool,
 
     /// Apply fixes only for specific diagnostic codes (comma-separated, requires --fix or --dry-run)
     #[arg(long, value_delimiter = ',')]
     pub code: Vec<String>,
 
+    /// Analyze every package, ignoring the cache of unchanged packages that
+    /// passed last time
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Output format; `checkstyle` prints only the XML report on stdout
+    #[arg(long, value_enum, default_value_t = AnalyzeFormat::Default, conflicts_with_all = ["fix", "dry_run"])]
+    pub format: AnalyzeFormat,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
 
 /// Run `dart analyze` across all matching packages
-pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: AnalyzeArgs, render: RenderSettings) -> Result<()> {
     // --code requires --fix or --dry-run
     if !args.code.is_empty() && !args.fix && !args.dry_run {
         anyhow::bail!("--code requires --fix or --dry-run");
@@ -66,6 +91,10 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
         &workspace.config.categories,
     )?;
 
+    if args.format == AnalyzeFormat::Checkstyle {
+        return run_checkstyle(workspace, &packages, &args).await;
+    }
+
     if packages.is_empty() {
         println!("{}", "No packages matched the given filters.".yellow());
         return Ok(());
@@ -90,9 +119,10 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
     }
     println!();
 
-    // --dry-run: preview fixes, parse output, display consolidated results
+    // --dry-run: preview fixes, parse output, display consolidated results.
+    // Nothing is applied and analysis is skipped.
     if args.dry_run {
-        let scan = scan_dry_run(
+        let (scan, summaries) = scan_dry_run(
             &packages,
             workspace,
             args.concurrency,
@@ -100,47 +130,15 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
             "scanning for conflicts",
         )
         .await?;
-
-        if scan.entries.is_empty() {
-            println!("{}", "Nothing to fix!".green());
-        } else {
-            for entry in &scan.entries {
-                println!("{}", entry.path);
-                for (code, count) in &entry.fixes {
-                    let label = if *count == 1 { "fix" } else { "fixes" };
-                    println!("  {} \u{2022} {} {}", code, count, label);
-                }
-                println!();
-            }
-
-            match scan.codes.len() {
-                1 => println!("To fix this diagnostic, run:"),
-                _ => println!("To fix an individual diagnostic, run one of:"),
-            }
-            for code in &scan.codes {
-                println!("  dart fix --apply --code={}", code);
-                println!("  melos-rs analyze --fix --code={}", code);
-            }
-            println!();
-            println!("To fix all diagnostics, run:");
-            println!("  dart fix --apply");
-            println!("  melos-rs analyze --fix");
-
-            if !scan.conflicts.is_empty() {
-                println!();
-                println!("{}", format_conflict_warnings(&scan.conflicts).yellow());
-            }
-        }
-
-        println!("\n{}", "Dry run complete. No changes were applied.".green());
-        println!();
+        print_dry_run_preview(&scan, &summaries, args.fix);
+        return Ok(());
     }
 
     // --fix: apply fixes before analysis (with conflict pre-scan)
     if args.fix {
         let mut skip_fix = false;
         if args.code.is_empty() {
-            let scan = scan_dry_run(
+            let (scan, _) = scan_dry_run(
                 &packages,
                 workspace,
                 args.concurrency,
@@ -166,7 +164,7 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
 
         if !skip_fix {
             let fix_cmd = build_fix_command(true, &args.code);
-            let (fix_tx, fix_render) = spawn_renderer(packages.len(), "fixing");
+            let (fix_tx, fix_render) = spawn_renderer(&packages, "fixing", render);
             let fix_runner = melos_core::runner::ProcessRunner::new(args.concurrency, false);
             let fix_results = fix_runner
                 .run_in_packages_with_events(
@@ -181,7 +179,7 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
             drop(fix_tx);
             fix_render.await??;
 
-            let fix_failed = fix_results.iter().filter(|(_, success)| !success).count();
+            let fix_failed = fix_results.failed();
             if fix_failed > 0 {
                 println!(
                     "{}",
@@ -194,7 +192,7 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
             } else {
                 println!(
                     "{}",
-                    format!("Applied fixes in {} package(s).", fix_results.len()).green()
+                    format!("Applied fixes in {} package(s).", fix_results.passed()).green()
                 );
             }
             println!();
@@ -206,40 +204,217 @@ pub async fn run(workspace: &Workspace, args: AnalyzeArgs) -> Result<()> {
         fatal_warnings: args.fatal_warnings,
         fatal_infos: args.fatal_infos,
         no_fatal: args.no_fatal,
+        json_output: false,
     };
 
-    let (tx, render_handle) = spawn_renderer(packages.len(), "analyzing");
-    let results =
-        melos_core::commands::analyze::run(&packages, workspace, &opts, Some(&tx)).await?;
-    drop(tx);
-    render_handle.await??;
+    // Skip packages whose sources are unchanged since they last passed.
+    // Hashes are taken after `--fix` so applied fixes count as changes.
+    let mut cache = (!args.no_cache).then(|| {
+        let sdk_version = dart_sdk_version(&workspace.env_vars());
+        let key = analyze_cache_key(&workspace.root_path, &sdk_version, &opts);
+        AnalyzeCache::load(&workspace.root_path, &key)
+    });
+    let hashes: HashMap<String, String> = match cache {
+        Some(_) => package_cache_hashes(&packages, &workspace.packages),
+        None => HashMap::new(),
+    };
+    let (cached, to_analyze): (Vec<Package>, Vec<Package>) =
+        packages.into_iter().partition(|pkg| {
+            cache.as_ref().is_some_and(|cache| {
+                hashes
+                    .get(&pkg.name)
+                    .is_some_and(|hash| cache.is_fresh(&pkg.name, hash))
+            })
+        });
+
+    if !cached.is_empty() {
+        println!(
+            "  {} {} package(s) unchanged since the last successful analyze (cache hits)\n",
+            "i".blue(),
+            cached.len()
+        );
+    }
+
+    let results = if to_analyze.is_empty() {
+        PackageResults::from(Vec::new())
+    } else {
+        let (tx, render_handle) = spawn_renderer(&to_analyze, "analyzing", render);
+        let results =
+            melos_core::commands::analyze::run(&to_analyze, workspace, &opts, Some(&tx)).await?;
+        drop(tx);
+        render_handle.await??;
+        results
+    };
+
+    if let Some(cache) = cache.as_mut() {
+        for (name, success) in &results.results {
+            match hashes.get(name) {
+                Some(hash) if *success => {
+                    cache.packages.insert(name.clone(), hash.clone());
+                }
+                _ => {
+                    cache.packages.remove(name);
+                }
+            }
+        }
+        if let Err(e) = cache.save(&workspace.root_path) {
+            eprintln!("{} {:#}", "WARNING:".yellow().bold(), e);
+        }
+    }
 
     if results.failed() > 0 {
         anyhow::bail!(
-            "{} package(s) failed analysis ({} passed)",
+            "{} package(s) failed analysis ({} passed, {} cached)",
             results.failed(),
-            results.passed()
+            results.passed(),
+            cached.len()
         );
     }
 
     println!(
         "\n{}",
-        format!("All {} package(s) passed analysis.", results.passed()).green()
+        format!(
+            "All {} package(s) passed analysis ({} cached).",
+            results.passed() + cached.len(),
+            cached.len()
+        )
+        .green()
     );
     Ok(())
 }
 
+/// Analyze `packages` with JSON output and print a checkstyle report.
+///
+/// Stdout carries only the XML; the exit status still follows the fatal
+/// flags, since it comes from each package's `dart analyze` exit code.
+async fn run_checkstyle(
+    workspace: &Workspace,
+    packages: &[Package],
+    args: &AnalyzeArgs,
+) -> Result<()> {
+    let opts = AnalyzeOpts {
+        concurrency: args.concurrency,
+        fatal_warnings: args.fatal_warnings,
+        fatal_infos: args.fatal_infos,
+        no_fatal: args.no_fatal,
+        json_output: true,
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let collector = tokio::spawn(async move {
+        let mut stdout: HashMap<String, String> = HashMap::new();
+        while let Some(event) = rx.recv().await {
+            if let Event::PackageOutput {
+                name,
+                line,
+                is_stderr: false,
+            } = event
+            {
+                let buf = stdout.entry(name).or_default();
+                buf.push_str(&line);
+                buf.push('\n');
+            }
+        }
+        stdout
+    });
+    let results = melos_core::commands::analyze::run(packages, workspace, &opts, Some(&tx)).await?;
+    drop(tx);
+    let stdout = collector.await?;
+
+    let report: Vec<_> = packages
+        .iter()
+        .map(|pkg| {
+            let output = stdout
+                .get(&pkg.name)
+                .map(String::as_str)
+                .unwrap_or_default();
+            (pkg.path.clone(), parse_analyzer_output(output))
+        })
+        .collect();
+    print!("{}", format_checkstyle(&workspace.root_path, &report));
+
+    if results.failed() > 0 {
+        anyhow::bail!(
+            "{} package(s) failed analysis ({} passed)",
+            results.failed(),
+            results.passed()
+        );
+    }
+    Ok(())
+}
+
+/// Print the results of a `dart fix --dry-run` scan.
+///
+/// With `per_package` (`--fix --dry-run`), each package's proposed fix count
+/// is printed ahead of the per-file breakdown.
+fn print_dry_run_preview(
+    scan: &DryRunScan,
+    summaries: &[(String, DryRunSummary)],
+    per_package: bool,
+) {
+    if per_package {
+        for (name, summary) in summaries {
+            let label = if summary.fixes == 1 { "fix" } else { "fixes" };
+            let files = if summary.files == 1 { "file" } else { "files" };
+            println!(
+                "  {} {} proposed {} in {} {}",
+                name.bold(),
+                summary.fixes.to_string().cyan(),
+                label,
+                summary.files,
+                files
+            );
+        }
+        println!();
+    }
+
+    if scan.entries.is_empty() {
+        println!("{}", "Nothing to fix!".green());
+    } else {
+        for entry in &scan.entries {
+            println!("{}", entry.path);
+            for (code, count) in &entry.fixes {
+                let label = if *count == 1 { "fix" } else { "fixes" };
+                println!("  {} \u{2022} {} {}", code, count, label);
+            }
+            println!();
+        }
+
+        match scan.codes.len() {
+            1 => println!("To fix this diagnostic, run:"),
+            _ => println!("To fix an individual diagnostic, run one of:"),
+        }
+        for code in &scan.codes {
+            println!("  dart fix --apply --code={}", code);
+            println!("  melos-rs analyze --fix --code={}", code);
+        }
+        println!();
+        println!("To fix all diagnostics, run:");
+        println!("  dart fix --apply");
+        println!("  melos-rs analyze --fix");
+
+        if !scan.conflicts.is_empty() {
+            println!();
+            println!("{}", format_conflict_warnings(&scan.conflicts).yellow());
+        }
+    }
+
+    println!("\n{}", "Dry run complete. No changes were applied.".green());
+    println!();
+}
+
 /// Run `dart fix --dry-run` across packages and parse output.
 ///
 /// Returns consolidated file entries, unique diagnostic codes, and any
-/// conflicting lint rule pairs detected via the equal-count heuristic.
+/// conflicting lint rule pairs detected via the equal-count heuristic,
+/// along with each package's proposed-changes summary (sorted by name).
 async fn scan_dry_run(
-    packages: &[melos_core::package::Package],
+    packages: &[Package],
     workspace: &Workspace,
     concurrency: usize,
     codes: &[String],
     progress_label: &str,
-) -> Result<melos_core::commands::analyze::DryRunScan> {
+) -> Result<(DryRunScan, Vec<(String, DryRunSummary)>)> {
     let fix_cmd = build_fix_command(false, codes);
 
     let semaphore = Arc::new(Semaphore::new(concurrency));
@@ -253,6 +428,7 @@ async fn scan_dry_run(
         let root_path = workspace.root_path.clone();
         let env = workspace.env_vars();
         let pb = pb.clone();
+        let name = pkg.name.clone();
 
         handles.push(tokio::spawn(async move {
             // safety: semaphore is never closed in this scope
@@ -276,23 +452,30 @@ async fn scan_dry_run(
             match result {
                 Ok(output) => {
                     let stdout = String::from_utf8_lossy(&output.stdout);
-                    parse_dry_run_output(&stdout, &prefix)
+                    (
+                        name,
+                        parse_dry_run_summary(&stdout),
+                        parse_dry_run_output(&stdout, &prefix),
+                    )
                 }
-                Err(_) => Vec::new(),
+                Err(_) => (name, DryRunSummary::default(), Vec::new()),
             }
         }));
     }
 
     let mut all_entries = Vec::new();
+    let mut summaries = Vec::new();
 
     for handle in handles {
-        let pkg_entries = handle.await?;
+        let (name, summary, pkg_entries) = handle.await?;
+        summaries.push((name, summary));
         all_entries.extend(pkg_entries);
     }
 
     pb.finish_and_clear();
+    summaries.sort_by(|a, b| a.0.cmp(&b.0));
 
-    Ok(assemble_dry_run_scan(all_entries, 2))
+    Ok((assemble_dry_run_scan(all_entries, 2), summaries))
 }
 
 #[cfg(test)]
@@ -352,7 +535,7 @@ mod tests {
     }
 
     #[test]
-    fn test_analyze_args_fix_and_dry_run_conflict() {
+    fn test_analyze_args_fix_and_dry_run_combine() {
         use clap::Parser;
 
         #[derive(Parser, Debug)]
@@ -361,8 +544,9 @@ mod tests {
             args: AnalyzeArgs,
         }
 
-        let result = TestCli::try_parse_from(["test", "--fix", "--dry-run"]);
-        assert!(result.is_err());
+        let cli = TestCli::parse_from(["test", "--fix", "--dry-run"]);
+        assert!(cli.args.fix);
+        assert!(cli.args.dry_run);
     }
 
     #[test]
@@ -404,6 +588,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_analyze_args_checkstyle_format() {
+        use clap::Parser;
+
+        #[derive(Parser, Debug)]
+        struct TestCli {
+            #[command(flatten)]
+            args: AnalyzeArgs,
+        }
+
+        let cli = TestCli::parse_from(["test", "--format", "checkstyle", "--fatal-infos"]);
+        assert_eq!(cli.args.format, AnalyzeFormat::Checkstyle);
+        assert!(cli.args.fatal_infos);
+        assert!(TestCli::try_parse_from(["test", "--format", "checkstyle", "--fix"]).is_err());
+    }
+
     #[test]
     fn test_analyze_args_code_with_dry_run() {
         use clap::Parser;
@@ -1,19 +1,26 @@
-use anyhow::Result;
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
 use colored::Colorize;
 
 use crate::cli::BootstrapArgs;
 use crate::filter_ext::package_filters_from_args;
+use crate::render::RenderSettings;
+use melos_core::commands::PackageResults;
 use melos_core::commands::bootstrap::{
-    build_pub_get_command, config_dependency_override_paths, config_enforce_lockfile,
-    config_enforce_versions, config_run_pub_get_offline, effective_concurrency,
-    generate_pubspec_overrides, sync_shared_dependencies,
+    OverridesDriftKind, bootstrap_hash, build_pub_get_command, config_dependency_override_paths,
+    config_dependency_overrides, config_enforce_lockfile, config_enforce_versions,
+    config_run_pub_get_offline, config_verify_links, effective_concurrency,
+    generate_pubspec_overrides, is_bootstrap_unchanged, pub_get_phase, sync_shared_dependencies,
+    validate_pubspec_overrides, verify_links, write_bootstrap_hash,
 };
+use melos_core::package::Package;
 use melos_core::package::filter::{apply_filters_with_categories, topological_sort};
 use melos_core::runner::ProcessRunner;
 use melos_core::workspace::Workspace;
 
 /// Bootstrap the workspace: link local packages and run `pub get` in each package
-pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: BootstrapArgs, render: RenderSettings) -> Result<()> {
     let filters = package_filters_from_args(&args.filters);
     let filtered = apply_filters_with_categories(
         &workspace.packages,
@@ -25,6 +32,10 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
     // Topological sort ensures dependencies are bootstrapped before dependents
     let packages = topological_sort(&filtered);
 
+    if args.validate_only {
+        return validate_overrides(workspace, &packages);
+    }
+
     let concurrency = effective_concurrency(workspace, args.concurrency);
 
     // Merge CLI flags with config flags
@@ -78,10 +89,12 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
             );
         } else {
             let override_paths = config_dependency_override_paths(workspace);
+            let explicit_overrides = config_dependency_overrides(workspace);
             let result = generate_pubspec_overrides(
                 &packages,
                 &workspace.packages,
                 &override_paths,
+                &explicit_overrides,
                 &workspace.root_path,
             )?;
 
@@ -97,16 +110,25 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
                 );
             }
 
+            if !explicit_overrides.is_empty() {
+                println!(
+                    "  {} Applying {} dependencyOverrides entr{}",
+                    "i".blue(),
+                    explicit_overrides.len(),
+                    if explicit_overrides.len() == 1 {
+                        "y"
+                    } else {
+                        "ies"
+                    }
+                );
+            }
+
+            let graph = workspace.dependency_graph();
             for pkg in &packages {
                 if pkg.uses_workspace_resolution() {
                     continue;
                 }
-                let local_dep_count = pkg
-                    .dependencies
-                    .iter()
-                    .chain(pkg.dev_dependencies.iter())
-                    .filter(|dep| workspace.packages.iter().any(|p| &p.name == *dep))
-                    .count();
+                let local_dep_count = graph.dependencies_of(&pkg.name).count();
                 if local_dep_count > 0 {
                     println!(
                         "  {} Generated pubspec_overrides.yaml for {} ({} local dep{})",
@@ -164,11 +186,55 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
     let flutter_cmd = build_pub_get_command("flutter", enforce_lockfile, args.no_example, offline);
     let dart_cmd = build_pub_get_command("dart", enforce_lockfile, args.no_example, offline);
 
-    let flutter_packages: Vec<_> = packages.iter().filter(|p| p.is_flutter).cloned().collect();
-    let dart_packages: Vec<_> = packages.iter().filter(|p| !p.is_flutter).cloned().collect();
+    // Hash each package's inputs (after overrides and shared deps are written)
+    // so unchanged packages can be skipped now and on the next run
+    let config_path = workspace.config_source.path();
+    let config_content = std::fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    let hashes = packages
+        .iter()
+        .map(|p| Ok((p.name.clone(), bootstrap_hash(p, &config_content)?)))
+        .collect::<Result<HashMap<String, String>>>()?;
+    let (unchanged, to_bootstrap): (Vec<_>, Vec<_>) = packages.iter().partition(|p| {
+        args.skip_if_unchanged && !args.force && is_bootstrap_unchanged(p, &hashes[&p.name])
+    });
+
+    let flutter_packages: Vec<_> = to_bootstrap
+        .iter()
+        .filter(|p| p.is_flutter)
+        .map(|p| (*p).clone())
+        .collect();
+    let dart_packages: Vec<_> = to_bootstrap
+        .iter()
+        .filter(|p| !p.is_flutter)
+        .map(|p| (*p).clone())
+        .collect();
+    let record_hashes = |results: &PackageResults| -> Result<()> {
+        for (name, _) in results.results.iter().filter(|(_, success)| *success) {
+            if let Some(pkg) = packages.iter().find(|p| &p.name == name) {
+                write_bootstrap_hash(pkg, &hashes[name])?;
+            }
+        }
+        Ok(())
+    };
+
+    // One deadline spans both the flutter and dart phases.
+    let deadline_at = args.retry.deadline().map(|d| std::time::Instant::now() + d);
+    let remaining =
+        || deadline_at.map(|at| at.saturating_duration_since(std::time::Instant::now()));
 
-    let total = flutter_packages.len() + dart_packages.len();
-    let (tx, render_handle) = crate::render::spawn_renderer(total, "bootstrapping");
+    let (tx, render_handle) = crate::render::spawn_renderer_with_phases(
+        &packages,
+        "bootstrapping",
+        render,
+        Some(pub_get_phase),
+    );
+    for pkg in &unchanged {
+        let _ = tx.send(melos_core::events::Event::PackageSkipped {
+            name: pkg.name.clone(),
+            reason: "unchanged".to_string(),
+        });
+    }
 
     let mut bail_msg: Option<String> = None;
 
@@ -178,7 +244,9 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
             total: 0,
             message: "flutter pub get...".into(),
         });
-        let runner = ProcessRunner::new(concurrency, true);
+        let runner = ProcessRunner::new(concurrency, true)
+            .with_retry(args.retry.retry_policy())
+            .with_deadline(remaining());
         let results = runner
             .run_in_packages_with_events(
                 &flutter_packages,
@@ -189,12 +257,15 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
                 &workspace.packages,
             )
             .await?;
-
-        for (name, success) in &results {
-            if !success {
-                bail_msg = Some(format!("flutter pub get failed in package '{}'", name));
-                break;
-            }
+        record_hashes(&results)?;
+
+        if let Some((name, _)) = results.results.iter().find(|(_, success)| !success) {
+            bail_msg = Some(format!("flutter pub get failed in package '{}'", name));
+        } else if let Some((name, reason)) = results.skipped.first() {
+            bail_msg = Some(format!(
+                "flutter pub get skipped in package '{}' ({})",
+                name, reason
+            ));
         }
     }
 
@@ -204,7 +275,9 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
             total: 0,
             message: "dart pub get...".into(),
         });
-        let runner = ProcessRunner::new(concurrency, true);
+        let runner = ProcessRunner::new(concurrency, true)
+            .with_retry(args.retry.retry_policy())
+            .with_deadline(remaining());
         let results = runner
             .run_in_packages_with_events(
                 &dart_packages,
@@ -215,12 +288,15 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
                 &workspace.packages,
             )
             .await?;
-
-        for (name, success) in &results {
-            if !success {
-                bail_msg = Some(format!("dart pub get failed in package '{}'", name));
-                break;
-            }
+        record_hashes(&results)?;
+
+        if let Some((name, _)) = results.results.iter().find(|(_, success)| !success) {
+            bail_msg = Some(format!("dart pub get failed in package '{}'", name));
+        } else if let Some((name, reason)) = results.skipped.first() {
+            bail_msg = Some(format!(
+                "dart pub get skipped in package '{}' ({})",
+                name, reason
+            ));
         }
     }
 
@@ -231,6 +307,27 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
         anyhow::bail!(msg);
     }
 
+    // Verify workspace dependencies resolved to local packages if configured
+    if config_verify_links(workspace) {
+        let issues = verify_links(&packages, &workspace.packages, &workspace.root_path)?;
+        if issues.is_empty() {
+            println!(
+                "\n  {} All workspace dependencies resolved to local packages.",
+                "OK".green()
+            );
+        } else {
+            eprintln!(
+                "\n{} {} workspace dependenc{} did not resolve to the local package:",
+                "WARNING:".yellow().bold(),
+                issues.len(),
+                if issues.len() == 1 { "y" } else { "ies" }
+            );
+            for issue in &issues {
+                eprintln!("  {} {}", "WARN".yellow(), issue);
+            }
+        }
+    }
+
     if let Some(post_hook) = workspace.hook("bootstrap", "post") {
         crate::runner::run_lifecycle_hook(post_hook, "post-bootstrap", &workspace.root_path, &[])
             .await?;
@@ -243,6 +340,79 @@ pub async fn run(workspace: &Workspace, args: BootstrapArgs) -> Result<()> {
     Ok(())
 }
 
+/// `bootstrap --validate-only`: compare each `pubspec_overrides.yaml` with
+/// what bootstrap would generate, printing a diff per out-of-date package.
+fn validate_overrides(workspace: &Workspace, packages: &[Package]) -> Result<()> {
+    println!(
+        "\n{} Validating pubspec_overrides.yaml in {} packages...\n",
+        "$".cyan(),
+        packages.len()
+    );
+
+    let (drifts, warnings) = validate_pubspec_overrides(
+        packages,
+        &workspace.packages,
+        &config_dependency_override_paths(workspace),
+        &config_dependency_overrides(workspace),
+        &workspace.root_path,
+        workspace.config_source.is_legacy(),
+    )?;
+
+    for warning in &warnings {
+        eprintln!("  {} {}", "WARN".yellow(), warning);
+    }
+
+    if drifts.is_empty() {
+        println!(
+            "  {} All pubspec_overrides.yaml files are up to date.",
+            "OK".green()
+        );
+        return Ok(());
+    }
+
+    for drift in &drifts {
+        let reason = match drift.kind {
+            OverridesDriftKind::Missing => "missing",
+            OverridesDriftKind::Stale => "out of date",
+            OverridesDriftKind::Unexpected => "should not exist (no local dependencies)",
+            OverridesDriftKind::WorkspaceResolution => {
+                "must not exist (package uses `resolution: workspace`)"
+            }
+        };
+        println!(
+            "  {} {}: pubspec_overrides.yaml {}",
+            "STALE".red(),
+            drift.package.bold(),
+            reason
+        );
+        for line in &drift.diff {
+            let line = match line.chars().next() {
+                Some('-') => line.red().to_string(),
+                Some('+') => line.green().to_string(),
+                _ => line.dimmed().to_string(),
+            };
+            println!("    {}", line);
+        }
+        println!();
+    }
+
+    let (workspace_resolution, regenerate): (Vec<_>, Vec<_>) = drifts
+        .iter()
+        .partition(|d| d.kind == OverridesDriftKind::WorkspaceResolution);
+    let mut hints = Vec::new();
+    if !regenerate.is_empty() {
+        hints.push("run `melos-rs bootstrap` to regenerate them");
+    }
+    if !workspace_resolution.is_empty() {
+        hints.push("delete them from `resolution: workspace` packages");
+    }
+    anyhow::bail!(
+        "{} pubspec_overrides.yaml file(s) out of date: {}.",
+        drifts.len(),
+        hints.join(", and ")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     // All pure logic tests have moved to melos_core::commands::bootstrap.
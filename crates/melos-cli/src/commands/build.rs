@@ -6,13 +6,14 @@ use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
+use crate::render::RenderSettings;
 use crate::runner::run_lifecycle_hook;
 use melos_core::commands::build::{
     BuildStepResult, Platform, build_flutter_command, format_duration, resolve_android_build_type,
     resolve_flavors, resolve_platforms, resolve_simulator_command, validate_version_bump,
 };
 use melos_core::package::filter::apply_filters_with_categories;
-use melos_core::runner::ProcessRunner;
+use melos_core::runner::{ProcessRunner, resolve_concurrency};
 use melos_core::workspace::Workspace;
 
 /// Arguments for the `build` command
@@ -55,8 +56,9 @@ pub struct BuildArgs {
     pub fail_fast: bool,
 
     /// Maximum number of concurrent build processes
-    #[arg(short = 'c', long, default_value = "1")]
-    pub concurrency: usize,
+    /// (default: `command.build.concurrency`, else 1)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
 
     /// Bump version before building: patch, minor, or major
     #[arg(long)]
@@ -145,7 +147,7 @@ fn format_build_summary(results: &[BuildStepResult], total_duration: Duration) -
 }
 
 /// Run the `build` command
-pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: BuildArgs, render: RenderSettings) -> Result<()> {
     let build_config = workspace
         .config
         .command
@@ -279,9 +281,10 @@ pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
         // Add platform-specific dir_exists filter
         let mut filters = base_filters.clone();
         filters.flutter = Some(true);
-        if filters.dir_exists.is_none() {
-            filters.dir_exists = Some(platform.dir_name().to_string());
-        }
+        filters
+            .dir_exists
+            .get_or_insert_with(Vec::new)
+            .push(platform.dir_name().to_string());
 
         let packages = apply_filters_with_categories(
             &workspace.packages,
@@ -405,9 +408,10 @@ pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
 
             let step_start = Instant::now();
 
-            let runner = ProcessRunner::new(args.concurrency, args.fail_fast);
+            let concurrency = resolve_concurrency(args.concurrency, build_config.concurrency, 1);
+            let runner = ProcessRunner::new(concurrency, args.fail_fast);
             let env_vars = workspace.env_vars();
-            let (tx, render_handle) = crate::render::spawn_plain_renderer();
+            let (tx, render_handle) = crate::render::spawn_plain_renderer(&packages, render);
             let results = runner
                 .run_in_packages_with_events(
                     &packages,
@@ -421,8 +425,8 @@ pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
             drop(tx);
             render_handle.await??;
 
-            let failed = results.iter().filter(|(_, success)| !success).count();
-            let passed = results.len() - failed;
+            let failed = results.failed();
+            let passed = results.passed();
             total_failed += failed;
 
             // Simulator post-build step
@@ -445,7 +449,7 @@ pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
                 // Run simulator command sequentially in each package dir
                 // (concurrency=1: bundletool/xcodebuild are heavy processes)
                 let sim_runner = ProcessRunner::new(1, args.fail_fast);
-                let (sim_tx, sim_render) = crate::render::spawn_plain_renderer();
+                let (sim_tx, sim_render) = crate::render::spawn_plain_renderer(&packages, render);
                 let sim_results = sim_runner
                     .run_in_packages_with_events(
                         &packages,
@@ -459,7 +463,7 @@ pub async fn run(workspace: &Workspace, args: BuildArgs) -> Result<()> {
                 drop(sim_tx);
                 sim_render.await??;
 
-                sim_failed = sim_results.iter().filter(|(_, success)| !success).count();
+                sim_failed = sim_results.failed();
                 total_failed += sim_failed;
             }
 
@@ -760,6 +764,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -785,6 +790,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -807,6 +813,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -829,6 +836,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -854,6 +862,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -876,6 +885,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             resolution: None,
         };
 
@@ -0,0 +1,165 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+
+use melos_core::commands::cache::{CacheFile, find_cache_files, remove_cache_files};
+use melos_core::workspace::Workspace;
+
+/// Arguments for the `cache` command
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommand,
+}
+
+/// Cache sub-subcommands
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// Delete melos-rs cache files (`.dart_tool/melos_*`) across the workspace
+    Clean,
+
+    /// Show melos-rs cache files with their sizes and last-modified times
+    Info,
+}
+
+/// Manage the cache files melos-rs keeps in `.dart_tool/` (analyze results,
+/// bootstrap hashes). Unlike `clean`, never touches Dart's own files.
+pub async fn run(workspace: &Workspace, args: CacheArgs) -> Result<()> {
+    let files = find_cache_files(&workspace.root_path, &workspace.packages)?;
+
+    if files.is_empty() {
+        println!("{}", "No melos-rs cache files found.".yellow());
+        return Ok(());
+    }
+
+    match args.command {
+        CacheCommand::Clean => {
+            let freed = remove_cache_files(&files)?;
+            for file in &files {
+                println!("  {} {}", "REMOVED".red(), relative(workspace, file));
+            }
+            println!(
+                "\n{}",
+                format!(
+                    "Removed {} cache file(s), freeing {}.",
+                    files.len(),
+                    format_size(freed)
+                )
+                .green()
+            );
+        }
+        CacheCommand::Info => {
+            let width = files
+                .iter()
+                .map(|f| relative(workspace, f).chars().count())
+                .max()
+                .unwrap_or(0);
+            for file in &files {
+                let path = relative(workspace, file);
+                let padding = " ".repeat(width - path.chars().count());
+                let modified = file
+                    .modified
+                    .map(format_utc_datetime)
+                    .unwrap_or_else(|| "unknown".to_string());
+                println!(
+                    "  {}{}  {:>9}  {}",
+                    path,
+                    padding,
+                    format_size(file.size),
+                    modified.dimmed()
+                );
+            }
+            let total: u64 = files.iter().map(|f| f.size).sum();
+            println!(
+                "\n{} cache file(s), {} total.",
+                files.len(),
+                format_size(total)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn relative(workspace: &Workspace, file: &CacheFile) -> String {
+    file.path
+        .strip_prefix(&workspace.root_path)
+        .unwrap_or(&file.path)
+        .display()
+        .to_string()
+}
+
+/// Format `time` as `YYYY-MM-DD HH:MM UTC`.
+fn format_utc_datetime(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (y, m, d) = civil_date(secs);
+    let secs_of_day = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        y,
+        m,
+        d,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60
+    )
+}
+
+/// Convert seconds since the Unix epoch to a `(year, month, day)` UTC date.
+fn civil_date(total_secs: u64) -> (i64, u64, u64) {
+    // Days since epoch
+    let days = (total_secs / 86400) as i64;
+
+    // Algorithm from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64; // day of era [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // year of era [0, 399]
+    let y = (yoe as i64) + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // day of year [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let d = doy - (153 * mp + 2) / 5 + 1; // day [1, 31]
+    let m = if mp < 10 { mp + 3 } else { mp - 9 }; // month [1, 12]
+    let y = if m <= 2 { y + 1 } else { y };
+
+    (y, m, d)
+}
+
+/// Format a byte count with a binary unit, e.g. `512 B` or `1.5 KiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc_datetime() {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_709_210_096);
+        assert_eq!(format_utc_datetime(time), "2024-02-29 12:34 UTC");
+        assert_eq!(format_utc_datetime(UNIX_EPOCH), "1970-01-01 00:00 UTC");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+
+use crate::cli::GlobalFilterArgs;
+use crate::filter_ext::package_filters_from_args;
+use melos_core::commands::bootstrap::constraint_violations;
+use melos_core::package::filter::apply_filters_with_categories;
+use melos_core::workspace::Workspace;
+
+/// Arguments for the `check-constraints` command
+#[derive(Args, Debug)]
+pub struct CheckConstraintsArgs {
+    #[command(flatten)]
+    pub filters: GlobalFilterArgs,
+}
+
+/// Check that every workspace dependency constraint is satisfied by the
+/// dependency's actual version, as `enforceVersionsForDependencyResolution`
+/// does during bootstrap. Read-only; fails when any constraint is violated.
+pub async fn run(workspace: &Workspace, args: CheckConstraintsArgs) -> Result<()> {
+    let filters = package_filters_from_args(&args.filters);
+    let packages = apply_filters_with_categories(
+        &workspace.packages,
+        &filters,
+        Some(&workspace.root_path),
+        &workspace.config.categories,
+    )?;
+
+    if packages.is_empty() {
+        println!("{}", "No packages matched the given filters.".yellow());
+        return Ok(());
+    }
+
+    let violations = constraint_violations(&packages, &workspace.packages);
+    if violations.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "All workspace dependency constraints in {} package(s) are satisfied.",
+                packages.len()
+            )
+            .green()
+        );
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!("  {} {}", "✗".red(), violation);
+    }
+    anyhow::bail!(
+        "{} workspace dependency constraint(s) not satisfied",
+        violations.len()
+    );
+}
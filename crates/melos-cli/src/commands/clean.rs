@@ -3,13 +3,17 @@ use colored::Colorize;
 
 use crate::cli::CleanArgs;
 use crate::filter_ext::package_filters_from_args;
-use melos_core::commands::clean::{DEEP_CLEAN_DIRS, DEEP_CLEAN_FILES, OverrideRemoval};
+use crate::render::RenderSettings;
+use melos_core::commands::clean::{
+    CLEAN_DRY_RUN_ENV, DEEP_CLEAN_DIRS, DEEP_CLEAN_FILES, OverrideRemoval, clean_targets,
+};
+use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
 use melos_core::runner::ProcessRunner;
 use melos_core::workspace::Workspace;
 
 /// Clean all packages
-pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: CleanArgs, render: RenderSettings) -> Result<()> {
     let filters = package_filters_from_args(&args.filters);
     let all_filtered = apply_filters_with_categories(
         &workspace.packages,
@@ -29,31 +33,39 @@ pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
         return Ok(());
     }
 
-    // Dry-run mode: show what would be cleaned without running
+    let hook_env = [(
+        CLEAN_DRY_RUN_ENV,
+        if args.dry_run { "true" } else { "false" },
+    )];
+
+    if let Some(pre_hook) = workspace.hook("clean", "pre") {
+        crate::runner::run_lifecycle_hook(pre_hook, "pre-clean", &workspace.root_path, &hook_env)
+            .await?;
+    }
+
+    // Dry-run mode: list the paths that would be removed, touching nothing
     if args.dry_run {
-        for pkg in &all_filtered {
-            let pkg_type = if pkg.is_flutter { "flutter" } else { "dart" };
-            println!("  {} {} ({})", "->".cyan(), pkg.name, pkg_type.dimmed());
-        }
-        if args.deep {
-            println!(
-                "\n  {} Deep clean would also remove: {}, {}",
-                "i".blue(),
-                DEEP_CLEAN_DIRS.join(", "),
-                DEEP_CLEAN_FILES.join(", ")
-            );
-        }
+        print_clean_plan(
+            &all_filtered,
+            workspace.config_source.is_legacy(),
+            args.deep,
+        );
         println!(
             "\n{}",
             "DRY RUN — no packages were cleaned.".yellow().bold()
         );
+        if let Some(post_hook) = workspace.hook("clean", "post") {
+            crate::runner::run_lifecycle_hook(
+                post_hook,
+                "post-clean",
+                &workspace.root_path,
+                &hook_env,
+            )
+            .await?;
+        }
         return Ok(());
     }
 
-    if let Some(pre_hook) = workspace.hook("clean", "pre") {
-        crate::runner::run_lifecycle_hook(pre_hook, "pre-clean", &workspace.root_path, &[]).await?;
-    }
-
     // In 6.x mode, remove generated pubspec_overrides.yaml files
     if workspace.config_source.is_legacy() {
         let results = melos_core::commands::clean::remove_pubspec_overrides(&all_filtered);
@@ -106,7 +118,7 @@ pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
 
     if !flutter_packages.is_empty() {
         let (tx, render_handle) =
-            crate::render::spawn_renderer(flutter_packages.len(), "flutter clean...");
+            crate::render::spawn_renderer(&flutter_packages, "flutter clean...", render);
         let runner = ProcessRunner::new(1, false);
         let results = runner
             .run_in_packages_with_events(
@@ -121,7 +133,7 @@ pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
         drop(tx);
         render_handle.await??;
 
-        for (name, success) in &results {
+        for (name, success) in &results.results {
             if *success {
                 println!("  {} {}", "CLEANED".green(), name);
             } else {
@@ -138,30 +150,19 @@ pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
         );
         println!("{}", "Cleaning pure Dart packages...".dimmed());
         for pkg in &dart_packages {
-            let build_dir = pkg.path.join("build");
-            if build_dir.exists()
-                && let Err(e) = std::fs::remove_dir_all(&build_dir)
-            {
-                eprintln!(
-                    "  {} Failed to remove {}: {}",
-                    "WARN".yellow(),
-                    build_dir.display(),
-                    e
-                );
-                failed += 1;
-            }
-
-            let dart_tool_dir = pkg.path.join(".dart_tool");
-            if dart_tool_dir.exists()
-                && let Err(e) = std::fs::remove_dir_all(&dart_tool_dir)
-            {
-                eprintln!(
-                    "  {} Failed to remove {}: {}",
-                    "WARN".yellow(),
-                    dart_tool_dir.display(),
-                    e
-                );
-                failed += 1;
+            for dir_name in DEEP_CLEAN_DIRS {
+                let dir_path = pkg.path.join(dir_name);
+                if dir_path.exists()
+                    && let Err(e) = std::fs::remove_dir_all(&dir_path)
+                {
+                    eprintln!(
+                        "  {} Failed to remove {}: {}",
+                        "WARN".yellow(),
+                        dir_path.display(),
+                        e
+                    );
+                    failed += 1;
+                }
             }
 
             println!("  {} {}", "CLEANED".green(), pkg.name);
@@ -220,9 +221,33 @@ pub async fn run(workspace: &Workspace, args: CleanArgs) -> Result<()> {
     );
 
     if let Some(post_hook) = workspace.hook("clean", "post") {
-        crate::runner::run_lifecycle_hook(post_hook, "post-clean", &workspace.root_path, &[])
+        crate::runner::run_lifecycle_hook(post_hook, "post-clean", &workspace.root_path, &hook_env)
             .await?;
     }
 
     Ok(())
 }
+
+/// Print the absolute paths a clean would remove, grouped per package.
+fn print_clean_plan(packages: &[Package], legacy: bool, deep: bool) {
+    let mut total = 0usize;
+    for pkg in packages {
+        let pkg_type = if pkg.is_flutter { "flutter" } else { "dart" };
+        println!(
+            "  {} {} ({})",
+            "->".cyan(),
+            pkg.name.bold(),
+            pkg_type.dimmed()
+        );
+
+        let targets = clean_targets(pkg, legacy, deep);
+        if targets.is_empty() {
+            println!("      {}", "nothing to remove".dimmed());
+        }
+        for path in &targets {
+            println!("      {}", path.display());
+        }
+        total += targets.len();
+    }
+    println!("\n  {} {} path(s) would be removed", "i".blue(), total);
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+
+use melos_core::commands::doctor::{CheckStatus, DoctorReport, run_checks};
+use melos_core::workspace::Workspace;
+
+/// Arguments for the `doctor` command
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Output the checks as JSON instead of plain text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Check that the environment is ready to run melos-rs.
+///
+/// Works without a workspace: a load failure is reported as a failed check.
+/// Fails when any critical check fails.
+pub fn run(loaded: &Result<Workspace>, args: DoctorArgs, sdk_path: Option<&str>) -> Result<()> {
+    let report = run_checks(loaded, sdk_path);
+
+    if args.json {
+        println!(
+            "{}",
+            // safety: DoctorReport derives Serialize with only primitive/String/Option fields
+            serde_json::to_string_pretty(&report).expect("DoctorReport is always serializable")
+        );
+    } else {
+        print_report(&report);
+    }
+
+    let failures = report.failures();
+    if failures > 0 {
+        anyhow::bail!("{} critical check(s) failed", failures);
+    }
+    Ok(())
+}
+
+fn print_report(report: &DoctorReport) {
+    for check in &report.checks {
+        let mark = match check.status {
+            CheckStatus::Ok => "✓".green(),
+            CheckStatus::Warning => "!".yellow(),
+            CheckStatus::Failed => "✗".red(),
+        };
+        println!("{} {}: {}", mark, check.name.bold(), check.detail);
+        if let Some(ref hint) = check.hint {
+            println!("    {} {}", "hint:".dimmed(), hint);
+        }
+    }
+
+    println!();
+    match (report.failures(), report.warnings()) {
+        (0, 0) => println!("{}", "No issues found.".green()),
+        (0, warnings) => println!("{}", format!("{} warning(s).", warnings).yellow()),
+        (failures, warnings) => println!(
+            "{}",
+            format!("{} failed, {} warning(s).", failures, warnings).red()
+        ),
+    }
+}
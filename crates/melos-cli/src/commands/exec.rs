@@ -1,14 +1,17 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
 
-use crate::cli::GlobalFilterArgs;
+use crate::cli::{GlobalFilterArgs, RetryArgs};
 use crate::filter_ext::package_filters_from_args;
-use melos_core::commands::exec::ExecOpts;
+use crate::render::{RenderSettings, status_println};
+use melos_core::commands::exec::{ExecOpts, LastRunManifest, packages_from};
+use melos_core::config::ExecCommandConfig;
 use melos_core::package::Package;
 use melos_core::package::filter::{apply_filters_with_categories, topological_sort};
+use melos_core::runner::resolve_concurrency;
 use melos_core::watcher;
 use melos_core::workspace::Workspace;
 
@@ -20,14 +23,20 @@ pub struct ExecArgs {
     pub command: Vec<String>,
 
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
-    pub concurrency: usize,
+    /// (default: `command.exec.concurrency`, else 5)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
 
-    /// Stop execution on first failure
+    /// Stop execution on first failure (default: `command.exec.failFast`)
     #[arg(long)]
     pub fail_fast: bool,
 
+    /// Keep running after a failure, even if `command.exec.failFast` is set
+    #[arg(long, conflicts_with = "fail_fast")]
+    pub no_fail_fast: bool,
+
     /// Execute packages in dependency order (topological sort)
+    /// (default: `command.exec.orderDependents`)
     #[arg(long)]
     pub order_dependents: bool,
 
@@ -39,18 +48,103 @@ pub struct ExecArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Report packages where the command is not found (exit code 127) as
+    /// `SKIPPED (not found)` instead of failed; they don't trigger --fail-fast
+    #[arg(long)]
+    pub continue_on_missing_script: bool,
+
     /// Watch for file changes and re-run on change
     #[arg(long)]
     pub watch: bool,
 
+    /// With --watch, poll for changes every MS milliseconds instead of using
+    /// native file events (for NFS or Docker-mounted volumes)
+    #[arg(long, value_name = "MS", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+    pub watch_poll: Option<u64>,
+
+    /// Also write each package's combined output to `<DIR>/<package>.log`
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<String>,
+
+    /// Load environment variables from a dotenv file (KEY=VALUE lines, `#`
+    /// comments, quoted values). Repeat to layer files; later files win.
+    /// Melos's own `MELOS_*` variables take precedence.
+    #[arg(long, value_name = "PATH")]
+    pub env_file: Vec<String>,
+
+    /// With --stream, print progress as newline-delimited JSON events on stdout
+    #[arg(long, requires = "stream")]
+    pub json: bool,
+
+    /// With --json, stream one event per line (`packageStarted`,
+    /// `packageOutput`, `packageFinished`, ...) instead of rendered output
+    #[arg(long, requires = "json", conflicts_with = "watch")]
+    pub stream: bool,
+
+    /// Skip packages that succeeded in the last run of the same command
+    /// (recorded in `.dart_tool/melos_last_run.json`)
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Start at PACKAGE, skipping the packages before it in execution order
+    /// (alphabetical, or topological with --order-dependents)
+    #[arg(long, value_name = "PACKAGE")]
+    pub from: Option<String>,
+
+    /// Don't record which packages succeeded in `.dart_tool/melos_last_run.json`
+    #[arg(long)]
+    pub no_manifest: bool,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
 
+/// Concurrency used when neither `--concurrency` nor `command.exec` set one.
+const DEFAULT_EXEC_CONCURRENCY: usize = 5;
+
+/// Effective exec settings: CLI flag > `command.exec` config > default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExecSettings {
+    concurrency: usize,
+    fail_fast: bool,
+    order_dependents: bool,
+}
+
+impl ExecSettings {
+    fn resolve(args: &ExecArgs, config: Option<&ExecCommandConfig>) -> Self {
+        Self {
+            concurrency: resolve_concurrency(
+                args.concurrency,
+                config.and_then(|c| c.concurrency),
+                DEFAULT_EXEC_CONCURRENCY,
+            ),
+            fail_fast: if args.no_fail_fast {
+                false
+            } else {
+                args.fail_fast || config.is_some_and(|c| c.should_fail_fast())
+            },
+            order_dependents: args.order_dependents
+                || config.is_some_and(|c| c.should_order_dependents()),
+        }
+    }
+}
+
 /// Execute a command across all matching packages
-pub async fn run(workspace: &Workspace, args: ExecArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: ExecArgs, render: RenderSettings) -> Result<()> {
     let cmd_str = args.command.join(" ");
     let watch_mode = args.watch;
+    let env = melos_core::dotenv::load_env_files(&args.env_file)?;
+    let settings = ExecSettings::resolve(
+        &args,
+        workspace
+            .config
+            .command
+            .as_ref()
+            .and_then(|c| c.exec.as_ref()),
+    );
 
     let filters = package_filters_from_args(&args.filters);
     let mut packages = apply_filters_with_categories(
@@ -61,20 +155,75 @@ pub async fn run(workspace: &Workspace, args: ExecArgs) -> Result<()> {
     )?;
 
     if packages.is_empty() {
-        println!("{}", "No packages matched the given filters.".yellow());
+        status_println!(
+            render,
+            "{}",
+            "No packages matched the given filters.".yellow()
+        );
         return Ok(());
     }
 
-    if args.order_dependents {
+    if settings.order_dependents {
         packages = topological_sort(&packages);
     }
 
+    if let Some(from) = &args.from {
+        packages = packages_from(&packages, from)?;
+    }
+
+    let previous = if args.resume {
+        LastRunManifest::load(&workspace.root_path, &cmd_str)
+    } else {
+        None
+    };
+    if args.resume && !args.stream {
+        match &previous {
+            Some(manifest) => {
+                let skipped = packages
+                    .iter()
+                    .filter(|p| manifest.succeeded.contains(&p.name))
+                    .count();
+                status_println!(
+                    render,
+                    "{} Resuming: skipping {} package(s) that succeeded in the last run",
+                    "i".blue(),
+                    skipped
+                );
+            }
+            None => status_println!(
+                render,
+                "{} No previous run of '{}' recorded; running all packages",
+                "i".blue(),
+                cmd_str
+            ),
+        }
+    }
+    if let Some(manifest) = &previous {
+        packages.retain(|p| !manifest.succeeded.contains(&p.name));
+        if packages.is_empty() {
+            if !args.stream {
+                status_println!(
+                    render,
+                    "{}",
+                    "All packages already succeeded in the last run.".green()
+                );
+            }
+            return Ok(());
+        }
+    }
+    // A resumed run keeps the successes it skipped
+    let manifest =
+        (!args.no_manifest).then(|| previous.unwrap_or_else(|| LastRunManifest::new(&cmd_str)));
+
     // Initial run
-    run_exec_once(&cmd_str, &packages, &args, workspace).await?;
+    run_exec_once(
+        &packages, &args, settings, &env, workspace, manifest, render,
+    )
+    .await?;
 
     // If watch mode, start watching and re-run on changes
     if watch_mode {
-        run_watch_loop(&cmd_str, &packages, &args, workspace).await?;
+        run_watch_loop(&packages, &args, settings, &env, workspace, render).await?;
     }
 
     Ok(())
@@ -83,47 +232,65 @@ pub async fn run(workspace: &Workspace, args: ExecArgs) -> Result<()> {
 /// Execute the command once across the given packages.
 ///
 /// Returns Ok(()) even if some packages fail (the error count is printed).
-/// Only returns Err if watch mode is NOT active and packages failed.
+/// Only returns Err if watch mode is NOT active and packages failed. With a
+/// `manifest`, the results are recorded in it and it is saved.
 async fn run_exec_once(
-    cmd_str: &str,
     packages: &[Package],
     args: &ExecArgs,
+    settings: ExecSettings,
+    env: &HashMap<String, String>,
     workspace: &Workspace,
+    manifest: Option<LastRunManifest>,
+    render: RenderSettings,
 ) -> Result<()> {
-    println!(
-        "\n{} Running '{}' in packages...\n",
-        "$".cyan(),
-        cmd_str.bold()
-    );
-
-    if args.order_dependents {
-        println!(
-            "{} Packages ordered by dependencies (topological sort)\n",
-            "i".blue()
+    let cmd_str = args.command.join(" ");
+    // The JSON event stream keeps stdout free of anything but events
+    if !args.stream {
+        status_println!(
+            render,
+            "\n{} Running '{}' in packages...\n",
+            "$".cyan(),
+            cmd_str.bold()
         );
-    }
 
-    let timeout_display = if args.timeout > 0 {
-        format!(", timeout {}s", args.timeout)
-    } else {
-        String::new()
-    };
+        if settings.order_dependents {
+            status_println!(
+                render,
+                "{} Packages ordered by dependencies (topological sort)\n",
+                "i".blue()
+            );
+        }
 
-    println!(
-        "Running in {} package(s) with concurrency {}{}:\n",
-        packages.len().to_string().cyan(),
-        args.concurrency.to_string().cyan(),
-        timeout_display,
-    );
+        let timeout_display = if args.timeout > 0 {
+            format!(", timeout {}s", args.timeout)
+        } else {
+            String::new()
+        };
 
-    for pkg in packages {
-        println!("  {} {}", "->".cyan(), pkg.name);
+        status_println!(
+            render,
+            "Running in {} package(s) with concurrency {}{}:\n",
+            packages.len().to_string().cyan(),
+            settings.concurrency.to_string().cyan(),
+            timeout_display,
+        );
+
+        // Green runs stay silent: only failing packages name themselves
+        if !render.quiet_failures {
+            for pkg in packages {
+                status_println!(render, "  {} {}", "->".cyan(), pkg.name);
+            }
+            status_println!(render);
+        }
     }
-    println!();
 
     // Dry-run mode: show what would be executed without running
     if args.dry_run {
-        println!("{}", "DRY RUN — no commands were executed.".yellow().bold());
+        status_println!(
+            render,
+            "{}",
+            "DRY RUN — no commands were executed.".yellow().bold()
+        );
         return Ok(());
     }
 
@@ -135,16 +302,35 @@ async fn run_exec_once(
 
     let opts = ExecOpts {
         command: cmd_str.to_string(),
-        concurrency: args.concurrency,
-        fail_fast: args.fail_fast,
+        concurrency: settings.concurrency,
+        fail_fast: settings.fail_fast,
         timeout,
+        retry: args.retry.retry_policy(),
+        deadline: args.retry.deadline(),
+        skip_missing: args.continue_on_missing_script,
+        env: env.clone(),
     };
 
-    let (tx, render_handle) = crate::render::spawn_renderer(packages.len(), "exec");
+    let (tx, render_handle) = if args.stream {
+        crate::render::spawn_json_renderer()
+    } else {
+        crate::render::spawn_renderer(packages, "exec", render)
+    };
+    let (tx, log_handle) = crate::render::attach_log_dir(args.output_dir.as_deref(), tx)?;
     let results = melos_core::commands::exec::run(packages, workspace, &opts, Some(&tx)).await?;
     drop(tx);
+    if let Some(log_handle) = log_handle {
+        log_handle.await??;
+    }
     render_handle.await??;
 
+    if let Some(mut manifest) = manifest {
+        manifest.record(&results);
+        if let Err(e) = manifest.save(&workspace.root_path) {
+            eprintln!("{} {:#}", "WARNING:".yellow().bold(), e);
+        }
+    }
+
     if results.failed() > 0 {
         if args.watch {
             eprintln!(
@@ -154,15 +340,22 @@ async fn run_exec_once(
             );
         } else {
             anyhow::bail!(
-                "{} package(s) failed exec ({} passed)",
+                "{} package(s) failed exec ({} passed{})",
                 results.failed(),
-                results.passed()
+                results.passed(),
+                crate::render::other_counts_suffix(&results)
             );
         }
-    } else if !args.watch {
-        println!(
+    } else if !args.watch && !args.stream {
+        status_println!(
+            render,
             "\n{}",
-            format!("All {} package(s) passed exec.", results.passed()).green()
+            format!(
+                "All {} package(s) passed exec{}.",
+                results.passed(),
+                crate::render::other_counts_suffix(&results)
+            )
+            .green()
         );
     }
 
@@ -171,30 +364,44 @@ async fn run_exec_once(
 
 /// Run the watch loop: wait for file changes, then re-execute in affected packages.
 async fn run_watch_loop(
-    cmd_str: &str,
     packages: &[Package],
     args: &ExecArgs,
+    settings: ExecSettings,
+    env: &HashMap<String, String>,
     workspace: &Workspace,
+    render: RenderSettings,
 ) -> Result<()> {
+    let cmd_str = args.command.join(" ");
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
     let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
-    println!(
+    status_println!(
+        render,
         "\n{} Watching {} package(s) for changes...",
         "i".blue(),
         packages.len()
     );
 
     let watch_packages: Vec<Package> = packages.to_vec();
+    let poll_interval = args.watch_poll.map(std::time::Duration::from_millis);
+    let (warning_tx, warning_handle) = crate::render::spawn_plain_renderer(packages, render);
 
     let watcher_handle = tokio::task::spawn_blocking(move || {
-        watcher::start_watching(&watch_packages, 0, event_tx, shutdown_rx, None)
+        watcher::start_watching(
+            &watch_packages,
+            0,
+            poll_interval,
+            event_tx,
+            shutdown_rx,
+            None,
+            Some(warning_tx),
+        )
     });
 
     let shutdown_tx_ctrlc = shutdown_tx.clone();
     tokio::spawn(async move {
         if tokio::signal::ctrl_c().await.is_ok() {
-            println!("\n{} Stopping watcher...", "!".yellow());
+            status_println!(render, "\n{} Stopping watcher...", "!".yellow());
             let _ = shutdown_tx_ctrlc.send(()).await;
         }
     });
@@ -222,7 +429,8 @@ async fn run_watch_loop(
             continue;
         }
 
-        println!(
+        status_println!(
+            render,
             "\n{} Changes detected in: {}\n",
             "↻".cyan().bold(),
             watcher::format_changed_packages(&changed_packages).bold(),
@@ -236,14 +444,24 @@ async fn run_watch_loop(
 
         let opts = ExecOpts {
             command: cmd_str.to_string(),
-            concurrency: args.concurrency,
-            fail_fast: args.fail_fast,
+            concurrency: settings.concurrency,
+            fail_fast: settings.fail_fast,
             timeout,
+            retry: args.retry.retry_policy(),
+            deadline: args.retry.deadline(),
+            skip_missing: args.continue_on_missing_script,
+            env: env.clone(),
         };
 
-        let (tx, render_handle) = crate::render::spawn_renderer(affected.len(), "exec");
+        let (tx, render_handle) = crate::render::spawn_renderer(&affected, "exec", render);
+        let (tx, log_handle) = crate::render::attach_log_dir(args.output_dir.as_deref(), tx)?;
         let result = melos_core::commands::exec::run(&affected, workspace, &opts, Some(&tx)).await;
         drop(tx);
+        if let Some(log_handle) = log_handle
+            && let Ok(Err(e)) = log_handle.await
+        {
+            eprintln!("{} {}", "WARNING:".yellow().bold(), e);
+        }
         let _ = render_handle.await;
 
         match result {
@@ -255,7 +473,8 @@ async fn run_watch_loop(
                         r.failed()
                     );
                 } else {
-                    println!(
+                    status_println!(
+                        render,
                         "\n{} All packages succeeded. Watching for changes...",
                         "✓".green().bold(),
                     );
@@ -273,6 +492,7 @@ async fn run_watch_loop(
 
     let _ = shutdown_tx.send(()).await;
     let _ = watcher_handle.await;
+    let _ = warning_handle.await;
 
     Ok(())
 }
@@ -293,12 +513,60 @@ mod tests {
 
         let cli = TestCli::parse_from(["test", "flutter", "test"]);
         assert_eq!(cli.args.command, vec!["flutter", "test"]);
-        assert_eq!(cli.args.concurrency, 5);
+        assert_eq!(cli.args.concurrency, None);
         assert!(!cli.args.fail_fast);
         assert!(!cli.args.order_dependents);
         assert_eq!(cli.args.timeout, 0);
         assert!(!cli.args.dry_run);
         assert!(!cli.args.watch);
+        assert!(!cli.args.resume);
+        assert_eq!(cli.args.from, None);
+        assert!(!cli.args.no_manifest);
+    }
+
+    #[test]
+    fn test_exec_settings_resolution() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct TestCli {
+            #[command(flatten)]
+            args: ExecArgs,
+        }
+
+        let bare = TestCli::parse_from(["test", "dart", "test"]).args;
+        assert_eq!(
+            ExecSettings::resolve(&bare, None),
+            ExecSettings {
+                concurrency: 5,
+                fail_fast: false,
+                order_dependents: false,
+            }
+        );
+
+        let config = ExecCommandConfig {
+            concurrency: Some(2),
+            fail_fast: Some(true),
+            order_dependents: Some(true),
+        };
+        assert_eq!(
+            ExecSettings::resolve(&bare, Some(&config)),
+            ExecSettings {
+                concurrency: 2,
+                fail_fast: true,
+                order_dependents: true,
+            }
+        );
+
+        let flagged = TestCli::parse_from(["test", "-c", "8", "dart", "test"]).args;
+        assert_eq!(
+            ExecSettings::resolve(&flagged, Some(&config)).concurrency,
+            8
+        );
+
+        let no_fail_fast = TestCli::parse_from(["test", "--no-fail-fast", "dart", "test"]).args;
+        assert!(!ExecSettings::resolve(&no_fail_fast, Some(&config)).fail_fast);
+        assert!(TestCli::try_parse_from(["test", "--fail-fast", "--no-fail-fast", "x"]).is_err());
     }
 
     #[test]
@@ -316,6 +584,28 @@ mod tests {
         assert_eq!(cli.args.command, vec!["flutter", "test"]);
     }
 
+    #[test]
+    fn test_exec_args_watch_poll() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct TestCli {
+            #[command(flatten)]
+            args: ExecArgs,
+        }
+
+        let cli = TestCli::parse_from(["test", "--watch", "--watch-poll", "250", "dart", "test"]);
+        assert_eq!(cli.args.watch_poll, Some(250));
+        assert!(
+            TestCli::try_parse_from(["test", "--watch-poll", "250", "dart", "test"]).is_err(),
+            "--watch-poll requires --watch"
+        );
+        assert!(
+            TestCli::try_parse_from(["test", "--watch", "--watch-poll", "0", "dart", "test"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_exec_args_all_flags() {
         use clap::Parser;
@@ -344,8 +634,45 @@ mod tests {
         assert!(cli.args.fail_fast);
         assert!(cli.args.order_dependents);
         assert!(cli.args.dry_run);
-        assert_eq!(cli.args.concurrency, 3);
+        assert_eq!(cli.args.concurrency, Some(3));
         assert_eq!(cli.args.timeout, 60);
         assert_eq!(cli.args.command, vec!["dart", "analyze", "."]);
     }
+
+    #[test]
+    fn test_exec_args_retry_and_deadline() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct TestCli {
+            #[command(flatten)]
+            args: ExecArgs,
+        }
+
+        let cli = TestCli::parse_from([
+            "test",
+            "--retries",
+            "2",
+            "--retry-backoff-ms",
+            "250",
+            "--retry-jitter",
+            "--deadline",
+            "30",
+            "dart",
+            "pub",
+            "get",
+        ]);
+        let policy = cli.args.retry.retry_policy();
+        assert_eq!(policy.max_retries, 2);
+        assert_eq!(policy.backoff, std::time::Duration::from_millis(250));
+        assert!(policy.jitter);
+        assert_eq!(
+            cli.args.retry.deadline(),
+            Some(std::time::Duration::from_secs(30))
+        );
+
+        let defaults = TestCli::parse_from(["test", "echo"]);
+        assert_eq!(defaults.args.retry.retry_policy().max_retries, 0);
+        assert!(defaults.args.retry.deadline().is_none());
+    }
 }
@@ -1,10 +1,14 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
-use melos_core::commands::format::FormatOpts;
+use crate::render::RenderSettings;
+use melos_core::commands::format::{FormatOpts, parse_changed_files};
+use melos_core::events::Event;
 use melos_core::package::filter::apply_filters_with_categories;
 use melos_core::workspace::Workspace;
 
@@ -12,13 +16,18 @@ use melos_core::workspace::Workspace;
 #[derive(Args, Debug)]
 pub struct FormatArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "1")]
+    #[arg(short = 'c', long, default_value = "1", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     /// Set exit code if formatting changes are needed (useful for CI)
     #[arg(long)]
     pub set_exit_if_changed: bool,
 
+    /// Don't write changes; list the files that need formatting under each
+    /// package and exit non-zero if there are any
+    #[arg(long, conflicts_with_all = ["set_exit_if_changed", "output"])]
+    pub check: bool,
+
     /// Output format: write (default), json, none
     #[arg(short, long, default_value = "write")]
     pub output: String,
@@ -32,7 +41,7 @@ pub struct FormatArgs {
 }
 
 /// Format Dart code across all matching packages using `dart format`
-pub async fn run(workspace: &Workspace, args: FormatArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: FormatArgs, render: RenderSettings) -> Result<()> {
     let filters = package_filters_from_args(&args.filters);
     let packages = apply_filters_with_categories(
         &workspace.packages,
@@ -41,11 +50,8 @@ pub async fn run(workspace: &Workspace, args: FormatArgs) -> Result<()> {
         &workspace.config.categories,
     )?;
 
-    println!(
-        "\n{} Formatting {} packages...\n",
-        "$".cyan(),
-        packages.len()
-    );
+    let verb = if args.check { "Checking" } else { "Formatting" };
+    println!("\n{} {} {} packages...\n", "$".cyan(), verb, packages.len());
 
     if packages.is_empty() {
         println!("{}", "No packages matched the given filters.".yellow());
@@ -59,21 +65,66 @@ pub async fn run(workspace: &Workspace, args: FormatArgs) -> Result<()> {
 
     let opts = FormatOpts {
         concurrency: args.concurrency,
-        set_exit_if_changed: args.set_exit_if_changed,
-        output: args.output.clone(),
+        set_exit_if_changed: args.set_exit_if_changed || args.check,
+        output: if args.check {
+            "none".to_string()
+        } else {
+            args.output.clone()
+        },
         line_length: args.line_length,
     };
 
-    let (tx, render_handle) = crate::render::spawn_renderer(packages.len(), "formatting");
+    let (render_tx, render_handle) = crate::render::spawn_renderer(&packages, "formatting", render);
+    // `--check` collects each package's `Changed <file>` lines on the way to
+    // the renderer
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let collector = tokio::spawn(async move {
+        let mut stdout: HashMap<String, String> = HashMap::new();
+        while let Some(event) = rx.recv().await {
+            if let Event::PackageOutput {
+                ref name,
+                ref line,
+                is_stderr: false,
+            } = event
+            {
+                let buf = stdout.entry(name.clone()).or_default();
+                buf.push_str(line);
+                buf.push('\n');
+            }
+            let _ = render_tx.send(event);
+        }
+        stdout
+    });
     let results = melos_core::commands::format::run(&packages, workspace, &opts, Some(&tx)).await?;
     drop(tx);
+    let stdout = collector.await?;
     render_handle.await??;
 
     let failed = results.failed();
     let passed = results.passed();
 
+    if args.check && failed > 0 {
+        let changed: Vec<(&str, Vec<String>)> = packages
+            .iter()
+            .map(|pkg| {
+                let output = stdout.get(&pkg.name).map(String::as_str).unwrap_or("");
+                (pkg.name.as_str(), parse_changed_files(output))
+            })
+            .filter(|(_, files)| !files.is_empty())
+            .collect();
+        if !changed.is_empty() {
+            println!("\n{}", "Files that need formatting:".yellow().bold());
+            for (name, files) in &changed {
+                println!("  {}", name.bold());
+                for file in files {
+                    println!("    {}", file);
+                }
+            }
+        }
+    }
+
     if failed > 0 {
-        if args.set_exit_if_changed {
+        if args.set_exit_if_changed || args.check {
             anyhow::bail!(
                 "{} package(s) have formatting changes ({} passed). Run `melos-rs format` to fix.",
                 failed,
@@ -93,3 +144,25 @@ pub async fn run(workspace: &Workspace, args: FormatArgs) -> Result<()> {
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        args: FormatArgs,
+    }
+
+    #[test]
+    fn test_format_args_check() {
+        let cli = TestCli::parse_from(["test", "--check"]);
+        assert!(cli.args.check);
+        assert!(!cli.args.set_exit_if_changed);
+
+        assert!(TestCli::try_parse_from(["test", "--check", "--set-exit-if-changed"]).is_err());
+        assert!(TestCli::try_parse_from(["test", "--check", "--output", "json"]).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+
+use crate::cli::GlobalFilterArgs;
+use crate::filter_ext::package_filters_from_args;
+use melos_core::commands::graph::{build_graph, generate_dot};
+use melos_core::package::filter::apply_filters_with_categories;
+use melos_core::workspace::Workspace;
+
+/// Output format for the graph command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    /// JSON adjacency lists, sorted by package name
+    Json,
+}
+
+/// Arguments for the `graph` command
+#[derive(Args, Debug)]
+pub struct GraphArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+    pub format: GraphFormat,
+
+    #[command(flatten)]
+    pub filters: GlobalFilterArgs,
+}
+
+/// Print the intra-workspace dependency graph
+pub async fn run(workspace: &Workspace, args: GraphArgs) -> Result<()> {
+    let filters = package_filters_from_args(&args.filters);
+    let packages = apply_filters_with_categories(
+        &workspace.packages,
+        &filters,
+        Some(&workspace.root_path),
+        &workspace.config.categories,
+    )?;
+
+    if packages.is_empty() && matches!(args.format, GraphFormat::Dot) {
+        println!("{}", "No packages found.".yellow());
+        return Ok(());
+    }
+
+    let graph = build_graph(&packages);
+    match args.format {
+        GraphFormat::Dot => println!("{}", generate_dot(&graph)),
+        GraphFormat::Json => println!(
+            "{}",
+            // safety: GraphNode derives Serialize with only String/bool/Option/Vec fields
+            serde_json::to_string_pretty(&graph).expect("GraphNode is always serializable")
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[command(flatten)]
+        args: GraphArgs,
+    }
+
+    #[test]
+    fn test_graph_args_default_format_is_dot() {
+        let cli = TestCli::parse_from(["test"]);
+        assert_eq!(cli.args.format, GraphFormat::Dot);
+    }
+
+    #[test]
+    fn test_graph_args_json_format() {
+        let cli = TestCli::parse_from(["test", "--format", "json"]);
+        assert_eq!(cli.args.format, GraphFormat::Json);
+    }
+}
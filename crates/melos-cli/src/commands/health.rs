@@ -5,7 +5,9 @@ use colored::Colorize;
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
 use melos_core::commands::health::{
-    HealthOpts, HealthReport, MissingFieldsIssue, SdkConsistencyResult, VersionDriftIssue,
+    AnalysisOptionsIssue, AnalysisOptionsProblem, FieldFix, HEALTH_SCHEMA_VERSION, HealthOpts,
+    HealthReport, MissingFieldsIssue, SdkConsistencyResult, SdkVersionResult, VersionDriftIssue,
+    fix_missing_fields,
 };
 use melos_core::package::filter::apply_filters_with_categories;
 use melos_core::workspace::Workspace;
@@ -22,10 +24,16 @@ pub struct HealthArgs {
     #[arg(long)]
     pub missing_fields: bool,
 
-    /// Check that SDK constraints are consistent across packages
+    /// Check that SDK constraints are consistent across packages and allow
+    /// the resolved Dart/Flutter SDK version
     #[arg(long)]
     pub sdk_consistency: bool,
 
+    /// Check that each package's `analysis_options.yaml` includes the
+    /// workspace's, or that it inherits the root one
+    #[arg(long)]
+    pub analysis_options: bool,
+
     /// Run all checks (default if no specific check is selected)
     #[arg(long, short = 'a')]
     pub all: bool,
@@ -34,6 +42,12 @@ pub struct HealthArgs {
     #[arg(long)]
     pub json: bool,
 
+    /// Write missing pubspec fields that can be inferred before checking:
+    /// `repository` from the workspace `repository` and the package path.
+    /// Present values are never overwritten
+    #[arg(long, conflicts_with = "json")]
+    pub fix: bool,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
@@ -51,9 +65,12 @@ pub async fn run(workspace: &Workspace, args: HealthArgs) -> Result<()> {
     if packages.is_empty() {
         if args.json {
             let report = HealthReport {
+                schema_version: HEALTH_SCHEMA_VERSION,
                 version_drift: None,
                 missing_fields: None,
                 sdk_consistency: None,
+                sdk_version: None,
+                analysis_options: None,
                 total_issues: 0,
             };
             println!(
@@ -67,12 +84,29 @@ pub async fn run(workspace: &Workspace, args: HealthArgs) -> Result<()> {
         return Ok(());
     }
 
+    if args.fix {
+        // Repository URLs point at the release branch (`command.version.branch`, default `main`)
+        let branch = workspace
+            .config
+            .command
+            .as_ref()
+            .and_then(|c| c.version.as_ref())
+            .and_then(|v| v.branch.as_deref())
+            .unwrap_or("main");
+        let repository = workspace.config.repository.as_ref();
+        let fixes = fix_missing_fields(&packages, &workspace.root_path, repository, branch)?;
+        print_fixes(&fixes, repository.is_some());
+    }
+
     let opts = HealthOpts {
         version_drift: args.version_drift,
         missing_fields: args.missing_fields,
         sdk_consistency: args.sdk_consistency,
+        analysis_options: args.analysis_options,
         all: args.all,
         json: args.json,
+        root_path: workspace.root_path.clone(),
+        sdk_path: workspace.sdk_path.clone(),
     };
 
     let report = melos_core::commands::health::run(&packages, &opts);
@@ -109,6 +143,14 @@ pub async fn run(workspace: &Workspace, args: HealthArgs) -> Result<()> {
         print_sdk_consistency(data);
     }
 
+    if let Some(ref data) = report.sdk_version {
+        print_sdk_version(data);
+    }
+
+    if let Some(ref data) = report.analysis_options {
+        print_analysis_options(data);
+    }
+
     println!();
     if report.total_issues > 0 {
         anyhow::bail!("{} health issue(s) found", report.total_issues);
@@ -156,6 +198,30 @@ fn print_version_drift(issues: &[VersionDriftIssue]) {
     println!();
 }
 
+/// Print the pubspec edits made by `--fix`.
+fn print_fixes(fixes: &[FieldFix], has_repository: bool) {
+    println!("\n{}", "Missing fields fix".bold().underline());
+
+    for fix in fixes {
+        println!(
+            "  {} {} {}: {}",
+            "FIXED".green().bold(),
+            fix.package.bold(),
+            fix.field,
+            fix.value.cyan()
+        );
+    }
+
+    if !has_repository {
+        println!(
+            "  {} No `repository` in the workspace config; package repository URLs can't be inferred.",
+            "i".blue()
+        );
+    } else if fixes.is_empty() {
+        println!("  {} Nothing to fix.", "OK".green());
+    }
+}
+
 /// Print missing-fields results in human-readable format.
 fn print_missing_fields(issues: &[MissingFieldsIssue]) {
     println!("{}", "Missing fields check".bold().underline());
@@ -185,6 +251,54 @@ fn print_missing_fields(issues: &[MissingFieldsIssue]) {
     println!();
 }
 
+/// Print analysis options results in human-readable format.
+fn print_analysis_options(issues: &[AnalysisOptionsIssue]) {
+    println!("{}", "Analysis options check".bold().underline());
+
+    for issue in issues {
+        let problem = match issue.problem {
+            AnalysisOptionsProblem::Missing => "has no analysis_options.yaml".to_string(),
+            AnalysisOptionsProblem::NoInclude => "has no `include:`".to_string(),
+            AnalysisOptionsProblem::ForeignInclude => {
+                format!("includes {}", issue.include.join(", "))
+            }
+            AnalysisOptionsProblem::ConflictingRules => {
+                "overrides the workspace lints:".to_string()
+            }
+        };
+        let file = issue
+            .file
+            .as_ref()
+            .map(|f| format!(" ({})", f).dimmed().to_string())
+            .unwrap_or_default();
+        println!(
+            "  {} {} {}{}",
+            "LINTS".yellow().bold(),
+            issue.package.bold(),
+            problem,
+            file
+        );
+        for conflict in &issue.conflicts {
+            println!("    {} {}", "->".dimmed(), conflict);
+        }
+    }
+
+    if issues.is_empty() {
+        println!(
+            "  {} All packages build on the workspace analysis options.",
+            "OK".green()
+        );
+    } else {
+        println!(
+            "\n  {} {} package(s) don't include the workspace analysis_options.yaml.",
+            "!".yellow(),
+            issues.len()
+        );
+    }
+
+    println!();
+}
+
 /// Print SDK consistency results in human-readable format.
 fn print_sdk_consistency(data: &SdkConsistencyResult) {
     println!("{}", "SDK consistency check".bold().underline());
@@ -240,3 +354,43 @@ fn print_sdk_consistency(data: &SdkConsistencyResult) {
 
     println!();
 }
+
+/// Print the resolved SDK versions and incompatible constraints.
+fn print_sdk_version(data: &SdkVersionResult) {
+    println!("{}", "SDK version check".bold().underline());
+
+    let unknown = || "unknown".to_string();
+    println!(
+        "  {} Dart {}",
+        "SDK".cyan().bold(),
+        data.dart.clone().unwrap_or_else(unknown)
+    );
+    if let Some(ref flutter) = data.flutter {
+        println!("  {} Flutter {}", "SDK".cyan().bold(), flutter);
+    }
+
+    for issue in &data.incompatible {
+        println!(
+            "  {} {} {} constraint {} excludes the resolved SDK",
+            "SDK".red().bold(),
+            issue.package.bold(),
+            issue.sdk,
+            issue.constraint.cyan()
+        );
+    }
+
+    if data.incompatible.is_empty() {
+        println!(
+            "  {} All SDK constraints allow the resolved SDK.",
+            "OK".green()
+        );
+    } else {
+        println!(
+            "\n  {} {} constraint(s) exclude the resolved SDK.",
+            "!".yellow(),
+            data.incompatible.len()
+        );
+    }
+
+    println!();
+}
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
@@ -7,7 +5,8 @@ use colored::Colorize;
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
 use melos_core::commands::list::{
-    build_packages_json, detect_cycles, generate_gviz, generate_mermaid,
+    build_packages_json, detect_cycles, generate_ascii_tree, generate_gviz, generate_mermaid,
+    is_git_work_tree, package_git_status, sort_by_path_depth, sort_topological,
 };
 use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
@@ -23,7 +22,7 @@ pub enum ListFormat {
     Parsable,
     /// JSON output
     Json,
-    /// Dependency graph as an adjacency list
+    /// Dependency tree rooted at packages nothing depends on (like `cargo tree`)
     Graph,
     /// Graphviz DOT format
     Gviz,
@@ -31,10 +30,24 @@ pub enum ListFormat {
     Mermaid,
 }
 
+/// Order of the listed packages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListOrder {
+    /// Alphabetical by package name
+    #[default]
+    Name,
+    /// Dependencies before dependents, ties broken by name
+    Topo,
+    /// Shallowest package paths first, then by path
+    Path,
+}
+
 /// Arguments for the `list` command
 #[derive(Args, Debug)]
 pub struct ListArgs {
-    /// Show full package details (path, version, dependencies)
+    /// Show a table of package details: version, type and path, plus
+    /// uncommitted changes and commits since the latest release tag in a git
+    /// repository
     #[arg(short, long)]
     pub long: bool,
 
@@ -54,7 +67,7 @@ pub struct ListArgs {
     #[arg(short, long)]
     pub parsable: bool,
 
-    /// Show dependency graph (shorthand for --format=graph)
+    /// Show the dependency tree (shorthand for --format=graph)
     #[arg(long)]
     pub graph: bool,
 
@@ -66,6 +79,10 @@ pub struct ListArgs {
     #[arg(long)]
     pub mermaid: bool,
 
+    /// Order of the listed packages
+    #[arg(long, value_enum, default_value_t = ListOrder::Name)]
+    pub order: ListOrder,
+
     /// Detect and report dependency cycles
     #[arg(long)]
     pub cycles: bool,
@@ -77,13 +94,19 @@ pub struct ListArgs {
 /// List packages in the workspace
 pub async fn run(workspace: &Workspace, args: ListArgs) -> Result<()> {
     let filters = package_filters_from_args(&args.filters);
-    let packages = apply_filters_with_categories(
+    let mut packages = apply_filters_with_categories(
         &workspace.packages,
         &filters,
         Some(&workspace.root_path),
         &workspace.config.categories,
     )?;
 
+    match args.order {
+        ListOrder::Name => packages.sort_by(|a, b| a.name.cmp(&b.name)),
+        ListOrder::Topo => sort_topological(&mut packages),
+        ListOrder::Path => sort_by_path_depth(&mut packages),
+    }
+
     if packages.is_empty() {
         println!("{}", "No packages found.".yellow());
         return Ok(());
@@ -113,13 +136,13 @@ pub async fn run(workspace: &Workspace, args: ListArgs) -> Result<()> {
     match format {
         ListFormat::Default => {
             if args.long {
-                print_long(&packages, workspace);
+                print_long(&packages, workspace)?;
             } else {
                 print_default(&packages);
             }
         }
         ListFormat::Parsable => print_parsable(&packages, workspace, args.relative),
-        ListFormat::Json => print_json(&packages),
+        ListFormat::Json => print_json(&packages, &workspace.root_path),
         ListFormat::Graph => print_graph(&packages),
         ListFormat::Gviz => println!("{}", generate_gviz(&packages)),
         ListFormat::Mermaid => println!("{}", generate_mermaid(&packages)),
@@ -136,34 +159,104 @@ fn print_default(packages: &[Package]) {
     println!();
 }
 
-fn print_long(packages: &[Package], workspace: &Workspace) {
+/// Print one row per package: name, version, type and path, plus whether
+/// the package has uncommitted changes and how many commits HEAD is past
+/// its latest release tag when the workspace is a git repository.
+fn print_long(packages: &[Package], workspace: &Workspace) -> Result<()> {
     println!(
         "\n{} ({} packages)\n",
         workspace.config.name.bold(),
         packages.len()
     );
-    for pkg in packages {
-        let version = pkg.version.as_deref().unwrap_or("unknown");
-        let pkg_type = if pkg.is_flutter {
-            "flutter".cyan()
-        } else {
-            "dart".blue()
-        };
-        let private_tag = if pkg.is_private() {
-            " (private)".dimmed()
-        } else {
-            "".dimmed()
-        };
-        println!(
-            "  {} {} [{}]{} {}",
-            pkg.name.bold(),
-            version.dimmed(),
-            pkg_type,
-            private_tag,
-            pkg.path.display().to_string().dimmed()
-        );
+
+    let git_statuses = if is_git_work_tree(&workspace.root_path) {
+        Some(
+            packages
+                .iter()
+                .map(|pkg| package_git_status(&workspace.root_path, pkg))
+                .collect::<Result<Vec<_>>>()?,
+        )
+    } else {
+        None
+    };
+
+    let mut header = vec!["NAME", "VERSION", "TYPE"];
+    if git_statuses.is_some() {
+        header.extend(["DIRTY", "SINCE TAG"]);
+    }
+    header.push("PATH");
+
+    let rows: Vec<Vec<String>> = packages
+        .iter()
+        .enumerate()
+        .map(|(i, pkg)| {
+            let pkg_type = if pkg.is_flutter { "flutter" } else { "dart" };
+            let mut row = vec![
+                pkg.name.clone(),
+                pkg.version.as_deref().unwrap_or("unknown").to_string(),
+                if pkg.is_private() {
+                    format!("{pkg_type} (private)")
+                } else {
+                    pkg_type.to_string()
+                },
+            ];
+            if let Some(statuses) = &git_statuses {
+                let status = &statuses[i];
+                row.push(if status.dirty { "Y" } else { "N" }.to_string());
+                row.push(
+                    status
+                        .commits_since_tag
+                        .map_or_else(|| "\u{2014}".to_string(), |n| n.to_string()),
+                );
+            }
+            row.push(pkg.path.display().to_string());
+            row
+        })
+        .collect();
+
+    // Pad before coloring so escape codes don't skew the alignment
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain(std::iter::once(header[col].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let pad = |col: usize, value: &str| {
+        let fill = widths[col].saturating_sub(value.chars().count());
+        format!("{}{}", value, " ".repeat(fill))
+    };
+
+    let header_line: Vec<String> = header.iter().enumerate().map(|(i, h)| pad(i, h)).collect();
+    println!("  {}", header_line.join("  ").trim_end().dimmed());
+    for row in &rows {
+        let last = row.len() - 1;
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let cell = if i == last {
+                    value.clone()
+                } else {
+                    pad(i, value)
+                };
+                match i {
+                    0 => cell.bold().to_string(),
+                    1 => cell.dimmed().to_string(),
+                    2 if value.starts_with("flutter") => cell.cyan().to_string(),
+                    2 => cell.blue().to_string(),
+                    _ if i == last => cell.dimmed().to_string(),
+                    _ if value == "Y" => cell.yellow().to_string(),
+                    _ => cell,
+                }
+            })
+            .collect();
+        println!("  {}", cells.join("  "));
     }
     println!();
+    Ok(())
 }
 
 fn print_parsable(packages: &[Package], workspace: &Workspace, relative: bool) {
@@ -182,8 +275,8 @@ fn print_parsable(packages: &[Package], workspace: &Workspace, relative: bool) {
     }
 }
 
-fn print_json(packages: &[Package]) {
-    let entries = build_packages_json(packages);
+fn print_json(packages: &[Package], root: &std::path::Path) {
+    let entries = build_packages_json(packages, root);
 
     // serde_json handles all escaping correctly
     match serde_json::to_string_pretty(&entries) {
@@ -193,23 +286,9 @@ fn print_json(packages: &[Package]) {
 }
 
 fn print_graph(packages: &[Package]) {
-    // Build a set of known package names for filtering
-    let known: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
-
     println!();
-    for pkg in packages {
-        let local_deps: Vec<&str> = pkg
-            .dependencies
-            .iter()
-            .filter(|d| known.contains(d.as_str()))
-            .map(|d| d.as_str())
-            .collect();
-
-        if local_deps.is_empty() {
-            println!("  {} -> (no local dependencies)", pkg.name.bold());
-        } else {
-            println!("  {} -> {}", pkg.name.bold(), local_deps.join(", "));
-        }
+    for line in generate_ascii_tree(packages).lines() {
+        println!("  {}", line);
     }
     println!();
 }
@@ -255,6 +334,7 @@ mod tests {
             dependencies: deps.into_iter().map(String::from).collect(),
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -281,10 +361,14 @@ mod tests {
                     categories: Default::default(),
                     use_root_as_package: None,
                     discover_nested_workspaces: None,
+                    respect_gitignore: None,
+                    env: HashMap::new(),
                 },
                 packages: packages.clone(),
                 sdk_path: None,
                 warnings: vec![],
+                overridden_fields: vec![],
+                color: Default::default(),
             },
             false,
         );
@@ -1,14 +1,19 @@
 pub mod analyze;
 pub mod bootstrap;
 pub mod build;
+pub mod cache;
+pub mod check_constraints;
 pub mod clean;
+pub mod doctor;
 pub mod exec;
 pub mod format;
+pub mod graph;
 pub mod health;
 pub mod init;
 pub mod list;
 pub mod pub_cmds;
 pub mod publish;
 pub mod run;
+pub mod selftest;
 pub mod test;
 pub mod version;
@@ -4,6 +4,8 @@ use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
+use crate::render::RenderSettings;
+use melos_core::commands::PackageResults;
 use melos_core::commands::pub_cmds::{build_pub_add_command, build_pub_remove_command, pub_cmd};
 use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
@@ -43,7 +45,7 @@ pub enum PubCommand {
 #[derive(Args, Debug)]
 pub struct PubGetArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     #[command(flatten)]
@@ -54,7 +56,7 @@ pub struct PubGetArgs {
 #[derive(Args, Debug)]
 pub struct PubOutdatedArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "1")]
+    #[arg(short = 'c', long, default_value = "1", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     #[command(flatten)]
@@ -65,7 +67,7 @@ pub struct PubOutdatedArgs {
 #[derive(Args, Debug)]
 pub struct PubUpgradeArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     /// Upgrade to latest major versions (passes --major-versions)
@@ -80,7 +82,7 @@ pub struct PubUpgradeArgs {
 #[derive(Args, Debug)]
 pub struct PubDowngradeArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     #[command(flatten)]
@@ -98,7 +100,7 @@ pub struct PubAddArgs {
     pub dev: bool,
 
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     #[command(flatten)]
@@ -112,7 +114,7 @@ pub struct PubRemoveArgs {
     pub package: String,
 
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "5")]
+    #[arg(short = 'c', long, default_value = "5", value_parser = crate::cli::parse_concurrency_arg)]
     pub concurrency: usize,
 
     #[command(flatten)]
@@ -120,14 +122,14 @@ pub struct PubRemoveArgs {
 }
 
 /// Dispatch to the appropriate pub sub-subcommand
-pub async fn run(workspace: &Workspace, args: PubArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: PubArgs, render: RenderSettings) -> Result<()> {
     match args.command {
-        PubCommand::Get(a) => run_pub_get(workspace, a).await,
-        PubCommand::Outdated(a) => run_pub_outdated(workspace, a).await,
-        PubCommand::Upgrade(a) => run_pub_upgrade(workspace, a).await,
-        PubCommand::Downgrade(a) => run_pub_downgrade(workspace, a).await,
-        PubCommand::Add(a) => run_pub_add(workspace, a).await,
-        PubCommand::Remove(a) => run_pub_remove(workspace, a).await,
+        PubCommand::Get(a) => run_pub_get(workspace, a, render).await,
+        PubCommand::Outdated(a) => run_pub_outdated(workspace, a, render).await,
+        PubCommand::Upgrade(a) => run_pub_upgrade(workspace, a, render).await,
+        PubCommand::Downgrade(a) => run_pub_downgrade(workspace, a, render).await,
+        PubCommand::Add(a) => run_pub_add(workspace, a, render).await,
+        PubCommand::Remove(a) => run_pub_remove(workspace, a, render).await,
     }
 }
 
@@ -141,6 +143,7 @@ async fn run_pub_subcommand(
     subcmd: &str,
     concurrency: usize,
     show_sdk: bool,
+    render: RenderSettings,
 ) -> Result<()> {
     let pf = package_filters_from_args(filters);
     let packages = apply_filters_with_categories(
@@ -177,62 +180,121 @@ async fn run_pub_subcommand(
         concurrency,
         &workspace.env_vars(),
         &workspace.packages,
+        render,
     )
     .await
 }
 
 /// Run `dart pub get` / `flutter pub get` in each matching package
-async fn run_pub_get(workspace: &Workspace, args: PubGetArgs) -> Result<()> {
-    run_pub_subcommand(workspace, &args.filters, "pub get", args.concurrency, true).await
+async fn run_pub_get(
+    workspace: &Workspace,
+    args: PubGetArgs,
+    render: RenderSettings,
+) -> Result<()> {
+    run_pub_subcommand(
+        workspace,
+        &args.filters,
+        "pub get",
+        args.concurrency,
+        true,
+        render,
+    )
+    .await
 }
 
 /// Run `dart pub outdated` in each matching package.
 ///
 /// pub outdated is informational — non-zero exit from outdated deps is expected,
 /// but the runner still reports failures per-package.
-async fn run_pub_outdated(workspace: &Workspace, args: PubOutdatedArgs) -> Result<()> {
+async fn run_pub_outdated(
+    workspace: &Workspace,
+    args: PubOutdatedArgs,
+    render: RenderSettings,
+) -> Result<()> {
     run_pub_subcommand(
         workspace,
         &args.filters,
         "pub outdated",
         args.concurrency,
         false,
+        render,
     )
     .await
 }
 
 /// Run `dart pub upgrade` in each matching package
-async fn run_pub_upgrade(workspace: &Workspace, args: PubUpgradeArgs) -> Result<()> {
+async fn run_pub_upgrade(
+    workspace: &Workspace,
+    args: PubUpgradeArgs,
+    render: RenderSettings,
+) -> Result<()> {
     let subcmd = if args.major_versions {
         "pub upgrade --major-versions"
     } else {
         "pub upgrade"
     };
-    run_pub_subcommand(workspace, &args.filters, subcmd, args.concurrency, true).await
+    run_pub_subcommand(
+        workspace,
+        &args.filters,
+        subcmd,
+        args.concurrency,
+        true,
+        render,
+    )
+    .await
 }
 
 /// Run `dart pub downgrade` in each matching package
-async fn run_pub_downgrade(workspace: &Workspace, args: PubDowngradeArgs) -> Result<()> {
+async fn run_pub_downgrade(
+    workspace: &Workspace,
+    args: PubDowngradeArgs,
+    render: RenderSettings,
+) -> Result<()> {
     run_pub_subcommand(
         workspace,
         &args.filters,
         "pub downgrade",
         args.concurrency,
         true,
+        render,
     )
     .await
 }
 
 /// Run `dart pub add` / `flutter pub add` in each matching package
-async fn run_pub_add(workspace: &Workspace, args: PubAddArgs) -> Result<()> {
+async fn run_pub_add(
+    workspace: &Workspace,
+    args: PubAddArgs,
+    render: RenderSettings,
+) -> Result<()> {
     let subcmd = build_pub_add_command(&args.package, args.dev);
-    run_pub_subcommand(workspace, &args.filters, &subcmd, args.concurrency, true).await
+    run_pub_subcommand(
+        workspace,
+        &args.filters,
+        &subcmd,
+        args.concurrency,
+        true,
+        render,
+    )
+    .await
 }
 
 /// Run `dart pub remove` / `flutter pub remove` in each matching package
-async fn run_pub_remove(workspace: &Workspace, args: PubRemoveArgs) -> Result<()> {
+async fn run_pub_remove(
+    workspace: &Workspace,
+    args: PubRemoveArgs,
+    render: RenderSettings,
+) -> Result<()> {
     let subcmd = build_pub_remove_command(&args.package);
-    run_pub_subcommand(workspace, &args.filters, &subcmd, args.concurrency, true).await
+    run_pub_subcommand(
+        workspace,
+        &args.filters,
+        &subcmd,
+        args.concurrency,
+        true,
+        render,
+    )
+    .await
 }
 
 /// Run a `pub` subcommand in each package, using the appropriate SDK (flutter vs dart).
@@ -245,14 +307,15 @@ async fn run_pub_in_packages(
     concurrency: usize,
     env_vars: &std::collections::HashMap<String, String>,
     all_packages: &[Package],
+    render: RenderSettings,
 ) -> Result<()> {
     // Group packages by SDK to batch them efficiently
     let flutter_pkgs: Vec<&Package> = packages.iter().filter(|p| p.is_flutter).collect();
     let dart_pkgs: Vec<&Package> = packages.iter().filter(|p| !p.is_flutter).collect();
 
-    let (tx, render_handle) = crate::render::spawn_renderer(packages.len(), pub_subcmd);
+    let (tx, render_handle) = crate::render::spawn_renderer(packages, pub_subcmd, render);
     let runner = ProcessRunner::new(concurrency, false);
-    let mut all_results = Vec::new();
+    let mut all_results = PackageResults::default();
 
     if !flutter_pkgs.is_empty() {
         let cmd = format!("flutter {}", pub_subcmd);
@@ -285,8 +348,8 @@ async fn run_pub_in_packages(
     drop(tx);
     render_handle.await??;
 
-    let failed = all_results.iter().filter(|(_, success)| !success).count();
-    let passed = all_results.len() - failed;
+    let failed = all_results.failed();
+    let passed = all_results.passed();
 
     if failed > 0 {
         anyhow::bail!("{} package(s) failed ({} passed)", failed, passed);
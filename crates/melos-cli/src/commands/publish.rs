@@ -1,11 +1,22 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
 use clap::Args;
 use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
-use melos_core::commands::publish::{PublishOpts, build_git_tag};
+use crate::render::RenderSettings;
+use melos_core::commands::PackageResults;
+use melos_core::commands::publish::{
+    PUBLISH_TAG_ENV, PublishOpts, PublishedStatus, build_git_tag, build_release_message,
+    check_published, fetch_published_versions, pending_dependents, publish_order, release_channel,
+    resolve_registry, select_packages, stable_packages_for_tag, wait_until_published,
+};
+use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
+use melos_core::runner::resolve_concurrency;
 use melos_core::workspace::Workspace;
 
 /// Arguments for the `publish` command
@@ -20,33 +31,74 @@ pub struct PublishArgs {
     pub git_tag_version: bool,
 
     /// Maximum number of concurrent publish operations
-    #[arg(short = 'c', long, default_value = "1")]
-    pub concurrency: usize,
+    /// (default: `command.publish.concurrency`, else 1)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
 
     /// Skip confirmation prompt
     #[arg(long)]
     pub yes: bool,
 
+    /// Publish exactly these packages (comma-separated names), without the
+    /// confirmation prompt. Each must exist, be publishable and match the
+    /// package filters. Dry run still applies unless --dry-run=false
+    #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+    pub select: Vec<String>,
+
     /// Print release URL links after publishing (requires `repository` in config).
     /// Generates prefilled release creation page links for each published package.
     #[arg(long, short = 'r')]
     pub release_url: bool,
 
+    /// Publish prereleases under a release channel (e.g. `beta`), in
+    /// dependency order as with --ordered. Stable versions cannot be kept off
+    /// the default channel and are rejected.
+    #[arg(long, value_name = "CHANNEL")]
+    pub tag: Option<String>,
+
+    /// Package server to compare local versions against in dry runs and to
+    /// poll with --ordered (default: `PUB_HOSTED_URL`, else https://pub.dev)
+    #[arg(long, value_name = "URL")]
+    pub registry: Option<String>,
+
+    /// Publish one package at a time in dependency order, waiting for each
+    /// dependency to appear on the registry before publishing its dependents
+    #[arg(long)]
+    pub ordered: bool,
+
+    /// Seconds between registry polls while waiting (with --ordered or --tag)
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    pub poll_interval: u64,
+
+    /// Seconds to wait for a published version to appear before giving up
+    /// (with --ordered or --tag)
+    #[arg(long, value_name = "SECONDS", default_value_t = 300)]
+    pub poll_timeout: u64,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
 
 /// Publish packages to pub.dev
-pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: PublishArgs, render: RenderSettings) -> Result<()> {
     let mut filters = package_filters_from_args(&args.filters);
     filters.no_private = true;
 
-    let packages = apply_filters_with_categories(
+    let mut packages = apply_filters_with_categories(
         &workspace.packages,
         &filters,
         Some(&workspace.root_path),
         &workspace.config.categories,
     )?;
+    if !args.select.is_empty() {
+        packages = select_packages(&packages, &workspace.packages, &args.select)?;
+    }
+    // A channel release must reach the registry dependencies first, so
+    // dependents on the channel resolve against the new prereleases
+    let ordered = args.ordered || args.tag.is_some();
+    if ordered {
+        packages = publish_order(&packages, &workspace.packages);
+    }
 
     if packages.is_empty() {
         println!(
@@ -71,11 +123,36 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
 
     for pkg in &packages {
         let version = pkg.version.as_deref().unwrap_or("unknown");
-        println!("  {} {} {}", "->".cyan(), pkg.name.bold(), version.dimmed());
+        match release_channel(version, args.tag.as_deref()) {
+            Some(channel) => println!(
+                "  {} {} {} {}",
+                "->".cyan(),
+                pkg.name.bold(),
+                version.dimmed(),
+                format!("[{}]", channel).magenta()
+            ),
+            None => println!("  {} {} {}", "->".cyan(), pkg.name.bold(), version.dimmed()),
+        }
     }
     println!();
 
+    if let Some(ref tag) = args.tag {
+        let stable = stable_packages_for_tag(&packages);
+        if !stable.is_empty() {
+            let msg = format!(
+                "--tag {} only applies to prerelease versions; stable versions would reach the default channel: {}",
+                tag,
+                stable.join(", ")
+            );
+            if !args.dry_run {
+                anyhow::bail!(msg);
+            }
+            println!("{} {}\n", "WARN:".yellow(), msg);
+        }
+    }
+
     if args.dry_run {
+        print_registry_check(&packages, args.registry.as_deref()).await?;
         println!(
             "{}",
             "Dry run mode: no packages will actually be published.".dimmed()
@@ -83,7 +160,8 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
         println!("{}", "Use --dry-run=false to publish for real.\n".dimmed());
     }
 
-    if !args.yes && !args.dry_run {
+    // Naming the packages with --select is the confirmation
+    if !args.yes && args.select.is_empty() && !args.dry_run {
         print!(
             "\n{} Publish these packages to pub.dev? [y/N] ",
             "CONFIRM:".yellow()
@@ -101,27 +179,42 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
     }
 
     let dry_run_str = if args.dry_run { "true" } else { "false" };
+    let tag_str = args.tag.as_deref().unwrap_or("");
+    let hook_env = [
+        ("MELOS_PUBLISH_DRY_RUN", dry_run_str),
+        (PUBLISH_TAG_ENV, tag_str),
+    ];
 
     if let Some(pre_hook) = workspace.hook("publish", "pre") {
-        crate::runner::run_lifecycle_hook(
-            pre_hook,
-            "pre-publish",
-            &workspace.root_path,
-            &[("MELOS_PUBLISH_DRY_RUN", dry_run_str)],
-        )
-        .await?;
+        crate::runner::run_lifecycle_hook(pre_hook, "pre-publish", &workspace.root_path, &hook_env)
+            .await?;
     }
 
     let opts = PublishOpts {
         dry_run: args.dry_run,
-        concurrency: args.concurrency,
+        concurrency: resolve_concurrency(
+            args.concurrency,
+            workspace
+                .config
+                .command
+                .as_ref()
+                .and_then(|c| c.publish.as_ref())
+                .and_then(|p| p.concurrency),
+            1,
+        ),
+        tag: args.tag.clone(),
     };
 
-    let (tx, render_handle) = crate::render::spawn_renderer(packages.len(), "publishing");
-    let results =
-        melos_core::commands::publish::run(&packages, workspace, &opts, Some(&tx)).await?;
-    drop(tx);
-    render_handle.await??;
+    let results = if ordered {
+        run_ordered(workspace, &packages, &opts, &args, render).await?
+    } else {
+        let (tx, render_handle) = crate::render::spawn_renderer(&packages, "publishing", render);
+        let results =
+            melos_core::commands::publish::run(&packages, workspace, &opts, Some(&tx)).await?;
+        drop(tx);
+        render_handle.await??;
+        results
+    };
 
     let succeeded: Vec<_> = results
         .results
@@ -142,7 +235,7 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
                         "-a",
                         &tag,
                         "-m",
-                        &format!("Release {} v{}", pkg_name, version),
+                        &build_release_message(pkg_name, version, args.tag.as_deref()),
                     ])
                     .current_dir(&workspace.root_path)
                     .status();
@@ -187,17 +280,13 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
             post_hook,
             "post-publish",
             &workspace.root_path,
-            &[("MELOS_PUBLISH_DRY_RUN", dry_run_str)],
+            &hook_env,
         )
         .await?;
     }
 
-    if results.failed() > 0 {
-        anyhow::bail!(
-            "{} package(s) failed to publish ({} passed)",
-            results.failed(),
-            results.passed()
-        );
+    if results.failed() > 0 || results.skipped() > 0 {
+        anyhow::bail!(publish_failure_message(&results));
     }
 
     let action = if args.dry_run {
@@ -212,6 +301,178 @@ pub async fn run(workspace: &Workspace, args: PublishArgs) -> Result<()> {
     Ok(())
 }
 
+/// Summarize an incomplete publish, counting failed packages apart from the
+/// ones held back because a dependency did not publish.
+fn publish_failure_message(results: &PackageResults) -> String {
+    const HELD_BACK: &str = "held back because a dependency did not publish";
+    let summary = match (results.failed(), results.skipped()) {
+        (failed, 0) => format!("{} package(s) failed to publish", failed),
+        (0, skipped) => format!("{} package(s) {}", skipped, HELD_BACK),
+        (failed, skipped) => format!(
+            "{} package(s) failed to publish, {} {}",
+            failed, skipped, HELD_BACK
+        ),
+    };
+    let aborted = if results.aborted() > 0 {
+        format!(", {} aborted", results.aborted())
+    } else {
+        String::new()
+    };
+    format!("{} ({} passed{})", summary, results.passed(), aborted)
+}
+
+/// Publish `packages` one at a time, in the given (dependency) order.
+///
+/// After a real publish, waits for the new version to reach the registry
+/// whenever a later package depends on it. When a package fails or its wait
+/// times out, the packages depending on it are skipped, since their
+/// dependencies might not resolve; the others are still published.
+async fn run_ordered(
+    workspace: &Workspace,
+    packages: &[Package],
+    opts: &PublishOpts,
+    args: &PublishArgs,
+    render: RenderSettings,
+) -> Result<PackageResults> {
+    let registry = resolve_registry(args.registry.as_deref());
+    let mut results = PackageResults::default();
+    // Held-back package -> why
+    let mut blocked: HashMap<String, String> = HashMap::new();
+
+    for (i, pkg) in packages.iter().enumerate() {
+        if let Some(reason) = blocked.get(&pkg.name) {
+            results.skipped.push((pkg.name.clone(), reason.clone()));
+            continue;
+        }
+
+        let batch = std::slice::from_ref(pkg);
+        let (tx, render_handle) = crate::render::spawn_plain_renderer(batch, render);
+        let outcome = melos_core::commands::publish::run(batch, workspace, opts, Some(&tx)).await?;
+        drop(tx);
+        render_handle.await??;
+        let failed = outcome.failed() > 0;
+        results.extend(outcome);
+
+        let remaining: Vec<Package> = packages[i + 1..]
+            .iter()
+            .filter(|p| !blocked.contains_key(&p.name))
+            .cloned()
+            .collect();
+        let reason = if failed {
+            Some(format!("{} failed", pkg.name))
+        } else {
+            wait_for_dependents(pkg, &remaining, workspace, opts, args, &registry).await?
+        };
+        let Some(reason) = reason else {
+            continue;
+        };
+        let dependents = pending_dependents(&pkg.name, &remaining, &workspace.packages);
+        if !dependents.is_empty() {
+            println!(
+                "\n{} {}; not publishing {}.",
+                "WARN:".yellow(),
+                reason,
+                dependents.join(", ")
+            );
+            for name in dependents {
+                blocked.insert(name, reason.clone());
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Wait for `pkg` to go live on the registry if a package in `remaining`
+/// depends on it.
+///
+/// Returns why publishing cannot continue when the wait times out. Dry runs
+/// publish nothing, so they never wait.
+async fn wait_for_dependents(
+    pkg: &Package,
+    remaining: &[Package],
+    workspace: &Workspace,
+    opts: &PublishOpts,
+    args: &PublishArgs,
+    registry: &str,
+) -> Result<Option<String>> {
+    let waiting_on = pending_dependents(&pkg.name, remaining, &workspace.packages);
+    let Some(version) = pkg.version.clone() else {
+        return Ok(None);
+    };
+    if opts.dry_run || waiting_on.is_empty() {
+        return Ok(None);
+    }
+
+    println!(
+        "  {} Waiting for {} {} on {} (needed by {})...",
+        "i".blue(),
+        pkg.name.bold(),
+        version.dimmed(),
+        registry,
+        waiting_on.join(", ")
+    );
+    let interval = Duration::from_secs(args.poll_interval);
+    let timeout = Duration::from_secs(args.poll_timeout);
+    let (name, reg, ver) = (pkg.name.clone(), registry.to_string(), version.clone());
+    let live = tokio::task::spawn_blocking(move || {
+        wait_until_published(
+            || fetch_published_versions(&reg, &name),
+            &ver,
+            interval,
+            timeout,
+        )
+    })
+    .await
+    .context("Registry poll panicked")?;
+
+    Ok((!live).then(|| {
+        format!(
+            "{} {} did not appear on {} within {}s (raise --poll-timeout)",
+            pkg.name, version, registry, args.poll_timeout
+        )
+    }))
+}
+
+/// Report whether each package's local version is already on the registry.
+async fn print_registry_check(packages: &[Package], registry: Option<&str>) -> Result<()> {
+    let registry = resolve_registry(registry);
+    println!(
+        "{} Checking published versions on {}...\n",
+        "$".cyan(),
+        registry
+    );
+
+    let to_check = packages.to_vec();
+    let checks = tokio::task::spawn_blocking(move || check_published(&to_check, &registry))
+        .await
+        .context("Registry check panicked")?;
+
+    for check in &checks {
+        let local = check.local_version.as_deref().unwrap_or("unknown");
+        let latest = check
+            .latest_published
+            .as_deref()
+            .map(|v| format!(" (latest published: {})", v))
+            .unwrap_or_default();
+        let status = match check.status {
+            PublishedStatus::Published => "already published".red(),
+            PublishedStatus::Newer => "newer".green(),
+            PublishedStatus::Older => "older".yellow(),
+            PublishedStatus::Unknown => "unknown".dimmed(),
+        };
+        println!(
+            "  {} {} {}{}",
+            check.name.bold(),
+            local.dimmed(),
+            status,
+            latest.dimmed()
+        );
+    }
+    println!();
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +485,7 @@ mod tests {
 
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo".to_string(),
+            kind: melos_core::config::RepoKind::GitHub,
         };
         let pkg_name = "my_package";
         let version = "1.2.3";
@@ -240,6 +502,7 @@ mod tests {
 
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo".to_string(),
+            kind: melos_core::config::RepoKind::GitHub,
         };
         let tag = format!("{}-v{}", "core", "2.0.0-beta.1");
         let title = format!("{} v{}", "core", "2.0.0-beta.1");
@@ -255,6 +518,29 @@ mod tests {
         assert_eq!(build_publish_command(false), "dart pub publish --force");
     }
 
+    #[test]
+    fn test_publish_failure_message_counts_failed_and_held_back_apart() {
+        let results = |failed: usize, skipped: usize| PackageResults {
+            results: std::iter::repeat_n(("ok".to_string(), true), 2)
+                .chain(std::iter::repeat_n(("bad".to_string(), false), failed))
+                .collect(),
+            skipped: vec![("app".to_string(), "core failed".to_string()); skipped],
+            aborted: Vec::new(),
+        };
+        assert_eq!(
+            publish_failure_message(&results(1, 0)),
+            "1 package(s) failed to publish (2 passed)"
+        );
+        assert_eq!(
+            publish_failure_message(&results(0, 1)),
+            "1 package(s) held back because a dependency did not publish (2 passed)"
+        );
+        assert_eq!(
+            publish_failure_message(&results(1, 2)),
+            "1 package(s) failed to publish, 2 held back because a dependency did not publish (2 passed)"
+        );
+    }
+
     #[test]
     fn test_build_git_tag_via_core() {
         assert_eq!(build_git_tag("my_package", "1.2.3"), "my_package-v1.2.3");
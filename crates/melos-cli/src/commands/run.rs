@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
 
 use anyhow::{Context, Result, bail};
 use clap::Args;
@@ -7,15 +8,20 @@ use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
+use crate::render::{RenderSettings, status_println};
 use melos_core::commands::run::{
-    MAX_SCRIPT_DEPTH, expand_command, extract_exec_command, extract_melos_run_script_name,
-    is_exec_command, normalize_line_continuations, parse_exec_flags, substitute_env_vars,
+    MAX_SCRIPT_DEPTH, expand_command, expand_env_vars, extract_exec_command,
+    extract_melos_run_script_name, fuzzy_matches, is_exec_command, normalize_line_continuations,
+    parse_exec_flags, resolve_matrix,
 };
 use melos_core::config::ScriptEntry;
 use melos_core::config::filter::PackageFilters;
+use melos_core::config::script::{MatrixEntry, StepEntry, normalize_working_directory};
 use melos_core::package::Package;
-use melos_core::package::filter::{apply_filters_with_categories, topological_sort};
-use melos_core::runner::ProcessRunner;
+use melos_core::package::filter::{
+    apply_filters_with_categories, retain_affected, topological_sort,
+};
+use melos_core::runner::{ProcessRunner, resolve_concurrency};
 use melos_core::watcher;
 use melos_core::workspace::Workspace;
 
@@ -41,25 +47,83 @@ pub struct RunArgs {
     #[arg(long)]
     pub include_private: bool,
 
-    /// Filter scripts by group (can be repeated)
+    /// Filter scripts by group (can be repeated); without a script name, run
+    /// every script in the group(s) one after another in name order
     #[arg(long)]
     pub group: Vec<String>,
 
+    /// Maximum number of concurrent processes for `exec` and `matrix` scripts
+    /// (default: the script's `exec.concurrency`, else 5)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
+
+    /// With --group, keep running the group's remaining scripts after one fails
+    #[arg(long, requires = "group")]
+    pub no_fail_fast: bool,
+
     /// Watch for file changes and re-run the script on change
     #[arg(long)]
     pub watch: bool,
 
+    /// With --watch, re-run per-package commands only in the packages that
+    /// changed (plus their dependents with --include-dependents)
+    #[arg(long, requires = "watch")]
+    pub changed_only: bool,
+
+    /// With --watch, poll for changes every MS milliseconds instead of using
+    /// native file events (for NFS or Docker-mounted volumes)
+    #[arg(long, value_name = "MS", requires = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+    pub watch_poll: Option<u64>,
+
     #[command(flatten)]
     pub filters: GlobalFilterArgs,
 }
 
+/// Package selection and output settings shared by every script in a run.
+#[derive(Debug, Clone, Copy)]
+struct ScriptScope<'a> {
+    /// Filters given on the command line
+    cli_filters: &'a PackageFilters,
+    /// In `--changed-only` watch re-runs, the packages that changed
+    changed: Option<&'a HashSet<String>>,
+    /// Output settings from the global flags
+    render: RenderSettings,
+    /// Concurrency given on the command line
+    concurrency: Option<usize>,
+}
+
+/// Concurrency for per-package scripts when neither `--concurrency` nor the
+/// script's `exec.concurrency` set one.
+const DEFAULT_SCRIPT_CONCURRENCY: usize = 5;
+
 /// Execute a named script from the melos.yaml scripts section
-pub async fn run(workspace: &Workspace, args: RunArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: RunArgs, render: RenderSettings) -> Result<()> {
     // Handle --list mode
     if args.list {
         return list_scripts(workspace, args.json, args.include_private, &args.group);
     }
 
+    if args.script.is_none() && !args.group.is_empty() {
+        if args.watch {
+            bail!("--watch needs a script name; it cannot watch a whole --group");
+        }
+        let cli_filters = package_filters_from_args(&args.filters);
+        let scope = ScriptScope {
+            cli_filters: &cli_filters,
+            changed: None,
+            render,
+            concurrency: args.concurrency,
+        };
+        return run_group(
+            workspace,
+            &args.group,
+            args.include_private,
+            scope,
+            !args.no_fail_fast,
+        )
+        .await;
+    }
+
     let script_name = match args.script {
         Some(name) => name,
         None if args.no_select => {
@@ -70,10 +134,16 @@ pub async fn run(workspace: &Workspace, args: RunArgs) -> Result<()> {
 
     let watch_mode = args.watch;
     let cli_filters = package_filters_from_args(&args.filters);
+    let scope = ScriptScope {
+        cli_filters: &cli_filters,
+        changed: None,
+        render,
+        concurrency: args.concurrency,
+    };
 
     // Initial run
     let mut visited = HashSet::new();
-    let result = run_script_recursive(workspace, &script_name, &cli_filters, &mut visited, 0).await;
+    let result = run_script_recursive(workspace, &script_name, scope, &mut visited, 0).await;
 
     if let Err(e) = &result {
         if watch_mode {
@@ -89,7 +159,15 @@ pub async fn run(workspace: &Workspace, args: RunArgs) -> Result<()> {
 
     // If watch mode, start watching and re-run on changes
     if watch_mode {
-        run_watch_loop(workspace, &script_name, &cli_filters).await?;
+        let poll_interval = args.watch_poll.map(std::time::Duration::from_millis);
+        run_watch_loop(
+            workspace,
+            &script_name,
+            scope,
+            args.changed_only,
+            poll_interval,
+        )
+        .await?;
     }
 
     Ok(())
@@ -98,11 +176,16 @@ pub async fn run(workspace: &Workspace, args: RunArgs) -> Result<()> {
 /// Run the watch loop for a named script: wait for file changes, then re-execute.
 ///
 /// Watches all workspace packages (or just filtered ones if the script has packageFilters)
-/// and re-runs the entire script on any change.
+/// and re-runs the entire script on any change. With `changed_only`, per-package
+/// commands only run in the packages that changed (and their dependents with
+/// `--include-dependents`); workspace-root commands still run as usual.
+/// With `poll_interval`, changes are found by polling instead of native events.
 async fn run_watch_loop(
     workspace: &Workspace,
     script_name: &str,
-    cli_filters: &PackageFilters,
+    scope: ScriptScope<'_>,
+    changed_only: bool,
+    poll_interval: Option<std::time::Duration>,
 ) -> Result<()> {
     // Determine which packages to watch:
     // If the script has packageFilters, watch only those packages.
@@ -114,17 +197,17 @@ async fn run_watch_loop(
         .ok_or_else(|| anyhow::anyhow!("Script '{}' not found in config", script_name))?;
 
     let watch_packages = if let Some(script_filters) = script.package_filters() {
-        let merged = script_filters.merge(cli_filters);
+        let merged = script_filters.merge(scope.cli_filters);
         apply_filters_with_categories(
             &workspace.packages,
             &merged,
             Some(&workspace.root_path),
             &workspace.config.categories,
         )?
-    } else if !cli_filters.is_empty() {
+    } else if !scope.cli_filters.is_empty() {
         apply_filters_with_categories(
             &workspace.packages,
-            cli_filters,
+            scope.cli_filters,
             Some(&workspace.root_path),
             &workspace.config.categories,
         )?
@@ -133,29 +216,40 @@ async fn run_watch_loop(
     };
 
     if watch_packages.is_empty() {
-        println!("{}", "No packages to watch.".yellow());
+        status_println!(scope.render, "{}", "No packages to watch.".yellow());
         return Ok(());
     }
 
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
     let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
-    println!(
+    status_println!(
+        scope.render,
         "\n{} Watching {} package(s) for changes...",
         "i".blue(),
         watch_packages.len()
     );
 
     let watch_pkgs_clone: Vec<Package> = watch_packages.to_vec();
+    let (warning_tx, warning_handle) =
+        crate::render::spawn_plain_renderer(&watch_packages, scope.render);
 
     let watcher_handle = tokio::task::spawn_blocking(move || {
-        watcher::start_watching(&watch_pkgs_clone, 0, event_tx, shutdown_rx, None)
+        watcher::start_watching(
+            &watch_pkgs_clone,
+            0,
+            poll_interval,
+            event_tx,
+            shutdown_rx,
+            None,
+            Some(warning_tx),
+        )
     });
 
     let shutdown_tx_ctrlc = shutdown_tx.clone();
     tokio::spawn(async move {
         if tokio::signal::ctrl_c().await.is_ok() {
-            println!("\n{} Stopping watcher...", "!".yellow());
+            status_println!(scope.render, "\n{} Stopping watcher...", "!".yellow());
             let _ = shutdown_tx_ctrlc.send(()).await;
         }
     });
@@ -173,17 +267,24 @@ async fn run_watch_loop(
             changed_packages.insert(event.package_name);
         }
 
-        println!(
+        status_println!(
+            scope.render,
             "\n{} Changes detected in: {}\n",
             "\u{21bb}".cyan().bold(),
             watcher::format_changed_packages(&changed_packages).bold(),
         );
 
-        // Re-run the entire script
+        // Re-run the entire script, or with --changed-only just its
+        // per-package commands in the affected packages
         let mut visited = HashSet::new();
-        match run_script_recursive(workspace, script_name, cli_filters, &mut visited, 0).await {
+        let rerun_scope = ScriptScope {
+            changed: changed_only.then_some(&changed_packages),
+            ..scope
+        };
+        match run_script_recursive(workspace, script_name, rerun_scope, &mut visited, 0).await {
             Ok(()) => {
-                println!(
+                status_println!(
+                    scope.render,
                     "\n{} Script '{}' succeeded. Watching for changes...",
                     "\u{2713}".green().bold(),
                     script_name,
@@ -202,6 +303,7 @@ async fn run_watch_loop(
 
     let _ = shutdown_tx.send(()).await;
     let _ = watcher_handle.await;
+    let _ = warning_handle.await;
 
     Ok(())
 }
@@ -216,14 +318,7 @@ fn list_scripts(
     include_private: bool,
     groups: &[String],
 ) -> Result<()> {
-    let mut scripts: Vec<(&String, &ScriptEntry)> = workspace
-        .config
-        .scripts
-        .iter()
-        .filter(|(_, entry)| include_private || !entry.is_private())
-        .filter(|(_, entry)| groups.is_empty() || groups.iter().any(|g| entry.in_group(g)))
-        .collect();
-    scripts.sort_by_key(|(name, _)| *name);
+    let scripts = visible_scripts(workspace, include_private, groups);
 
     if json {
         let entries: Vec<serde_json::Value> = scripts
@@ -249,6 +344,9 @@ fn list_scripts(
                 if entry.steps().is_some() {
                     obj.insert("steps".to_string(), serde_json::Value::Bool(true));
                 }
+                if entry.matrix().is_some() {
+                    obj.insert("matrix".to_string(), serde_json::Value::Bool(true));
+                }
                 if let Some(groups) = entry.groups() {
                     let groups_json: Vec<serde_json::Value> = groups
                         .iter()
@@ -280,9 +378,14 @@ fn list_scripts(
             } else {
                 String::new()
             };
-            let mode = match (entry.steps().is_some(), entry.has_exec_config()) {
-                (true, _) => format!(" {}", "(steps)".dimmed()),
-                (_, true) => format!(" {}", "(exec)".dimmed()),
+            let mode = match (
+                entry.steps().is_some(),
+                entry.matrix().is_some(),
+                entry.has_exec_config(),
+            ) {
+                (true, _, _) => format!(" {}", "(steps)".dimmed()),
+                (_, true, _) => format!(" {}", "(matrix)".dimmed()),
+                (_, _, true) => format!(" {}", "(exec)".dimmed()),
                 _ => String::new(),
             };
             println!(
@@ -300,20 +403,96 @@ fn list_scripts(
     Ok(())
 }
 
+/// Scripts in any of `groups` (all scripts if empty), sorted by name; private
+/// scripts only with `include_private`.
+fn visible_scripts<'a>(
+    workspace: &'a Workspace,
+    include_private: bool,
+    groups: &[String],
+) -> Vec<(&'a String, &'a ScriptEntry)> {
+    let mut scripts: Vec<(&String, &ScriptEntry)> = workspace
+        .config
+        .scripts
+        .iter()
+        .filter(|(_, entry)| include_private || !entry.is_private())
+        .filter(|(_, entry)| groups.is_empty() || groups.iter().any(|g| entry.in_group(g)))
+        .collect();
+    scripts.sort_by_key(|(name, _)| *name);
+    scripts
+}
+
+/// Run every script in `groups`, one after another in name order, each as if
+/// invoked with `melos-rs run <name>`.
+///
+/// With `fail_fast`, the first failure skips the remaining scripts; otherwise
+/// all run. Each script's outcome is summarized at the end, and the group
+/// fails if any script failed.
+async fn run_group(
+    workspace: &Workspace,
+    groups: &[String],
+    include_private: bool,
+    scope: ScriptScope<'_>,
+    fail_fast: bool,
+) -> Result<()> {
+    let scripts = visible_scripts(workspace, include_private, groups);
+    if scripts.is_empty() {
+        bail!("No scripts in group(s): {}", groups.join(", "));
+    }
+
+    status_println!(
+        scope.render,
+        "\n{} Running {} script(s) in group {}",
+        "i".blue(),
+        scripts.len(),
+        groups.join(", ").bold()
+    );
+
+    let mut outcomes: Vec<(&str, StepOutcome)> = Vec::new();
+    let mut failed = 0;
+    for (name, _) in &scripts {
+        if failed > 0 && fail_fast {
+            outcomes.push((name.as_str(), StepOutcome::Skipped));
+            continue;
+        }
+        let mut visited = HashSet::new();
+        match run_script_recursive(workspace, name, scope, &mut visited, 0).await {
+            Ok(()) => outcomes.push((name.as_str(), StepOutcome::Passed)),
+            Err(e) => {
+                eprintln!("{} {:#}", "ERROR".red().bold(), e);
+                outcomes.push((name.as_str(), StepOutcome::Failed));
+                failed += 1;
+            }
+        }
+    }
+
+    print_outcome_summary("Scripts:", &outcomes, 0, scope.render);
+
+    if failed > 0 {
+        bail!(
+            "{} of {} script(s) in group {} failed",
+            failed,
+            scripts.len(),
+            groups.join(", ")
+        );
+    }
+    Ok(())
+}
+
 /// Recursively execute a named script, resolving nested `melos run <X>` references.
 ///
 /// When a script's expanded command is `melos-rs run <other_script>` and that
 /// script exists in the config, it is executed inline instead of shelling out.
 /// A visited set tracks the call chain to detect and prevent cycles.
 ///
-/// Supports three execution modes:
+/// Supports four execution modes:
 /// 1. **Steps**: execute each step sequentially (shell commands or script references)
-/// 2. **Exec config**: per-package execution using `exec:` config
-/// 3. **Run command**: shell command at workspace root (with `melos exec` string detection)
+/// 2. **Matrix**: package-scoped sub-runs, each with its own filters and command
+/// 3. **Exec config**: per-package execution using `exec:` config
+/// 4. **Run command**: shell command at workspace root (with `melos exec` string detection)
 async fn run_script_recursive(
     workspace: &Workspace,
     script_name: &str,
-    cli_filters: &PackageFilters,
+    scope: ScriptScope<'_>,
     visited: &mut HashSet<String>,
     depth: usize,
 ) -> Result<()> {
@@ -324,6 +503,26 @@ async fn run_script_recursive(
         );
     }
 
+    let script = workspace
+        .config
+        .scripts
+        .get(script_name)
+        .ok_or_else(|| anyhow::anyhow!("Script '{}' not found in config", script_name))?;
+
+    // Checked before entering the call chain, so a skipped script can be
+    // referenced again later
+    if !script.supports_os(std::env::consts::OS) {
+        status_println!(
+            scope.render,
+            "\n{} Skipping script '{}': only runs on {} (current: {})",
+            "i".blue(),
+            script_name.bold(),
+            script.os().unwrap_or_default().join(", "),
+            std::env::consts::OS
+        );
+        return Ok(());
+    }
+
     if !visited.insert(script_name.to_string()) {
         let chain: Vec<_> = visited.iter().cloned().collect();
         bail!(
@@ -334,44 +533,87 @@ async fn run_script_recursive(
         );
     }
 
-    let script = workspace
-        .config
-        .scripts
-        .get(script_name)
-        .ok_or_else(|| anyhow::anyhow!("Script '{}' not found in config", script_name))?;
+    let result = run_script_body(workspace, script_name, script, scope, visited, depth).await;
+    // Remove from visited on every exit, so the same script can appear in
+    // separate chains (e.g. A -> B, A -> C -> B is fine; A -> B -> A is a
+    // cycle) and run again after a failure that a step continued past
+    visited.remove(script_name);
+    result
+}
+
+/// Run a script that [`run_script_recursive`] has entered in the call chain.
+async fn run_script_body(
+    workspace: &Workspace,
+    script_name: &str,
+    script: &ScriptEntry,
+    scope: ScriptScope<'_>,
+    visited: &mut HashSet<String>,
+    depth: usize,
+) -> Result<()> {
+    let mut env_vars = workspace.env_vars();
+    // Merge script-level env vars (they take precedence over workspace vars)
+    env_vars.extend(script.env().iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    if let Some(predicate) = script.when().filter(|w| !w.trim().is_empty()) {
+        let code = evaluate_when(predicate, &workspace.root_path, &env_vars)
+            .await
+            .with_context(|| format!("Failed to evaluate `when` for script '{}'", script_name))?;
+        if code != 0 {
+            status_println!(
+                scope.render,
+                "\n{} Skipping script '{}': `when` condition `{}` exited with code {}",
+                "i".blue(),
+                script_name.bold(),
+                predicate.trim(),
+                code
+            );
+            return Ok(());
+        }
+    }
 
     if let Some(desc) = script.description() {
-        println!("\n{} {}", "Description:".dimmed(), desc.trim());
+        status_println!(
+            scope.render,
+            "\n{} {}",
+            "Description:".dimmed(),
+            desc.trim()
+        );
     }
 
     let indent = "  ".repeat(depth);
-    println!(
+    status_println!(
+        scope.render,
         "\n{}{} Running script '{}'...\n",
         indent,
         "$".cyan(),
         script_name.bold()
     );
 
-    let mut env_vars = workspace.env_vars();
-    // Merge script-level env vars (they take precedence over workspace vars)
-    env_vars.extend(script.env().iter().map(|(k, v)| (k.clone(), v.clone())));
-
-    match (script.steps(), script.exec_command(), script.run_command()) {
-        (Some(steps), _, _) => {
-            run_steps(workspace, steps, &env_vars, cli_filters, visited, depth).await?;
+    match (
+        script.steps(),
+        script.matrix(),
+        script.exec_command(),
+        script.run_command(),
+    ) {
+        (Some(steps), _, _, _) => {
+            run_steps(workspace, steps, &env_vars, scope, visited, depth).await?;
         }
-        (None, Some(exec_cmd), _) => {
-            // Mode 2: Exec config (per-package execution via config, not string parsing)
-            run_exec_config_script(workspace, script, exec_cmd, &env_vars, cli_filters).await?;
+        (None, Some(matrix), _, _) => {
+            // Mode 2: Matrix (each entry runs in its own package subset)
+            run_matrix_script(workspace, script_name, script, matrix, &env_vars, scope).await?;
         }
-        (None, None, Some(run_command)) => {
-            // Mode 3: Traditional run command
+        (None, None, Some(exec_cmd), _) => {
+            // Mode 3: Exec config (per-package execution via config, not string parsing)
+            run_exec_config_script(workspace, script, exec_cmd, &env_vars, scope).await?;
+        }
+        (None, None, None, Some(run_command)) => {
+            // Mode 4: Traditional run command
             let substituted =
-                normalize_line_continuations(&substitute_env_vars(run_command, &env_vars));
+                normalize_line_continuations(&expand_env_vars(run_command, &env_vars));
 
             if is_exec_command(&substituted) {
                 // Legacy exec-style: `melos exec -- <command>` in run string
-                run_exec_script(workspace, script, &substituted, &env_vars, cli_filters).await?;
+                run_exec_script(workspace, script, &substituted, &env_vars, scope).await?;
             } else {
                 // Regular shell command at workspace root
                 let expanded = expand_command(&substituted)?;
@@ -382,7 +624,7 @@ async fn run_script_recursive(
                         Box::pin(run_script_recursive(
                             workspace,
                             ref_name,
-                            cli_filters,
+                            scope,
                             visited,
                             depth + 1,
                         ))
@@ -390,7 +632,7 @@ async fn run_script_recursive(
                         continue;
                     }
 
-                    println!("{}{} {}", indent, ">".dimmed(), cmd.dimmed());
+                    status_println!(scope.render, "{}{} {}", indent, ">".dimmed(), cmd.dimmed());
 
                     let (shell, shell_flag) = melos_core::runner::shell_command();
                     let status = tokio::process::Command::new(shell)
@@ -414,41 +656,81 @@ async fn run_script_recursive(
                 }
             }
         }
-        (None, None, None) => {
+        (None, None, None, None) => {
             bail!(
-                "Script '{}' has no runnable configuration (no `run`, `exec`, or `steps` defined)",
+                "Script '{}' has no runnable configuration (no `run`, `exec`, `steps`, or `matrix` defined)",
                 script_name
             );
         }
     }
 
-    // Remove from visited so the same script can appear in separate chains
-    // (e.g. A -> B, A -> C -> B is fine; A -> B -> A is a cycle)
-    visited.remove(script_name);
-
     Ok(())
 }
 
+/// Run a script's `when` predicate in `root` and return its exit code.
+///
+/// The predicate's stdout is discarded; stderr stays visible so a broken
+/// predicate is easy to spot.
+async fn evaluate_when(
+    predicate: &str,
+    root: &std::path::Path,
+    env_vars: &HashMap<String, String>,
+) -> Result<i32> {
+    let (shell, shell_flag) = melos_core::runner::shell_command();
+    let status = tokio::process::Command::new(shell)
+        .arg(shell_flag)
+        .arg(predicate)
+        .current_dir(root)
+        .envs(env_vars)
+        .stdout(std::process::Stdio::null())
+        .status()
+        .await?;
+    Ok(status.code().unwrap_or(-1))
+}
+
+/// How a step of a multi-step script ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepOutcome {
+    Passed,
+    /// Failed, but marked `continueOnError`
+    FailedContinued,
+    Failed,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
 /// Execute a multi-step script workflow.
 ///
 /// Each step is either:
 /// 1. A script name reference (if it matches a script in the config) → execute inline
 /// 2. A shell command → execute at workspace root
+///
+/// A failing step aborts the remaining steps unless it is marked
+/// `continueOnError`. Each step's outcome is summarized at the end; the
+/// workflow fails if a step without `continueOnError` failed.
 async fn run_steps(
     workspace: &Workspace,
-    steps: &[String],
+    steps: &[StepEntry],
     env_vars: &HashMap<String, String>,
-    cli_filters: &PackageFilters,
+    scope: ScriptScope<'_>,
     visited: &mut HashSet<String>,
     depth: usize,
 ) -> Result<()> {
-    for (i, step) in steps.iter().enumerate() {
-        let step = step.trim();
+    let mut outcomes: Vec<(&str, StepOutcome)> = Vec::new();
+    let mut failure: Option<anyhow::Error> = None;
+
+    for (i, entry) in steps.iter().enumerate() {
+        let step = entry.script.trim();
         if step.is_empty() {
             continue;
         }
+        if failure.is_some() {
+            outcomes.push((step, StepOutcome::Skipped));
+            continue;
+        }
 
-        println!(
+        status_println!(
+            scope.render,
             "{}Step {}/{}: {}",
             "  ".repeat(depth),
             i + 1,
@@ -456,45 +738,195 @@ async fn run_steps(
             step.bold()
         );
 
-        if workspace.config.scripts.contains_key(step) {
-            Box::pin(run_script_recursive(
-                workspace,
+        let result = run_step(workspace, step, env_vars, scope, visited, depth).await;
+        match result {
+            Ok(()) => outcomes.push((step, StepOutcome::Passed)),
+            Err(e) if entry.continue_on_error => {
+                eprintln!(
+                    "{}{} Step '{}' failed, continuing: {:#}",
+                    "  ".repeat(depth),
+                    "WARNING:".yellow().bold(),
+                    step,
+                    e
+                );
+                outcomes.push((step, StepOutcome::FailedContinued));
+            }
+            Err(e) => {
+                outcomes.push((step, StepOutcome::Failed));
+                failure = Some(e);
+            }
+        }
+    }
+
+    print_outcome_summary("Steps:", &outcomes, depth, scope.render);
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Run a single step: an inline script reference or a shell command at the
+/// workspace root.
+async fn run_step(
+    workspace: &Workspace,
+    step: &str,
+    env_vars: &HashMap<String, String>,
+    scope: ScriptScope<'_>,
+    visited: &mut HashSet<String>,
+    depth: usize,
+) -> Result<()> {
+    if workspace.config.scripts.contains_key(step) {
+        return Box::pin(run_script_recursive(
+            workspace,
+            step,
+            scope,
+            visited,
+            depth + 1,
+        ))
+        .await;
+    }
+
+    let substituted = expand_env_vars(step, env_vars);
+    let expanded = expand_command(&substituted)?;
+
+    for cmd in &expanded {
+        status_println!(
+            scope.render,
+            "{}{} {}",
+            "  ".repeat(depth + 1),
+            ">".dimmed(),
+            cmd.dimmed()
+        );
+
+        let (shell, shell_flag) = melos_core::runner::shell_command();
+        let status = tokio::process::Command::new(shell)
+            .arg(shell_flag)
+            .arg(cmd)
+            .current_dir(&workspace.root_path)
+            .envs(env_vars)
+            .status()
+            .await
+            .with_context(|| format!("Failed to spawn shell for step '{}'", step))?;
+
+        if !status.success() {
+            bail!(
+                "Step '{}' failed with exit code: {}",
                 step,
-                cli_filters,
-                visited,
-                depth + 1,
-            ))
-            .await?;
+                status.code().unwrap_or(-1)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Print one line per step (or grouped script) with whether it passed,
+/// failed or was skipped.
+fn print_outcome_summary(
+    title: &str,
+    outcomes: &[(&str, StepOutcome)],
+    depth: usize,
+    render: RenderSettings,
+) {
+    if outcomes.is_empty() {
+        return;
+    }
+    let indent = "  ".repeat(depth);
+    status_println!(render, "\n{}{}", indent, title.bold());
+    for (step, outcome) in outcomes {
+        let status = match outcome {
+            StepOutcome::Passed => "PASSED".green(),
+            StepOutcome::FailedContinued => "FAILED (continued)".yellow(),
+            StepOutcome::Failed => "FAILED".red(),
+            StepOutcome::Skipped => "SKIPPED".dimmed(),
+        };
+        status_println!(render, "{}  {} {}", indent, status, step);
+    }
+}
+
+/// Run a `matrix` script: each entry runs its command in the packages matched
+/// by its own filters, one entry after another.
+///
+/// Exec options (concurrency, failFast, orderDependents, timeout) apply to
+/// every entry.
+async fn run_matrix_script(
+    workspace: &Workspace,
+    script_name: &str,
+    script: &ScriptEntry,
+    matrix: &[MatrixEntry],
+    env_vars: &HashMap<String, String>,
+    scope: ScriptScope<'_>,
+) -> Result<()> {
+    let runs = resolve_matrix(matrix, scope.cli_filters, |filters| {
+        apply_filters_with_categories(
+            &workspace.packages,
+            filters,
+            Some(&workspace.root_path),
+            &workspace.config.categories,
+        )
+        .map(|packages| restrict_to_changed(packages, scope))
+    })
+    .map_err(|e| anyhow::anyhow!("Script '{}': {:#}", script_name, e))?;
+
+    let concurrency = resolve_concurrency(
+        scope.concurrency,
+        script.exec_options().and_then(|o| o.concurrency),
+        DEFAULT_SCRIPT_CONCURRENCY,
+    );
+    let fail_fast = script.exec_options().is_some_and(|o| o.fail_fast);
+    let order_dependents = script.exec_options().is_some_and(|o| o.order_dependents);
+    let timeout = script.exec_options().and_then(|o| o.timeout_duration());
+
+    for (i, run) in runs.iter().enumerate() {
+        status_println!(
+            scope.render,
+            "Matrix {}/{}: {}",
+            i + 1,
+            runs.len(),
+            run.command.bold()
+        );
+
+        if run.packages.is_empty() {
+            status_println!(
+                scope.render,
+                "{}\n",
+                "No packages matched this entry's filters.".yellow()
+            );
+            continue;
+        }
+
+        let packages = if order_dependents {
+            topological_sort(&run.packages)
         } else {
-            let substituted = substitute_env_vars(step, env_vars);
-            let expanded = expand_command(&substituted)?;
-
-            for cmd in &expanded {
-                println!(
-                    "{}{} {}",
-                    "  ".repeat(depth + 1),
-                    ">".dimmed(),
-                    cmd.dimmed()
-                );
+            run.packages.clone()
+        };
+        for pkg in &packages {
+            status_println!(scope.render, "  {} {}", "->".cyan(), pkg.name);
+        }
+        status_println!(scope.render);
+
+        let substituted = expand_env_vars(run.command, env_vars);
+        let (tx, render_handle) = crate::render::spawn_plain_renderer(&packages, scope.render);
+        let runner = ProcessRunner::new(concurrency, fail_fast)
+            .with_working_directory(script_working_directory(script)?)
+            .with_concurrency_group(script.concurrency_group());
+        let results = runner
+            .run_in_packages_with_events(
+                &packages,
+                &substituted,
+                env_vars,
+                timeout,
+                Some(&tx),
+                &workspace.packages,
+            )
+            .await?;
+        drop(tx);
+        render_handle.await??;
 
-                let (shell, shell_flag) = melos_core::runner::shell_command();
-                let status = tokio::process::Command::new(shell)
-                    .arg(shell_flag)
-                    .arg(cmd)
-                    .current_dir(&workspace.root_path)
-                    .envs(env_vars)
-                    .status()
-                    .await
-                    .with_context(|| format!("Failed to spawn shell for step '{}'", step))?;
-
-                if !status.success() {
-                    bail!(
-                        "Step '{}' failed with exit code: {}",
-                        step,
-                        status.code().unwrap_or(-1)
-                    );
-                }
-            }
+        let failed = results.failed();
+        if failed > 0 {
+            bail!("Matrix entry {} failed in {} package(s)", i + 1, failed);
         }
     }
 
@@ -504,69 +936,87 @@ async fn run_steps(
 /// Run a script that uses exec config (not string-parsed `melos exec` style).
 ///
 /// The exec command comes from the config's `exec:` field, and options
-/// come from `ExecOptions` (concurrency, failFast, orderDependents).
+/// come from `ExecOptions` (concurrency, failFast, orderDependents, timeout).
 async fn run_exec_config_script(
     workspace: &Workspace,
     script: &ScriptEntry,
     exec_command: &str,
     env_vars: &HashMap<String, String>,
-    cli_filters: &PackageFilters,
+    scope: ScriptScope<'_>,
 ) -> Result<()> {
     // Merge script-level packageFilters with CLI filters
     let filters = if let Some(script_filters) = script.package_filters() {
-        script_filters.merge(cli_filters)
+        script_filters.merge(scope.cli_filters)
     } else {
-        cli_filters.clone()
+        scope.cli_filters.clone()
     };
 
-    let mut packages = apply_filters_with_categories(
-        &workspace.packages,
-        &filters,
-        Some(&workspace.root_path),
-        &workspace.config.categories,
-    )?;
+    let mut packages = restrict_to_changed(
+        apply_filters_with_categories(
+            &workspace.packages,
+            &filters,
+            Some(&workspace.root_path),
+            &workspace.config.categories,
+        )?,
+        scope,
+    );
 
     if packages.is_empty() {
-        println!("{}", "No packages matched the script's filters.".yellow());
+        status_println!(
+            scope.render,
+            "{}",
+            "No packages matched the script's filters.".yellow()
+        );
         return Ok(());
     }
 
-    let concurrency = script
-        .exec_options()
-        .and_then(|o| o.concurrency)
-        .unwrap_or(5);
+    let concurrency = resolve_concurrency(
+        scope.concurrency,
+        script.exec_options().and_then(|o| o.concurrency),
+        DEFAULT_SCRIPT_CONCURRENCY,
+    );
     let fail_fast = script.exec_options().is_some_and(|o| o.fail_fast);
     let order_dependents = script.exec_options().is_some_and(|o| o.order_dependents);
+    let timeout = script.exec_options().and_then(|o| o.timeout_duration());
 
     if order_dependents {
         packages = topological_sort(&packages);
-        println!(
+        status_println!(
+            scope.render,
             "{} Packages ordered by dependencies (topological sort)\n",
             "i".blue()
         );
     }
 
-    println!(
-        "Running in {} package(s) with concurrency {}:\n",
+    let timeout_display = timeout
+        .map(|d| format!(", timeout {}s", d.as_secs()))
+        .unwrap_or_default();
+
+    status_println!(
+        scope.render,
+        "Running in {} package(s) with concurrency {}{}:\n",
         packages.len().to_string().cyan(),
         concurrency.to_string().cyan(),
+        timeout_display,
     );
     for pkg in &packages {
-        println!("  {} {}", "->".cyan(), pkg.name);
+        status_println!(scope.render, "  {} {}", "->".cyan(), pkg.name);
     }
-    println!();
+    status_println!(scope.render);
 
     // Substitute env vars in the exec command
-    let substituted = substitute_env_vars(exec_command, env_vars);
+    let substituted = expand_env_vars(exec_command, env_vars);
 
-    let (tx, render_handle) = crate::render::spawn_plain_renderer();
-    let runner = ProcessRunner::new(concurrency, fail_fast);
+    let (tx, render_handle) = crate::render::spawn_plain_renderer(&packages, scope.render);
+    let runner = ProcessRunner::new(concurrency, fail_fast)
+        .with_working_directory(script_working_directory(script)?)
+        .with_concurrency_group(script.concurrency_group());
     let results = runner
         .run_in_packages_with_events(
             &packages,
             &substituted,
             env_vars,
-            None,
+            timeout,
             Some(&tx),
             &workspace.packages,
         )
@@ -574,7 +1024,7 @@ async fn run_exec_config_script(
     drop(tx);
     render_handle.await??;
 
-    let failed = results.iter().filter(|(_, success)| !success).count();
+    let failed = results.failed();
     if failed > 0 {
         bail!("{} package(s) failed", failed);
     }
@@ -592,40 +1042,49 @@ async fn run_exec_script(
     script: &ScriptEntry,
     command: &str,
     env_vars: &HashMap<String, String>,
-    cli_filters: &PackageFilters,
+    scope: ScriptScope<'_>,
 ) -> Result<()> {
     // Merge script-level packageFilters with CLI filters
     let mut filters = if let Some(script_filters) = script.package_filters() {
-        script_filters.merge(cli_filters)
+        script_filters.merge(scope.cli_filters)
     } else {
-        cli_filters.clone()
+        scope.cli_filters.clone()
     };
 
     let flags = parse_exec_flags(command);
 
-    // Apply inline --file-exists from the exec command string when not already
-    // set by packageFilters or CLI filters (inline flag is lowest priority)
-    if filters.file_exists.is_none()
-        && let Some(ref fe) = flags.file_exists
-    {
-        filters.file_exists = Some(fe.clone());
+    // Inline --file-exists from the exec command string adds to the files
+    // required by packageFilters and CLI filters
+    if let Some(ref fe) = flags.file_exists {
+        filters
+            .file_exists
+            .get_or_insert_with(Vec::new)
+            .push(fe.clone());
     }
 
-    let mut packages = apply_filters_with_categories(
-        &workspace.packages,
-        &filters,
-        Some(&workspace.root_path),
-        &workspace.config.categories,
-    )?;
+    let mut packages = restrict_to_changed(
+        apply_filters_with_categories(
+            &workspace.packages,
+            &filters,
+            Some(&workspace.root_path),
+            &workspace.config.categories,
+        )?,
+        scope,
+    );
 
     if packages.is_empty() {
-        println!("{}", "No packages matched the script's filters.".yellow());
+        status_println!(
+            scope.render,
+            "{}",
+            "No packages matched the script's filters.".yellow()
+        );
         return Ok(());
     }
 
     if flags.order_dependents {
         packages = topological_sort(&packages);
-        println!(
+        status_println!(
+            scope.render,
             "{} Packages ordered by dependencies (topological sort)\n",
             "i".blue()
         );
@@ -636,28 +1095,35 @@ async fn run_exec_script(
         .map(|d| format!(", timeout {}s", d.as_secs()))
         .unwrap_or_default();
 
-    println!(
+    status_println!(
+        scope.render,
         "Running in {} package(s) with concurrency {}{}:\n",
         packages.len().to_string().cyan(),
         flags.concurrency.to_string().cyan(),
         timeout_display,
     );
     for pkg in &packages {
-        println!("  {} {}", "->".cyan(), pkg.name);
+        status_println!(scope.render, "  {} {}", "->".cyan(), pkg.name);
     }
-    println!();
+    status_println!(scope.render);
 
     // Dry-run mode: show what would be executed without running
     if flags.dry_run {
-        println!("{}", "DRY RUN — no commands were executed.".yellow().bold());
+        status_println!(
+            scope.render,
+            "{}",
+            "DRY RUN — no commands were executed.".yellow().bold()
+        );
         return Ok(());
     }
 
     // Extract the actual command after `melos exec` / `melos-rs exec`
     let actual_cmd = extract_exec_command(command);
 
-    let (tx, render_handle) = crate::render::spawn_plain_renderer();
-    let runner = ProcessRunner::new(flags.concurrency, flags.fail_fast);
+    let (tx, render_handle) = crate::render::spawn_plain_renderer(&packages, scope.render);
+    let runner = ProcessRunner::new(flags.concurrency, flags.fail_fast)
+        .with_working_directory(script_working_directory(script)?)
+        .with_concurrency_group(script.concurrency_group());
     let results = runner
         .run_in_packages_with_events(
             &packages,
@@ -671,7 +1137,7 @@ async fn run_exec_script(
     drop(tx);
     render_handle.await??;
 
-    let failed = results.iter().filter(|(_, success)| !success).count();
+    let failed = results.failed();
     if failed > 0 {
         bail!("{} package(s) failed", failed);
     }
@@ -679,20 +1145,39 @@ async fn run_exec_script(
     Ok(())
 }
 
-/// Prompt the user to select a script interactively from available scripts
+/// In `--changed-only` watch re-runs, narrow a script's packages to the
+/// changed ones (plus dependents with `--include-dependents`).
+fn restrict_to_changed(packages: Vec<Package>, scope: ScriptScope<'_>) -> Vec<Package> {
+    match scope.changed {
+        Some(changed) => retain_affected(packages, changed, scope.cli_filters.include_dependents),
+        None => packages,
+    }
+}
+
+/// Resolve the script's `workingDirectory` for per-package commands.
+///
+/// Errors on absolute or escaping paths; `None` means the package root.
+fn script_working_directory(script: &ScriptEntry) -> Result<Option<PathBuf>> {
+    let Some(dir) = script.working_directory() else {
+        return Ok(None);
+    };
+    let normalized = normalize_working_directory(dir).map_err(|e| anyhow::anyhow!(e))?;
+    Ok((!normalized.as_os_str().is_empty()).then_some(normalized))
+}
+
+/// Prompt the user to select a script interactively from available scripts.
+///
+/// Scripts are listed as `name — description [groups]`. The user can enter a
+/// number, an exact name, or a query that narrows the list by a
+/// case-insensitive subsequence match on the script name. A query only
+/// narrows the list, even to a single script; running it takes a number or
+/// the exact name.
 fn select_script_interactive(
     workspace: &Workspace,
     include_private: bool,
     groups: &[String],
 ) -> Result<String> {
-    let scripts: Vec<(&String, &ScriptEntry)> = workspace
-        .config
-        .scripts
-        .iter()
-        .filter(|(_, entry)| include_private || !entry.is_private())
-        .filter(|(_, entry)| groups.is_empty() || groups.iter().any(|g| entry.in_group(g)))
-        .collect();
-
+    let scripts = visible_scripts(workspace, include_private, groups);
     if scripts.is_empty() {
         if !include_private && workspace.config.scripts.values().any(|e| e.is_private()) {
             bail!(
@@ -702,40 +1187,76 @@ fn select_script_interactive(
         bail!("No scripts defined in melos.yaml");
     }
 
-    println!("\n{}\n", "Select a script to run:".bold());
-    let mut sorted_scripts: Vec<_> = scripts.iter().collect();
-    sorted_scripts.sort_by_key(|(name, _)| *name);
+    println!("\n{}", "Select a script to run:".bold());
+    let mut candidates = scripts.clone();
+    loop {
+        println!();
+        for (i, (name, entry)) in candidates.iter().enumerate() {
+            println!(
+                "  {} {}",
+                format!("[{}]", i + 1).cyan(),
+                picker_row(name, entry)
+            );
+        }
 
-    for (i, (name, entry)) in sorted_scripts.iter().enumerate() {
-        let desc = entry
-            .description()
-            .map(|d| format!(" - {}", d.trim().dimmed()))
-            .unwrap_or_default();
-        println!("  {} {}{}", format!("[{}]", i + 1).cyan(), name, desc);
-    }
+        print!("\n{} ", "Enter number, name or filter:".bold());
+        io::stdout().flush().context("Failed to flush stdout")?;
+
+        let mut input = String::new();
+        let read = io::stdin()
+            .lock()
+            .read_line(&mut input)
+            .context("Failed to read user input")?;
+        if read == 0 {
+            bail!("No script selected");
+        }
+        let input = input.trim();
 
-    print!("\n{} ", "Enter number or name:".bold());
-    io::stdout().flush().context("Failed to flush stdout")?;
+        // Empty input resets the filter
+        if input.is_empty() {
+            candidates = scripts.clone();
+            continue;
+        }
 
-    let mut input = String::new();
-    io::stdin()
-        .lock()
-        .read_line(&mut input)
-        .context("Failed to read user input")?;
-    let input = input.trim();
+        if let Ok(num) = input.parse::<usize>() {
+            if num >= 1 && num <= candidates.len() {
+                return Ok(candidates[num - 1].0.to_string());
+            }
+            bail!("Invalid selection: {}", num);
+        }
 
-    // Try as number first
-    if let Ok(num) = input.parse::<usize>() {
-        if num >= 1 && num <= sorted_scripts.len() {
-            return Ok(sorted_scripts[num - 1].0.to_string());
+        if let Some((name, _)) = candidates.iter().find(|(name, _)| name.as_str() == input) {
+            return Ok(name.to_string());
         }
-        bail!("Invalid selection: {}", num);
-    }
 
-    // Try as name
-    if workspace.config.scripts.contains_key(input) {
-        return Ok(input.to_string());
+        let matches: Vec<_> = candidates
+            .iter()
+            .filter(|(name, _)| fuzzy_matches(input, name))
+            .copied()
+            .collect();
+        match matches.as_slice() {
+            [] => println!(
+                "{} No scripts match '{}'",
+                "WARNING:".yellow().bold(),
+                input
+            ),
+            _ => candidates = matches,
+        }
     }
+}
 
-    bail!("Script '{}' not found", input);
+/// Format a picker row: `name — description [group, ...]`.
+fn picker_row(name: &str, entry: &ScriptEntry) -> String {
+    let mut row = name.to_string();
+    if let Some(desc) = entry.description() {
+        // Multi-line descriptions only show their first line
+        let first_line = desc.trim().lines().next().unwrap_or_default();
+        row.push_str(&format!(" — {}", first_line.dimmed()));
+    }
+    if let Some(groups) = entry.groups()
+        && !groups.is_empty()
+    {
+        row.push_str(&format!(" {}", format!("[{}]", groups.join(", ")).cyan()));
+    }
+    row
 }
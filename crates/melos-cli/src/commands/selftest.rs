@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::Args;
+
+use melos_core::commands::selftest::{build_report, format_report};
+use melos_core::workspace::Workspace;
+
+/// Arguments for the `selftest` command
+#[derive(Args, Debug)]
+pub struct SelftestArgs {
+    /// Output the report as JSON instead of plain text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Print environment diagnostics for bug reports.
+///
+/// Read-only, and works without a workspace: a load failure is reported
+/// instead of aborting.
+pub fn run(loaded: &Result<Workspace>, args: SelftestArgs, sdk_path: Option<&str>) -> Result<()> {
+    let report = build_report(env!("CARGO_PKG_VERSION"), loaded, sdk_path);
+
+    if args.json {
+        println!(
+            "{}",
+            // safety: SelftestReport derives Serialize with only primitive/String/Option fields
+            serde_json::to_string_pretty(&report).expect("SelftestReport is always serializable")
+        );
+    } else {
+        print!("{}", format_report(&report));
+    }
+    Ok(())
+}
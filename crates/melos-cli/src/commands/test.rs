@@ -4,16 +4,19 @@ use colored::Colorize;
 
 use crate::cli::GlobalFilterArgs;
 use crate::filter_ext::package_filters_from_args;
+use crate::render::{RenderSettings, status_println};
 use melos_core::commands::test::TestOpts;
 use melos_core::package::filter::apply_filters_with_categories;
+use melos_core::runner::resolve_concurrency;
 use melos_core::workspace::Workspace;
 
 /// Arguments for the `test` command
 #[derive(Args, Debug)]
 pub struct TestArgs {
     /// Maximum number of concurrent processes
-    #[arg(short = 'c', long, default_value = "1")]
-    pub concurrency: usize,
+    /// (default: `command.test.concurrency`, else 1)
+    #[arg(short = 'c', long, value_parser = crate::cli::parse_concurrency_arg)]
+    pub concurrency: Option<usize>,
 
     /// Abort on first test failure
     #[arg(long)]
@@ -35,6 +38,19 @@ pub struct TestArgs {
     #[arg(long)]
     pub no_run: bool,
 
+    /// Also write each package's combined output to `<DIR>/<package>.log`
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<String>,
+
+    /// With --stream, print progress as newline-delimited JSON events on stdout
+    #[arg(long, requires = "stream")]
+    pub json: bool,
+
+    /// With --json, stream one event per line (`packageStarted`,
+    /// `packageOutput`, `packageFinished`, ...) instead of rendered output
+    #[arg(long, requires = "json")]
+    pub stream: bool,
+
     /// Additional arguments passed to the test runner (after --)
     #[arg(last = true)]
     pub extra_args: Vec<String>,
@@ -44,7 +60,7 @@ pub struct TestArgs {
 }
 
 /// Run `dart test` / `flutter test` across all matching packages
-pub async fn run(workspace: &Workspace, args: TestArgs) -> Result<()> {
+pub async fn run(workspace: &Workspace, args: TestArgs, render: RenderSettings) -> Result<()> {
     let filters = package_filters_from_args(&args.filters);
     let packages = apply_filters_with_categories(
         &workspace.packages,
@@ -54,18 +70,25 @@ pub async fn run(workspace: &Workspace, args: TestArgs) -> Result<()> {
     )?;
 
     if packages.is_empty() {
-        println!("{}", "No packages matched the given filters.".yellow());
+        status_println!(
+            render,
+            "{}",
+            "No packages matched the given filters.".yellow()
+        );
         return Ok(());
     }
 
     // Only include packages that actually have a test directory
-    let testable_packages: Vec<_> = packages
+    let (testable_packages, untestable_packages): (Vec<_>, Vec<_>) = packages
         .into_iter()
-        .filter(|pkg| pkg.path.join("test").is_dir())
-        .collect();
+        .partition(|pkg| pkg.path.join("test").is_dir());
 
     if testable_packages.is_empty() {
-        println!("{}", "No packages with test/ directory found.".yellow());
+        status_println!(
+            render,
+            "{}",
+            "No packages with test/ directory found.".yellow()
+        );
         return Ok(());
     }
 
@@ -73,21 +96,37 @@ pub async fn run(workspace: &Workspace, args: TestArgs) -> Result<()> {
         crate::runner::run_lifecycle_hook(pre_hook, "pre-test", &workspace.root_path, &[]).await?;
     }
 
-    println!(
-        "\n{} Running tests in {} package(s)...\n",
-        "$".cyan(),
-        testable_packages.len()
-    );
-
-    for pkg in &testable_packages {
-        let sdk = if pkg.is_flutter { "flutter" } else { "dart" };
-        println!("  {} {} ({})", "->".cyan(), pkg.name, sdk);
+    // The JSON event stream keeps stdout free of anything but events
+    if !args.stream {
+        status_println!(
+            render,
+            "\n{} Running tests in {} package(s)...\n",
+            "$".cyan(),
+            testable_packages.len()
+        );
+
+        if !render.quiet_failures {
+            for pkg in &testable_packages {
+                let sdk = if pkg.is_flutter { "flutter" } else { "dart" };
+                status_println!(render, "  {} {} ({})", "->".cyan(), pkg.name, sdk);
+            }
+            status_println!(render);
+        }
     }
-    println!();
 
+    let test_config = workspace
+        .config
+        .command
+        .as_ref()
+        .and_then(|c| c.test.as_ref());
     let opts = TestOpts {
-        concurrency: args.concurrency,
+        concurrency: resolve_concurrency(
+            args.concurrency,
+            test_config.and_then(|t| t.concurrency),
+            1,
+        ),
         fail_fast: args.fail_fast,
+        timeout: test_config.and_then(|t| t.timeout_duration()),
         coverage: args.coverage,
         test_randomize_ordering_seed: args.test_randomize_ordering_seed,
         update_goldens: args.update_goldens,
@@ -95,23 +134,47 @@ pub async fn run(workspace: &Workspace, args: TestArgs) -> Result<()> {
         extra_args: args.extra_args,
     };
 
-    let (tx, render_handle) = crate::render::spawn_renderer(testable_packages.len(), "testing");
+    let (tx, render_handle) = if args.stream {
+        crate::render::spawn_json_renderer()
+    } else {
+        crate::render::spawn_renderer(&testable_packages, "testing", render)
+    };
+    let (tx, log_handle) = crate::render::attach_log_dir(args.output_dir.as_deref(), tx)?;
+    for pkg in &untestable_packages {
+        let _ = tx.send(melos_core::events::Event::PackageSkipped {
+            name: pkg.name.clone(),
+            reason: "no test/".to_string(),
+        });
+    }
     let results =
         melos_core::commands::test::run(&testable_packages, workspace, &opts, Some(&tx)).await?;
     drop(tx);
+    if let Some(log_handle) = log_handle {
+        log_handle.await??;
+    }
     render_handle.await??;
 
     let failed = results.failed();
     let passed = results.passed();
 
+    let others = crate::render::other_counts_suffix(&results);
+
     if failed > 0 {
-        anyhow::bail!("{} package(s) failed testing ({} passed)", failed, passed);
+        anyhow::bail!(
+            "{} package(s) failed testing ({} passed{})",
+            failed,
+            passed,
+            others
+        );
     }
 
-    println!(
-        "\n{}",
-        format!("All {} package(s) passed testing.", passed).green()
-    );
+    if !args.stream {
+        status_println!(
+            render,
+            "\n{}",
+            format!("All {} package(s) passed testing{}.", passed, others).green()
+        );
+    }
 
     if let Some(post_hook) = workspace.hook("test", "post") {
         crate::runner::run_lifecycle_hook(post_hook, "post-test", &workspace.root_path, &[])
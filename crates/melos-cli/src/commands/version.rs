@@ -1,17 +1,28 @@
-use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
 use clap::Args;
 use colored::Colorize;
 use semver::Version;
 
 use crate::filter_ext::package_filters_from_args;
+use crate::render::RenderSettings;
+use melos_core::commands::publish::build_git_tag;
 use melos_core::commands::version::{
-    BumpType, ChangelogOptions, ConventionalCommit, apply_version_bump, compute_next_prerelease,
-    compute_next_version, create_git_tag, create_release_branch, find_latest_git_tag,
-    generate_changelog_entry, git_checkout, git_commit, git_current_branch, git_fetch_tags,
-    git_push, graduate_version, highest_bump, is_prerelease, map_commits_to_packages,
-    package_matches_filters, parse_commits_since, push_release_branch,
-    update_dependency_constraint, update_git_tag_refs, validate_branch, write_changelog,
+    BumpType, COMMIT_MESSAGE_PLACEHOLDERS, ChangelogOptions, CommitRange, ConventionalCommit,
+    PlannedCommit, PlannedVersion, UNRELEASED_VERSION, VersionPlan, apply_version_bump,
+    chrono_date_today, compute_next_prerelease, compute_next_version, create_git_tag,
+    create_release_branch, find_existing_tags, find_latest_git_tag, generate_changelog_entry,
+    generate_dependency_changelog_entry, git_checkout, git_commit, git_current_branch,
+    git_fetch_tags, git_push, graduate_version, highest_bump, is_prerelease, list_git_tags,
+    map_commits_to_packages, package_matches_filters, parse_commits_since, parse_version_lenient,
+    plan_dependents_constraints, push_release_branch, render_commit_message,
+    replace_changelog_entry, resolve_version_fallbacks, run_package_version_hook,
+    update_dependents_constraints, update_git_tag_refs, validate_branch, version_change_kind,
+    write_changelog,
 };
+use melos_core::config::VersionCommandConfig;
+use melos_core::package::Package;
 use melos_core::package::filter::apply_filters_with_categories;
 use melos_core::workspace::Workspace;
 
@@ -38,15 +49,25 @@ pub struct VersionArgs {
     #[arg(long)]
     pub conventional_commits: bool,
 
-    /// Git ref to find conventional commits since (used with --conventional-commits).
-    /// If not provided, defaults to the latest git tag or HEAD~10 if no tags exist.
-    #[arg(long)]
+    /// Git ref or ISO-8601 date (e.g. 2024-05-01) to find conventional commits since
+    /// (used with --conventional-commits). Takes precedence over the latest git tag;
+    /// if not provided, defaults to the latest git tag or HEAD~10 if no tags exist.
+    #[arg(long, value_name = "REF_OR_DATE")]
     pub since_ref: Option<String>,
 
     /// Skip changelog generation
     #[arg(long)]
     pub no_changelog: bool,
 
+    /// Only regenerate CHANGELOG.md files from conventional commits since the
+    /// last tag, under an `Unreleased` heading; released sections are kept.
+    /// Versions, tags and git history are left untouched.
+    #[arg(
+        long,
+        conflicts_with_all = ["no_changelog", "dry_run", "coordinated", "graduate", "prerelease"]
+    )]
+    pub changelog_only: bool,
+
     /// Generate changelogs (default: true). Alias for the positive side of --[no-]changelog.
     #[arg(short = 'c', long, conflicts_with = "no_changelog")]
     pub changelog: bool,
@@ -59,6 +80,11 @@ pub struct VersionArgs {
     #[arg(short = 't', long, conflicts_with = "no_git_tag_version")]
     pub git_tag_version: bool,
 
+    /// Replace tags that already exist for the new versions instead of
+    /// failing before any change; only the replaced tags are force-pushed
+    #[arg(long, conflicts_with = "no_git_tag_version")]
+    pub overwrite_tags: bool,
+
     /// Skip pushing commits and tags to remote
     #[arg(long)]
     pub no_git_push: bool,
@@ -67,9 +93,18 @@ pub struct VersionArgs {
     #[arg(long)]
     pub coordinated: bool,
 
-    /// Version as prerelease (e.g., 1.0.0-dev.0). Cannot combine with --graduate.
-    #[arg(long, short = 'p', conflicts_with = "graduate")]
-    pub prerelease: bool,
+    /// Version as prerelease (e.g., 1.0.0-dev.0), optionally with its identifier
+    /// (`--prerelease=beta`, overriding --preid). Cannot combine with --graduate.
+    #[arg(
+        long,
+        short = 'p',
+        value_name = "ID",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "",
+        conflicts_with = "graduate"
+    )]
+    pub prerelease: Option<String>,
 
     /// Graduate prerelease packages to stable (e.g., 1.0.0-dev.3 -> 1.0.0).
     /// Cannot combine with --prerelease.
@@ -84,11 +119,13 @@ pub struct VersionArgs {
     #[arg(long)]
     pub dependent_preid: Option<String>,
 
-    /// Override the release commit message. Use {new_package_versions} as placeholder.
+    /// Override the release commit message. Placeholders: {new_package_versions},
+    /// {new_version}, {package_name}, {package_list}, {count} and {date}
     #[arg(long, short = 'm')]
     pub message: Option<String>,
 
-    /// Update dependency constraints in dependent packages (default: true)
+    /// Update dependents' constraints that no longer allow a bumped package's
+    /// new version (default: true; see `updateDependentsConstraints`)
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     pub dependent_constraints: bool,
 
@@ -117,49 +154,297 @@ pub struct VersionArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// With --dry-run, print the plan as JSON on stdout: each package's current
+    /// and new version, bump, triggering commits, changelog and tag
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+
     #[command(flatten)]
     pub filters: crate::cli::GlobalFilterArgs,
 }
 
+impl VersionArgs {
+    /// Whether versions are cut as prereleases.
+    fn is_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    /// Prerelease identifier: `--prerelease=<id>` if given, else `--preid`.
+    fn preid(&self) -> &str {
+        match self.prerelease.as_deref() {
+            Some(id) if !id.is_empty() => id,
+            _ => &self.preid,
+        }
+    }
+
+    /// Bump for a dependent versioned only because its constraints changed:
+    /// a patch, or the next patch prerelease when cutting prereleases.
+    fn dependent_bump(&self, pkg: &Package) -> String {
+        if self.is_prerelease() {
+            let preid = self.dependent_preid.as_deref().unwrap_or(self.preid());
+            let current = pkg.version.as_deref().unwrap_or("0.0.0");
+            compute_next_prerelease(current, "patch", preid)
+                .map(|v| v.to_string())
+                .unwrap_or_else(|_| "patch".to_string())
+        } else {
+            "patch".to_string()
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Main entry point
 // ---------------------------------------------------------------------------
 
+/// Print a progress line, to stderr with `--json` so stdout carries only the plan.
+fn say(json: bool, line: impl std::fmt::Display) {
+    if json {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Print a `--json` plan on stdout.
+fn print_plan(plan: &VersionPlan) -> Result<()> {
+    let json = serde_json::to_string_pretty(plan).context("Failed to serialize version plan")?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Run a `prePackage`/`postPackage` hook in each versioned package, bailing
+/// before the release commit if it fails.
+async fn run_package_hook(
+    workspace: &Workspace,
+    hook: &str,
+    label: &str,
+    all_packages: &[Package],
+    versioned: &[(String, String)],
+    render: RenderSettings,
+) -> Result<()> {
+    let targets: Vec<(&Package, String)> = versioned
+        .iter()
+        .filter_map(|(name, version)| {
+            all_packages
+                .iter()
+                .find(|p| &p.name == name)
+                .map(|p| (p, version.clone()))
+        })
+        .collect();
+    println!(
+        "\n{} Running {} hook in {} package(s): {}",
+        "$".cyan(),
+        label,
+        targets.len(),
+        hook
+    );
+    let packages: Vec<Package> = targets.iter().map(|(p, _)| (*p).clone()).collect();
+    let (tx, render_handle) = crate::render::spawn_plain_renderer(&packages, render);
+    let results = run_package_version_hook(workspace, hook, &targets, Some(&tx)).await;
+    drop(tx);
+    render_handle.await??;
+
+    if let Some((name, _)) = results?.results.iter().find(|(_, success)| !success) {
+        anyhow::bail!(
+            "{} hook failed in {}; version changes were not committed",
+            label,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Write package, workspace and aggregate changelogs from conventional
+/// commits.
+///
+/// Each package in `targets` gets an entry headed with its paired version;
+/// the workspace and aggregate changelogs use the first target's. With
+/// `replace`, an entry for the same version at the top of a changelog is
+/// rewritten rather than a new one prepended.
+fn write_changelogs(
+    workspace: &Workspace,
+    version_config: Option<&VersionCommandConfig>,
+    mapped: &HashMap<String, Vec<ConventionalCommit>>,
+    targets: &[(&Package, String)],
+    all_packages: &[Package],
+    opts: &ChangelogOptions<'_>,
+    replace: bool,
+) -> Result<()> {
+    let write = |dir: &std::path::Path, version: &str, entry: &str| {
+        if replace {
+            replace_changelog_entry(dir, version, entry)
+        } else {
+            write_changelog(dir, entry)
+        }
+    };
+
+    let summary_version = targets.first().map(|(_, v)| v.as_str()).unwrap_or("0.0.0");
+
+    println!("\n{} Generating changelogs...", "$".cyan());
+    for (pkg, version) in targets {
+        if let Some(commits) = mapped.get(&pkg.name)
+            && !commits.is_empty()
+        {
+            let entry = generate_changelog_entry(version, commits, opts);
+            write(&pkg.path, version, &entry)?;
+            println!(
+                "  {} Updated CHANGELOG.md for {}",
+                "OK".green(),
+                pkg.name.bold()
+            );
+        }
+    }
+
+    // Workspace-level changelog
+    let should_workspace = version_config
+        .map(|c| c.should_workspace_changelog())
+        .unwrap_or(true);
+    if should_workspace {
+        let all_commits: Vec<ConventionalCommit> = mapped.values().flatten().cloned().collect();
+        if !all_commits.is_empty() {
+            let entry = generate_changelog_entry(summary_version, &all_commits, opts);
+            write(&workspace.root_path, summary_version, &entry)?;
+            println!("  {} Updated workspace CHANGELOG.md", "OK".green());
+        }
+    }
+
+    // Aggregate changelogs
+    if let Some(agg_configs) = version_config.and_then(|c| c.changelogs.as_ref()) {
+        for agg in agg_configs {
+            let agg_path = workspace.root_path.join(&agg.path);
+
+            // Filter commits to only those from packages matching the aggregate filters
+            let agg_commits: Vec<ConventionalCommit> =
+                if let Some(ref filters) = agg.package_filters {
+                    mapped
+                        .iter()
+                        .filter(|(pkg_name, _)| {
+                            package_matches_filters(pkg_name, filters, all_packages)
+                        })
+                        .flat_map(|(_, commits)| commits.iter().cloned())
+                        .collect()
+                } else {
+                    // No filters -- include all commits
+                    mapped.values().flatten().cloned().collect()
+                };
+
+            if !agg_commits.is_empty() {
+                let entry = generate_changelog_entry(summary_version, &agg_commits, opts);
+
+                // If the file has a description configured, ensure it's at the top
+                let full_entry = if let Some(ref desc) = agg.description {
+                    if !agg_path.exists() {
+                        format!("# Changelog\n\n{}\n\n{}", desc, entry)
+                    } else {
+                        entry
+                    }
+                } else {
+                    entry
+                };
+
+                write(&agg_path, summary_version, &full_entry)?;
+                println!(
+                    "  {} Updated aggregate changelog {}",
+                    "OK".green(),
+                    agg.path.bold()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Execute the version command
-pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
-    println!("\n{} Managing versions across packages...\n", "$".cyan());
+pub async fn run(workspace: &Workspace, args: VersionArgs, render: RenderSettings) -> Result<()> {
+    let json = args.json;
+    say(
+        json,
+        format!("\n{} Managing versions across packages...\n", "$".cyan()),
+    );
 
     if workspace.packages.is_empty() {
-        println!("{}", "No packages found in workspace.".yellow());
-        return Ok(());
+        say(json, "No packages found in workspace.".yellow());
+        return if json {
+            print_plan(&VersionPlan::default())
+        } else {
+            Ok(())
+        };
+    }
+
+    let version_config = workspace
+        .config
+        .command
+        .as_ref()
+        .and_then(|c| c.version.as_ref());
+
+    // Packages without a pubspec version fall back to version.txt (with
+    // `versionFile: true`), then to their latest `<name>-v<version>` git tag.
+    // Packages with neither are left out rather than assumed to be 0.0.0.
+    let mut all_packages = workspace.packages.clone();
+    let mut unversioned = Vec::new();
+    let use_version_file = version_config.is_some_and(|c| c.should_use_version_file());
+    for (name, source) in
+        resolve_version_fallbacks(&workspace.root_path, &mut all_packages, use_version_file)
+    {
+        match source {
+            Some(source) => {
+                let version = all_packages
+                    .iter()
+                    .find(|p| p.name == name)
+                    .and_then(|p| p.version.as_deref())
+                    .unwrap_or_default();
+                say(
+                    json,
+                    format!(
+                        "  {} {}: version {} from {}",
+                        "i".blue(),
+                        name.bold(),
+                        version,
+                        source
+                    ),
+                );
+            }
+            None => unversioned.push(name),
+        }
+    }
+    if !unversioned.is_empty() {
+        say(
+            json,
+            format!(
+                "  {} No version found for {}; skipping. Add `version:` to pubspec.yaml, a version.txt (with `command.version.versionFile: true`), or a `<name>-v<version>` git tag.",
+                "WARN:".yellow(),
+                unversioned.join(", ")
+            ),
+        );
     }
 
     // Apply global filters to narrow down which packages are eligible for versioning.
     // This allows `melos-rs version --scope core_* --all patch` to bump only matching packages.
     let filters = package_filters_from_args(&args.filters);
     let eligible_packages = if filters.is_empty() {
-        workspace.packages.clone()
+        all_packages.clone()
     } else {
         apply_filters_with_categories(
-            &workspace.packages,
+            &all_packages,
             &filters,
             Some(&workspace.root_path),
             &workspace.config.categories,
         )?
     };
-
-    let version_config = workspace
-        .config
-        .command
-        .as_ref()
-        .and_then(|c| c.version.as_ref());
+    let eligible_packages: Vec<_> = eligible_packages
+        .into_iter()
+        .filter(|p| !unversioned.contains(&p.name))
+        .collect();
 
     // Branch validation
     if let Some(cfg) = version_config
         && let Some(ref branch) = cfg.branch
     {
         validate_branch(&workspace.root_path, branch)?;
-        println!("  {} Branch validation passed ({})", "OK".green(), branch);
+        say(
+            json,
+            format!("  {} Branch validation passed ({})", "OK".green(), branch),
+        );
     }
 
     // Fetch tags from remote if configured
@@ -167,9 +452,12 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
         .map(|c| c.should_fetch_tags())
         .unwrap_or(false)
     {
-        println!("  {} Fetching tags from remote...", "$".cyan());
+        say(
+            json,
+            format!("  {} Fetching tags from remote...", "$".cyan()),
+        );
         git_fetch_tags(&workspace.root_path)?;
-        println!("  {} Tags fetched", "OK".green());
+        say(json, format!("  {} Tags fetched", "OK".green()));
     }
 
     // Determine changelog/tag settings from config + CLI flags
@@ -186,17 +474,9 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
 
     // Resolve commit body inclusion: changelogCommitBodies takes precedence over
     // changelogConfig.includeCommitBody for backward compatibility.
-    let (include_body, only_breaking_bodies) =
-        if let Some(bodies_cfg) = version_config.and_then(|c| c.changelog_commit_bodies.as_ref()) {
-            (bodies_cfg.include, bodies_cfg.only_breaking)
-        } else {
-            let body = version_config
-                .and_then(|c| c.changelog_config.as_ref())
-                .and_then(|cc| cc.include_commit_body)
-                .unwrap_or(false);
-            // Legacy includeCommitBody includes ALL bodies (not just breaking)
-            (body, false)
-        };
+    let (include_body, only_breaking_bodies) = version_config
+        .map(|c| c.commit_body_rules())
+        .unwrap_or((false, false));
 
     let include_hash = version_config
         .and_then(|c| c.changelog_config.as_ref())
@@ -221,28 +501,97 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
         .and_then(|c| c.changelog_config.as_ref())
         .and_then(|cc| cc.exclude_types.clone());
 
+    let changelog_opts = ChangelogOptions {
+        include_body,
+        only_breaking_bodies,
+        include_hash,
+        include_scopes,
+        repository: workspace.config.repository.as_ref(),
+        include_types: changelog_include_types.as_deref(),
+        exclude_types: changelog_exclude_types.as_deref(),
+        include_date,
+    };
+
     // Collect conventional commits if requested
-    let conventional_commits = if args.conventional_commits {
-        // Resolve since_ref: CLI flag -> latest git tag -> fallback "HEAD~10"
-        let since_ref = args.since_ref.clone().unwrap_or_else(|| {
-            find_latest_git_tag(&workspace.root_path).unwrap_or_else(|| "HEAD~10".to_string())
+    let conventional_commits = if args.conventional_commits || args.changelog_only {
+        // Resolve the range: CLI flag -> latest git tag -> fallback "HEAD~10"
+        let range = CommitRange::resolve(args.since_ref.as_deref(), || {
+            find_latest_git_tag(&workspace.root_path)
         });
-        let commits = parse_commits_since(&workspace.root_path, &since_ref)?;
-        println!(
-            "  Found {} conventional commit(s) since {}",
-            commits.len().to_string().bold(),
-            since_ref
+        let commits = parse_commits_since(&workspace.root_path, &range)?;
+        say(
+            json,
+            format!(
+                "  Found {} conventional commit(s) since {}",
+                commits.len().to_string().bold(),
+                range
+            ),
         );
-        let mapped = map_commits_to_packages(&workspace.root_path, &commits, &workspace.packages)?;
+        let mapped = map_commits_to_packages(
+            &workspace.root_path,
+            &commits,
+            &all_packages,
+            include_scopes,
+        )?;
         Some(mapped)
     } else {
         None
     };
 
+    // Changelog-only: rewrite the unreleased section and stop. Commits since
+    // the last tag belong to no release yet, so released sections are kept.
+    if args.changelog_only {
+        let mapped = conventional_commits
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("conventional commits not loaded; this is a bug"))?;
+        let targets: Vec<(&Package, String)> = eligible_packages
+            .iter()
+            .filter(|p| mapped.get(&p.name).is_some_and(|c| !c.is_empty()))
+            .map(|p| (p, UNRELEASED_VERSION.to_string()))
+            .collect();
+        if targets.is_empty() {
+            println!(
+                "{}",
+                "No conventional commits to write changelogs for.".yellow()
+            );
+            return Ok(());
+        }
+        write_changelogs(
+            workspace,
+            version_config,
+            mapped,
+            &targets,
+            &all_packages,
+            &ChangelogOptions {
+                include_date: false,
+                ..changelog_opts
+            },
+            true,
+        )?;
+        println!(
+            "\n{}",
+            "Changelogs regenerated; versions, tags and commits were left untouched.".green()
+        );
+        return Ok(());
+    }
+
     // Determine whether coordinated versioning is enabled (CLI flag or config)
     let is_coordinated =
         args.coordinated || version_config.map(|c| c.is_coordinated()).unwrap_or(false);
 
+    // How packages are selected, mirroring the branches below
+    let reason = if args.graduate {
+        "graduate"
+    } else if is_coordinated {
+        "coordinated"
+    } else if !args.overrides.is_empty() {
+        "override"
+    } else if args.conventional_commits {
+        "conventionalCommits"
+    } else {
+        "all"
+    };
+
     // Determine which packages to version and how.
     //
     // The result is a Vec of (package, target_version_string) where the target
@@ -265,14 +614,21 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
             .collect();
 
         if graduated.is_empty() {
-            println!("{}", "No prerelease packages to graduate.".yellow());
-            return Ok(());
+            say(json, "No prerelease packages to graduate.".yellow());
+            return if json {
+                print_plan(&VersionPlan::default())
+            } else {
+                Ok(())
+            };
         }
 
-        println!(
-            "  {} Graduating {} prerelease package(s) to stable",
-            "INFO".blue(),
-            graduated.len()
+        say(
+            json,
+            format!(
+                "  {} Graduating {} prerelease package(s) to stable",
+                "INFO".blue(),
+                graduated.len()
+            ),
         );
         graduated
     } else if is_coordinated {
@@ -295,17 +651,20 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
             "{}.{}.{}",
             highest_current.major, highest_current.minor, highest_current.patch
         );
-        let coordinated_version = if args.prerelease {
-            compute_next_prerelease(&base_str, &args.bump, &args.preid)?
+        let coordinated_version = if args.is_prerelease() {
+            compute_next_prerelease(&base_str, &args.bump, args.preid())?
         } else {
             compute_next_version(&base_str, &args.bump)?
         };
         let explicit = coordinated_version.to_string();
 
-        println!(
-            "  {} Coordinated versioning: all packages -> {}",
-            "INFO".blue(),
-            explicit.green()
+        say(
+            json,
+            format!(
+                "  {} Coordinated versioning: all packages -> {}",
+                "INFO".blue(),
+                explicit.green()
+            ),
         );
 
         eligible_packages
@@ -321,9 +680,9 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
                     .iter()
                     .find(|p| p.name.contains(name))
                     .map(|p| {
-                        if args.prerelease {
+                        if args.is_prerelease() {
                             let current = p.version.as_deref().unwrap_or("0.0.0");
-                            let v = compute_next_prerelease(current, bump, &args.preid)
+                            let v = compute_next_prerelease(current, bump, args.preid())
                                 .map(|v| v.to_string())
                                 .unwrap_or_else(|_| bump.clone());
                             (p, v)
@@ -345,9 +704,9 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
                 let bump = highest_bump(commits);
                 if bump == BumpType::None {
                     None
-                } else if args.prerelease {
+                } else if args.is_prerelease() {
                     let current = p.version.as_deref().unwrap_or("0.0.0");
-                    let v = compute_next_prerelease(current, &bump.to_string(), &args.preid)
+                    let v = compute_next_prerelease(current, &bump.to_string(), args.preid())
                         .map(|v| v.to_string())
                         .unwrap_or_else(|_| bump.to_string());
                     Some((p, v))
@@ -358,12 +717,12 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
             .collect()
     } else if args.all {
         // Apply to all packages with the default bump type
-        if args.prerelease {
+        if args.is_prerelease() {
             eligible_packages
                 .iter()
                 .map(|p| {
                     let current = p.version.as_deref().unwrap_or("0.0.0");
-                    let v = compute_next_prerelease(current, &args.bump, &args.preid)
+                    let v = compute_next_prerelease(current, &args.bump, args.preid())
                         .map(|v| v.to_string())
                         .unwrap_or_else(|_| args.bump.clone());
                     (p, v)
@@ -376,17 +735,53 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
                 .collect()
         }
     } else {
-        println!(
-            "{}",
+        say(
+            json,
             "Specify --all, --conventional-commits, --graduate, or use -V overrides to select packages."
-                .yellow()
+                .yellow(),
         );
-        return Ok(());
+        return if json {
+            print_plan(&VersionPlan::default())
+        } else {
+            Ok(())
+        };
     };
 
     if packages_to_version.is_empty() {
-        println!("{}", "No packages need version bumps.".yellow());
-        return Ok(());
+        say(json, "No packages need version bumps.".yellow());
+        return if json {
+            print_plan(&VersionPlan::default())
+        } else {
+            Ok(())
+        };
+    }
+
+    if json {
+        let mut plan = VersionPlan::default();
+        for (pkg, bump) in &packages_to_version {
+            let current = pkg.version.as_deref().unwrap_or("0.0.0");
+            let next = compute_next_version(current, bump)?;
+            let current_version =
+                parse_version_lenient(current).unwrap_or_else(|| Version::new(0, 0, 0));
+            let commits = match conventional_commits {
+                Some(ref mapped) if reason == "conventionalCommits" => mapped
+                    .get(&pkg.name)
+                    .map(|commits| PlannedCommit::triggering(commits))
+                    .unwrap_or_default(),
+                _ => Vec::new(),
+            };
+            plan.packages.push(PlannedVersion {
+                package: pkg.name.clone(),
+                current_version: current.to_string(),
+                new_version: next.to_string(),
+                bump: version_change_kind(&current_version, &next),
+                reason,
+                commits,
+                changelog: should_changelog,
+                tag: should_tag.then(|| build_git_tag(&pkg.name, &next.to_string())),
+            });
+        }
+        return print_plan(&plan);
     }
 
     // Show plan
@@ -414,6 +809,54 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
         return Ok(());
     }
 
+    let should_update_constraints = args.dependent_constraints
+        && version_config.is_none_or(|c| c.should_update_dependents_constraints());
+
+    // Pre-flight: tags left by an earlier, partially failed run would make
+    // tagging fail after the release commit. Dependents bumped for their
+    // constraints are tagged too, so they are checked as well.
+    if should_tag && !args.overwrite_tags {
+        let mut planned = packages_to_version
+            .iter()
+            .map(|(pkg, bump)| {
+                let current = pkg.version.as_deref().unwrap_or("0.0.0");
+                Ok((
+                    pkg.name.clone(),
+                    compute_next_version(current, bump)?.to_string(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if should_update_constraints && args.dependent_versions {
+            let updates = plan_dependents_constraints(&all_packages, &planned)?;
+            for update in &updates {
+                if planned.iter().any(|(name, _)| name == &update.package) {
+                    continue;
+                }
+                let Some(pkg) = all_packages
+                    .iter()
+                    .find(|p| p.name == update.package && p.version.is_some())
+                else {
+                    continue;
+                };
+                let current = pkg.version.as_deref().unwrap_or("0.0.0");
+                let next = compute_next_version(current, &args.dependent_bump(pkg))?;
+                planned.push((pkg.name.clone(), next.to_string()));
+            }
+        }
+        let existing = list_git_tags(&workspace.root_path)?;
+        let collisions = find_existing_tags(&existing, &planned);
+        if !collisions.is_empty() {
+            let lines: Vec<String> = collisions
+                .iter()
+                .map(|(name, tag)| format!("  {}: tag {} already exists", name, tag))
+                .collect();
+            bail!(
+                "Planned tags already exist:\n{}\nDelete them (git tag -d <tag>) or pass --overwrite-tags to replace them.",
+                lines.join("\n")
+            );
+        }
+    }
+
     if !args.yes {
         print!(
             "\n{} Apply these version changes? [y/N] ",
@@ -445,187 +888,94 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
     }
 
     // Update dependent package constraints (--dependent-constraints, default: on)
-    if args.dependent_constraints && !versioned.is_empty() {
-        let versioned_names: std::collections::HashMap<&str, &str> = versioned
-            .iter()
-            .map(|(n, v)| (n.as_str(), v.as_str()))
-            .collect();
-
-        // Find packages that depend on any bumped package but were not themselves bumped
-        let mut dependents_to_bump: Vec<(&melos_core::package::Package, String)> = Vec::new();
-
-        for pkg in &workspace.packages {
-            if versioned_names.contains_key(pkg.name.as_str()) {
-                continue; // Already bumped
-            }
-
-            let mut was_updated = false;
-            for dep_name in pkg.dependencies.iter().chain(pkg.dev_dependencies.iter()) {
-                if let Some(&new_ver) = versioned_names.get(dep_name.as_str()) {
-                    let updated = update_dependency_constraint(pkg, dep_name, new_ver)?;
-                    if updated {
-                        let constraint = format!(
-                            "^{}",
-                            semver::Version::parse(new_ver)
-                                .or_else(|_| {
-                                    let cleaned = new_ver.split('+').next().unwrap_or(new_ver);
-                                    semver::Version::parse(cleaned)
-                                })
-                                .unwrap_or_else(|_| semver::Version::new(0, 0, 0))
-                        );
-                        println!(
-                            "  {} Updated {} dependency on {} to {}",
-                            "OK".green(),
-                            pkg.name.bold(),
-                            dep_name,
-                            constraint
-                        );
-                        was_updated = true;
-                    }
-                }
-            }
-
-            if was_updated && args.dependent_versions {
-                // Determine the version for the dependent
-                let dependent_ver = if args.prerelease {
-                    let preid = args.dependent_preid.as_deref().unwrap_or(&args.preid);
-                    let current = pkg.version.as_deref().unwrap_or("0.0.0");
-                    compute_next_prerelease(current, "patch", preid)
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|_| "patch".to_string())
-                } else {
-                    "patch".to_string()
-                };
-                dependents_to_bump.push((pkg, dependent_ver));
+    if should_update_constraints && !versioned.is_empty() {
+        let updates = update_dependents_constraints(&all_packages, &versioned)?;
+        for update in &updates {
+            for (dep_name, constraint) in &update.constraints {
+                println!(
+                    "  {} Updated {} dependency on {} to {}",
+                    "OK".green(),
+                    update.package.bold(),
+                    dep_name,
+                    constraint
+                );
             }
         }
 
-        if !dependents_to_bump.is_empty() {
+        // Packages versioned in this run already carry their own bump
+        let unbumped: Vec<_> = updates
+            .iter()
+            .filter(|u| !versioned.iter().any(|(name, _)| name == &u.package))
+            .collect();
+        if !unbumped.is_empty() && args.dependent_versions {
             println!(
                 "\n{} Bumping {} dependent package(s)...",
                 "$".cyan(),
-                dependents_to_bump.len()
+                unbumped.len()
             );
-            for (pkg, bump) in &dependents_to_bump {
-                let new_version = apply_version_bump(pkg, bump)?;
+            for update in unbumped {
+                let Some(pkg) = all_packages
+                    .iter()
+                    .find(|p| p.name == update.package && p.version.is_some())
+                else {
+                    continue;
+                };
+                let new_version = apply_version_bump(pkg, &args.dependent_bump(pkg))?;
                 println!(
                     "  {} Updated {} to {}",
                     "OK".green(),
                     pkg.path.join("pubspec.yaml").display(),
                     new_version
                 );
+                if should_changelog {
+                    let entry = generate_dependency_changelog_entry(
+                        &new_version,
+                        &update.constraints,
+                        include_date,
+                    );
+                    write_changelog(&pkg.path, &entry)?;
+                }
                 versioned.push((pkg.name.clone(), new_version));
             }
         }
     }
 
+    let version_hooks = version_config.and_then(|cfg| cfg.hooks.as_ref());
+    if let Some(hook) = version_hooks.and_then(|h| h.pre_package.as_deref()) {
+        run_package_hook(
+            workspace,
+            hook,
+            "prePackage",
+            &all_packages,
+            &versioned,
+            render,
+        )
+        .await?;
+    }
+
     // Generate changelogs
     if should_changelog {
         if let Some(ref mapped) = conventional_commits {
-            let repo = workspace.config.repository.as_ref();
-            let make_changelog_opts = || ChangelogOptions {
-                include_body,
-                only_breaking_bodies,
-                include_hash,
-                include_scopes,
-                repository: repo,
-                include_types: changelog_include_types.as_deref(),
-                exclude_types: changelog_exclude_types.as_deref(),
-                include_date,
-            };
-            println!("\n{} Generating changelogs...", "$".cyan());
-            for (pkg, _bump) in &packages_to_version {
-                if let Some(commits) = mapped.get(&pkg.name)
-                    && !commits.is_empty()
-                {
+            let targets: Vec<(&Package, String)> = packages_to_version
+                .iter()
+                .map(|(pkg, _bump)| {
                     let new_ver = versioned
                         .iter()
                         .find(|(n, _)| n == &pkg.name)
-                        .map(|(_, v)| v.as_str())
-                        .unwrap_or("unknown");
-                    let entry = generate_changelog_entry(new_ver, commits, &make_changelog_opts());
-                    write_changelog(&pkg.path, &entry)?;
-                    println!(
-                        "  {} Updated CHANGELOG.md for {}",
-                        "OK".green(),
-                        pkg.name.bold()
-                    );
-                }
-            }
-
-            // Workspace-level changelog
-            let should_workspace = version_config
-                .map(|c| c.should_workspace_changelog())
-                .unwrap_or(true);
-            if should_workspace {
-                let all_commits: Vec<ConventionalCommit> =
-                    mapped.values().flatten().cloned().collect();
-                if !all_commits.is_empty() {
-                    let summary_version = versioned
-                        .first()
-                        .map(|(_, v)| v.as_str())
-                        .unwrap_or("0.0.0");
-                    let entry = generate_changelog_entry(
-                        summary_version,
-                        &all_commits,
-                        &make_changelog_opts(),
-                    );
-                    write_changelog(&workspace.root_path, &entry)?;
-                    println!("  {} Updated workspace CHANGELOG.md", "OK".green());
-                }
-            }
-
-            // Aggregate changelogs
-            if let Some(agg_configs) = version_config.and_then(|c| c.changelogs.as_ref()) {
-                for agg in agg_configs {
-                    let agg_path = workspace.root_path.join(&agg.path);
-
-                    // Filter commits to only those from packages matching the aggregate filters
-                    let agg_commits: Vec<ConventionalCommit> =
-                        if let Some(ref filters) = agg.package_filters {
-                            mapped
-                                .iter()
-                                .filter(|(pkg_name, _)| {
-                                    package_matches_filters(pkg_name, filters, &workspace.packages)
-                                })
-                                .flat_map(|(_, commits)| commits.iter().cloned())
-                                .collect()
-                        } else {
-                            // No filters -- include all commits
-                            mapped.values().flatten().cloned().collect()
-                        };
-
-                    if !agg_commits.is_empty() {
-                        let agg_version = versioned
-                            .first()
-                            .map(|(_, v)| v.as_str())
-                            .unwrap_or("0.0.0");
-                        let entry = generate_changelog_entry(
-                            agg_version,
-                            &agg_commits,
-                            &make_changelog_opts(),
-                        );
-
-                        // If the file has a description configured, ensure it's at the top
-                        let full_entry = if let Some(ref desc) = agg.description {
-                            if !agg_path.exists() {
-                                format!("# Changelog\n\n{}\n\n{}", desc, entry)
-                            } else {
-                                entry
-                            }
-                        } else {
-                            entry
-                        };
-
-                        write_changelog(&agg_path, &full_entry)?;
-                        println!(
-                            "  {} Updated aggregate changelog {}",
-                            "OK".green(),
-                            agg.path.bold()
-                        );
-                    }
-                }
-            }
+                        .map(|(_, v)| v.clone())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    (*pkg, new_ver)
+                })
+                .collect();
+            write_changelogs(
+                workspace,
+                version_config,
+                mapped,
+                &targets,
+                &all_packages,
+                &changelog_opts,
+                false,
+            )?;
         } else {
             println!(
                 "\n{} Changelog generation requires --conventional-commits; skipping.",
@@ -640,7 +990,7 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
         .unwrap_or(false);
     if should_update_refs && !versioned.is_empty() {
         println!("\n{} Updating git tag references...", "$".cyan());
-        let count = update_git_tag_refs(&workspace.root_path, &workspace.packages, &versioned)?;
+        let count = update_git_tag_refs(&workspace.root_path, &all_packages, &versioned)?;
         if count > 0 {
             println!(
                 "  {} Updated git tag refs in {} file(s)",
@@ -652,31 +1002,43 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
         }
     }
 
-    if let Some(pre_commit) = version_config
-        .and_then(|cfg| cfg.hooks.as_ref())
-        .and_then(|h| h.pre_commit.as_deref())
-    {
+    if let Some(hook) = version_hooks.and_then(|h| h.post_package.as_deref()) {
+        run_package_hook(
+            workspace,
+            hook,
+            "postPackage",
+            &all_packages,
+            &versioned,
+            render,
+        )
+        .await?;
+    }
+
+    if let Some(pre_commit) = version_hooks.and_then(|h| h.pre_commit.as_deref()) {
         crate::runner::run_lifecycle_hook(pre_commit, "pre-commit", &workspace.root_path, &[])
             .await?;
     }
 
-    let new_package_versions = versioned
-        .iter()
-        .map(|(name, ver)| format!(" - {} @ {}", name, ver))
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    let commit_message = if let Some(ref msg) = args.message {
-        // CLI --message overrides everything
-        msg.replace("{new_package_versions}", &new_package_versions)
-    } else {
-        let template = version_config
-            .map(|c| c.message_template().to_string())
-            .unwrap_or_else(|| {
-                "chore(release): publish packages\n\n{new_package_versions}".to_string()
-            });
-        template.replace("{new_package_versions}", &new_package_versions)
+    // CLI --message overrides everything
+    let template = match (&args.message, version_config) {
+        (Some(msg), _) => msg.as_str(),
+        (None, Some(c)) => c.message_template(),
+        (None, None) => "chore(release): publish packages\n\n{new_package_versions}",
     };
+    let (commit_message, unknown) =
+        render_commit_message(template, &versioned, &chrono_date_today());
+    for name in &unknown {
+        eprintln!(
+            "{} Unknown placeholder {{{}}} in the commit message was left as is (known: {})",
+            "WARNING:".yellow().bold(),
+            name,
+            COMMIT_MESSAGE_PLACEHOLDERS
+                .iter()
+                .map(|p| format!("{{{p}}}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
     println!(
         "\n{} Committing: {}",
         "$".cyan(),
@@ -689,19 +1051,31 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
     git_commit(&workspace.root_path, &commit_message)?;
     println!("  {} Committed version changes", "OK".green());
 
-    if let Some(post_commit) = version_config
-        .and_then(|cfg| cfg.hooks.as_ref())
-        .and_then(|h| h.post_commit.as_deref())
-    {
+    if let Some(post_commit) = version_hooks.and_then(|h| h.post_commit.as_deref()) {
         crate::runner::run_lifecycle_hook(post_commit, "post-commit", &workspace.root_path, &[])
             .await?;
     }
 
+    let mut tags = Vec::new();
+    let mut replaced_tags = Vec::new();
     if should_tag {
+        if args.overwrite_tags {
+            let existing = list_git_tags(&workspace.root_path)?;
+            replaced_tags = find_existing_tags(&existing, &versioned)
+                .into_iter()
+                .map(|(_, tag)| tag)
+                .collect();
+        }
         println!("\n{} Creating git tags...", "$".cyan());
         for (pkg_name, version) in &versioned {
-            let tag_name = create_git_tag(&workspace.root_path, pkg_name, version)?;
-            println!("  {} Created tag {}", "TAG".blue(), tag_name.bold());
+            let tag_name =
+                create_git_tag(&workspace.root_path, pkg_name, version, args.overwrite_tags)?;
+            if replaced_tags.contains(&tag_name) {
+                println!("  {} Replaced tag {}", "TAG".blue(), tag_name.bold());
+            } else {
+                println!("  {} Created tag {}", "TAG".blue(), tag_name.bold());
+            }
+            tags.push(tag_name);
         }
     }
 
@@ -712,7 +1086,7 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
     };
     if should_push {
         println!("\n{} Pushing to remote...", "$".cyan());
-        git_push(&workspace.root_path, should_tag)?;
+        git_push(&workspace.root_path, &tags, &replaced_tags)?;
         println!(
             "  {} Pushed commits{}",
             "OK".green(),
@@ -779,3 +1153,37 @@ pub async fn run(workspace: &Workspace, args: VersionArgs) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_args_prerelease_identifier() {
+        use clap::Parser;
+
+        #[derive(Parser, Debug)]
+        struct TestCli {
+            #[command(flatten)]
+            args: VersionArgs,
+        }
+
+        let cli = TestCli::parse_from(["test", "minor"]);
+        assert!(!cli.args.is_prerelease());
+
+        // A bare flag does not swallow the bump
+        let cli = TestCli::parse_from(["test", "--prerelease", "minor"]);
+        assert!(cli.args.is_prerelease());
+        assert_eq!(cli.args.bump, "minor");
+        assert_eq!(cli.args.preid(), "dev");
+
+        let cli = TestCli::parse_from(["test", "--prerelease=beta", "--preid", "rc", "major"]);
+        assert_eq!(cli.args.preid(), "beta");
+        assert_eq!(cli.args.bump, "major");
+
+        let cli = TestCli::parse_from(["test", "-p", "--preid", "rc"]);
+        assert_eq!(cli.args.preid(), "rc");
+
+        assert!(TestCli::try_parse_from(["test", "--prerelease", "--graduate"]).is_err());
+    }
+}
@@ -9,8 +9,16 @@ use melos_core::config::filter::PackageFilters;
 pub fn package_filters_from_args(args: &GlobalFilterArgs) -> PackageFilters {
     PackageFilters {
         flutter: args.flutter_filter(),
-        dir_exists: args.dir_exists.clone(),
-        file_exists: args.file_exists.clone(),
+        dir_exists: if args.dir_exists.is_empty() {
+            None
+        } else {
+            Some(args.dir_exists.clone())
+        },
+        file_exists: if args.file_exists.is_empty() {
+            None
+        } else {
+            Some(args.file_exists.clone())
+        },
         depends_on: if args.depends_on.is_empty() {
             None
         } else {
@@ -33,12 +41,14 @@ pub fn package_filters_from_args(args: &GlobalFilterArgs) -> PackageFilters {
         },
         no_private: args.no_private,
         diff: args.effective_diff().map(String::from),
+        since_latest_tag: args.only_changed_since_tag,
         category: if args.category.is_empty() {
             None
         } else {
             Some(args.category.clone())
         },
         include_dependencies: args.include_dependencies,
+        exclude_dependencies: args.exclude_dependencies,
         include_dependents: args.include_dependents,
         published: args.published_filter(),
     }
@@ -55,8 +65,9 @@ mod tests {
             ignore: vec!["test*".to_string()],
             diff: Some("main".to_string()),
             since: None,
-            dir_exists: Some("lib".to_string()),
-            file_exists: None,
+            only_changed_since_tag: false,
+            dir_exists: vec!["lib".to_string()],
+            file_exists: vec![],
             flutter: true,
             no_flutter: false,
             depends_on: vec!["core".to_string()],
@@ -64,22 +75,25 @@ mod tests {
             no_private: true,
             category: vec!["apps".to_string()],
             include_dependencies: true,
+            exclude_dependencies: true,
             include_dependents: false,
             published: false,
             no_published: false,
+            private_only: false,
         };
         let filters = package_filters_from_args(&args);
         assert_eq!(filters.flutter, Some(true));
         assert_eq!(filters.scope, Some(vec!["app*".to_string()]));
         assert_eq!(filters.ignore, Some(vec!["test*".to_string()]));
         assert_eq!(filters.diff, Some("main".to_string()));
-        assert_eq!(filters.dir_exists, Some("lib".to_string()));
+        assert_eq!(filters.dir_exists, Some(vec!["lib".to_string()]));
         assert!(filters.file_exists.is_none());
         assert_eq!(filters.depends_on, Some(vec!["core".to_string()]));
         assert!(filters.no_depends_on.is_none());
         assert!(filters.no_private);
         assert_eq!(filters.category, Some(vec!["apps".to_string()]));
         assert!(filters.include_dependencies);
+        assert!(filters.exclude_dependencies);
         assert!(!filters.include_dependents);
     }
 
@@ -123,4 +137,27 @@ mod tests {
         let filters = package_filters_from_args(&args);
         assert_eq!(filters.published, Some(false));
     }
+
+    #[test]
+    fn test_from_global_filter_args_private_only() {
+        let args = GlobalFilterArgs {
+            private_only: true,
+            ..Default::default()
+        };
+        let filters = package_filters_from_args(&args);
+        assert_eq!(filters.published, Some(false));
+        assert!(!filters.no_private);
+    }
+
+    #[test]
+    fn test_from_global_filter_args_only_changed_since_tag() {
+        let args = GlobalFilterArgs {
+            only_changed_since_tag: true,
+            diff: Some("HEAD~1".to_string()),
+            ..Default::default()
+        };
+        let filters = package_filters_from_args(&args);
+        assert!(filters.since_latest_tag);
+        assert_eq!(filters.diff, Some("HEAD~1".to_string()));
+    }
 }
@@ -1,13 +1,17 @@
 mod cli;
 mod commands;
 mod filter_ext;
+mod plugin;
 mod render;
 mod runner;
 
+use std::path::Path;
+
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use cli::{Cli, Commands, Verbosity};
 use colored::Colorize;
+use melos_core::lock::WorkspaceLock;
 use melos_core::workspace;
 
 /// Built-in command names that can be overridden by scripts with the same name.
@@ -30,14 +34,44 @@ const OVERRIDABLE_COMMANDS: &[&str] = &[
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let matches = <Cli as CommandFactory>::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches)
+        .unwrap_or_else(|e| e.format(&mut <Cli as CommandFactory>::command()).exit());
     let verbosity = cli.verbosity();
+    let render_settings = render::RenderSettings {
+        max_name_width: cli.max_name_width,
+        stream_mode: cli.stream_mode,
+        max_log_lines: cli.max_log_lines,
+        quiet_failures: cli.quiet_failures,
+        porcelain: cli.porcelain,
+    };
+    render::set_color(cli.color, cli.porcelain);
+    // `exec`/`test --json --stream`, `version --dry-run --json` and
+    // `analyze --format=checkstyle` reserve stdout for machine-readable output
+    let machine_output = cli.porcelain
+        || match cli.command {
+            Commands::Exec(ref args) => args.stream,
+            Commands::Test(ref args) => args.stream,
+            Commands::Version(ref args) => args.json,
+            Commands::Analyze(ref args) => {
+                args.format == commands::analyze::AnalyzeFormat::Checkstyle
+            }
+            _ => false,
+        };
+    match melos_core::redact::Redactor::new(&cli.redact, &cli.redact_preset) {
+        Ok(redactor) if !redactor.is_empty() => melos_core::redact::install(redactor),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("{} {:#}", "ERROR".red().bold(), e);
+            std::process::exit(1);
+        }
+    }
 
     // `init` and `completion` don't require an existing workspace — handle them early
     if let Commands::Init(args) = cli.command {
         return match commands::init::run(args) {
             Ok(()) => {
-                if verbosity != Verbosity::Quiet {
+                if verbosity != Verbosity::Quiet && !cli.porcelain {
                     println!("\n{}", "SUCCESS".green().bold());
                 }
                 Ok(())
@@ -59,12 +93,67 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    if let Commands::Tui(ref args) = cli.command {
-        return launch_tui(args.workspace.as_deref());
+    if let Commands::Tui = cli.command {
+        return launch_tui(&cli);
+    }
+
+    // Load the workspace from --config, or find it starting from --workspace
+    let loaded = match (cli.config.as_deref(), cli.workspace.as_deref()) {
+        (Some(path), _) => {
+            workspace::Workspace::load_from_config(Path::new(path), cli.sdk_path.as_deref())
+        }
+        (None, Some(dir)) => {
+            workspace::Workspace::find_and_load_from(Path::new(dir), cli.sdk_path.as_deref())
+        }
+        (None, None) => workspace::Workspace::find_and_load(cli.sdk_path.as_deref()),
+    };
+
+    // `selftest` reports a missing or broken workspace instead of failing
+    if let Commands::Selftest(args) = cli.command {
+        if let Err(e) = commands::selftest::run(&loaded, args, cli.sdk_path.as_deref()) {
+            eprintln!("{} {}", "FAILED".red().bold(), e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // `doctor` reports a missing or broken workspace as a failed check
+    if let Commands::Doctor(args) = cli.command {
+        if let Err(e) = commands::doctor::run(&loaded, args, cli.sdk_path.as_deref()) {
+            eprintln!("{} {}", "FAILED".red().bold(), e);
+            std::process::exit(1);
+        }
+        return Ok(());
     }
 
-    // Find and load workspace
-    let workspace = match workspace::Workspace::find_and_load(cli.sdk_path.as_deref()) {
+    // Unknown commands run the workspace script of that name, else the
+    // `melos-rs-<name>` plugin from PATH
+    if let Commands::External(ref args) = cli.command {
+        let (name, plugin_args) = match args.split_first() {
+            Some((name, rest)) => (name.as_str(), rest),
+            None => unreachable!("clap always passes the external command name"),
+        };
+        let is_script = loaded
+            .as_ref()
+            .is_ok_and(|ws| ws.config.scripts.contains_key(name));
+        if is_script {
+            cli.command = Commands::Run(script_run_args(name));
+        } else {
+            let path_var = std::env::var_os("PATH").unwrap_or_default();
+            let Some(plugin) = plugin::find_plugin(name, &path_var) else {
+                eprintln!(
+                    "{} {}",
+                    "ERROR".red().bold(),
+                    plugin::unknown_command_message(name, &path_var)
+                );
+                std::process::exit(1);
+            };
+            let code = plugin::run_plugin(&plugin, plugin_args, loaded.as_ref().ok())?;
+            std::process::exit(code);
+        }
+    }
+
+    let mut workspace = match loaded {
         Ok(ws) => ws,
         Err(e) => {
             eprintln!("{} Failed to load workspace: {}", "ERROR".red().bold(), e);
@@ -72,12 +161,14 @@ async fn main() -> Result<()> {
         }
     };
 
+    workspace.color = cli.color.into();
+
     // Print any warnings collected during workspace loading
     for warning in &workspace.warnings {
         eprintln!("{} {}", "WARNING:".yellow().bold(), warning);
     }
 
-    if verbosity != Verbosity::Quiet {
+    if verbosity != Verbosity::Quiet && !machine_output {
         let config_mode = if workspace.config_source.is_legacy() {
             "melos.yaml"
         } else {
@@ -100,8 +191,34 @@ async fn main() -> Result<()> {
             workspace.packages.len(),
             workspace.config_source.path().display()
         );
+        if let Some((spec, concurrency)) = cli::auto_concurrency(&matches) {
+            println!(
+                "{} --concurrency {} resolved to {}",
+                "DEBUG".dimmed(),
+                spec,
+                concurrency
+            );
+        }
+        if !workspace.overridden_fields.is_empty() {
+            println!(
+                "{} {} overrides: {}",
+                "DEBUG".dimmed(),
+                melos_core::config::overrides::OVERRIDES_FILE,
+                workspace.overridden_fields.join(", ")
+            );
+        }
     }
 
+    // Held until the command finishes, whether a script overrides it or not;
+    // the OS releases it if we exit early
+    let _lock = match acquire_workspace_lock(&cli, &workspace, verbosity) {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("{} {}", "ERROR".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
     // Check for script overrides: if a script has the same name as the built-in
     // command being invoked, run the script instead — unless the user passed
     // command-specific flags that only make sense with the built-in command.
@@ -116,45 +233,58 @@ async fn main() -> Result<()> {
                 script_name,
             );
         }
-        let run_args = commands::run::RunArgs {
-            script: Some(script_name.to_string()),
-            no_select: false,
-            list: false,
-            json: false,
-            include_private: false,
-            group: vec![],
-            watch: false,
-            filters: cli::GlobalFilterArgs::default(),
-        };
-        commands::run::run(&workspace, run_args).await
+        commands::run::run(&workspace, script_run_args(script_name), render_settings).await
     } else {
         match cli.command {
-            Commands::Analyze(args) => commands::analyze::run(&workspace, args).await,
-            Commands::Bootstrap(args) => commands::bootstrap::run(&workspace, args).await,
-            Commands::Build(args) => commands::build::run(&workspace, args).await,
-            Commands::Clean(args) => commands::clean::run(&workspace, args).await,
+            Commands::Analyze(args) => {
+                commands::analyze::run(&workspace, args, render_settings).await
+            }
+            Commands::Bootstrap(args) => {
+                commands::bootstrap::run(&workspace, args, render_settings).await
+            }
+            Commands::Build(args) => commands::build::run(&workspace, args, render_settings).await,
+            Commands::Cache(args) => commands::cache::run(&workspace, args).await,
+            Commands::CheckConstraints(args) => {
+                commands::check_constraints::run(&workspace, args).await
+            }
+            Commands::Clean(args) => commands::clean::run(&workspace, args, render_settings).await,
             Commands::Completion(_) => unreachable!("completion handled above"),
-            Commands::Exec(args) => commands::exec::run(&workspace, args).await,
-            Commands::Format(args) => commands::format::run(&workspace, args).await,
+            Commands::Exec(args) => commands::exec::run(&workspace, args, render_settings).await,
+            Commands::Format(args) => {
+                commands::format::run(&workspace, args, render_settings).await
+            }
+            Commands::Graph(args) => commands::graph::run(&workspace, args).await,
             Commands::Health(args) => commands::health::run(&workspace, args).await,
             Commands::Init(_) => unreachable!("init handled above"),
-            Commands::Tui(_) => unreachable!("tui handled above"),
+            Commands::Tui => unreachable!("tui handled above"),
+            Commands::Selftest(_) => unreachable!("selftest handled above"),
+            Commands::Doctor(_) => unreachable!("doctor handled above"),
+            Commands::External(_) => unreachable!("external commands handled above"),
             Commands::List(args) => commands::list::run(&workspace, args).await,
-            Commands::Pub(args) => commands::pub_cmds::run(&workspace, args).await,
-            Commands::Publish(args) => commands::publish::run(&workspace, args).await,
-            Commands::Run(args) => commands::run::run(&workspace, args).await,
-            Commands::Test(args) => commands::test::run(&workspace, args).await,
-            Commands::Version(args) => commands::version::run(&workspace, args).await,
+            Commands::Pub(args) => commands::pub_cmds::run(&workspace, args, render_settings).await,
+            Commands::Publish(args) => {
+                commands::publish::run(&workspace, args, render_settings).await
+            }
+            Commands::Run(args) => commands::run::run(&workspace, args, render_settings).await,
+            Commands::Test(args) => commands::test::run(&workspace, args, render_settings).await,
+            Commands::Version(args) => {
+                commands::version::run(&workspace, args, render_settings).await
+            }
         }
     };
 
     match result {
         Ok(()) => {
-            if verbosity != Verbosity::Quiet {
+            if verbosity != Verbosity::Quiet && !machine_output {
                 println!("\n{}", "SUCCESS".green().bold());
             }
             Ok(())
         }
+        // Porcelain drops the footer but still reports why the run failed
+        Err(e) if machine_output => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
         Err(e) => {
             eprintln!("\n{} {}", "FAILED".red().bold(), e);
             std::process::exit(1);
@@ -162,6 +292,37 @@ async fn main() -> Result<()> {
     }
 }
 
+/// Take the workspace lock for commands that mutate workspace files, and
+/// export it so nested `melos-rs` calls from hooks and scripts run under it.
+///
+/// Read-only commands, `--no-lock` and processes spawned by the lock holder
+/// skip it.
+fn acquire_workspace_lock(
+    cli: &Cli,
+    workspace: &workspace::Workspace,
+    verbosity: Verbosity,
+) -> Result<Option<WorkspaceLock>> {
+    let mutating = matches!(
+        cli.command,
+        Commands::Bootstrap(_) | Commands::Clean(_) | Commands::Version(_) | Commands::Publish(_)
+    );
+    if !mutating || cli.no_lock {
+        return Ok(None);
+    }
+    let Some(lock) = WorkspaceLock::acquire_unless_inherited(&workspace.root_path)? else {
+        return Ok(None);
+    };
+    lock.export_to_children();
+    if verbosity == Verbosity::Verbose {
+        println!(
+            "{} Holding workspace lock {}",
+            "DEBUG".dimmed(),
+            lock.path().display()
+        );
+    }
+    Ok(Some(lock))
+}
+
 /// If the CLI command is a built-in that can be overridden by a script,
 /// return the command name as a string.
 fn get_overridable_command_name(command: &Commands) -> Option<&'static str> {
@@ -178,8 +339,18 @@ fn get_overridable_command_name(command: &Commands) -> Option<&'static str> {
         Commands::Publish(_) => "publish",
         Commands::Version(_) => "version",
         Commands::Test(_) => "test",
-        // `run`, `init`, `completion`, `tui` are never overridden
-        Commands::Run(_) | Commands::Init(_) | Commands::Completion(_) | Commands::Tui(_) => {
+        // `run`, `graph`, `cache`, `check-constraints`, `init`, `completion`, `tui`,
+        // `selftest`, `doctor` and external commands are never overridden
+        Commands::Run(_)
+        | Commands::Graph(_)
+        | Commands::Cache(_)
+        | Commands::CheckConstraints(_)
+        | Commands::Init(_)
+        | Commands::Completion(_)
+        | Commands::Tui
+        | Commands::Selftest(_)
+        | Commands::Doctor(_)
+        | Commands::External(_) => {
             return None;
         }
     };
@@ -192,6 +363,24 @@ fn get_overridable_command_name(command: &Commands) -> Option<&'static str> {
     }
 }
 
+/// Arguments for running `script_name` as if invoked as `melos-rs run <script>`.
+fn script_run_args(script_name: &str) -> commands::run::RunArgs {
+    commands::run::RunArgs {
+        script: Some(script_name.to_string()),
+        no_select: false,
+        list: false,
+        json: false,
+        include_private: false,
+        group: vec![],
+        concurrency: None,
+        no_fail_fast: false,
+        watch: false,
+        changed_only: false,
+        watch_poll: None,
+        filters: cli::GlobalFilterArgs::default(),
+    }
+}
+
 /// Check if the command has flags specific to the built-in implementation.
 ///
 /// When the user passes flags like `--fix`, `--dry-run`, `--fatal-warnings`, etc.,
@@ -206,6 +395,8 @@ fn command_has_builtin_flags(command: &Commands) -> bool {
                 || args.fatal_warnings
                 || args.fatal_infos
                 || args.no_fatal
+                || args.no_cache
+                || args.format != commands::analyze::AnalyzeFormat::Default
         }
         Commands::Bootstrap(args) => {
             args.enforce_lockfile
@@ -213,10 +404,17 @@ fn command_has_builtin_flags(command: &Commands) -> bool {
                 || args.no_example
                 || args.offline
                 || args.dry_run
+                || args.validate_only
+                || args.skip_if_unchanged
+                || args.force
+                || args.retry.is_set()
         }
         Commands::Clean(args) => args.deep || args.dry_run,
         Commands::Format(args) => {
-            args.set_exit_if_changed || args.line_length.is_some() || args.output != "write"
+            args.set_exit_if_changed
+                || args.check
+                || args.line_length.is_some()
+                || args.output != "write"
         }
         Commands::Test(args) => {
             args.fail_fast
@@ -224,6 +422,8 @@ fn command_has_builtin_flags(command: &Commands) -> bool {
                 || args.update_goldens
                 || args.no_run
                 || args.test_randomize_ordering_seed.is_some()
+                || args.output_dir.is_some()
+                || args.stream
                 || !args.extra_args.is_empty()
         }
         // Note: dry_run defaults to true for publish, so it doesn't count
@@ -234,15 +434,25 @@ fn command_has_builtin_flags(command: &Commands) -> bool {
 
 /// Launch the `melos-tui` binary by exec-ing into it.
 ///
-/// Looks for the binary on PATH. If not found, prints a helpful install message.
-fn launch_tui(workspace: Option<&str>) -> Result<()> {
+/// Forwards `--workspace`, `--config` and the redaction flags. Looks for the
+/// binary on PATH. If not found, prints a helpful install message.
+fn launch_tui(cli: &Cli) -> Result<()> {
     use std::process::Command;
 
     let binary = "melos-tui";
     let mut cmd = Command::new(binary);
-    if let Some(ws) = workspace {
+    if let Some(ref ws) = cli.workspace {
         cmd.args(["--workspace", ws]);
     }
+    if let Some(ref path) = cli.config {
+        cmd.args(["--config", path]);
+    }
+    for pattern in &cli.redact {
+        cmd.args(["--redact", pattern]);
+    }
+    if !cli.redact_preset.is_empty() {
+        cmd.args(["--redact-preset", &cli.redact_preset.join(",")]);
+    }
     let status = cmd.status();
 
     match status {
@@ -0,0 +1,152 @@
+//! External subcommands: `melos-rs foo` runs `melos-rs-foo` from PATH when
+//! `foo` is neither a built-in command nor a workspace script, like git and
+//! cargo do.
+
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+use melos_core::workspace::Workspace;
+
+/// File name prefix of plugin executables.
+const PLUGIN_PREFIX: &str = "melos-rs-";
+
+/// Find the `melos-rs-<name>` executable in the directories of `path_var`
+/// (a `PATH`-style list); the first match wins.
+pub fn find_plugin(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    let file_name = format!("{PLUGIN_PREFIX}{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/// Names of all `melos-rs-<name>` executables in `path_var`, sorted and
+/// without duplicates.
+pub fn discover_plugins(path_var: &OsStr) -> Vec<String> {
+    let mut names = BTreeSet::new();
+    for dir in std::env::split_paths(path_var) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name
+                .to_str()
+                .and_then(|f| f.strip_prefix(PLUGIN_PREFIX))
+                .and_then(|f| f.strip_suffix(std::env::consts::EXE_SUFFIX))
+            else {
+                continue;
+            };
+            if !name.is_empty() && is_executable(&entry.path()) {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// Names of the built-in subcommands shown in `--help`.
+pub fn builtin_commands() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .filter(|cmd| !cmd.is_hide_set())
+        .map(|cmd| cmd.get_name().to_string())
+        .collect()
+}
+
+/// The error for a command that is not a built-in, script or plugin.
+pub fn unknown_command_message(name: &str, path_var: &OsStr) -> String {
+    let plugins = discover_plugins(path_var);
+    let plugins = if plugins.is_empty() {
+        format!("none (no {PLUGIN_PREFIX}<name> executables on PATH)")
+    } else {
+        plugins.join(", ")
+    };
+    format!(
+        "Unknown command '{}'.\n\nBuilt-in commands: {}\nPlugins: {}\n\nRun scripts with `melos-rs run <script>`.",
+        name,
+        builtin_commands().join(", "),
+        plugins
+    )
+}
+
+/// Run a plugin with `args`, inheriting stdio, and return its exit code.
+///
+/// With a loaded workspace, the plugin gets the workspace environment
+/// (`MELOS_ROOT_PATH` and friends) plus `MELOS_WORKSPACE_CONFIG`, the path
+/// of the config file the workspace was loaded from.
+pub fn run_plugin(plugin: &Path, args: &[String], workspace: Option<&Workspace>) -> Result<i32> {
+    let mut cmd = std::process::Command::new(plugin);
+    cmd.args(args);
+    if let Some(ws) = workspace {
+        cmd.envs(ws.env_vars()).env(
+            "MELOS_WORKSPACE_CONFIG",
+            ws.config_source.path().as_os_str(),
+        );
+    }
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run plugin {}", plugin.display()))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_and_discover_plugins() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let write = |dir: &Path, name: &str, mode: u32| {
+            let path = dir.join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+            path
+        };
+        let hello = write(first.path(), "melos-rs-hello", 0o755);
+        write(second.path(), "melos-rs-hello", 0o755);
+        write(second.path(), "melos-rs-audit", 0o755);
+        write(second.path(), "melos-rs-notexec", 0o644);
+        write(second.path(), "melos-tui", 0o755);
+
+        let path_var = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_plugin("hello", &path_var), Some(hello));
+        assert_eq!(find_plugin("notexec", &path_var), None);
+        assert_eq!(find_plugin("missing", &path_var), None);
+        assert_eq!(discover_plugins(&path_var), vec!["audit", "hello"]);
+
+        let message = unknown_command_message("nope", &path_var);
+        assert!(message.starts_with("Unknown command 'nope'."));
+        assert!(message.contains("Plugins: audit, hello"));
+        assert!(message.contains("bootstrap"));
+    }
+
+    #[test]
+    fn test_unknown_command_message_without_plugins() {
+        let message = unknown_command_message("nope", OsStr::new(""));
+        assert!(message.contains("Plugins: none"));
+        let builtins = builtin_commands();
+        assert!(builtins.contains(&"exec".to_string()));
+        assert!(!builtins.iter().any(|c| c.is_empty()));
+    }
+}
@@ -1,12 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::{Color, Colorize};
 use indicatif::{ProgressBar, ProgressStyle};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
+use melos_core::commands::{Fnv1a, PackageResults};
 use melos_core::events::Event;
+use melos_core::package::Package;
+use melos_core::workspace::ColorMode;
 
 /// Colors assigned to packages for distinguishing concurrent output.
 const PKG_COLORS: &[Color] = &[
@@ -22,6 +27,182 @@ const PKG_COLORS: &[Color] = &[
     Color::BrightBlue,
 ];
 
+/// Default cap on the width of `[package]` output prefixes.
+pub const DEFAULT_MAX_NAME_WIDTH: usize = 32;
+
+/// How package output lines reach the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StreamMode {
+    /// Print each line as it arrives; lines from concurrent packages interleave
+    #[default]
+    Interleaved,
+    /// Hold each package's lines and print them together once it finishes
+    Buffered,
+}
+
+/// Default cap on lines held per package in buffered mode.
+pub const DEFAULT_MAX_LOG_LINES: usize = 10_000;
+
+/// How the renderers print package output, from the global output flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderSettings {
+    /// Cap on the width of `[package]` prefixes (`--max-name-width`)
+    pub max_name_width: usize,
+    /// How package output lines are printed (`--stream-mode`)
+    pub stream_mode: StreamMode,
+    /// Lines held per package in buffered mode; 0 keeps all (`--max-log-lines`)
+    pub max_log_lines: usize,
+    /// Only print the output of packages that fail: output is buffered as in
+    /// buffered mode, and dropped along with the SUCCESS line when a package
+    /// succeeds (`--quiet-failures`)
+    pub quiet_failures: bool,
+    /// Tab-separated per-package records on stdout, with no separators,
+    /// progress bars or colors (`--porcelain`)
+    pub porcelain: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            max_name_width: DEFAULT_MAX_NAME_WIDTH,
+            stream_mode: StreamMode::default(),
+            max_log_lines: DEFAULT_MAX_LOG_LINES,
+            quiet_failures: false,
+            porcelain: false,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Whether package output is held until the package finishes.
+    fn buffered(&self) -> bool {
+        self.quiet_failures || self.stream_mode == StreamMode::Buffered
+    }
+}
+
+/// A package's held output in buffered mode.
+///
+/// Once `cap` lines are held, the oldest is dropped for each new one: the
+/// tail, where errors usually are, survives and memory stays bounded.
+#[derive(Debug, Default)]
+struct OutputBuffer {
+    lines: VecDeque<(String, bool)>,
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, line: String, is_stderr: bool, cap: usize) {
+        if cap > 0 && self.lines.len() >= cap {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back((line, is_stderr));
+    }
+
+    /// Print the truncation marker, if any, then the held lines.
+    fn print(self, name: &str, color: Color, name_width: usize, porcelain: bool) {
+        if self.dropped > 0 {
+            let prefix = format_prefix(name, name_width).color(color).bold();
+            let marker = format!(
+                "... {} earlier line(s) dropped (--max-log-lines)",
+                self.dropped
+            );
+            if porcelain {
+                eprintln!("{} {}", prefix, marker.dimmed());
+            } else {
+                println!("{} {}", prefix, marker.dimmed());
+            }
+        }
+        for (line, is_stderr) in self.lines {
+            print_output_line(name, &line, is_stderr, color, name_width, porcelain);
+        }
+    }
+}
+
+/// When to use ANSI colors, for melos-rs itself and for child processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal; child processes decide for themselves
+    #[default]
+    Auto,
+    /// Always color, and tell child processes to color captured output
+    Always,
+    /// Never color, and tell child processes not to either
+    Never,
+}
+
+impl From<ColorChoice> for ColorMode {
+    fn from(choice: ColorChoice) -> Self {
+        match choice {
+            ColorChoice::Auto => ColorMode::Auto,
+            ColorChoice::Always => ColorMode::Always,
+            ColorChoice::Never => ColorMode::Never,
+        }
+    }
+}
+
+/// Apply `--color` to melos-rs's own output. Porcelain output is never
+/// colored.
+pub fn set_color(choice: ColorChoice, porcelain: bool) {
+    match choice {
+        _ if porcelain => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+    }
+}
+
+/// `println!` for human-readable status lines (headers, package lists,
+/// summaries), taking the command's [`RenderSettings`] first. In porcelain
+/// mode they go to stderr, so stdout carries only the per-package records.
+macro_rules! status_println {
+    ($render:expr) => {
+        if $render.porcelain {
+            eprintln!()
+        } else {
+            println!()
+        }
+    };
+    ($render:expr, $($arg:tt)*) => {
+        if $render.porcelain {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+pub(crate) use status_println;
+
+/// Format one porcelain record: `name<TAB>status<TAB>duration_ms`.
+fn porcelain_record(name: &str, status: &str, duration: std::time::Duration) -> String {
+    format!("{}\t{}\t{}", name, status, duration.as_millis())
+}
+
+/// Width that package names are padded to: the longest selected name,
+/// capped at `max_width`.
+fn name_width(packages: &[Package], max_width: usize) -> usize {
+    packages
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(max_width)
+}
+
+/// Format a `[name]` prefix right-padded to `width`, truncating longer names
+/// with `…`.
+fn format_prefix(name: &str, width: usize) -> String {
+    let len = name.chars().count();
+    if width == 0 {
+        format!("[{}]", name)
+    } else if len > width {
+        let truncated: String = name.chars().take(width.saturating_sub(1)).collect();
+        format!("[{}…]", truncated)
+    } else {
+        format!("[{}]{}", name, " ".repeat(width - len))
+    }
+}
+
 /// Create a styled progress bar for package processing.
 ///
 /// Uses a consistent style across all commands:
@@ -42,32 +223,147 @@ pub fn create_progress_bar(total: u64, message: &str) -> ProgressBar {
 ///
 /// Returns an event sender and a join handle. Drop the sender when done
 /// to signal the render loop to finish, then await the handle.
+///
+/// `[package]` prefixes are aligned to the longest name in `packages`,
+/// capped at `settings.max_name_width` (`--max-name-width`).
 pub fn spawn_renderer(
-    total: usize,
+    packages: &[Package],
+    message: &str,
+    settings: RenderSettings,
+) -> (mpsc::UnboundedSender<Event>, JoinHandle<Result<()>>) {
+    spawn_renderer_with_phases(packages, message, settings, None)
+}
+
+/// Extracts a short phase description from a line of command output.
+pub type PhaseParser = fn(&str) -> Option<String>;
+
+/// Spawn a renderer task with a progress bar that also shows the phase of
+/// the most recently active package.
+///
+/// Each output line is passed to `phase_of`; when it names a phase, the bar
+/// message becomes `<message> <package>: <phase>` until that package
+/// finishes or the next `Progress` event replaces the message.
+pub fn spawn_renderer_with_phases(
+    packages: &[Package],
     message: &str,
+    settings: RenderSettings,
+    phase_of: Option<PhaseParser>,
 ) -> (mpsc::UnboundedSender<Event>, JoinHandle<Result<()>>) {
-    let pb = create_progress_bar(total as u64, message);
+    let pb = (!settings.porcelain).then(|| create_progress_bar(packages.len() as u64, message));
+    let width = name_width(packages, settings.max_name_width);
     let (tx, rx) = mpsc::unbounded_channel();
-    let handle = tokio::spawn(async move { render_loop(rx, Some(pb)).await });
+    let handle = tokio::spawn(async move { render_loop(rx, pb, width, settings, phase_of).await });
     (tx, handle)
 }
 
 /// Spawn a renderer task without a progress bar.
 ///
 /// Useful for commands that want colored output but no progress indicator.
-pub fn spawn_plain_renderer() -> (mpsc::UnboundedSender<Event>, JoinHandle<Result<()>>) {
+pub fn spawn_plain_renderer(
+    packages: &[Package],
+    settings: RenderSettings,
+) -> (mpsc::UnboundedSender<Event>, JoinHandle<Result<()>>) {
+    let width = name_width(packages, settings.max_name_width);
     let (tx, rx) = mpsc::unbounded_channel();
-    let handle = tokio::spawn(async move { render_loop(rx, None).await });
+    let handle = tokio::spawn(async move { render_loop(rx, None, width, settings, None).await });
+    (tx, handle)
+}
+
+/// Spawn a renderer that prints each event as one JSON object per line on
+/// stdout (`--json --stream`).
+///
+/// No progress bar, colors or summaries are printed.
+pub fn spawn_json_renderer() -> (mpsc::UnboundedSender<Event>, JoinHandle<Result<()>>) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+    let handle = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            println!("{}", json_event_line(event));
+        }
+        Ok(())
+    });
     (tx, handle)
 }
 
-/// Get the color for a package name, assigning a new one if not seen before.
-fn pkg_color(color_map: &mut HashMap<String, Color>, color_idx: &mut usize, name: &str) -> Color {
-    *color_map.entry(name.to_string()).or_insert_with(|| {
-        let c = PKG_COLORS[*color_idx % PKG_COLORS.len()];
-        *color_idx += 1;
-        c
-    })
+/// Serialize an event for the JSON stream.
+fn json_event_line(event: Event) -> String {
+    event.to_json().to_string()
+}
+
+/// Sender/handle pair returned by [`attach_log_dir`]; the handle is `None`
+/// when no log directory was requested.
+pub type LoggedSender = (mpsc::UnboundedSender<Event>, Option<JoinHandle<Result<()>>>);
+
+/// Also write each package's output to `<dir>/<package>.log` when `dir` is
+/// set (`--output-dir`).
+///
+/// Returns the sender to pass to the command in place of `tx`: every event
+/// is forwarded to `tx` unchanged. The directory is created if missing and
+/// existing logs are overwritten. Drop the returned sender and await the log
+/// handle before awaiting the renderer.
+pub fn attach_log_dir(dir: Option<&str>, tx: mpsc::UnboundedSender<Event>) -> Result<LoggedSender> {
+    let Some(dir) = dir else {
+        return Ok((tx, None));
+    };
+    let dir = PathBuf::from(dir);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+    let (log_tx, rx) = mpsc::unbounded_channel();
+    let handle = tokio::spawn(async move { log_loop(rx, tx, &dir).await });
+    Ok((log_tx, Some(handle)))
+}
+
+/// Forward events to `tx`, collecting output lines per package and writing
+/// them to `<dir>/<package>.log` once the package finishes.
+///
+/// Stdout and stderr are combined in arrival order. Packages still running
+/// when the channel closes are written with whatever output they produced.
+async fn log_loop(
+    mut rx: mpsc::UnboundedReceiver<Event>,
+    tx: mpsc::UnboundedSender<Event>,
+    dir: &Path,
+) -> Result<()> {
+    let mut logs: HashMap<String, String> = HashMap::new();
+    let write_log = |name: &str, content: &str| {
+        let path = dir.join(format!("{}.log", name));
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write log {}", path.display()))
+    };
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            Event::PackageStarted { ref name } => {
+                logs.insert(name.clone(), String::new());
+            }
+            Event::PackageOutput {
+                ref name, ref line, ..
+            } => {
+                let log = logs.entry(name.clone()).or_default();
+                log.push_str(line);
+                log.push('\n');
+            }
+            Event::PackageFinished { ref name, .. } => {
+                write_log(name, &logs.remove(name).unwrap_or_default())?;
+            }
+            _ => {}
+        }
+        let _ = tx.send(event);
+    }
+
+    for (name, content) in logs {
+        write_log(&name, &content)?;
+    }
+    Ok(())
+}
+
+/// Get the color for a package name.
+///
+/// Picked from a hash of the name, so a package keeps its color whichever
+/// packages are selected and in whatever order they start.
+fn pkg_color(name: &str) -> Color {
+    let mut hasher = Fnv1a::default();
+    hasher.write(name.as_bytes());
+    let hash = hasher.finish();
+    PKG_COLORS[(hash % PKG_COLORS.len() as u64) as usize]
 }
 
 /// Width (in characters) of the separator line drawn around package output.
@@ -91,31 +387,100 @@ fn closing_separator(color: Color) -> String {
     format!("{}", "─".repeat(SEPARATOR_WIDTH).color(color))
 }
 
+/// Build the end-of-run skip summary: `2 skipped: x (unchanged), y (no test/)`.
+///
+/// Returns `None` when nothing was skipped.
+fn format_skipped_summary(skipped: &[(String, String)]) -> Option<String> {
+    if skipped.is_empty() {
+        return None;
+    }
+    let items: Vec<String> = skipped
+        .iter()
+        .map(|(name, reason)| format!("{} ({})", name, reason))
+        .collect();
+    Some(format!("{} skipped: {}", skipped.len(), items.join(", ")))
+}
+
+/// `", N aborted, M skipped"` (non-zero counts only) for appending to a
+/// command's pass/fail counts; empty when every package passed or failed.
+pub fn other_counts_suffix(results: &PackageResults) -> String {
+    let mut suffix = String::new();
+    if results.aborted() > 0 {
+        suffix.push_str(&format!(", {} aborted", results.aborted()));
+    }
+    if results.skipped() > 0 {
+        suffix.push_str(&format!(", {} skipped", results.skipped()));
+    }
+    suffix
+}
+
+/// Print one `[package] line` of command output.
+///
+/// In porcelain mode every line goes to stderr, leaving stdout to the records.
+fn print_output_line(
+    name: &str,
+    line: &str,
+    is_stderr: bool,
+    color: Color,
+    name_width: usize,
+    porcelain: bool,
+) {
+    let prefix = format_prefix(name, name_width).color(color).bold();
+    if is_stderr || porcelain {
+        eprintln!("{} {}", prefix, line);
+    } else {
+        println!("{} {}", prefix, line);
+    }
+}
+
 /// Internal render loop that processes events and produces terminal output.
 async fn render_loop(
     mut rx: mpsc::UnboundedReceiver<Event>,
     pb: Option<ProgressBar>,
+    name_width: usize,
+    settings: RenderSettings,
+    phase_of: Option<PhaseParser>,
 ) -> Result<()> {
-    let mut color_map: HashMap<String, Color> = HashMap::new();
-    let mut color_idx = 0usize;
+    // Bar message without a phase, and the package whose phase it shows
+    let mut base_message = pb.as_ref().map(|pb| pb.message()).unwrap_or_default();
+    let mut phase_package: Option<String> = None;
+    let mut skipped: Vec<(String, String)> = Vec::new();
+    let RenderSettings {
+        quiet_failures,
+        max_log_lines,
+        porcelain,
+        ..
+    } = settings;
+    let buffered = settings.buffered();
+    let mut buffers: HashMap<String, OutputBuffer> = HashMap::new();
 
     while let Some(event) = rx.recv().await {
         match event {
             Event::PackageStarted { ref name } => {
-                let color = pkg_color(&mut color_map, &mut color_idx, name);
-                println!("{}", separator_line(name, color));
+                let color = pkg_color(name);
+                if !buffered && !porcelain {
+                    println!("{}", separator_line(name, color));
+                }
             }
             Event::PackageOutput {
-                ref name,
-                ref line,
+                name,
+                line,
                 is_stderr,
             } => {
-                let color = pkg_color(&mut color_map, &mut color_idx, name);
-                let prefix = format!("[{}]", name).color(color).bold();
-                if is_stderr {
-                    eprintln!("{} {}", prefix, line);
+                if let (Some(pb), Some(phase_of)) = (&pb, phase_of)
+                    && let Some(phase) = phase_of(&line)
+                {
+                    pb.set_message(format!("{} {}: {}", base_message, name, phase));
+                    phase_package = Some(name.clone());
+                }
+                if buffered {
+                    buffers
+                        .entry(name)
+                        .or_default()
+                        .push(line, is_stderr, max_log_lines);
                 } else {
-                    println!("{} {}", prefix, line);
+                    let color = pkg_color(&name);
+                    print_output_line(&name, &line, is_stderr, color, name_width, porcelain);
                 }
             }
             Event::PackageFinished {
@@ -123,27 +488,123 @@ async fn render_loop(
                 success,
                 duration,
             } => {
-                let color = pkg_color(&mut color_map, &mut color_idx, name);
-                let prefix = format!("[{}]", name).color(color).bold();
-                let elapsed = format!("({:.1}s)", duration.as_secs_f64());
-                if success {
-                    println!("{} {} {}", prefix, "SUCCESS".green(), elapsed.dimmed());
+                let color = pkg_color(name);
+                if porcelain {
+                    if let Some(buffer) = buffers.remove(name) {
+                        buffer.print(name, color, name_width, porcelain);
+                    }
+                    let status = if success { "success" } else { "failed" };
+                    println!("{}", porcelain_record(name, status, duration));
+                    continue;
+                }
+                if quiet_failures && success {
+                    buffers.remove(name);
                 } else {
-                    eprintln!("{} {} {}", prefix, "FAILED".red(), elapsed.dimmed());
+                    if buffered {
+                        println!("{}", separator_line(name, color));
+                        if let Some(buffer) = buffers.remove(name) {
+                            buffer.print(name, color, name_width, porcelain);
+                        }
+                    }
+                    let prefix = format_prefix(name, name_width).color(color).bold();
+                    let elapsed = format!("({:.1}s)", duration.as_secs_f64());
+                    if success {
+                        println!("{} {} {}", prefix, "SUCCESS".green(), elapsed.dimmed());
+                    } else {
+                        eprintln!("{} {} {}", prefix, "FAILED".red(), elapsed.dimmed());
+                    }
+                    println!("{}", closing_separator(color));
+                }
+                if let Some(ref pb) = pb {
+                    pb.inc(1);
+                    if phase_package.as_ref() == Some(name) {
+                        pb.set_message(base_message.clone());
+                        phase_package = None;
+                    }
+                }
+            }
+            Event::PackageAborted {
+                ref name,
+                ref reason,
+                duration,
+            } => {
+                let color = pkg_color(name);
+                if porcelain {
+                    if let Some(buffer) = buffers.remove(name) {
+                        buffer.print(name, color, name_width, porcelain);
+                    }
+                    println!("{}", porcelain_record(name, "aborted", duration));
+                    continue;
+                }
+                if buffered {
+                    println!("{}", separator_line(name, color));
+                    if let Some(buffer) = buffers.remove(name) {
+                        buffer.print(name, color, name_width, porcelain);
+                    }
                 }
+                let prefix = format_prefix(name, name_width).color(color).bold();
+                let elapsed = format!("({:.1}s)", duration.as_secs_f64());
+                eprintln!(
+                    "{} {} {}",
+                    prefix,
+                    format!("ABORTED ({})", reason).yellow(),
+                    elapsed.dimmed()
+                );
                 println!("{}", closing_separator(color));
                 if let Some(ref pb) = pb {
                     pb.inc(1);
+                    if phase_package.as_ref() == Some(name) {
+                        pb.set_message(base_message.clone());
+                        phase_package = None;
+                    }
                 }
             }
+            // Retry notices would break the silence of packages that pass
+            Event::PackageRetry { .. } if quiet_failures => {}
+            Event::PackageRetry {
+                ref name,
+                attempt,
+                max_attempts,
+                delay,
+            } => {
+                let color = pkg_color(name);
+                let prefix = format_prefix(name, name_width).color(color).bold();
+                eprintln!(
+                    "{} {} {}",
+                    prefix,
+                    format!("retry {}/{}", attempt, max_attempts).yellow(),
+                    format!("(in {}ms)", delay.as_millis()).dimmed()
+                );
+            }
+            Event::PackageSkipped { name, reason } if porcelain => {
+                println!(
+                    "{}",
+                    porcelain_record(&name, "skipped", std::time::Duration::ZERO)
+                );
+                eprintln!("[{}] skipped ({})", name, reason);
+            }
+            Event::PackageSkipped { name, reason } => {
+                // A package skipped after starting (e.g. command not found)
+                // leaves its buffered output behind
+                buffers.remove(&name);
+                let color = pkg_color(&name);
+                let prefix = format_prefix(&name, name_width).color(color).bold();
+                println!("{} {}", prefix, format!("SKIPPED ({})", reason).dimmed());
+                skipped.push((name, reason));
+            }
             Event::Progress { ref message, .. } => {
                 if let Some(ref pb) = pb {
                     pb.set_message(message.clone());
                 }
+                base_message = message.clone();
+                phase_package = None;
             }
             Event::Warning(ref msg) => {
                 eprintln!("{} {}", "WARNING:".yellow().bold(), msg);
             }
+            Event::Info(ref msg) if porcelain => {
+                eprintln!("{}", msg);
+            }
             Event::Info(ref msg) => {
                 println!("{}", msg);
             }
@@ -157,5 +618,251 @@ async fn render_loop(
         pb.finish_and_clear();
     }
 
+    if let Some(summary) = format_skipped_summary(&skipped)
+        && !porcelain
+    {
+        println!("{}", summary.dimmed());
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn make_pkg(name: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/workspace/packages/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_output_buffer_keeps_tail_within_cap() {
+        let mut buffer = OutputBuffer::default();
+        for i in 1..=5 {
+            buffer.push(format!("line {i}"), i == 5, 3);
+        }
+        assert_eq!(buffer.dropped, 2);
+        let lines: Vec<(&str, bool)> = buffer.lines.iter().map(|(l, e)| (l.as_str(), *e)).collect();
+        assert_eq!(
+            lines,
+            vec![("line 3", false), ("line 4", false), ("line 5", true)]
+        );
+
+        let mut unlimited = OutputBuffer::default();
+        for i in 0..100 {
+            unlimited.push(i.to_string(), false, 0);
+        }
+        assert_eq!((unlimited.lines.len(), unlimited.dropped), (100, 0));
+    }
+
+    #[test]
+    fn test_pkg_color_stable_for_name() {
+        let first: Vec<Color> = ["core", "app", "ui"].iter().map(|n| pkg_color(n)).collect();
+        let second: Vec<Color> = ["ui", "core", "app"].iter().map(|n| pkg_color(n)).collect();
+        assert_eq!(first, vec![second[1], second[2], second[0]]);
+        // Pinned: the same names keep their colors across runs and releases
+        assert_eq!(
+            first,
+            vec![Color::Yellow, Color::Magenta, Color::BrightGreen]
+        );
+    }
+
+    #[test]
+    fn test_prefixes_padded_to_longest_name() {
+        let packages = vec![make_pkg("a"), make_pkg("core"), make_pkg("feature_auth")];
+        let width = name_width(&packages, DEFAULT_MAX_NAME_WIDTH);
+        assert_eq!(width, 12);
+
+        let prefixes: Vec<String> = packages
+            .iter()
+            .map(|p| format_prefix(&p.name, width))
+            .collect();
+        assert_eq!(prefixes[0], "[a]           ");
+        assert_eq!(prefixes[1], "[core]        ");
+        assert_eq!(prefixes[2], "[feature_auth]");
+        assert!(prefixes.iter().all(|p| p.chars().count() == width + 2));
+    }
+
+    #[test]
+    fn test_prefixes_truncated_beyond_max_width() {
+        let packages = vec![make_pkg("app"), make_pkg("very_long_package_name")];
+        let width = name_width(&packages, 8);
+        assert_eq!(width, 8);
+        assert_eq!(format_prefix("very_long_package_name", width), "[very_lo…]");
+        assert_eq!(format_prefix("app", width), "[app]     ");
+    }
+
+    #[test]
+    fn test_prefix_unpadded_without_packages() {
+        assert_eq!(name_width(&[], DEFAULT_MAX_NAME_WIDTH), 0);
+        assert_eq!(format_prefix("core", 0), "[core]");
+    }
+
+    #[test]
+    fn test_porcelain_record_is_tab_separated() {
+        assert_eq!(
+            porcelain_record("core", "success", std::time::Duration::from_millis(1234)),
+            "core\tsuccess\t1234"
+        );
+        assert_eq!(
+            porcelain_record("app", "skipped", std::time::Duration::ZERO),
+            "app\tskipped\t0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_loop_shows_latest_phase_in_progress_message() {
+        let run = |finish_app: bool| async move {
+            let pb = ProgressBar::hidden();
+            let (tx, rx) = mpsc::unbounded_channel();
+            let output = |name: &str, line: &str| Event::PackageOutput {
+                name: name.to_string(),
+                line: line.to_string(),
+                is_stderr: true,
+            };
+            let finished = |name: &str| Event::PackageFinished {
+                name: name.to_string(),
+                success: true,
+                duration: std::time::Duration::ZERO,
+            };
+            tx.send(Event::Progress {
+                completed: 0,
+                total: 0,
+                message: "dart pub get...".into(),
+            })
+            .unwrap();
+            tx.send(output("core", "Resolving dependencies..."))
+                .unwrap();
+            tx.send(output("app", "+ http 1.2.3")).unwrap();
+            tx.send(output("app", "  meta 1.15.0")).unwrap();
+            tx.send(finished("core")).unwrap();
+            if finish_app {
+                tx.send(finished("app")).unwrap();
+            }
+            drop(tx);
+            render_loop(
+                rx,
+                Some(pb.clone()),
+                8,
+                RenderSettings::default(),
+                Some(melos_core::commands::bootstrap::pub_get_phase),
+            )
+            .await
+            .unwrap();
+            pb.message()
+        };
+
+        assert_eq!(
+            run(false).await,
+            "dart pub get... app: downloading http-1.2.3"
+        );
+        assert_eq!(run(true).await, "dart pub get...");
+    }
+
+    #[tokio::test]
+    async fn test_log_dir_writes_per_package_logs_and_forwards_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log_dir = dir.path().join("logs");
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(log_dir.join("core.log"), "stale\n").unwrap();
+
+        let (render_tx, mut render_rx) = mpsc::unbounded_channel();
+        let (tx, handle) = attach_log_dir(log_dir.to_str(), render_tx).unwrap();
+        let output = |name: &str, line: &str, is_stderr| Event::PackageOutput {
+            name: name.to_string(),
+            line: line.to_string(),
+            is_stderr,
+        };
+        for name in ["core", "app"] {
+            tx.send(Event::PackageStarted {
+                name: name.to_string(),
+            })
+            .unwrap();
+        }
+        tx.send(output("core", "compiling", false)).unwrap();
+        tx.send(output("app", "app line", false)).unwrap();
+        tx.send(output("core", "warning: unused", true)).unwrap();
+        tx.send(Event::PackageFinished {
+            name: "core".to_string(),
+            success: true,
+            duration: std::time::Duration::ZERO,
+        })
+        .unwrap();
+        drop(tx);
+        handle.unwrap().await.unwrap().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(log_dir.join("core.log")).unwrap(),
+            "compiling\nwarning: unused\n"
+        );
+        // Unfinished packages are flushed when the channel closes
+        assert_eq!(
+            std::fs::read_to_string(log_dir.join("app.log")).unwrap(),
+            "app line\n"
+        );
+
+        let mut forwarded = 0;
+        while render_rx.try_recv().is_ok() {
+            forwarded += 1;
+        }
+        assert_eq!(forwarded, 6);
+    }
+
+    #[test]
+    fn test_json_event_line_is_single_line_object() {
+        let line = json_event_line(Event::PackageOutput {
+            name: "core".to_string(),
+            line: "multi\nline".to_string(),
+            is_stderr: false,
+        });
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["event"], "packageOutput");
+        assert_eq!(value["line"], "multi\nline");
+    }
+
+    #[test]
+    fn test_attach_log_dir_without_dir_passes_sender_through() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let (_tx, handle) = attach_log_dir(None, tx).unwrap();
+        assert!(handle.is_none());
+    }
+
+    #[test]
+    fn test_skipped_summary_lists_reasons() {
+        let skipped = vec![
+            ("x".to_string(), "unchanged".to_string()),
+            ("y".to_string(), "no test/".to_string()),
+        ];
+        assert_eq!(
+            format_skipped_summary(&skipped).as_deref(),
+            Some("2 skipped: x (unchanged), y (no test/)")
+        );
+        assert!(format_skipped_summary(&[]).is_none());
+    }
+
+    #[test]
+    fn test_other_counts_suffix() {
+        let mut results = PackageResults::from(vec![("a".to_string(), true)]);
+        assert_eq!(other_counts_suffix(&results), "");
+        results
+            .skipped
+            .push(("b".to_string(), "fail-fast".to_string()));
+        assert_eq!(other_counts_suffix(&results), ", 1 skipped");
+        results.aborted.push("c".to_string());
+        assert_eq!(other_counts_suffix(&results), ", 1 aborted, 1 skipped");
+    }
+}
@@ -49,6 +49,32 @@ fn create_fixture_workspace(
     }
 }
 
+/// Commit identity for git in fixture repositories, so commits work without
+/// a global git config.
+const GIT_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "test"),
+    ("GIT_AUTHOR_EMAIL", "test@example.com"),
+    ("GIT_COMMITTER_NAME", "test"),
+    ("GIT_COMMITTER_EMAIL", "test@example.com"),
+];
+
+/// Run git in `dir` and return its stdout, failing the test if git fails.
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .envs(GIT_IDENTITY)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {}: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
 /// Build a `Command` for the melos-rs binary.
 fn melos_cmd() -> Command {
     assert_cmd::cargo_bin_cmd!("melos-rs")
@@ -90,6 +116,73 @@ fn test_no_workspace_error() {
         .stderr(predicate::str::contains("Could not find melos.yaml"));
 }
 
+#[cfg(unix)]
+#[test]
+fn test_external_command_runs_script_then_plugin() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "plugin_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: plugin_ws\npackages:\n  - packages/*\nscripts:\n  greet: echo from-script\n",
+    )
+    .unwrap();
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    for name in ["melos-rs-hello", "melos-rs-greet"] {
+        fs::write(
+            bin.join(name),
+            "#!/bin/sh\necho \"plugin args: $*\"\necho \"root: $MELOS_ROOT_PATH\"\necho \"config: $MELOS_WORKSPACE_CONFIG\"\nexit 3\n",
+        )
+        .unwrap();
+        fs::set_permissions(bin.join(name), fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args(["hello", "one", "--two"])
+        .output()
+        .unwrap();
+    // The plugin's exit code is passed through
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let root = dir.path().canonicalize().unwrap();
+    assert!(stdout.contains("plugin args: one --two"), "{stdout}");
+    assert!(
+        stdout.contains(&format!("root: {}", root.display())),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("config: {}", root.join("melos.yaml").display())),
+        "{stdout}"
+    );
+
+    // Scripts take precedence over plugins of the same name
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .arg("greet")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("from-script"))
+        .stdout(predicate::str::contains("plugin args").not());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .arg("nope")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown command 'nope'"))
+        .stderr(predicate::str::contains(
+            "Built-in commands: analyze, bootstrap",
+        ))
+        .stderr(predicate::str::contains("Plugins: greet, hello"));
+}
+
 // ---------------------------------------------------------------------------
 // Init command tests
 // ---------------------------------------------------------------------------
@@ -167,626 +260,987 @@ fn test_list_packages() {
 }
 
 #[test]
-fn test_list_json_output() {
-    let dir = TempDir::new().unwrap();
+fn test_workspace_flag_overrides_cwd() {
+    let ws = TempDir::new().unwrap();
     create_fixture_workspace(
-        dir.path(),
-        "json_test",
-        &[
-            ("alpha", "1.2.3", false, &[]),
-            ("beta", "0.1.0", false, &[]),
-        ],
+        ws.path(),
+        "remote_ws",
+        &[("remote_core", "1.0.0", false, &[])],
     );
 
-    let output = melos_cmd()
-        .current_dir(dir.path())
-        .args(["list", "--json", "--quiet"])
-        .output()
-        .expect("command should run");
-
-    assert!(output.status.success());
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    // Should be valid JSON
-    let parsed: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("Invalid JSON output: {e}\nOutput: {stdout}"));
-
-    let arr = parsed.as_array().expect("should be a JSON array");
-    assert_eq!(arr.len(), 2);
+    let cwd = TempDir::new().unwrap();
+    create_fixture_workspace(
+        cwd.path(),
+        "local_ws",
+        &[("local_pkg", "1.0.0", false, &[])],
+    );
 
-    // Check fields
-    let names: Vec<&str> = arr.iter().map(|v| v["name"].as_str().unwrap()).collect();
-    assert!(names.contains(&"alpha"));
-    assert!(names.contains(&"beta"));
+    melos_cmd()
+        .current_dir(cwd.path())
+        .args(["list", "--workspace"])
+        .arg(ws.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("remote_ws"))
+        .stdout(predicate::str::contains("remote_core"))
+        .stdout(predicate::str::contains("local_pkg").not());
 }
 
 #[test]
-fn test_list_parsable_output() {
-    let dir = TempDir::new().unwrap();
+fn test_config_flag_loads_given_file() {
+    let ws = TempDir::new().unwrap();
     create_fixture_workspace(
-        dir.path(),
-        "parsable_test",
-        &[("widget", "3.0.0", false, &[])],
+        ws.path(),
+        "remote_ws",
+        &[("remote_core", "1.0.0", false, &[])],
+    );
+    fs::rename(
+        ws.path().join("melos.yaml"),
+        ws.path().join("melos.ci.yaml"),
+    )
+    .unwrap();
+
+    let cwd = TempDir::new().unwrap();
+    create_fixture_workspace(
+        cwd.path(),
+        "local_ws",
+        &[("local_pkg", "1.0.0", false, &[])],
     );
 
     melos_cmd()
-        .current_dir(dir.path())
-        .args(["list", "--parsable", "--quiet"])
+        .current_dir(cwd.path())
+        .args(["list", "--config"])
+        .arg(ws.path().join("melos.ci.yaml"))
         .assert()
         .success()
-        .stdout(predicate::str::contains("widget:3.0.0:"));
+        .stdout(predicate::str::contains("remote_core"))
+        .stdout(predicate::str::contains("local_pkg").not());
+
+    melos_cmd()
+        .current_dir(cwd.path())
+        .args(["list", "--config"])
+        .arg(ws.path().join("missing.yaml"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
 }
 
 #[test]
-fn test_list_graph_output() {
+fn test_exec_honors_order_dependents_from_config() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "graph_test",
+        "exec_cfg_ws",
         &[
-            ("base", "1.0.0", false, &[]),
-            ("derived", "1.0.0", false, &["base"]),
+            ("app", "1.0.0", false, &["core"]),
+            ("core", "1.0.0", false, &[]),
         ],
     );
+    let melos_yaml = dir.path().join("melos.yaml");
+    let mut config = fs::read_to_string(&melos_yaml).unwrap();
+    config.push_str("command:\n  exec:\n    orderDependents: true\n    concurrency: 1\n");
+    fs::write(&melos_yaml, config).unwrap();
 
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["list", "--graph", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("derived"))
-        .stdout(predicate::str::contains("base"));
+        .args(["exec", "--", "echo", "ran"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Packages ordered by dependencies"));
+    assert!(stdout.contains("with concurrency 1"));
+    let core = stdout.find("-> core").unwrap();
+    let app = stdout.find("-> app").unwrap();
+    assert!(core < app, "core should run before app:\n{stdout}");
 }
 
-// ---------------------------------------------------------------------------
-// Exec command tests
-// ---------------------------------------------------------------------------
-
 #[test]
-fn test_exec_echo() {
+fn test_run_exec_script_timeout_marks_package_failed() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "exec_test",
+        "timeout_ws",
         &[
             ("pkg_a", "1.0.0", false, &[]),
             ("pkg_b", "1.0.0", false, &[]),
         ],
     );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: timeout_ws\npackages:\n  - packages/*\nscripts:\n  slow:\n    run: sleep 5\n    exec:\n      concurrency: 1\n      failFast: true\n      timeout: 1\n",
+    )
+    .unwrap();
 
-    melos_cmd()
+    let start = std::time::Instant::now();
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["exec", "--quiet", "--", "echo", "hello"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("hello"));
+        .args(["run", "slow"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(5),
+        "timeout should kill the command before sleep finishes"
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("timeout 1s"), "stdout:\n{stdout}");
+    assert_eq!(
+        stderr.matches("TIMEOUT: timed out after 1s").count(),
+        1,
+        "expected one timeout:\n{stderr}"
+    );
+    assert!(
+        stdout.contains("SKIPPED (fail-fast)"),
+        "fail-fast should skip the remaining package:\n{stdout}"
+    );
 }
 
+#[cfg(unix)]
 #[test]
-fn test_exec_dry_run() {
+fn test_exec_continue_on_missing_script_skips_not_found() {
+    use std::os::unix::fs::PermissionsExt;
+
     let dir = TempDir::new().unwrap();
-    create_fixture_workspace(dir.path(), "dry_test", &[("pkg_x", "1.0.0", false, &[])]);
+    create_fixture_workspace(
+        dir.path(),
+        "missing_tool_ws",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+    // Only pkg_b has the tool
+    let tool = dir.path().join("packages/pkg_b/tool.sh");
+    fs::write(&tool, "#!/bin/sh\necho tool ran\n").unwrap();
+    fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
 
-    melos_cmd()
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--fail-fast", "--", "./tool.sh"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let output = melos_cmd()
         .current_dir(dir.path())
         .args([
             "exec",
-            "--dry-run",
-            "--quiet",
+            "--fail-fast",
+            "--continue-on-missing-script",
             "--",
-            "echo",
-            "should_not_run",
+            "./tool.sh",
         ])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("pkg_x"))
-        .stdout(predicate::str::contains("echo should_not_run"));
-}
-
-// ---------------------------------------------------------------------------
-// Completion command test
-// ---------------------------------------------------------------------------
-
-#[test]
-fn test_completion_bash() {
-    // Completion doesn't require a workspace
-    melos_cmd()
-        .args(["completion", "bash"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("complete"));
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("SKIPPED (not found)"));
+    assert!(stdout.contains("tool ran"));
+    // The not-found package is neither passed nor failed
+    assert!(
+        stdout.contains("All 1 package(s) passed exec, 1 skipped."),
+        "{stdout}"
+    );
 }
 
-// ---------------------------------------------------------------------------
-// Health command test
-// ---------------------------------------------------------------------------
-
+#[cfg(unix)]
 #[test]
-fn test_health_check_no_issues() {
+fn test_run_working_directory_skips_packages_without_it() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "health_test",
+        "workdir_ws",
         &[
-            ("svc_a", "1.0.0", false, &[]),
-            ("svc_b", "2.0.0", false, &[]),
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
         ],
     );
+    fs::create_dir_all(dir.path().join("packages/pkg_a/android")).unwrap();
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: workdir_ws\npackages:\n  - packages/*\nscripts:\n  gradle:\n    exec: pwd\n    workingDirectory: android\n",
+    )
+    .unwrap();
 
-    // Only run version-drift check (fixture packages lack description/homepage/sdk)
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["health", "--version-drift", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("No health issues found"));
+        .args(["run", "gradle"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("pkg_a/android"), "{stdout}");
+    assert!(stdout.contains("SKIPPED (no android/)"), "{stdout}");
 }
 
-// ---------------------------------------------------------------------------
-// Scope filter integration test
-// ---------------------------------------------------------------------------
-
+#[cfg(unix)]
 #[test]
-fn test_list_with_scope_filter() {
+fn test_exec_fail_fast_counts_aborted_packages_separately() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "scope_test",
+        "abort_ws",
         &[
-            ("auth", "1.0.0", false, &[]),
-            ("billing", "1.0.0", false, &[]),
-            ("core", "1.0.0", false, &[]),
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
         ],
     );
 
-    // Scope to only "auth"
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["list", "--scope", "auth", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("auth"))
-        .stdout(predicate::str::contains("billing").not())
-        .stdout(predicate::str::contains("core").not());
-}
+        .args([
+            "exec",
+            "--fail-fast",
+            "-c",
+            "2",
+            "--",
+            r#"if [ "$MELOS_PACKAGE_NAME" = pkg_a ]; then sleep 0.2; exit 1; else sleep 10; fi"#,
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
 
-// ---------------------------------------------------------------------------
-// Clean command tests
-// ---------------------------------------------------------------------------
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 package(s) failed exec (0 passed, 1 aborted)"),
+        "stderr:\n{stderr}"
+    );
+}
 
 #[test]
-fn test_clean_dart_packages() {
+fn test_exec_fail_fast_counts_skipped_packages_separately() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "clean_test",
+        "fail_fast_ws",
         &[
             ("pkg_a", "1.0.0", false, &[]),
             ("pkg_b", "1.0.0", false, &[]),
         ],
     );
 
-    // Create build/ directories to be cleaned
-    let build_a = dir.path().join("packages/pkg_a/build");
-    let build_b = dir.path().join("packages/pkg_b/build");
-    fs::create_dir_all(&build_a).unwrap();
-    fs::create_dir_all(&build_b).unwrap();
-    fs::write(build_a.join("output.js"), "// compiled").unwrap();
-
-    assert!(build_a.exists());
-    assert!(build_b.exists());
-
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["clean", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("CLEANED"));
+        .args(["exec", "--fail-fast", "-c", "1", "--", "exit 1"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
 
-    // build/ directories should be removed for pure Dart packages
-    assert!(!build_a.exists(), "pkg_a/build should be removed");
-    assert!(!build_b.exists(), "pkg_b/build should be removed");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 package(s) failed exec (0 passed, 1 skipped)"),
+        "stderr:\n{stderr}"
+    );
 }
 
 #[test]
-fn test_clean_deep() {
+#[cfg(unix)]
+fn test_exec_color_always_sets_force_color_for_children() {
     let dir = TempDir::new().unwrap();
-    create_fixture_workspace(
-        dir.path(),
-        "deep_clean_test",
-        &[("mylib", "1.0.0", false, &[])],
-    );
-
-    let pkg_dir = dir.path().join("packages/mylib");
+    create_fixture_workspace(dir.path(), "color_ws", &[("pkg_a", "1.0.0", false, &[])]);
 
-    // Create artifacts that deep clean should remove
-    let dart_tool = pkg_dir.join(".dart_tool");
-    let build_dir = pkg_dir.join("build");
-    let lock_file = pkg_dir.join("pubspec.lock");
-    fs::create_dir_all(&dart_tool).unwrap();
-    fs::create_dir_all(&build_dir).unwrap();
-    fs::write(&lock_file, "# lock file").unwrap();
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args([
+            "exec",
+            "--color=always",
+            "--",
+            "echo",
+            "force=$FORCE_COLOR clicolor=$CLICOLOR_FORCE",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("force=1 clicolor=1"), "{stdout}");
+    // melos-rs's own output is colored too, even though stdout is a pipe
+    assert!(stdout.contains("\x1b["), "{stdout}");
 
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["clean", "--deep", "--quiet"])
-        .assert()
-        .success();
+        .args(["exec", "--color=never", "--", "echo", "no_color=$NO_COLOR"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("no_color=1"), "{stdout}");
+    assert!(!stdout.contains("\x1b["), "{stdout}");
+}
 
-    assert!(
-        !dart_tool.exists(),
-        ".dart_tool should be removed by deep clean"
+#[test]
+fn test_check_constraints_reports_violations() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "constraints_ws",
+        &[("core", "2.0.0", false, &[]), ("ui", "1.0.0", false, &[])],
     );
-    assert!(!build_dir.exists(), "build should be removed by deep clean");
+    let app = dir.path().join("packages/app");
+    fs::create_dir_all(&app).unwrap();
+    fs::write(
+        app.join("pubspec.yaml"),
+        "name: app\nversion: 1.0.0\ndependencies:\n  core: ^1.0.0\n  ui: ^1.0.0\n",
+    )
+    .unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .arg("check-constraints")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
     assert!(
-        !lock_file.exists(),
-        "pubspec.lock should be removed by deep clean"
+        stdout.contains("app -> core: constraint ^1.0.0 not satisfied by 2.0.0"),
+        "{stdout}"
     );
-}
+    assert!(!stdout.contains("app -> ui"), "{stdout}");
 
-// ---------------------------------------------------------------------------
-// Exec with scope filter test
-// ---------------------------------------------------------------------------
+    fs::write(
+        app.join("pubspec.yaml"),
+        "name: app\nversion: 1.0.0\ndependencies:\n  core: ^2.0.0\n",
+    )
+    .unwrap();
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .arg("check-constraints")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
 
 #[test]
-fn test_exec_with_scope_filter() {
+#[cfg(unix)]
+fn test_exec_env_file_layers_below_melos_vars() {
     let dir = TempDir::new().unwrap();
-    create_fixture_workspace(
-        dir.path(),
-        "exec_scope_test",
-        &[
-            ("alpha", "1.0.0", false, &[]),
-            ("beta", "1.0.0", false, &[]),
-        ],
-    );
+    create_fixture_workspace(dir.path(), "env_file_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join(".env"),
+        "# shared\nAPI_URL=https://base\nTOKEN=\"s3cr3t value\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join(".env.local"),
+        "API_URL=https://local\nMELOS_PACKAGE_NAME=ignored\n",
+    )
+    .unwrap();
 
-    // Only execute in "alpha" package
     let output = melos_cmd()
         .current_dir(dir.path())
         .args([
-            "exec", "--scope", "alpha", "--quiet", "--", "echo", "FOUND_IT",
+            "exec",
+            "--env-file",
+            ".env",
+            "--env-file",
+            ".env.local",
+            "--",
+            "echo",
+            "\"$API_URL|$TOKEN|$MELOS_PACKAGE_NAME\"",
         ])
         .output()
-        .expect("command should run");
-
-    assert!(output.status.success());
+        .unwrap();
     let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("FOUND_IT"), "Should see echo output");
-    // Should only run in alpha, not beta
-    assert!(stdout.contains("alpha"), "Should mention alpha package");
-}
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("https://local|s3cr3t value|pkg_a"),
+        "{stdout}"
+    );
 
-// ---------------------------------------------------------------------------
-// List with ignore filter test
-// ---------------------------------------------------------------------------
+    fs::write(dir.path().join(".env"), "OK=1\nnot valid\n").unwrap();
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--env-file", ".env", "--", "true"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("line 2: expected KEY=VALUE"), "{stderr}");
+}
 
 #[test]
-fn test_list_with_ignore_filter() {
+fn test_exec_porcelain_emits_tab_separated_records() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "ignore_test",
+        "porcelain_ws",
         &[
-            ("core", "1.0.0", false, &[]),
-            ("internal", "1.0.0", false, &[]),
-            ("utils", "1.0.0", false, &[]),
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
         ],
     );
 
-    // Ignore "internal" package
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["list", "--ignore", "internal", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("core"))
-        .stdout(predicate::str::contains("utils"))
-        .stdout(predicate::str::contains("internal").not());
-}
+        .args(["exec", "--porcelain", "--", "echo", "hello"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
 
-// ---------------------------------------------------------------------------
-// List with no-private filter test
-// ---------------------------------------------------------------------------
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let records: Vec<Vec<&str>> = stdout
+        .lines()
+        .filter(|l| l.contains('\t'))
+        .map(|l| l.split('\t').collect())
+        .collect();
+    let mut names: Vec<&str> = records.iter().map(|r| r[0]).collect();
+    names.sort();
+    assert_eq!(names, vec!["pkg_a", "pkg_b"]);
+    for record in &records {
+        assert_eq!(record.len(), 3);
+        assert_eq!(record[1], "success");
+        assert!(record[2].parse::<u64>().is_ok());
+    }
+    assert!(!stdout.contains("SUCCESS"));
+    assert!(!stdout.contains("───"));
+    assert!(!stdout.contains("\x1b["));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("hello"));
+}
 
 #[test]
-fn test_list_with_no_private_filter() {
+fn test_porcelain_stdout_contains_only_records() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "private_test",
-        &[("public_pkg", "1.0.0", false, &[])],
+        "porcelain_only_ws",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
     );
-
-    // Create a private package manually (publish_to: none)
-    let private_dir = dir.path().join("packages/private_pkg");
-    fs::create_dir_all(&private_dir).unwrap();
     fs::write(
-        private_dir.join("pubspec.yaml"),
-        "name: private_pkg\nversion: 1.0.0\npublish_to: none\n",
+        dir.path().join("melos.yaml"),
+        "name: porcelain_only_ws\npackages:\n  - packages/*\nscripts:\n  hello:\n    exec: echo hello\n",
     )
     .unwrap();
 
-    melos_cmd()
-        .current_dir(dir.path())
-        .args(["list", "--no-private", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("public_pkg"))
-        .stdout(predicate::str::contains("private_pkg").not());
+    for args in [
+        &["exec", "--porcelain", "--", "echo", "hello"][..],
+        &["run", "hello", "--porcelain"][..],
+    ] {
+        let output = melos_cmd()
+            .current_dir(dir.path())
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut names: Vec<&str> = stdout
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                assert_eq!(fields.len(), 3, "not a record: {line:?} in {stdout:?}");
+                fields[0]
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["pkg_a", "pkg_b"]);
+
+        // The headers and package list are still shown, on stderr
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Running in 2 package(s)"));
+        assert!(stderr.contains("-> pkg_a"));
+    }
 }
 
-// ---------------------------------------------------------------------------
-// Init with apps directory test
-// ---------------------------------------------------------------------------
-
 #[test]
-fn test_init_7x_with_apps() {
+fn test_exec_redacts_matching_output() {
     let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "redact_ws", &[("pkg_a", "1.0.0", false, &[])]);
+
     melos_cmd()
         .current_dir(dir.path())
-        .args(["init", "apps_ws", "-d", "."])
-        .write_stdin("y\n") // accept apps directory
+        .args([
+            "exec",
+            "--redact",
+            "token=[a-z0-9]+",
+            "--output-dir",
+            "logs",
+            "--",
+            "echo",
+            "auth token=s3cr3t done",
+        ])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Created:"));
+        .stdout(predicate::str::contains("[pkg_a] auth *** done"))
+        .stdout(predicate::str::contains("[pkg_a] auth token=s3cr3t").not());
 
-    // Should have both packages/ and apps/ dirs
-    assert!(dir.path().join("packages").is_dir());
-    assert!(dir.path().join("apps").is_dir());
+    // Logs are redacted too
+    let log = std::fs::read_to_string(dir.path().join("logs/pkg_a.log")).unwrap();
+    assert_eq!(log, "auth *** done\n");
 }
 
-// ---------------------------------------------------------------------------
-// Bootstrap dry-run test (Batch 29)
-// ---------------------------------------------------------------------------
-
+#[cfg(unix)]
 #[test]
-fn test_bootstrap_dry_run() {
+fn test_exec_buffered_stream_mode_groups_package_output() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "boot_dry",
+        "stream_ws",
         &[
-            ("core", "1.0.0", false, &[]),
-            ("app", "2.0.0", false, &["core"]),
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
         ],
     );
 
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["bootstrap", "--dry-run", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("core"))
-        .stdout(predicate::str::contains("app"))
-        .stdout(predicate::str::contains("DRY RUN"));
+        .args([
+            "exec",
+            "--stream-mode",
+            "buffered",
+            "-c",
+            "2",
+            "--",
+            "echo first; sleep 0.3; echo second",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
 
-    // No pubspec_overrides.yaml should have been generated
-    assert!(
-        !dir.path()
-            .join("packages/core/pubspec_overrides.yaml")
-            .exists(),
-        "dry-run should not generate pubspec_overrides.yaml"
-    );
-    assert!(
-        !dir.path()
-            .join("packages/app/pubspec_overrides.yaml")
-            .exists(),
-        "dry-run should not generate pubspec_overrides.yaml"
-    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.ends_with(" first") || l.ends_with(" second"))
+        .collect();
+    assert_eq!(lines.len(), 4, "unexpected output:\n{}", stdout);
+    for pair in lines.chunks(2) {
+        let pkg = pair[0].split(']').next().unwrap();
+        assert!(pair[0].ends_with("first") && pair[1].ends_with("second"));
+        assert!(pair[1].starts_with(pkg), "interleaved output:\n{}", stdout);
+    }
 }
 
-// ---------------------------------------------------------------------------
-// Clean dry-run test (Batch 29)
-// ---------------------------------------------------------------------------
-
+#[cfg(unix)]
 #[test]
-fn test_clean_dry_run() {
+fn test_exec_buffered_max_log_lines_keeps_tail() {
     let dir = TempDir::new().unwrap();
-    create_fixture_workspace(
-        dir.path(),
-        "clean_dry",
-        &[
-            ("pkg_a", "1.0.0", false, &[]),
-            ("pkg_b", "1.0.0", false, &[]),
-        ],
-    );
-
-    // Create build artifacts that should NOT be removed in dry-run
-    let build_a = dir.path().join("packages/pkg_a/build");
-    let build_b = dir.path().join("packages/pkg_b/build");
-    fs::create_dir_all(&build_a).unwrap();
-    fs::create_dir_all(&build_b).unwrap();
-    fs::write(build_a.join("output.js"), "// compiled").unwrap();
+    create_fixture_workspace(dir.path(), "tail_ws", &[("pkg_a", "1.0.0", false, &[])]);
 
-    melos_cmd()
+    let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["clean", "--dry-run", "--quiet"])
-        .assert()
-        .success()
-        .stdout(predicate::str::contains("DRY RUN"));
+        .args([
+            "exec",
+            "--stream-mode",
+            "buffered",
+            "--max-log-lines",
+            "3",
+            "--",
+            "for i in 1 2 3 4 5 6; do echo out$i; done",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
 
-    // Build artifacts must still exist (nothing was deleted)
-    assert!(build_a.exists(), "dry-run should not remove pkg_a/build");
-    assert!(build_b.exists(), "dry-run should not remove pkg_b/build");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.starts_with("[pkg_a]") && !l.contains("SUCCESS"))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "[pkg_a] ... 3 earlier line(s) dropped (--max-log-lines)",
+            "[pkg_a] out4",
+            "[pkg_a] out5",
+            "[pkg_a] out6",
+        ],
+        "unexpected output:\n{}",
+        stdout
+    );
 }
 
-// ---------------------------------------------------------------------------
-// Version dry-run test (Batch 29)
-// ---------------------------------------------------------------------------
-
+#[cfg(unix)]
 #[test]
-fn test_version_dry_run() {
+fn test_exec_quiet_failures_prints_only_failed_output() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "ver_dry",
+        "quiet_ws",
         &[
-            ("core", "1.0.0", false, &[]),
-            ("app", "2.0.0", false, &["core"]),
+            ("pkg_ok", "1.0.0", false, &[]),
+            ("pkg_bad", "1.0.0", false, &[]),
         ],
     );
 
     let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["version", "--dry-run", "--all", "--quiet", "patch"])
+        .args([
+            "exec",
+            "--quiet-failures",
+            "--",
+            r#"echo "out-$MELOS_PACKAGE_NAME"; echo "err-$MELOS_PACKAGE_NAME" >&2; [ "$MELOS_PACKAGE_NAME" != pkg_bad ]"#,
+        ])
         .output()
-        .expect("command should run");
-
-    assert!(output.status.success());
+        .unwrap();
+    assert!(!output.status.success());
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stdout.contains("pkg_ok") && !stderr.contains("pkg_ok"));
     assert!(
-        stdout.contains("Version changes:"),
-        "should show the version plan"
-    );
-    assert!(stdout.contains("core"), "should mention core package");
-    assert!(stdout.contains("app"), "should mention app package");
-    assert!(
-        stdout.contains("DRY RUN"),
-        "should indicate this is a dry run"
+        !stdout.contains("SUCCESS"),
+        "unexpected output:\n{}",
+        stdout
     );
+    assert!(stdout.contains("[pkg_bad] out-pkg_bad"));
+    assert!(stderr.contains("[pkg_bad] err-pkg_bad"));
+    assert!(stderr.contains("FAILED"));
+    assert!(stderr.contains("1 package(s) failed exec (1 passed)"));
+}
 
-    // Pubspec versions must remain unchanged on disk
-    let core_pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
-    assert!(
-        core_pubspec.contains("version: 1.0.0"),
-        "dry-run should not modify core version"
+#[test]
+fn test_unknown_redact_preset_fails() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "redact_ws", &[("pkg_a", "1.0.0", false, &[])]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--redact-preset", "gitlab"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown redaction preset 'gitlab'",
+        ));
+}
+
+#[test]
+fn test_selftest_runs_without_workspace() {
+    let dir = TempDir::new().unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .arg("selftest")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")))
+        .stdout(predicate::str::contains(std::env::consts::OS))
+        .stdout(predicate::str::contains("no workspace found"));
+}
+
+#[test]
+fn test_selftest_json_reports_workspace() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "diag_ws",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
     );
-    let app_pubspec = fs::read_to_string(dir.path().join("packages/app/pubspec.yaml")).unwrap();
-    assert!(
-        app_pubspec.contains("version: 2.0.0"),
-        "dry-run should not modify app version"
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["selftest", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["tool_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["os"], std::env::consts::OS);
+    assert_eq!(report["workspace"]["name"], "diag_ws");
+    assert_eq!(report["workspace"]["packages"], 2);
+    assert_eq!(report["workspace"]["config_mode"], "melos.yaml");
+}
+
+#[test]
+fn test_graph_json_output() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "graph_ws",
+        &[
+            ("app", "1.0.0", false, &["core"]),
+            ("core", "2.0.0", false, &[]),
+        ],
     );
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["--quiet", "graph", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(graph[0]["name"], "app");
+    assert_eq!(graph[0]["dependencies"], serde_json::json!(["core"]));
+    assert_eq!(graph[1]["name"], "core");
+    assert_eq!(graph[1]["version"], "2.0.0");
 }
 
-// ---------------------------------------------------------------------------
-// Health --json with no issues (Batch 29)
-// ---------------------------------------------------------------------------
+#[test]
+fn test_graph_json_output_with_no_matches() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "graph_ws", &[("app", "1.0.0", false, &[])]);
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["--quiet", "graph", "--format", "json", "--scope", "missing"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(graph, serde_json::json!([]));
+}
 
 #[test]
-fn test_health_json_no_issues() {
+fn test_graph_dot_output() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "health_json_ok",
+        "graph_ws",
         &[
-            ("svc_a", "1.0.0", false, &[]),
-            ("svc_b", "2.0.0", false, &[]),
+            ("app", "1.0.0", false, &["core"]),
+            ("core", "1.0.0", false, &[]),
+        ],
+    );
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["--quiet", "graph"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph packages {"))
+        .stdout(predicate::str::contains("\"app\" -> \"core\";"));
+}
+
+#[test]
+fn test_list_json_output() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "json_test",
+        &[
+            ("alpha", "1.2.3", false, &[]),
+            ("beta", "0.1.0", false, &[]),
         ],
     );
 
     let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["health", "--json", "--version-drift", "--quiet"])
+        .args(["list", "--json", "--quiet"])
         .output()
         .expect("command should run");
 
     assert!(output.status.success());
 
     let stdout = String::from_utf8_lossy(&output.stdout);
+    // Should be valid JSON
     let parsed: serde_json::Value = serde_json::from_str(&stdout)
         .unwrap_or_else(|e| panic!("Invalid JSON output: {e}\nOutput: {stdout}"));
 
-    assert_eq!(
-        parsed["total_issues"], 0,
-        "should have zero issues for consistent workspace"
-    );
-    let drift = parsed["version_drift"]
-        .as_array()
-        .expect("version_drift should be an array");
-    assert!(drift.is_empty(), "version_drift should be empty");
-}
+    let arr = parsed.as_array().expect("should be a JSON array");
+    assert_eq!(arr.len(), 2);
 
-// ---------------------------------------------------------------------------
-// Health --json with version drift (Batch 29)
-// ---------------------------------------------------------------------------
+    // Check fields
+    let names: Vec<&str> = arr.iter().map(|v| v["name"].as_str().unwrap()).collect();
+    assert!(names.contains(&"alpha"));
+    assert!(names.contains(&"beta"));
+}
 
 #[test]
-fn test_health_json_with_drift() {
+fn test_list_json_golden() {
     let dir = TempDir::new().unwrap();
-
-    // Write melos.yaml
-    fs::write(
-        dir.path().join("melos.yaml"),
-        "name: drift_test\n\npackages:\n  - packages/*\n\nscripts: {}\n",
-    )
-    .unwrap();
-
-    // Create two packages that depend on the same external dep at different versions
-    let pkg_a_dir = dir.path().join("packages/pkg_a");
-    let pkg_b_dir = dir.path().join("packages/pkg_b");
-    fs::create_dir_all(&pkg_a_dir).unwrap();
-    fs::create_dir_all(&pkg_b_dir).unwrap();
-
-    fs::write(
-        pkg_a_dir.join("pubspec.yaml"),
-        "name: pkg_a\nversion: 1.0.0\ndependencies:\n  http: ^1.0.0\n",
-    )
-    .unwrap();
+    create_fixture_workspace(dir.path(), "json_golden", &[("core", "1.0.0", false, &[])]);
+    fs::create_dir_all(dir.path().join("packages/app")).unwrap();
     fs::write(
-        pkg_b_dir.join("pubspec.yaml"),
-        "name: pkg_b\nversion: 1.0.0\ndependencies:\n  http: ^2.0.0\n",
+        dir.path().join("packages/app/pubspec.yaml"),
+        "name: app\nversion: 2.1.0\npublish_to: none\ndependencies:\n  http: ^1.1.0\n  flutter:\n    sdk: flutter\n  core: ^1.0.0\ndev_dependencies:\n  test: any\n  lints: ^3.0.0\n",
     )
     .unwrap();
 
     let output = melos_cmd()
         .current_dir(dir.path())
-        .args(["health", "--json", "--version-drift", "--quiet"])
+        .args(["list", "--json", "--quiet"])
         .output()
         .expect("command should run");
+    assert!(output.status.success());
 
-    // Should fail because of drift
+    let expected = r#"[
+  {
+    "name": "app",
+    "version": "2.1.0",
+    "path": "packages/app",
+    "flutter": true,
+    "private": true,
+    "dependencies": [
+      "core",
+      "flutter",
+      "http"
+    ],
+    "devDependencies": [
+      "lints",
+      "test"
+    ],
+    "dependencyVersions": {
+      "core": "^1.0.0",
+      "http": "^1.1.0",
+      "lints": "^3.0.0"
+    }
+  },
+  {
+    "name": "core",
+    "version": "1.0.0",
+    "path": "packages/core",
+    "flutter": false,
+    "private": false,
+    "dependencies": [],
+    "devDependencies": [],
+    "dependencyVersions": {}
+  }
+]
+"#;
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+#[test]
+fn test_list_long_git_columns() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "long_ws",
+        &[("core", "1.0.0", false, &[]), ("util", "0.1.0", false, &[])],
+    );
+    let list_long = || {
+        let output = melos_cmd()
+            .current_dir(dir.path())
+            .env("NO_COLOR", "1")
+            .args(["list", "--long"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let stdout = list_long();
+    assert!(stdout.contains("NAME"), "{stdout}");
     assert!(
-        !output.status.success(),
-        "health should exit non-zero when drift is found"
+        !stdout.contains("DIRTY"),
+        "no git columns outside git:\n{stdout}"
     );
 
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(
-        stderr.contains("health issue(s) found"),
-        "stderr should mention health issues"
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    git(dir.path(), &["tag", "core-v1.0.0"]);
+    git(
+        dir.path(),
+        &["commit", "-q", "--allow-empty", "-m", "chore: tweak"],
     );
+    fs::write(dir.path().join("packages/util/lib.dart"), "// wip\n").unwrap();
+
+    let stdout = list_long();
+    assert!(stdout.contains("DIRTY") && stdout.contains("SINCE TAG"));
+    let row = |name: &str| -> Vec<String> {
+        stdout
+            .lines()
+            .find(|l| l.trim_start().starts_with(&format!("{name} ")))
+            .unwrap()
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+    };
+    assert_eq!(row("core")[..5], ["core", "1.0.0", "dart", "N", "1"]);
+    assert_eq!(row("util")[..5], ["util", "0.1.0", "dart", "Y", "\u{2014}"]);
+}
 
-    // JSON is on stdout
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let parsed: serde_json::Value = serde_json::from_str(&stdout)
-        .unwrap_or_else(|e| panic!("Invalid JSON output: {e}\nOutput: {stdout}"));
+#[test]
+fn test_list_parsable_output() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "parsable_test",
+        &[("widget", "3.0.0", false, &[])],
+    );
 
-    assert!(
-        parsed["total_issues"].as_u64().unwrap_or(0) > 0,
-        "should have at least one issue"
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--parsable", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("widget:3.0.0:"));
+}
+
+#[test]
+fn test_list_category_filter() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "category_test",
+        &[
+            ("app_main", "1.0.0", false, &[]),
+            ("core_lib", "1.0.0", false, &[]),
+            ("utils", "1.0.0", false, &[]),
+        ],
     );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: category_test\npackages:\n  - packages/*\ncategories:\n  apps: [\"app_*\"]\n  libs: [\"core_*\"]\n",
+    )
+    .unwrap();
 
-    let drift = parsed["version_drift"]
-        .as_array()
-        .expect("version_drift should be an array");
-    assert!(!drift.is_empty(), "version_drift should have entries");
+    melos_cmd()
+        .current_dir(dir.path())
+        .args([
+            "list",
+            "--quiet",
+            "--category",
+            "apps",
+            "--category",
+            "libs",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("app_main"))
+        .stdout(predicate::str::contains("core_lib"))
+        .stdout(predicate::str::contains("utils").not());
 
-    // Find the http drift entry
-    let http_drift = drift
-        .iter()
-        .find(|d| d["dependency"] == "http")
-        .expect("should have a drift entry for 'http'");
-    let constraints = http_drift["constraints"]
-        .as_array()
-        .expect("constraints should be an array");
-    assert_eq!(
-        constraints.len(),
-        2,
-        "http should have 2 different constraints"
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--category", "tools"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Category 'tools' is not defined in `categories`. Available categories: apps, libs",
+        ));
+}
+
+#[test]
+fn test_list_graph_output() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "graph_test",
+        &[
+            ("base", "1.0.0", false, &[]),
+            ("derived", "1.0.0", false, &["base"]),
+        ],
     );
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--graph", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  derived\n  └── base\n"));
 }
 
 // ---------------------------------------------------------------------------
-// Exec success summary (Batch 29)
+// Exec command tests
 // ---------------------------------------------------------------------------
 
 #[test]
-fn test_exec_success_summary() {
+fn test_exec_echo() {
     let dir = TempDir::new().unwrap();
     create_fixture_workspace(
         dir.path(),
-        "exec_summary",
+        "exec_test",
         &[
             ("pkg_a", "1.0.0", false, &[]),
             ("pkg_b", "1.0.0", false, &[]),
@@ -795,8 +1249,2515 @@ fn test_exec_success_summary() {
 
     melos_cmd()
         .current_dir(dir.path())
-        .args(["exec", "--quiet", "--", "echo", "hi"])
+        .args(["exec", "--quiet", "--", "echo", "hello"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("All 2 package(s) passed exec."));
+        .stdout(predicate::str::contains("hello"));
+}
+
+#[test]
+fn test_exec_output_dir_writes_package_logs() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exec_logs",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+    let logs = dir.path().join("logs");
+    std::fs::create_dir_all(&logs).unwrap();
+    std::fs::write(logs.join("pkg_a.log"), "stale").unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--output-dir", "logs", "--", "echo", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello"));
+
+    for name in ["pkg_a", "pkg_b"] {
+        let log = std::fs::read_to_string(logs.join(format!("{name}.log"))).unwrap();
+        assert_eq!(log, "hello\n");
+    }
+}
+
+#[test]
+fn test_exec_resume_and_from_skip_packages() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exec_resume",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+            ("pkg_c", "1.0.0", false, &[]),
+        ],
+    );
+    // Each run logs the package, then fails where a `fail` marker exists
+    let command = r#"echo "$MELOS_PACKAGE_NAME" >> "$MELOS_ROOT_PATH/ran.log"; test ! -f fail"#;
+    let fail_marker = dir.path().join("packages/pkg_b/fail");
+    std::fs::write(&fail_marker, "").unwrap();
+    let ran = |dir: &TempDir| {
+        let log = std::fs::read_to_string(dir.path().join("ran.log")).unwrap_or_default();
+        std::fs::remove_file(dir.path().join("ran.log")).ok();
+        let mut names: Vec<String> = log.lines().map(str::to_string).collect();
+        names.sort();
+        names
+    };
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--", command])
+        .assert()
+        .failure();
+    assert_eq!(ran(&dir), vec!["pkg_a", "pkg_b", "pkg_c"]);
+    let manifest =
+        std::fs::read_to_string(dir.path().join(".dart_tool/melos_last_run.json")).unwrap();
+    assert!(manifest.contains("pkg_a") && !manifest.contains("pkg_b"));
+
+    // Only the failed package runs again; then nothing is left to resume
+    std::fs::remove_file(&fail_marker).unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--resume", "--", command])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("skipping 2 package(s)"));
+    assert_eq!(ran(&dir), vec!["pkg_b"]);
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--resume", "--", command])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All packages already succeeded"));
+    assert!(ran(&dir).is_empty());
+    // The JSON event stream keeps stdout free of the message
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--resume", "--json", "--stream", "--", command])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    // --resume only applies to the same command
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--resume", "--no-manifest", "--", "true"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No previous run of 'true' recorded",
+        ));
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--from", "pkg_b", "--", command])
+        .assert()
+        .success();
+    assert_eq!(ran(&dir), vec!["pkg_b", "pkg_c"]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--from", "nope", "--", "true"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("'nope' passed to --from"));
+}
+
+#[test]
+fn test_exec_json_stream_emits_ordered_events() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exec_stream",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args([
+            "exec", "--json", "--stream", "-c", "2", "--", "echo", "hello",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Every stdout line is a JSON event
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("{e}: {l}")))
+        .collect();
+
+    for pkg in ["pkg_a", "pkg_b"] {
+        let position = |kind: &str| {
+            events
+                .iter()
+                .position(|e| e["event"] == kind && e["package"] == pkg)
+                .unwrap_or_else(|| panic!("no {kind} for {pkg}:\n{stdout}"))
+        };
+        let started = position("packageStarted");
+        let output = position("packageOutput");
+        let finished = position("packageFinished");
+        assert!(started < output && output < finished, "{stdout}");
+        assert_eq!(events[output]["line"], "hello");
+        assert_eq!(events[finished]["success"], true);
+    }
+}
+
+#[test]
+fn test_exec_stream_requires_json() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "stream_json", &[("pkg_a", "1.0.0", false, &[])]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--stream", "--", "echo", "hello"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--json"));
+}
+
+#[test]
+fn test_exec_max_name_width_truncates_prefixes() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "width_ws",
+        &[("feature_auth", "1.0.0", false, &[])],
+    );
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["--max-name-width", "5", "exec", "--", "echo", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[feat…] hello"));
+}
+
+#[test]
+fn test_exec_concurrency_auto() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "auto_conc", &[("pkg_a", "1.0.0", false, &[])]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--verbose", "-c", "auto/2", "--", "echo", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--concurrency auto/2 resolved to"))
+        .stdout(predicate::str::contains("hello"));
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "-c", "0", "--", "echo", "hello"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("concurrency must be at least 1"));
+}
+
+#[test]
+fn test_exec_dry_run() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "dry_test", &[("pkg_x", "1.0.0", false, &[])]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args([
+            "exec",
+            "--dry-run",
+            "--quiet",
+            "--",
+            "echo",
+            "should_not_run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pkg_x"))
+        .stdout(predicate::str::contains("echo should_not_run"));
+}
+
+// ---------------------------------------------------------------------------
+// Completion command test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_completion_bash() {
+    // Completion doesn't require a workspace
+    melos_cmd()
+        .args(["completion", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete"));
+}
+
+// ---------------------------------------------------------------------------
+// Health command test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_health_check_no_issues() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "health_test",
+        &[
+            ("svc_a", "1.0.0", false, &[]),
+            ("svc_b", "2.0.0", false, &[]),
+        ],
+    );
+
+    // Only run version-drift check (fixture packages lack description/homepage/sdk)
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--version-drift", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No health issues found"));
+}
+
+#[test]
+fn test_health_fix_writes_inferred_repository() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "health_fix", &[("svc_a", "1.0.0", false, &[])]);
+    let melos_yaml = dir.path().join("melos.yaml");
+    let config = fs::read_to_string(&melos_yaml).unwrap();
+    fs::write(
+        &melos_yaml,
+        format!("{config}repository: https://github.com/org/repo\n"),
+    )
+    .unwrap();
+
+    // `description` can't be inferred, so the check still fails
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--missing-fields", "--fix"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "FIXED svc_a repository: https://github.com/org/repo/tree/main/packages/svc_a",
+        ))
+        .stdout(predicate::str::contains("missing: description"))
+        .stdout(predicate::str::contains("homepage/repository").not());
+
+    let pubspec = fs::read_to_string(dir.path().join("packages/svc_a/pubspec.yaml")).unwrap();
+    assert_eq!(
+        pubspec,
+        "name: svc_a\nversion: 1.0.0\nrepository: https://github.com/org/repo/tree/main/packages/svc_a\n"
+    );
+}
+
+#[test]
+fn test_health_analysis_options_reports_foreign_include() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "health_lints",
+        &[
+            ("svc_a", "1.0.0", false, &[]),
+            ("svc_b", "1.0.0", false, &[]),
+        ],
+    );
+    fs::write(
+        dir.path().join("analysis_options.yaml"),
+        "include: package:lints/recommended.yaml\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("packages/svc_b/analysis_options.yaml"),
+        "include: package:flutter_lints/flutter.yaml\n",
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--analysis-options"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "LINTS svc_b includes package:flutter_lints/flutter.yaml",
+        ))
+        .stdout(predicate::str::contains("svc_a").not());
+
+    fs::write(
+        dir.path().join("packages/svc_b/analysis_options.yaml"),
+        "include: ../../analysis_options.yaml\n",
+    )
+    .unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--analysis-options"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No health issues found"));
+
+    // Including the baseline doesn't excuse turning its rules off
+    fs::write(
+        dir.path().join("packages/svc_b/analysis_options.yaml"),
+        "include: ../../analysis_options.yaml\nlinter:\n  rules:\n    avoid_print: false\n",
+    )
+    .unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--analysis-options"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "LINTS svc_b overrides the workspace lints:",
+        ))
+        .stdout(predicate::str::contains(
+            "linter.rules.avoid_print: false (workspace: unset)",
+        ));
+}
+
+// ---------------------------------------------------------------------------
+// Scope filter integration test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_list_with_scope_filter() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "scope_test",
+        &[
+            ("auth", "1.0.0", false, &[]),
+            ("billing", "1.0.0", false, &[]),
+            ("core", "1.0.0", false, &[]),
+        ],
+    );
+
+    // Scope to only "auth"
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--scope", "auth", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("auth"))
+        .stdout(predicate::str::contains("billing").not())
+        .stdout(predicate::str::contains("core").not());
+}
+
+// ---------------------------------------------------------------------------
+// Clean command tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_clean_dart_packages() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "clean_test",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+
+    // Create build/ directories to be cleaned
+    let build_a = dir.path().join("packages/pkg_a/build");
+    let build_b = dir.path().join("packages/pkg_b/build");
+    fs::create_dir_all(&build_a).unwrap();
+    fs::create_dir_all(&build_b).unwrap();
+    fs::write(build_a.join("output.js"), "// compiled").unwrap();
+
+    assert!(build_a.exists());
+    assert!(build_b.exists());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("CLEANED"));
+
+    // build/ directories should be removed for pure Dart packages
+    assert!(!build_a.exists(), "pkg_a/build should be removed");
+    assert!(!build_b.exists(), "pkg_b/build should be removed");
+}
+
+#[test]
+fn test_clean_deep() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "deep_clean_test",
+        &[("mylib", "1.0.0", false, &[])],
+    );
+
+    let pkg_dir = dir.path().join("packages/mylib");
+
+    // Create artifacts that deep clean should remove
+    let dart_tool = pkg_dir.join(".dart_tool");
+    let build_dir = pkg_dir.join("build");
+    let lock_file = pkg_dir.join("pubspec.lock");
+    fs::create_dir_all(&dart_tool).unwrap();
+    fs::create_dir_all(&build_dir).unwrap();
+    fs::write(&lock_file, "# lock file").unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--deep", "--quiet"])
+        .assert()
+        .success();
+
+    assert!(
+        !dart_tool.exists(),
+        ".dart_tool should be removed by deep clean"
+    );
+    assert!(!build_dir.exists(), "build should be removed by deep clean");
+    assert!(
+        !lock_file.exists(),
+        "pubspec.lock should be removed by deep clean"
+    );
+}
+
+#[test]
+fn test_clean_dry_run_lists_paths_and_keeps_them() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "dry_clean_test",
+        &[
+            ("mylib", "1.0.0", false, &[]),
+            ("other", "1.0.0", false, &[]),
+        ],
+    );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: dry_clean_test\npackages:\n  - packages/*\ncommand:\n  clean:\n    hooks:\n      pre: echo pre-hook dry=$MELOS_CLEAN_DRY_RUN\n",
+    )
+    .unwrap();
+
+    let pkg_dir = dir.path().canonicalize().unwrap().join("packages/mylib");
+    let dart_tool = pkg_dir.join(".dart_tool");
+    let lock_file = pkg_dir.join("pubspec.lock");
+    fs::create_dir_all(&dart_tool).unwrap();
+    fs::write(&lock_file, "# lock file").unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--deep", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pre-hook dry=true"))
+        .stdout(predicate::str::contains(format!(
+            "      {}\n      {}\n",
+            dart_tool.display(),
+            lock_file.display()
+        )))
+        .stdout(predicate::str::contains("nothing to remove"))
+        .stdout(predicate::str::contains("2 path(s) would be removed"));
+
+    assert!(dart_tool.exists(), "dry run must not remove .dart_tool");
+    assert!(lock_file.exists(), "dry run must not remove pubspec.lock");
+}
+
+// ---------------------------------------------------------------------------
+// Exec with scope filter test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_exec_with_scope_filter() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exec_scope_test",
+        &[
+            ("alpha", "1.0.0", false, &[]),
+            ("beta", "1.0.0", false, &[]),
+        ],
+    );
+
+    // Only execute in "alpha" package
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args([
+            "exec", "--scope", "alpha", "--quiet", "--", "echo", "FOUND_IT",
+        ])
+        .output()
+        .expect("command should run");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("FOUND_IT"), "Should see echo output");
+    // Should only run in alpha, not beta
+    assert!(stdout.contains("alpha"), "Should mention alpha package");
+}
+
+// ---------------------------------------------------------------------------
+// List with ignore filter test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_list_with_ignore_filter() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ignore_test",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("internal", "1.0.0", false, &[]),
+            ("utils", "1.0.0", false, &[]),
+        ],
+    );
+
+    // Ignore "internal" package
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--ignore", "internal", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("core"))
+        .stdout(predicate::str::contains("utils"))
+        .stdout(predicate::str::contains("internal").not());
+}
+
+// ---------------------------------------------------------------------------
+// List with no-private filter test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_list_with_no_private_filter() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "private_test",
+        &[("public_pkg", "1.0.0", false, &[])],
+    );
+
+    // Create a private package manually (publish_to: none)
+    let private_dir = dir.path().join("packages/private_pkg");
+    fs::create_dir_all(&private_dir).unwrap();
+    fs::write(
+        private_dir.join("pubspec.yaml"),
+        "name: private_pkg\nversion: 1.0.0\npublish_to: none\n",
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--no-private", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("public_pkg"))
+        .stdout(predicate::str::contains("private_pkg").not());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--private-only", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("private_pkg"))
+        .stdout(predicate::str::contains("public_pkg").not());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--no-private", "--private-only"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_list_with_sdk_type_filters() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "sdk_filter_test",
+        &[
+            ("mobile_app", "1.0.0", true, &[]),
+            ("core", "1.0.0", false, &[]),
+        ],
+    );
+    let list = |flag: &str| {
+        melos_cmd()
+            .current_dir(dir.path())
+            .args(["list", flag, "--quiet"])
+            .assert()
+    };
+
+    list("--dart")
+        .success()
+        .stdout(predicate::str::contains("core"))
+        .stdout(predicate::str::contains("mobile_app").not());
+    list("--flutter")
+        .success()
+        .stdout(predicate::str::contains("mobile_app"))
+        .stdout(predicate::str::contains("core").not());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list", "--flutter", "--dart"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+// ---------------------------------------------------------------------------
+// Init with apps directory test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_init_7x_with_apps() {
+    let dir = TempDir::new().unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["init", "apps_ws", "-d", "."])
+        .write_stdin("y\n") // accept apps directory
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created:"));
+
+    // Should have both packages/ and apps/ dirs
+    assert!(dir.path().join("packages").is_dir());
+    assert!(dir.path().join("apps").is_dir());
+}
+
+// ---------------------------------------------------------------------------
+// Bootstrap dry-run test (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_bootstrap_dry_run() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "boot_dry",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["bootstrap", "--dry-run", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("core"))
+        .stdout(predicate::str::contains("app"))
+        .stdout(predicate::str::contains("DRY RUN"));
+
+    // No pubspec_overrides.yaml should have been generated
+    assert!(
+        !dir.path()
+            .join("packages/core/pubspec_overrides.yaml")
+            .exists(),
+        "dry-run should not generate pubspec_overrides.yaml"
+    );
+    assert!(
+        !dir.path()
+            .join("packages/app/pubspec_overrides.yaml")
+            .exists(),
+        "dry-run should not generate pubspec_overrides.yaml"
+    );
+}
+
+#[test]
+fn test_bootstrap_validate_only() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "boot_validate",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+    let overrides = dir.path().join("packages/app/pubspec_overrides.yaml");
+    let validate = || {
+        melos_cmd()
+            .current_dir(dir.path())
+            .args(["bootstrap", "--validate-only"])
+            .assert()
+    };
+
+    validate()
+        .failure()
+        .stdout(predicate::str::contains(
+            "app: pubspec_overrides.yaml missing",
+        ))
+        .stdout(predicate::str::contains("+    path: ../core"))
+        .stderr(predicate::str::contains(
+            "1 pubspec_overrides.yaml file(s) out of date",
+        ));
+    assert!(!overrides.exists(), "--validate-only must not write files");
+
+    fs::write(
+        &overrides,
+        "# Generated by melos-rs. Do not edit.\ndependency_overrides:\n  core:\n    path: ../core\n",
+    )
+    .unwrap();
+    validate()
+        .success()
+        .stdout(predicate::str::contains("up to date"));
+
+    fs::write(
+        &overrides,
+        "# Generated by melos-rs. Do not edit.\ndependency_overrides:\n  core:\n    path: ../old_core\n",
+    )
+    .unwrap();
+    validate()
+        .failure()
+        .stdout(predicate::str::contains(
+            "app: pubspec_overrides.yaml out of date",
+        ))
+        .stdout(predicate::str::contains("-    path: ../old_core"))
+        .stdout(predicate::str::contains("+    path: ../core"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bootstrap_skip_if_unchanged() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "boot_skip",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+    // Stand-in for `dart` that records which packages ran `pub get`
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("pub_get.log");
+    fs::write(
+        bin.join("dart"),
+        format!("#!/bin/sh\nbasename \"$PWD\" >> {}\n", log.display()),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    let bootstrap = |args: &[&str]| {
+        fs::write(&log, "").unwrap();
+        melos_cmd()
+            .current_dir(dir.path())
+            .env("PATH", &path)
+            .arg("bootstrap")
+            .args(args)
+            .assert()
+            .success();
+        let mut ran: Vec<String> = fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        ran.sort();
+        ran
+    };
+
+    assert_eq!(bootstrap(&[]), vec!["app", "core"]);
+    assert!(
+        dir.path()
+            .join("packages/core/.dart_tool/melos_bootstrap_hash")
+            .exists()
+    );
+    assert!(bootstrap(&["--skip-if-unchanged"]).is_empty());
+
+    fs::write(
+        dir.path().join("packages/core/pubspec.yaml"),
+        "name: core\nversion: 1.1.0\n",
+    )
+    .unwrap();
+    assert_eq!(bootstrap(&["--skip-if-unchanged"]), vec!["core"]);
+
+    assert_eq!(
+        bootstrap(&["--skip-if-unchanged", "--force"]),
+        vec!["app", "core"]
+    );
+}
+
+#[test]
+fn test_cache_info_and_clean_only_touch_melos_files() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "cache_ws", &[("core", "1.0.0", false, &[])]);
+    let root_tool = dir.path().join(".dart_tool");
+    let pkg_tool = dir.path().join("packages/core/.dart_tool");
+    fs::create_dir_all(&root_tool).unwrap();
+    fs::create_dir_all(&pkg_tool).unwrap();
+    fs::write(root_tool.join("melos_analyze_cache.json"), "{}").unwrap();
+    fs::write(pkg_tool.join("melos_bootstrap_hash"), "0123456789abcdef").unwrap();
+    fs::write(pkg_tool.join("package_config.json"), "{}").unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["cache", "info"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".dart_tool/melos_analyze_cache.json",
+        ))
+        .stdout(predicate::str::contains(
+            "packages/core/.dart_tool/melos_bootstrap_hash",
+        ))
+        .stdout(predicate::str::contains("16 B"))
+        .stdout(predicate::str::contains("UTC"))
+        .stdout(predicate::str::contains("2 cache file(s), 18 B total."));
+    assert!(pkg_tool.join("melos_bootstrap_hash").exists());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["cache", "clean"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed 2 cache file(s), freeing 18 B.",
+        ));
+    assert!(!root_tool.join("melos_analyze_cache.json").exists());
+    assert!(!pkg_tool.join("melos_bootstrap_hash").exists());
+    assert!(pkg_tool.join("package_config.json").exists());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["cache", "clean"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No melos-rs cache files found."));
+}
+// ---------------------------------------------------------------------------
+// Clean dry-run test (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_clean_dry_run() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "clean_dry",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+
+    // Create build artifacts that should NOT be removed in dry-run
+    let build_a = dir.path().join("packages/pkg_a/build");
+    let build_b = dir.path().join("packages/pkg_b/build");
+    fs::create_dir_all(&build_a).unwrap();
+    fs::create_dir_all(&build_b).unwrap();
+    fs::write(build_a.join("output.js"), "// compiled").unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--dry-run", "--quiet"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DRY RUN"));
+
+    // Build artifacts must still exist (nothing was deleted)
+    assert!(build_a.exists(), "dry-run should not remove pkg_a/build");
+    assert!(build_b.exists(), "dry-run should not remove pkg_b/build");
+}
+
+// ---------------------------------------------------------------------------
+// Version dry-run test (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_version_dry_run() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_dry",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["version", "--dry-run", "--all", "--quiet", "patch"])
+        .output()
+        .expect("command should run");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Version changes:"),
+        "should show the version plan"
+    );
+    assert!(stdout.contains("core"), "should mention core package");
+    assert!(stdout.contains("app"), "should mention app package");
+    assert!(
+        stdout.contains("DRY RUN"),
+        "should indicate this is a dry run"
+    );
+
+    // Pubspec versions must remain unchanged on disk
+    let core_pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
+    assert!(
+        core_pubspec.contains("version: 1.0.0"),
+        "dry-run should not modify core version"
+    );
+    let app_pubspec = fs::read_to_string(dir.path().join("packages/app/pubspec.yaml")).unwrap();
+    assert!(
+        app_pubspec.contains("version: 2.0.0"),
+        "dry-run should not modify app version"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_version_package_hooks_run_before_commit() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_hooks",
+        &[("core", "1.0.0", false, &[]), ("util", "0.1.0", false, &[])],
+    );
+    let melos_yaml = dir.path().join("melos.yaml");
+    let mut config = fs::read_to_string(&melos_yaml).unwrap();
+    config.push_str(
+        "command:\n  version:\n    hooks:\n      prePackage: echo \"const v = '$MELOS_NEW_VERSION';\" > version.dart\n",
+    );
+    fs::write(&melos_yaml, &config).unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    let version_cmd = || {
+        melos_cmd()
+            .current_dir(dir.path())
+            .envs(GIT_IDENTITY)
+            .args([
+                "version",
+                "--all",
+                "--yes",
+                "--no-git-push",
+                "--no-git-tag-version",
+                "minor",
+            ])
+            .output()
+            .unwrap()
+    };
+    let commit_count = || {
+        git(dir.path(), &["rev-list", "--count", "HEAD"])
+            .trim()
+            .to_string()
+    };
+
+    let output = version_cmd();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("packages/core/version.dart")).unwrap(),
+        "const v = '1.1.0';\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("packages/util/version.dart")).unwrap(),
+        "const v = '0.2.0';\n"
+    );
+    // The generated files are part of the release commit
+    assert_eq!(commit_count(), "2");
+    assert!(git(dir.path(), &["status", "--porcelain"]).is_empty());
+
+    // A failing hook aborts before the commit
+    fs::write(
+        &melos_yaml,
+        config.replace("prePackage: echo", "postPackage: exit 3 && echo"),
+    )
+    .unwrap();
+    git(dir.path(), &["commit", "-q", "-am", "chore: failing hook"]);
+    let output = version_cmd();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("postPackage hook failed in core"),
+        "{stderr}"
+    );
+    assert_eq!(commit_count(), "3");
+}
+
+#[test]
+fn test_version_dry_run_json_plan() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_plan",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    git(dir.path(), &["tag", "core-v1.0.0"]);
+    fs::write(dir.path().join("packages/core/lib.dart"), "void api() {}\n").unwrap();
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "feat(core): add api"]);
+    let pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["version", "--dry-run", "--json", "--conventional-commits"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let plan: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be only the JSON plan");
+    let packages = plan["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 1);
+    let core = &packages[0];
+    assert_eq!(core["package"], "core");
+    assert_eq!(core["currentVersion"], "1.0.0");
+    assert_eq!(core["newVersion"], "1.1.0");
+    assert_eq!(core["bump"], "minor");
+    assert_eq!(core["reason"], "conventionalCommits");
+    assert_eq!(core["commits"][0]["description"], "add api");
+    assert_eq!(core["changelog"], true);
+    assert_eq!(core["tag"], "core-v1.1.0");
+
+    // Nothing was written
+    assert_eq!(
+        fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap(),
+        pubspec
+    );
+    assert!(!dir.path().join("packages/core/CHANGELOG.md").exists());
+}
+
+#[test]
+fn test_version_changelog_only_keeps_versions() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_changelog_only",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("app", "2.0.0", false, &["core"]),
+        ],
+    );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: ver_changelog_only\npackages:\n  - packages/*\ncommand:\n  version:\n    changelogConfig:\n      excludeTypes: [docs]\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("packages/core/CHANGELOG.md"),
+        "# Changelog\n\n## 1.0.0\n\n- Initial release\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    git(dir.path(), &["tag", "core-v1.0.0"]);
+    fs::write(dir.path().join("packages/core/lib.dart"), "void api() {}\n").unwrap();
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "feat(core): add api"]);
+    fs::write(dir.path().join("packages/core/README.md"), "docs\n").unwrap();
+    git(dir.path(), &["add", "-A"]);
+    git(
+        dir.path(),
+        &["commit", "-q", "-m", "docs(core): describe api"],
+    );
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+    let changelog_only = || {
+        let output = melos_cmd()
+            .current_dir(dir.path())
+            .args(["version", "--changelog-only"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        assert!(output.status.success(), "{stdout}");
+    };
+    changelog_only();
+    // Running again rewrites the entry instead of adding another
+    changelog_only();
+
+    let pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("version: 1.0.0"), "{pubspec}");
+    // Unreleased commits go above the released section, which is untouched
+    let changelog = fs::read_to_string(dir.path().join("packages/core/CHANGELOG.md")).unwrap();
+    assert_eq!(changelog.matches("## Unreleased").count(), 1, "{changelog}");
+    assert!(
+        changelog.ends_with("## 1.0.0\n\n- Initial release\n"),
+        "{changelog}"
+    );
+    let unreleased = &changelog[..changelog.find("## 1.0.0").unwrap()];
+    assert!(unreleased.contains("add api"), "{changelog}");
+    assert!(!changelog.contains("describe api"), "{changelog}");
+    assert!(!dir.path().join("packages/app/CHANGELOG.md").exists());
+    assert!(dir.path().join("CHANGELOG.md").exists());
+    // No release commit or tag
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(dir.path(), &["tag"]).trim(), "core-v1.0.0");
+}
+
+#[test]
+fn test_version_fails_before_changes_when_tag_exists() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_tag_exists",
+        &[("core", "1.0.0", false, &[]), ("app", "2.0.0", false, &[])],
+    );
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    // Left behind by an earlier, partially failed run
+    git(dir.path(), &["tag", "core-v1.1.0"]);
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+    let version = |extra: &[&str]| {
+        let mut cmd = melos_cmd();
+        cmd.current_dir(dir.path())
+            .envs(GIT_IDENTITY)
+            .args(["version", "--all", "--yes", "--no-git-push"])
+            .args(extra)
+            .arg("minor");
+        cmd.output().unwrap()
+    };
+
+    let output = version(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("core: tag core-v1.1.0 already exists"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("app:"), "{stderr}");
+    assert!(stderr.contains("--overwrite-tags"), "{stderr}");
+    // Nothing was applied
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+    let pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("version: 1.0.0"), "{pubspec}");
+
+    let output = version(&["--overwrite-tags"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // The tag now points at the release commit
+    assert_eq!(
+        git(dir.path(), &["rev-parse", "core-v1.1.0^{commit}"]),
+        git(dir.path(), &["rev-parse", "HEAD"])
+    );
+    assert_eq!(git(dir.path(), &["tag"]).lines().count(), 2);
+}
+
+#[test]
+fn test_version_tag_check_covers_bumped_dependents() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_dependent_tag",
+        &[("core", "1.0.0", false, &[]), ("app", "2.0.0", false, &[])],
+    );
+    fs::write(
+        dir.path().join("packages/app/pubspec.yaml"),
+        "name: app\nversion: 2.0.0\n\ndependencies:\n  core: ^1.0.0\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    // `app` gets a patch bump once core 2.0.0 falls outside its constraint
+    git(dir.path(), &["tag", "app-v2.0.1"]);
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["version", "-Vcore:major", "--yes", "--no-git-push"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("app: tag app-v2.0.1 already exists"),
+        "{stderr}"
+    );
+    // Nothing was applied, not even the constraint rewrite
+    assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+    let pubspec = fs::read_to_string(dir.path().join("packages/app/pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("core: ^1.0.0"), "{pubspec}");
+    let pubspec = fs::read_to_string(dir.path().join("packages/core/pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("version: 1.0.0"), "{pubspec}");
+}
+
+#[test]
+fn test_version_overwrite_tags_force_pushes_only_replaced_tags() {
+    let dir = TempDir::new().unwrap();
+    let remote = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_push_tags",
+        &[("core", "1.0.0", false, &[]), ("app", "2.0.0", false, &[])],
+    );
+    git(remote.path(), &["init", "-q", "--bare"]);
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+    git(
+        dir.path(),
+        &["remote", "add", "origin", remote.path().to_str().unwrap()],
+    );
+    // A stale release tag on the remote, and an unrelated remote tag that the
+    // local copy disagrees with
+    git(dir.path(), &["tag", "core-v1.1.0"]);
+    git(dir.path(), &["tag", "legacy"]);
+    git(
+        dir.path(),
+        &["push", "-q", "-u", "origin", "HEAD", "--tags"],
+    );
+    let remote_legacy = git(remote.path(), &["rev-parse", "legacy^{commit}"]);
+    git(
+        dir.path(),
+        &["commit", "-q", "--allow-empty", "-m", "chore: wip"],
+    );
+    git(dir.path(), &["tag", "-f", "legacy"]);
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .envs(GIT_IDENTITY)
+        .args(["version", "--all", "--yes", "--overwrite-tags", "minor"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Replaced tag core-v1.1.0"));
+
+    let head = git(dir.path(), &["rev-parse", "HEAD"]);
+    for tag in ["core-v1.1.0^{commit}", "app-v2.1.0^{commit}"] {
+        assert_eq!(git(remote.path(), &["rev-parse", tag]), head, "{tag}");
+    }
+    // Tags outside the release are left alone on the remote
+    assert_eq!(
+        git(remote.path(), &["rev-parse", "legacy^{commit}"]),
+        remote_legacy
+    );
+}
+
+#[test]
+fn test_version_commit_message_placeholders() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "ver_message",
+        &[("core", "1.0.0", false, &[]), ("app", "2.0.0", false, &[])],
+    );
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "chore: init"]);
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .envs(GIT_IDENTITY)
+        .args([
+            "version",
+            "--all",
+            "--yes",
+            "--no-git-push",
+            "--no-git-tag-version",
+            "--message",
+            "release {count}: {package_list} [{new_version}] {bogus} {bogus}",
+            "minor",
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert_eq!(stderr.matches("Unknown placeholder {bogus}").count(), 1);
+
+    let subject = git(dir.path(), &["log", "-1", "--format=%s"]);
+    assert_eq!(
+        subject.trim(),
+        "release 2: app, core [app@2.1.0, core@1.1.0] {bogus} {bogus}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Health --json with no issues (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_health_json_no_issues() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "health_json_ok",
+        &[
+            ("svc_a", "1.0.0", false, &[]),
+            ("svc_b", "2.0.0", false, &[]),
+        ],
+    );
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--json", "--version-drift", "--quiet"])
+        .output()
+        .expect("command should run");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("Invalid JSON output: {e}\nOutput: {stdout}"));
+
+    assert_eq!(parsed["schema_version"], 1);
+    assert_eq!(
+        parsed["total_issues"], 0,
+        "should have zero issues for consistent workspace"
+    );
+    let drift = parsed["version_drift"]
+        .as_array()
+        .expect("version_drift should be an array");
+    assert!(drift.is_empty(), "version_drift should be empty");
+}
+
+// ---------------------------------------------------------------------------
+// Health --json with version drift (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_health_json_with_drift() {
+    let dir = TempDir::new().unwrap();
+
+    // Write melos.yaml
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: drift_test\n\npackages:\n  - packages/*\n\nscripts: {}\n",
+    )
+    .unwrap();
+
+    // Create two packages that depend on the same external dep at different versions
+    let pkg_a_dir = dir.path().join("packages/pkg_a");
+    let pkg_b_dir = dir.path().join("packages/pkg_b");
+    fs::create_dir_all(&pkg_a_dir).unwrap();
+    fs::create_dir_all(&pkg_b_dir).unwrap();
+
+    fs::write(
+        pkg_a_dir.join("pubspec.yaml"),
+        "name: pkg_a\nversion: 1.0.0\ndependencies:\n  http: ^1.0.0\n",
+    )
+    .unwrap();
+    fs::write(
+        pkg_b_dir.join("pubspec.yaml"),
+        "name: pkg_b\nversion: 1.0.0\ndependencies:\n  http: ^2.0.0\n",
+    )
+    .unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["health", "--json", "--version-drift", "--quiet"])
+        .output()
+        .expect("command should run");
+
+    // Should fail because of drift
+    assert!(
+        !output.status.success(),
+        "health should exit non-zero when drift is found"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("health issue(s) found"),
+        "stderr should mention health issues"
+    );
+
+    // JSON is on stdout
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("Invalid JSON output: {e}\nOutput: {stdout}"));
+
+    assert!(
+        parsed["total_issues"].as_u64().unwrap_or(0) > 0,
+        "should have at least one issue"
+    );
+
+    let drift = parsed["version_drift"]
+        .as_array()
+        .expect("version_drift should be an array");
+    assert!(!drift.is_empty(), "version_drift should have entries");
+
+    // Find the http drift entry
+    let http_drift = drift
+        .iter()
+        .find(|d| d["dependency"] == "http")
+        .expect("should have a drift entry for 'http'");
+    let constraints = http_drift["constraints"]
+        .as_array()
+        .expect("constraints should be an array");
+    assert_eq!(
+        constraints.len(),
+        2,
+        "http should have 2 different constraints"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Exec success summary (Batch 29)
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_exec_success_summary() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exec_summary",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+        ],
+    );
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["exec", "--quiet", "--", "echo", "hi"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All 2 package(s) passed exec."));
+}
+
+// ---------------------------------------------------------------------------
+// Run matrix script test
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_run_interactive_picker_filters_by_fuzzy_query() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "picker_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: picker_ws\npackages:\n  - packages/*\nscripts:\n  build_runner:\n    run: echo ran-build-runner\n    description: Generate code\n    groups: [codegen]\n  analyze: echo ran-analyze\n  bump_internal:\n    run: echo ran-bump\n    private: true\n",
+    )
+    .unwrap();
+
+    // `b` matches build_runner and the hidden private script; only the
+    // former is listed, but a lone match still needs confirming
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run"])
+        .write_stdin("b\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "build_runner — Generate code [codegen]",
+        ))
+        .stdout(predicate::str::contains("bump_internal").not())
+        .stdout(predicate::str::contains("ran-build-runner").not());
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run"])
+        .write_stdin("b\n1\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ran-build-runner"));
+
+    // A query matching several scripts narrows the list before a pick
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run"])
+        .write_stdin("n\n1\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ran-analyze"));
+}
+
+#[test]
+fn test_run_leaves_shell_variables_to_the_shell() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "shell_vars_ws",
+        &[("pkg_a", "1.0.0", false, &[])],
+    );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: shell_vars_ws\npackages:\n  - packages/*\nscripts:\n  loop: 'for f in one two; do echo item-$f; done; x=1; echo x-is-${x}'\n",
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "loop"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("item-one"))
+        .stdout(predicate::str::contains("item-two"))
+        .stdout(predicate::str::contains("x-is-1"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_passes_process_env_values_through_unchanged() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "env_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: env_ws\npackages:\n  - packages/*\nscripts:\n  show: 'echo \"title=$TITLE secret=${SECRET}\"'\n",
+    )
+    .unwrap();
+
+    // Process env values are resolved by the shell, not pasted into the command
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("TITLE", "$(echo INJECTED)")
+        .env("SECRET", "a$$b")
+        .args(["run", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "title=$(echo INJECTED) secret=a$$b",
+        ));
+}
+
+#[test]
+fn test_run_steps_continue_on_error_and_summary() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "steps_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: steps_ws\npackages:\n  - packages/*\nscripts:\n  lint: exit 3\n  ci:\n    steps:\n      - script: lint\n        continueOnError: true\n      - echo step-two\n  strict:\n    steps:\n      - exit 4\n      - echo never-runs\n  flaky: test -f marker || { touch marker; exit 1; }\n  retry:\n    steps:\n      - script: flaky\n        continueOnError: true\n      - flaky\n",
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "ci"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("step-two"))
+        .stdout(predicate::str::contains("FAILED (continued) lint"))
+        .stdout(predicate::str::contains("PASSED echo step-two"));
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "strict"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("FAILED exit 4"))
+        .stdout(predicate::str::contains("SKIPPED echo never-runs"))
+        .stdout(predicate::str::contains("> echo never-runs").not());
+
+    // A script that failed in a continued step can run again later
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "retry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FAILED (continued) flaky"))
+        .stdout(predicate::str::contains("PASSED flaky"))
+        .stderr(predicate::str::contains("Circular").not());
+}
+
+#[test]
+fn test_run_group_runs_scripts_in_name_order() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "group_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: group_ws\npackages:\n  - packages/*\nscripts:\n  c_test:\n    run: echo ran-c\n    groups: [ci]\n  a_lint:\n    run: echo ran-a\n    groups: [ci]\n  b_check:\n    run: exit 2\n    groups: [ci]\n  d_hidden:\n    run: echo ran-hidden\n    groups: [ci]\n    private: true\n  other:\n    run: echo ran-other\n",
+    )
+    .unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "--group", "ci"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ran-a"), "{stdout}");
+    assert!(!stdout.contains("ran-c") && !stdout.contains("ran-hidden"));
+    assert!(stdout.contains("PASSED a_lint"));
+    assert!(stdout.contains("FAILED b_check"));
+    assert!(stdout.contains("SKIPPED c_test"));
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "--group", "ci", "--no-fail-fast"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let a = stdout.find("ran-a").unwrap();
+    let c = stdout.find("ran-c").unwrap();
+    assert!(a < c, "scripts should run in name order:\n{stdout}");
+    assert!(stdout.contains("PASSED c_test"));
+    assert!(!stdout.contains("ran-other") && !stdout.contains("ran-hidden"));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 3 script(s) in group ci failed")
+    );
+}
+
+#[test]
+fn test_run_skips_script_for_other_os() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "os_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    let other = if cfg!(windows) { "linux" } else { "windows" };
+    fs::write(
+        dir.path().join("melos.yaml"),
+        format!(
+            "name: os_ws\npackages:\n  - packages/*\nscripts:\n  elsewhere:\n    run: echo should-not-run\n    os: [{other}]\n  here:\n    run: echo ran-here\n    os: [{}]\n",
+            std::env::consts::OS
+        ),
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "elsewhere"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipping script 'elsewhere'"))
+        .stdout(predicate::str::contains("should-not-run").not());
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "here"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ran-here"));
+}
+
+#[test]
+fn test_run_skipped_os_script_can_be_referenced_twice() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "os_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    let other = if cfg!(windows) { "linux" } else { "windows" };
+    fs::write(
+        dir.path().join("melos.yaml"),
+        format!(
+            "name: os_ws\npackages:\n  - packages/*\nscripts:\n  podinstall:\n    run: echo should-not-run\n    os: [{other}]\n  ios:\n    steps:\n      - podinstall\n  all:\n    steps:\n      - podinstall\n      - ios\n"
+        ),
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipping script 'podinstall'").count(2))
+        .stdout(predicate::str::contains("should-not-run").not());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_skips_script_when_predicate_fails() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "when_ws", &[("pkg_a", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: when_ws\npackages:\n  - packages/*\nscripts:\n  gated:\n    run: echo ran-gated\n    when: test -f \"$MELOS_ROOT_PATH/enable.flag\" && [ \"$MODE\" = ci ]\n    env:\n      MODE: ci\n",
+    )
+    .unwrap();
+    let run = || {
+        melos_cmd()
+            .current_dir(dir.path())
+            .args(["run", "gated"])
+            .assert()
+            .success()
+    };
+
+    run()
+        .stdout(predicate::str::contains("Skipping script 'gated'"))
+        .stdout(predicate::str::contains("exited with code 1"))
+        .stdout(predicate::str::contains("ran-gated").not());
+
+    fs::write(dir.path().join("enable.flag"), "").unwrap();
+    run()
+        .stdout(predicate::str::contains("ran-gated"))
+        .stdout(predicate::str::contains("Skipping").not());
+}
+
+#[test]
+fn test_run_matrix_script_runs_each_entry_in_its_subset() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "matrix_test",
+        &[("app", "1.0.0", true, &[]), ("core", "1.0.0", false, &[])],
+    );
+    fs::write(
+        dir.path().join("melos.yaml"),
+        r#"name: matrix_test
+
+packages:
+  - packages/*
+
+scripts:
+  gen:
+    matrix:
+      - packageFilters:
+          flutter: false
+        run: echo DART_GEN
+      - packageFilters:
+          flutter: true
+        run: echo FLUTTER_GEN
+"#,
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "gen", "--no-select"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"\[core\]\s+DART_GEN").unwrap())
+        .stdout(predicate::str::is_match(r"\[app\]\s+FLUTTER_GEN").unwrap())
+        .stdout(
+            predicate::str::is_match(r"\[app\]\s+DART_GEN")
+                .unwrap()
+                .not(),
+        )
+        .stdout(
+            predicate::str::is_match(r"\[core\]\s+FLUTTER_GEN")
+                .unwrap()
+                .not(),
+        );
+}
+
+#[test]
+fn test_run_matrix_script_rejects_invalid_matrix() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "matrix_test", &[("core", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        r#"name: matrix_test
+
+packages:
+  - packages/*
+
+scripts:
+  empty:
+    matrix: []
+  blank:
+    matrix:
+      - run: echo OK
+      - run: ""
+"#,
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "empty", "--no-select"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Script 'empty': `matrix` has no entries",
+        ));
+
+    // Nothing runs when a later entry is invalid
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "blank", "--no-select"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("OK").not())
+        .stderr(predicate::str::contains(
+            "`matrix` entry 2 has an empty `run` command",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_matrix_script_respects_concurrency_flag() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "matrix_test",
+        &[
+            ("pkg_a", "1.0.0", false, &[]),
+            ("pkg_b", "1.0.0", false, &[]),
+            ("pkg_c", "1.0.0", false, &[]),
+        ],
+    );
+    // Fails if two packages hold the shared directory at once
+    fs::write(
+        dir.path().join("melos.yaml"),
+        r#"name: matrix_test
+
+packages:
+  - packages/*
+
+scripts:
+  serial:
+    matrix:
+      - run: mkdir "$MELOS_ROOT_PATH/busy" && sleep 0.3 && rmdir "$MELOS_ROOT_PATH/busy"
+"#,
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "serial", "--no-select", "-c", "1"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_run_exec_script_combines_config_and_cli_exists_filters() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "exists_test",
+        &[
+            ("both", "1.0.0", false, &[]),
+            ("dir_only", "1.0.0", false, &[]),
+            ("file_only", "1.0.0", false, &[]),
+        ],
+    );
+    for name in ["both", "dir_only"] {
+        fs::create_dir_all(dir.path().join("packages").join(name).join("lib")).unwrap();
+    }
+    for name in ["both", "file_only"] {
+        fs::write(dir.path().join("packages").join(name).join("marker"), "").unwrap();
+    }
+    fs::write(
+        dir.path().join("melos.yaml"),
+        r#"name: exists_test
+
+packages:
+  - packages/*
+
+scripts:
+  check:
+    exec: echo "IN-$MELOS_PACKAGE_NAME"
+    packageFilters:
+      dirExists: lib
+"#,
+    )
+    .unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["run", "check", "--no-select", "--file-exists", "marker"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("IN-both"))
+        .stdout(predicate::str::contains("IN-dir_only").not())
+        .stdout(predicate::str::contains("IN-file_only").not());
+}
+
+#[test]
+fn test_workspace_lock_blocks_mutating_commands() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "test_ws", &[("core", "1.0.0", false, &[])]);
+
+    // Hold the lock as a concurrent melos-rs run would
+    fs::create_dir_all(dir.path().join(".dart_tool")).unwrap();
+    let held = fs::File::create(dir.path().join(".dart_tool/melos.lock")).unwrap();
+    held.try_lock().unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Another melos-rs process"))
+        .stderr(predicate::str::contains("--no-lock"));
+
+    // Read-only commands and --no-lock do not take the lock
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["list"])
+        .assert()
+        .success();
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--dry-run", "--no-lock"])
+        .assert()
+        .success();
+
+    drop(held);
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--dry-run"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_workspace_lock_blocks_script_overridden_commands() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "lock_ws", &[("core", "1.0.0", false, &[])]);
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: lock_ws\npackages:\n  - packages/*\nscripts:\n  bootstrap: echo overridden-bootstrap\n",
+    )
+    .unwrap();
+
+    fs::create_dir_all(dir.path().join(".dart_tool")).unwrap();
+    let held = fs::File::create(dir.path().join(".dart_tool/melos.lock")).unwrap();
+    held.try_lock().unwrap();
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["bootstrap"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("overridden-bootstrap").not())
+        .stderr(predicate::str::contains("Another melos-rs process"));
+
+    drop(held);
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["bootstrap"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("overridden-bootstrap"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ctrl_c_after_a_completed_run_ends_the_process() {
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::time::{Duration, Instant};
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "sigint_ws", &[("core", "1.0.0", false, &[])]);
+    // `pkgs` runs through the package runner; `wait` then blocks afterwards
+    fs::write(
+        dir.path().join("melos.yaml"),
+        "name: sigint_ws\npackages:\n  - packages/*\nscripts:\n  pkgs:\n    exec: echo in-package\n  wait: touch started && sleep 20\n  all:\n    steps:\n      - pkgs\n      - wait\n",
+    )
+    .unwrap();
+
+    // Own process group, so the test can clean up the orphaned `sleep`
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_melos-rs"))
+        .current_dir(dir.path())
+        .args(["run", "all"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .process_group(0)
+        .spawn()
+        .unwrap();
+    let pgid = format!("-{}", child.id());
+
+    let started = dir.path().join("started");
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while !started.exists() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(started.exists(), "the wait step never started");
+
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let _ = std::process::Command::new("kill")
+        .args(["-KILL", "--", &pgid])
+        .status();
+    let _ = child.wait();
+
+    let status = status.expect("Ctrl-C after the run did not end the process");
+    assert_eq!(status.signal(), Some(2), "status: {status:?}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_run_nested_commands_under_the_parent_lock() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "test_ws", &[("core", "1.0.0", false, &[])]);
+    // The pre hook re-enters `clean` once; NESTED stops the recursion
+    fs::write(
+        dir.path().join("melos.yaml"),
+        format!(
+            "name: test_ws\npackages:\n  - packages/*\ncommand:\n  clean:\n    hooks:\n      \
+             pre: '[ -n \"$NESTED\" ] || NESTED=1 {} clean --dry-run'\n",
+            env!("CARGO_BIN_EXE_melos-rs")
+        ),
+    )
+    .unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["clean", "--dry-run"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(!stderr.contains("Another melos-rs process"));
+    // Both the outer and the nested run finished
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("SUCCESS").count(), 2, "stdout: {stdout}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_analyze_checkstyle_format() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "analyze_cs",
+        &[("app", "1.0.0", false, &[]), ("core", "1.0.0", false, &[])],
+    );
+    // Stand-in for `dart analyze --format=json`: one warning in `core`,
+    // fatal only when --fatal-warnings is passed
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    fs::write(
+        bin.join("dart"),
+        r#"#!/bin/sh
+if [ "$(basename "$PWD")" = core ]; then
+  echo '{"version":1,"diagnostics":[{"code":"unused_import","severity":"WARNING","location":{"file":"'"$PWD"'/lib/core.dart","range":{"start":{"line":1,"column":8}}},"problemMessage":"Unused import."}]}'
+  case "$*" in *--fatal-warnings*) exit 2 ;; esac
+else
+  echo '{"version":1,"diagnostics":[]}'
+fi
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args(["analyze", "--format", "checkstyle"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("<?xml"), "unexpected stdout: {stdout}");
+    assert!(stdout.contains(r#"<file name="packages/app"/>"#));
+    assert!(stdout.contains(r#"<file name="packages/core/lib/core.dart">"#));
+    assert!(stdout.contains(
+        r#"<error line="1" column="8" severity="warning" message="Unused import." source="dart.unused_import"/>"#
+    ));
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args(["analyze", "--format", "checkstyle", "--fatal-warnings"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("</checkstyle>"))
+        .stderr(predicate::str::contains("1 package(s) failed analysis"));
+}
+
+#[test]
+fn test_list_order_topo() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "order_ws",
+        &[
+            ("app", "1.0.0", false, &["ui", "core"]),
+            ("core", "1.0.0", false, &[]),
+            ("ui", "1.0.0", false, &["core"]),
+        ],
+    );
+
+    let list = |order: &str| {
+        let output = melos_cmd()
+            .current_dir(dir.path())
+            .args(["list", "--quiet", "--order", order])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(list("name"), vec!["app", "core", "ui"]);
+    assert_eq!(list("topo"), vec!["core", "ui", "app"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_format_check_lists_changed_files_per_package() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "format_check",
+        &[("app", "1.0.0", false, &[]), ("core", "1.0.0", false, &[])],
+    );
+    // Stand-in for `dart format` that reports a change in `app` only
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("format.log");
+    fs::write(
+        bin.join("dart"),
+        format!(
+            "#!/bin/sh\necho \"$@\" >> {}\n[ \"$(basename \"$PWD\")\" = core ] && exit 0\necho 'Changed lib/app.dart'\necho 'Formatted 2 files (1 changed) in 0.01 seconds.'\nexit 1\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args(["format", "--check"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "{stdout}");
+    let summary = stdout
+        .split("Files that need formatting:")
+        .nth(1)
+        .expect("summary missing");
+    assert!(summary.contains("  app\n    lib/app.dart"), "{summary}");
+    assert!(!summary.contains("core"), "{summary}");
+    let log = fs::read_to_string(&log).unwrap();
+    assert!(
+        log.contains("format --set-exit-if-changed --output=none ."),
+        "{log}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_analyze_skips_unchanged_packages() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "analyze_cache",
+        &[("app", "1.0.0", false, &[]), ("core", "1.0.0", false, &[])],
+    );
+    // Stand-in for `dart` that records which packages were analyzed
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("analyze.log");
+    fs::write(
+        bin.join("dart"),
+        format!(
+            "#!/bin/sh\n[ \"$1\" = --version ] && echo 'Dart SDK version: 3.5.0' && exit 0\nbasename \"$PWD\" >> {}\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    let analyze = |args: &[&str]| {
+        fs::write(&log, "").unwrap();
+        let output = melos_cmd()
+            .current_dir(dir.path())
+            .env("PATH", &path)
+            .arg("analyze")
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let mut ran: Vec<String> = fs::read_to_string(&log)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        ran.sort();
+        (ran, String::from_utf8(output.stdout).unwrap())
+    };
+
+    assert_eq!(analyze(&[]).0, vec!["app", "core"]);
+    assert!(
+        dir.path()
+            .join(".dart_tool/melos_analyze_cache.json")
+            .exists()
+    );
+
+    let (ran, stdout) = analyze(&[]);
+    assert!(ran.is_empty());
+    assert!(
+        stdout.contains("2 package(s) unchanged"),
+        "stdout: {stdout}"
+    );
+
+    fs::write(dir.path().join("packages/core/lib.dart"), "int x = 1;\n").unwrap();
+    assert_eq!(analyze(&[]).0, vec!["core"]);
+    assert_eq!(analyze(&["--no-cache"]).0, vec!["app", "core"]);
+}
+
+#[test]
+fn test_doctor_reports_missing_sdk() {
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(dir.path(), "doctor_ws", &[("core", "1.0.0", false, &[])]);
+
+    melos_cmd()
+        .current_dir(dir.path())
+        .args(["doctor", "--sdk-path", "/nonexistent/sdk"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✗ Dart SDK: `dart` not found"))
+        .stdout(predicate::str::contains("hint:"))
+        .stdout(predicate::str::contains("✓ Config"))
+        .stderr(predicate::str::contains("1 critical check(s) failed"));
+}
+
+#[test]
+fn test_doctor_without_workspace_fails_config_check() {
+    let dir = TempDir::new().unwrap();
+
+    let output = melos_cmd()
+        .current_dir(dir.path())
+        .args(["doctor", "--json", "--sdk-path", "/nonexistent/sdk"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let config = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "Config")
+        .unwrap();
+    assert_eq!(config["status"], "failed");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_publish_ordered_waits_for_dependency_on_registry() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "publish_ordered",
+        &[
+            ("app", "1.0.0", false, &["internal"]),
+            ("internal", "1.0.0", false, &["core"]),
+            ("core", "1.0.0", false, &[]),
+            ("util", "1.0.0", false, &[]),
+        ],
+    );
+    fs::write(
+        dir.path().join("packages/internal/pubspec.yaml"),
+        "name: internal\nversion: 1.0.0\npublish_to: none\n\ndependencies:\n  core:\n    path: ../core\n",
+    )
+    .unwrap();
+    // Stand-in for `dart pub publish` that records which packages ran
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("publish.log");
+    fs::write(
+        bin.join("dart"),
+        format!("#!/bin/sh\nbasename \"$PWD\" >> {}\n", log.display()),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    // `core` never shows up on the unreachable registry, so `app` (which
+    // reaches it through the private `internal`) is held back; the
+    // unrelated `util` is still published
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args([
+            "publish",
+            "--ordered",
+            "--dry-run=false",
+            "--yes",
+            "--registry",
+            "http://127.0.0.1:9",
+            "--poll-interval",
+            "0",
+            "--poll-timeout",
+            "0",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Waiting for core"))
+        .stdout(predicate::str::contains("(needed by app)"))
+        .stdout(predicate::str::contains("not publishing app."))
+        .stderr(predicate::str::contains(
+            "1 package(s) held back because a dependency did not publish (2 passed)",
+        ));
+    let mut published: Vec<_> = fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    published.sort();
+    assert_eq!(published, vec!["core", "util"]);
+
+    // Dry runs publish nothing, so they run in order without waiting
+    fs::write(&log, "").unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args(["publish", "--ordered", "--registry", "http://127.0.0.1:9"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Waiting for").not());
+    assert_eq!(fs::read_to_string(&log).unwrap(), "core\nutil\napp\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_publish_tag_publishes_channel_in_dependency_order() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "publish_channel",
+        &[
+            ("app", "2.0.0-beta.1", false, &["core"]),
+            ("core", "2.0.0-beta.1", false, &[]),
+        ],
+    );
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("publish.log");
+    fs::write(
+        bin.join("dart"),
+        format!(
+            "#!/bin/sh\necho \"$(basename \"$PWD\") $MELOS_PUBLISH_TAG\" >> {}\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    // The channel release of `core` never reaches the unreachable registry,
+    // so `app` is held back rather than published against a missing beta
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args([
+            "publish",
+            "--tag",
+            "beta",
+            "--dry-run=false",
+            "--yes",
+            "--registry",
+            "http://127.0.0.1:9",
+            "--poll-interval",
+            "0",
+            "--poll-timeout",
+            "0",
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("[beta]"))
+        .stdout(predicate::str::contains("Waiting for core"))
+        .stdout(predicate::str::contains("not publishing app."));
+    assert_eq!(fs::read_to_string(&log).unwrap(), "core beta\n");
+
+    // Dry runs go through the whole channel in dependency order
+    fs::write(&log, "").unwrap();
+    melos_cmd()
+        .current_dir(dir.path())
+        .env("PATH", &path)
+        .args([
+            "publish",
+            "--tag",
+            "beta",
+            "--registry",
+            "http://127.0.0.1:9",
+        ])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&log).unwrap(), "core beta\napp beta\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_publish_select_publishes_named_packages_without_prompt() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    create_fixture_workspace(
+        dir.path(),
+        "publish_select",
+        &[
+            ("core", "1.0.0", false, &[]),
+            ("util", "1.0.0", false, &[]),
+            ("extra", "1.0.0", false, &[]),
+        ],
+    );
+    fs::create_dir_all(dir.path().join("packages/internal")).unwrap();
+    fs::write(
+        dir.path().join("packages/internal/pubspec.yaml"),
+        "name: internal\nversion: 1.0.0\npublish_to: none\n",
+    )
+    .unwrap();
+    let bin = dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = dir.path().join("publish.log");
+    fs::write(
+        bin.join("dart"),
+        format!(
+            "#!/bin/sh\necho \"$(basename \"$PWD\") $*\" >> {}\n",
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("dart"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+    let publish = |args: &[&str]| {
+        melos_cmd()
+            .current_dir(dir.path())
+            .env("PATH", &path)
+            .arg("publish")
+            .args(args)
+            .assert()
+    };
+    let published = || {
+        let mut lines: Vec<String> = fs::read_to_string(&log)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+        lines.sort();
+        fs::write(&log, "").unwrap();
+        lines
+    };
+
+    // No prompt: stdin is closed, which would abort a confirmation
+    publish(&["--select", "util,core", "--dry-run=false"])
+        .success()
+        .stdout(predicate::str::contains("CONFIRM").not());
+    assert_eq!(
+        published(),
+        vec!["core pub publish --force", "util pub publish --force"]
+    );
+
+    // Dry run stays the default
+    publish(&["--select", "core", "--registry", "http://127.0.0.1:9"]).success();
+    assert_eq!(published(), vec!["core pub publish --dry-run"]);
+
+    publish(&["--select", "core,internal", "--dry-run=false"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "private package(s) that cannot be published: internal",
+        ));
+    publish(&["--select", "nope", "--dry-run=false"])
+        .failure()
+        .stderr(predicate::str::contains("unknown package(s): nope"));
+    assert!(published().is_empty());
 }
@@ -1,16 +1,19 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::events::Event;
 use crate::package::Package;
+use crate::package::graph::DependencyGraph;
 use crate::runner::ProcessRunner;
 use crate::workspace::Workspace;
 
-use super::PackageResults;
+use super::{PackageResults, fnv1a_hex};
 
 /// Options for the analyze command (clap-free).
 #[derive(Debug, Clone)]
@@ -19,6 +22,8 @@ pub struct AnalyzeOpts {
     pub fatal_warnings: bool,
     pub fatal_infos: bool,
     pub no_fatal: bool,
+    /// Run `dart analyze --format=json` so diagnostics can be parsed
+    pub json_output: bool,
 }
 
 /// Result of a `dart fix --dry-run` scan across packages.
@@ -37,6 +42,28 @@ pub struct DryRunFileEntry {
     pub fixes: Vec<(String, usize)>,
 }
 
+/// Summary line of `dart fix --dry-run` output for one package.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DryRunSummary {
+    /// Number of proposed fixes
+    pub fixes: usize,
+    /// Number of files the fixes touch
+    pub files: usize,
+}
+
+/// A single diagnostic reported by `dart analyze`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzerDiagnostic {
+    /// File path as reported by the analyzer (usually absolute)
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    /// Analyzer severity: `ERROR`, `WARNING` or `INFO`
+    pub severity: String,
+    pub code: String,
+    pub message: String,
+}
+
 /// A pair of diagnostic codes detected as conflicting.
 #[derive(Debug, PartialEq)]
 pub struct ConflictingPair {
@@ -58,6 +85,16 @@ pub static FIX_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^(\w+)\s+\S+\s+(\d+)\s+fix(?:es)?$").expect("valid regex")
 });
 
+/// Regex for the summary line of `dart fix --dry-run` output.
+///
+/// Matches `112 proposed fixes in 13 files.` and `1 proposed fix in 1 file.`
+///
+/// Captures: (1) fix count, (2) file count.
+pub static SUMMARY_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // safety: this regex is valid and tested
+    Regex::new(r"^(\d+)\s+proposed\s+fix(?:es)?\s+in\s+(\d+)\s+files?\.?$").expect("valid regex")
+});
+
 /// Build a `dart fix` command string.
 ///
 /// - `apply`: true for `--apply`, false for `--dry-run`
@@ -75,6 +112,182 @@ pub fn build_fix_command(apply: bool, codes: &[String]) -> String {
     parts.join(" ")
 }
 
+/// Workspace-root file holding per-package source hashes of the last
+/// successful analyze.
+pub const ANALYZE_CACHE_PATH: &str = ".dart_tool/melos_analyze_cache.json";
+
+/// Source hashes of packages that last passed analysis.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzeCache {
+    /// Hash of the inputs shared by every package (see [`analyze_cache_key`]);
+    /// entries recorded under a different key are discarded on load
+    pub key: String,
+    /// Package name -> hash from [`package_cache_hashes`]
+    pub packages: BTreeMap<String, String>,
+}
+
+impl AnalyzeCache {
+    /// Read the cache under `root`, starting empty when it is missing,
+    /// unreadable or was recorded under a different `key`.
+    pub fn load(root: &Path, key: &str) -> Self {
+        std::fs::read_to_string(root.join(ANALYZE_CACHE_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.key == key)
+            .unwrap_or_else(|| Self {
+                key: key.to_string(),
+                packages: BTreeMap::new(),
+            })
+    }
+
+    /// Write the cache under `root`.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(ANALYZE_CACHE_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether `name` last passed analysis with sources hashing to `hash`.
+    pub fn is_fresh(&self, name: &str, hash: &str) -> bool {
+        self.packages.get(name).is_some_and(|cached| cached == hash)
+    }
+}
+
+/// Hash the inputs that invalidate every cached package: the SDK version,
+/// the root `analysis_options.yaml` and `pubspec.lock`, and the fatal flags.
+pub fn analyze_cache_key(root: &Path, sdk_version: &str, opts: &AnalyzeOpts) -> String {
+    let options = std::fs::read(root.join("analysis_options.yaml")).unwrap_or_default();
+    let lock = std::fs::read(root.join("pubspec.lock")).unwrap_or_default();
+    let flags = format!(
+        "{} {} {}",
+        opts.fatal_warnings, opts.fatal_infos, opts.no_fatal
+    );
+    fnv1a_hex([
+        sdk_version.as_bytes(),
+        &options[..],
+        &lock[..],
+        flags.as_bytes(),
+    ])
+}
+
+/// Cache hashes for `packages`, keyed by name.
+///
+/// A package's hash covers its own sources (see [`package_source_hash`]) and
+/// those of every workspace package it transitively depends on, so a change
+/// in a dependency re-analyzes its dependents. Packages whose sources cannot
+/// be read are left out and always analyzed.
+pub fn package_cache_hashes(
+    packages: &[Package],
+    workspace_packages: &[Package],
+) -> HashMap<String, String> {
+    let graph = DependencyGraph::new(workspace_packages);
+    let by_name: HashMap<&str, &Package> = workspace_packages
+        .iter()
+        .chain(packages)
+        .map(|p| (p.name.as_str(), p))
+        .collect();
+    let mut source_hashes: HashMap<String, Option<String>> = HashMap::new();
+
+    let mut hashes = HashMap::new();
+    for pkg in packages {
+        let mut closure: Vec<String> = graph
+            .transitive_dependencies([pkg.name.as_str()])
+            .into_iter()
+            .collect();
+        if closure.is_empty() {
+            closure.push(pkg.name.clone());
+        }
+        closure.sort();
+
+        for name in &closure {
+            if !source_hashes.contains_key(name) {
+                let hash = by_name
+                    .get(name.as_str())
+                    .and_then(|member| package_source_hash(member).ok());
+                source_hashes.insert(name.clone(), hash);
+            }
+        }
+        let member_hashes: Option<Vec<&str>> = closure
+            .iter()
+            .map(|name| source_hashes[name].as_deref())
+            .collect();
+        if let Some(member_hashes) = member_hashes {
+            let parts = closure
+                .iter()
+                .zip(member_hashes)
+                .flat_map(|(name, hash)| [name.as_bytes(), hash.as_bytes()]);
+            hashes.insert(pkg.name.clone(), fnv1a_hex(parts));
+        }
+    }
+    hashes
+}
+
+/// Hash a package's analyzed sources: every `.dart` file plus its
+/// `pubspec.yaml`, `pubspec.lock` and `analysis_options.yaml`.
+///
+/// Hidden directories (such as `.dart_tool`) and `build` are skipped. Files
+/// are visited in path order so the hash is stable.
+pub fn package_source_hash(pkg: &Package) -> Result<String> {
+    let mut files = Vec::new();
+    collect_analyzed_files(&pkg.path, &mut files)?;
+    files.sort();
+
+    let mut parts = Vec::with_capacity(files.len() * 2);
+    for file in &files {
+        let relative = file.strip_prefix(&pkg.path).unwrap_or(file);
+        let content =
+            std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        parts.push(relative.to_string_lossy().into_owned().into_bytes());
+        parts.push(content);
+    }
+    Ok(fnv1a_hex(parts.iter().map(Vec::as_slice)))
+}
+
+fn collect_analyzed_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type().is_ok_and(|ft| ft.is_dir()) {
+            if !name.starts_with('.') && name != "build" {
+                collect_analyzed_files(&path, files)?;
+            }
+        } else if name.ends_with(".dart")
+            || name == "pubspec.yaml"
+            || name == "pubspec.lock"
+            || name == "analysis_options.yaml"
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Output of `dart --version`, used to invalidate the analyze cache when the
+/// SDK changes; `"unknown"` when it cannot be run.
+pub fn dart_sdk_version(env: &HashMap<String, String>) -> String {
+    std::process::Command::new("dart")
+        .arg("--version")
+        .envs(env)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            // Older SDKs print the version on stderr
+            let text = [output.stdout, output.stderr].concat();
+            String::from_utf8_lossy(&text).trim().to_string()
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 /// Build the analyze command string from flags.
 ///
 /// Uses `flutter analyze` for Flutter packages and `dart analyze` for
@@ -107,6 +320,158 @@ pub fn build_analyze_command(
     cmd_parts.join(" ")
 }
 
+/// Build the analyze command used for machine-readable output.
+///
+/// Always uses `dart analyze`, since `flutter analyze` has no `--format=json`;
+/// the fatal flags are applied the same way as [`build_analyze_command`].
+pub fn build_json_analyze_command(
+    fatal_warnings: bool,
+    fatal_infos: bool,
+    no_fatal: bool,
+) -> String {
+    let cmd = build_analyze_command(false, fatal_warnings, fatal_infos, no_fatal);
+    // Keep the `.` target last
+    let base = cmd.strip_suffix(" .").unwrap_or(&cmd);
+    format!("{base} --format=json .")
+}
+
+/// Parse `dart analyze` output into diagnostics.
+///
+/// Accepts the `--format=json` document as well as `--format=machine` lines
+/// (`SEVERITY|TYPE|CODE|FILE|LINE|COLUMN|LENGTH|MESSAGE`); anything else,
+/// such as progress text, is ignored.
+pub fn parse_analyzer_output(stdout: &str) -> Vec<AnalyzerDiagnostic> {
+    let json_start = stdout.find('{');
+    if let Some(start) = json_start
+        && let Ok(doc) = serde_json::from_str::<serde_json::Value>(stdout[start..].trim())
+    {
+        return parse_analyzer_json(&doc);
+    }
+    stdout.lines().filter_map(parse_machine_line).collect()
+}
+
+fn parse_analyzer_json(doc: &serde_json::Value) -> Vec<AnalyzerDiagnostic> {
+    let Some(diagnostics) = doc.get("diagnostics").and_then(|d| d.as_array()) else {
+        return Vec::new();
+    };
+    diagnostics
+        .iter()
+        .filter_map(|d| {
+            let location = d.get("location")?;
+            let start = location.get("range").and_then(|r| r.get("start"));
+            let position = |key: &str| {
+                start
+                    .and_then(|s| s.get(key))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as usize
+            };
+            let text = |key: &str| {
+                d.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            Some(AnalyzerDiagnostic {
+                file: location.get("file")?.as_str()?.to_string(),
+                line: position("line"),
+                column: position("column"),
+                severity: text("severity"),
+                code: text("code"),
+                message: text("problemMessage"),
+            })
+        })
+        .collect()
+}
+
+fn parse_machine_line(line: &str) -> Option<AnalyzerDiagnostic> {
+    let fields: Vec<&str> = line.trim().splitn(8, '|').collect();
+    if fields.len() != 8 || !matches!(fields[0], "ERROR" | "WARNING" | "INFO") {
+        return None;
+    }
+    Some(AnalyzerDiagnostic {
+        file: fields[3].to_string(),
+        line: fields[4].parse().ok()?,
+        column: fields[5].parse().ok()?,
+        severity: fields[0].to_string(),
+        code: fields[2].to_lowercase(),
+        message: fields[7].replace("\\|", "|"),
+    })
+}
+
+/// Map an analyzer severity to a checkstyle severity level.
+pub fn checkstyle_severity(severity: &str) -> &'static str {
+    match severity.to_ascii_uppercase().as_str() {
+        "ERROR" => "error",
+        "WARNING" => "warning",
+        _ => "info",
+    }
+}
+
+/// Render diagnostics as a checkstyle XML report.
+///
+/// `packages` pairs each analyzed package's path with its diagnostics. Paths
+/// are made relative to `root`; there is one `<file>` element per affected
+/// file, and an empty one naming the package directory when it has no issues.
+pub fn format_checkstyle(root: &Path, packages: &[(PathBuf, Vec<AnalyzerDiagnostic>)]) -> String {
+    let relative = |path: &Path| {
+        path.strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<checkstyle version=\"4.3\">\n");
+    for (pkg_path, diagnostics) in packages {
+        if diagnostics.is_empty() {
+            out.push_str(&format!(
+                "  <file name=\"{}\"/>\n",
+                xml_escape(&relative(pkg_path))
+            ));
+            continue;
+        }
+        let mut by_file: BTreeMap<String, Vec<&AnalyzerDiagnostic>> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            // Relative paths are resolved against the package directory
+            let file = pkg_path.join(&diagnostic.file);
+            by_file.entry(relative(&file)).or_default().push(diagnostic);
+        }
+        for (file, diagnostics) in by_file {
+            out.push_str(&format!("  <file name=\"{}\">\n", xml_escape(&file)));
+            for d in diagnostics {
+                out.push_str(&format!(
+                    "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"dart.{}\"/>\n",
+                    d.line,
+                    d.column,
+                    checkstyle_severity(&d.severity),
+                    xml_escape(&d.message),
+                    xml_escape(&d.code)
+                ));
+            }
+            out.push_str("  </file>\n");
+        }
+    }
+    out.push_str("</checkstyle>\n");
+    out
+}
+
+/// Escape text for use in an XML attribute value.
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// Parse a single diagnostic fix line from `dart fix --dry-run` output.
 ///
 /// Returns `(code, count)` on success.
@@ -181,6 +546,33 @@ pub fn parse_dry_run_output(stdout: &str, pkg_prefix: &str) -> Vec<DryRunFileEnt
     entries
 }
 
+/// Parse the proposed-changes summary from `dart fix --dry-run` stdout.
+///
+/// Returns a zero summary for `Nothing to fix!`. When no summary line is
+/// present, the counts are totalled from the per-file entries instead.
+pub fn parse_dry_run_summary(stdout: &str) -> DryRunSummary {
+    for line in stdout.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Nothing to fix") {
+            return DryRunSummary::default();
+        }
+        if let Some(caps) = SUMMARY_LINE_RE.captures(trimmed)
+            && let (Ok(fixes), Ok(files)) = (caps[1].parse(), caps[2].parse())
+        {
+            return DryRunSummary { fixes, files };
+        }
+    }
+
+    let entries = parse_dry_run_output(stdout, "");
+    DryRunSummary {
+        fixes: entries
+            .iter()
+            .flat_map(|e| e.fixes.iter().map(|(_, count)| count))
+            .sum(),
+        files: entries.len(),
+    }
+}
+
 /// Detect diagnostic code pairs that likely conflict.
 ///
 /// Two codes are considered conflicting when they appear together in the same
@@ -280,11 +672,27 @@ pub async fn run(
     opts: &AnalyzeOpts,
     events: Option<&UnboundedSender<Event>>,
 ) -> Result<PackageResults> {
+    let runner = ProcessRunner::new(opts.concurrency, false);
+
+    if opts.json_output {
+        let cmd = build_json_analyze_command(opts.fatal_warnings, opts.fatal_infos, opts.no_fatal);
+        let results = runner
+            .run_in_packages_with_events(
+                packages,
+                &cmd,
+                &workspace.env_vars(),
+                None,
+                events,
+                &workspace.packages,
+            )
+            .await?;
+        return Ok(results);
+    }
+
     let flutter_pkgs: Vec<_> = packages.iter().filter(|p| p.is_flutter).cloned().collect();
     let dart_pkgs: Vec<_> = packages.iter().filter(|p| !p.is_flutter).cloned().collect();
 
-    let runner = ProcessRunner::new(opts.concurrency, false);
-    let mut all_results = Vec::new();
+    let mut all_results = PackageResults::default();
 
     if !flutter_pkgs.is_empty() {
         let cmd = build_analyze_command(true, opts.fatal_warnings, opts.fatal_infos, opts.no_fatal);
@@ -331,7 +739,7 @@ pub async fn run(
         all_results.extend(results);
     }
 
-    Ok(PackageResults::from(all_results))
+    Ok(all_results)
 }
 
 #[cfg(test)]
@@ -842,4 +1250,262 @@ lib/foo.dart
         assert_eq!(scan.conflicts[0].code_a, "rule_a");
         assert_eq!(scan.conflicts[0].code_b, "rule_b");
     }
+
+    // -----------------------------------------------------------------------
+    // parse_dry_run_summary
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_dry_run_summary_counts() {
+        let stdout = "\
+Computing fixes in ui (dry run)...
+
+112 proposed fixes in 13 files.
+
+lib/main.dart
+  omit_local_variable_types - 4 fixes
+
+To fix all diagnostics, run:
+  dart fix --apply";
+        assert_eq!(
+            parse_dry_run_summary(stdout),
+            DryRunSummary {
+                fixes: 112,
+                files: 13
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_dry_run_summary_singular() {
+        let stdout = "Computing fixes in core (dry run)...\n\n1 proposed fix in 1 file.\n";
+        assert_eq!(
+            parse_dry_run_summary(stdout),
+            DryRunSummary { fixes: 1, files: 1 }
+        );
+    }
+
+    #[test]
+    fn test_parse_dry_run_summary_nothing_to_fix() {
+        let stdout = "Computing fixes in core (dry run)...\nNothing to fix!\n";
+        assert_eq!(parse_dry_run_summary(stdout), DryRunSummary::default());
+    }
+
+    #[test]
+    fn test_parse_dry_run_summary_falls_back_to_entries() {
+        let stdout = "\
+lib/a.dart
+  unnecessary_cast - 2 fixes
+  unused_import - 1 fix
+
+lib/b.dart
+  unused_import - 3 fixes
+";
+        assert_eq!(
+            parse_dry_run_summary(stdout),
+            DryRunSummary { fixes: 6, files: 2 }
+        );
+    }
+
+    // -- checkstyle tests --
+
+    #[test]
+    fn test_build_json_analyze_command() {
+        assert_eq!(
+            build_json_analyze_command(true, false, false),
+            "dart analyze --fatal-warnings --format=json ."
+        );
+        assert_eq!(
+            build_json_analyze_command(false, false, true),
+            "dart analyze --no-fatal-warnings --no-fatal-infos --format=json ."
+        );
+    }
+
+    #[test]
+    fn test_parse_analyzer_output_json() {
+        let stdout = r#"Analyzing core...
+{"version":1,"diagnostics":[{"code":"unused_import","severity":"WARNING","type":"STATIC_WARNING","location":{"file":"/ws/packages/core/lib/a.dart","range":{"start":{"offset":7,"line":1,"column":8},"end":{"offset":20,"line":1,"column":21}}},"problemMessage":"Unused import: 'dart:io'."}]}"#;
+        let diagnostics = parse_analyzer_output(stdout);
+        assert_eq!(
+            diagnostics,
+            vec![AnalyzerDiagnostic {
+                file: "/ws/packages/core/lib/a.dart".to_string(),
+                line: 1,
+                column: 8,
+                severity: "WARNING".to_string(),
+                code: "unused_import".to_string(),
+                message: "Unused import: 'dart:io'.".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_analyzer_output_machine_lines() {
+        let stdout = "Analyzing core...\nINFO|LINT|PREFER_CONST|/ws/lib/a.dart|3|5|4|Use const \\| here\nNo issues found!\n";
+        let diagnostics = parse_analyzer_output(stdout);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "prefer_const");
+        assert_eq!(diagnostics[0].line, 3);
+        assert_eq!(diagnostics[0].message, "Use const | here");
+    }
+
+    #[test]
+    fn test_parse_analyzer_output_no_issues() {
+        assert!(parse_analyzer_output("Analyzing core...\nNo issues found!\n").is_empty());
+        assert!(parse_analyzer_output(r#"{"version":1,"diagnostics":[]}"#).is_empty());
+    }
+
+    #[test]
+    fn test_checkstyle_severity_mapping() {
+        assert_eq!(checkstyle_severity("ERROR"), "error");
+        assert_eq!(checkstyle_severity("WARNING"), "warning");
+        assert_eq!(checkstyle_severity("INFO"), "info");
+    }
+
+    #[test]
+    fn test_format_checkstyle_groups_by_file() {
+        let root = Path::new("/ws");
+        let diagnostic = |file: &str, line, severity: &str, message: &str| AnalyzerDiagnostic {
+            file: file.to_string(),
+            line,
+            column: 1,
+            severity: severity.to_string(),
+            code: "some_code".to_string(),
+            message: message.to_string(),
+        };
+        let packages = vec![
+            (
+                PathBuf::from("/ws/packages/core"),
+                vec![
+                    diagnostic("/ws/packages/core/lib/b.dart", 2, "ERROR", "Bad <thing>"),
+                    diagnostic("/ws/packages/core/lib/a.dart", 1, "INFO", "Use \"const\""),
+                    diagnostic("/ws/packages/core/lib/b.dart", 9, "WARNING", "x & y"),
+                ],
+            ),
+            (PathBuf::from("/ws/packages/clean"), vec![]),
+        ];
+
+        let xml = format_checkstyle(root, &packages);
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="packages/core/lib/a.dart">
+    <error line="1" column="1" severity="info" message="Use &quot;const&quot;" source="dart.some_code"/>
+  </file>
+  <file name="packages/core/lib/b.dart">
+    <error line="2" column="1" severity="error" message="Bad &lt;thing&gt;" source="dart.some_code"/>
+    <error line="9" column="1" severity="warning" message="x &amp; y" source="dart.some_code"/>
+  </file>
+  <file name="packages/clean"/>
+</checkstyle>
+"#
+        );
+    }
+
+    // -- analyze cache tests --
+
+    fn write_package(dir: &Path) -> Package {
+        std::fs::create_dir_all(dir.join("lib/src")).unwrap();
+        std::fs::create_dir_all(dir.join(".dart_tool")).unwrap();
+        std::fs::write(dir.join("pubspec.yaml"), "name: core\n").unwrap();
+        std::fs::write(dir.join("lib/core.dart"), "export 'src/a.dart';\n").unwrap();
+        std::fs::write(dir.join("lib/src/a.dart"), "int a = 1;\n").unwrap();
+        std::fs::write(dir.join(".dart_tool/generated.dart"), "x").unwrap();
+        Package::from_path(dir).unwrap()
+    }
+
+    #[test]
+    fn test_package_source_hash_tracks_dart_sources() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = write_package(dir.path());
+        let hash = package_source_hash(&pkg).unwrap();
+        assert_eq!(package_source_hash(&pkg).unwrap(), hash);
+
+        // Hidden directories and non-source files are ignored
+        std::fs::write(dir.path().join(".dart_tool/generated.dart"), "y").unwrap();
+        std::fs::write(dir.path().join("README.md"), "docs").unwrap();
+        assert_eq!(package_source_hash(&pkg).unwrap(), hash);
+
+        std::fs::write(dir.path().join("lib/src/a.dart"), "int a = 2;\n").unwrap();
+        let edited = package_source_hash(&pkg).unwrap();
+        assert_ne!(edited, hash);
+
+        std::fs::write(dir.path().join("analysis_options.yaml"), "linter:\n").unwrap();
+        assert_ne!(package_source_hash(&pkg).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_package_cache_hashes_cover_transitive_dependencies() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let core = write_package(&dir.path().join("core"));
+        std::fs::create_dir_all(dir.path().join("mid/lib")).unwrap();
+        std::fs::write(
+            dir.path().join("mid/pubspec.yaml"),
+            "name: mid\ndependencies:\n  core:\n    path: ../core\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("app/lib")).unwrap();
+        std::fs::write(
+            dir.path().join("app/pubspec.yaml"),
+            "name: app\ndependencies:\n  mid:\n    path: ../mid\n",
+        )
+        .unwrap();
+        let mid = Package::from_path(&dir.path().join("mid")).unwrap();
+        let app = Package::from_path(&dir.path().join("app")).unwrap();
+        let all = vec![core, mid, app.clone()];
+
+        let hashes = package_cache_hashes(std::slice::from_ref(&app), &all);
+        let hash = hashes["app"].clone();
+        assert_ne!(hash, package_source_hash(&app).unwrap());
+
+        // An edit two levels down changes the dependent's hash
+        std::fs::write(dir.path().join("core/lib/src/a.dart"), "int a = 2;\n").unwrap();
+        let edited = package_cache_hashes(std::slice::from_ref(&app), &all)["app"].clone();
+        assert_ne!(edited, hash);
+
+        // So does a change to the package's resolved dependencies
+        std::fs::write(dir.path().join("app/pubspec.lock"), "packages: {}\n").unwrap();
+        assert_ne!(
+            package_cache_hashes(std::slice::from_ref(&app), &all)["app"],
+            edited
+        );
+    }
+
+    #[test]
+    fn test_analyze_cache_round_trip_and_key_invalidation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let opts = AnalyzeOpts {
+            concurrency: 1,
+            fatal_warnings: false,
+            fatal_infos: false,
+            no_fatal: false,
+            json_output: false,
+        };
+        let key = analyze_cache_key(dir.path(), "3.5.0", &opts);
+
+        let mut cache = AnalyzeCache::load(dir.path(), &key);
+        assert!(cache.packages.is_empty());
+        cache.packages.insert("core".to_string(), "abc".to_string());
+        cache.save(dir.path()).unwrap();
+
+        let cache = AnalyzeCache::load(dir.path(), &key);
+        assert!(cache.is_fresh("core", "abc"));
+        assert!(!cache.is_fresh("core", "def"));
+        assert!(!cache.is_fresh("app", "abc"));
+
+        // A new SDK or root analysis_options.yaml drops every entry
+        let sdk_key = analyze_cache_key(dir.path(), "3.6.0", &opts);
+        assert!(AnalyzeCache::load(dir.path(), &sdk_key).packages.is_empty());
+        std::fs::write(dir.path().join("analysis_options.yaml"), "include: x\n").unwrap();
+        let options_key = analyze_cache_key(dir.path(), "3.5.0", &opts);
+        assert_ne!(options_key, key);
+        std::fs::write(dir.path().join("pubspec.lock"), "packages: {}\n").unwrap();
+        assert_ne!(analyze_cache_key(dir.path(), "3.5.0", &opts), options_key);
+        assert!(
+            AnalyzeCache::load(dir.path(), &options_key)
+                .packages
+                .is_empty()
+        );
+    }
 }
@@ -1,16 +1,17 @@
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::config::BootstrapCommandConfig;
 use crate::events::Event;
-use crate::package::Package;
-use crate::runner::ProcessRunner;
+use crate::package::{DepKind, Package};
+use crate::runner::{ProcessRunner, resolve_concurrency};
 use crate::workspace::Workspace;
 
-use super::PackageResults;
+use super::{PackageResults, fnv1a_hex};
 
 /// Options for the bootstrap command (clap-free).
 #[derive(Debug, Clone)]
@@ -34,7 +35,7 @@ pub async fn run(
     let dart_pkgs: Vec<_> = packages.iter().filter(|p| !p.is_flutter).cloned().collect();
 
     let runner = ProcessRunner::new(opts.concurrency, false);
-    let mut all_results = Vec::new();
+    let mut all_results = PackageResults::default();
 
     if !flutter_pkgs.is_empty() {
         let cmd = build_pub_get_command(
@@ -86,7 +87,7 @@ pub async fn run(
         all_results.extend(results);
     }
 
-    Ok(PackageResults::from(all_results))
+    Ok(all_results)
 }
 
 /// Extract the bootstrap command config from the workspace, if present.
@@ -98,14 +99,23 @@ pub fn bootstrap_config(workspace: &Workspace) -> Option<&BootstrapCommandConfig
         .and_then(|c| c.bootstrap.as_ref())
 }
 
+/// Default bootstrap concurrency when neither the CLI nor config sets one.
+pub const DEFAULT_CONCURRENCY: usize = 5;
+
 /// Determine effective concurrency for bootstrap.
 ///
 /// If the config has `command.bootstrap.run_pub_get_in_parallel: false`,
-/// concurrency is forced to 1. Otherwise, the CLI `-c N` value is used.
-pub fn effective_concurrency(workspace: &Workspace, cli_concurrency: usize) -> usize {
-    match bootstrap_config(workspace).and_then(|b| b.run_pub_get_in_parallel) {
+/// concurrency is forced to 1. Otherwise the CLI `-c N` value is used,
+/// falling back to `command.bootstrap.concurrency`, then [`DEFAULT_CONCURRENCY`].
+pub fn effective_concurrency(workspace: &Workspace, cli_concurrency: Option<usize>) -> usize {
+    let config = bootstrap_config(workspace);
+    match config.and_then(|b| b.run_pub_get_in_parallel) {
         Some(false) => 1,
-        _ => cli_concurrency,
+        _ => resolve_concurrency(
+            cli_concurrency,
+            config.and_then(|b| b.concurrency),
+            DEFAULT_CONCURRENCY,
+        ),
     }
 }
 
@@ -130,6 +140,26 @@ pub fn config_dependency_override_paths(workspace: &Workspace) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// Get `dependencyOverrides` from bootstrap config, sorted by package name.
+pub fn config_dependency_overrides(workspace: &Workspace) -> BTreeMap<String, yaml_serde::Value> {
+    bootstrap_config(workspace)
+        .and_then(|b| b.dependency_overrides.as_ref())
+        .map(|overrides| {
+            overrides
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check if `verifyLinks` is set in bootstrap config.
+pub fn config_verify_links(workspace: &Workspace) -> bool {
+    bootstrap_config(workspace)
+        .and_then(|b| b.verify_links)
+        .unwrap_or(false)
+}
+
 /// Check if `enforce_versions_for_dependency_resolution` is set in bootstrap config.
 pub fn config_enforce_versions(workspace: &Workspace) -> bool {
     bootstrap_config(workspace)
@@ -230,15 +260,73 @@ pub fn build_pub_get_command(
     cmd
 }
 
-/// Validate that workspace packages' version constraints on sibling packages are
-/// satisfied by the siblings' actual versions.
+/// Short description of the `pub get` phase a line of its output announces,
+/// e.g. `resolving` or `downloading http-1.2.3`, for progress display.
 ///
-/// Returns `Ok(())` if all constraints are satisfied, or a list of violation
-/// messages if any are not.
-pub fn enforce_versions(
+/// Returns `None` for lines that do not start a phase.
+pub fn pub_get_phase(line: &str) -> Option<String> {
+    let line = line.trim();
+    // Drop the elapsed-time suffix of `Resolving dependencies... (1.2s)`
+    let line = match line.rfind(" (") {
+        Some(idx) if line.ends_with("s)") => &line[..idx],
+        _ => line,
+    };
+    let line = line.trim_end_matches("...").trim_end_matches('!');
+
+    if line.starts_with("Resolving dependencies") {
+        return Some("resolving".to_string());
+    }
+    if line.starts_with("Precompiling") {
+        return Some("precompiling".to_string());
+    }
+    if line.starts_with("Got dependencies") || line.starts_with("Changed ") {
+        return Some("done".to_string());
+    }
+    // `Downloading http 1.2.3` (older pub) and `+ http 1.2.3` both name a
+    // package being fetched
+    let fetched = line
+        .strip_prefix("Downloading ")
+        .or_else(|| line.strip_prefix("+ "))?;
+    let mut parts = fetched.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("packages"), None) | (None, _) => Some("downloading".to_string()),
+        (Some(name), Some(version)) => Some(format!("downloading {name}-{version}")),
+        (Some(name), None) => Some(format!("downloading {name}")),
+    }
+}
+
+/// A workspace dependency whose constraint excludes the sibling's actual version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// Package declaring the constraint
+    pub dependent: String,
+    /// Workspace package the constraint applies to
+    pub dependency: String,
+    pub constraint: String,
+    /// The dependency's version in the workspace
+    pub version: String,
+}
+
+impl std::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {}: constraint {} not satisfied by {}",
+            self.dependent, self.dependency, self.constraint, self.version
+        )
+    }
+}
+
+/// Find constraints in `packages` on sibling packages that the siblings'
+/// actual versions do not satisfy.
+///
+/// Dependencies without a constraint, `git`/`sdk` dependencies, siblings
+/// without a version and constraints or versions that don't parse as semver
+/// are skipped.
+pub fn constraint_violations(
     packages: &[Package],
     all_workspace_packages: &[Package],
-) -> Result<Vec<String>> {
+) -> Vec<ConstraintViolation> {
     let workspace_map: HashMap<&str, &Package> = all_workspace_packages
         .iter()
         .map(|p| (p.name.as_str(), p))
@@ -252,6 +340,14 @@ pub fn enforce_versions(
                 continue;
             };
 
+            // A git or SDK dependency is not resolved to the sibling
+            if matches!(
+                pkg.dependency_kind(dep_name),
+                Some(DepKind::Git | DepKind::Sdk)
+            ) {
+                continue;
+            }
+
             let Some(constraint_str) = pkg.dependency_versions.get(dep_name) else {
                 continue;
             };
@@ -276,15 +372,37 @@ pub fn enforce_versions(
             };
 
             if !constraint.matches(&sibling_version) {
-                violations.push(format!(
-                    "  {} depends on {} {} but workspace has {}",
-                    pkg.name, dep_name, constraint_str, sibling_version_str
-                ));
+                violations.push(ConstraintViolation {
+                    dependent: pkg.name.clone(),
+                    dependency: dep_name.clone(),
+                    constraint: constraint_str.clone(),
+                    version: sibling_version_str.clone(),
+                });
             }
         }
     }
 
-    Ok(violations)
+    violations
+}
+
+/// Validate that workspace packages' version constraints on sibling packages are
+/// satisfied by the siblings' actual versions.
+///
+/// Returns `Ok(())` if all constraints are satisfied, or a list of violation
+/// messages if any are not.
+pub fn enforce_versions(
+    packages: &[Package],
+    all_workspace_packages: &[Package],
+) -> Result<Vec<String>> {
+    Ok(constraint_violations(packages, all_workspace_packages)
+        .into_iter()
+        .map(|v| {
+            format!(
+                "  {} depends on {} {} but workspace has {}",
+                v.dependent, v.dependency, v.constraint, v.version
+            )
+        })
+        .collect())
 }
 
 /// Sync shared dependency versions from bootstrap config into each package's pubspec.yaml.
@@ -353,17 +471,24 @@ pub struct PubspecOverridesResult {
     pub warnings: Vec<String>,
 }
 
-/// Generate `pubspec_overrides.yaml` files for local package linking (Melos 6.x mode).
-///
-/// For each package that depends on other workspace packages, creates a
-/// `pubspec_overrides.yaml` with `dependency_overrides:` entries pointing to
-/// the sibling package via a relative path.
-pub fn generate_pubspec_overrides(
-    packages: &[Package],
+/// The `pubspec_overrides.yaml` files bootstrap would leave behind.
+struct OverridesPlan<'a> {
+    /// Each non-workspace-resolution package with its expected file content,
+    /// or `None` when it should have no overrides file.
+    files: Vec<(&'a Package, Option<String>)>,
+    extra_package_count: usize,
+    warnings: Vec<String>,
+}
+
+/// Compute the expected `pubspec_overrides.yaml` of each package without
+/// touching the filesystem (apart from reading `dependencyOverridePaths`).
+fn plan_pubspec_overrides<'a>(
+    packages: &'a [Package],
     all_workspace_packages: &[Package],
     dependency_override_paths: &[String],
+    explicit_overrides: &BTreeMap<String, yaml_serde::Value>,
     workspace_root: &Path,
-) -> Result<PubspecOverridesResult> {
+) -> Result<OverridesPlan<'a>> {
     let mut warnings = Vec::new();
 
     // Discover extra packages from dependencyOverridePaths
@@ -408,7 +533,8 @@ pub fn generate_pubspec_overrides(
         .map(|p| p.name.as_str())
         .collect();
 
-    let mut generated = 0u32;
+    let mut files = Vec::new();
+    let mut conflicts = BTreeSet::new();
 
     for pkg in packages {
         // Skip packages that use Dart workspace resolution
@@ -421,6 +547,15 @@ pub fn generate_pubspec_overrides(
             .iter()
             .chain(pkg.dev_dependencies.iter())
             .filter(|dep| override_names.contains(dep.as_str()))
+            // SDK packages (e.g. `flutter_test`) are never linked to siblings
+            .filter(|dep| pkg.dependency_kind(dep) != Some(DepKind::Sdk))
+            .filter(|dep| {
+                let explicit = explicit_overrides.contains_key(dep.as_str());
+                if explicit {
+                    conflicts.insert(dep.to_string());
+                }
+                !explicit
+            })
             .filter_map(|dep| {
                 all_override_sources
                     .iter()
@@ -429,59 +564,428 @@ pub fn generate_pubspec_overrides(
             })
             .collect();
 
-        let override_path = pkg.path.join("pubspec_overrides.yaml");
+        if local_deps.is_empty() && explicit_overrides.is_empty() {
+            files.push((pkg, None));
+        } else {
+            let content =
+                build_pubspec_overrides_content(&local_deps, explicit_overrides, &pkg.path)?;
+            files.push((pkg, Some(content)));
+        }
+    }
+
+    warnings.extend(conflicts.into_iter().map(|name| {
+        format!(
+            "dependencyOverrides: '{}' replaces the generated sibling path override",
+            name
+        )
+    }));
+
+    Ok(OverridesPlan {
+        files,
+        extra_package_count,
+        warnings,
+    })
+}
+
+/// Generate `pubspec_overrides.yaml` files for local package linking (Melos 6.x mode).
+///
+/// For each package that depends on other workspace packages, creates a
+/// `pubspec_overrides.yaml` with `dependency_overrides:` entries pointing to
+/// the sibling package via a relative path. `explicit_overrides` (from
+/// `dependencyOverrides`) are added to every such file verbatim and replace a
+/// sibling override of the same name, with a warning.
+pub fn generate_pubspec_overrides(
+    packages: &[Package],
+    all_workspace_packages: &[Package],
+    dependency_override_paths: &[String],
+    explicit_overrides: &BTreeMap<String, yaml_serde::Value>,
+    workspace_root: &Path,
+) -> Result<PubspecOverridesResult> {
+    let plan = plan_pubspec_overrides(
+        packages,
+        all_workspace_packages,
+        dependency_override_paths,
+        explicit_overrides,
+        workspace_root,
+    )?;
 
-        if local_deps.is_empty() {
-            if override_path.exists() {
-                std::fs::remove_file(&override_path).with_context(|| {
+    let mut generated = 0u32;
+    for (pkg, expected) in &plan.files {
+        let override_path = pkg.path.join(PUBSPEC_OVERRIDES);
+        match expected {
+            None => {
+                if override_path.exists() {
+                    std::fs::remove_file(&override_path).with_context(|| {
+                        format!(
+                            "Failed to remove stale pubspec_overrides.yaml in {}",
+                            pkg.name
+                        )
+                    })?;
+                }
+            }
+            Some(content) => {
+                std::fs::write(&override_path, content).with_context(|| {
                     format!(
-                        "Failed to remove stale pubspec_overrides.yaml in {}",
+                        "Failed to write pubspec_overrides.yaml for package '{}'",
                         pkg.name
                     )
                 })?;
+                generated += 1;
             }
-            continue;
         }
-
-        let content = build_pubspec_overrides_content(&local_deps, &pkg.path)?;
-        std::fs::write(&override_path, &content).with_context(|| {
-            format!(
-                "Failed to write pubspec_overrides.yaml for package '{}'",
-                pkg.name
-            )
-        })?;
-
-        generated += 1;
     }
 
     Ok(PubspecOverridesResult {
         generated,
-        extra_package_count,
-        warnings,
+        extra_package_count: plan.extra_package_count,
+        warnings: plan.warnings,
     })
 }
 
-/// Build the YAML content for a `pubspec_overrides.yaml` file.
-pub fn build_pubspec_overrides_content(local_deps: &[&Package], pkg_path: &Path) -> Result<String> {
-    let mut content =
-        String::from("# Generated by melos-rs. Do not edit.\ndependency_overrides:\n");
+/// File name of the generated local-linking overrides.
+pub const PUBSPEC_OVERRIDES: &str = "pubspec_overrides.yaml";
+
+/// Why a package's `pubspec_overrides.yaml` is out of date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverridesDriftKind {
+    /// Bootstrap would create the file
+    Missing,
+    /// The file differs from what bootstrap would write
+    Stale,
+    /// Bootstrap would delete the file: the package links no siblings
+    Unexpected,
+    /// The package uses `resolution: workspace` and must not have the file
+    WorkspaceResolution,
+}
+
+/// A package whose `pubspec_overrides.yaml` does not match bootstrap's output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverridesDrift {
+    pub package: String,
+    pub kind: OverridesDriftKind,
+    /// Line diff from the on-disk file to the expected one: each line is
+    /// prefixed with `-` (only on disk), `+` (only expected) or ` ` (both)
+    pub diff: Vec<String>,
+}
+
+/// Compare each package's `pubspec_overrides.yaml` with what
+/// [`generate_pubspec_overrides`] would write, without writing anything.
+///
+/// With `link_siblings` false (pubspec.yaml workspaces, where bootstrap
+/// generates no overrides), only `resolution: workspace` packages carrying a
+/// file are reported. Warnings about `dependencyOverridePaths` are returned
+/// alongside the drifts.
+pub fn validate_pubspec_overrides(
+    packages: &[Package],
+    all_workspace_packages: &[Package],
+    dependency_override_paths: &[String],
+    explicit_overrides: &BTreeMap<String, yaml_serde::Value>,
+    workspace_root: &Path,
+    link_siblings: bool,
+) -> Result<(Vec<OverridesDrift>, Vec<String>)> {
+    let mut drifts = Vec::new();
+
+    for pkg in packages.iter().filter(|p| p.uses_workspace_resolution()) {
+        if let Some(actual) = read_overrides(pkg)? {
+            drifts.push(OverridesDrift {
+                package: pkg.name.clone(),
+                kind: OverridesDriftKind::WorkspaceResolution,
+                diff: line_diff(&actual, ""),
+            });
+        }
+    }
+
+    if !link_siblings {
+        return Ok((drifts, Vec::new()));
+    }
+
+    let plan = plan_pubspec_overrides(
+        packages,
+        all_workspace_packages,
+        dependency_override_paths,
+        explicit_overrides,
+        workspace_root,
+    )?;
+    for (pkg, expected) in &plan.files {
+        let actual = read_overrides(pkg)?;
+        let kind = match (&actual, expected) {
+            (None, None) => continue,
+            (Some(actual), Some(expected)) if actual == expected => continue,
+            (None, Some(_)) => OverridesDriftKind::Missing,
+            (Some(_), None) => OverridesDriftKind::Unexpected,
+            (Some(_), Some(_)) => OverridesDriftKind::Stale,
+        };
+        drifts.push(OverridesDrift {
+            package: pkg.name.clone(),
+            kind,
+            diff: line_diff(
+                actual.as_deref().unwrap_or_default(),
+                expected.as_deref().unwrap_or_default(),
+            ),
+        });
+    }
+
+    Ok((drifts, plan.warnings))
+}
+
+fn read_overrides(pkg: &Package) -> Result<Option<String>> {
+    let path = pkg.path.join(PUBSPEC_OVERRIDES);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Line diff from `old` to `new` via longest common subsequence.
+fn line_diff(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
 
-    // Sort deps by name for deterministic output
-    let mut sorted_deps: Vec<&&Package> = local_deps.iter().collect();
-    sorted_deps.sort_by_key(|p| &p.name);
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(format!(" {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("-{}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+{}", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
 
-    for dep in sorted_deps {
+/// Build the YAML content for a `pubspec_overrides.yaml` file.
+///
+/// Sibling packages become `path:` overrides; `explicit` entries are written
+/// as configured. Entries are sorted by name.
+pub fn build_pubspec_overrides_content(
+    local_deps: &[&Package],
+    explicit: &BTreeMap<String, yaml_serde::Value>,
+    pkg_path: &Path,
+) -> Result<String> {
+    let mut entries: BTreeMap<&str, String> = BTreeMap::new();
+
+    for dep in local_deps {
         let relative =
             pathdiff::diff_paths(&dep.path, pkg_path).unwrap_or_else(|| dep.path.clone());
-        let relative_str = relative.display().to_string();
+        entries.insert(
+            &dep.name,
+            format!("  {}:\n    path: {}\n", dep.name, relative.display()),
+        );
+    }
 
-        content.push_str(&format!("  {}:\n", dep.name));
-        content.push_str(&format!("    path: {}\n", relative_str));
+    for (name, value) in explicit {
+        let mut entry = yaml_serde::Mapping::new();
+        entry.insert(yaml_serde::Value::String(name.clone()), value.clone());
+        let yaml = yaml_serde::to_string(&entry)
+            .with_context(|| format!("Failed to serialize dependency override '{}'", name))?;
+        let indented: String = yaml.lines().map(|line| format!("  {line}\n")).collect();
+        entries.insert(name, indented);
+    }
+
+    let mut content =
+        String::from("# Generated by melos-rs. Do not edit.\ndependency_overrides:\n");
+    for entry in entries.into_values() {
+        content.push_str(&entry);
     }
 
     Ok(content)
 }
 
+/// Per-package file recording the inputs of the last successful bootstrap.
+pub const BOOTSTRAP_HASH_PATH: &str = ".dart_tool/melos_bootstrap_hash";
+
+/// Hash the inputs that decide a package's `pub get` result.
+///
+/// Covers the package's `pubspec.yaml`, its generated `pubspec_overrides.yaml`
+/// (if any) and the workspace config content, so a changed shared dependency
+/// in config invalidates every package. Uses 64-bit FNV-1a, which is stable
+/// across builds, rendered as 16 hex digits.
+pub fn bootstrap_hash(pkg: &Package, config_content: &str) -> Result<String> {
+    let pubspec_path = pkg.path.join("pubspec.yaml");
+    let pubspec = std::fs::read(&pubspec_path)
+        .with_context(|| format!("Failed to read {}", pubspec_path.display()))?;
+    let overrides = std::fs::read(pkg.path.join("pubspec_overrides.yaml")).unwrap_or_default();
+
+    Ok(fnv1a_hex([
+        &pubspec[..],
+        &overrides[..],
+        config_content.as_bytes(),
+    ]))
+}
+
+/// Whether the hash stored by the last bootstrap of `pkg` equals `hash`.
+pub fn is_bootstrap_unchanged(pkg: &Package, hash: &str) -> bool {
+    std::fs::read_to_string(pkg.path.join(BOOTSTRAP_HASH_PATH))
+        .is_ok_and(|stored| stored.trim() == hash)
+}
+
+/// Record `hash` as the inputs of a successful bootstrap of `pkg`.
+pub fn write_bootstrap_hash(pkg: &Package, hash: &str) -> Result<()> {
+    let path = pkg.path.join(BOOTSTRAP_HASH_PATH);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, format!("{}\n", hash))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Path of `package_config.json` relative to a package (or workspace) root.
+const PACKAGE_CONFIG_PATH: &str = ".dart_tool/package_config.json";
+
+/// The parts of `.dart_tool/package_config.json` needed to verify links.
+#[derive(Debug, Deserialize)]
+struct PackageConfig {
+    packages: Vec<PackageConfigEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageConfigEntry {
+    name: String,
+    root_uri: String,
+}
+
+/// A workspace dependency that `pub get` resolved outside the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkIssue {
+    /// Package whose `package_config.json` was checked
+    pub package: String,
+    /// Workspace dependency that did not resolve to the local package
+    pub dependency: String,
+    /// The dependency's `rootUri` as written by `pub get`
+    pub root_uri: String,
+}
+
+impl LinkIssue {
+    /// Whether the dependency resolved into the pub cache (i.e. pub.dev).
+    pub fn is_pub_cache(&self) -> bool {
+        self.root_uri.contains(".pub-cache") || self.root_uri.contains("Pub/Cache")
+    }
+}
+
+impl std::fmt::Display for LinkIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: '{}' resolved to {}",
+            self.package, self.dependency, self.root_uri
+        )?;
+        if self.is_pub_cache() {
+            write!(f, " (pub cache)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify that workspace dependencies of `packages` resolved to local paths.
+///
+/// Reads each package's `.dart_tool/package_config.json` (the workspace
+/// root's for `resolution: workspace` members) and reports every dependency
+/// that names a workspace package but whose `rootUri` points outside
+/// `root`. Packages without a `package_config.json` are skipped.
+pub fn verify_links(
+    packages: &[Package],
+    workspace_packages: &[Package],
+    root: &Path,
+) -> Result<Vec<LinkIssue>> {
+    let workspace_names: HashSet<&str> =
+        workspace_packages.iter().map(|p| p.name.as_str()).collect();
+    let root = normalize_path(root);
+    let mut issues = Vec::new();
+
+    for pkg in packages {
+        let config_root = if pkg.uses_workspace_resolution() {
+            root.as_path()
+        } else {
+            pkg.path.as_path()
+        };
+        let config_path = config_root.join(PACKAGE_CONFIG_PATH);
+        let Ok(content) = std::fs::read_to_string(&config_path) else {
+            continue;
+        };
+        let config: PackageConfig = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+        // `rootUri` is relative to the directory containing the file
+        let base = config_root.join(".dart_tool");
+
+        let deps = pkg.dependencies.iter().chain(&pkg.dev_dependencies);
+        for dep in deps.filter(|d| workspace_names.contains(d.as_str())) {
+            let Some(entry) = config.packages.iter().find(|e| &e.name == dep) else {
+                continue;
+            };
+            let resolved = match entry.root_uri.strip_prefix("file://") {
+                Some(path) => PathBuf::from(percent_decode(path)),
+                None => base.join(percent_decode(&entry.root_uri)),
+            };
+            if !normalize_path(&resolved).starts_with(&root) {
+                issues.push(LinkIssue {
+                    package: pkg.name.clone(),
+                    dependency: dep.clone(),
+                    root_uri: entry.root_uri.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Lexically resolve `.` and `..` components without touching the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Decode `%XX` escapes in a `file:` URI path (e.g. `%20` for spaces).
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,6 +1005,7 @@ mod tests {
             dependencies: deps.into_iter().map(String::from).collect(),
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -518,6 +1023,7 @@ mod tests {
                     version: None,
                     bootstrap: bootstrap_config,
                     build: None,
+                    exec: None,
                     clean: None,
                     publish: None,
                     test: None,
@@ -527,10 +1033,14 @@ mod tests {
                 categories: HashMap::new(),
                 use_root_as_package: None,
                 discover_nested_workspaces: None,
+                respect_gitignore: None,
+                env: HashMap::new(),
             },
             packages: vec![],
             sdk_path: None,
             warnings: vec![],
+            overridden_fields: vec![],
+            color: Default::default(),
         }
     }
 
@@ -552,6 +1062,7 @@ mod tests {
                 .into_iter()
                 .map(|(k, v)| (k.to_string(), v.to_string()))
                 .collect(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -565,7 +1076,7 @@ mod tests {
         let app_path = PathBuf::from("/workspace/packages/app");
 
         let deps: Vec<&Package> = vec![&core, &utils];
-        let content = build_pubspec_overrides_content(&deps, &app_path).unwrap();
+        let content = build_pubspec_overrides_content(&deps, &BTreeMap::new(), &app_path).unwrap();
 
         assert!(content.contains("# Generated by melos-rs"));
         assert!(content.contains("dependency_overrides:"));
@@ -582,7 +1093,7 @@ mod tests {
         let app_path = PathBuf::from("/workspace/packages/app");
 
         let deps: Vec<&Package> = vec![&zebra, &alpha];
-        let content = build_pubspec_overrides_content(&deps, &app_path).unwrap();
+        let content = build_pubspec_overrides_content(&deps, &BTreeMap::new(), &app_path).unwrap();
 
         let alpha_pos = content.find("alpha:").unwrap();
         let zebra_pos = content.find("zebra:").unwrap();
@@ -597,59 +1108,114 @@ mod tests {
     #[test]
     fn test_effective_concurrency_default() {
         let ws = make_workspace(None);
-        assert_eq!(effective_concurrency(&ws, 5), 5);
+        assert_eq!(effective_concurrency(&ws, Some(5)), 5);
+        assert_eq!(effective_concurrency(&ws, None), DEFAULT_CONCURRENCY);
     }
 
     #[test]
     fn test_effective_concurrency_parallel_false_forces_one() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: Some(false),
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
-        assert_eq!(effective_concurrency(&ws, 5), 1);
+        assert_eq!(effective_concurrency(&ws, Some(5)), 1);
     }
 
     #[test]
     fn test_effective_concurrency_parallel_true_uses_cli() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: Some(true),
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
-        assert_eq!(effective_concurrency(&ws, 8), 8);
+        assert_eq!(effective_concurrency(&ws, Some(8)), 8);
     }
 
     #[test]
     fn test_effective_concurrency_parallel_none_uses_cli() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
-        assert_eq!(effective_concurrency(&ws, 3), 3);
+        assert_eq!(effective_concurrency(&ws, Some(3)), 3);
+    }
+
+    #[test]
+    fn test_effective_concurrency_falls_back_to_config() {
+        let ws = make_workspace(Some(BootstrapCommandConfig {
+            run_pub_get_in_parallel: None,
+            concurrency: Some(12),
+            enforce_versions_for_dependency_resolution: None,
+            enforce_lockfile: None,
+            run_pub_get_offline: None,
+            dependency_override_paths: None,
+            dependency_overrides: None,
+            environment: None,
+            dependencies: None,
+            dev_dependencies: None,
+            verify_links: None,
+            hooks: None,
+        }));
+        assert_eq!(effective_concurrency(&ws, None), 12);
+        assert_eq!(effective_concurrency(&ws, Some(2)), 2);
     }
 
     // -- build_pub_get_command tests --
 
+    #[test]
+    fn test_pub_get_phase() {
+        let phase = |line: &str| pub_get_phase(line);
+        assert_eq!(
+            phase("Resolving dependencies... (1.4s)").as_deref(),
+            Some("resolving")
+        );
+        assert_eq!(
+            phase("Downloading packages...").as_deref(),
+            Some("downloading")
+        );
+        assert_eq!(
+            phase("Downloading http 1.2.3...").as_deref(),
+            Some("downloading http-1.2.3")
+        );
+        assert_eq!(
+            phase("+ http 1.2.3").as_deref(),
+            Some("downloading http-1.2.3")
+        );
+        assert_eq!(phase("Got dependencies!").as_deref(), Some("done"));
+        assert_eq!(phase("Changed 12 dependencies!").as_deref(), Some("done"));
+        assert_eq!(phase("  meta 1.15.0 (1.16.0 available)"), None);
+        assert_eq!(phase(""), None);
+    }
+
     #[test]
     fn test_build_pub_get_command_default() {
         let cmd = build_pub_get_command("flutter", false, false, false);
@@ -689,13 +1255,16 @@ mod tests {
     fn test_config_enforce_lockfile_true() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: Some(true),
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         assert!(config_enforce_lockfile(&ws));
@@ -705,13 +1274,16 @@ mod tests {
     fn test_config_enforce_lockfile_false() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: Some(false),
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         assert!(!config_enforce_lockfile(&ws));
@@ -764,6 +1336,40 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_constraint_violations_display() {
+        let core = make_versioned_package("core", "2.0.0+3", vec![], vec![]);
+        let ui = make_versioned_package("ui", "1.1.0", vec![], vec![]);
+        let app = make_versioned_package(
+            "app",
+            "1.0.0",
+            vec!["core", "ui"],
+            vec![("core", "^1.0.0"), ("ui", "^1.0.0")],
+        );
+        let all = vec![core, ui, app.clone()];
+        let violations = constraint_violations(&[app], &all);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "app -> core: constraint ^1.0.0 not satisfied by 2.0.0+3"
+        );
+    }
+
+    #[test]
+    fn test_constraint_violations_skip_git_dependencies() {
+        let core = make_versioned_package("core", "2.0.0", vec![], vec![]);
+        let mut app =
+            make_versioned_package("app", "1.0.0", vec!["core"], vec![("core", "^1.0.0")]);
+        app.dependency_kinds
+            .insert("core".to_string(), DepKind::Git);
+        let all = vec![core, app.clone()];
+        assert!(constraint_violations(&[app.clone()], &all).is_empty());
+
+        app.dependency_kinds
+            .insert("core".to_string(), DepKind::Path);
+        assert_eq!(constraint_violations(&[app], &all).len(), 1);
+    }
+
     #[test]
     fn test_config_enforce_versions_default() {
         let ws = make_workspace(None);
@@ -774,13 +1380,16 @@ mod tests {
     fn test_config_enforce_versions_true() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: Some(true),
             enforce_lockfile: None,
             run_pub_get_offline: None,
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         assert!(config_enforce_versions(&ws));
@@ -792,13 +1401,16 @@ mod tests {
     fn test_config_run_pub_get_offline_true() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: Some(true),
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         assert!(config_run_pub_get_offline(&ws));
@@ -808,13 +1420,16 @@ mod tests {
     fn test_config_run_pub_get_offline_false() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: Some(false),
             dependency_override_paths: None,
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         assert!(!config_run_pub_get_offline(&ws));
@@ -832,6 +1447,7 @@ mod tests {
     fn test_config_dependency_override_paths_some() {
         let ws = make_workspace(Some(BootstrapCommandConfig {
             run_pub_get_in_parallel: None,
+            concurrency: None,
             enforce_versions_for_dependency_resolution: None,
             enforce_lockfile: None,
             run_pub_get_offline: None,
@@ -839,9 +1455,11 @@ mod tests {
                 "../external".to_string(),
                 "../other".to_string(),
             ]),
+            dependency_overrides: None,
             environment: None,
             dependencies: None,
             dev_dependencies: None,
+            verify_links: None,
             hooks: None,
         }));
         let paths = config_dependency_override_paths(&ws);
@@ -872,6 +1490,7 @@ mod tests {
             dependencies: vec!["core".to_string(), "external_lib".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -889,8 +1508,13 @@ mod tests {
         )
         .unwrap();
 
-        let result =
-            generate_pubspec_overrides(&[app], &[core], &["external".to_string()], dir.path());
+        let result = generate_pubspec_overrides(
+            &[app],
+            &[core],
+            &["external".to_string()],
+            &BTreeMap::new(),
+            dir.path(),
+        );
         assert!(result.is_ok());
 
         let overrides_path = pkg_dir.join("pubspec_overrides.yaml");
@@ -915,6 +1539,7 @@ mod tests {
             dependencies: vec!["core".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -922,7 +1547,7 @@ mod tests {
         std::fs::create_dir_all(&core_dir).unwrap();
         let core = make_package("core", &core_dir.to_string_lossy(), vec![]);
 
-        let result = generate_pubspec_overrides(&[app], &[core], &[], dir.path());
+        let result = generate_pubspec_overrides(&[app], &[core], &[], &BTreeMap::new(), dir.path());
         assert!(result.is_ok());
 
         let overrides_path = pkg_dir.join("pubspec_overrides.yaml");
@@ -931,6 +1556,72 @@ mod tests {
         assert!(content.contains("core:"));
     }
 
+    #[test]
+    fn test_generate_pubspec_overrides_explicit_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let app_dir = dir.path().join("packages").join("app");
+        let core_dir = dir.path().join("packages").join("core");
+        std::fs::create_dir_all(&app_dir).unwrap();
+        std::fs::create_dir_all(&core_dir).unwrap();
+
+        let app = make_package("app", &app_dir.to_string_lossy(), vec!["core", "shared"]);
+        let core = make_package("core", &core_dir.to_string_lossy(), vec![]);
+        let shared = make_package(
+            "shared",
+            &dir.path().join("packages/shared").to_string_lossy(),
+            vec![],
+        );
+
+        let explicit: BTreeMap<String, yaml_serde::Value> = yaml_serde::from_str(
+            "http:\n  git:\n    url: https://github.com/me/http.git\n    ref: fix\nshared: ^2.0.0\n",
+        )
+        .unwrap();
+
+        let result = generate_pubspec_overrides(
+            &[app, core.clone()],
+            &[core, shared],
+            &[],
+            &explicit,
+            dir.path(),
+        )
+        .unwrap();
+        assert_eq!(result.generated, 2);
+        assert_eq!(
+            result.warnings,
+            vec!["dependencyOverrides: 'shared' replaces the generated sibling path override"]
+        );
+
+        let content = std::fs::read_to_string(app_dir.join("pubspec_overrides.yaml")).unwrap();
+        assert_eq!(
+            content,
+            "# Generated by melos-rs. Do not edit.\n\
+             dependency_overrides:\n  \
+             core:\n    path: ../core\n  \
+             http:\n    git:\n      url: https://github.com/me/http.git\n      ref: fix\n  \
+             shared: ^2.0.0\n"
+        );
+        // Packages without sibling deps still receive the explicit overrides
+        let core_content =
+            std::fs::read_to_string(core_dir.join("pubspec_overrides.yaml")).unwrap();
+        assert!(core_content.contains("  http:\n    git:"));
+        assert!(!core_content.contains("path:"));
+    }
+
+    #[test]
+    fn test_generate_pubspec_overrides_explicit_skips_workspace_resolution() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let app_dir = dir.path().join("packages").join("app");
+        std::fs::create_dir_all(&app_dir).unwrap();
+        let mut app = make_package("app", &app_dir.to_string_lossy(), vec![]);
+        app.resolution = Some("workspace".to_string());
+
+        let explicit: BTreeMap<String, yaml_serde::Value> =
+            yaml_serde::from_str("http: ^1.0.0\n").unwrap();
+        let result = generate_pubspec_overrides(&[app], &[], &[], &explicit, dir.path()).unwrap();
+        assert_eq!(result.generated, 0);
+        assert!(!app_dir.join("pubspec_overrides.yaml").exists());
+    }
+
     // -- sync_yaml_section tests --
 
     #[test]
@@ -1049,6 +1740,7 @@ mod tests {
             dependencies: vec!["http".to_string(), "intl".to_string()],
             dev_dependencies: vec!["test".to_string()],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1079,16 +1771,20 @@ mod tests {
                     version: None,
                     bootstrap: Some(BootstrapCommandConfig {
                         run_pub_get_in_parallel: None,
+                        concurrency: None,
                         enforce_versions_for_dependency_resolution: None,
                         enforce_lockfile: None,
                         run_pub_get_offline: None,
                         dependency_override_paths: None,
+                        dependency_overrides: None,
                         environment: Some(shared_env),
                         dependencies: Some(shared_deps),
                         dev_dependencies: Some(shared_dev_deps),
+                        verify_links: None,
                         hooks: None,
                     }),
                     build: None,
+                    exec: None,
                     clean: None,
                     publish: None,
                     test: None,
@@ -1098,10 +1794,14 @@ mod tests {
                 categories: HashMap::new(),
                 use_root_as_package: None,
                 discover_nested_workspaces: None,
+                respect_gitignore: None,
+                env: HashMap::new(),
             },
             packages: vec![app.clone()],
             sdk_path: None,
             warnings: vec![],
+            overridden_fields: vec![],
+            color: Default::default(),
         };
 
         let result = sync_shared_dependencies(&[app], &ws).unwrap();
@@ -1150,6 +1850,7 @@ mod tests {
             dependencies: vec!["http".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1179,6 +1880,7 @@ mod tests {
             dependencies: vec!["core".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: Some("workspace".to_string()),
         };
 
@@ -1186,7 +1888,7 @@ mod tests {
         std::fs::create_dir_all(&core_dir).unwrap();
         let core = make_package("core", &core_dir.to_string_lossy(), vec![]);
 
-        let result = generate_pubspec_overrides(&[app], &[core], &[], dir.path());
+        let result = generate_pubspec_overrides(&[app], &[core], &[], &BTreeMap::new(), dir.path());
         assert!(result.is_ok());
 
         let overrides_path = app_dir.join("pubspec_overrides.yaml");
@@ -1212,6 +1914,7 @@ mod tests {
             dependencies: vec!["core".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: Some("workspace".to_string()),
         };
 
@@ -1227,6 +1930,7 @@ mod tests {
             dependencies: vec!["core".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1234,7 +1938,13 @@ mod tests {
         std::fs::create_dir_all(&core_dir).unwrap();
         let core = make_package("core", &core_dir.to_string_lossy(), vec![]);
 
-        let result = generate_pubspec_overrides(&[app, legacy_app], &[core], &[], dir.path());
+        let result = generate_pubspec_overrides(
+            &[app, legacy_app],
+            &[core],
+            &[],
+            &BTreeMap::new(),
+            dir.path(),
+        );
         assert!(result.is_ok());
 
         assert!(
@@ -1250,6 +1960,108 @@ mod tests {
         assert!(content.contains("core:"));
     }
 
+    // -- validate_pubspec_overrides tests --
+
+    #[test]
+    fn test_validate_pubspec_overrides_reports_drift_without_writing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg_dir = |name: &str| {
+            let path = dir.path().join("packages").join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            path
+        };
+        let core = make_package("core", &pkg_dir("core").to_string_lossy(), vec![]);
+        let app = make_package("app", &pkg_dir("app").to_string_lossy(), vec!["core"]);
+        let cli = make_package("cli", &pkg_dir("cli").to_string_lossy(), vec!["core"]);
+        let mut modern = make_package("modern", &pkg_dir("modern").to_string_lossy(), vec![]);
+        modern.resolution = Some("workspace".to_string());
+        let packages = vec![core, app, cli, modern];
+
+        generate_pubspec_overrides(&packages, &packages, &[], &BTreeMap::new(), dir.path())
+            .unwrap();
+        let (drifts, _) = validate_pubspec_overrides(
+            &packages,
+            &packages,
+            &[],
+            &BTreeMap::new(),
+            dir.path(),
+            true,
+        )
+        .unwrap();
+        assert!(drifts.is_empty(), "freshly generated files are up to date");
+
+        let app_overrides = dir.path().join("packages/app/pubspec_overrides.yaml");
+        let edited = std::fs::read_to_string(&app_overrides)
+            .unwrap()
+            .replace("path: ../core", "path: ../../core");
+        std::fs::write(&app_overrides, &edited).unwrap();
+        std::fs::remove_file(dir.path().join("packages/cli/pubspec_overrides.yaml")).unwrap();
+        std::fs::write(
+            dir.path().join("packages/core/pubspec_overrides.yaml"),
+            "x\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("packages/modern/pubspec_overrides.yaml"),
+            "y\n",
+        )
+        .unwrap();
+
+        let (drifts, _) = validate_pubspec_overrides(
+            &packages,
+            &packages,
+            &[],
+            &BTreeMap::new(),
+            dir.path(),
+            true,
+        )
+        .unwrap();
+        let kinds: Vec<(&str, &OverridesDriftKind)> = drifts
+            .iter()
+            .map(|d| (d.package.as_str(), &d.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("modern", &OverridesDriftKind::WorkspaceResolution),
+                ("core", &OverridesDriftKind::Unexpected),
+                ("app", &OverridesDriftKind::Stale),
+                ("cli", &OverridesDriftKind::Missing),
+            ]
+        );
+        assert_eq!(
+            drifts[2].diff,
+            vec![
+                " # Generated by melos-rs. Do not edit.",
+                " dependency_overrides:",
+                "   core:",
+                "-    path: ../../core",
+                "+    path: ../core",
+            ]
+        );
+        assert_eq!(drifts[1].diff, vec!["-x"]);
+        // Nothing was rewritten
+        assert_eq!(std::fs::read_to_string(&app_overrides).unwrap(), edited);
+        assert!(
+            !dir.path()
+                .join("packages/cli/pubspec_overrides.yaml")
+                .exists()
+        );
+
+        // pubspec.yaml workspaces only check workspace-resolution packages
+        let (drifts, _) = validate_pubspec_overrides(
+            &packages,
+            &packages,
+            &[],
+            &BTreeMap::new(),
+            dir.path(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].package, "modern");
+    }
+
     #[test]
     fn test_bootstrap_opts_struct_construction() {
         let opts = BootstrapOpts {
@@ -1277,4 +2089,110 @@ mod tests {
         assert!(!opts.no_example);
         assert!(!opts.offline);
     }
+
+    fn write_package_config(dir: &Path, entries: &[(&str, &str)]) {
+        let packages: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(name, uri)| {
+                serde_json::json!({"name": name, "rootUri": uri, "packageUri": "lib/"})
+            })
+            .collect();
+        let config = serde_json::json!({"configVersion": 2, "packages": packages});
+        std::fs::create_dir_all(dir.join(".dart_tool")).unwrap();
+        std::fs::write(dir.join(PACKAGE_CONFIG_PATH), config.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_links_flags_pub_cache_resolution() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let mut app = make_package("app", "", vec!["core", "utils", "http"]);
+        app.path = root.join("packages/app");
+        let mut core = make_package("core", "", vec![]);
+        core.path = root.join("packages/core");
+        let mut utils = make_package("utils", "", vec![]);
+        utils.path = root.join("packages/utils");
+        write_package_config(
+            &app.path,
+            &[
+                ("core", "../../core"),
+                (
+                    "utils",
+                    "file:///home/dev/.pub-cache/hosted/pub.dev/utils-1.0.0",
+                ),
+                (
+                    "http",
+                    "file:///home/dev/.pub-cache/hosted/pub.dev/http-1.2.0",
+                ),
+            ],
+        );
+        let workspace_packages = vec![app.clone(), core, utils];
+
+        let issues = verify_links(&[app], &workspace_packages, root).unwrap();
+        // `http` is not a workspace package, so only `utils` is reported
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].dependency, "utils");
+        assert!(issues[0].is_pub_cache());
+        assert_eq!(
+            issues[0].to_string(),
+            "app: 'utils' resolved to file:///home/dev/.pub-cache/hosted/pub.dev/utils-1.0.0 (pub cache)"
+        );
+    }
+
+    #[test]
+    fn test_verify_links_accepts_absolute_local_uris_and_skips_missing_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let mut app = make_package("app", "", vec!["core"]);
+        app.path = root.join("app");
+        let mut core = make_package("core", "", vec![]);
+        core.path = root.join("core");
+        let uri = format!("file://{}/", core.path.display());
+        write_package_config(&app.path, &[("core", &uri)]);
+        let workspace_packages = vec![app.clone(), core.clone()];
+
+        let issues = verify_links(&[app, core], &workspace_packages, root).unwrap();
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("/my%20ws/core"), "/my ws/core");
+        assert_eq!(percent_decode("/100%"), "/100%");
+    }
+
+    #[test]
+    fn test_bootstrap_hash_round_trip_and_invalidation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut pkg = make_package("core", "", vec![]);
+        pkg.path = dir.path().to_path_buf();
+        std::fs::write(pkg.path.join("pubspec.yaml"), "name: core\n").unwrap();
+
+        let hash = bootstrap_hash(&pkg, "name: ws\n").unwrap();
+        assert_eq!(hash.len(), 16);
+        assert_eq!(hash, bootstrap_hash(&pkg, "name: ws\n").unwrap());
+        assert!(!is_bootstrap_unchanged(&pkg, &hash));
+
+        write_bootstrap_hash(&pkg, &hash).unwrap();
+        assert!(is_bootstrap_unchanged(&pkg, &hash));
+
+        // A config change (e.g. shared dependencies) invalidates the package
+        let config_changed = bootstrap_hash(&pkg, "name: ws\nshared: 2\n").unwrap();
+        assert!(!is_bootstrap_unchanged(&pkg, &config_changed));
+
+        std::fs::write(
+            pkg.path.join("pubspec.yaml"),
+            "name: core\nversion: 2.0.0\n",
+        )
+        .unwrap();
+        let pubspec_changed = bootstrap_hash(&pkg, "name: ws\n").unwrap();
+        assert_ne!(pubspec_changed, hash);
+
+        std::fs::write(
+            pkg.path.join("pubspec_overrides.yaml"),
+            "dependency_overrides:\n",
+        )
+        .unwrap();
+        assert_ne!(bootstrap_hash(&pkg, "name: ws\n").unwrap(), pubspec_changed);
+    }
 }
@@ -880,6 +880,7 @@ mod tests {
                 simulator: ios_sim,
             }),
             package_filters: None,
+            concurrency: None,
             hooks: None,
         }
     }
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+
+use crate::package::Package;
+
+/// Directory, relative to the workspace root and each package, holding the
+/// cache files melos-rs writes.
+const CACHE_DIR: &str = ".dart_tool";
+
+/// File name prefix of every melos-rs cache file, e.g.
+/// `melos_analyze_cache.json` and `melos_bootstrap_hash`.
+pub const CACHE_FILE_PREFIX: &str = "melos_";
+
+/// A melos-rs cache file found in the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Find the melos-rs cache files under the workspace root and each package.
+///
+/// Only regular files in `.dart_tool/` whose name starts with
+/// [`CACHE_FILE_PREFIX`] count; everything else `.dart_tool/` holds belongs
+/// to Dart. Sorted by path.
+pub fn find_cache_files(root: &Path, packages: &[Package]) -> Result<Vec<CacheFile>> {
+    let mut dirs: Vec<PathBuf> = std::iter::once(root)
+        .chain(packages.iter().map(|p| p.path.as_path()))
+        .map(|dir| dir.join(CACHE_DIR))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+
+    let mut files = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let is_cache = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(CACHE_FILE_PREFIX));
+            if !is_cache {
+                continue;
+            }
+            let metadata = entry
+                .metadata()
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            if metadata.is_file() {
+                files.push(CacheFile {
+                    path: entry.path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().ok(),
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Delete `files`, returning the number of bytes freed.
+pub fn remove_cache_files(files: &[CacheFile]) -> Result<u64> {
+    let mut freed = 0;
+    for file in files {
+        std::fs::remove_file(&file.path)
+            .with_context(|| format!("Failed to remove {}", file.path.display()))?;
+        freed += file.size;
+    }
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_find_and_remove_cache_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        let pkg_dir = root.join("packages/core");
+        std::fs::create_dir_all(root.join(".dart_tool")).unwrap();
+        std::fs::create_dir_all(pkg_dir.join(".dart_tool/melos_dir")).unwrap();
+        std::fs::write(root.join(".dart_tool/melos_analyze_cache.json"), "{}").unwrap();
+        std::fs::write(root.join(".dart_tool/melos.lock"), "").unwrap();
+        std::fs::write(root.join(".dart_tool/package_config.json"), "{}").unwrap();
+        std::fs::write(pkg_dir.join(".dart_tool/melos_bootstrap_hash"), "0123").unwrap();
+        let core = Package {
+            name: "core".to_string(),
+            path: pkg_dir.clone(),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        };
+        // A root package shares the root's .dart_tool
+        let root_pkg = Package {
+            name: "ws".to_string(),
+            path: root.to_path_buf(),
+            ..core.clone()
+        };
+
+        let files = find_cache_files(root, &[root_pkg, core]).unwrap();
+        let paths: Vec<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                root.join(".dart_tool/melos_analyze_cache.json").as_path(),
+                pkg_dir.join(".dart_tool/melos_bootstrap_hash").as_path(),
+            ]
+        );
+        assert_eq!(files[1].size, 4);
+        assert!(files[0].modified.is_some());
+
+        assert_eq!(remove_cache_files(&files).unwrap(), 6);
+        assert!(find_cache_files(root, &[]).unwrap().is_empty());
+        // Dart's own files and the lock are untouched
+        assert!(root.join(".dart_tool/melos.lock").exists());
+        assert!(root.join(".dart_tool/package_config.json").exists());
+        assert!(pkg_dir.join(".dart_tool/melos_dir").is_dir());
+    }
+}
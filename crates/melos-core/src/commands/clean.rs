@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::time::Instant;
 
 use anyhow::Result;
@@ -16,12 +17,60 @@ pub struct CleanOpts {
     pub concurrency: usize,
 }
 
-/// Paths removed during a deep clean.
+/// Directories removed from pure Dart packages, and from every package during
+/// a deep clean.
 pub const DEEP_CLEAN_DIRS: &[&str] = &[".dart_tool", "build"];
 
 /// Files removed during a deep clean.
 pub const DEEP_CLEAN_FILES: &[&str] = &["pubspec.lock"];
 
+/// Artifacts `flutter clean` removes from Flutter packages.
+pub const FLUTTER_CLEAN_PATHS: &[&str] = &[
+    "build",
+    ".dart_tool",
+    ".flutter-plugins",
+    ".flutter-plugins-dependencies",
+    "ios/Flutter/ephemeral",
+    "macos/Flutter/ephemeral",
+    "linux/flutter/ephemeral",
+    "windows/flutter/ephemeral",
+];
+
+/// Environment variable telling clean hooks whether this is a dry run
+/// (`true` or `false`).
+pub const CLEAN_DRY_RUN_ENV: &str = "MELOS_CLEAN_DRY_RUN";
+
+/// Absolute paths that cleaning `pkg` would remove, in removal order.
+///
+/// Mirrors the clean steps: `pubspec_overrides.yaml` in 6.x (`legacy`) mode,
+/// then the Flutter or Dart artifacts, then the deep-clean paths when `deep`
+/// is set. Only paths that currently exist are returned, each once.
+pub fn clean_targets(pkg: &Package, legacy: bool, deep: bool) -> Vec<PathBuf> {
+    let artifacts = if pkg.is_flutter {
+        FLUTTER_CLEAN_PATHS
+    } else {
+        DEEP_CLEAN_DIRS
+    };
+    let legacy_files: &[&str] = if legacy {
+        &["pubspec_overrides.yaml"]
+    } else {
+        &[]
+    };
+    let deep_paths = deep
+        .then_some(DEEP_CLEAN_DIRS.iter().chain(DEEP_CLEAN_FILES))
+        .into_iter()
+        .flatten();
+
+    let mut targets: Vec<PathBuf> = Vec::new();
+    for rel in legacy_files.iter().chain(artifacts).chain(deep_paths) {
+        let path = pkg.path.join(rel);
+        if path.exists() && !targets.contains(&path) {
+            targets.push(path);
+        }
+    }
+    targets
+}
+
 /// Run clean across packages.
 ///
 /// Flutter packages are cleaned via `flutter clean` through the [`ProcessRunner`].
@@ -38,7 +87,7 @@ pub async fn run(
     let flutter_pkgs: Vec<_> = packages.iter().filter(|p| p.is_flutter).cloned().collect();
     let dart_pkgs: Vec<_> = packages.iter().filter(|p| !p.is_flutter).cloned().collect();
 
-    let mut all_results = Vec::new();
+    let mut all_results = PackageResults::default();
 
     // Flutter packages: run `flutter clean` via ProcessRunner.
     if !flutter_pkgs.is_empty() {
@@ -99,7 +148,9 @@ pub async fn run(
                             if let Some(tx) = events {
                                 let _ = tx.send(Event::PackageOutput {
                                     name: pkg.name.clone(),
-                                    line: format!("failed to remove {dir_name}/: {e}"),
+                                    line: crate::redact::redact_output(format!(
+                                        "failed to remove {dir_name}/: {e}"
+                                    )),
                                     is_stderr: true,
                                 });
                             }
@@ -121,11 +172,11 @@ pub async fn run(
                     message: "cleaning dart packages...".into(),
                 });
             }
-            all_results.push((pkg.name.clone(), success));
+            all_results.results.push((pkg.name.clone(), success));
         }
     }
 
-    Ok(PackageResults::from(all_results))
+    Ok(all_results)
 }
 
 /// Result of attempting to remove a `pubspec_overrides.yaml` from a single package.
@@ -176,6 +227,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -193,6 +245,52 @@ mod tests {
         assert_eq!(DEEP_CLEAN_FILES.len(), 1);
     }
 
+    #[test]
+    fn test_clean_targets_lists_existing_paths_without_removing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg_dir = dir.path().join("app");
+        std::fs::create_dir_all(pkg_dir.join(".dart_tool")).unwrap();
+        std::fs::write(pkg_dir.join("pubspec.lock"), "").unwrap();
+        std::fs::write(pkg_dir.join("pubspec_overrides.yaml"), "").unwrap();
+        let pkg = make_package("app", pkg_dir.clone());
+
+        assert_eq!(
+            clean_targets(&pkg, false, false),
+            vec![pkg_dir.join(".dart_tool")]
+        );
+        // Deep clean adds pubspec.lock once; .dart_tool is not repeated
+        assert_eq!(
+            clean_targets(&pkg, true, true),
+            vec![
+                pkg_dir.join("pubspec_overrides.yaml"),
+                pkg_dir.join(".dart_tool"),
+                pkg_dir.join("pubspec.lock"),
+            ]
+        );
+        assert!(pkg_dir.join(".dart_tool").exists());
+        assert!(pkg_dir.join("pubspec.lock").exists());
+    }
+
+    #[test]
+    fn test_clean_targets_flutter_artifacts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg_dir = dir.path().join("ui");
+        std::fs::create_dir_all(pkg_dir.join("build")).unwrap();
+        std::fs::create_dir_all(pkg_dir.join("ios/Flutter/ephemeral")).unwrap();
+        std::fs::write(pkg_dir.join(".flutter-plugins"), "").unwrap();
+        let mut pkg = make_package("ui", pkg_dir.clone());
+        pkg.is_flutter = true;
+
+        assert_eq!(
+            clean_targets(&pkg, false, false),
+            vec![
+                pkg_dir.join("build"),
+                pkg_dir.join(".flutter-plugins"),
+                pkg_dir.join("ios/Flutter/ephemeral"),
+            ]
+        );
+    }
+
     #[test]
     fn test_remove_pubspec_overrides_removes_existing() {
         let dir = tempfile::TempDir::new().unwrap();
@@ -295,10 +393,14 @@ mod tests {
                 categories: HashMap::new(),
                 use_root_as_package: None,
                 discover_nested_workspaces: None,
+                respect_gitignore: None,
+                env: HashMap::new(),
             },
             packages: vec![pkg.clone()],
             sdk_path: None,
             warnings: vec![],
+            overridden_fields: vec![],
+            color: Default::default(),
         };
 
         let opts = CleanOpts { concurrency: 1 };
@@ -0,0 +1,353 @@
+use anyhow::Result;
+
+use crate::commands::bootstrap::PUBSPEC_OVERRIDES;
+use crate::commands::selftest::detect_tool_version;
+use crate::workspace::Workspace;
+
+/// Environment variable consulted for the SDK path when no workspace loaded.
+const SDK_PATH_ENV: &str = "MELOS_SDK_PATH";
+
+// ---------------------------------------------------------------------------
+// Result types
+// ---------------------------------------------------------------------------
+
+/// Outcome of a single doctor check.
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Something is off, but melos-rs can still run
+    Warning,
+    /// A critical problem: the command exits non-zero
+    Failed,
+}
+
+/// A single environment check with its remediation hint.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was found, e.g. the tool version or the failing packages
+    pub detail: String,
+    /// How to fix a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+}
+
+/// All checks run by `doctor`, in display order.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// Number of critical checks that failed.
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Failed)
+            .count()
+    }
+
+    /// Number of checks that passed with a warning.
+    pub fn warnings(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warning)
+            .count()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Checks
+// ---------------------------------------------------------------------------
+
+/// Check the environment melos-rs runs in.
+///
+/// Covers the Dart and Flutter SDKs, git, the workspace config and whether
+/// `pubspec_overrides.yaml` files match each package's resolution mode.
+/// Never fails itself: a workspace that could not be loaded becomes a failed
+/// check. Read-only.
+pub fn run_checks(loaded: &Result<Workspace>, sdk_path_override: Option<&str>) -> DoctorReport {
+    let sdk_path = match loaded {
+        Ok(ws) => ws.sdk_path.clone(),
+        Err(_) => sdk_path_override
+            .map(String::from)
+            .or_else(|| std::env::var(SDK_PATH_ENV).ok()),
+    };
+    let needs_flutter = loaded
+        .as_ref()
+        .is_ok_and(|ws| ws.packages.iter().any(|p| p.is_flutter));
+
+    let mut checks = vec![
+        check_dart(sdk_path.as_deref()),
+        check_flutter(sdk_path.as_deref(), needs_flutter),
+        check_git(),
+        check_config(loaded),
+    ];
+    if let Ok(ws) = loaded {
+        checks.push(check_pubspec_overrides(ws));
+    }
+    DoctorReport { checks }
+}
+
+fn sdk_location(sdk_path: Option<&str>) -> String {
+    match sdk_path {
+        Some(path) => format!(" (from {path}/bin)"),
+        None => " (on PATH)".to_string(),
+    }
+}
+
+fn check_dart(sdk_path: Option<&str>) -> DoctorCheck {
+    match detect_tool_version("dart", sdk_path) {
+        Some(version) => DoctorCheck::new(
+            "Dart SDK",
+            CheckStatus::Ok,
+            format!("{version}{}", sdk_location(sdk_path)),
+        ),
+        None => DoctorCheck::new("Dart SDK", CheckStatus::Failed, "`dart` not found").with_hint(
+            "Install the Dart SDK and add it to PATH, or point --sdk-path, MELOS_SDK_PATH or `sdkPath` at it",
+        ),
+    }
+}
+
+fn check_flutter(sdk_path: Option<&str>, needs_flutter: bool) -> DoctorCheck {
+    match detect_tool_version("flutter", sdk_path) {
+        Some(version) => DoctorCheck::new(
+            "Flutter SDK",
+            CheckStatus::Ok,
+            format!("{version}{}", sdk_location(sdk_path)),
+        ),
+        None if needs_flutter => DoctorCheck::new(
+            "Flutter SDK",
+            CheckStatus::Failed,
+            "`flutter` not found, but the workspace has Flutter packages",
+        )
+        .with_hint("Install Flutter and add its bin directory to PATH"),
+        None => DoctorCheck::new(
+            "Flutter SDK",
+            CheckStatus::Ok,
+            "not found (not needed: no Flutter packages)",
+        ),
+    }
+}
+
+fn check_git() -> DoctorCheck {
+    match detect_tool_version("git", None) {
+        Some(version) => DoctorCheck::new("git", CheckStatus::Ok, version),
+        None => DoctorCheck::new("git", CheckStatus::Warning, "`git` not found")
+            .with_hint("Install git; --diff filters, `version` and `publish` need it"),
+    }
+}
+
+fn check_config(loaded: &Result<Workspace>) -> DoctorCheck {
+    match loaded {
+        Err(e) => DoctorCheck::new("Config", CheckStatus::Failed, format!("{e:#}")).with_hint(
+            "Run from inside a workspace, or fix the error in melos.yaml / pubspec.yaml",
+        ),
+        Ok(ws) if ws.warnings.is_empty() => DoctorCheck::new(
+            "Config",
+            CheckStatus::Ok,
+            format!(
+                "{} parsed, {} package(s)",
+                ws.config_source.path().display(),
+                ws.packages.len()
+            ),
+        ),
+        Ok(ws) => DoctorCheck::new(
+            "Config",
+            CheckStatus::Warning,
+            format!(
+                "{} warning(s): {}",
+                ws.warnings.len(),
+                ws.warnings.join("; ")
+            ),
+        )
+        .with_hint("Resolve the warnings in the workspace config"),
+    }
+}
+
+/// Compare `pubspec_overrides.yaml` files with each package's resolution.
+///
+/// Packages using `resolution: workspace` must not carry one. In `melos.yaml`
+/// (6.x) mode, other packages with workspace dependencies need one to link
+/// their siblings.
+fn check_pubspec_overrides(ws: &Workspace) -> DoctorCheck {
+    let mut stale = Vec::new();
+    let mut missing = Vec::new();
+    for pkg in &ws.packages {
+        let has_overrides = pkg.path.join(PUBSPEC_OVERRIDES).is_file();
+        if pkg.uses_workspace_resolution() {
+            if has_overrides {
+                stale.push(pkg.name.as_str());
+            }
+            continue;
+        }
+        let links_siblings = pkg
+            .dependencies
+            .iter()
+            .chain(pkg.dev_dependencies.iter())
+            .any(|dep| ws.packages.iter().any(|p| &p.name == dep));
+        if ws.config_source.is_legacy() && links_siblings && !has_overrides {
+            missing.push(pkg.name.as_str());
+        }
+    }
+
+    if stale.is_empty() && missing.is_empty() {
+        return DoctorCheck::new(
+            "pubspec_overrides.yaml",
+            CheckStatus::Ok,
+            "consistent with each package's resolution",
+        );
+    }
+
+    let mut details = Vec::new();
+    let mut hints = Vec::new();
+    if !stale.is_empty() {
+        details.push(format!(
+            "present in `resolution: workspace` package(s): {}",
+            stale.join(", ")
+        ));
+        hints.push("delete pubspec_overrides.yaml from workspace-resolution packages");
+    }
+    if !missing.is_empty() {
+        details.push(format!(
+            "missing for package(s) with workspace dependencies: {}",
+            missing.join(", ")
+        ));
+        hints.push("run `melos-rs bootstrap` to link local packages");
+    }
+    let mut check = DoctorCheck::new(
+        "pubspec_overrides.yaml",
+        CheckStatus::Warning,
+        details.join("; "),
+    );
+    check.hint = Some(capitalize(&hints.join(", then ")));
+    check
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    fn write_workspace(dir: &std::path::Path, app_pubspec: &str) -> Workspace {
+        fs::write(
+            dir.join("melos.yaml"),
+            "name: ws\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        for (name, pubspec) in [
+            ("core", "name: core\nversion: 1.0.0\n"),
+            ("app", app_pubspec),
+        ] {
+            let pkg = dir.join("packages").join(name);
+            fs::create_dir_all(&pkg).unwrap();
+            fs::write(pkg.join("pubspec.yaml"), pubspec).unwrap();
+        }
+        Workspace::find_and_load_from(dir, None).unwrap()
+    }
+
+    fn overrides_check(report: &DoctorReport) -> &DoctorCheck {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == "pubspec_overrides.yaml")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_run_checks_without_workspace_fails_config() {
+        let loaded: Result<Workspace> = Err(anyhow::anyhow!("No melos.yaml found"));
+        let report = run_checks(&loaded, Some("/nonexistent/sdk"));
+
+        let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Dart SDK", "Flutter SDK", "git", "Config"]);
+        let dart = &report.checks[0];
+        assert_eq!(dart.status, CheckStatus::Failed);
+        assert!(dart.hint.as_deref().unwrap().contains("--sdk-path"));
+        // Flutter is not required when no packages need it
+        assert_eq!(report.checks[1].status, CheckStatus::Ok);
+        let config = &report.checks[3];
+        assert_eq!(config.status, CheckStatus::Failed);
+        assert!(config.detail.contains("No melos.yaml found"));
+        assert_eq!(report.failures(), 2);
+    }
+
+    #[test]
+    fn test_pubspec_overrides_missing_in_legacy_mode() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ws = write_workspace(
+            dir.path(),
+            "name: app\nversion: 1.0.0\ndependencies:\n  core: ^1.0.0\n",
+        );
+        let report = run_checks(&Ok(ws), None);
+        let check = overrides_check(&report);
+        assert_eq!(check.status, CheckStatus::Warning);
+        assert!(
+            check
+                .detail
+                .contains("missing for package(s) with workspace dependencies: app")
+        );
+        assert!(
+            check
+                .hint
+                .as_deref()
+                .unwrap()
+                .contains("melos-rs bootstrap")
+        );
+
+        fs::write(dir.path().join("packages/app/pubspec_overrides.yaml"), "").unwrap();
+        let ws = Workspace::find_and_load_from(dir.path(), None).unwrap();
+        let report = run_checks(&Ok(ws), None);
+        assert_eq!(overrides_check(&report).status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn test_pubspec_overrides_stale_under_workspace_resolution() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ws = write_workspace(
+            dir.path(),
+            "name: app\nversion: 1.0.0\nresolution: workspace\n",
+        );
+        fs::write(dir.path().join("packages/app/pubspec_overrides.yaml"), "").unwrap();
+        let report = run_checks(&Ok(ws), None);
+        let check = overrides_check(&report);
+        assert_eq!(check.status, CheckStatus::Warning);
+        assert!(
+            check
+                .detail
+                .contains("`resolution: workspace` package(s): app")
+        );
+        assert!(check.hint.as_deref().unwrap().starts_with("Delete"));
+    }
+}
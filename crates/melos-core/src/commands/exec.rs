@@ -1,11 +1,16 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::config::filter::PackageFilters;
 use crate::events::Event;
 use crate::package::Package;
-use crate::runner::ProcessRunner;
+use crate::package::filter::apply_filters_with_categories;
+use crate::runner::{ProcessRunner, RetryPolicy};
 use crate::workspace::Workspace;
 
 use super::PackageResults;
@@ -17,6 +22,14 @@ pub struct ExecOpts {
     pub concurrency: usize,
     pub fail_fast: bool,
     pub timeout: Option<Duration>,
+    /// Retry policy for packages whose command fails
+    pub retry: RetryPolicy,
+    /// Wall-time budget for the whole run
+    pub deadline: Option<Duration>,
+    /// Report packages where the command is not found as skipped, not failed
+    pub skip_missing: bool,
+    /// Extra environment (e.g. from `--env-file`), below the `MELOS_*` vars
+    pub env: HashMap<String, String>,
 }
 
 /// Execute a shell command across packages, emitting events for progress tracking.
@@ -28,18 +41,169 @@ pub async fn run(
     opts: &ExecOpts,
     events: Option<&UnboundedSender<Event>>,
 ) -> Result<PackageResults> {
-    let runner = ProcessRunner::new(opts.concurrency, opts.fail_fast);
+    let runner = ProcessRunner::new(opts.concurrency, opts.fail_fast)
+        .with_retry(opts.retry)
+        .with_deadline(opts.deadline)
+        .with_skip_missing(opts.skip_missing);
     let results = runner
         .run_in_packages_with_events(
             packages,
             &opts.command,
-            &workspace.env_vars(),
+            &workspace.env_vars_with(&opts.env),
             opts.timeout,
             events,
             &workspace.packages,
         )
         .await?;
-    Ok(PackageResults::from(results))
+    Ok(results)
+}
+
+/// Workspace-root file recording which packages succeeded in the last
+/// `exec` run, for `--resume`.
+pub const LAST_RUN_PATH: &str = ".dart_tool/melos_last_run.json";
+
+/// Packages that succeeded in the last run of `command`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastRunManifest {
+    /// The command string the run executed; `--resume` only applies to
+    /// the same command
+    pub command: String,
+    pub succeeded: BTreeSet<String>,
+}
+
+impl LastRunManifest {
+    /// An empty manifest for a fresh run of `command`.
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            succeeded: BTreeSet::new(),
+        }
+    }
+
+    /// Read the manifest under `root` if it records a run of `command`;
+    /// `None` when it is missing, unreadable or for another command.
+    pub fn load(root: &Path, command: &str) -> Option<Self> {
+        std::fs::read_to_string(root.join(LAST_RUN_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|manifest| manifest.command == command)
+    }
+
+    /// Write the manifest under `root`.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(LAST_RUN_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Mark the packages of `results` as succeeded or not.
+    pub fn record(&mut self, results: &PackageResults) {
+        for (name, success) in &results.results {
+            if *success {
+                self.succeeded.insert(name.clone());
+            } else {
+                self.succeeded.remove(name);
+            }
+        }
+    }
+}
+
+/// The packages from `from` onwards, keeping the order of `packages`.
+///
+/// Errors when `from` is not one of `packages`.
+pub fn packages_from(packages: &[Package], from: &str) -> Result<Vec<Package>> {
+    let Some(start) = packages.iter().position(|p| p.name == from) else {
+        bail!(
+            "Package '{}' passed to --from is not among the selected packages",
+            from
+        );
+    };
+    Ok(packages[start..].to_vec())
+}
+
+/// Concurrency used by [`CommandSpec::new`].
+const DEFAULT_SPEC_CONCURRENCY: usize = 5;
+
+/// A shell command to run across a workspace, selected by package filters.
+///
+/// The entry point for embedding melos-core in other tools; see
+/// [`run_command`].
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub command: String,
+    /// Packages to run in; the default selects every workspace package
+    pub filters: PackageFilters,
+    pub concurrency: usize,
+    pub fail_fast: bool,
+    /// Start a package only after the packages it depends on succeeded
+    pub order_dependents: bool,
+    pub timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+}
+
+impl CommandSpec {
+    /// Run `command` in every package, 5 at a time, without fail-fast.
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            filters: PackageFilters::default(),
+            concurrency: DEFAULT_SPEC_CONCURRENCY,
+            fail_fast: false,
+            order_dependents: false,
+            timeout: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// Run `spec` across the workspace packages matching its filters.
+///
+/// Nothing is printed: progress and output are only sent as [`Event`]s on
+/// `events`, for the caller to render. Returns per-package results, in
+/// completion order.
+pub async fn run_command(
+    workspace: &Workspace,
+    spec: &CommandSpec,
+    events: Option<&UnboundedSender<Event>>,
+) -> Result<PackageResults> {
+    let packages = apply_filters_with_categories(
+        &workspace.packages,
+        &spec.filters,
+        Some(&workspace.root_path),
+        &workspace.config.categories,
+    )?;
+    let runner = ProcessRunner::new(spec.concurrency, spec.fail_fast).with_retry(spec.retry);
+    let env = workspace.env_vars();
+    let results = if spec.order_dependents {
+        runner
+            .run_in_packages_ordered(
+                &packages,
+                &spec.command,
+                &env,
+                spec.timeout,
+                events,
+                &workspace.packages,
+            )
+            .await?
+    } else {
+        runner
+            .run_in_packages_with_events(
+                &packages,
+                &spec.command,
+                &env,
+                spec.timeout,
+                events,
+                &workspace.packages,
+            )
+            .await?
+    };
+    Ok(results)
 }
 
 #[cfg(test)]
@@ -53,6 +217,10 @@ mod tests {
             concurrency: 5,
             fail_fast: false,
             timeout: None,
+            retry: RetryPolicy::default(),
+            deadline: None,
+            skip_missing: false,
+            env: HashMap::new(),
         };
         assert_eq!(opts.command, "echo hello");
         assert_eq!(opts.concurrency, 5);
@@ -67,8 +235,69 @@ mod tests {
             concurrency: 3,
             fail_fast: true,
             timeout: Some(Duration::from_secs(60)),
+            retry: RetryPolicy::default(),
+            deadline: None,
+            skip_missing: false,
+            env: HashMap::new(),
         };
         assert_eq!(opts.timeout, Some(Duration::from_secs(60)));
         assert!(opts.fail_fast);
     }
+
+    fn package(name: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/ws/packages/{name}")),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_last_run_manifest_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(LastRunManifest::load(dir.path(), "dart test").is_none());
+
+        let mut manifest = LastRunManifest::new("dart test");
+        manifest.record(&PackageResults::from(vec![
+            ("a".to_string(), true),
+            ("b".to_string(), false),
+        ]));
+        manifest.save(dir.path()).unwrap();
+
+        let mut loaded = LastRunManifest::load(dir.path(), "dart test").unwrap();
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.succeeded, BTreeSet::from(["a".to_string()]));
+        // Another command starts from scratch
+        assert!(LastRunManifest::load(dir.path(), "dart analyze").is_none());
+
+        // A later failure clears an earlier success
+        loaded.record(&PackageResults::from(vec![
+            ("a".to_string(), false),
+            ("b".to_string(), true),
+        ]));
+        assert_eq!(loaded.succeeded, BTreeSet::from(["b".to_string()]));
+    }
+
+    #[test]
+    fn test_packages_from() {
+        let packages = vec![package("a"), package("b"), package("c")];
+        let names = |pkgs: Vec<Package>| pkgs.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(packages_from(&packages, "b").unwrap()),
+            vec!["b", "c"]
+        );
+        assert_eq!(
+            names(packages_from(&packages, "a").unwrap()),
+            vec!["a", "b", "c"]
+        );
+        let err = packages_from(&packages, "zzz").unwrap_err();
+        assert!(err.to_string().contains("'zzz' passed to --from"));
+    }
 }
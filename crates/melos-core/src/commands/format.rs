@@ -43,6 +43,17 @@ pub fn build_format_command(
     cmd_parts.join(" ")
 }
 
+/// Files `dart format` reported as changed (or needing changes), from its
+/// `Changed <path>` lines, relative to the package root.
+pub fn parse_changed_files(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Changed "))
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
 /// Run `dart format` across packages, emitting events for progress tracking.
 ///
 /// Returns [`PackageResults`] with per-package success/failure status.
@@ -64,7 +75,7 @@ pub async fn run(
             &workspace.packages,
         )
         .await?;
-    Ok(PackageResults::from(results))
+    Ok(results)
 }
 
 #[cfg(test)]
@@ -110,6 +121,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_changed_files() {
+        let output = "Changed lib/src/a.dart\nChanged test/a_test.dart\n\
+                      Formatted 5 files (2 changed) in 0.12 seconds.\n";
+        assert_eq!(
+            parse_changed_files(output),
+            vec!["lib/src/a.dart", "test/a_test.dart"]
+        );
+        assert!(parse_changed_files("Formatted 3 files (0 changed) in 0.05 seconds.").is_empty());
+    }
+
     #[test]
     fn test_build_format_command_write_output_not_added() {
         // "write" is the default and should not be added to the command
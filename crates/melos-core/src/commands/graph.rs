@@ -0,0 +1,161 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::package::Package;
+
+/// A workspace package and its edges to other workspace packages.
+///
+/// Serializes as one entry of the JSON adjacency list.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub name: String,
+    pub version: Option<String>,
+    pub private: bool,
+    /// Workspace packages listed under `dependencies`, sorted by name
+    pub dependencies: Vec<String>,
+    /// Workspace packages listed under `dev_dependencies`, sorted by name
+    pub dev_dependencies: Vec<String>,
+}
+
+/// Build the intra-workspace dependency graph, sorted by package name.
+///
+/// Edges to packages outside `packages` (hosted/SDK dependencies, or
+/// workspace packages excluded by filters) are dropped.
+pub fn build_graph(packages: &[Package]) -> Vec<GraphNode> {
+    let known: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    let internal = |deps: &[String]| -> Vec<String> {
+        deps.iter()
+            .filter(|d| known.contains(d.as_str()))
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    };
+
+    let mut nodes: Vec<GraphNode> = packages
+        .iter()
+        .map(|pkg| GraphNode {
+            name: pkg.name.clone(),
+            version: pkg.version.clone(),
+            private: pkg.is_private(),
+            dependencies: internal(&pkg.dependencies),
+            dev_dependencies: internal(&pkg.dev_dependencies),
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    nodes
+}
+
+/// Render the graph as Graphviz DOT.
+///
+/// Private packages are drawn dashed, and `dev_dependencies` edges are dashed
+/// and labelled `dev`.
+pub fn generate_dot(nodes: &[GraphNode]) -> String {
+    let mut lines = Vec::new();
+
+    lines.push("digraph packages {".to_string());
+    lines.push("  rankdir=LR;".to_string());
+    lines.push("  node [shape=box];".to_string());
+
+    for node in nodes {
+        let label = match node.version {
+            Some(ref v) => format!("{}\\n{}", node.name, v),
+            None => node.name.clone(),
+        };
+        let style = if node.private { ", style=dashed" } else { "" };
+        lines.push(format!(
+            "  \"{}\" [label=\"{}\"{}];",
+            node.name, label, style
+        ));
+    }
+
+    for node in nodes {
+        for dep in &node.dependencies {
+            lines.push(format!("  \"{}\" -> \"{}\";", node.name, dep));
+        }
+        for dep in &node.dev_dependencies {
+            lines.push(format!(
+                "  \"{}\" -> \"{}\" [style=dashed, label=\"dev\"];",
+                node.name, dep
+            ));
+        }
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn make_pkg(name: &str, deps: &[&str], dev_deps: &[&str]) -> Package {
+        Package {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/workspace/packages/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            dev_dependencies: dev_deps.iter().map(|d| d.to_string()).collect(),
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_build_graph_sorted_internal_edges() {
+        let mut app = make_pkg("app", &["utils", "http", "core"], &["test_utils"]);
+        app.publish_to = Some("none".to_string());
+        let packages = vec![
+            app,
+            make_pkg("utils", &["core"], &[]),
+            make_pkg("test_utils", &[], &[]),
+            make_pkg("core", &[], &["test_utils"]),
+        ];
+
+        let graph = build_graph(&packages);
+        let names: Vec<&str> = graph.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "core", "test_utils", "utils"]);
+
+        assert_eq!(graph[0].dependencies, vec!["core", "utils"]);
+        assert_eq!(graph[0].dev_dependencies, vec!["test_utils"]);
+        assert!(graph[0].private);
+        assert!(!graph[1].private);
+        assert_eq!(graph[1].dev_dependencies, vec!["test_utils"]);
+    }
+
+    #[test]
+    fn test_graph_json_is_stable() {
+        let forward = vec![make_pkg("a", &["b"], &[]), make_pkg("b", &[], &[])];
+        let reversed = vec![make_pkg("b", &[], &[]), make_pkg("a", &["b"], &[])];
+
+        let json = serde_json::to_string(&build_graph(&forward)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_string(&build_graph(&reversed)).unwrap()
+        );
+        assert_eq!(
+            json,
+            r#"[{"name":"a","version":"1.0.0","private":false,"dependencies":["b"],"dev_dependencies":[]},{"name":"b","version":"1.0.0","private":false,"dependencies":[],"dev_dependencies":[]}]"#
+        );
+    }
+
+    #[test]
+    fn test_generate_dot_distinguishes_dev_edges() {
+        let packages = vec![
+            make_pkg("my-app", &["core"], &["fixtures"]),
+            make_pkg("core", &[], &[]),
+            make_pkg("fixtures", &[], &[]),
+        ];
+
+        let dot = generate_dot(&build_graph(&packages));
+        assert!(dot.starts_with("digraph packages {"));
+        assert!(dot.contains("  \"my-app\" [label=\"my-app\\n1.0.0\"];"));
+        assert!(dot.contains("  \"my-app\" -> \"core\";"));
+        assert!(dot.contains("  \"my-app\" -> \"fixtures\" [style=dashed, label=\"dev\"];"));
+        assert!(dot.ends_with('}'));
+    }
+}
@@ -1,5 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
 
+use anyhow::{Context, Result};
+use regex::Regex;
+use semver::Version;
+
+use crate::commands::version::constraint_allows;
+use crate::config::RepositoryConfig;
 use crate::package::Package;
 
 /// Options for the health command (clap-free).
@@ -8,8 +17,14 @@ pub struct HealthOpts {
     pub version_drift: bool,
     pub missing_fields: bool,
     pub sdk_consistency: bool,
+    pub analysis_options: bool,
     pub all: bool,
     pub json: bool,
+    /// Workspace root, whose `analysis_options.yaml` is the lint baseline
+    pub root_path: PathBuf,
+    /// Resolved SDK path whose `bin/` tools are asked for their version
+    /// during the SDK consistency check (`PATH` when `None`)
+    pub sdk_path: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -37,6 +52,14 @@ pub struct MissingFieldsIssue {
     pub missing: Vec<String>,
 }
 
+/// A pubspec field written by `health --fix`.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct FieldFix {
+    pub package: String,
+    pub field: String,
+    pub value: String,
+}
+
 /// SDK consistency results.
 #[derive(serde::Serialize, Debug, Clone, PartialEq, Default)]
 pub struct SdkConsistencyResult {
@@ -47,15 +70,99 @@ pub struct SdkConsistencyResult {
     pub flutter_sdk_drift: Vec<ConstraintUsage>,
 }
 
+/// A package whose `environment` constraint excludes the resolved SDK.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SdkIncompatibility {
+    pub package: String,
+    /// `dart` or `flutter`
+    pub sdk: String,
+    pub constraint: String,
+}
+
+/// SDK versions reported by the toolchain in use.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Default)]
+pub struct SdkVersionResult {
+    pub dart: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flutter: Option<String>,
+    pub incompatible: Vec<SdkIncompatibility>,
+}
+
+/// Why a package's lints don't follow the workspace baseline.
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisOptionsProblem {
+    /// No `analysis_options.yaml` applies (the package is outside the root)
+    Missing,
+    /// The options file that applies has no `include:`
+    NoInclude,
+    /// The `include:` chain never reaches the workspace baseline
+    ForeignInclude,
+    /// The baseline is included, but lint rules or analyzer error
+    /// severities are overridden against it
+    ConflictingRules,
+}
+
+/// A lint setting a package overrides against the workspace baseline.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LintConflict {
+    /// `linter.rules.<rule>` or `analyzer.errors.<code>`
+    pub setting: String,
+    pub value: String,
+    /// The baseline's value; absent when the baseline doesn't set it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+}
+
+impl std::fmt::Display for LintConflict {
+    /// `linter.rules.avoid_print: false (workspace: true)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.setting, self.value)?;
+        match &self.baseline {
+            Some(baseline) => write!(f, " (workspace: {})", baseline),
+            None => write!(f, " (workspace: unset)"),
+        }
+    }
+}
+
+/// A package whose `analysis_options.yaml` doesn't build on the workspace's.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct AnalysisOptionsIssue {
+    pub package: String,
+    pub problem: AnalysisOptionsProblem,
+    /// The options file that applies to the package, relative to the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The `include:` entries of that file
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Settings overridden against the baseline, for `conflicting_rules`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<LintConflict>,
+}
+
+/// Version of the [`HealthReport`] JSON schema.
+///
+/// Bumped whenever a field is renamed or removed, or its meaning changes.
+pub const HEALTH_SCHEMA_VERSION: u32 = 1;
+
 /// Full health report for JSON output.
 #[derive(serde::Serialize, Debug, Clone, PartialEq)]
 pub struct HealthReport {
+    /// Always [`HEALTH_SCHEMA_VERSION`]
+    pub schema_version: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_drift: Option<Vec<VersionDriftIssue>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub missing_fields: Option<Vec<MissingFieldsIssue>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sdk_consistency: Option<SdkConsistencyResult>,
+    /// Resolved SDK versions; absent when the SDK check is disabled or no
+    /// SDK tool could be run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sdk_version: Option<SdkVersionResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_options: Option<Vec<AnalysisOptionsIssue>>,
     pub total_issues: u32,
 }
 
@@ -65,8 +172,11 @@ pub struct HealthReport {
 
 /// Run all enabled health checks and return a structured report.
 pub fn run(packages: &[Package], opts: &HealthOpts) -> HealthReport {
-    let run_all =
-        opts.all || (!opts.version_drift && !opts.missing_fields && !opts.sdk_consistency);
+    let run_all = opts.all
+        || (!opts.version_drift
+            && !opts.missing_fields
+            && !opts.sdk_consistency
+            && !opts.analysis_options);
 
     let mut total_issues = 0u32;
 
@@ -86,7 +196,9 @@ pub fn run(packages: &[Package], opts: &HealthOpts) -> HealthReport {
         None
     };
 
-    let sdk_data = if run_all || opts.sdk_consistency {
+    let check_sdk = run_all || opts.sdk_consistency;
+
+    let sdk_data = if check_sdk {
         let data = collect_sdk_consistency(packages);
         let sdk_issues = if !data.missing_sdk.is_empty() {
             1u32
@@ -104,10 +216,35 @@ pub fn run(packages: &[Package], opts: &HealthOpts) -> HealthReport {
         None
     };
 
+    let sdk_version = if check_sdk {
+        let uses_flutter = packages.iter().any(|p| p.is_flutter);
+        let (dart, flutter) = detect_sdk_versions(opts.sdk_path.as_deref(), uses_flutter);
+        if dart.is_none() && flutter.is_none() {
+            None
+        } else {
+            let data = collect_sdk_version(packages, dart.as_ref(), flutter.as_ref());
+            total_issues += data.incompatible.len() as u32;
+            Some(data)
+        }
+    } else {
+        None
+    };
+
+    let analysis_data = if run_all || opts.analysis_options {
+        let data = collect_analysis_options(packages, &opts.root_path);
+        total_issues += data.len() as u32;
+        Some(data)
+    } else {
+        None
+    };
+
     HealthReport {
+        schema_version: HEALTH_SCHEMA_VERSION,
         version_drift: drift_data,
         missing_fields: missing_data,
         sdk_consistency: sdk_data,
+        sdk_version,
+        analysis_options: analysis_data,
         total_issues,
     }
 }
@@ -256,6 +393,339 @@ pub fn collect_missing_fields(packages: &[Package]) -> Vec<MissingFieldsIssue> {
     issues
 }
 
+/// Fill in the missing pubspec fields of public packages that can be
+/// inferred, returning the edits made.
+///
+/// Only `repository` can be inferred: the workspace `repository` plus the
+/// package's path relative to `root`, on `branch`. It is written when neither
+/// `homepage` nor `repository` is declared, as a new line after `version:`
+/// (or `name:`), leaving the rest of the file as it was. Other missing fields
+/// stay reported-only.
+pub fn fix_missing_fields(
+    packages: &[Package],
+    root: &Path,
+    repository: Option<&RepositoryConfig>,
+    branch: &str,
+) -> Result<Vec<FieldFix>> {
+    let Some(repository) = repository else {
+        return Ok(Vec::new());
+    };
+
+    let mut fixes = Vec::new();
+    for pkg in packages {
+        if pkg.is_private() {
+            continue;
+        }
+        let pubspec_path = pkg.path.join("pubspec.yaml");
+        let Ok(content) = std::fs::read_to_string(&pubspec_path) else {
+            continue;
+        };
+        // A declared key, even with an empty value, is never overwritten
+        if declares_top_level_key(&content, "homepage")
+            || declares_top_level_key(&content, "repository")
+        {
+            continue;
+        }
+
+        let relative = pkg.path.strip_prefix(root).unwrap_or(&pkg.path);
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let value = repository.directory_url(&relative, branch);
+        let Some(new_content) = insert_top_level_field(&content, "repository", &value) else {
+            continue;
+        };
+        std::fs::write(&pubspec_path, new_content)
+            .with_context(|| format!("Failed to write {}", pubspec_path.display()))?;
+        fixes.push(FieldFix {
+            package: pkg.name.clone(),
+            field: "repository".to_string(),
+            value,
+        });
+    }
+
+    Ok(fixes)
+}
+
+fn declares_top_level_key(content: &str, key: &str) -> bool {
+    content.lines().any(|line| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(':'))
+    })
+}
+
+/// Insert a `key: value` line after the top-level `version:` line, or the
+/// `name:` line without one. `None` when the pubspec has neither.
+fn insert_top_level_field(content: &str, key: &str, value: &str) -> Option<String> {
+    let anchor = ["version", "name"]
+        .into_iter()
+        .find(|anchor| declares_top_level_key(content, anchor))?;
+
+    let mut out = String::with_capacity(content.len() + key.len() + value.len() + 3);
+    let mut inserted = false;
+    for line in content.split_inclusive('\n') {
+        out.push_str(line);
+        if !inserted && declares_top_level_key(line, anchor) {
+            if !line.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(&format!("{}: {}\n", key, value));
+            inserted = true;
+        }
+    }
+    Some(out)
+}
+
+// ---------------------------------------------------------------------------
+// Analysis Options
+// ---------------------------------------------------------------------------
+
+/// Analyzer options file name, looked up from each package upwards.
+pub const ANALYSIS_OPTIONS_FILE: &str = "analysis_options.yaml";
+
+/// Collect packages whose lints don't build on the workspace baseline, the
+/// root `analysis_options.yaml`.
+///
+/// The analyzer uses the nearest options file up from a package, so a
+/// package without one inherits the root's. An options file of its own (or
+/// in a directory between it and the root) must reach the baseline through
+/// `include:`: a path to the root file, possibly via other files, or a
+/// `package:` URI the root file includes itself. Once it does, the
+/// `linter.rules` and `analyzer.errors` set between the package and the
+/// baseline must not contradict the baseline's: turning off a rule or
+/// ignoring an error it doesn't, or changing a value it sets. Enabling more
+/// rules is fine. Without a root file there is no baseline and nothing is
+/// reported.
+pub fn collect_analysis_options(packages: &[Package], root: &Path) -> Vec<AnalysisOptionsIssue> {
+    let baseline = root.join(ANALYSIS_OPTIONS_FILE);
+    if !baseline.is_file() {
+        return Vec::new();
+    }
+    let baseline = canonical(&baseline);
+    let baseline_uris: Vec<String> = read_includes(&baseline)
+        .into_iter()
+        .filter(|include| include.starts_with("package:"))
+        .collect();
+    let baseline_settings = layered_settings(&baseline, None, 0);
+
+    let mut issues = Vec::new();
+    for pkg in packages {
+        let Some(file) = nearest_options_file(&pkg.path, root) else {
+            issues.push(AnalysisOptionsIssue {
+                package: pkg.name.clone(),
+                problem: AnalysisOptionsProblem::Missing,
+                file: None,
+                include: Vec::new(),
+                conflicts: Vec::new(),
+            });
+            continue;
+        };
+        if canonical(&file) == baseline {
+            continue;
+        }
+
+        let include = read_includes(&file);
+        let mut conflicts = Vec::new();
+        let problem = if include.is_empty() {
+            AnalysisOptionsProblem::NoInclude
+        } else if includes_baseline(&file, &baseline, &baseline_uris, 0) {
+            conflicts = lint_conflicts(
+                &layered_settings(&file, Some(&baseline), 0),
+                &baseline_settings,
+            );
+            if conflicts.is_empty() {
+                continue;
+            }
+            AnalysisOptionsProblem::ConflictingRules
+        } else {
+            AnalysisOptionsProblem::ForeignInclude
+        };
+        issues.push(AnalysisOptionsIssue {
+            package: pkg.name.clone(),
+            problem,
+            file: Some(
+                file.strip_prefix(root)
+                    .unwrap_or(&file)
+                    .display()
+                    .to_string(),
+            ),
+            include,
+            conflicts,
+        });
+    }
+
+    issues
+}
+
+/// Lint settings of an options file: `linter.rules` (enabled or not) and
+/// `analyzer.errors` (severity per code).
+#[derive(Debug, Default, Clone, PartialEq)]
+struct LintSettings {
+    rules: BTreeMap<String, bool>,
+    errors: BTreeMap<String, String>,
+}
+
+impl LintSettings {
+    fn from_options(options: &yaml_serde::Value) -> Self {
+        let mut settings = Self::default();
+        match options.get("linter").and_then(|l| l.get("rules")) {
+            // The list form enables every rule it names
+            Some(yaml_serde::Value::Sequence(rules)) => {
+                for rule in rules.iter().filter_map(|r| r.as_str()) {
+                    settings.rules.insert(rule.to_string(), true);
+                }
+            }
+            Some(yaml_serde::Value::Mapping(rules)) => {
+                for (rule, enabled) in rules {
+                    if let (Some(rule), Some(enabled)) = (rule.as_str(), enabled.as_bool()) {
+                        settings.rules.insert(rule.to_string(), enabled);
+                    }
+                }
+            }
+            _ => {}
+        }
+        if let Some(yaml_serde::Value::Mapping(errors)) =
+            options.get("analyzer").and_then(|a| a.get("errors"))
+        {
+            for (code, severity) in errors {
+                if let (Some(code), Some(severity)) = (code.as_str(), severity.as_str()) {
+                    settings
+                        .errors
+                        .insert(code.to_string(), severity.to_lowercase());
+                }
+            }
+        }
+        settings
+    }
+
+    /// Overlay `other`, whose values win.
+    fn extend(&mut self, other: Self) {
+        self.rules.extend(other.rules);
+        self.errors.extend(other.errors);
+    }
+}
+
+/// The lint settings `file` ends up with from itself and the files it
+/// includes by path, stopping at `stop` (the baseline). `package:` includes
+/// can't be resolved and contribute nothing.
+fn layered_settings(file: &Path, stop: Option<&Path>, depth: usize) -> LintSettings {
+    let mut settings = LintSettings::default();
+    let Some(options) = read_options(file) else {
+        return settings;
+    };
+    if depth < MAX_INCLUDE_DEPTH {
+        let dir = file.parent().unwrap_or(Path::new(""));
+        for include in includes_of(&options) {
+            if include.starts_with("package:") {
+                continue;
+            }
+            let included = canonical(&dir.join(&include));
+            if Some(included.as_path()) != stop && included.is_file() {
+                settings.extend(layered_settings(&included, stop, depth + 1));
+            }
+        }
+    }
+    settings.extend(LintSettings::from_options(&options));
+    settings
+}
+
+/// Settings in `local` that contradict `baseline`: a different value for a
+/// rule or code the baseline sets, or a rule turned off or an error ignored
+/// that the baseline leaves alone.
+fn lint_conflicts(local: &LintSettings, baseline: &LintSettings) -> Vec<LintConflict> {
+    let mut conflicts = Vec::new();
+    for (rule, enabled) in &local.rules {
+        let base = baseline.rules.get(rule);
+        if base.map_or(!enabled, |base| base != enabled) {
+            conflicts.push(LintConflict {
+                setting: format!("linter.rules.{rule}"),
+                value: enabled.to_string(),
+                baseline: base.map(bool::to_string),
+            });
+        }
+    }
+    for (code, severity) in &local.errors {
+        let base = baseline.errors.get(code);
+        if base.map_or(severity == "ignore", |base| base != severity) {
+            conflicts.push(LintConflict {
+                setting: format!("analyzer.errors.{code}"),
+                value: severity.clone(),
+                baseline: base.cloned(),
+            });
+        }
+    }
+    conflicts
+}
+
+/// The options file the analyzer uses for `dir`: the nearest one in `dir` or
+/// an ancestor, searching no higher than `root`.
+fn nearest_options_file(dir: &Path, root: &Path) -> Option<PathBuf> {
+    let mut dir = dir;
+    loop {
+        let file = dir.join(ANALYSIS_OPTIONS_FILE);
+        if file.is_file() {
+            return Some(file);
+        }
+        if dir == root || !dir.starts_with(root) {
+            return None;
+        }
+        dir = dir.parent()?;
+    }
+}
+
+/// Include chains longer than this are treated as not reaching the baseline
+/// (and cycles end here too).
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Whether `file` includes the baseline, directly or through included files.
+fn includes_baseline(file: &Path, baseline: &Path, baseline_uris: &[String], depth: usize) -> bool {
+    if depth >= MAX_INCLUDE_DEPTH {
+        return false;
+    }
+    let dir = file.parent().unwrap_or(Path::new(""));
+    read_includes(file).iter().any(|include| {
+        if include.starts_with("package:") {
+            return baseline_uris.contains(include);
+        }
+        let included = canonical(&dir.join(include));
+        included == baseline
+            || (included.is_file()
+                && includes_baseline(&included, baseline, baseline_uris, depth + 1))
+    })
+}
+
+/// The `include:` entries of an options file. Empty when the file can't be
+/// read or parsed.
+fn read_includes(file: &Path) -> Vec<String> {
+    read_options(file)
+        .map(|options| includes_of(&options))
+        .unwrap_or_default()
+}
+
+fn read_options(file: &Path) -> Option<yaml_serde::Value> {
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|content| yaml_serde::from_str::<yaml_serde::Value>(&content).ok())
+}
+
+/// `include:` is a single string or, since Dart 3.8, a list.
+fn includes_of(options: &yaml_serde::Value) -> Vec<String> {
+    match options.get("include") {
+        Some(yaml_serde::Value::String(include)) => vec![include.clone()],
+        Some(yaml_serde::Value::Sequence(includes)) => includes
+            .iter()
+            .filter_map(|include| include.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
 // ---------------------------------------------------------------------------
 // SDK Consistency
 // ---------------------------------------------------------------------------
@@ -350,6 +820,127 @@ pub fn build_sorted_usages(map: &HashMap<String, Vec<String>>) -> Vec<Constraint
         .collect()
 }
 
+// ---------------------------------------------------------------------------
+// SDK Version
+// ---------------------------------------------------------------------------
+
+/// `Dart SDK version: 3.5.0 (stable)` from `dart --version`, or
+/// `Tools • Dart 3.5.0 • DevTools 2.37.2` from `flutter --version`.
+static DART_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // safety: pattern is a valid literal regex
+    Regex::new(r"\bDart(?: SDK version:)?\s+(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?)")
+        .expect("valid regex")
+});
+
+/// `Flutter 3.24.0 • channel stable • ...` from `flutter --version`.
+static FLUTTER_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // safety: pattern is a valid literal regex
+    Regex::new(r"\bFlutter\s+(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?)").expect("valid regex")
+});
+
+/// Run `<tool> --version` (from `sdk_path/bin` when given) and return its
+/// combined stdout and stderr, or `None` if the tool is unavailable.
+///
+/// Older Dart SDKs print their version to stderr, so both streams are read.
+pub fn tool_version_output(tool: &str, sdk_path: Option<&str>) -> Option<String> {
+    let program = match sdk_path {
+        Some(sdk) => Path::new(sdk).join("bin").join(tool),
+        None => tool.into(),
+    };
+    let output = Command::new(program).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Extract the Dart SDK version from `dart --version` or `flutter --version`
+/// output.
+pub fn parse_dart_version(output: &str) -> Option<Version> {
+    let caps = DART_VERSION_RE.captures(output)?;
+    Version::parse(&caps[1]).ok()
+}
+
+/// Extract the Flutter SDK version from `flutter --version` output.
+pub fn parse_flutter_version(output: &str) -> Option<Version> {
+    let caps = FLUTTER_VERSION_RE.captures(output)?;
+    Version::parse(&caps[1]).ok()
+}
+
+/// Resolve the Dart and Flutter SDK versions in use.
+///
+/// Flutter workspaces ask `flutter`, whose output also names the bundled
+/// Dart SDK; `dart` is asked directly when that yields no Dart version.
+pub fn detect_sdk_versions(
+    sdk_path: Option<&str>,
+    uses_flutter: bool,
+) -> (Option<Version>, Option<Version>) {
+    let flutter_output = uses_flutter
+        .then(|| tool_version_output("flutter", sdk_path))
+        .flatten();
+    let flutter = flutter_output.as_deref().and_then(parse_flutter_version);
+    let dart = flutter_output
+        .as_deref()
+        .and_then(parse_dart_version)
+        .or_else(|| {
+            tool_version_output("dart", sdk_path)
+                .as_deref()
+                .and_then(parse_dart_version)
+        });
+    (dart, flutter)
+}
+
+/// Whether `constraint` allows the SDK `version`.
+///
+/// Pre-release SDKs (`3.6.0-216.1.beta`) are checked as their release
+/// version, since pub accepts them for constraints on that release.
+/// Unparseable constraints are treated as allowing every version.
+fn sdk_constraint_allows(constraint: &str, version: &Version) -> bool {
+    let mut release = version.clone();
+    release.pre = semver::Prerelease::EMPTY;
+    release.build = semver::BuildMetadata::EMPTY;
+    constraint_allows(constraint, &release).unwrap_or(true)
+}
+
+/// Flag packages whose `environment.sdk` (or `environment.flutter`)
+/// constraint excludes the resolved Dart (or Flutter) version.
+pub fn collect_sdk_version(
+    packages: &[Package],
+    dart: Option<&Version>,
+    flutter: Option<&Version>,
+) -> SdkVersionResult {
+    let mut incompatible = Vec::new();
+
+    for pkg in packages {
+        let constraints = read_sdk_constraints(pkg);
+        for (sdk, constraint, version) in [
+            ("dart", constraints.sdk, dart),
+            ("flutter", constraints.flutter, flutter),
+        ] {
+            if let (Some(constraint), Some(version)) = (constraint, version)
+                && !constraint.is_empty()
+                && !sdk_constraint_allows(&constraint, version)
+            {
+                incompatible.push(SdkIncompatibility {
+                    package: pkg.name.clone(),
+                    sdk: sdk.to_string(),
+                    constraint,
+                });
+            }
+        }
+    }
+
+    SdkVersionResult {
+        dart: dart.map(Version::to_string),
+        flutter: flutter.map(Version::to_string),
+        incompatible,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -369,6 +960,7 @@ mod tests {
             dependencies: dep_versions.keys().cloned().collect(),
             dev_dependencies: vec![],
             dependency_versions: dep_versions,
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -487,13 +1079,17 @@ mod tests {
     #[test]
     fn test_health_report_json_serializable() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: Some(vec![]),
             missing_fields: None,
             sdk_consistency: None,
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 0,
         };
         let json =
             serde_json::to_string_pretty(&report).expect("safety: HealthReport should serialize");
+        assert!(json.contains("\"schema_version\": 1"));
         assert!(json.contains("total_issues"));
         assert!(json.contains("version_drift"));
         // missing_fields and sdk_consistency are None, should be skipped
@@ -512,12 +1108,243 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         let issues = collect_missing_fields(&[private_pkg]);
         assert!(issues.is_empty());
     }
 
+    #[test]
+    fn test_fix_missing_fields_infers_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write_pkg = |name: &str, pubspec: &str, publish_to: Option<&str>| {
+            let path = root.join("packages").join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join("pubspec.yaml"), pubspec).unwrap();
+            Package {
+                path,
+                publish_to: publish_to.map(str::to_string),
+                ..make_package(name, HashMap::new())
+            }
+        };
+        let packages = vec![
+            write_pkg(
+                "core",
+                "# The core package\nname: core\nversion: 1.0.0 # bumped by CI\n\ndependencies: {}\n",
+                None,
+            ),
+            write_pkg("no_version", "name: no_version", None),
+            write_pkg(
+                "has_home",
+                "name: has_home\nhomepage: https://x.dev\n",
+                None,
+            ),
+            write_pkg("empty_repo", "name: empty_repo\nrepository:\n", None),
+            write_pkg("private", "name: private\n", Some("none")),
+        ];
+        let repository = RepositoryConfig {
+            url: "https://github.com/org/repo".to_string(),
+            kind: crate::config::RepoKind::GitHub,
+        };
+
+        assert!(
+            fix_missing_fields(&packages, root, None, "main")
+                .unwrap()
+                .is_empty()
+        );
+        let fixes = fix_missing_fields(&packages, root, Some(&repository), "main").unwrap();
+        let fixed: Vec<&str> = fixes.iter().map(|f| f.package.as_str()).collect();
+        assert_eq!(fixed, vec!["core", "no_version"]);
+        assert_eq!(
+            fixes[0].value,
+            "https://github.com/org/repo/tree/main/packages/core"
+        );
+
+        let read = |name: &str| {
+            std::fs::read_to_string(root.join("packages").join(name).join("pubspec.yaml")).unwrap()
+        };
+        assert_eq!(
+            read("core"),
+            "# The core package\nname: core\nversion: 1.0.0 # bumped by CI\nrepository: https://github.com/org/repo/tree/main/packages/core\n\ndependencies: {}\n"
+        );
+        assert_eq!(
+            read("no_version"),
+            "name: no_version\nrepository: https://github.com/org/repo/tree/main/packages/no_version\n"
+        );
+        assert_eq!(read("empty_repo"), "name: empty_repo\nrepository:\n");
+        // `description` can't be inferred and stays reported
+        assert_eq!(
+            collect_missing_fields(&packages[..1])[0].missing,
+            vec!["description"]
+        );
+
+        // Nothing is left to fix on a second run
+        assert!(
+            fix_missing_fields(&packages, root, Some(&repository), "main")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_collect_analysis_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("ws");
+        let pkg = |rel: &str, options: Option<&str>| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(&path).unwrap();
+            if let Some(options) = options {
+                std::fs::write(path.join(ANALYSIS_OPTIONS_FILE), options).unwrap();
+            }
+            Package {
+                path,
+                ..make_package(rel.rsplit('/').next().unwrap(), HashMap::new())
+            }
+        };
+        let packages = vec![
+            pkg("packages/inherits", None),
+            pkg(
+                "packages/relative",
+                Some("include: ../../analysis_options.yaml\n"),
+            ),
+            pkg(
+                "packages/same_uri",
+                Some("include:\n  - package:very_good_analysis/analysis_options.yaml\n"),
+            ),
+            pkg(
+                "packages/bare",
+                Some("linter:\n  rules:\n    - avoid_print\n"),
+            ),
+            pkg(
+                "packages/foreign",
+                Some("include: package:lints/recommended.yaml\n"),
+            ),
+            pkg("packages/group/nested", None),
+            Package {
+                path: dir.path().join("outside"),
+                ..make_package("outside", HashMap::new())
+            },
+        ];
+        std::fs::write(
+            root.join("packages/group").join(ANALYSIS_OPTIONS_FILE),
+            "include: ../relative/analysis_options.yaml\n",
+        )
+        .unwrap();
+
+        // Without a root baseline there is nothing to compare against
+        assert!(collect_analysis_options(&packages, &root).is_empty());
+
+        std::fs::write(
+            root.join(ANALYSIS_OPTIONS_FILE),
+            "include: package:very_good_analysis/analysis_options.yaml\n",
+        )
+        .unwrap();
+        let issues = collect_analysis_options(&packages, &root);
+        let summary: Vec<(&str, AnalysisOptionsProblem)> = issues
+            .iter()
+            .map(|i| (i.package.as_str(), i.problem))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("bare", AnalysisOptionsProblem::NoInclude),
+                ("foreign", AnalysisOptionsProblem::ForeignInclude),
+                ("outside", AnalysisOptionsProblem::Missing),
+            ]
+        );
+        assert_eq!(
+            issues[1].file.as_deref(),
+            Some("packages/foreign/analysis_options.yaml")
+        );
+        assert_eq!(issues[1].include, vec!["package:lints/recommended.yaml"]);
+    }
+
+    #[test]
+    fn test_collect_analysis_options_reports_conflicting_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let pkg = |rel: &str, options: Option<&str>| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(&path).unwrap();
+            if let Some(options) = options {
+                std::fs::write(path.join(ANALYSIS_OPTIONS_FILE), options).unwrap();
+            }
+            Package {
+                path,
+                ..make_package(rel.rsplit('/').next().unwrap(), HashMap::new())
+            }
+        };
+        std::fs::write(
+            root.join(ANALYSIS_OPTIONS_FILE),
+            "include: package:lints/recommended.yaml\n\
+             linter:\n  rules:\n    - prefer_single_quotes\n\
+             analyzer:\n  errors:\n    todo: ignore\n    unused_import: warning\n",
+        )
+        .unwrap();
+        let packages = vec![
+            pkg(
+                "packages/strict",
+                Some(
+                    "include: ../../analysis_options.yaml\n\
+                     linter:\n  rules:\n    always_declare_return_types: true\n\
+                     analyzer:\n  errors:\n    todo: ignore\n",
+                ),
+            ),
+            pkg(
+                "packages/loose",
+                Some(
+                    "include: ../../analysis_options.yaml\n\
+                     linter:\n  rules:\n    prefer_single_quotes: false\n    avoid_print: false\n\
+                     analyzer:\n  errors:\n    unused_import: info\n    dead_code: ignore\n",
+                ),
+            ),
+            pkg("packages/group/inner", None),
+        ];
+        std::fs::write(
+            root.join("packages/group").join(ANALYSIS_OPTIONS_FILE),
+            "include: ../../analysis_options.yaml\nlinter:\n  rules:\n    prefer_single_quotes: false\n",
+        )
+        .unwrap();
+
+        let issues = collect_analysis_options(&packages, root);
+        let names: Vec<&str> = issues.iter().map(|i| i.package.as_str()).collect();
+        assert_eq!(names, vec!["loose", "inner"]);
+        assert!(
+            issues
+                .iter()
+                .all(|i| i.problem == AnalysisOptionsProblem::ConflictingRules)
+        );
+
+        let conflict = |setting: &str, value: &str, baseline: Option<&str>| LintConflict {
+            setting: setting.to_string(),
+            value: value.to_string(),
+            baseline: baseline.map(str::to_string),
+        };
+        assert_eq!(
+            issues[0].conflicts,
+            vec![
+                conflict("linter.rules.avoid_print", "false", None),
+                conflict("linter.rules.prefer_single_quotes", "false", Some("true")),
+                conflict("analyzer.errors.dead_code", "ignore", None),
+                conflict("analyzer.errors.unused_import", "info", Some("warning")),
+            ]
+        );
+        assert_eq!(
+            issues[1].file.as_deref(),
+            Some("packages/group/analysis_options.yaml")
+        );
+        assert_eq!(
+            issues[1].conflicts,
+            vec![conflict(
+                "linter.rules.prefer_single_quotes",
+                "false",
+                Some("true")
+            )]
+        );
+    }
+
     #[test]
     fn test_collect_sdk_consistency_missing() {
         let pkg = make_package("missing_pubspec", HashMap::new());
@@ -525,6 +1352,78 @@ mod tests {
         assert_eq!(result.missing_sdk, vec!["missing_pubspec"]);
     }
 
+    #[test]
+    fn test_parse_dart_version_formats() {
+        let dart =
+            "Dart SDK version: 3.5.0 (stable) (Tue Jul 30 02:17:59 2024 -0700) on \"linux_x64\"";
+        assert_eq!(parse_dart_version(dart), Some(Version::new(3, 5, 0)));
+
+        let beta = "Dart SDK version: 3.6.0-216.1.beta (beta) on \"macos_arm64\"";
+        assert_eq!(
+            parse_dart_version(beta).map(|v| v.to_string()),
+            Some("3.6.0-216.1.beta".to_string())
+        );
+
+        let flutter = "Flutter 3.24.0 • channel stable • https://github.com/flutter/flutter.git\n\
+                       Framework • revision 80c2e84975 (3 weeks ago) • 2024-07-30 23:06:49 +0700\n\
+                       Engine • revision b8800d88be\n\
+                       Tools • Dart 3.5.0 • DevTools 2.37.2\n";
+        assert_eq!(parse_dart_version(flutter), Some(Version::new(3, 5, 0)));
+        assert_eq!(parse_flutter_version(flutter), Some(Version::new(3, 24, 0)));
+
+        assert_eq!(parse_dart_version("command not found"), None);
+        assert_eq!(parse_flutter_version(dart), None);
+    }
+
+    #[test]
+    fn test_collect_sdk_version_flags_excluding_constraints() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write_pkg = |name: &str, environment: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(
+                path.join("pubspec.yaml"),
+                format!("name: {name}\nenvironment:\n{environment}"),
+            )
+            .unwrap();
+            let mut pkg = make_package(name, HashMap::new());
+            pkg.path = path;
+            pkg
+        };
+        let packages = vec![
+            write_pkg("ok", "  sdk: '>=3.0.0 <4.0.0'\n"),
+            write_pkg("too_new", "  sdk: ^3.6.0\n"),
+            write_pkg("ui", "  sdk: ^3.0.0\n  flutter: '>=3.27.0'\n"),
+        ];
+
+        let dart = Version::parse("3.5.0-180.3.beta").unwrap();
+        let flutter = Version::new(3, 24, 0);
+        let result = collect_sdk_version(&packages, Some(&dart), Some(&flutter));
+
+        assert_eq!(result.dart.as_deref(), Some("3.5.0-180.3.beta"));
+        assert_eq!(result.flutter.as_deref(), Some("3.24.0"));
+        assert_eq!(
+            result.incompatible,
+            vec![
+                SdkIncompatibility {
+                    package: "too_new".to_string(),
+                    sdk: "dart".to_string(),
+                    constraint: "^3.6.0".to_string(),
+                },
+                SdkIncompatibility {
+                    package: "ui".to_string(),
+                    sdk: "flutter".to_string(),
+                    constraint: ">=3.27.0".to_string(),
+                },
+            ]
+        );
+
+        // Without a resolved Flutter version only Dart constraints are checked
+        let dart_only = collect_sdk_version(&packages, Some(&dart), None);
+        assert_eq!(dart_only.incompatible.len(), 1);
+        assert!(dart_only.flutter.is_none());
+    }
+
     #[test]
     fn test_build_sorted_usages_deterministic() {
         let mut map = HashMap::new();
@@ -1,33 +1,150 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
 
-use crate::package::Package;
+use anyhow::{Context, Result, bail};
 
-/// Serializable representation of a package for JSON output.
+use crate::commands::version::find_package_version_tag;
+use crate::package::Package;
+use crate::package::graph::DependencyGraph;
+
+/// Serializable representation of a package for `list --json`.
+///
+/// This is the schema external tools consume, so fields are only ever
+/// added. One object per package:
+///
+/// ```json
+/// {
+///   "name": "app",
+///   "version": "1.2.0",
+///   "path": "packages/app",
+///   "flutter": true,
+///   "private": false,
+///   "dependencies": ["core", "http"],
+///   "devDependencies": ["test"],
+///   "dependencyVersions": { "core": "^1.0.0", "http": "^1.1.0" }
+/// }
+/// ```
+///
+/// - `version` is `"unknown"` when the package has none.
+/// - `path` is relative to the workspace root, with `/` separators (`.` for
+///   the root package).
+/// - `dependencies` and `devDependencies` are sorted by name.
+/// - `dependencyVersions` maps regular and dev dependencies to their version
+///   constraint; SDK, path-only, git-only and `any` dependencies are absent.
 #[derive(serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct PackageJson<'a> {
     pub name: &'a str,
     pub version: &'a str,
     pub path: String,
     pub flutter: bool,
     pub private: bool,
-    pub dependencies: &'a Vec<String>,
+    pub dependencies: Vec<&'a str>,
+    pub dev_dependencies: Vec<&'a str>,
+    pub dependency_versions: BTreeMap<&'a str, &'a str>,
 }
 
-/// Build a list of [`PackageJson`] from packages for JSON serialization.
-pub fn build_packages_json(packages: &[Package]) -> Vec<PackageJson<'_>> {
+/// Build a list of [`PackageJson`] from packages for JSON serialization,
+/// in the order given.
+pub fn build_packages_json<'a>(packages: &'a [Package], root: &Path) -> Vec<PackageJson<'a>> {
+    let sorted = |deps: &'a [String]| {
+        let mut deps: Vec<&str> = deps.iter().map(String::as_str).collect();
+        deps.sort_unstable();
+        deps
+    };
     packages
         .iter()
         .map(|p| PackageJson {
             name: &p.name,
             version: p.version.as_deref().unwrap_or("unknown"),
-            path: p.path.display().to_string(),
+            path: relative_path(&p.path, root),
             flutter: p.is_flutter,
             private: p.is_private(),
-            dependencies: &p.dependencies,
+            dependencies: sorted(&p.dependencies),
+            dev_dependencies: sorted(&p.dev_dependencies),
+            dependency_versions: p
+                .dependency_versions
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect(),
         })
         .collect()
 }
 
+/// `path` relative to `root` with `/` separators, or `.` for the root itself.
+fn relative_path(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Release-readiness git state of a package, for `list --long`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageGitStatus {
+    /// Whether `git status --porcelain` reports changes under the package
+    pub dirty: bool,
+    /// Commits on HEAD since the package's latest `<name>-v<version>` tag,
+    /// or `None` when it has no tag
+    pub commits_since_tag: Option<u64>,
+}
+
+/// Whether `root` is inside a git work tree.
+pub fn is_git_work_tree(root: &Path) -> bool {
+    std::process::Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(root)
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Read the git state of `pkg` in the repository at `root`.
+pub fn package_git_status(root: &Path, pkg: &Package) -> Result<PackageGitStatus> {
+    let status = git_stdout(root, &["status", "--porcelain", "--"], Some(&pkg.path))?;
+    let commits_since_tag = match find_package_version_tag(root, &pkg.name) {
+        Some((tag, _)) => {
+            let range = format!("{tag}..HEAD");
+            let count = git_stdout(root, &["rev-list", &range, "--count"], None)?;
+            Some(
+                count
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Unexpected `git rev-list` output: {count}"))?,
+            )
+        }
+        None => None,
+    };
+    Ok(PackageGitStatus {
+        dirty: !status.trim().is_empty(),
+        commits_since_tag,
+    })
+}
+
+fn git_stdout(root: &Path, args: &[&str], path: Option<&Path>) -> Result<String> {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(args).current_dir(root);
+    if let Some(path) = path {
+        cmd.arg(path);
+    }
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run git {}", args.join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Result of dependency cycle detection.
 #[derive(Debug, Clone)]
 pub struct CycleResult {
@@ -172,6 +289,90 @@ pub fn generate_mermaid(packages: &[Package]) -> String {
     lines.join("\n")
 }
 
+/// Sort `packages` so workspace dependencies come before their dependents.
+///
+/// Ties are broken by name; packages on a cycle keep their relative order
+/// after everything else (see [`DependencyGraph::topo_order`]).
+pub fn sort_topological(packages: &mut [Package]) {
+    let rank: HashMap<String, usize> = DependencyGraph::new(packages)
+        .topo_order()
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), i))
+        .collect();
+    packages.sort_by_key(|pkg| rank.get(&pkg.name).copied().unwrap_or(usize::MAX));
+}
+
+/// Sort `packages` by path depth (shallowest first), then by path.
+pub fn sort_by_path_depth(packages: &mut [Package]) {
+    packages.sort_by(|a, b| {
+        let depth = |pkg: &Package| pkg.path.components().count();
+        depth(a).cmp(&depth(b)).then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+/// Render the intra-workspace dependencies as an indented tree, like
+/// `cargo tree`.
+///
+/// Each tree is rooted at a package nothing else depends on, in workspace
+/// order, and lists its workspace dependencies (including dev) beneath it.
+/// A dependency that closes a cycle is marked `(*)` and not expanded.
+/// Packages only reachable through a cycle get a tree of their own.
+pub fn generate_ascii_tree(packages: &[Package]) -> String {
+    let graph = DependencyGraph::new(packages);
+
+    let mut roots: Vec<&str> = graph
+        .names()
+        .iter()
+        .map(String::as_str)
+        .filter(|name| graph.dependents_of(name).next().is_none())
+        .collect();
+    let mut covered: HashSet<String> = graph.transitive_dependencies(roots.iter().copied());
+    for name in graph.names() {
+        if !covered.contains(name) {
+            roots.push(name);
+            covered.extend(graph.transitive_dependencies([name.as_str()]));
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|root| {
+            let mut lines = vec![root.to_string()];
+            let mut path = vec![root.to_string()];
+            write_subtree(&graph, root, "", &mut path, &mut lines);
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Append the dependency lines below `name`, indented by `prefix`.
+///
+/// `path` holds the chain from the root to `name`, used to spot cycles.
+fn write_subtree(
+    graph: &DependencyGraph,
+    name: &str,
+    prefix: &str,
+    path: &mut Vec<String>,
+    lines: &mut Vec<String>,
+) {
+    let deps: Vec<&str> = graph.dependencies_of(name).collect();
+    for (i, dep) in deps.iter().enumerate() {
+        let last = i + 1 == deps.len();
+        let connector = if last { "└── " } else { "├── " };
+        if path.iter().any(|p| p == dep) {
+            lines.push(format!("{prefix}{connector}{dep} (*)"));
+            continue;
+        }
+        lines.push(format!("{prefix}{connector}{dep}"));
+        let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+        path.push(dep.to_string());
+        write_subtree(graph, dep, &child_prefix, path, lines);
+        path.pop();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,6 +388,7 @@ mod tests {
             dependencies: deps.into_iter().map(String::from).collect(),
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -216,6 +418,35 @@ mod tests {
         assert_eq!(result.total, 3);
     }
 
+    #[test]
+    fn test_generate_ascii_tree() {
+        let packages = vec![
+            make_pkg("core", vec![]),
+            make_pkg("utils", vec!["core"]),
+            make_pkg("app", vec!["utils", "core", "http"]),
+            make_pkg("tool", vec![]),
+        ];
+        assert_eq!(
+            generate_ascii_tree(&packages),
+            "app\n├── core\n└── utils\n    └── core\n\ntool"
+        );
+    }
+
+    #[test]
+    fn test_generate_ascii_tree_marks_cycles() {
+        let packages = vec![
+            make_pkg("a", vec!["b"]),
+            make_pkg("b", vec!["a"]),
+            make_pkg("app", vec!["c"]),
+            make_pkg("c", vec!["d"]),
+            make_pkg("d", vec!["c"]),
+        ];
+        assert_eq!(
+            generate_ascii_tree(&packages),
+            "app\n└── c\n    └── d\n        └── c (*)\n\na\n└── b\n    └── a (*)"
+        );
+    }
+
     #[test]
     fn test_generate_gviz_basic() {
         let packages = vec![make_pkg("core", vec![]), make_pkg("app", vec!["core"])];
@@ -238,10 +469,113 @@ mod tests {
     #[test]
     fn test_build_packages_json() {
         let packages = vec![make_pkg("core", vec![])];
-        let json_entries = build_packages_json(&packages);
+        let json_entries = build_packages_json(&packages, Path::new("/workspace"));
         assert_eq!(json_entries.len(), 1);
         assert_eq!(json_entries[0].name, "core");
         assert_eq!(json_entries[0].version, "1.0.0");
         assert!(!json_entries[0].flutter);
     }
+
+    #[test]
+    fn test_build_packages_json_sorted_relative() {
+        let mut app = make_pkg("app", vec!["ui", "core"]);
+        app.dev_dependencies = vec!["test".to_string(), "lints".to_string()];
+        app.dependency_versions = HashMap::from([("core".to_string(), "^1.0.0".to_string())]);
+        app.version = None;
+        let root = app.path.parent().unwrap().to_path_buf();
+        let packages = vec![app];
+
+        let json = serde_json::to_string(&build_packages_json(&packages, &root)).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"name":"app","version":"unknown","path":"app","flutter":false,"private":false,"dependencies":["core","ui"],"devDependencies":["lints","test"],"dependencyVersions":{"core":"^1.0.0"}}]"#
+        );
+        assert_eq!(relative_path(&root, &root), ".");
+    }
+
+    #[test]
+    fn test_sort_topological_dependencies_first() {
+        let mut packages = vec![
+            make_pkg("app", vec!["ui", "core"]),
+            make_pkg("core", vec![]),
+            make_pkg("ui", vec!["core"]),
+            make_pkg("analytics", vec!["core"]),
+        ];
+        sort_topological(&mut packages);
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "analytics", "ui", "app"]);
+    }
+
+    #[test]
+    fn test_sort_by_path_depth() {
+        let mut packages = vec![
+            make_pkg("b", vec![]),
+            make_pkg("a", vec![]),
+            make_pkg("nested", vec![]),
+        ];
+        packages[2].path = PathBuf::from("/workspace/packages/group/nested");
+        packages.push(Package {
+            path: PathBuf::from("/workspace"),
+            ..make_pkg("root", vec![])
+        });
+        sort_by_path_depth(&mut packages);
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["root", "a", "b", "nested"]);
+    }
+
+    #[test]
+    fn test_package_git_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        assert!(!is_git_work_tree(root));
+
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@test.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@test.com")
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        let packages: Vec<Package> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let dir = root.join("packages").join(name);
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join("pubspec.yaml"), format!("name: {name}\n")).unwrap();
+                Package {
+                    path: dir,
+                    ..make_pkg(name, vec![])
+                }
+            })
+            .collect();
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        git(&["tag", "a-v1.0.0"]);
+        std::fs::write(root.join("README.md"), "docs\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "docs"]);
+        std::fs::write(root.join("packages/b/lib.dart"), "void main() {}\n").unwrap();
+
+        assert!(is_git_work_tree(root));
+        assert_eq!(
+            package_git_status(root, &packages[0]).unwrap(),
+            PackageGitStatus {
+                dirty: false,
+                commits_since_tag: Some(1),
+            }
+        );
+        assert_eq!(
+            package_git_status(root, &packages[1]).unwrap(),
+            PackageGitStatus {
+                dirty: true,
+                commits_since_tag: None,
+            }
+        );
+    }
 }
@@ -1,24 +1,36 @@
 pub mod analyze;
 pub mod bootstrap;
 pub mod build;
+pub mod cache;
 pub mod clean;
+pub mod doctor;
 pub mod exec;
 pub mod format;
+pub mod graph;
 pub mod health;
 pub mod init;
 pub mod list;
 pub mod pub_cmds;
 pub mod publish;
 pub mod run;
+pub mod selftest;
 pub mod test;
 pub mod version;
 
 /// Results from running a command across multiple packages.
 ///
-/// Each entry is a `(package_name, success)` tuple.
-#[derive(Debug, Clone)]
+/// Each entry in `results` is a `(package_name, success)` tuple for a package
+/// whose command ran. Packages that never ran (fail-fast, deadline, missing
+/// working directory, ...) are listed in `skipped` instead, and packages whose
+/// command was killed by fail-fast in `aborted`; both count as neither passed
+/// nor failed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct PackageResults {
     pub results: Vec<(String, bool)>,
+    /// `(package_name, reason)` for each skipped package
+    pub skipped: Vec<(String, String)>,
+    /// Packages whose running command was killed
+    pub aborted: Vec<String>,
 }
 
 impl PackageResults {
@@ -31,14 +43,75 @@ impl PackageResults {
     pub fn failed(&self) -> usize {
         self.results.iter().filter(|(_, s)| !*s).count()
     }
+
+    /// Number of packages that were skipped.
+    pub fn skipped(&self) -> usize {
+        self.skipped.len()
+    }
+
+    /// Number of packages whose command was killed.
+    pub fn aborted(&self) -> usize {
+        self.aborted.len()
+    }
+
+    /// Append the results of another run.
+    pub fn extend(&mut self, other: PackageResults) {
+        self.results.extend(other.results);
+        self.skipped.extend(other.skipped);
+        self.aborted.extend(other.aborted);
+    }
 }
 
 impl From<Vec<(String, bool)>> for PackageResults {
     fn from(results: Vec<(String, bool)>) -> Self {
-        Self { results }
+        Self {
+            results,
+            ..Default::default()
+        }
     }
 }
 
+/// 64-bit FNV-1a hasher.
+///
+/// Stable across builds and Rust releases, unlike `DefaultHasher`, so it is
+/// safe for cache keys and anything else that must not change between runs.
+#[derive(Debug, Clone, Copy)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl std::hash::Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// [`Fnv1a`] over `parts`, rendered as 16 hex digits.
+///
+/// Each part is length-prefixed so content cannot shift from one part into
+/// the next.
+pub(crate) fn fnv1a_hex<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    use std::hash::Hasher;
+
+    let mut hasher = Fnv1a::default();
+    for part in parts {
+        hasher.write(&(part.len() as u64).to_le_bytes());
+        hasher.write(part);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,6 +134,20 @@ mod tests {
         assert_eq!(results.failed(), 0);
     }
 
+    #[test]
+    fn test_package_results_skipped_and_aborted_are_neither_passed_nor_failed() {
+        let mut results = PackageResults::from(vec![("a".to_string(), true)]);
+        results.extend(PackageResults {
+            results: vec![("b".to_string(), false)],
+            skipped: vec![("c".to_string(), "fail-fast".to_string())],
+            aborted: vec!["d".to_string()],
+        });
+        assert_eq!(results.passed(), 1);
+        assert_eq!(results.failed(), 1);
+        assert_eq!(results.skipped(), 1);
+        assert_eq!(results.aborted(), 1);
+    }
+
     #[test]
     fn test_package_results_empty() {
         let results = PackageResults::from(vec![]);
@@ -81,7 +81,7 @@ pub async fn run(
 
     let runner = ProcessRunner::new(opts.concurrency, false);
     let env_vars = workspace.env_vars();
-    let mut all_results: Vec<(String, bool)> = Vec::new();
+    let mut all_results = PackageResults::default();
 
     if !flutter_pkgs.is_empty() {
         let cmd = format!("flutter {subcmd}");
@@ -127,7 +127,7 @@ pub async fn run(
         all_results.extend(results);
     }
 
-    Ok(PackageResults::from(all_results))
+    Ok(all_results)
 }
 
 #[cfg(test)]
@@ -144,6 +144,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             publish_to: None,
             resolution: None,
         };
@@ -160,6 +161,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: std::collections::HashMap::new(),
+            dependency_kinds: std::collections::HashMap::new(),
             publish_to: None,
             resolution: None,
         };
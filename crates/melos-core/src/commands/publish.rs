@@ -1,20 +1,32 @@
-use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use rayon::prelude::*;
+use semver::Version;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::events::Event;
 use crate::package::Package;
+use crate::package::graph::DependencyGraph;
 use crate::runner::ProcessRunner;
 use crate::workspace::Workspace;
 
 use super::PackageResults;
+use super::version::is_prerelease;
 
 /// Options for the publish command (clap-free).
 #[derive(Debug, Clone)]
 pub struct PublishOpts {
     pub dry_run: bool,
     pub concurrency: usize,
+    /// Release channel (`--tag`), e.g. `beta`. Only applies to prerelease versions.
+    pub tag: Option<String>,
 }
 
+/// Environment variable exposing the release channel to the publish step and hooks.
+pub const PUBLISH_TAG_ENV: &str = "MELOS_PUBLISH_TAG";
+
 /// Build the `dart pub publish` command string.
 pub fn build_publish_command(dry_run: bool) -> String {
     let mut cmd = String::from("dart pub publish");
@@ -32,6 +44,287 @@ pub fn build_git_tag(package_name: &str, version: &str) -> String {
     format!("{}-v{}", package_name, version)
 }
 
+/// Resolve the release channel for a package version.
+///
+/// pub.dev has no dist-tags: consumers only resolve prerelease versions when
+/// they ask for them, so a channel only applies to prerelease versions. Stable
+/// versions always land on the default channel and yield `None`.
+pub fn release_channel<'a>(version: &str, tag: Option<&'a str>) -> Option<&'a str> {
+    tag.filter(|_| is_prerelease(version))
+}
+
+/// Names of packages whose stable version would ignore the requested channel.
+pub fn stable_packages_for_tag(packages: &[Package]) -> Vec<&str> {
+    packages
+        .iter()
+        .filter(|p| !p.version.as_deref().is_some_and(is_prerelease))
+        .map(|p| p.name.as_str())
+        .collect()
+}
+
+/// Narrow `filtered` to the packages named by `--select`, keeping its order.
+///
+/// Every name must be a workspace package (looked up in `all`) that is
+/// publishable and matched by the package filters; otherwise this errors,
+/// naming each offending package.
+pub fn select_packages(
+    filtered: &[Package],
+    all: &[Package],
+    names: &[String],
+) -> Result<Vec<Package>> {
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|n| !all.iter().any(|p| &p.name == *n))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        bail!("--select names unknown package(s): {}", unknown.join(", "));
+    }
+
+    let private: Vec<&str> = names
+        .iter()
+        .filter(|n| all.iter().any(|p| &p.name == *n && p.is_private()))
+        .map(String::as_str)
+        .collect();
+    if !private.is_empty() {
+        bail!(
+            "--select names private package(s) that cannot be published: {}",
+            private.join(", ")
+        );
+    }
+
+    let excluded: Vec<&str> = names
+        .iter()
+        .filter(|n| !filtered.iter().any(|p| &p.name == *n))
+        .map(String::as_str)
+        .collect();
+    if !excluded.is_empty() {
+        bail!(
+            "--select names package(s) excluded by the package filters: {}",
+            excluded.join(", ")
+        );
+    }
+
+    Ok(filtered
+        .iter()
+        .filter(|p| names.contains(&p.name))
+        .cloned()
+        .collect())
+}
+
+/// Build the environment for the publish step, adding [`PUBLISH_TAG_ENV`]
+/// when a channel was requested.
+pub fn build_publish_env(
+    base: HashMap<String, String>,
+    tag: Option<&str>,
+) -> HashMap<String, String> {
+    let mut env = base;
+    if let Some(tag) = tag {
+        env.insert(PUBLISH_TAG_ENV.to_string(), tag.to_string());
+    }
+    env
+}
+
+/// Build the annotation message for a release git tag, noting the channel
+/// for prerelease versions.
+pub fn build_release_message(package_name: &str, version: &str, tag: Option<&str>) -> String {
+    match release_channel(version, tag) {
+        Some(channel) => format!("Release {} v{} ({})", package_name, version, channel),
+        None => format!("Release {} v{}", package_name, version),
+    }
+}
+
+/// Default package registry queried for published versions.
+pub const DEFAULT_REGISTRY: &str = "https://pub.dev";
+
+/// Environment variable `dart pub` reads for a custom package server.
+pub const PUB_HOSTED_URL_ENV: &str = "PUB_HOSTED_URL";
+
+/// How a package's local version relates to the versions on the registry.
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishedStatus {
+    /// The local version is already on the registry
+    Published,
+    /// The local version is newer than every published version (or the
+    /// package was never published)
+    Newer,
+    /// The local version is unpublished but older than the latest published one
+    Older,
+    /// The registry could not be reached or the versions could not be compared
+    Unknown,
+}
+
+/// Registry comparison for one package.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PublishedCheck {
+    pub name: String,
+    pub local_version: Option<String>,
+    /// Highest version on the registry, if any were found
+    pub latest_published: Option<String>,
+    pub status: PublishedStatus,
+}
+
+/// Resolve the registry to query: `--registry`, then `PUB_HOSTED_URL`,
+/// then pub.dev.
+pub fn resolve_registry(registry: Option<&str>) -> String {
+    registry
+        .map(String::from)
+        .or_else(|| std::env::var(PUB_HOSTED_URL_ENV).ok())
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_REGISTRY.to_string())
+}
+
+/// URL of the package listing in the hosted pub repository API.
+pub fn package_api_url(registry: &str, package_name: &str) -> String {
+    format!(
+        "{}/api/packages/{}",
+        registry.trim_end_matches('/'),
+        package_name
+    )
+}
+
+/// Extract the version strings from a pub API package listing.
+pub fn parse_published_versions(json: &str) -> Result<Vec<String>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Registry returned invalid JSON")?;
+    let versions = value
+        .get("versions")
+        .and_then(|v| v.as_array())
+        .context("Registry response has no `versions` list")?;
+    Ok(versions
+        .iter()
+        .filter_map(|v| v.get("version").and_then(|v| v.as_str()))
+        .map(String::from)
+        .collect())
+}
+
+/// Compare a local version against the published ones.
+///
+/// Returns the status together with the highest published version.
+pub fn compare_with_published(
+    local: Option<&str>,
+    published: &[String],
+) -> (PublishedStatus, Option<String>) {
+    let latest = published
+        .iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .max();
+    let latest_str = latest.as_ref().map(Version::to_string);
+    let Some(local) = local.and_then(|v| Version::parse(v).ok()) else {
+        return (PublishedStatus::Unknown, latest_str);
+    };
+
+    let status = if published
+        .iter()
+        .any(|v| Version::parse(v).is_ok_and(|v| v == local))
+    {
+        PublishedStatus::Published
+    } else {
+        match latest {
+            Some(ref latest) if local < *latest => PublishedStatus::Older,
+            _ => PublishedStatus::Newer,
+        }
+    };
+    (status, latest_str)
+}
+
+/// Fetch the published versions of a package with `curl`.
+///
+/// A 404 means the package was never published and yields an empty list.
+/// Any other failure (no `curl`, network error, unexpected status or body)
+/// yields `None`.
+pub fn fetch_published_versions(registry: &str, package_name: &str) -> Option<Vec<String>> {
+    let output = std::process::Command::new("curl")
+        .args(["-sSL", "--max-time", "10", "-w", "\n%{http_code}"])
+        .arg(package_api_url(registry, package_name))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.trim_end().rsplit_once('\n')?;
+    match status {
+        "200" => parse_published_versions(body).ok(),
+        "404" => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+/// Compare each package's local version with the registry, in parallel.
+///
+/// Unreachable registries degrade to [`PublishedStatus::Unknown`].
+pub fn check_published(packages: &[Package], registry: &str) -> Vec<PublishedCheck> {
+    packages
+        .par_iter()
+        .map(|pkg| {
+            let (status, latest_published) = match fetch_published_versions(registry, &pkg.name) {
+                Some(published) => compare_with_published(pkg.version.as_deref(), &published),
+                None => (PublishedStatus::Unknown, None),
+            };
+            PublishedCheck {
+                name: pkg.name.clone(),
+                local_version: pkg.version.clone(),
+                latest_published,
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Order `packages` for publishing: dependencies before their dependents.
+///
+/// The order is computed over every workspace package in `all`, so a
+/// dependency reached only through a private (unpublished) package still
+/// comes first.
+pub fn publish_order(packages: &[Package], all: &[Package]) -> Vec<Package> {
+    let by_name: HashMap<&str, &Package> = packages.iter().map(|p| (p.name.as_str(), p)).collect();
+    DependencyGraph::new(all)
+        .topo_order()
+        .into_iter()
+        .filter_map(|name| by_name.get(name).map(|&pkg| pkg.clone()))
+        .collect()
+}
+
+/// Names of the packages in `pending` that depend on `name`, directly or
+/// through other workspace packages in `all`.
+///
+/// Publishing these must wait until `name` is live on the registry.
+pub fn pending_dependents(name: &str, pending: &[Package], all: &[Package]) -> Vec<String> {
+    let dependents = DependencyGraph::new(all).transitive_dependents([name]);
+    pending
+        .iter()
+        .filter(|p| p.name != name && dependents.contains(&p.name))
+        .map(|p| p.name.clone())
+        .collect()
+}
+
+/// Poll until `version` shows up in the versions returned by `fetch`.
+///
+/// Fetch failures count as "not yet". Returns `false` once `timeout` has
+/// elapsed without the version appearing. Blocks the calling thread.
+pub fn wait_until_published(
+    mut fetch: impl FnMut() -> Option<Vec<String>>,
+    version: &str,
+    interval: Duration,
+    timeout: Duration,
+) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(published) = fetch()
+            && compare_with_published(Some(version), &published).0 == PublishedStatus::Published
+        {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::sleep(interval.min(deadline - now));
+    }
+}
+
 /// Run `dart pub publish` across packages, emitting events for progress tracking.
 ///
 /// Returns [`PackageResults`] with per-package success/failure status.
@@ -42,18 +335,12 @@ pub async fn run(
     events: Option<&UnboundedSender<Event>>,
 ) -> Result<PackageResults> {
     let cmd = build_publish_command(opts.dry_run);
+    let env = build_publish_env(workspace.env_vars(), opts.tag.as_deref());
     let runner = ProcessRunner::new(opts.concurrency, false);
     let results = runner
-        .run_in_packages_with_events(
-            packages,
-            &cmd,
-            &workspace.env_vars(),
-            None,
-            events,
-            &workspace.packages,
-        )
+        .run_in_packages_with_events(packages, &cmd, &env, None, events, &workspace.packages)
         .await?;
-    Ok(PackageResults::from(results))
+    Ok(results)
 }
 
 #[cfg(test)]
@@ -86,4 +373,247 @@ mod tests {
     fn test_build_git_tag_zero_version() {
         assert_eq!(build_git_tag("utils", "0.0.0"), "utils-v0.0.0");
     }
+
+    #[test]
+    fn test_release_channel_prerelease_only() {
+        assert_eq!(release_channel("2.0.0-beta.1", Some("beta")), Some("beta"));
+        assert_eq!(release_channel("2.0.0", Some("beta")), None);
+        assert_eq!(release_channel("2.0.0-beta.1", None), None);
+    }
+
+    #[test]
+    fn test_publish_invocation_threads_channel_for_prerelease() {
+        let version = "2.0.0-beta.1";
+        let channel = release_channel(version, Some("beta"));
+        let env = build_publish_env(HashMap::new(), channel);
+        assert_eq!(env.get(PUBLISH_TAG_ENV).map(String::as_str), Some("beta"));
+        assert_eq!(
+            build_release_message("core", version, channel),
+            "Release core v2.0.0-beta.1 (beta)"
+        );
+    }
+
+    #[test]
+    fn test_publish_env_without_tag() {
+        let env = build_publish_env(HashMap::new(), None);
+        assert!(!env.contains_key(PUBLISH_TAG_ENV));
+        assert_eq!(
+            build_release_message("core", "1.0.0", None),
+            "Release core v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_package_api_url_trims_trailing_slash() {
+        assert_eq!(
+            package_api_url("https://pub.dev", "core"),
+            "https://pub.dev/api/packages/core"
+        );
+        assert_eq!(
+            package_api_url("https://pub.example.com/", "core"),
+            "https://pub.example.com/api/packages/core"
+        );
+    }
+
+    #[test]
+    fn test_resolve_registry_prefers_flag() {
+        assert_eq!(
+            resolve_registry(Some("https://pub.example.com")),
+            "https://pub.example.com"
+        );
+    }
+
+    #[test]
+    fn test_parse_published_versions() {
+        let json = r#"{"name":"core","latest":{"version":"1.1.0"},"versions":[{"version":"1.0.0","pubspec":{}},{"version":"1.1.0"}]}"#;
+        assert_eq!(
+            parse_published_versions(json).unwrap(),
+            vec!["1.0.0", "1.1.0"]
+        );
+        assert!(parse_published_versions(r#"{"error":"x"}"#).is_err());
+        assert!(parse_published_versions("<html>").is_err());
+    }
+
+    #[test]
+    fn test_compare_with_published() {
+        let published = vec!["1.0.0".to_string(), "1.2.0".to_string()];
+        let latest = Some("1.2.0".to_string());
+        assert_eq!(
+            compare_with_published(Some("1.2.0"), &published),
+            (PublishedStatus::Published, latest.clone())
+        );
+        assert_eq!(
+            compare_with_published(Some("1.3.0"), &published),
+            (PublishedStatus::Newer, latest.clone())
+        );
+        assert_eq!(
+            compare_with_published(Some("1.1.0"), &published),
+            (PublishedStatus::Older, latest.clone())
+        );
+        assert_eq!(
+            compare_with_published(Some("2.0.0-beta.1"), &published),
+            (PublishedStatus::Newer, latest.clone())
+        );
+        assert_eq!(
+            compare_with_published(Some("0.1.0"), &[]),
+            (PublishedStatus::Newer, None)
+        );
+        assert_eq!(
+            compare_with_published(None, &published),
+            (PublishedStatus::Unknown, latest)
+        );
+    }
+
+    #[test]
+    fn test_fetch_unreachable_registry_is_unknown() {
+        let checks = check_published(
+            &[Package {
+                name: "core".to_string(),
+                path: std::path::PathBuf::from("/ws/core"),
+                version: Some("1.0.0".to_string()),
+                is_flutter: false,
+                publish_to: None,
+                dependencies: vec![],
+                dev_dependencies: vec![],
+                dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
+                resolution: None,
+            }],
+            "http://127.0.0.1:9",
+        );
+        assert_eq!(checks[0].status, PublishedStatus::Unknown);
+        assert_eq!(checks[0].latest_published, None);
+    }
+
+    fn pkg(name: &str, deps: &[&str]) -> Package {
+        Package {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/ws/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_publish_order_respects_private_packages() {
+        // app -> internal (private) -> core
+        let all = vec![
+            pkg("app", &["internal"]),
+            pkg("internal", &["core"]),
+            pkg("core", &[]),
+            pkg("zed", &[]),
+        ];
+        let publishable = vec![all[0].clone(), all[2].clone(), all[3].clone()];
+        let order: Vec<String> = publish_order(&publishable, &all)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(order, vec!["core", "zed", "app"]);
+
+        assert_eq!(
+            pending_dependents("core", &publishable[..1], &all),
+            vec!["app"]
+        );
+        assert!(pending_dependents("zed", &publishable[..1], &all).is_empty());
+    }
+
+    #[test]
+    fn test_wait_until_published() {
+        let mut polls = 0;
+        let found = wait_until_published(
+            || {
+                polls += 1;
+                match polls {
+                    1 => None,
+                    2 => Some(vec!["0.9.0".to_string()]),
+                    _ => Some(vec!["0.9.0".to_string(), "1.0.0".to_string()]),
+                }
+            },
+            "1.0.0",
+            Duration::ZERO,
+            Duration::from_secs(5),
+        );
+        assert!(found);
+        assert_eq!(polls, 3);
+
+        let found = wait_until_published(
+            || Some(vec![]),
+            "1.0.0",
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        );
+        assert!(!found);
+    }
+
+    #[test]
+    fn test_stable_packages_for_tag() {
+        let make = |name: &str, version: Option<&str>| Package {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/ws/{}", name)),
+            version: version.map(String::from),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        };
+        let packages = vec![
+            make("core", Some("2.0.0-beta.1")),
+            make("utils", Some("1.4.0")),
+            make("bare", None),
+        ];
+        assert_eq!(stable_packages_for_tag(&packages), vec!["utils", "bare"]);
+    }
+
+    #[test]
+    fn test_select_packages() {
+        let make = |name: &str, publish_to: Option<&str>| Package {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/ws/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: publish_to.map(String::from),
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        };
+        let all = vec![
+            make("core", None),
+            make("utils", None),
+            make("app", Some("none")),
+            make("extra", None),
+        ];
+        let filtered = vec![all[1].clone(), all[0].clone()];
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let selected = select_packages(&filtered, &all, &names(&["core", "utils"])).unwrap();
+        let selected: Vec<&str> = selected.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(selected, vec!["utils", "core"]);
+
+        let err = select_packages(&filtered, &all, &names(&["core", "nope", "gone"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--select names unknown package(s): nope, gone"
+        );
+        let err = select_packages(&filtered, &all, &names(&["app"])).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("private package(s) that cannot be published: app")
+        );
+        let err = select_packages(&filtered, &all, &names(&["extra"])).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("excluded by the package filters: extra")
+        );
+    }
 }
@@ -7,6 +7,10 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
+use crate::config::filter::PackageFilters;
+use crate::config::script::MatrixEntry;
+use crate::package::Package;
+
 /// Maximum recursion depth for nested script references.
 pub const MAX_SCRIPT_DEPTH: usize = 16;
 
@@ -36,8 +40,11 @@ impl Default for ExecFlags {
 
 /// Parse all exec flags from a `melos exec [flags] -- <command>` string.
 ///
-/// Recognizes: `-c N` / `--concurrency N`, `--fail-fast`, `--order-dependents`,
+/// Recognizes: `-c N` / `--concurrency N` (also `auto`, `auto/N`), `--fail-fast`, `--order-dependents`,
 /// `--timeout N`, `--dry-run`, `--file-exists[=]<path>`.
+// Flags missing their value are kept in their own arms rather than folded
+// into match guards, so each flag reads as one unit.
+#[allow(clippy::collapsible_match)]
 pub fn parse_exec_flags(command: &str) -> ExecFlags {
     let mut flags = ExecFlags::default();
     let parts: Vec<&str> = command.split_whitespace().collect();
@@ -47,7 +54,7 @@ pub fn parse_exec_flags(command: &str) -> ExecFlags {
         match parts[i] {
             "-c" | "--concurrency" => {
                 if i + 1 < parts.len() {
-                    if let Ok(n) = parts[i + 1].parse::<usize>() {
+                    if let Ok(n) = crate::runner::parse_concurrency(parts[i + 1]) {
                         flags.concurrency = n;
                     }
                     i += 1;
@@ -220,51 +227,77 @@ pub fn normalize_line_continuations(command: &str) -> String {
     result
 }
 
-/// Substitute environment variables in a command string.
+/// Expand environment variable references in a command string.
 ///
 /// Replaces `${VAR_NAME}` (braced form) and `$VAR_NAME` (bare form) with their
-/// values from the env map. The bare `$VAR` form uses word-boundary matching to
-/// avoid replacing `$MELOS_ROOT_PATH` when `$MELOS_ROOT` is also defined.
-pub fn substitute_env_vars(command: &str, env: &HashMap<String, String>) -> String {
-    let mut result = command.to_string();
-
-    // Sort keys by length descending so longer variable names are replaced first.
-    // This prevents `$MELOS_ROOT` from matching before `$MELOS_ROOT_PATH`.
-    let mut sorted_keys: Vec<&String> = env.keys().collect();
-    sorted_keys.sort_by_key(|k| std::cmp::Reverse(k.len()));
-
-    for key in sorted_keys {
-        let value = &env[key];
-        // Replace ${VAR} form (always safe - braces delimit the name)
-        result = result.replace(&format!("${{{}}}", key), value);
-
-        // Replace $VAR form with word-boundary awareness:
-        // Match $KEY only when NOT followed by another alphanumeric or underscore.
-        // Since the regex crate doesn't support lookahead, we use a replacement
-        // closure that checks the character after the match.
-        let pattern = format!(r"\${}", regex::escape(key));
-        if let Ok(re) = regex::Regex::new(&pattern) {
-            let bytes = result.clone();
-            let bytes = bytes.as_bytes();
-            result = re
-                .replace_all(&result.clone(), |caps: &regex::Captures| {
-                    // safety: regex group 0 always exists in a Captures
-                    let m = caps.get(0).expect("regex group 0 always exists");
-                    let end = m.end();
-                    // If followed by an alphanumeric or underscore, don't replace
-                    if end < bytes.len() {
-                        let next = bytes[end];
-                        if next.is_ascii_alphanumeric() || next == b'_' {
-                            return caps[0].to_string();
-                        }
-                    }
-                    value.clone()
+/// values from the env map. The bare form takes the longest valid name, so
+/// `$MELOS_ROOT_PATH` is never read as `$MELOS_ROOT` followed by `_PATH`.
+///
+/// - Values are inserted verbatim, never expanded again, so `$(...)` or `$$`
+///   inside a value reaches the command unchanged.
+/// - Variables the env map does not define are left as written for the
+///   shell, which may set them itself (loop variables, assignments earlier in
+///   the command, per-package `MELOS_PACKAGE_*` variables).
+/// - `$$` escapes to a literal `$`.
+/// - A `$` not followed by a name (`$1`, `$(...)`, `$?`) is kept as-is.
+pub fn expand_env_vars(input: &str, env: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let Some(offset) = input[i..].find('$') else {
+            out.push_str(&input[i..]);
+            break;
+        };
+        out.push_str(&input[i..i + offset]);
+        let dollar = i + offset;
+        let rest = &input[dollar + 1..];
+
+        if rest.starts_with('$') {
+            out.push('$');
+            i = dollar + 2;
+            continue;
+        }
+
+        let (name, reference_end) = if let Some(braced) = rest.strip_prefix('{') {
+            match braced.find('}') {
+                Some(close) if is_var_name(&braced[..close]) => {
+                    (&braced[..close], dollar + 2 + close + 1)
+                }
+                _ => ("", dollar + 1),
+            }
+        } else {
+            let len = rest
+                .bytes()
+                .enumerate()
+                .take_while(|&(idx, b)| {
+                    b == b'_' || b.is_ascii_alphabetic() || (idx > 0 && b.is_ascii_digit())
                 })
-                .to_string();
+                .count();
+            (&rest[..len], dollar + 1 + len)
+        };
+
+        if name.is_empty() {
+            out.push('$');
+            i = dollar + 1;
+            continue;
         }
+
+        match env.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&input[dollar..reference_end]),
+        }
+        i = reference_end;
     }
+    out
+}
 
-    result
+/// Whether `name` is a valid shell variable name (`[A-Za-z_][A-Za-z0-9_]*`).
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
 }
 
 /// Expand a run command, resolving `melos run <X>` references to the actual
@@ -311,6 +344,61 @@ pub fn expand_command(command: &str) -> anyhow::Result<Vec<String>> {
     Ok(parts)
 }
 
+/// A resolved `matrix` entry: the command and the packages it runs in.
+#[derive(Debug, Clone)]
+pub struct MatrixRun<'a> {
+    pub command: &'a str,
+    pub packages: Vec<Package>,
+}
+
+/// Resolve each `matrix` entry to the packages it runs in.
+///
+/// Each entry's `packageFilters` is merged with the CLI filters and passed to
+/// `select`, which applies them to the workspace packages. Entries keep their
+/// declaration order.
+///
+/// Errors on an empty matrix, an entry with an empty `run` command, or an
+/// entry whose filters `select` rejects (e.g. an unknown category).
+pub fn resolve_matrix<'a>(
+    entries: &'a [MatrixEntry],
+    cli_filters: &PackageFilters,
+    mut select: impl FnMut(&PackageFilters) -> anyhow::Result<Vec<Package>>,
+) -> anyhow::Result<Vec<MatrixRun<'a>>> {
+    if entries.is_empty() {
+        anyhow::bail!("`matrix` has no entries");
+    }
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let command = entry.run.trim();
+            if command.is_empty() {
+                anyhow::bail!("`matrix` entry {} has an empty `run` command", i + 1);
+            }
+            let filters = match entry.package_filters {
+                Some(ref f) => f.merge(cli_filters),
+                None => cli_filters.clone(),
+            };
+            let packages = select(&filters)
+                .map_err(|e| anyhow::anyhow!("`matrix` entry {}: {:#}", i + 1, e))?;
+            Ok(MatrixRun { command, packages })
+        })
+        .collect()
+}
+
+/// Case-insensitive subsequence match used by the interactive script picker.
+///
+/// Every character of `query` must appear in `candidate` in order, not
+/// necessarily adjacent: `bld` matches `build_runner`. An empty query matches
+/// everything.
+pub fn fuzzy_matches(query: &str, candidate: &str) -> bool {
+    let mut chars = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| chars.any(|c| c == q))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,16 +437,16 @@ mod tests {
     }
 
     #[test]
-    fn test_substitute_env_vars() {
+    fn test_expand_env_vars() {
         let mut env = HashMap::new();
         env.insert("MELOS_ROOT_PATH".to_string(), "/workspace".to_string());
 
         assert_eq!(
-            substitute_env_vars("echo $MELOS_ROOT_PATH", &env),
+            expand_env_vars("echo $MELOS_ROOT_PATH", &env),
             "echo /workspace"
         );
         assert_eq!(
-            substitute_env_vars("echo ${MELOS_ROOT_PATH}/bin", &env),
+            expand_env_vars("echo ${MELOS_ROOT_PATH}/bin", &env),
             "echo /workspace/bin"
         );
     }
@@ -373,30 +461,92 @@ mod tests {
 
         // Bare $MELOS_ROOT_PATH should resolve to /workspace, not /root_PATH
         assert_eq!(
-            substitute_env_vars("echo $MELOS_ROOT_PATH", &env),
+            expand_env_vars("echo $MELOS_ROOT_PATH", &env),
             "echo /workspace"
         );
 
         // Bare $MELOS_ROOT alone should still resolve
         assert_eq!(
-            substitute_env_vars("echo $MELOS_ROOT end", &env),
+            expand_env_vars("echo $MELOS_ROOT end", &env),
             "echo /root end"
         );
 
         // Both in the same string
         assert_eq!(
-            substitute_env_vars("$MELOS_ROOT and $MELOS_ROOT_PATH", &env),
+            expand_env_vars("$MELOS_ROOT and $MELOS_ROOT_PATH", &env),
             "/root and /workspace"
         );
 
         // Braced forms should always be unambiguous
         assert_eq!(
-            substitute_env_vars("${MELOS_ROOT} and ${MELOS_ROOT_PATH}", &env),
+            expand_env_vars("${MELOS_ROOT} and ${MELOS_ROOT_PATH}", &env),
             "/root and /workspace"
         );
 
         // $MELOS_ROOT at end of string (no trailing char)
-        assert_eq!(substitute_env_vars("path=$MELOS_ROOT", &env), "path=/root");
+        assert_eq!(expand_env_vars("path=$MELOS_ROOT", &env), "path=/root");
+    }
+
+    #[test]
+    fn test_expand_env_vars_inserts_values_verbatim() {
+        let mut env = HashMap::new();
+        env.insert("OUT_DIR".to_string(), "/workspace/build".to_string());
+        env.insert("REPORT".to_string(), "$OUT_DIR/report.txt".to_string());
+        env.insert("TITLE".to_string(), "$(echo INJECTED)".to_string());
+        env.insert("SECRET".to_string(), "a$$b".to_string());
+
+        assert_eq!(
+            expand_env_vars("cat $REPORT ${OUT_DIR}", &env),
+            "cat $OUT_DIR/report.txt /workspace/build"
+        );
+        assert_eq!(
+            expand_env_vars("echo $TITLE $SECRET", &env),
+            "echo $(echo INJECTED) a$$b"
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars_dollar_escape() {
+        let mut env = HashMap::new();
+        env.insert("NAME".to_string(), "x".to_string());
+
+        assert_eq!(
+            expand_env_vars("echo $$NAME costs $$5 and ${NAME}$$", &env),
+            "echo $NAME costs $5 and x$"
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars_leaves_undefined_for_the_shell() {
+        let mut env = HashMap::new();
+        env.insert("DIR".to_string(), "lib".to_string());
+
+        assert_eq!(
+            expand_env_vars("echo [$MISSING] [${ALSO_MISSING}]", &env),
+            "echo [$MISSING] [${ALSO_MISSING}]"
+        );
+        // Shell-local variables reach the shell intact
+        assert_eq!(
+            expand_env_vars("for f in $DIR/*; do echo $f; done", &env),
+            "for f in lib/*; do echo $f; done"
+        );
+        assert_eq!(
+            expand_env_vars("x=1; echo $x ${x}", &env),
+            "x=1; echo $x ${x}"
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars_keeps_shell_syntax_and_runtime_vars() {
+        let env = HashMap::new();
+
+        assert_eq!(
+            expand_env_vars(
+                "echo $1 $? $(pwd) ${} $ end; cd $MELOS_PACKAGE_PATH && echo ${MELOS_PARENT_PACKAGE_NAME}",
+                &env,
+            ),
+            "echo $1 $? $(pwd) ${} $ end; cd $MELOS_PACKAGE_PATH && echo ${MELOS_PARENT_PACKAGE_NAME}"
+        );
     }
 
     #[test]
@@ -665,4 +815,119 @@ mod tests {
             "flutter test"
         );
     }
+
+    // -----------------------------------------------------------------------
+    // resolve_matrix
+    // -----------------------------------------------------------------------
+
+    fn matrix_pkg(name: &str, is_flutter: bool) -> Package {
+        Package {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/ws/packages/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    fn matrix_entry(flutter: bool, run: &str) -> MatrixEntry {
+        MatrixEntry {
+            package_filters: Some(PackageFilters {
+                flutter: Some(flutter),
+                ..Default::default()
+            }),
+            run: run.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_matrix_runs_each_command_in_its_subset() {
+        let packages = vec![
+            matrix_pkg("app", true),
+            matrix_pkg("core", false),
+            matrix_pkg("utils", false),
+        ];
+        let entries = vec![
+            matrix_entry(false, "dart run build_runner build"),
+            matrix_entry(true, "flutter gen-l10n"),
+        ];
+
+        let runs = resolve_matrix(&entries, &PackageFilters::default(), |filters| {
+            crate::package::filter::apply_filters(&packages, filters, None)
+        })
+        .unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].command, "dart run build_runner build");
+        let names: Vec<&str> = runs[0].packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "utils"]);
+        assert_eq!(runs[1].command, "flutter gen-l10n");
+        let names: Vec<&str> = runs[1].packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app"]);
+    }
+
+    #[test]
+    fn test_resolve_matrix_merges_cli_filters() {
+        let packages = vec![matrix_pkg("core", false), matrix_pkg("utils", false)];
+        let entries = vec![matrix_entry(false, "dart test")];
+        let cli_filters = PackageFilters {
+            scope: Some(vec!["core".to_string()]),
+            ..Default::default()
+        };
+
+        let runs = resolve_matrix(&entries, &cli_filters, |filters| {
+            crate::package::filter::apply_filters(&packages, filters, None)
+        })
+        .unwrap();
+
+        assert_eq!(runs[0].packages.len(), 1);
+        assert_eq!(runs[0].packages[0].name, "core");
+    }
+
+    #[test]
+    fn test_resolve_matrix_rejects_empty_matrix_and_commands() {
+        let packages = vec![matrix_pkg("core", false)];
+        let select = |filters: &PackageFilters| {
+            crate::package::filter::apply_filters(&packages, filters, None)
+        };
+
+        let err = resolve_matrix(&[], &PackageFilters::default(), select).unwrap_err();
+        assert!(err.to_string().contains("no entries"), "got: {err}");
+
+        let entries = vec![matrix_entry(false, "dart test"), matrix_entry(true, "  ")];
+        let err = resolve_matrix(&entries, &PackageFilters::default(), select).unwrap_err();
+        assert!(
+            err.to_string().contains("entry 2 has an empty `run`"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_resolve_matrix_reports_invalid_entry_filters() {
+        let entries = vec![matrix_entry(false, "dart test")];
+        let err = resolve_matrix(&entries, &PackageFilters::default(), |_| {
+            anyhow::bail!("Category 'missing' is not defined")
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`matrix` entry 1: Category 'missing' is not defined"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_matches_subsequence_case_insensitive() {
+        assert!(fuzzy_matches("bld", "build_runner"));
+        assert!(fuzzy_matches("BR", "build_runner"));
+        assert!(fuzzy_matches("", "analyze"));
+        assert!(fuzzy_matches("test", "test"));
+        assert!(!fuzzy_matches("rb", "build"));
+        assert!(!fuzzy_matches("tset", "test"));
+        assert!(!fuzzy_matches("analyzer", "analyze"));
+    }
 }
@@ -0,0 +1,236 @@
+use anyhow::Result;
+
+use crate::commands::health::{self, HealthOpts};
+use crate::workspace::Workspace;
+
+/// Environment variable consulted for the SDK path when no workspace loaded.
+const SDK_PATH_ENV: &str = "MELOS_SDK_PATH";
+
+// ---------------------------------------------------------------------------
+// JSON-serializable result types
+// ---------------------------------------------------------------------------
+
+/// Summary of the loaded workspace for the diagnostics report.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct WorkspaceDiagnostics {
+    pub name: String,
+    pub root: String,
+    pub config_source: String,
+    /// `melos.yaml` (legacy) or `pubspec.yaml` (7.x)
+    pub config_mode: String,
+    pub packages: usize,
+    pub warnings: Vec<String>,
+    pub health_issues: u32,
+}
+
+/// Environment diagnostics for bug reports.
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SelftestReport {
+    pub tool_version: String,
+    pub os: String,
+    pub arch: String,
+    pub sdk_path: Option<String>,
+    pub dart_version: Option<String>,
+    pub flutter_version: Option<String>,
+    pub workspace: Option<WorkspaceDiagnostics>,
+    /// Why no workspace could be loaded, when `workspace` is `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_error: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Collection
+// ---------------------------------------------------------------------------
+
+/// Run `<tool> --version` (from `sdk_path/bin` when given) and return the
+/// first non-empty line it prints, or `None` if the tool is unavailable.
+pub fn detect_tool_version(tool: &str, sdk_path: Option<&str>) -> Option<String> {
+    health::tool_version_output(tool, sdk_path)?
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(String::from)
+}
+
+/// Build the diagnostics report from the outcome of loading the workspace.
+///
+/// Never fails: a workspace that could not be loaded is reported as
+/// "no workspace found" together with the load error. Read-only.
+pub fn build_report(
+    tool_version: &str,
+    loaded: &Result<Workspace>,
+    sdk_path_override: Option<&str>,
+) -> SelftestReport {
+    let (workspace, workspace_error, sdk_path) = match loaded {
+        Ok(ws) => {
+            let health = health::run(
+                &ws.packages,
+                &HealthOpts {
+                    version_drift: false,
+                    missing_fields: false,
+                    sdk_consistency: false,
+                    analysis_options: false,
+                    all: true,
+                    json: true,
+                    root_path: ws.root_path.clone(),
+                    sdk_path: ws.sdk_path.clone(),
+                },
+            );
+            let config_mode = if ws.config_source.is_legacy() {
+                "melos.yaml"
+            } else {
+                "pubspec.yaml"
+            };
+            let diagnostics = WorkspaceDiagnostics {
+                name: ws.config.name.clone(),
+                root: ws.root_path.display().to_string(),
+                config_source: ws.config_source.path().display().to_string(),
+                config_mode: config_mode.to_string(),
+                packages: ws.packages.len(),
+                warnings: ws.warnings.clone(),
+                health_issues: health.total_issues,
+            };
+            (Some(diagnostics), None, ws.sdk_path.clone())
+        }
+        Err(e) => {
+            let sdk_path = sdk_path_override
+                .map(String::from)
+                .or_else(|| std::env::var(SDK_PATH_ENV).ok());
+            (None, Some(format!("{:#}", e)), sdk_path)
+        }
+    };
+
+    SelftestReport {
+        tool_version: tool_version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        dart_version: detect_tool_version("dart", sdk_path.as_deref()),
+        flutter_version: detect_tool_version("flutter", sdk_path.as_deref()),
+        sdk_path,
+        workspace,
+        workspace_error,
+    }
+}
+
+/// Format the report as plain text suitable for pasting into an issue.
+pub fn format_report(report: &SelftestReport) -> String {
+    let not_found = || "not found".to_string();
+    let mut out = String::new();
+    out.push_str(&format!("melos-rs:   {}\n", report.tool_version));
+    out.push_str(&format!("OS:         {} ({})\n", report.os, report.arch));
+    out.push_str(&format!(
+        "SDK path:   {}\n",
+        report
+            .sdk_path
+            .clone()
+            .unwrap_or_else(|| "(PATH)".to_string())
+    ));
+    out.push_str(&format!(
+        "Dart:       {}\n",
+        report.dart_version.clone().unwrap_or_else(not_found)
+    ));
+    out.push_str(&format!(
+        "Flutter:    {}\n",
+        report.flutter_version.clone().unwrap_or_else(not_found)
+    ));
+
+    match &report.workspace {
+        Some(ws) => {
+            out.push_str(&format!("Workspace:  {} ({})\n", ws.name, ws.root));
+            out.push_str(&format!(
+                "Config:     {} [{}]\n",
+                ws.config_source, ws.config_mode
+            ));
+            out.push_str(&format!("Packages:   {}\n", ws.packages));
+            out.push_str(&format!("Health:     {} issue(s)\n", ws.health_issues));
+            if ws.warnings.is_empty() {
+                out.push_str("Warnings:   none\n");
+            } else {
+                out.push_str(&format!("Warnings:   {}\n", ws.warnings.len()));
+                for warning in &ws.warnings {
+                    out.push_str(&format!("  - {}\n", warning));
+                }
+            }
+        }
+        None => {
+            out.push_str("Workspace:  no workspace found\n");
+            if let Some(ref err) = report.workspace_error {
+                out.push_str(&format!("  - {}\n", err));
+            }
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_without_workspace() {
+        let loaded: Result<Workspace> = Err(anyhow::anyhow!("No melos.yaml found"));
+        let report = build_report("1.2.3", &loaded, Some("/nonexistent/sdk"));
+
+        assert_eq!(report.tool_version, "1.2.3");
+        assert_eq!(report.os, std::env::consts::OS);
+        assert!(report.workspace.is_none());
+        assert_eq!(report.dart_version, None);
+
+        let text = format_report(&report);
+        assert!(text.contains("melos-rs:   1.2.3"));
+        assert!(text.contains(std::env::consts::OS));
+        assert!(text.contains("no workspace found"));
+        assert!(text.contains("No melos.yaml found"));
+    }
+
+    #[test]
+    fn test_format_report_with_workspace() {
+        let report = SelftestReport {
+            tool_version: "0.1.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            sdk_path: None,
+            dart_version: Some("Dart SDK version: 3.5.0".to_string()),
+            flutter_version: None,
+            workspace: Some(WorkspaceDiagnostics {
+                name: "my_ws".to_string(),
+                root: "/ws".to_string(),
+                config_source: "/ws/melos.yaml".to_string(),
+                config_mode: "melos.yaml".to_string(),
+                packages: 3,
+                warnings: vec!["config: script 'x' is empty".to_string()],
+                health_issues: 0,
+            }),
+            workspace_error: None,
+        };
+
+        let text = format_report(&report);
+        assert!(text.contains("Dart:       Dart SDK version: 3.5.0"));
+        assert!(text.contains("Flutter:    not found"));
+        assert!(text.contains("Config:     /ws/melos.yaml [melos.yaml]"));
+        assert!(text.contains("Packages:   3"));
+        assert!(text.contains("  - config: script 'x' is empty"));
+    }
+
+    #[test]
+    fn test_report_json_omits_error_when_loaded() {
+        let report = SelftestReport {
+            tool_version: "0.1.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            sdk_path: None,
+            dart_version: None,
+            flutter_version: None,
+            workspace: None,
+            workspace_error: None,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["tool_version"], "0.1.0");
+        assert!(json.get("workspace_error").is_none());
+        assert!(json["workspace"].is_null());
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -13,6 +15,8 @@ use super::PackageResults;
 pub struct TestOpts {
     pub concurrency: usize,
     pub fail_fast: bool,
+    /// Kill a package's tests after this long
+    pub timeout: Option<Duration>,
     pub coverage: bool,
     pub test_randomize_ordering_seed: Option<String>,
     pub update_goldens: bool,
@@ -63,9 +67,12 @@ pub async fn run(
     let flutter_pkgs: Vec<_> = packages.iter().filter(|p| p.is_flutter).cloned().collect();
     let dart_pkgs: Vec<_> = packages.iter().filter(|p| !p.is_flutter).cloned().collect();
 
-    let extra_flags = build_extra_flags(opts);
+    let mut extra_flags = build_extra_flags(opts);
+    if let Some(flag) = workspace.color.test_flag() {
+        extra_flags.push(flag.to_string());
+    }
     let runner = ProcessRunner::new(opts.concurrency, opts.fail_fast);
-    let mut all_results = Vec::new();
+    let mut all_results = PackageResults::default();
 
     if !flutter_pkgs.is_empty() {
         let cmd = build_test_command("flutter", &extra_flags, &opts.extra_args);
@@ -81,7 +88,7 @@ pub async fn run(
                 &flutter_pkgs,
                 &cmd,
                 &workspace.env_vars(),
-                None,
+                opts.timeout,
                 events,
                 &workspace.packages,
             )
@@ -103,7 +110,7 @@ pub async fn run(
                 &dart_pkgs,
                 &cmd,
                 &workspace.env_vars(),
-                None,
+                opts.timeout,
                 events,
                 &workspace.packages,
             )
@@ -111,7 +118,7 @@ pub async fn run(
         all_results.extend(results);
     }
 
-    Ok(PackageResults::from(all_results))
+    Ok(all_results)
 }
 
 #[cfg(test)]
@@ -151,6 +158,7 @@ mod tests {
         let opts = TestOpts {
             concurrency: 1,
             fail_fast: false,
+            timeout: None,
             coverage: false,
             test_randomize_ordering_seed: None,
             no_run: false,
@@ -166,6 +174,7 @@ mod tests {
         let opts = TestOpts {
             concurrency: 5,
             fail_fast: true,
+            timeout: None,
             coverage: true,
             test_randomize_ordering_seed: Some("0".to_string()),
             no_run: true,
@@ -185,6 +194,7 @@ mod tests {
         let opts = TestOpts {
             concurrency: 1,
             fail_fast: false,
+            timeout: None,
             coverage: false,
             test_randomize_ordering_seed: None,
             no_run: false,
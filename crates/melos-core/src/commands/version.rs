@@ -5,16 +5,23 @@
 //! All functions are free of terminal/colored dependencies so they can be
 //! tested and reused independently.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::path::Path;
 
 use anyhow::{Context, Result, bail};
 use semver::{Prerelease, Version};
 
+use tokio::sync::mpsc::UnboundedSender;
+
 use crate::config::RepositoryConfig;
 use crate::config::filter::PackageFilters;
-use crate::package::Package;
+use crate::events::Event;
+use crate::package::{Package, VERSION_FILE, read_version_file};
+use crate::runner::ProcessRunner;
+use crate::workspace::Workspace;
+
+use super::PackageResults;
 
 // ---------------------------------------------------------------------------
 // Conventional commit types
@@ -143,15 +150,83 @@ pub fn highest_bump(commits: &[ConventionalCommit]) -> BumpType {
 // Git I/O
 // ---------------------------------------------------------------------------
 
-/// Retrieve git log commits since a ref and parse them as conventional commits.
+/// Lower bound of the git log range scanned for conventional commits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitRange {
+    /// Commits reachable from HEAD but not from this ref (`<ref>..HEAD`)
+    SinceRef(String),
+    /// Commits on HEAD newer than this ISO-8601 date (`git log --since`)
+    SinceDate(String),
+}
+
+impl CommitRange {
+    /// Classify a user-supplied bound: an ISO-8601 date (`2024-05-01`,
+    /// `2024-05-01T09:30:00Z`) is a date, anything else a git ref.
+    pub fn parse(value: &str) -> Self {
+        if is_iso8601_date(value) {
+            Self::SinceDate(value.to_string())
+        } else {
+            Self::SinceRef(value.to_string())
+        }
+    }
+
+    /// Resolve the range for a version run: the explicit `bound` wins over
+    /// the latest tag (only looked up when needed), and `HEAD~10` is the
+    /// last resort.
+    pub fn resolve(bound: Option<&str>, latest_tag: impl FnOnce() -> Option<String>) -> Self {
+        match bound {
+            Some(value) => Self::parse(value),
+            None => Self::SinceRef(latest_tag().unwrap_or_else(|| "HEAD~10".to_string())),
+        }
+    }
+
+    /// Revision-range arguments for `git log`.
+    pub fn git_log_args(&self) -> Vec<String> {
+        match self {
+            Self::SinceRef(r) => vec![format!("{}..HEAD", r)],
+            Self::SinceDate(date) => vec![format!("--since={}", date), "HEAD".to_string()],
+        }
+    }
+}
+
+impl fmt::Display for CommitRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SinceRef(r) => write!(f, "{}", r),
+            Self::SinceDate(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+/// Whether `value` is an ISO-8601 calendar date (`YYYY-MM-DD`), optionally
+/// followed by a `T`-separated time.
+fn is_iso8601_date(value: &str) -> bool {
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    let number = |s: &str, len: usize| -> Option<u32> {
+        (s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| s.parse().ok())
+            .flatten()
+    };
+    let valid_date = number(year, 4).is_some()
+        && number(month, 2).is_some_and(|m| (1..=12).contains(&m))
+        && number(day, 2).is_some_and(|d| (1..=31).contains(&d));
+    valid_date && time.is_none_or(|t| t.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Retrieve git log commits in `range` and parse them as conventional commits.
 /// Returns commits that successfully parse as conventional commits.
-pub fn parse_commits_since(root: &Path, since_ref: &str) -> Result<Vec<ConventionalCommit>> {
+pub fn parse_commits_since(root: &Path, range: &CommitRange) -> Result<Vec<ConventionalCommit>> {
     let output = std::process::Command::new("git")
-        .args([
-            "log",
-            &format!("{}..HEAD", since_ref),
-            "--format=%h%n%B%n---END---",
-        ])
+        .arg("log")
+        .args(range.git_log_args())
+        .arg("--format=%h%n%B%n---END---")
         .current_dir(root)
         .output()
         .context("Failed to run git log")?;
@@ -188,14 +263,25 @@ pub fn parse_commits_since(root: &Path, since_ref: &str) -> Result<Vec<Conventio
 
 /// Map commits to packages based on changed files in each commit.
 /// Returns a map of package name -> `Vec<ConventionalCommit>`.
+///
+/// With `map_scopes` (`includeScopes`), a commit whose scope exactly matches
+/// a workspace package name is also attributed to that package, even if it
+/// touched no files there. Each package gets a commit at most once.
 pub fn map_commits_to_packages(
     root: &Path,
     commits: &[ConventionalCommit],
     packages: &[Package],
+    map_scopes: bool,
 ) -> Result<HashMap<String, Vec<ConventionalCommit>>> {
     let mut package_commits: HashMap<String, Vec<ConventionalCommit>> = HashMap::new();
 
     for commit in commits {
+        let scoped = if map_scopes {
+            scope_package(commit, packages)
+        } else {
+            None
+        };
+
         let output = std::process::Command::new("git")
             .args([
                 "diff-tree",
@@ -218,9 +304,10 @@ pub fn map_commits_to_packages(
             let pkg_relative = pkg.path.strip_prefix(root).unwrap_or(&pkg.path);
             let pkg_prefix = pkg_relative.to_string_lossy();
 
-            let affects_package = changed_files
-                .iter()
-                .any(|f| f.starts_with(pkg_prefix.as_ref()));
+            let affects_package = scoped.is_some_and(|s| s.name == pkg.name)
+                || changed_files
+                    .iter()
+                    .any(|f| f.starts_with(pkg_prefix.as_ref()));
 
             if affects_package {
                 package_commits
@@ -234,6 +321,12 @@ pub fn map_commits_to_packages(
     Ok(package_commits)
 }
 
+/// The workspace package whose name exactly matches the commit's scope.
+fn scope_package<'a>(commit: &ConventionalCommit, packages: &'a [Package]) -> Option<&'a Package> {
+    let scope = commit.scope.as_deref()?;
+    packages.iter().find(|p| p.name == scope)
+}
+
 /// Validate that we are on the expected branch (from config).
 pub fn validate_branch(root: &Path, expected_branch: &str) -> Result<()> {
     let output = std::process::Command::new("git")
@@ -256,16 +349,64 @@ pub fn validate_branch(root: &Path, expected_branch: &str) -> Result<()> {
     Ok(())
 }
 
+/// List every tag in the repository at `root`.
+pub fn list_git_tags(root: &Path) -> Result<HashSet<String>> {
+    let output = std::process::Command::new("git")
+        .args(["tag", "--list"])
+        .current_dir(root)
+        .output()
+        .context("Failed to list git tags")?;
+
+    if !output.status.success() {
+        bail!(
+            "git tag --list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// The `(package, tag)` pairs of `planned` `(package, version)` releases
+/// whose `<name>-v<version>` tag is already in `existing`, in plan order.
+///
+/// Used as a pre-flight check so `version` fails before changing anything
+/// instead of midway through tagging, e.g. after a partially failed run.
+pub fn find_existing_tags(
+    existing: &HashSet<String>,
+    planned: &[(String, String)],
+) -> Vec<(String, String)> {
+    planned
+        .iter()
+        .map(|(name, version)| (name.clone(), super::publish::build_git_tag(name, version)))
+        .filter(|(_, tag)| existing.contains(tag))
+        .collect()
+}
+
 /// Create an annotated git tag for a package version.
 ///
+/// With `overwrite`, an existing tag of the same name is replaced.
 /// Returns the tag name string (e.g. `"my_pkg-v1.2.0"`) so the caller can
 /// print colored output or perform other presentation logic.
-pub fn create_git_tag(root: &Path, pkg_name: &str, version: &str) -> Result<String> {
-    let tag_name = format!("{}-v{}", pkg_name, version);
+pub fn create_git_tag(
+    root: &Path,
+    pkg_name: &str,
+    version: &str,
+    overwrite: bool,
+) -> Result<String> {
+    let tag_name = super::publish::build_git_tag(pkg_name, version);
     let message = format!("{} v{}", pkg_name, version);
 
+    let mut args = vec!["tag", "-a", &tag_name, "-m", &message];
+    if overwrite {
+        args.push("--force");
+    }
     let status = std::process::Command::new("git")
-        .args(["tag", "-a", &tag_name, "-m", &message])
+        .args(&args)
         .current_dir(root)
         .status()
         .context("Failed to create git tag")?;
@@ -302,8 +443,11 @@ pub fn git_commit(root: &Path, message: &str) -> Result<()> {
     Ok(())
 }
 
-/// Push commits and tags to the remote repository.
-pub fn git_push(root: &Path, include_tags: bool) -> Result<()> {
+/// Push commits, then `tags` to `origin`.
+///
+/// Only the given tags are pushed; those also in `replaced` are force-pushed
+/// so they overwrite the remote's copy, and no other remote tag is touched.
+pub fn git_push(root: &Path, tags: &[String], replaced: &[String]) -> Result<()> {
     let push_status = std::process::Command::new("git")
         .args(["push"])
         .current_dir(root)
@@ -314,15 +458,23 @@ pub fn git_push(root: &Path, include_tags: bool) -> Result<()> {
         bail!("git push failed");
     }
 
-    if include_tags {
+    if !tags.is_empty() {
+        let refspecs: Vec<String> = tags
+            .iter()
+            .map(|tag| {
+                let force = if replaced.contains(tag) { "+" } else { "" };
+                format!("{}refs/tags/{}", force, tag)
+            })
+            .collect();
         let tag_status = std::process::Command::new("git")
-            .args(["push", "--tags"])
+            .args(["push", "origin"])
+            .args(&refspecs)
             .current_dir(root)
             .status()
             .context("Failed to push tags")?;
 
         if !tag_status.success() {
-            bail!("git push --tags failed");
+            bail!("git push origin {} failed", refspecs.join(" "));
         }
     }
 
@@ -451,6 +603,16 @@ pub struct ChangelogOptions<'a> {
     pub include_date: bool,
 }
 
+impl ChangelogOptions<'_> {
+    /// Whether a commit's body should be written to the changelog.
+    ///
+    /// Bodies are included only when `include_body` is set and either
+    /// `only_breaking_bodies` is off or the commit is a breaking change.
+    pub fn should_include_body(&self, commit: &ConventionalCommit) -> bool {
+        self.include_body && (!self.only_breaking_bodies || commit.breaking)
+    }
+}
+
 impl Default for ChangelogOptions<'_> {
     fn default() -> Self {
         Self {
@@ -493,6 +655,83 @@ pub fn chrono_date_today() -> String {
     format!("{:04}-{:02}-{:02}", y, m, d)
 }
 
+/// Placeholders recognized by [`render_commit_message`].
+pub const COMMIT_MESSAGE_PLACEHOLDERS: &[&str] = &[
+    "new_package_versions",
+    "new_version",
+    "package_name",
+    "package_list",
+    "count",
+    "date",
+];
+
+/// Fill in the release commit message `template` for the `versioned`
+/// `(package, new version)` pairs.
+///
+/// - `{new_package_versions}`: one ` - name @ version` line per package
+/// - `{new_version}`: the version shared by every package (coordinated mode
+///   or a single package), else `name@version` pairs joined with `, `
+/// - `{package_name}` / `{package_list}`: package names joined with `, `
+/// - `{count}`: the number of packages
+/// - `{date}`: `date`, an ISO `YYYY-MM-DD` date
+///
+/// Other `{identifier}` placeholders are left as written and returned, each
+/// once, so the caller can warn about them.
+pub fn render_commit_message(
+    template: &str,
+    versioned: &[(String, String)],
+    date: &str,
+) -> (String, Vec<String>) {
+    let names: Vec<&str> = versioned.iter().map(|(name, _)| name.as_str()).collect();
+    let package_list = names.join(", ");
+    let new_version = match versioned.first() {
+        Some((_, first)) if versioned.iter().all(|(_, v)| v == first) => first.clone(),
+        _ => versioned
+            .iter()
+            .map(|(name, v)| format!("{}@{}", name, v))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let new_package_versions = versioned
+        .iter()
+        .map(|(name, ver)| format!(" - {} @ {}", name, ver))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut message = String::with_capacity(template.len());
+    let mut unknown: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        message.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        if name_len == 0 || !after[name_len..].starts_with('}') {
+            message.push('{');
+            rest = after;
+            continue;
+        }
+        let name = &after[..name_len];
+        match name {
+            "new_package_versions" => message.push_str(&new_package_versions),
+            "new_version" => message.push_str(&new_version),
+            "package_name" | "package_list" => message.push_str(&package_list),
+            "count" => message.push_str(&versioned.len().to_string()),
+            "date" => message.push_str(date),
+            _ => {
+                message.push_str(&rest[open..open + name_len + 2]);
+                if !unknown.iter().any(|u| u == name) {
+                    unknown.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[name_len + 1..];
+    }
+    message.push_str(rest);
+    (message, unknown)
+}
+
 /// Generate a CHANGELOG.md entry for a package version.
 pub fn generate_changelog_entry(
     version: &str,
@@ -553,9 +792,8 @@ pub fn generate_changelog_entry(
         let mut entry = format!("- {}{}{}", scope_prefix, commit.description, hash_suffix);
 
         // Include body if configured, respecting only_breaking_bodies filter
-        if opts.include_body
+        if opts.should_include_body(commit)
             && let Some(ref body) = commit.body
-            && (!opts.only_breaking_bodies || commit.breaking)
         {
             entry.push_str(&format!("\n  {}", body.replace('\n', "\n  ")));
         }
@@ -605,16 +843,25 @@ pub fn generate_changelog_entry(
 // Filesystem I/O
 // ---------------------------------------------------------------------------
 
+/// Heading of the changelog section for commits not yet in a release.
+pub const UNRELEASED_VERSION: &str = "Unreleased";
+
 /// Write or prepend a CHANGELOG entry to a package's CHANGELOG.md.
+///
+/// A topmost [`UNRELEASED_VERSION`] section is dropped, since the released
+/// entry covers the same commits.
 pub fn write_changelog(pkg_path: &Path, entry: &str) -> Result<()> {
     let changelog_path = pkg_path.join("CHANGELOG.md");
 
-    let existing = if changelog_path.exists() {
+    let mut existing = if changelog_path.exists() {
         std::fs::read_to_string(&changelog_path)
             .with_context(|| format!("Failed to read {}", changelog_path.display()))?
     } else {
         String::new()
     };
+    if let Some((start, end)) = top_section_for(&existing, UNRELEASED_VERSION) {
+        existing.replace_range(start..end.unwrap_or(existing.len()), "");
+    }
 
     // If there's an existing file with a top-level heading, insert after it
     let new_content = match existing.as_str() {
@@ -634,6 +881,149 @@ pub fn write_changelog(pkg_path: &Path, entry: &str) -> Result<()> {
     Ok(())
 }
 
+/// Write a CHANGELOG entry for `version`, replacing the topmost section if it
+/// is already for that version.
+///
+/// Used when regenerating changelogs without bumping, so that running it
+/// again rewrites the entry instead of stacking duplicates. Falls back to
+/// [`write_changelog`] when the latest section is for another version.
+pub fn replace_changelog_entry(pkg_path: &Path, version: &str, entry: &str) -> Result<()> {
+    let changelog_path = pkg_path.join("CHANGELOG.md");
+    let existing = if changelog_path.exists() {
+        std::fs::read_to_string(&changelog_path)
+            .with_context(|| format!("Failed to read {}", changelog_path.display()))?
+    } else {
+        String::new()
+    };
+
+    let Some((start, end)) = top_section_for(&existing, version) else {
+        return write_changelog(pkg_path, entry);
+    };
+    let new_content = match end {
+        Some(end) => format!("{}{}\n{}", &existing[..start], entry, &existing[end..]),
+        None => format!("{}{}", &existing[..start], entry),
+    };
+    std::fs::write(&changelog_path, new_content)
+        .with_context(|| format!("Failed to write {}", changelog_path.display()))?;
+
+    Ok(())
+}
+
+/// Byte range of the topmost `## ` section if it is headed with `version`;
+/// the end is `None` when the section runs to the end of the file.
+fn top_section_for(content: &str, version: &str) -> Option<(usize, Option<usize>)> {
+    let start = find_section_heading(content, 0)?;
+    let heading = format!("## {}", version);
+    let first_line = content[start..].lines().next().unwrap_or_default();
+    if first_line != heading && !first_line.starts_with(&format!("{} (", heading)) {
+        return None;
+    }
+    Some((
+        start,
+        find_section_heading(content, start + first_line.len()),
+    ))
+}
+
+/// Byte offset of the first `## ` heading line at or after `from`.
+fn find_section_heading(content: &str, from: usize) -> Option<usize> {
+    let mut pos = from;
+    for line in content[from..].split_inclusive('\n') {
+        if line.starts_with("## ") && (pos == 0 || content.as_bytes()[pos - 1] == b'\n') {
+            return Some(pos);
+        }
+        pos += line.len();
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Version baselines
+// ---------------------------------------------------------------------------
+
+/// Where a package's current version comes from.
+///
+/// Sources are tried in declaration order: the pubspec `version`, then
+/// `version.txt` next to it (when enabled), then the latest
+/// `<name>-vX.Y.Z` git tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionSource {
+    Pubspec,
+    VersionFile,
+    /// The latest release tag of the package, e.g. `core-v1.2.0`
+    GitTag(String),
+}
+
+impl fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionSource::Pubspec => write!(f, "pubspec.yaml"),
+            VersionSource::VersionFile => write!(f, "{}", VERSION_FILE),
+            VersionSource::GitTag(tag) => write!(f, "git tag {}", tag),
+        }
+    }
+}
+
+/// Whether pubspec `content` declares a top-level `version`.
+fn pubspec_declares_version(content: &str) -> bool {
+    content.lines().any(|line| line.starts_with("version:"))
+}
+
+/// Find the highest `<name>-vX.Y.Z` release tag of a package.
+///
+/// Returns `(tag, version)`, or `None` if git fails or no tag parses as a
+/// version.
+pub fn find_package_version_tag(root: &Path, pkg_name: &str) -> Option<(String, String)> {
+    let prefix = format!("{}-v", pkg_name);
+    let output = std::process::Command::new("git")
+        .args(["tag", "--list", &format!("{}*", prefix)])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|tag| {
+            let version = parse_version_lenient(tag.trim().strip_prefix(&prefix)?)?;
+            Some((version, tag.trim().to_string()))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(version, tag)| (tag, version.to_string()))
+}
+
+/// Fill in versions the pubspec does not declare.
+///
+/// Packages without a pubspec version read it from `version.txt` when
+/// `use_version_file` is set (`command.version.versionFile`), else from
+/// their latest release tag. Returns every package whose version does not
+/// come from its pubspec, with the source used, or `None` when no version
+/// could be derived.
+pub fn resolve_version_fallbacks(
+    root: &Path,
+    packages: &mut [Package],
+    use_version_file: bool,
+) -> Vec<(String, Option<VersionSource>)> {
+    let mut fallbacks = Vec::new();
+    for pkg in packages.iter_mut().filter(|p| p.version.is_none()) {
+        let source = match use_version_file
+            .then(|| read_version_file(&pkg.path))
+            .flatten()
+        {
+            Some(version) => {
+                pkg.version = Some(version);
+                Some(VersionSource::VersionFile)
+            }
+            None => find_package_version_tag(root, &pkg.name).map(|(tag, version)| {
+                pkg.version = Some(version);
+                VersionSource::GitTag(tag)
+            }),
+        };
+        fallbacks.push((pkg.name.clone(), source));
+    }
+    fallbacks
+}
+
 // ---------------------------------------------------------------------------
 // Version computation
 // ---------------------------------------------------------------------------
@@ -680,17 +1070,41 @@ pub fn compute_next_version(current: &str, bump: &str) -> Result<Version> {
     Ok(version)
 }
 
+/// Rank of a `major`/`minor`/`patch` bump (higher is bigger); `None` for
+/// other bump types.
+fn bump_rank(bump: &str) -> Option<u8> {
+    match bump {
+        "patch" => Some(1),
+        "minor" => Some(2),
+        "major" => Some(3),
+        _ => None,
+    }
+}
+
+/// Rank of the release a prerelease base leads up to: `x.0.0` is a major
+/// release, `x.y.0` a minor one, anything else a patch.
+fn prerelease_base_rank(base: &Version) -> u8 {
+    match (base.minor, base.patch) {
+        (0, 0) => 3,
+        (_, 0) => 2,
+        _ => 1,
+    }
+}
+
 /// Compute the next prerelease version.
 ///
-/// If the current version is already a prerelease with the same base bump and
-/// preid, increment the prerelease counter. Otherwise, bump to the next base
-/// version and start at `<preid>.0`.
+/// If the current version is already a prerelease whose base covers the
+/// bump (e.g. `1.1.0-dev.N` covers `minor` and `patch`), the base is kept:
+/// the counter is incremented for the same preid, or reset to `<preid>.0`
+/// for a different one. Otherwise, bump to the next base version and start
+/// at `<preid>.0`.
 ///
 /// Examples (bump = "minor", preid = "dev"):
 ///   - "1.0.0"         -> "1.1.0-dev.0"
 ///   - "1.1.0-dev.0"   -> "1.1.0-dev.1"
 ///   - "1.1.0-dev.5"   -> "1.1.0-dev.6"
 ///   - "1.1.0-beta.0"  -> "1.1.0-dev.0"  (different preid, reset)
+///   - "1.0.1-dev.2"   -> "1.1.0-dev.0"  (patch prerelease, minor bump needed)
 ///   - "2.0.0-dev.0" with bump "major" -> "2.0.0-dev.1" (already at major prerelease)
 pub fn compute_next_prerelease(current: &str, bump: &str, preid: &str) -> Result<Version> {
     let current_ver = Version::parse(current)
@@ -703,8 +1117,13 @@ pub fn compute_next_prerelease(current: &str, bump: &str, preid: &str) -> Result
     let current_base = Version::new(current_ver.major, current_ver.minor, current_ver.patch);
     let pre_str = current_ver.pre.as_str();
 
+    // A prerelease whose base is too small for the bump (e.g. a breaking change
+    // on `1.1.0-dev.3`) moves to the next base, like a stable version would
+    let covers_bump =
+        bump_rank(bump).is_none_or(|rank| rank <= prerelease_base_rank(&current_base));
+
     // If current is already a prerelease with the same preid, just increment the counter
-    if !pre_str.is_empty() {
+    if !pre_str.is_empty() && covers_bump {
         let prefix = format!("{}.", preid);
         if let Some(counter_str) = pre_str.strip_prefix(&prefix)
             && let Ok(counter) = counter_str.parse::<u64>()
@@ -725,7 +1144,8 @@ pub fn compute_next_prerelease(current: &str, bump: &str, preid: &str) -> Result
         return Ok(result);
     }
 
-    // Current is stable -- bump the base, then add prerelease suffix
+    // Current is stable (or its base is too small) -- bump the base, then add
+    // prerelease suffix
     let base = compute_next_version(
         &format!(
             "{}.{}.{}",
@@ -773,6 +1193,136 @@ pub fn extract_build_number(version_str: &str) -> Option<u64> {
     version_str.split('+').nth(1).and_then(|b| b.parse().ok())
 }
 
+// ---------------------------------------------------------------------------
+// Release plan
+// ---------------------------------------------------------------------------
+
+/// What `version --dry-run --json` would do, package by package.
+#[derive(serde::Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionPlan {
+    pub packages: Vec<PlannedVersion>,
+}
+
+/// One package's planned version change.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedVersion {
+    pub package: String,
+    pub current_version: String,
+    pub new_version: String,
+    /// Kind of change, see [`version_change_kind`]
+    pub bump: &'static str,
+    /// How the package was selected: `conventionalCommits`, `all`,
+    /// `override`, `coordinated` or `graduate`
+    pub reason: &'static str,
+    /// Commits that call for a bump (conventional-commits mode only)
+    pub commits: Vec<PlannedCommit>,
+    /// Whether a CHANGELOG.md entry would be written
+    pub changelog: bool,
+    /// Name of the git tag that would be created, if tagging is enabled
+    pub tag: Option<String>,
+}
+
+/// A conventional commit that triggered a planned bump.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCommit {
+    pub hash: String,
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+    /// Bump this commit implies on its own
+    pub bump: String,
+}
+
+impl PlannedCommit {
+    /// The commits of `commits` that imply a bump.
+    pub fn triggering(commits: &[ConventionalCommit]) -> Vec<Self> {
+        commits
+            .iter()
+            .filter(|c| c.bump_type() != BumpType::None)
+            .map(|c| Self {
+                hash: c.hash.clone(),
+                commit_type: c.commit_type.clone(),
+                scope: c.scope.clone(),
+                breaking: c.breaking,
+                description: c.description.clone(),
+                bump: c.bump_type().to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Classify the change from `current` to `next`.
+///
+/// `major`, `minor` or `patch` when that part of the version moves,
+/// otherwise `prerelease` for a new prerelease of the same version,
+/// `graduate` when a prerelease becomes stable, and `build` when only build
+/// metadata changes.
+pub fn version_change_kind(current: &Version, next: &Version) -> &'static str {
+    if next.major != current.major {
+        "major"
+    } else if next.minor != current.minor {
+        "minor"
+    } else if next.patch != current.patch {
+        "patch"
+    } else if next.pre != current.pre {
+        if next.pre.is_empty() {
+            "graduate"
+        } else {
+            "prerelease"
+        }
+    } else {
+        "build"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Per-package hooks
+// ---------------------------------------------------------------------------
+
+/// Environment variable holding the version a package is being bumped to,
+/// set for `prePackage`/`postPackage` version hooks.
+pub const NEW_VERSION_ENV: &str = "MELOS_NEW_VERSION";
+
+/// Run a `prePackage`/`postPackage` version hook in each versioned package.
+///
+/// `versioned` pairs each package with its new version. Packages run one at
+/// a time, in order, with the usual `MELOS_PACKAGE_*` variables (where
+/// `MELOS_PACKAGE_VERSION` is still the version before the bump) plus
+/// [`NEW_VERSION_ENV`]. Stops at the first failure; the returned results
+/// only cover the packages that ran.
+pub async fn run_package_version_hook(
+    workspace: &Workspace,
+    hook: &str,
+    versioned: &[(&Package, String)],
+    events: Option<&UnboundedSender<Event>>,
+) -> Result<PackageResults> {
+    let runner = ProcessRunner::new(1, true);
+    let mut results = PackageResults::default();
+    for (pkg, new_version) in versioned {
+        let mut env = workspace.env_vars();
+        env.insert(NEW_VERSION_ENV.to_string(), new_version.clone());
+        let outcome = runner
+            .run_in_packages_with_events(
+                std::slice::from_ref(*pkg),
+                hook,
+                &env,
+                None,
+                events,
+                &workspace.packages,
+            )
+            .await?;
+        let failed = outcome.failed() > 0;
+        results.extend(outcome);
+        if failed {
+            break;
+        }
+    }
+    Ok(results)
+}
+
 // ---------------------------------------------------------------------------
 // Pubspec manipulation
 // ---------------------------------------------------------------------------
@@ -784,6 +1334,10 @@ pub fn extract_build_number(version_str: &str) -> Option<u64> {
 /// For `bump == "patch"/"minor"/"major"`, bumps the semver part while
 /// preserving any `+N` suffix.
 ///
+/// A pubspec without a `version` gets the new version written to its
+/// `version.txt` if that is where the current version came from, else a
+/// `version:` line after `name:`.
+///
 /// Returns the new version string. Does **not** print any output.
 pub fn apply_version_bump(pkg: &Package, bump: &str) -> Result<String> {
     let pubspec_path = pkg.path.join("pubspec.yaml");
@@ -809,11 +1363,28 @@ pub fn apply_version_bump(pkg: &Package, bump: &str) -> Result<String> {
         }
     };
 
-    // Replace version in pubspec.yaml
-    let new_content = regex::Regex::new(r"(?m)^version:\s*\S+")
-        .context("Failed to compile version regex")?
-        .replace(&content, &format!("version: {}", next_version_str))
-        .to_string();
+    let declared = pubspec_declares_version(&content);
+    if !declared && read_version_file(&pkg.path).as_deref() == Some(current_version) {
+        let version_path = pkg.path.join(VERSION_FILE);
+        std::fs::write(&version_path, format!("{}\n", next_version_str))
+            .with_context(|| format!("Failed to write {}", version_path.display()))?;
+        return Ok(next_version_str);
+    }
+
+    // Replace version in pubspec.yaml, or add it after the name
+    let new_content = if declared {
+        regex::Regex::new(r"(?m)^version:\s*\S+")
+            .context("Failed to compile version regex")?
+            .replace(&content, &format!("version: {}", next_version_str))
+            .to_string()
+    } else {
+        regex::Regex::new(r"(?m)^name:.*$")
+            .context("Failed to compile name regex")?
+            .replace(&content, |caps: &regex::Captures| {
+                format!("{}\nversion: {}", &caps[0], next_version_str)
+            })
+            .to_string()
+    };
 
     std::fs::write(&pubspec_path, new_content)
         .with_context(|| format!("Failed to write {}", pubspec_path.display()))?;
@@ -821,58 +1392,230 @@ pub fn apply_version_bump(pkg: &Package, bump: &str) -> Result<String> {
     Ok(next_version_str)
 }
 
-/// Update a dependent package's pubspec.yaml to use the new version constraint
-/// for a bumped dependency. Returns `true` if any changes were made.
+/// Parse a version string, ignoring any `+build` suffix.
+pub fn parse_version_lenient(version: &str) -> Option<Version> {
+    Version::parse(version)
+        .or_else(|_| Version::parse(version.split('+').next().unwrap_or(version)))
+        .ok()
+}
+
+/// Whether a hosted Dart version constraint (e.g. `^1.0.0`, `>=1.0.0 <2.0.0`,
+/// `1.2.3`, `any`) allows `version`.
+///
+/// Returns `None` when the constraint cannot be parsed.
+pub fn constraint_allows(constraint: &str, version: &Version) -> Option<bool> {
+    let constraint = constraint.trim().trim_matches(|c| c == '"' || c == '\'');
+    if constraint == "any" {
+        return Some(true);
+    }
+
+    let mut allowed = true;
+    for part in constraint.split_whitespace() {
+        let req = if part.starts_with(['^', '<', '>']) {
+            semver::VersionReq::parse(part).ok()?
+        } else {
+            semver::VersionReq::parse(&format!("={}", part)).ok()?
+        };
+        allowed &= req.matches(version);
+    }
+    Some(allowed)
+}
+
+/// The first version that is a breaking change from `version` under Dart's
+/// caret semantics (`^1.2.3` -> `2.0.0`, `^0.2.3` -> `0.3.0`,
+/// `^0.0.3` -> `0.0.4`).
+fn next_breaking(version: &Version) -> Version {
+    if version.major > 0 {
+        Version::new(version.major + 1, 0, 0)
+    } else if version.minor > 0 {
+        Version::new(0, version.minor + 1, 0)
+    } else {
+        Version::new(0, 0, version.patch + 1)
+    }
+}
+
+/// Rewrite `old` so that it allows `new_version`, keeping its shape: carets
+/// stay carets, ranges stay ranges and exact pins stay exact.
+pub fn rewrite_constraint(old: &str, new_version: &Version) -> String {
+    let old = old.trim();
+    let quote = old
+        .chars()
+        .next()
+        .filter(|c| *c == '"' || *c == '\'')
+        .map(String::from)
+        .unwrap_or_default();
+    let bare = old.trim_matches(|c| c == '"' || c == '\'');
+
+    if bare.starts_with('^') {
+        format!("{q}^{v}{q}", q = quote, v = new_version)
+    } else if bare.starts_with(['<', '>']) {
+        // Ranges must be quoted, since a leading `>` starts a YAML block scalar
+        let quote = if quote.is_empty() { "'" } else { &quote };
+        format!(
+            "{q}>={v} <{next}{q}",
+            q = quote,
+            v = new_version,
+            next = next_breaking(new_version)
+        )
+    } else {
+        format!("{q}{v}{q}", q = quote, v = new_version)
+    }
+}
+
+/// Update a dependent package's pubspec.yaml so its hosted constraint on a
+/// bumped dependency allows the new version.
+///
+/// Constraints that already allow the new version (e.g. `^1.0.0` after a
+/// minor bump to `1.1.0`) and path/git/sdk dependencies are left untouched.
+/// Returns the rewritten constraint, or `None` if nothing changed.
 ///
 /// Does **not** print any output.
 pub fn update_dependency_constraint(
     dependent_pkg: &Package,
     dep_name: &str,
     new_version: &str,
-) -> Result<bool> {
+) -> Result<Option<String>> {
+    dependency_constraint_change(dependent_pkg, dep_name, new_version, true)
+}
+
+/// [`update_dependency_constraint`], writing the pubspec only with `write`.
+fn dependency_constraint_change(
+    dependent_pkg: &Package,
+    dep_name: &str,
+    new_version: &str,
+    write: bool,
+) -> Result<Option<String>> {
     let pubspec_path = dependent_pkg.path.join("pubspec.yaml");
     let content = std::fs::read_to_string(&pubspec_path)
         .with_context(|| format!("Failed to read {}", pubspec_path.display()))?;
 
-    // Parse the new version to build a caret constraint like ^1.2.0
-    let ver = Version::parse(new_version)
-        .or_else(|_| {
-            let cleaned = new_version.split('+').next().unwrap_or(new_version);
-            Version::parse(cleaned)
-        })
-        .unwrap_or_else(|_| Version::new(0, 0, 0));
-
-    let constraint = format!("^{}", ver);
+    let Some(mut ver) = parse_version_lenient(new_version) else {
+        return Ok(None);
+    };
+    // Build numbers (`+N`) are not part of a dependency constraint
+    ver.build = semver::BuildMetadata::EMPTY;
 
-    // Match patterns like:
+    // Match scalar constraints like:
     //   dep_name: ^1.0.0
     //   dep_name: "^1.0.0"
     //   dep_name: '>=1.0.0 <2.0.0'
-    //   dep_name: any
     // But NOT dep_name with a map value (path/git/sdk dependency)
     let pattern = format!(
-        r#"(?m)^(\s+{dep}:\s*)(?:["']?)[<>=^~\d][^"\n]*(?:["']?)"#,
+        r#"(?m)^([ \t]+{dep}:[ \t]*)(["']?[<>=^\d][^"'\n#]*?["']?)([ \t]*(?:#.*)?)$"#,
         dep = regex::escape(dep_name)
     );
     let re = regex::Regex::new(&pattern)
         .with_context(|| format!("Failed to compile dependency regex for '{}'", dep_name))?;
 
-    if !re.is_match(&content) {
-        return Ok(false);
+    let Some(caps) = re.captures(&content) else {
+        return Ok(None);
+    };
+    let old_constraint = &caps[2];
+    if constraint_allows(old_constraint, &ver).unwrap_or(true) {
+        return Ok(None);
     }
 
-    let new_content = re
-        .replace(&content, format!("${{1}}{}", constraint))
-        .to_string();
-
-    if new_content == content {
-        return Ok(false);
+    let constraint = rewrite_constraint(old_constraint, &ver);
+    if write {
+        let new_content = re
+            .replace(&content, |caps: &regex::Captures| {
+                format!("{}{}{}", &caps[1], constraint, &caps[3])
+            })
+            .to_string();
+        std::fs::write(&pubspec_path, new_content)
+            .with_context(|| format!("Failed to write {}", pubspec_path.display()))?;
     }
 
-    std::fs::write(&pubspec_path, new_content)
-        .with_context(|| format!("Failed to write {}", pubspec_path.display()))?;
+    Ok(Some(
+        constraint
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string(),
+    ))
+}
+
+/// A workspace package whose constraints on bumped packages were rewritten.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependentConstraintUpdate {
+    /// Name of the dependent package.
+    pub package: String,
+    /// `(dependency, new constraint)` pairs that were rewritten.
+    pub constraints: Vec<(String, String)>,
+}
+
+/// Rewrite constraints in every package that depends on a bumped package.
+///
+/// Bumped packages are updated too, so sibling constraints stay valid when
+/// several packages are versioned together (e.g. coordinated mode). Path, git
+/// and `any` dependencies, and constraints that already allow the new
+/// version, are left alone. Dependents in the result that were not bumped
+/// themselves should get their own patch bump and changelog entry. Does
+/// **not** print any output.
+pub fn update_dependents_constraints(
+    packages: &[Package],
+    versioned: &[(String, String)],
+) -> Result<Vec<DependentConstraintUpdate>> {
+    dependents_constraints(packages, versioned, true)
+}
+
+/// The updates [`update_dependents_constraints`] would make, without writing
+/// any pubspec. Lets callers check the full release (including dependents
+/// that get their own bump) before changing anything.
+pub fn plan_dependents_constraints(
+    packages: &[Package],
+    versioned: &[(String, String)],
+) -> Result<Vec<DependentConstraintUpdate>> {
+    dependents_constraints(packages, versioned, false)
+}
+
+fn dependents_constraints(
+    packages: &[Package],
+    versioned: &[(String, String)],
+    write: bool,
+) -> Result<Vec<DependentConstraintUpdate>> {
+    let versioned: HashMap<&str, &str> = versioned
+        .iter()
+        .map(|(n, v)| (n.as_str(), v.as_str()))
+        .collect();
+
+    let mut updates = Vec::new();
+    for pkg in packages {
+        let mut constraints = Vec::new();
+        for dep_name in pkg.dependencies.iter().chain(pkg.dev_dependencies.iter()) {
+            if let Some(&new_ver) = versioned.get(dep_name.as_str())
+                && let Some(constraint) =
+                    dependency_constraint_change(pkg, dep_name, new_ver, write)?
+            {
+                constraints.push((dep_name.clone(), constraint));
+            }
+        }
+
+        if !constraints.is_empty() {
+            updates.push(DependentConstraintUpdate {
+                package: pkg.name.clone(),
+                constraints,
+            });
+        }
+    }
+    Ok(updates)
+}
 
-    Ok(true)
+/// Generate a CHANGELOG.md entry for a package bumped only because its
+/// dependency constraints changed.
+pub fn generate_dependency_changelog_entry(
+    version: &str,
+    constraints: &[(String, String)],
+    include_date: bool,
+) -> String {
+    let mut output = if include_date {
+        format!("## {} ({})\n", version, chrono_date_today())
+    } else {
+        format!("## {}\n", version)
+    };
+    output.push_str("\n### Dependencies\n\n");
+    for (dep, constraint) in constraints {
+        output.push_str(&format!("- Bump `{}` to `{}`\n", dep, constraint));
+    }
+    output
 }
 
 // ---------------------------------------------------------------------------
@@ -983,6 +1726,7 @@ pub fn update_git_tag_refs(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RepoKind;
 
     // -----------------------------------------------------------------------
     // parse_version_override
@@ -1067,9 +1811,63 @@ mod tests {
     // -----------------------------------------------------------------------
 
     #[test]
-    fn test_highest_bump() {
-        let commits = vec![
-            parse_conventional_commit("a", "fix: bug").unwrap(),
+    fn test_commit_range_parse_dates_and_refs() {
+        assert_eq!(
+            CommitRange::parse("2024-05-01"),
+            CommitRange::SinceDate("2024-05-01".to_string())
+        );
+        assert_eq!(
+            CommitRange::parse("2024-05-01T09:30:00Z"),
+            CommitRange::SinceDate("2024-05-01T09:30:00Z".to_string())
+        );
+        for r in [
+            "v1.2.0",
+            "core-v1.0.0",
+            "HEAD~5",
+            "2024-13-01",
+            "2024-5-1",
+            "main",
+        ] {
+            assert_eq!(CommitRange::parse(r), CommitRange::SinceRef(r.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_commit_range_git_log_args() {
+        assert_eq!(
+            CommitRange::SinceRef("v1.0.0".to_string()).git_log_args(),
+            vec!["v1.0.0..HEAD"]
+        );
+        assert_eq!(
+            CommitRange::SinceDate("2024-05-01".to_string()).git_log_args(),
+            vec!["--since=2024-05-01", "HEAD"]
+        );
+    }
+
+    #[test]
+    fn test_commit_range_resolve_prefers_explicit_bound() {
+        assert_eq!(
+            CommitRange::resolve(Some("2024-01-15"), || panic!("tag not needed")),
+            CommitRange::SinceDate("2024-01-15".to_string())
+        );
+        assert_eq!(
+            CommitRange::resolve(Some("v1.0.0"), || None),
+            CommitRange::SinceRef("v1.0.0".to_string())
+        );
+        assert_eq!(
+            CommitRange::resolve(None, || Some("v2.0.0".to_string())),
+            CommitRange::SinceRef("v2.0.0".to_string())
+        );
+        assert_eq!(
+            CommitRange::resolve(None, || None),
+            CommitRange::SinceRef("HEAD~10".to_string())
+        );
+    }
+
+    #[test]
+    fn test_highest_bump() {
+        let commits = vec![
+            parse_conventional_commit("a", "fix: bug").unwrap(),
             parse_conventional_commit("b", "feat: feature").unwrap(),
             parse_conventional_commit("c", "chore: cleanup").unwrap(),
         ];
@@ -1181,8 +1979,41 @@ mod tests {
 
     #[test]
     fn test_compute_next_prerelease_different_base() {
+        // A breaking change cannot ship as the 1.1.0 minor release
         let v = compute_next_prerelease("1.1.0-dev.3", "major", "dev").unwrap();
-        assert_eq!(v.to_string(), "1.1.0-dev.4");
+        assert_eq!(v.to_string(), "2.0.0-dev.0");
+    }
+
+    #[test]
+    fn test_compute_next_prerelease_smaller_bump_keeps_base() {
+        // A fix on a minor prerelease is already covered by it
+        let v = compute_next_prerelease("1.2.0-dev.1", "patch", "dev").unwrap();
+        assert_eq!(v.to_string(), "1.2.0-dev.2");
+        let v = compute_next_prerelease("2.0.0-dev.4", "minor", "dev").unwrap();
+        assert_eq!(v.to_string(), "2.0.0-dev.5");
+    }
+
+    #[test]
+    fn test_compute_next_prerelease_patch_base_needs_minor() {
+        let v = compute_next_prerelease("1.0.1-dev.2", "minor", "dev").unwrap();
+        assert_eq!(v.to_string(), "1.1.0-dev.0");
+        let v = compute_next_prerelease("1.0.1-beta.2", "major", "dev").unwrap();
+        assert_eq!(v.to_string(), "2.0.0-dev.0");
+    }
+
+    #[test]
+    fn test_compute_next_prerelease_from_conventional_commits() {
+        // A `feat` on an existing `-dev.N` minor prerelease bumps the counter
+        let feat = parse_conventional_commit("abc1234", "feat: add option").unwrap();
+        let bump = highest_bump(&[feat]).to_string();
+        let v = compute_next_prerelease("1.2.0-dev.3", &bump, "dev").unwrap();
+        assert_eq!(v.to_string(), "1.2.0-dev.4");
+
+        // Graduating afterwards drops the suffix
+        assert_eq!(
+            graduate_version(&v.to_string()).unwrap().to_string(),
+            "1.2.0"
+        );
     }
 
     #[test]
@@ -1333,6 +2164,7 @@ mod tests {
     fn test_generate_changelog_with_commit_links() {
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo".to_string(),
+            kind: RepoKind::GitHub,
         };
         let commits = vec![parse_conventional_commit("abc1234", "feat(ui): new button").unwrap()];
         let entry = generate_changelog_entry(
@@ -1550,6 +2382,64 @@ mod tests {
     // Commit message placeholder
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn test_render_commit_message_coordinated() {
+        let versioned = [
+            ("core".to_string(), "2.0.0".to_string()),
+            ("app".to_string(), "2.0.0".to_string()),
+        ];
+        let render = |template: &str| render_commit_message(template, &versioned, "2026-10-15").0;
+        assert_eq!(
+            render("chore: release {new_version}"),
+            "chore: release 2.0.0"
+        );
+        assert_eq!(render("release {package_list}"), "release core, app");
+        assert_eq!(render("release {package_name}"), "release core, app");
+        assert_eq!(render("{count} packages"), "2 packages");
+        assert_eq!(render("release {date}"), "release 2026-10-15");
+        assert_eq!(
+            render("v{new_version}\n\n{new_package_versions}"),
+            "v2.0.0\n\n - core @ 2.0.0\n - app @ 2.0.0"
+        );
+    }
+
+    #[test]
+    fn test_render_commit_message_independent() {
+        let versioned = [
+            ("core".to_string(), "1.3.0".to_string()),
+            ("app".to_string(), "0.2.1".to_string()),
+        ];
+        let render = |template: &str| render_commit_message(template, &versioned, "2026-10-15").0;
+        assert_eq!(
+            render("release {new_version}"),
+            "release core@1.3.0, app@0.2.1"
+        );
+        assert_eq!(render("release {package_list}"), "release core, app");
+        assert_eq!(render("{count} packages"), "2 packages");
+        assert_eq!(render("{date}: {package_name}"), "2026-10-15: core, app");
+
+        let single = [("core".to_string(), "1.3.0".to_string())];
+        assert_eq!(
+            render_commit_message("{package_name} v{new_version} ({count})", &single, "d").0,
+            "core v1.3.0 (1)"
+        );
+    }
+
+    #[test]
+    fn test_render_commit_message_keeps_unknown_placeholders() {
+        let versioned = [("core".to_string(), "1.0.0".to_string())];
+        let (message, unknown) = render_commit_message(
+            "{oops} {count} {oops} {Other} { spaced } {} {unterminated",
+            &versioned,
+            "d",
+        );
+        assert_eq!(
+            message,
+            "{oops} 1 {oops} {Other} { spaced } {} {unterminated"
+        );
+        assert_eq!(unknown, vec!["oops", "Other"]);
+    }
+
     #[test]
     fn test_message_placeholder_replacement() {
         let template = "chore(release): publish\n\n{new_package_versions}";
@@ -1714,6 +2604,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1724,6 +2615,217 @@ mod tests {
         assert!(content.contains("version: 1.2.4"));
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_package_version_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("melos.yaml"),
+            "name: ws\npackages:\n  - packages/*\n",
+        )
+        .unwrap();
+        for name in ["a", "b", "c"] {
+            let pkg = dir.path().join("packages").join(name);
+            std::fs::create_dir_all(&pkg).unwrap();
+            std::fs::write(
+                pkg.join("pubspec.yaml"),
+                format!("name: {name}\nversion: 1.0.0\n"),
+            )
+            .unwrap();
+        }
+        let ws = Workspace::find_and_load_from(dir.path(), None).unwrap();
+        let pkgs = &ws.packages;
+        let versioned = vec![
+            (&pkgs[0], "1.1.0".to_string()),
+            (&pkgs[1], "2.0.0".to_string()),
+            (&pkgs[2], "1.0.1".to_string()),
+        ];
+
+        let hook =
+            r#"echo "$MELOS_PACKAGE_NAME $MELOS_PACKAGE_VERSION $MELOS_NEW_VERSION" > hook.txt"#;
+        let results = run_package_version_hook(&ws, hook, &versioned, None)
+            .await
+            .unwrap();
+        assert_eq!(results.passed(), 3);
+        let written = std::fs::read_to_string(pkgs[1].path.join("hook.txt")).unwrap();
+        assert_eq!(written, "b 1.0.0 2.0.0\n");
+
+        // The first failure stops the remaining packages
+        let hook = r#"touch ran; [ "$MELOS_PACKAGE_NAME" != b ]"#;
+        let results = run_package_version_hook(&ws, hook, &versioned, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            results.results,
+            vec![("a".to_string(), true), ("b".to_string(), false)]
+        );
+        assert!(!pkgs[2].path.join("ran").exists());
+    }
+
+    #[test]
+    fn test_version_change_kind() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(version_change_kind(&v("1.2.3"), &v("2.0.0")), "major");
+        assert_eq!(version_change_kind(&v("1.2.3"), &v("1.3.0")), "minor");
+        assert_eq!(version_change_kind(&v("1.2.3"), &v("1.2.4")), "patch");
+        assert_eq!(version_change_kind(&v("1.2.3"), &v("1.2.4-dev.0")), "patch");
+        assert_eq!(
+            version_change_kind(&v("1.3.0-dev.0"), &v("1.3.0-dev.1")),
+            "prerelease"
+        );
+        assert_eq!(
+            version_change_kind(&v("1.3.0-dev.1"), &v("1.3.0")),
+            "graduate"
+        );
+        assert_eq!(version_change_kind(&v("1.2.3"), &v("1.2.3+1")), "build");
+    }
+
+    #[test]
+    fn test_planned_commit_triggering_and_json() {
+        let commits: Vec<ConventionalCommit> = [
+            ("a1", "feat: add login"),
+            ("b2", "chore: tidy"),
+            ("c3", "fix(auth)!: drop token"),
+        ]
+        .iter()
+        .filter_map(|(hash, msg)| parse_conventional_commit(hash, msg))
+        .collect();
+        let triggering = PlannedCommit::triggering(&commits);
+        let hashes: Vec<&str> = triggering.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["a1", "c3"]);
+
+        let plan = VersionPlan {
+            packages: vec![PlannedVersion {
+                package: "auth".to_string(),
+                current_version: "1.0.0".to_string(),
+                new_version: "2.0.0".to_string(),
+                bump: "major",
+                reason: "conventionalCommits",
+                commits: triggering,
+                changelog: true,
+                tag: Some("auth-v2.0.0".to_string()),
+            }],
+        };
+        let json = serde_json::to_value(&plan).unwrap();
+        let entry = &json["packages"][0];
+        assert_eq!(entry["currentVersion"], "1.0.0");
+        assert_eq!(entry["newVersion"], "2.0.0");
+        assert_eq!(entry["tag"], "auth-v2.0.0");
+        assert_eq!(entry["commits"][1]["type"], "fix");
+        assert_eq!(entry["commits"][1]["scope"], "auth");
+        assert_eq!(entry["commits"][1]["breaking"], true);
+        assert_eq!(entry["commits"][1]["bump"], "major");
+    }
+
+    #[test]
+    fn test_apply_version_bump_without_pubspec_version() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let pubspec = dir.path().join("pubspec.yaml");
+        std::fs::write(&pubspec, "name: gen\ndescription: x\n").expect("write pubspec");
+        let mut pkg = Package {
+            name: "gen".to_string(),
+            path: dir.path().to_path_buf(),
+            version: Some("0.3.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        };
+
+        // A version.txt is updated in place of the pubspec
+        std::fs::write(dir.path().join(VERSION_FILE), "0.3.0\n").unwrap();
+        assert_eq!(apply_version_bump(&pkg, "minor").unwrap(), "0.4.0");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(VERSION_FILE)).unwrap(),
+            "0.4.0\n"
+        );
+        assert!(
+            !std::fs::read_to_string(&pubspec)
+                .unwrap()
+                .contains("version:")
+        );
+
+        // A version from elsewhere (e.g. a git tag) leaves version.txt alone
+        pkg.version = Some("1.0.0".to_string());
+        assert_eq!(apply_version_bump(&pkg, "patch").unwrap(), "1.0.1");
+        assert_eq!(
+            std::fs::read_to_string(&pubspec).unwrap(),
+            "name: gen\nversion: 1.0.1\ndescription: x\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(VERSION_FILE)).unwrap(),
+            "0.4.0\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_version_fallbacks_from_git_tags() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let root = dir.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .env("GIT_AUTHOR_NAME", "test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        for tag in [
+            "tagged-v1.2.0",
+            "tagged-v1.10.0",
+            "tagged-vnext",
+            "other-v9.0.0",
+        ] {
+            git(&["tag", tag]);
+        }
+
+        let mut packages = Vec::new();
+        for (name, pubspec, version_txt) in [
+            ("declared", "name: declared\nversion: 2.0.0\n", None),
+            ("file", "name: file\n", Some("0.1.0")),
+            ("tagged", "name: tagged\n", None),
+            ("orphan", "name: orphan\n", None),
+        ] {
+            let pkg_dir = root.join(name);
+            std::fs::create_dir_all(&pkg_dir).unwrap();
+            std::fs::write(pkg_dir.join("pubspec.yaml"), pubspec).unwrap();
+            if let Some(v) = version_txt {
+                std::fs::write(pkg_dir.join(VERSION_FILE), v).unwrap();
+            }
+            packages.push(Package::from_path(&pkg_dir).unwrap());
+        }
+
+        let mut without_file = packages.clone();
+        assert_eq!(
+            resolve_version_fallbacks(root, &mut without_file, false)[0],
+            ("file".to_string(), None)
+        );
+
+        let fallbacks = resolve_version_fallbacks(root, &mut packages, true);
+        assert_eq!(
+            fallbacks,
+            vec![
+                ("file".to_string(), Some(VersionSource::VersionFile)),
+                (
+                    "tagged".to_string(),
+                    Some(VersionSource::GitTag("tagged-v1.10.0".to_string()))
+                ),
+                ("orphan".to_string(), None),
+            ]
+        );
+        assert_eq!(packages[2].version.as_deref(), Some("1.10.0"));
+        assert_eq!(packages[3].version, None);
+    }
+
     #[test]
     fn test_apply_version_bump_minor() {
         let dir = tempfile::tempdir().expect("create temp dir");
@@ -1739,6 +2841,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1761,6 +2864,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1783,6 +2887,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1808,6 +2913,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1830,6 +2936,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -1863,11 +2970,12 @@ mod tests {
             dependencies: vec!["core_lib".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
         let updated = update_dependency_constraint(&pkg, "core_lib", "2.0.0").unwrap();
-        assert!(updated);
+        assert_eq!(updated.as_deref(), Some("^2.0.0"));
 
         let content = std::fs::read_to_string(&pubspec).expect("read pubspec");
         assert!(
@@ -1897,11 +3005,209 @@ mod tests {
             dependencies: vec!["core_lib".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
         let updated = update_dependency_constraint(&pkg, "core_lib", "2.0.0").unwrap();
-        assert!(!updated);
+        assert!(updated.is_none());
+    }
+
+    fn dependent_fixture(dir: &Path, name: &str, pubspec: &str, deps: &[&str]) -> Package {
+        let path = dir.join(name);
+        std::fs::create_dir_all(&path).expect("create package dir");
+        std::fs::write(path.join("pubspec.yaml"), pubspec).expect("write pubspec");
+        Package {
+            name: name.to_string(),
+            path,
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    #[test]
+    fn test_update_dependency_constraint_keeps_allowing_constraint() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let content = "name: my_app\nversion: 1.0.0\ndependencies:\n  core_lib: ^1.0.0\n";
+        let pkg = dependent_fixture(dir.path(), "my_app", content, &["core_lib"]);
+
+        let updated = update_dependency_constraint(&pkg, "core_lib", "1.1.0").unwrap();
+        assert!(updated.is_none());
+        let after = std::fs::read_to_string(pkg.path.join("pubspec.yaml")).unwrap();
+        assert_eq!(after, content);
+    }
+
+    #[test]
+    fn test_update_dependency_constraint_range() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let pkg = dependent_fixture(
+            dir.path(),
+            "my_app",
+            "name: my_app\ndependencies:\n  core_lib: '>=1.0.0 <2.0.0' # pinned\n  other: ^3.0.0\n",
+            &["core_lib"],
+        );
+
+        let updated = update_dependency_constraint(&pkg, "core_lib", "2.0.0+7").unwrap();
+        assert_eq!(updated.as_deref(), Some(">=2.0.0 <3.0.0"));
+        let after = std::fs::read_to_string(pkg.path.join("pubspec.yaml")).unwrap();
+        assert!(after.contains("  core_lib: '>=2.0.0 <3.0.0' # pinned\n"));
+        assert!(after.contains("  other: ^3.0.0\n"));
+    }
+
+    #[test]
+    fn test_major_bump_updates_and_flags_dependents() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let core = dependent_fixture(dir.path(), "core", "name: core\nversion: 1.0.0\n", &[]);
+        let app = dependent_fixture(
+            dir.path(),
+            "app",
+            "name: app\nversion: 1.0.0\ndependencies:\n  core: ^1.2.0\n",
+            &["core"],
+        );
+        let unrelated = dependent_fixture(
+            dir.path(),
+            "unrelated",
+            "name: unrelated\nversion: 1.0.0\n",
+            &[],
+        );
+
+        let versioned = vec![("core".to_string(), "2.0.0".to_string())];
+        let updates =
+            update_dependents_constraints(&[core, app.clone(), unrelated], &versioned).unwrap();
+
+        assert_eq!(
+            updates,
+            vec![DependentConstraintUpdate {
+                package: "app".to_string(),
+                constraints: vec![("core".to_string(), "^2.0.0".to_string())],
+            }]
+        );
+        let after = std::fs::read_to_string(app.path.join("pubspec.yaml")).unwrap();
+        assert!(after.contains("  core: ^2.0.0\n"));
+    }
+
+    #[test]
+    fn test_minor_bump_flags_no_dependents() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let app = dependent_fixture(
+            dir.path(),
+            "app",
+            "name: app\nversion: 1.0.0\ndependencies:\n  core: ^1.2.0\n",
+            &["core"],
+        );
+
+        let versioned = vec![("core".to_string(), "1.3.0".to_string())];
+        assert!(
+            update_dependents_constraints(&[app], &versioned)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_coordinated_bump_updates_bumped_siblings() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let core = dependent_fixture(dir.path(), "core", "name: core\nversion: 1.0.0\n", &[]);
+        let app = dependent_fixture(
+            dir.path(),
+            "app",
+            "name: app\nversion: 1.0.0\ndependencies:\n  core: ^1.0.0\n",
+            &["core"],
+        );
+
+        let versioned = vec![
+            ("app".to_string(), "2.0.0".to_string()),
+            ("core".to_string(), "2.0.0".to_string()),
+        ];
+        let updates = update_dependents_constraints(&[app.clone(), core], &versioned).unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].package, "app");
+        let after = std::fs::read_to_string(app.path.join("pubspec.yaml")).unwrap();
+        assert!(after.contains("  core: ^2.0.0\n"));
+    }
+
+    #[test]
+    fn test_any_and_git_dependencies_untouched() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let content = "name: app\nversion: 1.0.0\ndependencies:\n  core: any\n  utils:\n    git:\n      url: https://example.com/utils.git\n";
+        let app = dependent_fixture(dir.path(), "app", content, &["core", "utils"]);
+
+        let versioned = vec![
+            ("core".to_string(), "2.0.0".to_string()),
+            ("utils".to_string(), "3.0.0".to_string()),
+        ];
+        assert!(
+            update_dependents_constraints(std::slice::from_ref(&app), &versioned)
+                .unwrap()
+                .is_empty()
+        );
+        let after = std::fs::read_to_string(app.path.join("pubspec.yaml")).unwrap();
+        assert_eq!(after, content);
+    }
+
+    // -----------------------------------------------------------------------
+    // constraint_allows / rewrite_constraint
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_constraint_allows() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(constraint_allows("^1.2.0", &v("1.9.0")), Some(true));
+        assert_eq!(constraint_allows("^1.2.0", &v("2.0.0")), Some(false));
+        assert_eq!(constraint_allows("^0.2.0", &v("0.3.0")), Some(false));
+        assert_eq!(constraint_allows("^0.0.3", &v("0.0.3")), Some(true));
+        assert_eq!(constraint_allows("^0.0.3", &v("0.0.4")), Some(false));
+        assert_eq!(
+            constraint_allows("'>=1.0.0 <2.0.0'", &v("1.5.0")),
+            Some(true)
+        );
+        assert_eq!(
+            constraint_allows(">=1.0.0 <2.0.0", &v("2.0.0")),
+            Some(false)
+        );
+        assert_eq!(constraint_allows("1.0.0", &v("1.0.1")), Some(false));
+        assert_eq!(constraint_allows("any", &v("9.0.0")), Some(true));
+        assert_eq!(constraint_allows("^banana", &v("1.0.0")), None);
+    }
+
+    #[test]
+    fn test_rewrite_constraint_keeps_shape() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(rewrite_constraint("^1.0.0", &v("2.0.0")), "^2.0.0");
+        assert_eq!(rewrite_constraint("\"^1.0.0\"", &v("2.0.0")), "\"^2.0.0\"");
+        assert_eq!(
+            rewrite_constraint(">=1.0.0 <2.0.0", &v("2.1.0")),
+            "'>=2.1.0 <3.0.0'"
+        );
+        assert_eq!(
+            rewrite_constraint("\">=0.1.0 <0.2.0\"", &v("0.2.0")),
+            "\">=0.2.0 <0.3.0\""
+        );
+        assert_eq!(
+            rewrite_constraint(">=0.0.2 <0.0.3", &v("0.0.3")),
+            "'>=0.0.3 <0.0.4'"
+        );
+        assert_eq!(rewrite_constraint("1.0.0", &v("2.0.0")), "2.0.0");
+    }
+
+    #[test]
+    fn test_generate_dependency_changelog_entry() {
+        let entry = generate_dependency_changelog_entry(
+            "1.0.1",
+            &[("core".to_string(), "^2.0.0".to_string())],
+            false,
+        );
+        assert_eq!(
+            entry,
+            "## 1.0.1\n\n### Dependencies\n\n- Bump `core` to `^2.0.0`\n"
+        );
     }
 
     // -----------------------------------------------------------------------
@@ -1933,6 +3239,7 @@ dependencies:
             dependencies: vec!["core_lib".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             publish_to: None,
             resolution: None,
         }];
@@ -1972,6 +3279,7 @@ dependencies:
             dependencies: vec!["core_lib".to_string()],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             publish_to: None,
             resolution: None,
         }];
@@ -2009,6 +3317,71 @@ dependencies:
         assert!(content.contains("## 0.1.0"));
     }
 
+    #[test]
+    fn test_replace_changelog_entry_rewrites_current_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let changelog_path = dir.path().join("CHANGELOG.md");
+        std::fs::write(
+            &changelog_path,
+            "# Changelog\n\n## 1.1.0 (2024-01-01)\n\n- stale\n\n## 1.0.0\n\n- old stuff\n",
+        )
+        .unwrap();
+
+        let entry = "## 1.1.0\n\n### Features\n\n- fresh\n";
+        replace_changelog_entry(dir.path(), "1.1.0", entry).unwrap();
+        replace_changelog_entry(dir.path(), "1.1.0", entry).unwrap();
+
+        let content = std::fs::read_to_string(&changelog_path).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## 1.1.0\n\n### Features\n\n- fresh\n\n## 1.0.0\n\n- old stuff\n"
+        );
+    }
+
+    #[test]
+    fn test_write_changelog_supersedes_unreleased_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let changelog_path = dir.path().join("CHANGELOG.md");
+        std::fs::write(&changelog_path, "# Changelog\n\n## 1.0.0\n\n- old stuff\n").unwrap();
+
+        replace_changelog_entry(dir.path(), UNRELEASED_VERSION, "## Unreleased\n\n- wip\n")
+            .unwrap();
+        replace_changelog_entry(dir.path(), UNRELEASED_VERSION, "## Unreleased\n\n- more\n")
+            .unwrap();
+        let content = std::fs::read_to_string(&changelog_path).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## Unreleased\n\n- more\n\n## 1.0.0\n\n- old stuff\n"
+        );
+
+        // The release reads as if the unreleased section never existed
+        let released = tempfile::tempdir().unwrap();
+        std::fs::write(
+            released.path().join("CHANGELOG.md"),
+            "# Changelog\n\n## 1.0.0\n\n- old stuff\n",
+        )
+        .unwrap();
+        write_changelog(released.path(), "## 1.1.0\n\n- more\n").unwrap();
+        write_changelog(dir.path(), "## 1.1.0\n\n- more\n").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&changelog_path).unwrap(),
+            std::fs::read_to_string(released.path().join("CHANGELOG.md")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replace_changelog_entry_prepends_other_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let changelog_path = dir.path().join("CHANGELOG.md");
+        std::fs::write(&changelog_path, "# Changelog\n\n## 1.0.0\n\n- old stuff\n").unwrap();
+
+        replace_changelog_entry(dir.path(), "1.0.1", "## 1.0.1\n\n- new\n").unwrap();
+
+        let content = std::fs::read_to_string(&changelog_path).unwrap();
+        assert!(content.starts_with("# Changelog\n\n## 1.0.1\n\n- new\n"));
+        assert!(content.contains("## 1.0.0\n\n- old stuff"));
+    }
+
     // -----------------------------------------------------------------------
     // Config parsing (tests that parse YAML and check version config)
     // -----------------------------------------------------------------------
@@ -2203,6 +3576,7 @@ command:
     fn test_repository_release_url() {
         let repo = RepositoryConfig {
             url: "https://github.com/invertase/melos".to_string(),
+            kind: RepoKind::GitHub,
         };
         let url = repo.release_url("my_pkg-v1.2.0", "my_pkg v1.2.0");
         assert!(url.starts_with("https://github.com/invertase/melos/releases/new?"));
@@ -2214,6 +3588,7 @@ command:
     fn test_repository_release_url_special_chars() {
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo".to_string(),
+            kind: RepoKind::GitHub,
         };
         let url = repo.release_url("pkg-v2.0.0-beta.1", "pkg v2.0.0-beta.1");
         assert!(url.contains("tag=pkg-v2.0.0-beta.1"));
@@ -2333,6 +3708,100 @@ command:
         assert!(!bodies.only_breaking);
     }
 
+    fn body_commit(breaking: bool) -> ConventionalCommit {
+        ConventionalCommit {
+            commit_type: "feat".to_string(),
+            scope: None,
+            breaking,
+            description: "change".to_string(),
+            body: Some("Commit body text.".to_string()),
+            hash: "abc".to_string(),
+        }
+    }
+
+    fn body_included(include: bool, only_breaking: bool, breaking: bool) -> bool {
+        let entry = generate_changelog_entry(
+            "1.0.0",
+            &[body_commit(breaking)],
+            &ChangelogOptions {
+                include_body: include,
+                only_breaking_bodies: only_breaking,
+                ..ChangelogOptions::default()
+            },
+        );
+        entry.contains("Commit body text.")
+    }
+
+    #[test]
+    fn test_commit_body_rule_only_breaking_with_breaking_commit() {
+        assert!(body_included(true, true, true));
+    }
+
+    #[test]
+    fn test_commit_body_rule_only_breaking_with_non_breaking_commit() {
+        assert!(!body_included(true, true, false));
+    }
+
+    #[test]
+    fn test_commit_body_rule_all_bodies_with_non_breaking_commit() {
+        assert!(body_included(true, false, false));
+    }
+
+    #[test]
+    fn test_commit_body_rule_include_disabled() {
+        assert!(!body_included(false, false, true));
+        assert!(!body_included(false, true, true));
+    }
+
+    #[test]
+    fn test_commit_body_rules_legacy_include_commit_body() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  version:
+    changelogConfig:
+      includeCommitBody: true
+"#;
+        let config: crate::config::MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let version_config = config.command.unwrap().version.unwrap();
+        assert_eq!(version_config.commit_body_rules(), (true, false));
+    }
+
+    #[test]
+    fn test_commit_body_rules_new_config_takes_precedence() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  version:
+    changelogConfig:
+      includeCommitBody: true
+    changelogCommitBodies:
+      include: true
+"#;
+        let config: crate::config::MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let version_config = config.command.unwrap().version.unwrap();
+        assert_eq!(version_config.commit_body_rules(), (true, true));
+    }
+
+    #[test]
+    fn test_commit_body_rules_default_excludes_bodies() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  version:
+    branch: main
+"#;
+        let config: crate::config::MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let version_config = config.command.unwrap().version.unwrap();
+        assert_eq!(version_config.commit_body_rules(), (false, false));
+    }
+
     // -----------------------------------------------------------------------
     // changelogFormat.includeDate config
     // -----------------------------------------------------------------------
@@ -2456,6 +3925,66 @@ command:
         assert_eq!(changelogs[0].path, "CHANGELOG_MOBILE.md");
     }
 
+    // -----------------------------------------------------------------------
+    // map_commits_to_packages
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_map_commits_to_packages_scope_attribution() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@test.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@test.com")
+                .output()
+                .unwrap()
+        };
+        git(&["init"]);
+        let packages = vec![
+            dependent_fixture(&root.join("packages"), "core", "name: core\n", &[]),
+            dependent_fixture(&root.join("packages"), "app", "name: app\n", &[]),
+        ];
+        git(&["add", "."]);
+        git(&["commit", "-m", "chore: init"]);
+
+        let mut commits = Vec::new();
+        for (message, path) in [
+            ("feat(core): share models", "packages/app/lib.dart"),
+            ("fix(ui): tweak", "packages/app/lib.dart"),
+            ("fix(core): guard input", "packages/core/lib.dart"),
+        ] {
+            std::fs::write(root.join(path), message).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-m", message]);
+            let hash = String::from_utf8(git(&["rev-parse", "HEAD"]).stdout).unwrap();
+            commits.push(parse_conventional_commit(hash.trim(), message).unwrap());
+        }
+        let descriptions = |mapped: &HashMap<String, Vec<ConventionalCommit>>, pkg: &str| {
+            mapped
+                .get(pkg)
+                .map(|c| c.iter().map(|c| c.description.clone()).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        // A scoped commit is listed once for its package, and still for the
+        // packages it touched; unknown scopes use the touched paths only
+        let mapped = map_commits_to_packages(root, &commits, &packages, true).unwrap();
+        assert_eq!(
+            descriptions(&mapped, "core"),
+            vec!["share models", "guard input"]
+        );
+        assert_eq!(descriptions(&mapped, "app"), vec!["share models", "tweak"]);
+
+        let mapped = map_commits_to_packages(root, &commits, &packages, false).unwrap();
+        assert_eq!(descriptions(&mapped, "core"), vec!["guard input"]);
+        assert_eq!(descriptions(&mapped, "app"), vec!["share models", "tweak"]);
+    }
+
     // -----------------------------------------------------------------------
     // find_latest_git_tag
     // -----------------------------------------------------------------------
@@ -2520,6 +4049,46 @@ command:
         assert_eq!(tag.as_deref(), Some("v1.0.0"));
     }
 
+    #[test]
+    fn test_find_existing_tags_and_overwrite() {
+        let tmp = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(tmp.path())
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@test.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@test.com")
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        // Annotated tags need an identity from the repository config
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@test.com"]);
+        std::fs::write(tmp.path().join("file.txt"), "hello").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        git(&["tag", "core-v1.1.0"]);
+
+        let existing = list_git_tags(tmp.path()).unwrap();
+        let planned = vec![
+            ("app".to_string(), "2.0.0".to_string()),
+            ("core".to_string(), "1.1.0".to_string()),
+        ];
+        assert_eq!(
+            find_existing_tags(&existing, &planned),
+            vec![("core".to_string(), "core-v1.1.0".to_string())]
+        );
+
+        // Tagging over an existing tag fails unless overwriting
+        assert!(create_git_tag(tmp.path(), "core", "1.1.0", false).is_err());
+        let tag = create_git_tag(tmp.path(), "core", "1.1.0", true).unwrap();
+        assert_eq!(tag, "core-v1.1.0");
+    }
+
     // -----------------------------------------------------------------------
     // Release branch management
     // -----------------------------------------------------------------------
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Package-level filters that can come from melos.yaml `packageFilters` or CLI flags.
 ///
@@ -18,55 +18,97 @@ use serde::Deserialize;
 /// ```sh
 /// melos-rs exec --scope="app*" --no-private -- flutter test
 /// ```
-#[derive(Debug, Deserialize, Clone, Default)]
+///
+/// Canonical (camelCase) field names, shared by the YAML config form and the
+/// serialized form: `flutter`, `dirExists`, `fileExists`, `dependsOn`,
+/// `noDependsOn`, `ignore`, `scope`, `noPrivate`, `diff`, `sinceLatestTag`,
+/// `category`, `includeDependencies`, `excludeDependencies`, `includeDependents`,
+/// `published`.
+///
+/// Unset fields are omitted when serializing, so a serialized filter set
+/// reads like a hand-written `packageFilters` block and round-trips unchanged.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct PackageFilters {
     /// Filter to only Flutter packages (true) or only Dart packages (false)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flutter: Option<bool>,
 
-    /// Only include packages where this directory exists
-    #[serde(default)]
-    pub dir_exists: Option<String>,
+    /// Only include packages where all of these directories exist.
+    ///
+    /// Accepts a single path or a list in YAML.
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub dir_exists: Option<Vec<String>>,
 
-    /// Only include packages where this file exists
-    #[serde(default)]
-    pub file_exists: Option<String>,
+    /// Only include packages where all of these files exist.
+    ///
+    /// Accepts a single path or a list in YAML.
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub file_exists: Option<Vec<String>>,
 
     /// Only include packages that depend on these packages
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub depends_on: Option<Vec<String>>,
 
     /// Exclude packages that depend on these packages
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub no_depends_on: Option<Vec<String>>,
 
     /// Exclude packages matching these glob patterns
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ignore: Option<Vec<String>>,
 
     /// Only include packages matching these glob/name patterns
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scope: Option<Vec<String>>,
 
     /// Exclude private packages (publish_to: none)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub no_private: bool,
 
     /// Only include packages changed since this git ref
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub diff: Option<String>,
 
+    /// Only include packages changed since the most recent git tag.
+    ///
+    /// When no tag exists, `diff` is used as the fallback baseline.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub since_latest_tag: bool,
+
     /// Only include packages in these categories (from melos.yaml categories config)
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<Vec<String>>,
 
-    /// Also include transitive dependencies of matched packages
-    #[serde(default)]
+    /// Also include transitive dependencies of matched packages.
+    ///
+    /// Follows `dependencies` and `dev_dependencies` to workspace packages
+    /// until closure; each package is included once even in diamond graphs.
+    #[serde(default, skip_serializing_if = "is_false")]
     pub include_dependencies: bool,
 
-    /// Also include transitive dependents of matched packages
-    #[serde(default)]
+    /// With `include_dependencies`, add only the direct dependencies of
+    /// matched packages, leaving out the dependencies those pull in.
+    ///
+    /// Matched packages stay selected even when they are also a dependency of
+    /// another matched package, and dependents added by `include_dependents`
+    /// are unaffected. Without `include_dependencies` this has no effect.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub exclude_dependencies: bool,
+
+    /// Also include transitive dependents of matched packages.
+    ///
+    /// With `include_dependencies` as well, both closures are taken from the
+    /// directly matched packages, not from each other's results.
+    #[serde(default, skip_serializing_if = "is_false")]
     pub include_dependents: bool,
 
     /// Filter by published status.
@@ -74,7 +116,7 @@ pub struct PackageFilters {
     /// - `Some(true)`: only include publishable packages (publish_to is NOT "none")
     /// - `Some(false)`: only include non-published/private packages (publish_to IS "none")
     /// - `None`: no filter
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub published: Option<bool>,
 }
 
@@ -90,8 +132,10 @@ impl PackageFilters {
             && self.scope.is_none()
             && !self.no_private
             && self.diff.is_none()
+            && !self.since_latest_tag
             && self.category.is_none()
             && !self.include_dependencies
+            && !self.exclude_dependencies
             && !self.include_dependents
             && self.published.is_none()
     }
@@ -103,25 +147,49 @@ impl PackageFilters {
     pub fn merge(&self, other: &PackageFilters) -> PackageFilters {
         PackageFilters {
             flutter: other.flutter.or(self.flutter),
-            dir_exists: other.dir_exists.clone().or_else(|| self.dir_exists.clone()),
-            file_exists: other
-                .file_exists
-                .clone()
-                .or_else(|| self.file_exists.clone()),
+            dir_exists: merge_opt_vec(&self.dir_exists, &other.dir_exists),
+            file_exists: merge_opt_vec(&self.file_exists, &other.file_exists),
             depends_on: merge_opt_vec(&self.depends_on, &other.depends_on),
             no_depends_on: merge_opt_vec(&self.no_depends_on, &other.no_depends_on),
             ignore: merge_opt_vec(&self.ignore, &other.ignore),
             scope: merge_opt_vec(&self.scope, &other.scope),
             no_private: self.no_private || other.no_private,
             diff: other.diff.clone().or_else(|| self.diff.clone()),
+            since_latest_tag: self.since_latest_tag || other.since_latest_tag,
             category: merge_opt_vec(&self.category, &other.category),
             include_dependencies: self.include_dependencies || other.include_dependencies,
+            exclude_dependencies: self.exclude_dependencies || other.exclude_dependencies,
             include_dependents: self.include_dependents || other.include_dependents,
             published: other.published.or(self.published),
         }
     }
 }
 
+/// `skip_serializing_if` helper for boolean flags that default to `false`.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Deserialize a field given either as a single string or a list of strings.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(
+        Option::<OneOrMany>::deserialize(deserializer)?.map(|value| match value {
+            OneOrMany::One(item) => vec![item],
+            OneOrMany::Many(items) => items,
+        }),
+    )
+}
+
 /// Merge two optional vecs: if both present, concatenate; otherwise take whichever is Some.
 fn merge_opt_vec(a: &Option<Vec<String>>, b: &Option<Vec<String>>) -> Option<Vec<String>> {
     match (a, b) {
@@ -155,7 +223,7 @@ mod tests {
     fn test_merge_other_takes_precedence() {
         let a = PackageFilters {
             flutter: Some(true),
-            dir_exists: Some("test".to_string()),
+            dir_exists: Some(vec!["test".to_string()]),
             ..Default::default()
         };
         let b = PackageFilters {
@@ -166,7 +234,7 @@ mod tests {
         // `other` (b) takes precedence for flutter
         assert_eq!(merged.flutter, Some(false));
         // `self` (a) dir_exists survives since b has None
-        assert_eq!(merged.dir_exists, Some("test".to_string()));
+        assert_eq!(merged.dir_exists, Some(vec!["test".to_string()]));
     }
 
     #[test]
@@ -279,4 +347,139 @@ mod tests {
         };
         assert!(!f.is_empty());
     }
+
+    // -----------------------------------------------------------------------
+    // Serde round-trip
+    // -----------------------------------------------------------------------
+
+    fn full_filters() -> PackageFilters {
+        PackageFilters {
+            flutter: Some(true),
+            dir_exists: Some(vec!["test".to_string()]),
+            file_exists: Some(vec!["pubspec.lock".to_string()]),
+            depends_on: Some(vec!["core".to_string()]),
+            no_depends_on: Some(vec!["legacy".to_string()]),
+            ignore: Some(vec!["*_example".to_string()]),
+            scope: Some(vec!["app*".to_string()]),
+            no_private: true,
+            diff: Some("origin/main".to_string()),
+            since_latest_tag: true,
+            category: Some(vec!["apps".to_string()]),
+            include_dependencies: true,
+            exclude_dependencies: true,
+            include_dependents: true,
+            published: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_serde_round_trip_all_fields() {
+        let filters = full_filters();
+        let yaml = yaml_serde::to_string(&filters).unwrap();
+        let parsed: PackageFilters = yaml_serde::from_str(&yaml).unwrap();
+        assert_eq!(parsed, filters);
+    }
+
+    #[test]
+    fn test_serde_round_trip_default() {
+        let filters = PackageFilters::default();
+        let json = serde_json::to_string(&filters).unwrap();
+        assert_eq!(json, "{}");
+        let parsed: PackageFilters = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, filters);
+    }
+
+    #[test]
+    fn test_serialize_uses_camel_case_field_names() {
+        let value = serde_json::to_value(full_filters()).unwrap();
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "category",
+                "dependsOn",
+                "diff",
+                "dirExists",
+                "excludeDependencies",
+                "fileExists",
+                "flutter",
+                "ignore",
+                "includeDependencies",
+                "includeDependents",
+                "noDependsOn",
+                "noPrivate",
+                "published",
+                "scope",
+                "sinceLatestTag",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_realistic_yaml_filter_block() {
+        let yaml = r#"
+scope:
+  - "app*"
+  - core
+ignore:
+  - "*_example"
+flutter: true
+dirExists: test
+dependsOn:
+  - core
+noPrivate: true
+category:
+  - apps
+includeDependents: true
+"#;
+        let filters: PackageFilters = yaml_serde::from_str(yaml).unwrap();
+        assert_eq!(
+            filters,
+            PackageFilters {
+                scope: Some(vec!["app*".to_string(), "core".to_string()]),
+                ignore: Some(vec!["*_example".to_string()]),
+                flutter: Some(true),
+                dir_exists: Some(vec!["test".to_string()]),
+                depends_on: Some(vec!["core".to_string()]),
+                no_private: true,
+                category: Some(vec!["apps".to_string()]),
+                include_dependents: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_exists_filters_accept_single_or_list() {
+        let filters: PackageFilters =
+            yaml_serde::from_str("dirExists: ios\nfileExists:\n  - pubspec.lock\n  - Podfile\n")
+                .unwrap();
+        assert_eq!(filters.dir_exists, Some(vec!["ios".to_string()]));
+        assert_eq!(
+            filters.file_exists,
+            Some(vec!["pubspec.lock".to_string(), "Podfile".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_merge_exists_filters_accumulate() {
+        let a = PackageFilters {
+            dir_exists: Some(vec!["ios".to_string()]),
+            ..Default::default()
+        };
+        let b = PackageFilters {
+            dir_exists: Some(vec!["test".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            a.merge(&b).dir_exists,
+            Some(vec!["ios".to_string(), "test".to_string()])
+        );
+    }
 }
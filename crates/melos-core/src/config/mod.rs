@@ -1,4 +1,5 @@
 pub mod filter;
+pub mod overrides;
 pub mod script;
 
 use std::collections::HashMap;
@@ -8,7 +9,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use serde::Deserialize;
 
-use self::script::{ExecEntry, ScriptConfig};
+use self::script::{ExecEntry, MatrixEntry, ScriptConfig, StepEntry};
 
 /// How the workspace configuration was found
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,7 +42,7 @@ pub struct MelosConfig {
     /// Workspace name
     pub name: String,
 
-    /// Package glob patterns
+    /// Package glob patterns; entries starting with `!` exclude matches
     pub packages: Vec<String>,
 
     /// Repository URL or object for changelog commit links
@@ -87,6 +88,18 @@ pub struct MelosConfig {
     /// listed workspace paths are also scanned for packages.
     #[serde(default)]
     pub discover_nested_workspaces: Option<bool>,
+
+    /// When true (default), skip candidate package directories ignored by the
+    /// workspace `.gitignore` or by `.gitignore` files nested below it.
+    #[serde(default)]
+    pub respect_gitignore: Option<bool>,
+
+    /// Environment variables set for every command and script.
+    ///
+    /// Values may reference `${MELOS_ROOT_PATH}` and other variables of the
+    /// calling environment. Script-level `env` takes precedence.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
 }
 
 impl MelosConfig {
@@ -113,6 +126,58 @@ impl MelosConfig {
             let has_steps = entry.steps().is_some();
             let has_exec_config = entry.has_exec_config();
 
+            if let Some(dir) = entry.working_directory()
+                && let Err(e) = script::normalize_working_directory(dir)
+            {
+                warnings.push(format!("Script '{}': {}.", name, e));
+            }
+
+            // Only per-package commands run inside each package
+            if entry.working_directory().is_some()
+                && entry.matrix().is_none()
+                && !has_exec_config
+                && !entry.run_command().is_some_and(is_exec_style)
+            {
+                warnings.push(format!(
+                    "Script '{}': `workingDirectory` only applies to per-package commands \
+                     (`exec` or `melos exec`) and is ignored.",
+                    name
+                ));
+            }
+
+            if entry
+                .concurrency_group()
+                .is_some_and(|g| g.trim().is_empty())
+            {
+                warnings.push(format!(
+                    "Script '{}': `concurrencyGroup` is empty and has no effect.",
+                    name
+                ));
+            }
+
+            if entry.when().is_some_and(|w| w.trim().is_empty()) {
+                warnings.push(format!(
+                    "Script '{}': `when` is empty and has no effect.",
+                    name
+                ));
+            }
+
+            for os in entry.os().unwrap_or_default() {
+                if !script::KNOWN_SCRIPT_OS.contains(&os.as_str()) {
+                    warnings.push(format!(
+                        "Script '{}': unknown os '{}' (expected one of: {}).",
+                        name,
+                        os,
+                        script::KNOWN_SCRIPT_OS.join(", ")
+                    ));
+                }
+            }
+
+            if let Some(matrix) = entry.matrix() {
+                warnings.extend(self.validate_matrix(name, matrix));
+                continue;
+            }
+
             if let Some(cmd) = entry.run_command() {
                 // Warn about exec-style scripts missing `--` separator
                 if is_exec_style(cmd) && !cmd.contains(" -- ") {
@@ -147,11 +212,7 @@ impl MelosConfig {
                              Available categories: {}",
                             name,
                             cat,
-                            if self.categories.is_empty() {
-                                "(none)".to_string()
-                            } else {
-                                self.categories.keys().cloned().collect::<Vec<_>>().join(", ")
-                            }
+                            available_categories(&self.categories)
                         ));
                     }
                 }
@@ -160,6 +221,58 @@ impl MelosConfig {
 
         warnings
     }
+
+    /// Whether package discovery skips `.gitignore`d directories (default: true)
+    pub fn should_respect_gitignore(&self) -> bool {
+        self.respect_gitignore.unwrap_or(true)
+    }
+
+    /// Validate the entries of a `matrix` script.
+    fn validate_matrix(&self, name: &str, matrix: &[MatrixEntry]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if matrix.is_empty() {
+            warnings.push(format!("Script '{}' has an empty `matrix`.", name));
+        }
+        for (i, entry) in matrix.iter().enumerate() {
+            if entry.run.trim().is_empty() {
+                warnings.push(format!(
+                    "Script '{}' matrix entry {} has an empty `run` command.",
+                    name,
+                    i + 1
+                ));
+            }
+            match entry.package_filters {
+                None => warnings.push(format!(
+                    "Script '{}' matrix entry {} has no `packageFilters` and will run in every package.",
+                    name,
+                    i + 1
+                )),
+                Some(ref filters) => {
+                    for cat in filters.category.iter().flatten() {
+                        if !self.categories.contains_key(cat) {
+                            warnings.push(format!(
+                                "Script '{}' matrix entry {} references category '{}' which is not defined in `categories`.",
+                                name,
+                                i + 1,
+                                cat
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        warnings
+    }
+}
+
+/// Comma-separated, sorted category names for error messages, or `(none)`.
+pub fn available_categories(categories: &HashMap<String, Vec<String>>) -> String {
+    if categories.is_empty() {
+        return "(none)".to_string();
+    }
+    let mut names: Vec<&str> = categories.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names.join(", ")
 }
 
 /// Check if a command string looks like an exec-style command
@@ -252,13 +365,21 @@ impl ScriptEntry {
     }
 
     /// Get steps if this is a multi-step script.
-    pub fn steps(&self) -> Option<&[String]> {
+    pub fn steps(&self) -> Option<&[StepEntry]> {
         match self {
             ScriptEntry::Simple(_) => None,
             ScriptEntry::Full(config) => config.steps.as_deref(),
         }
     }
 
+    /// Get matrix entries if this is a package-scoped matrix script.
+    pub fn matrix(&self) -> Option<&[MatrixEntry]> {
+        match self {
+            ScriptEntry::Simple(_) => None,
+            ScriptEntry::Full(config) => config.matrix.as_deref(),
+        }
+    }
+
     /// Whether this script is private (hidden from interactive selection and `run --list`).
     pub fn is_private(&self) -> bool {
         match self {
@@ -293,6 +414,48 @@ impl ScriptEntry {
         }
     }
 
+    /// Get the per-package working directory, relative to each package root
+    pub fn working_directory(&self) -> Option<&str> {
+        match self {
+            ScriptEntry::Simple(_) => None,
+            ScriptEntry::Full(config) => config.working_directory.as_deref(),
+        }
+    }
+
+    /// Get the concurrency group serializing this script's package commands
+    pub fn concurrency_group(&self) -> Option<&str> {
+        match self {
+            ScriptEntry::Simple(_) => None,
+            ScriptEntry::Full(config) => config.concurrency_group.as_deref(),
+        }
+    }
+
+    /// Get the platforms this script is restricted to, if any
+    pub fn os(&self) -> Option<&[String]> {
+        match self {
+            ScriptEntry::Simple(_) => None,
+            ScriptEntry::Full(config) => config.os.as_deref(),
+        }
+    }
+
+    /// Get the shell predicate gating this script, if any
+    pub fn when(&self) -> Option<&str> {
+        match self {
+            ScriptEntry::Simple(_) => None,
+            ScriptEntry::Full(config) => config.when.as_deref(),
+        }
+    }
+
+    /// Check whether this script runs on the given platform
+    /// (a `std::env::consts::OS` value). An absent or empty `os` list
+    /// matches every platform.
+    pub fn supports_os(&self, current: &str) -> bool {
+        match self.os() {
+            None | Some([]) => true,
+            Some(list) => list.iter().any(|os| os == current),
+        }
+    }
+
     /// Get the groups this script belongs to.
     ///
     /// Returns `None` for simple scripts or full scripts without groups.
@@ -334,37 +497,105 @@ pub fn url_encode(s: &str) -> String {
     encoded
 }
 
+/// Hosting provider of the repository, which decides the link formats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepoKind {
+    /// GitHub, also used for URL strings and unknown `type` values
+    #[default]
+    GitHub,
+    GitLab,
+    Bitbucket,
+    /// Azure DevOps (`type: azure` or `azure-devops`)
+    AzureDevOps,
+}
+
+impl RepoKind {
+    /// Map a `repository.type` value to its kind, defaulting to GitHub.
+    pub fn from_type(repo_type: Option<&str>) -> Self {
+        match repo_type {
+            Some("gitlab") => RepoKind::GitLab,
+            Some("bitbucket") => RepoKind::Bitbucket,
+            Some("azure") | Some("azure-devops") => RepoKind::AzureDevOps,
+            _ => RepoKind::GitHub,
+        }
+    }
+
+    /// Default host when the object form has no `origin`.
+    fn default_origin(self) -> &'static str {
+        match self {
+            RepoKind::GitHub => "https://github.com",
+            RepoKind::GitLab => "https://gitlab.com",
+            RepoKind::Bitbucket => "https://bitbucket.org",
+            RepoKind::AzureDevOps => "https://dev.azure.com",
+        }
+    }
+}
+
 /// Repository URL or structured config for changelog commit links.
 ///
 /// Supports two forms:
 ///   - Simple URL string: `repository: https://github.com/org/repo`
 ///   - Object form: `repository: { type: github, origin: ..., owner: ..., name: ... }`
+///
+/// The URL string form uses GitHub-style links.
 #[derive(Debug, Clone)]
 pub struct RepositoryConfig {
     /// The full URL to the repository (e.g., https://github.com/invertase/melos)
     pub url: String,
+    /// Hosting provider, from the object form's `type`
+    pub kind: RepoKind,
 }
 
 impl RepositoryConfig {
     /// Get the commit URL for a given commit hash.
-    /// Returns a URL like `https://github.com/org/repo/commit/<hash>`.
+    ///
+    /// GitHub/GitLab: `<url>/commit/<hash>`, Bitbucket: `<url>/commits/<hash>`,
+    /// Azure DevOps: `<url>?version=GC<hash>`.
     pub fn commit_url(&self, hash: &str) -> String {
         let base = self.url.trim_end_matches('/');
-        // GitHub/GitLab/Bitbucket all use /commit/<hash>
-        format!("{}/commit/{}", base, hash)
+        match self.kind {
+            RepoKind::GitHub | RepoKind::GitLab => format!("{}/commit/{}", base, hash),
+            RepoKind::Bitbucket => format!("{}/commits/{}", base, hash),
+            RepoKind::AzureDevOps => format!("{}?version=GC{}", base, hash),
+        }
     }
 
     /// Get a prefilled release creation page URL for a given tag and title.
     ///
-    /// GitHub format: `https://github.com/owner/repo/releases/new?tag=<tag>&title=<title>`
+    /// GitHub format: `https://github.com/owner/repo/releases/new?tag=<tag>&title=<title>`.
+    /// Bitbucket and Azure DevOps have no release pages, so these link to the
+    /// tag instead: `<url>/commits/tag/<tag>` and `<url>?version=GT<tag>`.
     pub fn release_url(&self, tag: &str, title: &str) -> String {
         let base = self.url.trim_end_matches('/');
         let encoded_tag = url_encode(tag);
-        let encoded_title = url_encode(title);
-        format!(
-            "{}/releases/new?tag={}&title={}",
-            base, encoded_tag, encoded_title
-        )
+        match self.kind {
+            RepoKind::GitHub | RepoKind::GitLab => format!(
+                "{}/releases/new?tag={}&title={}",
+                base,
+                encoded_tag,
+                url_encode(title)
+            ),
+            RepoKind::Bitbucket => format!("{}/commits/tag/{}", base, encoded_tag),
+            RepoKind::AzureDevOps => format!("{}?version=GT{}", base, encoded_tag),
+        }
+    }
+
+    /// Get the URL of a directory on `branch`, for a package's pubspec
+    /// `repository` field; an empty `path` is the repository itself.
+    ///
+    /// GitHub/GitLab: `<url>/tree/<branch>/<path>`, Bitbucket:
+    /// `<url>/src/<branch>/<path>`, Azure DevOps: `<url>?path=/<path>&version=GB<branch>`.
+    pub fn directory_url(&self, path: &str, branch: &str) -> String {
+        let base = self.url.trim_end_matches('/');
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return base.to_string();
+        }
+        match self.kind {
+            RepoKind::GitHub | RepoKind::GitLab => format!("{}/tree/{}/{}", base, branch, path),
+            RepoKind::Bitbucket => format!("{}/src/{}/{}", base, branch, path),
+            RepoKind::AzureDevOps => format!("{}?path=/{}&version=GB{}", base, path, branch),
+        }
     }
 }
 
@@ -386,7 +617,10 @@ impl<'de> serde::Deserialize<'de> for RepositoryConfig {
             }
 
             fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
-                Ok(RepositoryConfig { url: v.to_string() })
+                Ok(RepositoryConfig {
+                    url: v.to_string(),
+                    kind: RepoKind::GitHub,
+                })
             }
 
             fn visit_map<M: de::MapAccess<'de>>(
@@ -413,22 +647,21 @@ impl<'de> serde::Deserialize<'de> for RepositoryConfig {
                 let owner = owner.ok_or_else(|| de::Error::missing_field("owner"))?;
                 let name = name.ok_or_else(|| de::Error::missing_field("name"))?;
 
+                let kind = RepoKind::from_type(repo_type.as_deref());
                 let base_url = match origin {
                     Some(ref o) => o.trim_end_matches('/').to_string(),
-                    None => {
-                        let host = match repo_type.as_deref() {
-                            Some("gitlab") => "https://gitlab.com",
-                            Some("bitbucket") => "https://bitbucket.org",
-                            Some("azure") => "https://dev.azure.com",
-                            _ => "https://github.com", // default to github
-                        };
-                        host.to_string()
+                    None => kind.default_origin().to_string(),
+                };
+                let url = match kind {
+                    // Azure DevOps repos live under `<org>/<project>/_git/<repo>`;
+                    // the project is assumed to share the repository's name
+                    RepoKind::AzureDevOps => {
+                        format!("{}/{}/{}/_git/{}", base_url, owner, name, name)
                     }
+                    _ => format!("{}/{}/{}", base_url, owner, name),
                 };
 
-                Ok(RepositoryConfig {
-                    url: format!("{}/{}/{}", base_url, owner, name),
-                })
+                Ok(RepositoryConfig { url, kind })
             }
         }
 
@@ -437,7 +670,7 @@ impl<'de> serde::Deserialize<'de> for RepositoryConfig {
 }
 
 /// Configuration for the `command` section
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct CommandConfig {
     /// Version command config
     pub version: Option<VersionCommandConfig>,
@@ -456,6 +689,9 @@ pub struct CommandConfig {
 
     /// Build command config (beyond Melos parity — see docs/rationale/0004)
     pub build: Option<BuildCommandConfig>,
+
+    /// Exec command config (defaults for bare `melos exec`)
+    pub exec: Option<ExecCommandConfig>,
 }
 
 /// Configuration for the `version` command
@@ -465,10 +701,13 @@ pub struct VersionCommandConfig {
     /// Branch to use for versioning (validates current branch matches)
     pub branch: Option<String>,
 
-    /// Commit message template. Supports {new_version} and {package_name} placeholders.
+    /// Commit message template. Supports the placeholders of
+    /// [`crate::commands::version::render_commit_message`].
     pub message: Option<String>,
 
-    /// Whether to include scopes in conventional commit changelogs
+    /// Whether to include scopes in conventional commit changelogs. Also
+    /// attributes commits whose scope names a workspace package to that
+    /// package's changelog.
     #[serde(default)]
     pub include_scopes: Option<bool>,
 
@@ -544,6 +783,17 @@ pub struct VersionCommandConfig {
     #[serde(default)]
     pub update_git_tag_refs: Option<bool>,
 
+    /// Whether to rewrite dependents' hosted constraints on bumped packages
+    /// when the old constraint no longer allows the new version (e.g. `^1.0.0`
+    /// after a major bump). Updated dependents get their own patch bump.
+    #[serde(default)]
+    pub update_dependents_constraints: Option<bool>,
+
+    /// Whether packages whose pubspec has no `version` read it from a
+    /// `version.txt` next to the pubspec, and get it bumped there.
+    #[serde(default)]
+    pub version_file: Option<bool>,
+
     /// Release branch pattern. When set, a release branch is created after versioning.
     ///
     /// The pattern supports `{version}` as a placeholder for the resolved version string.
@@ -602,6 +852,16 @@ impl VersionCommandConfig {
         self.update_git_tag_refs.unwrap_or(false)
     }
 
+    /// Whether to update dependents' constraints on bumped packages (default: true)
+    pub fn should_update_dependents_constraints(&self) -> bool {
+        self.update_dependents_constraints.unwrap_or(true)
+    }
+
+    /// Whether `version.txt` is a version source (default: false)
+    pub fn should_use_version_file(&self) -> bool {
+        self.version_file.unwrap_or(false)
+    }
+
     /// Get the release branch pattern, if configured.
     ///
     /// Returns `None` when no release branch should be created.
@@ -616,6 +876,23 @@ impl VersionCommandConfig {
             .and_then(|f| f.include_date)
             .unwrap_or(false)
     }
+
+    /// Resolve commit body inclusion rules as `(include, only_breaking)`.
+    ///
+    /// `changelogCommitBodies` takes precedence. When it is absent, the legacy
+    /// `changelogConfig.includeCommitBody` is treated as
+    /// `include: <value>, onlyBreaking: false` (it always included every body).
+    pub fn commit_body_rules(&self) -> (bool, bool) {
+        if let Some(ref bodies) = self.changelog_commit_bodies {
+            return (bodies.include, bodies.only_breaking);
+        }
+        let legacy = self
+            .changelog_config
+            .as_ref()
+            .and_then(|cc| cc.include_commit_body)
+            .unwrap_or(false);
+        (legacy, false)
+    }
 }
 
 /// Changelog-specific configuration
@@ -644,6 +921,10 @@ pub struct ChangelogConfig {
 }
 
 /// Hooks for versioning
+///
+/// Run order: version bumps, `prePackage` in each versioned package,
+/// changelogs, `postPackage` in each versioned package, `preCommit`, the
+/// release commit, then `postCommit`.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionHooks {
@@ -652,6 +933,14 @@ pub struct VersionHooks {
 
     /// Script to run after committing version changes
     pub post_commit: Option<String>,
+
+    /// Command run in each versioned package after its version is bumped and
+    /// before changelogs are written (`MELOS_NEW_VERSION` is set)
+    pub pre_package: Option<String>,
+
+    /// Command run in each versioned package after changelogs are written,
+    /// before the release commit (`MELOS_NEW_VERSION` is set)
+    pub post_package: Option<String>,
 }
 
 /// Aggregate changelog configuration.
@@ -719,13 +1008,17 @@ pub struct ChangelogFormatConfig {
 }
 
 /// Configuration for the `bootstrap` command
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BootstrapCommandConfig {
     /// Run `pub get` in parallel
     #[serde(default)]
     pub run_pub_get_in_parallel: Option<bool>,
 
+    /// Default concurrency when `--concurrency` is not passed
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
     /// When true, validate that workspace packages' version constraints on
     /// sibling packages are satisfied by the sibling's actual version before
     /// running `pub get`. This catches constraint mismatches early — important
@@ -749,6 +1042,21 @@ pub struct BootstrapCommandConfig {
     #[serde(default)]
     pub dependency_override_paths: Option<Vec<String>>,
 
+    /// Explicit `dependency_overrides` entries written verbatim into every
+    /// generated `pubspec_overrides.yaml` (e.g. a git fork of a third-party
+    /// package). They win over sibling path overrides of the same name.
+    ///
+    /// Example:
+    /// ```yaml
+    /// dependencyOverrides:
+    ///   http:
+    ///     git:
+    ///       url: https://github.com/me/http.git
+    ///       ref: fix-timeouts
+    /// ```
+    #[serde(default)]
+    pub dependency_overrides: Option<HashMap<String, yaml_serde::Value>>,
+
     /// Shared environment SDK constraints to sync across all packages.
     ///
     /// Example:
@@ -773,6 +1081,12 @@ pub struct BootstrapCommandConfig {
     #[serde(default)]
     pub dev_dependencies: Option<HashMap<String, yaml_serde::Value>>,
 
+    /// After `pub get`, check each package's `.dart_tool/package_config.json`
+    /// and warn when a workspace dependency resolved outside the workspace
+    /// (e.g. to the pub cache) instead of the local package
+    #[serde(default)]
+    pub verify_links: Option<bool>,
+
     /// Lifecycle hooks (pre/post)
     #[serde(default)]
     pub hooks: Option<BootstrapHooks>,
@@ -798,13 +1112,60 @@ pub struct CleanHooks {
 }
 
 /// Configuration for the `test` command
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestCommandConfig {
+    /// Default concurrency when `--concurrency` is not passed
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
+    /// Kill a package's tests after this many seconds (0 = no timeout)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+
     /// Lifecycle hooks (pre/post)
     pub hooks: Option<TestHooks>,
 }
 
+impl TestCommandConfig {
+    /// Per-package timeout, or `None` when unset or `0`.
+    pub fn timeout_duration(&self) -> Option<std::time::Duration> {
+        script::timeout_from_secs(self.timeout)
+    }
+}
+
+/// Configuration for the `exec` command.
+///
+/// Mirrors the per-script `exec` options; each value applies only when the
+/// matching CLI flag is omitted.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecCommandConfig {
+    /// Default concurrency when `--concurrency` is not passed
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
+    /// Stop on first failure when `--fail-fast` is not passed
+    #[serde(default)]
+    pub fail_fast: Option<bool>,
+
+    /// Run packages in dependency order when `--order-dependents` is not passed
+    #[serde(default)]
+    pub order_dependents: Option<bool>,
+}
+
+impl ExecCommandConfig {
+    /// Whether to stop on first failure (default: false)
+    pub fn should_fail_fast(&self) -> bool {
+        self.fail_fast.unwrap_or(false)
+    }
+
+    /// Whether to run packages in dependency order (default: false)
+    pub fn should_order_dependents(&self) -> bool {
+        self.order_dependents.unwrap_or(false)
+    }
+}
+
 /// Hooks for the test command
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -828,9 +1189,13 @@ pub struct BootstrapHooks {
 }
 
 /// Configuration for the `publish` command
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PublishCommandConfig {
+    /// Default concurrency when `--concurrency` is not passed
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
     /// Lifecycle hooks (pre/post)
     #[serde(default)]
     pub hooks: Option<PublishHooks>,
@@ -873,7 +1238,7 @@ pub struct PublishHooks {
 ///     ios:
 ///       extraArgs: ["--export-options-plist", "ios/runner/exportOptions.plist"]
 /// ```
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildCommandConfig {
     /// Flavor definitions keyed by name (e.g. "prod", "qa", "dev")
@@ -892,6 +1257,10 @@ pub struct BuildCommandConfig {
     /// Package filters applied to all build targets
     pub package_filters: Option<filter::PackageFilters>,
 
+    /// Default concurrency when `--concurrency` is not passed
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+
     /// Lifecycle hooks (pre/post)
     pub hooks: Option<BuildHooks>,
 }
@@ -1096,6 +1465,14 @@ struct MelosSection {
     /// When true, recursively discover nested workspaces.
     #[serde(default)]
     discover_nested_workspaces: Option<bool>,
+
+    /// When true (default), skip package directories ignored by `.gitignore`.
+    #[serde(default)]
+    respect_gitignore: Option<bool>,
+
+    /// Environment variables set for every command and script
+    #[serde(default)]
+    env: HashMap<String, String>,
 }
 ///
 /// - **6.x (`melos.yaml`)**: Direct deserialization to `MelosConfig`.
@@ -1108,17 +1485,18 @@ pub fn parse_config(source: &ConfigSource) -> Result<MelosConfig> {
 
     match source {
         ConfigSource::MelosYaml(_) => {
-            let config: MelosConfig = yaml_serde::from_str(&content)
+            let config: MelosConfig = from_yaml_with_merge_keys(&content)
                 .with_context(|| format!("Failed to parse melos.yaml: {}", path.display()))?;
             Ok(config)
         }
         ConfigSource::PubspecYaml(_) => {
-            let wrapper: PubspecWithMelos = yaml_serde::from_str(&content).with_context(|| {
-                format!(
-                    "Failed to parse melos config from pubspec.yaml: {}",
-                    path.display()
-                )
-            })?;
+            let wrapper: PubspecWithMelos =
+                from_yaml_with_merge_keys(&content).with_context(|| {
+                    format!(
+                        "Failed to parse melos config from pubspec.yaml: {}",
+                        path.display()
+                    )
+                })?;
 
             // Name: prefer melos.name override, then pubspec top-level name
             let name = wrapper.melos.name.unwrap_or(wrapper.name);
@@ -1150,11 +1528,30 @@ pub fn parse_config(source: &ConfigSource) -> Result<MelosConfig> {
                 categories: wrapper.melos.categories,
                 use_root_as_package: wrapper.melos.use_root_as_package,
                 discover_nested_workspaces: wrapper.melos.discover_nested_workspaces,
+                respect_gitignore: wrapper.melos.respect_gitignore,
+                env: wrapper.melos.env,
             })
         }
     }
 }
 
+/// Deserialize YAML `content`, resolving `<<` merge keys first.
+///
+/// `yaml_serde` expands anchors and aliases but leaves `<<: *base` as a
+/// literal key, so documents using merge keys go through a [`yaml_serde::Value`]
+/// and [`yaml_serde::Value::apply_merge`]. Others are deserialized directly,
+/// which keeps line and column numbers in error messages.
+fn from_yaml_with_merge_keys<T: serde::de::DeserializeOwned>(content: &str) -> Result<T> {
+    if !content.contains("<<") {
+        return Ok(yaml_serde::from_str(content)?);
+    }
+    let mut value: yaml_serde::Value = yaml_serde::from_str(content)?;
+    value
+        .apply_merge()
+        .context("Failed to resolve YAML merge keys (`<<`)")?;
+    Ok(yaml_serde::from_value(value)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1190,6 +1587,7 @@ command:
     hooks:
       preCommit: dart format .
       postCommit: echo done
+      prePackage: dart run tool/write_version.dart
 "#;
         let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
         let version_config = config.command.unwrap().version.unwrap();
@@ -1205,6 +1603,11 @@ command:
         let hooks = version_config.hooks.unwrap();
         assert_eq!(hooks.pre_commit.as_deref(), Some("dart format ."));
         assert_eq!(hooks.post_commit.as_deref(), Some("echo done"));
+        assert_eq!(
+            hooks.pre_package.as_deref(),
+            Some("dart run tool/write_version.dart")
+        );
+        assert_eq!(hooks.post_package, None);
     }
 
     #[test]
@@ -1232,6 +1635,18 @@ scripts:
         assert_eq!(format.description(), None);
     }
 
+    #[test]
+    fn test_parse_respect_gitignore() {
+        let config: MelosConfig =
+            yaml_serde::from_str("name: ws\npackages:\n  - packages/**\n").unwrap();
+        assert!(config.should_respect_gitignore());
+
+        let config: MelosConfig =
+            yaml_serde::from_str("name: ws\npackages:\n  - packages/**\nrespectGitignore: false\n")
+                .unwrap();
+        assert!(!config.should_respect_gitignore());
+    }
+
     #[test]
     fn test_parse_script_with_env() {
         let yaml = r#"
@@ -1256,6 +1671,76 @@ scripts:
         assert_eq!(env["API_URL"], "https://api.example.com");
     }
 
+    #[test]
+    fn test_parse_config_resolves_merge_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("melos.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: test_project
+packages:
+  - packages/**
+x-env: &base_env
+  API_URL: https://api.example.com
+  FLAVOR: dev
+scripts:
+  build:
+    run: dart run build_runner build
+    env:
+      <<: *base_env
+      FLAVOR: production
+command:
+  bootstrap: &bootstrap
+    runPubGetOffline: true
+  test:
+    <<: *bootstrap
+    concurrency: 2
+"#,
+        )
+        .unwrap();
+
+        let config = parse_config(&ConfigSource::MelosYaml(path)).unwrap();
+        let env = config.scripts["build"].env();
+        assert_eq!(env.len(), 2);
+        assert_eq!(env["API_URL"], "https://api.example.com");
+        // Keys set next to the merge key win over the merged ones
+        assert_eq!(env["FLAVOR"], "production");
+        let command = config.command.unwrap();
+        assert_eq!(command.bootstrap.unwrap().run_pub_get_offline, Some(true));
+        assert_eq!(command.test.unwrap().concurrency, Some(2));
+    }
+
+    #[test]
+    fn test_parse_config_pubspec_resolves_merge_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("pubspec.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: ws
+workspace:
+  - packages/core
+melos:
+  scripts:
+    lint: &lint
+      run: dart analyze
+      env:
+        STRICT: "1"
+    lint:ci:
+      <<: *lint
+      description: Lint in CI
+"#,
+        )
+        .unwrap();
+
+        let config = parse_config(&ConfigSource::PubspecYaml(path)).unwrap();
+        let lint = &config.scripts["lint:ci"];
+        assert_eq!(lint.run_command(), Some("dart analyze"));
+        assert_eq!(lint.description(), Some("Lint in CI"));
+        assert_eq!(lint.env()["STRICT"], "1");
+    }
+
     #[test]
     fn test_parse_config_with_categories() {
         let yaml = r#"
@@ -1382,6 +1867,8 @@ melos:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert_eq!(warnings.len(), 1);
@@ -1406,6 +1893,8 @@ melos:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert_eq!(warnings.len(), 1);
@@ -1430,6 +1919,8 @@ melos:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert!(warnings.is_empty());
@@ -1450,12 +1941,160 @@ melos:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert_eq!(warnings.len(), 1);
         assert!(warnings[0].contains("empty `run` command"));
     }
 
+    #[test]
+    fn test_validate_broken_working_directory() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  gradle:
+    exec: ./gradlew build
+    workingDirectory: android
+  escape:
+    exec: ls
+    workingDirectory: ../outside
+  absolute:
+    exec: ls
+    workingDirectory: /tmp
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let mut warnings = config.validate();
+        warnings.sort();
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].starts_with("Script 'absolute'"));
+        assert!(warnings[0].contains("must be relative to the package root"));
+        assert!(warnings[1].starts_with("Script 'escape'"));
+        assert!(warnings[1].contains("escapes the package root"));
+    }
+
+    #[test]
+    fn test_validate_working_directory_on_root_script() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  root:
+    run: ./gradlew build
+    workingDirectory: android
+  per_package:
+    run: melos exec -- ./gradlew build
+    workingDirectory: android
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let warnings = config.validate();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].starts_with("Script 'root': `workingDirectory` only applies"));
+    }
+
+    #[test]
+    fn test_validate_unknown_script_os() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  pods:
+    run: pod install
+    os: [macos, darwin]
+  anywhere:
+    run: echo hi
+    os: []
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let warnings = config.validate();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].starts_with("Script 'pods': unknown os 'darwin'"));
+    }
+
+    #[test]
+    fn test_parse_script_concurrency_group() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  integration:
+    exec: flutter test integration_test
+    concurrencyGroup: emulator
+  blank:
+    exec: ls
+    concurrencyGroup: " "
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        assert_eq!(
+            config
+                .scripts
+                .get("integration")
+                .unwrap()
+                .concurrency_group(),
+            Some("emulator")
+        );
+        let warnings = config.validate();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].starts_with("Script 'blank': `concurrencyGroup` is empty"));
+    }
+
+    #[test]
+    fn test_parse_script_when() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  pods:
+    run: pod install
+    when: test -d ios
+  plain: echo hi
+  blank:
+    run: echo hi
+    when: ""
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let script = |name: &str| config.scripts.get(name).unwrap();
+        assert_eq!(script("pods").when(), Some("test -d ios"));
+        assert_eq!(script("plain").when(), None);
+        let warnings = config.validate();
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].starts_with("Script 'blank': `when` is empty"));
+    }
+
+    #[test]
+    fn test_script_supports_os() {
+        let yaml = r#"
+name: ws
+packages:
+  - packages/**
+scripts:
+  simple: echo hi
+  empty:
+    run: echo hi
+    os: []
+  unix:
+    run: echo hi
+    os: [macos, linux]
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let script = |name: &str| config.scripts.get(name).unwrap();
+        assert!(script("simple").supports_os("windows"));
+        assert!(script("empty").supports_os("windows"));
+        assert!(script("unix").supports_os("linux"));
+        assert!(!script("unix").supports_os("windows"));
+        assert_eq!(
+            script("unix").os(),
+            Some(&["macos".to_string(), "linux".to_string()][..])
+        );
+    }
+
     #[test]
     fn test_validate_undefined_category_reference() {
         let mut scripts = HashMap::new();
@@ -1465,6 +2104,7 @@ melos:
                 run: "flutter test".to_string(),
                 exec: None,
                 steps: None,
+                matrix: None,
                 private: None,
                 description: None,
                 package_filters: Some(filter::PackageFilters {
@@ -1473,6 +2113,10 @@ melos:
                 }),
                 env: HashMap::new(),
                 groups: None,
+                working_directory: None,
+                os: None,
+                concurrency_group: None,
+                when: None,
             })),
         );
         let config = MelosConfig {
@@ -1486,6 +2130,8 @@ melos:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert_eq!(warnings.len(), 1);
@@ -1601,6 +2247,7 @@ repository:
     fn test_repository_config_commit_url() {
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo".to_string(),
+            kind: RepoKind::GitHub,
         };
         assert_eq!(
             repo.commit_url("abc1234"),
@@ -1612,6 +2259,7 @@ repository:
     fn test_repository_config_commit_url_trailing_slash() {
         let repo = RepositoryConfig {
             url: "https://github.com/org/repo/".to_string(),
+            kind: RepoKind::GitHub,
         };
         assert_eq!(
             repo.commit_url("def5678"),
@@ -1619,6 +2267,100 @@ repository:
         );
     }
 
+    fn repo_from_yaml(repository: &str) -> RepositoryConfig {
+        let yaml =
+            format!("name: test_project\npackages:\n  - packages/**\nrepository:\n{repository}");
+        let config: MelosConfig = yaml_serde::from_str(&yaml).unwrap();
+        config.repository.unwrap()
+    }
+
+    #[test]
+    fn test_repository_config_commit_url_per_provider() {
+        let github = repo_from_yaml("  type: github\n  owner: org\n  name: repo\n");
+        assert_eq!(github.kind, RepoKind::GitHub);
+        assert_eq!(
+            github.commit_url("abc1234"),
+            "https://github.com/org/repo/commit/abc1234"
+        );
+
+        let gitlab = repo_from_yaml("  type: gitlab\n  owner: org\n  name: repo\n");
+        assert_eq!(gitlab.kind, RepoKind::GitLab);
+        assert_eq!(
+            gitlab.commit_url("abc1234"),
+            "https://gitlab.com/org/repo/commit/abc1234"
+        );
+
+        let bitbucket = repo_from_yaml("  type: bitbucket\n  owner: org\n  name: repo\n");
+        assert_eq!(bitbucket.kind, RepoKind::Bitbucket);
+        assert_eq!(
+            bitbucket.commit_url("abc1234"),
+            "https://bitbucket.org/org/repo/commits/abc1234"
+        );
+
+        let azure = repo_from_yaml("  type: azure-devops\n  owner: org\n  name: repo\n");
+        assert_eq!(azure.kind, RepoKind::AzureDevOps);
+        assert_eq!(azure.url, "https://dev.azure.com/org/repo/_git/repo");
+        assert_eq!(
+            azure.commit_url("abc1234"),
+            "https://dev.azure.com/org/repo/_git/repo?version=GCabc1234"
+        );
+    }
+
+    #[test]
+    fn test_repository_config_release_url_per_provider() {
+        let bitbucket = repo_from_yaml("  type: bitbucket\n  owner: org\n  name: repo\n");
+        assert_eq!(
+            bitbucket.release_url("core-v1.0.0", "core v1.0.0"),
+            "https://bitbucket.org/org/repo/commits/tag/core-v1.0.0"
+        );
+
+        let azure = repo_from_yaml("  type: azure\n  owner: org\n  name: repo\n");
+        assert_eq!(
+            azure.release_url("core-v1.0.0", "core v1.0.0"),
+            "https://dev.azure.com/org/repo/_git/repo?version=GTcore-v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_repository_config_directory_url_per_provider() {
+        let github = RepositoryConfig {
+            url: "https://github.com/org/repo/".to_string(),
+            kind: RepoKind::GitHub,
+        };
+        assert_eq!(
+            github.directory_url("packages/core", "main"),
+            "https://github.com/org/repo/tree/main/packages/core"
+        );
+        assert_eq!(
+            github.directory_url("", "main"),
+            "https://github.com/org/repo"
+        );
+
+        let bitbucket = repo_from_yaml("  type: bitbucket\n  owner: org\n  name: repo\n");
+        assert_eq!(
+            bitbucket.directory_url("packages/core", "dev"),
+            "https://bitbucket.org/org/repo/src/dev/packages/core"
+        );
+
+        let azure = repo_from_yaml("  type: azure\n  owner: org\n  name: repo\n");
+        assert_eq!(
+            azure.directory_url("packages/core", "main"),
+            "https://dev.azure.com/org/repo/_git/repo?path=/packages/core&version=GBmain"
+        );
+    }
+
+    #[test]
+    fn test_repository_config_url_string_is_github_style() {
+        let yaml = "name: test_project\npackages:\n  - packages/**\nrepository: https://bitbucket.org/org/repo\n";
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let repo = config.repository.unwrap();
+        assert_eq!(repo.kind, RepoKind::GitHub);
+        assert_eq!(
+            repo.commit_url("abc1234"),
+            "https://bitbucket.org/org/repo/commit/abc1234"
+        );
+    }
+
     #[test]
     fn test_repository_config_absent() {
         let yaml = r#"
@@ -1723,9 +2465,9 @@ scripts:
 
         let steps = entry.steps().unwrap();
         assert_eq!(steps.len(), 3);
-        assert_eq!(steps[0], "analyze");
-        assert_eq!(steps[1], "dart format --set-exit-if-changed .");
-        assert_eq!(steps[2], "test:unit");
+        assert_eq!(steps[0].script, "analyze");
+        assert_eq!(steps[1].script, "dart format --set-exit-if-changed .");
+        assert_eq!(steps[2].script, "test:unit");
         // run_command returns None for steps-only scripts
         assert!(entry.run_command().is_none());
     }
@@ -1766,12 +2508,24 @@ scripts:
             ScriptEntry::Full(Box::new(ScriptConfig {
                 run: String::new(),
                 exec: None,
-                steps: Some(vec!["analyze".to_string(), "test".to_string()]),
+                steps: Some(
+                    ["analyze", "test"]
+                        .map(|step| StepEntry {
+                            script: step.to_string(),
+                            continue_on_error: false,
+                        })
+                        .to_vec(),
+                ),
+                matrix: None,
                 private: None,
                 description: None,
                 package_filters: None,
                 env: HashMap::new(),
                 groups: None,
+                working_directory: None,
+                os: None,
+                concurrency_group: None,
+                when: None,
             })),
         );
         let config = MelosConfig {
@@ -1785,6 +2539,8 @@ scripts:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert!(
@@ -1804,11 +2560,16 @@ scripts:
                 run: String::new(),
                 exec: Some(script::ExecEntry::Command("flutter test".to_string())),
                 steps: None,
+                matrix: None,
                 private: None,
                 description: None,
                 package_filters: None,
                 env: HashMap::new(),
                 groups: None,
+                working_directory: None,
+                os: None,
+                concurrency_group: None,
+                when: None,
             })),
         );
         let config = MelosConfig {
@@ -1822,6 +2583,8 @@ scripts:
             categories: HashMap::new(),
             use_root_as_package: None,
             discover_nested_workspaces: None,
+            respect_gitignore: None,
+            env: HashMap::new(),
         };
         let warnings = config.validate();
         assert!(
@@ -1967,6 +2730,36 @@ melos:
         );
     }
 
+    #[test]
+    fn test_parse_workspace_env() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+env:
+  PUB_CACHE: ${MELOS_ROOT_PATH}/.pub-cache
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        assert_eq!(
+            config.env.get("PUB_CACHE").map(String::as_str),
+            Some("${MELOS_ROOT_PATH}/.pub-cache")
+        );
+
+        let yaml = r#"
+name: my_workspace
+workspace:
+  - packages/core
+melos:
+  env:
+    FLUTTER_ROOT: /opt/flutter
+"#;
+        let wrapper: PubspecWithMelos = yaml_serde::from_str(yaml).unwrap();
+        assert_eq!(
+            wrapper.melos.env.get("FLUTTER_ROOT").map(String::as_str),
+            Some("/opt/flutter")
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Script groups tests
     // -----------------------------------------------------------------------
@@ -2051,4 +2844,121 @@ command:
         assert_eq!(hooks.pre.as_deref(), Some("echo pre-publish"));
         assert_eq!(hooks.post.as_deref(), Some("echo post-publish"));
     }
+
+    #[test]
+    fn test_parse_command_concurrency_defaults() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  bootstrap:
+    concurrency: 8
+  test:
+    concurrency: 4
+  publish: {}
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let cmd = config.command.unwrap();
+        assert_eq!(cmd.bootstrap.unwrap().concurrency, Some(8));
+        assert_eq!(cmd.test.unwrap().concurrency, Some(4));
+        assert_eq!(cmd.publish.unwrap().concurrency, None);
+    }
+
+    #[test]
+    fn test_parse_test_command_timeout() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  test:
+    timeout: 120
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let test = config.command.unwrap().test.unwrap();
+        assert_eq!(test.timeout, Some(120));
+        assert_eq!(
+            test.timeout_duration(),
+            Some(std::time::Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn test_parse_exec_command_config() {
+        let yaml = r#"
+name: test_project
+packages:
+  - packages/**
+command:
+  exec:
+    concurrency: 2
+    failFast: true
+    orderDependents: true
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let exec = config.command.unwrap().exec.unwrap();
+        assert_eq!(exec.concurrency, Some(2));
+        assert!(exec.should_fail_fast());
+        assert!(exec.should_order_dependents());
+
+        let defaults = ExecCommandConfig::default();
+        assert!(!defaults.should_fail_fast());
+        assert!(!defaults.should_order_dependents());
+    }
+
+    #[test]
+    fn test_validate_matrix_well_formed() {
+        let yaml = r#"
+name: test
+packages:
+  - packages/**
+scripts:
+  gen:
+    matrix:
+      - packageFilters:
+          flutter: false
+        run: dart run build_runner build
+      - packageFilters:
+          flutter: true
+        run: flutter gen-l10n
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let warnings = config.validate();
+        assert!(
+            warnings.is_empty(),
+            "Expected no warnings, got: {:?}",
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_validate_matrix_malformed_entries() {
+        let yaml = r#"
+name: test
+packages:
+  - packages/**
+scripts:
+  gen:
+    matrix:
+      - packageFilters:
+          category: [missing]
+        run: ""
+      - run: dart test
+"#;
+        let config: MelosConfig = yaml_serde::from_str(yaml).unwrap();
+        let warnings = config.validate();
+        assert_eq!(warnings.len(), 3, "got: {:?}", warnings);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("entry 1 has an empty `run`"))
+        );
+        assert!(warnings.iter().any(|w| w.contains("category 'missing'")));
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("entry 2 has no `packageFilters`"))
+        );
+    }
 }
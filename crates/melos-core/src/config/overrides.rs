@@ -0,0 +1,284 @@
+//! Developer-local config overlays from `.melos_overrides.yaml`.
+//!
+//! The file sits next to the workspace config and is meant to be gitignored.
+//! Only a whitelist of machine-specific settings can be overridden; scripts,
+//! packages and everything else that shapes the shared workflow cannot.
+//!
+//! ```yaml
+//! sdkPath: /opt/flutter
+//! respectGitignore: false
+//! command:
+//!   bootstrap:
+//!     concurrency: 2
+//!   exec:
+//!     concurrency: 8
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::{
+    BootstrapCommandConfig, BuildCommandConfig, CommandConfig, ExecCommandConfig, MelosConfig,
+    PublishCommandConfig, TestCommandConfig,
+};
+
+/// File name of the local overrides file, resolved next to the config file.
+pub const OVERRIDES_FILE: &str = ".melos_overrides.yaml";
+
+/// `command` sections whose `concurrency` can be overridden.
+const OVERRIDABLE_COMMANDS: &[&str] = &["bootstrap", "build", "exec", "publish", "test"];
+
+/// Whitelisted contents of an overrides file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigOverrides {
+    sdk_path: Option<String>,
+    respect_gitignore: Option<bool>,
+    #[serde(default)]
+    command: BTreeMap<String, CommandOverride>,
+    /// Keys that are not overridable, kept only to report them
+    #[serde(flatten)]
+    other: BTreeMap<String, yaml_serde::Value>,
+}
+
+/// Overridable settings of a single `command.<name>` section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandOverride {
+    concurrency: Option<usize>,
+    #[serde(flatten)]
+    other: BTreeMap<String, yaml_serde::Value>,
+}
+
+/// Outcome of applying an overrides file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AppliedOverrides {
+    /// Dotted paths of the fields that were overridden, e.g. `sdkPath` or
+    /// `command.exec.concurrency`
+    pub fields: Vec<String>,
+    /// Dotted paths of keys that are not overridable and were ignored
+    pub ignored: Vec<String>,
+}
+
+/// Apply `.melos_overrides.yaml` from `dir` to `config`, if the file exists.
+///
+/// Returns `None` when there is no overrides file.
+pub fn load_overrides(dir: &Path, config: &mut MelosConfig) -> Result<Option<AppliedOverrides>> {
+    let path = dir.join(OVERRIDES_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let applied = apply_overrides(&content, config)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(applied))
+}
+
+/// Merge the whitelisted fields of the overrides YAML `content` into `config`.
+///
+/// Missing `command` sections are created as needed; fields not set in the
+/// overrides keep their configured values.
+pub fn apply_overrides(content: &str, config: &mut MelosConfig) -> Result<AppliedOverrides> {
+    // An empty file deserializes to `null`; treat it as no overrides
+    let overrides: ConfigOverrides =
+        yaml_serde::from_str::<Option<ConfigOverrides>>(content)?.unwrap_or_default();
+    let mut applied = AppliedOverrides::default();
+
+    if let Some(sdk_path) = overrides.sdk_path {
+        config.sdk_path = Some(sdk_path);
+        applied.fields.push("sdkPath".to_string());
+    }
+    if let Some(respect) = overrides.respect_gitignore {
+        config.respect_gitignore = Some(respect);
+        applied.fields.push("respectGitignore".to_string());
+    }
+
+    for (name, section) in overrides.command {
+        if !OVERRIDABLE_COMMANDS.contains(&name.as_str()) {
+            applied.ignored.push(format!("command.{name}"));
+            continue;
+        }
+        applied.ignored.extend(
+            section
+                .other
+                .keys()
+                .map(|key| format!("command.{name}.{key}")),
+        );
+        if let Some(concurrency) = section.concurrency {
+            set_command_concurrency(config, &name, concurrency);
+            applied.fields.push(format!("command.{name}.concurrency"));
+        }
+    }
+
+    applied.ignored.extend(overrides.other.into_keys());
+    applied.ignored.sort();
+    Ok(applied)
+}
+
+/// Set `command.<name>.concurrency`, creating the sections when missing.
+fn set_command_concurrency(config: &mut MelosConfig, name: &str, concurrency: usize) {
+    let command = config.command.get_or_insert_with(CommandConfig::default);
+    let slot = match name {
+        "bootstrap" => {
+            &mut command
+                .bootstrap
+                .get_or_insert_with(BootstrapCommandConfig::default)
+                .concurrency
+        }
+        "build" => {
+            &mut command
+                .build
+                .get_or_insert_with(BuildCommandConfig::default)
+                .concurrency
+        }
+        "exec" => {
+            &mut command
+                .exec
+                .get_or_insert_with(ExecCommandConfig::default)
+                .concurrency
+        }
+        "publish" => {
+            &mut command
+                .publish
+                .get_or_insert_with(PublishCommandConfig::default)
+                .concurrency
+        }
+        "test" => {
+            &mut command
+                .test
+                .get_or_insert_with(TestCommandConfig::default)
+                .concurrency
+        }
+        _ => return,
+    };
+    *slot = Some(concurrency);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_config() -> MelosConfig {
+        yaml_serde::from_str(
+            r#"
+name: ws
+packages:
+  - packages/**
+sdkPath: /opt/committed
+scripts:
+  analyze: dart analyze
+command:
+  bootstrap:
+    concurrency: 4
+    runPubGetOffline: true
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_overrides_merges_whitelisted_fields() {
+        let mut config = base_config();
+        let applied = apply_overrides(
+            r#"
+sdkPath: /opt/local
+command:
+  bootstrap:
+    concurrency: 1
+  exec:
+    concurrency: 8
+"#,
+            &mut config,
+        )
+        .unwrap();
+
+        assert_eq!(
+            applied.fields,
+            vec![
+                "sdkPath",
+                "command.bootstrap.concurrency",
+                "command.exec.concurrency"
+            ]
+        );
+        assert!(applied.ignored.is_empty());
+        assert_eq!(config.sdk_path.as_deref(), Some("/opt/local"));
+
+        let command = config.command.as_ref().unwrap();
+        let bootstrap = command.bootstrap.as_ref().unwrap();
+        assert_eq!(bootstrap.concurrency, Some(1));
+        // Sibling fields of an overridden section are kept
+        assert_eq!(bootstrap.run_pub_get_offline, Some(true));
+        assert_eq!(command.exec.as_ref().unwrap().concurrency, Some(8));
+    }
+
+    #[test]
+    fn test_apply_overrides_ignores_scripts_and_packages() {
+        let mut config = base_config();
+        let applied = apply_overrides(
+            r#"
+packages:
+  - other/**
+scripts:
+  analyze: echo hijacked
+command:
+  bootstrap:
+    runPubGetOffline: false
+  version:
+    branch: dev
+"#,
+            &mut config,
+        )
+        .unwrap();
+
+        assert!(applied.fields.is_empty());
+        assert_eq!(
+            applied.ignored,
+            vec![
+                "command.bootstrap.runPubGetOffline",
+                "command.version",
+                "packages",
+                "scripts"
+            ]
+        );
+        assert_eq!(config.packages, vec!["packages/**"]);
+        assert_eq!(
+            config.scripts["analyze"].run_command(),
+            Some("dart analyze")
+        );
+        assert_eq!(
+            config
+                .command
+                .as_ref()
+                .unwrap()
+                .bootstrap
+                .as_ref()
+                .unwrap()
+                .run_pub_get_offline,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_load_overrides_missing_or_empty_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = base_config();
+        assert!(load_overrides(dir.path(), &mut config).unwrap().is_none());
+
+        std::fs::write(dir.path().join(OVERRIDES_FILE), "").unwrap();
+        let applied = load_overrides(dir.path(), &mut config).unwrap().unwrap();
+        assert_eq!(applied, AppliedOverrides::default());
+        assert_eq!(config.sdk_path.as_deref(), Some("/opt/committed"));
+    }
+
+    #[test]
+    fn test_load_overrides_invalid_yaml_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join(OVERRIDES_FILE), "sdkPath: [unclosed").unwrap();
+        let err = load_overrides(dir.path(), &mut base_config()).unwrap_err();
+        assert!(format!("{err:#}").contains(OVERRIDES_FILE));
+    }
+}
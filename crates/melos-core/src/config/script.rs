@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
 
 use serde::Deserialize;
 
@@ -29,9 +31,20 @@ pub struct ScriptConfig {
     /// 1. If it matches a script name in the config → execute that script inline
     /// 2. Otherwise → execute as a shell command at workspace root
     ///
+    /// A step can also be an object, `{ script: analyze, continueOnError: true }`,
+    /// so that its failure doesn't abort the remaining steps.
+    ///
     /// `packageFilters` and exec options cannot be used on the steps wrapper.
     #[serde(default)]
-    pub steps: Option<Vec<String>>,
+    pub steps: Option<Vec<StepEntry>>,
+
+    /// Package-scoped sub-runs: each entry runs its own command in the
+    /// packages matched by its own `packageFilters`.
+    ///
+    /// Entries are executed sequentially in declaration order. When `matrix`
+    /// is present, `run` and `exec` are ignored.
+    #[serde(default)]
+    pub matrix: Option<Vec<MatrixEntry>>,
 
     /// Whether this script is private (hidden from interactive selection and `run --list`).
     ///
@@ -58,6 +71,144 @@ pub struct ScriptConfig {
     /// only scripts that belong to at least one matching group are shown/run.
     #[serde(default)]
     pub groups: Option<Vec<String>>,
+
+    /// Directory, relative to each package root, to run per-package commands in
+    /// (e.g. `android` for Gradle tasks). Defaults to the package root.
+    #[serde(default)]
+    pub working_directory: Option<String>,
+
+    /// Platforms this script runs on (`macos`, `linux`, `windows`).
+    ///
+    /// On any other platform `run` skips the script. Absent or empty means
+    /// all platforms.
+    #[serde(default)]
+    pub os: Option<Vec<String>>,
+
+    /// Shell predicate deciding whether the script runs, e.g.
+    /// `test -f pubspec.lock` or `[ -n "$CI" ]`.
+    ///
+    /// Evaluated once per script invocation (not per package) in the
+    /// workspace root, with the workspace and script `env` set. A non-zero
+    /// exit skips the script.
+    #[serde(default)]
+    pub when: Option<String>,
+
+    /// Named group whose per-package commands never run concurrently.
+    ///
+    /// All packages of scripts sharing a group run one at a time across the
+    /// whole `melos-rs` run, however high `concurrency` is. Each command still
+    /// takes a slot under the script's own concurrency limit, so a group can
+    /// only lower parallelism. Scripts in different groups, or without one,
+    /// run concurrently as usual.
+    #[serde(default)]
+    pub concurrency_group: Option<String>,
+}
+
+/// Platform names accepted in a script's `os` list, as reported by
+/// `std::env::consts::OS`.
+pub const KNOWN_SCRIPT_OS: &[&str] = &["macos", "linux", "windows"];
+
+/// Validate a script `workingDirectory` and normalize it to a relative path.
+///
+/// Rejects empty and absolute paths, and paths whose `..` components climb
+/// above the package root. The returned path may be empty (`.` or `a/..`),
+/// meaning the package root itself.
+pub fn normalize_working_directory(dir: &str) -> Result<PathBuf, String> {
+    if dir.trim().is_empty() {
+        return Err("`workingDirectory` is empty".to_string());
+    }
+    let mut normalized = PathBuf::new();
+    for component in Path::new(dir).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(format!(
+                        "`workingDirectory` '{}' escapes the package root",
+                        dir
+                    ));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "`workingDirectory` '{}' must be relative to the package root",
+                    dir
+                ));
+            }
+        }
+    }
+    Ok(normalized)
+}
+
+/// One sub-run of a `matrix` script.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatrixEntry {
+    /// Filters selecting the packages this entry runs in
+    #[serde(default)]
+    pub package_filters: Option<PackageFilters>,
+
+    /// The command to run in each matched package
+    #[serde(default)]
+    pub run: String,
+}
+
+/// One entry of a `steps` list: a script name or shell command, given as a
+/// plain string or as an object with failure handling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepEntry {
+    /// Script name or shell command to run
+    pub script: String,
+    /// Continue with the next step when this one fails (object form only)
+    pub continue_on_error: bool,
+}
+
+/// Object form of a [`StepEntry`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StepObject {
+    script: String,
+    #[serde(default)]
+    continue_on_error: bool,
+}
+
+impl<'de> Deserialize<'de> for StepEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de;
+
+        struct StepEntryVisitor;
+
+        impl<'de> de::Visitor<'de> for StepEntryVisitor {
+            type Value = StepEntry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(
+                    "a script name or command string, or an object with script/continueOnError",
+                )
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(StepEntry {
+                    script: v.to_string(),
+                    continue_on_error: false,
+                })
+            }
+
+            fn visit_map<M: de::MapAccess<'de>>(self, map: M) -> Result<Self::Value, M::Error> {
+                let step = StepObject::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(StepEntry {
+                    script: step.script,
+                    continue_on_error: step.continue_on_error,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(StepEntryVisitor)
+    }
 }
 
 /// Exec configuration that can be either a string shorthand or an options object.
@@ -89,6 +240,23 @@ pub struct ExecOptions {
     /// Execute packages in dependency order
     #[serde(default)]
     pub order_dependents: bool,
+
+    /// Kill the command in a package after this many seconds (0 = no timeout)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+impl ExecOptions {
+    /// Per-package timeout, or `None` when unset or `0`.
+    pub fn timeout_duration(&self) -> Option<Duration> {
+        timeout_from_secs(self.timeout)
+    }
+}
+
+/// Convert a config timeout in seconds to a [`Duration`], treating `0` as
+/// no timeout.
+pub fn timeout_from_secs(secs: Option<u64>) -> Option<Duration> {
+    secs.filter(|&s| s > 0).map(Duration::from_secs)
 }
 
 impl<'de> Deserialize<'de> for ExecEntry {
@@ -158,6 +326,27 @@ exec:
         }
     }
 
+    #[test]
+    fn test_exec_options_timeout() {
+        let yaml = r#"
+run: dart test
+exec:
+  timeout: 30
+"#;
+        let config: ScriptConfig = yaml_serde::from_str(yaml).unwrap();
+        let Some(ExecEntry::Options(opts)) = config.exec else {
+            panic!("Expected ExecEntry::Options");
+        };
+        assert_eq!(opts.timeout_duration(), Some(Duration::from_secs(30)));
+
+        let no_timeout = ExecOptions {
+            timeout: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(no_timeout.timeout_duration(), None);
+        assert_eq!(ExecOptions::default().timeout_duration(), None);
+    }
+
     #[test]
     fn test_exec_entry_object_defaults() {
         let yaml = r#"
@@ -186,9 +375,49 @@ steps:
         let config: ScriptConfig = yaml_serde::from_str(yaml).unwrap();
         let steps = config.steps.unwrap();
         assert_eq!(steps.len(), 3);
-        assert_eq!(steps[0], "analyze");
-        assert_eq!(steps[1], "dart format --set-exit-if-changed .");
-        assert_eq!(steps[2], "test:unit");
+        assert_eq!(steps[0].script, "analyze");
+        assert_eq!(steps[1].script, "dart format --set-exit-if-changed .");
+        assert_eq!(steps[2].script, "test:unit");
+        assert!(steps.iter().all(|s| !s.continue_on_error));
+    }
+
+    #[test]
+    fn test_steps_object_form_with_continue_on_error() {
+        let yaml = r#"
+steps:
+  - script: analyze
+    continueOnError: true
+  - script: test:unit
+  - dart format .
+"#;
+        let config: ScriptConfig = yaml_serde::from_str(yaml).unwrap();
+        let steps = config.steps.unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                StepEntry {
+                    script: "analyze".to_string(),
+                    continue_on_error: true,
+                },
+                StepEntry {
+                    script: "test:unit".to_string(),
+                    continue_on_error: false,
+                },
+                StepEntry {
+                    script: "dart format .".to_string(),
+                    continue_on_error: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_steps_object_form_requires_script() {
+        let yaml = r#"
+steps:
+  - continueOnError: true
+"#;
+        assert!(yaml_serde::from_str::<ScriptConfig>(yaml).is_err());
     }
 
     #[test]
@@ -245,4 +474,65 @@ env:
         assert!(config.package_filters.is_some());
         assert_eq!(config.env.get("CI"), Some(&"true".to_string()));
     }
+
+    #[test]
+    fn test_working_directory_parsing() {
+        let yaml = r#"
+exec: ./gradlew assembleDebug
+workingDirectory: android
+"#;
+        let config: ScriptConfig = yaml_serde::from_str(yaml).unwrap();
+        assert_eq!(config.working_directory.as_deref(), Some("android"));
+    }
+
+    #[test]
+    fn test_normalize_working_directory() {
+        assert_eq!(
+            normalize_working_directory("android/app").unwrap(),
+            PathBuf::from("android/app")
+        );
+        assert_eq!(
+            normalize_working_directory("./android/../ios").unwrap(),
+            PathBuf::from("ios")
+        );
+        assert_eq!(normalize_working_directory(".").unwrap(), PathBuf::new());
+
+        assert!(
+            normalize_working_directory("")
+                .unwrap_err()
+                .contains("empty")
+        );
+        assert!(
+            normalize_working_directory("/tmp")
+                .unwrap_err()
+                .contains("must be relative")
+        );
+        assert!(
+            normalize_working_directory("android/../../other")
+                .unwrap_err()
+                .contains("escapes the package root")
+        );
+    }
+
+    #[test]
+    fn test_matrix_parsing() {
+        let yaml = r#"
+matrix:
+  - packageFilters:
+      flutter: false
+    run: dart run build_runner build
+  - packageFilters:
+      flutter: true
+    run: flutter gen-l10n
+"#;
+        let config: ScriptConfig = yaml_serde::from_str(yaml).unwrap();
+        let matrix = config.matrix.unwrap();
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(matrix[0].run, "dart run build_runner build");
+        assert_eq!(
+            matrix[0].package_filters.as_ref().and_then(|f| f.flutter),
+            Some(false)
+        );
+        assert_eq!(matrix[1].run, "flutter gen-l10n");
+    }
 }
@@ -0,0 +1,186 @@
+//! Parsing of dotenv files (`--env-file`).
+//!
+//! Supports `KEY=VALUE` lines, an optional `export ` prefix, `#` comments,
+//! single-quoted values (taken literally) and double-quoted values (with
+//! `\n`, `\t`, `\"` and `\\` escapes). Values span a single line.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+/// Parse dotenv `content` into `(key, value)` pairs, in file order.
+///
+/// Errors name the 1-based line number of the first malformed line.
+pub fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected KEY=VALUE, got `{}`", line_no, line);
+        };
+        let key = key.trim();
+        if !is_valid_key(key) {
+            bail!("line {}: invalid variable name `{}`", line_no, key);
+        }
+        let value = parse_value(value.trim()).with_context(|| format!("line {}", line_no))?;
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+/// Load dotenv files in order; later files override earlier ones.
+pub fn load_env_files<P: AsRef<Path>>(paths: &[P]) -> Result<HashMap<String, String>> {
+    let mut env = HashMap::new();
+    for path in paths {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read env file {}", path.display()))?;
+        // Keep the line number in the one-line message callers print
+        let vars = parse_dotenv(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse env file {}: {:#}", path.display(), e))?;
+        env.extend(vars);
+    }
+    Ok(env)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse the text after `=`: quoted, or unquoted up to a ` #` comment.
+fn parse_value(raw: &str) -> Result<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let Some(end) = rest.find('\'') else {
+            bail!("unterminated single-quoted value");
+        };
+        check_trailing(&rest[end + 1..])?;
+        return Ok(rest[..end].to_string());
+    }
+
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    check_trailing(&rest[i + 1..])?;
+                    return Ok(value);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(other) => {
+                        if !matches!(other, '"' | '\\' | '$') {
+                            value.push('\\');
+                        }
+                        value.push(other);
+                    }
+                    None => bail!("unterminated double-quoted value"),
+                },
+                _ => value.push(c),
+            }
+        }
+        bail!("unterminated double-quoted value");
+    }
+
+    let value = match raw.find(" #") {
+        Some(idx) => &raw[..idx],
+        None => raw,
+    };
+    Ok(value.trim_end().to_string())
+}
+
+/// Only whitespace or a comment may follow a closing quote.
+fn check_trailing(rest: &str) -> Result<()> {
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(())
+    } else {
+        bail!("unexpected `{}` after closing quote", rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv_values() {
+        let content = "\
+# database
+DB_HOST=localhost
+export DB_PORT = 5432
+EMPTY=
+GREETING=\"hello\\nworld\" # trailing comment
+RAW='no $expansion \\n here'
+URL=https://example.com/#anchor # comment
+";
+        let vars = parse_dotenv(content).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+                ("EMPTY".to_string(), String::new()),
+                ("GREETING".to_string(), "hello\nworld".to_string()),
+                ("RAW".to_string(), "no $expansion \\n here".to_string()),
+                ("URL".to_string(), "https://example.com/#anchor".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dotenv_errors_name_line() {
+        let err = parse_dotenv("A=1\n\nnot a pair\n").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "line 3: expected KEY=VALUE, got `not a pair`"
+        );
+
+        let err = parse_dotenv("1BAD=x").unwrap_err();
+        assert!(format!("{err:#}").contains("line 1: invalid variable name `1BAD`"));
+
+        let err = parse_dotenv("A=1\nB=\"open\n").unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "line 2: unterminated double-quoted value"
+        );
+
+        let err = parse_dotenv("C='x' y").unwrap_err();
+        assert!(format!("{err:#}").contains("after closing quote"));
+    }
+
+    #[test]
+    fn test_load_env_files_layers_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join(".env");
+        let local = dir.path().join(".env.local");
+        std::fs::write(&base, "A=base\nB=base\n").unwrap();
+        std::fs::write(&local, "B=local\n").unwrap();
+
+        let env = load_env_files(&[&base, &local]).unwrap();
+        assert_eq!(env["A"], "base");
+        assert_eq!(env["B"], "local");
+
+        std::fs::write(&local, "B=local\nbroken\n").unwrap();
+        let err = load_env_files(&[&base, &local]).unwrap_err();
+        assert!(
+            err.to_string()
+                .ends_with(".env.local: line 2: expected KEY=VALUE, got `broken`")
+        );
+
+        let err = load_env_files(&[dir.path().join("missing.env")]).unwrap_err();
+        assert!(err.to_string().contains("Failed to read env file"));
+    }
+}
@@ -5,6 +5,10 @@ use std::time::Duration;
 /// These events decouple the execution engine from the presentation layer,
 /// allowing different frontends (CLI progress bars, TUI, JSON output) to
 /// consume the same event stream.
+///
+/// For a given package, `PackageStarted` is always sent before its
+/// `PackageOutput`, `PackageRetry` and `PackageFinished` events: the runner
+/// emits all of them from the package's task over a single FIFO channel.
 #[derive(Debug, Clone)]
 pub enum Event {
     /// A command is about to run across packages.
@@ -22,6 +26,25 @@ pub enum Event {
         success: bool,
         duration: Duration,
     },
+    /// A package command failed and will be run again after `delay`.
+    ///
+    /// `attempt` is the upcoming attempt (2 for the first retry) out of
+    /// `max_attempts` in total.
+    PackageRetry {
+        name: String,
+        attempt: usize,
+        max_attempts: usize,
+        delay: Duration,
+    },
+    /// A package was skipped without running (e.g. deadline, fail-fast).
+    PackageSkipped { name: String, reason: String },
+    /// A running package command was killed (e.g. fail-fast after another
+    /// package failed). Sent instead of `PackageFinished`.
+    PackageAborted {
+        name: String,
+        reason: String,
+        duration: Duration,
+    },
     /// A line of output from a package command.
     PackageOutput {
         name: String,
@@ -39,3 +62,135 @@ pub enum Event {
     /// An informational message.
     Info(String),
 }
+
+impl Event {
+    /// Serialize the event as a JSON object for `--json --stream` output.
+    ///
+    /// The variant name is the camelCase `event` field; durations are
+    /// reported in milliseconds (`durationMs`, `delayMs`).
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::json;
+        match self {
+            Event::CommandStarted {
+                command,
+                package_count,
+            } => json!({
+                "event": "commandStarted",
+                "command": command,
+                "packageCount": package_count,
+            }),
+            Event::CommandFinished { command, duration } => json!({
+                "event": "commandFinished",
+                "command": command,
+                "durationMs": duration.as_millis() as u64,
+            }),
+            Event::PackageStarted { name } => json!({
+                "event": "packageStarted",
+                "package": name,
+            }),
+            Event::PackageFinished {
+                name,
+                success,
+                duration,
+            } => json!({
+                "event": "packageFinished",
+                "package": name,
+                "success": success,
+                "durationMs": duration.as_millis() as u64,
+            }),
+            Event::PackageRetry {
+                name,
+                attempt,
+                max_attempts,
+                delay,
+            } => json!({
+                "event": "packageRetry",
+                "package": name,
+                "attempt": attempt,
+                "maxAttempts": max_attempts,
+                "delayMs": delay.as_millis() as u64,
+            }),
+            Event::PackageSkipped { name, reason } => json!({
+                "event": "packageSkipped",
+                "package": name,
+                "reason": reason,
+            }),
+            Event::PackageAborted {
+                name,
+                reason,
+                duration,
+            } => json!({
+                "event": "packageAborted",
+                "package": name,
+                "reason": reason,
+                "durationMs": duration.as_millis() as u64,
+            }),
+            Event::PackageOutput {
+                name,
+                line,
+                is_stderr,
+            } => json!({
+                "event": "packageOutput",
+                "package": name,
+                "line": line,
+                "stream": if *is_stderr { "stderr" } else { "stdout" },
+            }),
+            Event::Progress {
+                completed,
+                total,
+                message,
+            } => json!({
+                "event": "progress",
+                "completed": completed,
+                "total": total,
+                "message": message,
+            }),
+            Event::Warning(message) => json!({
+                "event": "warning",
+                "message": message,
+            }),
+            Event::Info(message) => json!({
+                "event": "info",
+                "message": message,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_to_json_package_lifecycle() {
+        let finished = Event::PackageFinished {
+            name: "core".to_string(),
+            success: false,
+            duration: Duration::from_millis(1500),
+        };
+        assert_eq!(
+            finished.to_json(),
+            serde_json::json!({
+                "event": "packageFinished",
+                "package": "core",
+                "success": false,
+                "durationMs": 1500,
+            })
+        );
+
+        let output = Event::PackageOutput {
+            name: "core".to_string(),
+            line: "warning: deprecated".to_string(),
+            is_stderr: true,
+        };
+        assert_eq!(output.to_json()["stream"], "stderr");
+        assert_eq!(output.to_json()["line"], "warning: deprecated");
+    }
+
+    #[test]
+    fn test_event_to_json_messages() {
+        let warning = Event::Warning("slow package".to_string()).to_json();
+        assert_eq!(warning["event"], "warning");
+        assert_eq!(warning["message"], "slow package");
+    }
+}
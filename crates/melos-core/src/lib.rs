@@ -1,7 +1,61 @@
+//! Core library for melos-rs: config parsing, package discovery, filtering
+//! and running commands across the packages of a Dart/Flutter workspace.
+//!
+//! Loading a workspace and running commands print nothing: warnings are
+//! collected in [`Workspace::warnings`], and commands report progress and
+//! output as [`Event`]s sent on a channel, so other tools can embed the crate
+//! and render results themselves.
+//!
+//! ```
+//! use melos_core::events::Event;
+//! use melos_core::{CommandSpec, Workspace, run_command};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! # let dir = tempfile::tempdir()?;
+//! # std::fs::write(dir.path().join("melos.yaml"), "name: demo\npackages:\n  - packages/*\n")?;
+//! # for name in ["app", "core"] {
+//! #     let pkg = dir.path().join("packages").join(name);
+//! #     std::fs::create_dir_all(&pkg)?;
+//! #     std::fs::write(pkg.join("pubspec.yaml"), format!("name: {name}\nversion: 1.0.0\n"))?;
+//! # }
+//! # let root = dir.path();
+//! let workspace = Workspace::find_and_load_from(root, None)?;
+//! assert_eq!(workspace.packages.len(), 2);
+//!
+//! let mut spec = CommandSpec::new("echo hello");
+//! spec.filters.scope = Some(vec!["core".to_string()]);
+//!
+//! let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+//! let runtime = tokio::runtime::Runtime::new()?;
+//! let results = runtime.block_on(run_command(&workspace, &spec, Some(&tx)))?;
+//! drop(tx);
+//!
+//! assert_eq!(results.results, vec![("core".to_string(), true)]);
+//! let mut output = Vec::new();
+//! while let Ok(event) = rx.try_recv() {
+//!     if let Event::PackageOutput { name, line, .. } = event {
+//!         output.push(format!("[{name}] {line}"));
+//!     }
+//! }
+//! assert_eq!(output, vec!["[core] hello"]);
+//! # Ok(())
+//! # }
+//! ```
+
 pub mod commands;
 pub mod config;
+pub mod dotenv;
 pub mod events;
+pub mod lock;
 pub mod package;
+pub mod redact;
 pub mod runner;
 pub mod watcher;
 pub mod workspace;
+
+pub use commands::PackageResults;
+pub use commands::exec::{CommandSpec, run_command};
+pub use config::filter::PackageFilters;
+pub use events::Event;
+pub use package::Package;
+pub use workspace::Workspace;
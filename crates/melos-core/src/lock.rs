@@ -0,0 +1,166 @@
+//! Advisory workspace lock that keeps mutating commands from racing.
+//!
+//! Commands that rewrite workspace files (bootstrap, clean, version, publish)
+//! hold an exclusive OS file lock on [`LOCK_PATH`] for their whole run. The
+//! lock belongs to the open file handle, so it is released when the guard is
+//! dropped and by the OS when the process exits or panics.
+//!
+//! The holder exports [`LOCK_HELD_ENV`] so melos-rs processes it spawns
+//! (e.g. a hook running `melos-rs clean`) run under its lock instead of
+//! failing on it.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+/// Lock file location, relative to the workspace root.
+pub const LOCK_PATH: &str = ".dart_tool/melos.lock";
+
+/// Environment variable naming the lock file held by an ancestor process.
+pub const LOCK_HELD_ENV: &str = "MELOS_LOCK_HELD";
+
+/// Guard holding the workspace lock until it is dropped.
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl WorkspaceLock {
+    /// Take the lock for the workspace at `root` without waiting.
+    ///
+    /// Fails immediately when another process holds it. The holder's PID is
+    /// written into the file so the error can name it.
+    pub fn acquire(root: &Path) -> Result<Self> {
+        let path = root.join(LOCK_PATH);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = match holder.trim() {
+                    "" => String::new(),
+                    pid => format!(" (pid {pid})"),
+                };
+                bail!(
+                    "Another melos-rs process{} is running in this workspace (lock held on {}). \
+                     Wait for it to finish, or pass --no-lock to skip locking.",
+                    holder,
+                    path.display()
+                );
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+
+        // Best effort: a stale or missing PID only makes the error less specific
+        let _ = file
+            .set_len(0)
+            .and_then(|()| file.seek(SeekFrom::Start(0)))
+            .and_then(|_| writeln!(file, "{}", std::process::id()));
+
+        Ok(Self { _file: file, path })
+    }
+
+    /// Take the lock for `root` unless an ancestor process already holds it.
+    ///
+    /// Returns `None` when [`LOCK_HELD_ENV`] names this workspace's lock file.
+    pub fn acquire_unless_inherited(root: &Path) -> Result<Option<Self>> {
+        if is_inherited(root) {
+            return Ok(None);
+        }
+        Self::acquire(root).map(Some)
+    }
+
+    /// Export [`LOCK_HELD_ENV`] so child processes inherit the lock.
+    ///
+    /// Call before the command spawns tasks or child processes: it mutates
+    /// the process environment.
+    pub fn export_to_children(&self) {
+        // SAFETY: callers export before any task that could read the
+        // environment concurrently is spawned (see above).
+        unsafe { std::env::set_var(LOCK_HELD_ENV, &self.path) };
+    }
+
+    /// Path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Whether [`LOCK_HELD_ENV`] names the lock file of the workspace at `root`.
+fn is_inherited(root: &Path) -> bool {
+    let Some(held) = std::env::var_os(LOCK_HELD_ENV) else {
+        return false;
+    };
+    let path = root.join(LOCK_PATH);
+    // Compare canonical paths: the holder and the child may spell the root differently
+    match (Path::new(&held).canonicalize(), path.canonicalize()) {
+        (Ok(held), Ok(path)) => held == path,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_fails_while_held_and_succeeds_after_drop() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let lock = WorkspaceLock::acquire(dir.path()).unwrap();
+        assert_eq!(lock.path(), dir.path().join(LOCK_PATH));
+
+        let err = WorkspaceLock::acquire(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("Another melos-rs process"));
+        assert!(err.contains(&format!("pid {}", std::process::id())));
+        assert!(err.contains("--no-lock"));
+
+        drop(lock);
+        assert!(WorkspaceLock::acquire(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_acquire_unless_inherited_skips_lock_held_by_ancestor() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let lock = WorkspaceLock::acquire(dir.path()).unwrap();
+        let _other_lock = WorkspaceLock::acquire(other.path()).unwrap();
+        assert!(!is_inherited(dir.path()));
+
+        // Only this test sets the variable
+        lock.export_to_children();
+        assert!(is_inherited(dir.path()));
+        assert!(
+            WorkspaceLock::acquire_unless_inherited(dir.path())
+                .unwrap()
+                .is_none()
+        );
+        // Another workspace's lock is still taken
+        assert!(WorkspaceLock::acquire_unless_inherited(other.path()).is_err());
+        // SAFETY: test-only; no other test reads or writes this variable
+        unsafe { std::env::remove_var(LOCK_HELD_ENV) };
+    }
+
+    #[test]
+    fn test_acquire_creates_dart_tool() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let _lock = WorkspaceLock::acquire(dir.path()).unwrap();
+        assert!(dir.path().join(".dart_tool").is_dir());
+    }
+}
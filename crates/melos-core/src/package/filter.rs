@@ -1,10 +1,12 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 
 use crate::config::filter::PackageFilters;
 use crate::package::Package;
+use crate::package::graph::DependencyGraph;
+use crate::watcher::find_owning_package;
 
 /// Apply package filters without category definitions.
 ///
@@ -70,7 +72,7 @@ pub fn apply_filters_with_categories(
 
     // Resolve category filter into a set of matching package names
     let category_names: Option<HashSet<String>> =
-        resolve_category_packages(packages, filters, categories);
+        resolve_category_packages(packages, filters, categories)?;
 
     // First pass: apply direct filters
     let mut matched: Vec<Package> = packages
@@ -85,35 +87,133 @@ pub fn apply_filters_with_categories(
         .collect();
 
     // Git diff filter: only keep packages with changed files since the ref
-    if let Some(ref diff_ref) = filters.diff
-        && let Some(root) = workspace_root
+    if let Some(root) = workspace_root
+        && let Some(diff_ref) = resolve_diff_ref(filters, root)?
     {
-        let changed = changed_packages_since(root, packages, diff_ref)?;
+        let changed = changed_packages_since(root, packages, &diff_ref)?;
         matched.retain(|pkg| changed.contains(&pkg.name));
     }
 
-    // Expand with transitive dependencies if requested
-    if filters.include_dependencies {
-        matched = expand_with_dependencies(&matched, packages);
+    // Expand along the dependency graph if requested. Both closures start from
+    // the directly matched set, so combining the flags pulls in what the
+    // selection needs and what needs the selection, but not unrelated dependents
+    // of those dependencies.
+    if filters.include_dependencies || filters.include_dependents {
+        let mut selected: HashSet<String> = matched.iter().map(|p| p.name.clone()).collect();
+        if filters.include_dependencies && filters.exclude_dependencies {
+            selected.extend(direct_dependencies(&matched, packages));
+        } else if filters.include_dependencies {
+            selected.extend(transitive_dependencies(&matched, packages));
+        }
+        if filters.include_dependents {
+            selected.extend(transitive_dependents(&matched, packages));
+        }
+        matched = packages
+            .iter()
+            .filter(|p| selected.contains(&p.name))
+            .cloned()
+            .collect();
     }
 
-    // Expand with transitive dependents if requested
-    if filters.include_dependents {
-        matched = expand_with_dependents(&matched, packages);
+    Ok(matched)
+}
+
+/// Whether a package name matches a scope/ignore/category glob.
+///
+/// Brace groups are expanded first (see [`expand_braces`]); the name must
+/// match one of the resulting patterns. A pattern that is not a valid glob
+/// falls back to a substring match.
+fn name_matches_glob(name: &str, pattern: &str) -> bool {
+    expand_braces(pattern).iter().any(|pattern| {
+        glob::Pattern::new(pattern)
+            .map(|p| p.matches(name))
+            .unwrap_or_else(|_| name.contains(pattern.as_str()))
+    })
+}
+
+/// Expand shell-style brace groups into the patterns they stand for.
+///
+/// `app_{ios,android}` becomes `app_ios` and `app_android`. Groups may nest
+/// (`{a,b{1,2}}`) and alternatives may be empty (`app{,_test}` also yields
+/// `app`). As in the shell, a group without a comma or without a closing
+/// brace is kept literally, and `\{`, `\}`, `\,` and `\\` stand for the
+/// character itself.
+pub fn expand_braces(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '{' => {
+                if let Some((close, commas)) = find_brace_group(&chars, i)
+                    && !commas.is_empty()
+                {
+                    let prefix: String = chars[..i].iter().collect();
+                    let suffix: String = chars[close + 1..].iter().collect();
+                    let mut bounds = vec![i];
+                    bounds.extend(&commas);
+                    bounds.push(close);
+                    return bounds
+                        .windows(2)
+                        .flat_map(|w| {
+                            let alternative: String = chars[w[0] + 1..w[1]].iter().collect();
+                            expand_braces(&format!("{prefix}{alternative}{suffix}"))
+                        })
+                        .collect();
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
     }
+    vec![unescape_braces(&chars)]
+}
 
-    Ok(matched)
+/// Find the `}` closing the group opened at `open`, and the positions of the
+/// commas separating its top-level alternatives.
+fn find_brace_group(chars: &[char], open: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    let mut i = open + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some((i, commas)),
+            '}' => depth -= 1,
+            ',' if depth == 0 => commas.push(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Drop the backslash from escaped brace-syntax characters.
+fn unescape_braces(chars: &[char]) -> String {
+    let mut out = String::with_capacity(chars.len());
+    let mut iter = chars.iter().peekable();
+    while let Some(&c) = iter.next() {
+        if c == '\\'
+            && let Some(&&next) = iter.peek()
+            && matches!(next, '{' | '}' | ',' | '\\')
+        {
+            out.push(next);
+            iter.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
 }
 
 /// Check if a single package matches all the given direct filters (no git/transitive expansion)
 fn matches_filters(pkg: &Package, filters: &PackageFilters) -> bool {
     // Scope filter: package name must match at least one scope glob
     if let Some(ref scopes) = filters.scope {
-        let matches_any = scopes.iter().any(|pattern| {
-            glob::Pattern::new(pattern)
-                .map(|p| p.matches(&pkg.name))
-                .unwrap_or_else(|_| pkg.name.contains(pattern))
-        });
+        let matches_any = scopes
+            .iter()
+            .any(|pattern| name_matches_glob(&pkg.name, pattern));
         if !matches_any {
             return false;
         }
@@ -121,11 +221,9 @@ fn matches_filters(pkg: &Package, filters: &PackageFilters) -> bool {
 
     // Ignore filter: package name must NOT match any ignore glob
     if let Some(ref ignores) = filters.ignore {
-        let matches_any = ignores.iter().any(|pattern| {
-            glob::Pattern::new(pattern)
-                .map(|p| p.matches(&pkg.name))
-                .unwrap_or_else(|_| pkg.name.contains(pattern))
-        });
+        let matches_any = ignores
+            .iter()
+            .any(|pattern| name_matches_glob(&pkg.name, pattern));
         if matches_any {
             return false;
         }
@@ -138,16 +236,16 @@ fn matches_filters(pkg: &Package, filters: &PackageFilters) -> bool {
         return false;
     }
 
-    // Directory exists filter
-    if let Some(ref dir) = filters.dir_exists
-        && !pkg.dir_exists(dir)
+    // Directory exists filter: ALL listed directories must exist
+    if let Some(ref dirs) = filters.dir_exists
+        && !dirs.iter().all(|dir| pkg.dir_exists(dir))
     {
         return false;
     }
 
-    // File exists filter
-    if let Some(ref file) = filters.file_exists
-        && !pkg.file_exists(file)
+    // File exists filter: ALL listed files must exist
+    if let Some(ref files) = filters.file_exists
+        && !files.iter().all(|file| pkg.file_exists(file))
     {
         return false;
     }
@@ -194,34 +292,40 @@ fn matches_filters(pkg: &Package, filters: &PackageFilters) -> bool {
 ///
 /// Returns `None` if no category filter is set (meaning no category restriction).
 /// Returns `Some(set)` with matching package names if a category filter is active.
+/// Errors if a requested category is not defined in `categories`.
 fn resolve_category_packages(
     packages: &[Package],
     filters: &PackageFilters,
     categories: &HashMap<String, Vec<String>>,
-) -> Option<HashSet<String>> {
-    let category_filter = filters.category.as_ref()?;
+) -> Result<Option<HashSet<String>>> {
+    let Some(category_filter) = filters.category.as_ref() else {
+        return Ok(None);
+    };
     if category_filter.is_empty() {
-        return None;
+        return Ok(None);
     }
 
     let mut matching = HashSet::new();
 
     for requested_category in category_filter {
-        if let Some(patterns) = categories.get(requested_category) {
-            for pkg in packages {
-                let in_category = patterns.iter().any(|pattern| {
-                    glob::Pattern::new(pattern)
-                        .map(|p| p.matches(&pkg.name))
-                        .unwrap_or_else(|_| pkg.name.contains(pattern))
-                });
-                if in_category {
-                    matching.insert(pkg.name.clone());
-                }
+        let Some(patterns) = categories.get(requested_category) else {
+            bail!(
+                "Category '{}' is not defined in `categories`. Available categories: {}",
+                requested_category,
+                crate::config::available_categories(categories)
+            );
+        };
+        for pkg in packages {
+            let in_category = patterns
+                .iter()
+                .any(|pattern| name_matches_glob(&pkg.name, pattern));
+            if in_category {
+                matching.insert(pkg.name.clone());
             }
         }
     }
 
-    Some(matching)
+    Ok(Some(matching))
 }
 
 /// Topological sort of packages by their dependency relationships.
@@ -233,184 +337,222 @@ fn resolve_category_packages(
 /// Uses Kahn's algorithm. If there are cycles, the cyclic packages are appended
 /// at the end (not silently dropped).
 pub fn topological_sort(packages: &[Package]) -> Vec<Package> {
-    let known: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
     let pkg_map: HashMap<&str, &Package> = packages.iter().map(|p| (p.name.as_str(), p)).collect();
+    DependencyGraph::new(packages)
+        .topo_order()
+        .into_iter()
+        .filter_map(|name| pkg_map.get(name).map(|&pkg| pkg.clone()))
+        .collect()
+}
 
-    // Build adjacency list and in-degree map
-    // Edge direction: dependency -> dependent (so deps come first in sort)
-    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
-    let mut in_degree: HashMap<&str, usize> = HashMap::new();
-
-    for pkg in packages {
-        dependents.entry(pkg.name.as_str()).or_default();
-        in_degree.entry(pkg.name.as_str()).or_insert(0);
-
-        for dep in pkg.dependencies.iter().chain(pkg.dev_dependencies.iter()) {
-            if known.contains(dep.as_str()) {
-                dependents
-                    .entry(dep.as_str())
-                    .or_default()
-                    .push(pkg.name.as_str());
-                *in_degree.entry(pkg.name.as_str()).or_insert(0) += 1;
+/// Find a dependency cycle among `packages`, considering only dependencies on
+/// other packages in the list.
+///
+/// Returns the cycle as a path that starts and ends with the same package
+/// (e.g. `["a", "b", "a"]`), or `None` if the graph is acyclic. Packages are
+/// visited in name order so the reported cycle is deterministic.
+pub fn find_dependency_cycle(packages: &[Package]) -> Option<Vec<String>> {
+    fn visit<'a>(
+        node: &'a str,
+        pkg_map: &HashMap<&'a str, &'a Package>,
+        state: &mut HashMap<&'a str, bool>,
+        stack: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        // `false` = on the current DFS path, `true` = fully explored
+        match state.get(node) {
+            Some(true) => return None,
+            Some(false) => {
+                let start = stack.iter().position(|&n| n == node).unwrap_or(0);
+                let mut cycle: Vec<String> = stack[start..].iter().map(|n| n.to_string()).collect();
+                cycle.push(node.to_string());
+                return Some(cycle);
             }
+            None => {}
         }
-    }
 
-    // Kahn's algorithm
-    let mut queue: VecDeque<&str> = in_degree
-        .iter()
-        .filter(|&(_, &deg)| deg == 0)
-        .map(|(&name, _)| name)
-        .collect();
-
-    // Sort the initial queue for deterministic output
-    let mut sorted_queue: Vec<&str> = queue.drain(..).collect();
-    sorted_queue.sort();
-    queue.extend(sorted_queue);
-
-    let mut result: Vec<Package> = Vec::with_capacity(packages.len());
-
-    while let Some(node) = queue.pop_front() {
-        if let Some(&pkg) = pkg_map.get(node) {
-            result.push(pkg.clone());
-        }
-
-        // Collect and sort neighbors for deterministic output
-        if let Some(neighbors) = dependents.get(node) {
-            let mut ready = Vec::new();
-            for &neighbor in neighbors {
-                if let Some(deg) = in_degree.get_mut(neighbor) {
-                    *deg -= 1;
-                    if *deg == 0 {
-                        ready.push(neighbor);
-                    }
+        state.insert(node, false);
+        stack.push(node);
+        if let Some(pkg) = pkg_map.get(node) {
+            let mut deps: Vec<&str> = pkg
+                .dependencies
+                .iter()
+                .chain(pkg.dev_dependencies.iter())
+                .map(String::as_str)
+                .filter(|dep| pkg_map.contains_key(dep))
+                .collect();
+            deps.sort();
+            for dep in deps {
+                if let Some(cycle) = visit(dep, pkg_map, state, stack) {
+                    return Some(cycle);
                 }
             }
-            ready.sort();
-            queue.extend(ready);
         }
+        stack.pop();
+        state.insert(node, true);
+        None
     }
 
-    // Append any remaining packages (part of cycles) to avoid dropping them
-    if result.len() < packages.len() {
-        let in_result: HashSet<String> = result.iter().map(|p| p.name.clone()).collect();
-        let remaining: Vec<Package> = packages
-            .iter()
-            .filter(|p| !in_result.contains(&p.name))
-            .cloned()
-            .collect();
-        result.extend(remaining);
+    let pkg_map: HashMap<&str, &Package> = packages.iter().map(|p| (p.name.as_str(), p)).collect();
+    let mut names: Vec<&str> = pkg_map.keys().copied().collect();
+    names.sort();
+
+    let mut state = HashMap::new();
+    let mut stack = Vec::new();
+    names
+        .into_iter()
+        .find_map(|name| visit(name, &pkg_map, &mut state, &mut stack))
+}
+
+/// Resolve the git ref used as the diff baseline.
+///
+/// With `since_latest_tag`, the most recent tag wins and `diff` is only the
+/// fallback when the repository has no tags. Errors if neither is available.
+pub fn resolve_diff_ref(filters: &PackageFilters, workspace_root: &Path) -> Result<Option<String>> {
+    if !filters.since_latest_tag {
+        return Ok(filters.diff.clone());
+    }
+    match crate::commands::version::find_latest_git_tag(workspace_root) {
+        Some(tag) => Ok(Some(tag)),
+        None => match filters.diff {
+            Some(ref fallback) => Ok(Some(fallback.clone())),
+            None => anyhow::bail!(
+                "No git tags found in '{}' to use as the diff baseline. \
+                 Create a release tag, or pass --diff <ref> (e.g. --diff HEAD~1) as a fallback.",
+                workspace_root.display()
+            ),
+        },
     }
+}
 
-    result
+/// Run a git command in `dir` and return its stdout lines.
+fn git_lines(dir: &Path, args: &[&str]) -> Result<Vec<String>> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args[0]))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git {} failed: {}", args[0], stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
 }
 
 /// Determine which packages have changed files since a git ref.
 ///
-/// Runs `git diff --name-only <ref>` and maps changed file paths to their
-/// containing packages.
+/// Compares `git_ref` against the working tree, so uncommitted and untracked
+/// changes count too. Each changed file is attributed to the package with
+/// the longest matching path (nested packages win over their parents);
+/// files outside every package are ignored.
 fn changed_packages_since(
     workspace_root: &Path,
     packages: &[Package],
     git_ref: &str,
 ) -> Result<HashSet<String>> {
-    let output = std::process::Command::new("git")
-        .args(["diff", "--name-only", git_ref])
-        .current_dir(workspace_root)
-        .output()
-        .context("Failed to run git diff")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("git diff failed: {}", stderr.trim());
+    let commit = format!("{}^{{commit}}", git_ref);
+    if git_lines(
+        workspace_root,
+        &["rev-parse", "--verify", "--quiet", &commit],
+    )
+    .is_err()
+    {
+        anyhow::bail!(
+            "Invalid --diff ref '{}': not a commit in the repository at '{}'",
+            git_ref,
+            workspace_root.display()
+        );
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let changed_files: Vec<&str> = stdout.lines().collect();
-
-    let mut changed_packages = HashSet::new();
-
-    for pkg in packages {
-        // Get the package path relative to workspace root
-        let rel_path = pkg.path.strip_prefix(workspace_root).unwrap_or(&pkg.path);
-        let rel_str = rel_path.to_string_lossy();
-
-        // A package is "changed" if any changed file is under its directory
-        for file in &changed_files {
-            if file.starts_with(rel_str.as_ref()) {
-                changed_packages.insert(pkg.name.clone());
-                break;
-            }
-        }
-    }
+    // `--relative` makes paths relative to (and limited to) the workspace root
+    let mut changed_files = git_lines(
+        workspace_root,
+        &["diff", "--name-only", "--relative", git_ref],
+    )?;
+    changed_files.extend(git_lines(
+        workspace_root,
+        &["ls-files", "--others", "--exclude-standard"],
+    )?);
+
+    let root = workspace_root
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_root.to_path_buf());
+    let package_paths: Vec<(std::path::PathBuf, String)> = packages
+        .iter()
+        .map(|pkg| {
+            let path = pkg.path.canonicalize().unwrap_or_else(|_| pkg.path.clone());
+            (path, pkg.name.clone())
+        })
+        .collect();
 
-    Ok(changed_packages)
+    Ok(changed_files
+        .iter()
+        .filter_map(|file| find_owning_package(&root.join(file), &package_paths))
+        .collect())
 }
 
-/// Expand a matched set of packages to also include their transitive dependencies.
+/// Narrow an already filtered package list to the packages in `changed`.
 ///
-/// For each matched package, walks its `dependencies` and `dev_dependencies` to find
-/// other workspace packages that are depended on, recursively.
-fn expand_with_dependencies(matched: &[Package], all_packages: &[Package]) -> Vec<Package> {
-    let all_by_name: std::collections::HashMap<&str, &Package> =
-        all_packages.iter().map(|p| (p.name.as_str(), p)).collect();
-
-    let mut result_names: HashSet<String> = matched.iter().map(|p| p.name.clone()).collect();
-    let mut queue: VecDeque<String> = matched.iter().map(|p| p.name.clone()).collect();
-
-    while let Some(name) = queue.pop_front() {
-        if let Some(pkg) = all_by_name.get(name.as_str()) {
-            for dep in pkg.dependencies.iter().chain(pkg.dev_dependencies.iter()) {
-                if all_by_name.contains_key(dep.as_str()) && result_names.insert(dep.clone()) {
-                    queue.push_back(dep.clone());
-                }
-            }
-        }
-    }
-
-    all_packages
+/// With `include_dependents`, packages in `selected` that transitively depend
+/// on a changed package are kept as well. Order of `selected` is preserved.
+/// Used by watch mode to re-run only what a change affects.
+pub fn retain_affected(
+    selected: Vec<Package>,
+    changed: &HashSet<String>,
+    include_dependents: bool,
+) -> Vec<Package> {
+    let mut keep: HashSet<String> = selected
         .iter()
-        .filter(|p| result_names.contains(&p.name))
-        .cloned()
+        .filter(|p| changed.contains(&p.name))
+        .map(|p| p.name.clone())
+        .collect();
+    if include_dependents {
+        let seeds: Vec<Package> = selected
+            .iter()
+            .filter(|p| keep.contains(&p.name))
+            .cloned()
+            .collect();
+        keep = transitive_dependents(&seeds, &selected);
+    }
+    selected
+        .into_iter()
+        .filter(|p| keep.contains(&p.name))
         .collect()
 }
 
-/// Expand a matched set of packages to also include their transitive dependents.
+/// Names of the matched packages plus their transitive workspace dependencies.
 ///
-/// For each matched package, finds all workspace packages that (transitively)
-/// depend on it.
-fn expand_with_dependents(matched: &[Package], all_packages: &[Package]) -> Vec<Package> {
-    let mut result_names: HashSet<String> = matched.iter().map(|p| p.name.clone()).collect();
-    let mut changed = true;
-
-    // Fixed-point iteration: keep adding dependents until no new ones are found
-    while changed {
-        changed = false;
-        for pkg in all_packages {
-            if result_names.contains(&pkg.name) {
-                continue;
-            }
-            // If this package depends on any package in our result set, include it
-            let depends_on_matched = pkg
-                .dependencies
-                .iter()
-                .chain(pkg.dev_dependencies.iter())
-                .any(|dep| result_names.contains(dep));
-
-            if depends_on_matched {
-                result_names.insert(pkg.name.clone());
-                changed = true;
-            }
-        }
-    }
+/// Follows `dependencies` and `dev_dependencies`; each package is visited
+/// once, so diamonds and cycles terminate without duplicates.
+fn transitive_dependencies(matched: &[Package], all_packages: &[Package]) -> HashSet<String> {
+    DependencyGraph::new(all_packages)
+        .transitive_dependencies(matched.iter().map(|p| p.name.as_str()))
+}
 
-    all_packages
+/// Names of the workspace packages the matched packages depend on directly,
+/// via `dependencies` or `dev_dependencies`.
+fn direct_dependencies(matched: &[Package], all_packages: &[Package]) -> HashSet<String> {
+    let graph = DependencyGraph::new(all_packages);
+    matched
         .iter()
-        .filter(|p| result_names.contains(&p.name))
-        .cloned()
+        .flat_map(|p| graph.dependencies_of(&p.name))
+        .map(String::from)
         .collect()
 }
 
+/// Names of the matched packages plus every workspace package that
+/// (transitively) depends on one of them via `dependencies` or
+/// `dev_dependencies`.
+fn transitive_dependents(matched: &[Package], all_packages: &[Package]) -> HashSet<String> {
+    DependencyGraph::new(all_packages)
+        .transitive_dependents(matched.iter().map(|p| p.name.as_str()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -432,6 +574,7 @@ mod tests {
             dependencies: deps.into_iter().map(String::from).collect(),
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -446,6 +589,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
@@ -522,6 +666,65 @@ mod tests {
         assert_eq!(result[1].name, "core_lib");
     }
 
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("app_{ios,android}"),
+            vec!["app_ios", "app_android"]
+        );
+        assert_eq!(expand_braces("{app_*,*_app}"), vec!["app_*", "*_app"]);
+        assert_eq!(expand_braces("core"), vec!["core"]);
+        // Nested groups and several groups in one pattern
+        assert_eq!(expand_braces("{a,b{1,2}}_x"), vec!["a_x", "b1_x", "b2_x"]);
+        assert_eq!(
+            expand_braces("{a,b}_{1,2}"),
+            vec!["a_1", "a_2", "b_1", "b_2"]
+        );
+        // Empty alternatives
+        assert_eq!(expand_braces("app{,_test}"), vec!["app", "app_test"]);
+        assert_eq!(expand_braces("{,}"), vec!["", ""]);
+    }
+
+    #[test]
+    fn test_expand_braces_literal_groups() {
+        // Escaped braces and commas are kept as characters
+        assert_eq!(expand_braces(r"\{a,b\}"), vec!["{a,b}"]);
+        assert_eq!(expand_braces(r"{a\,b,c}"), vec!["a,b", "c"]);
+        // No comma, or no closing brace: not a group
+        assert_eq!(expand_braces("{app}"), vec!["{app}"]);
+        assert_eq!(expand_braces("{}"), vec!["{}"]);
+        assert_eq!(expand_braces("app_{ios"), vec!["app_{ios"]);
+        assert_eq!(expand_braces("{x}_{a,b}"), vec!["{x}_a", "{x}_b"]);
+    }
+
+    #[test]
+    fn test_scope_and_ignore_expand_braces() {
+        let packages = vec![
+            make_package("app_android", false, vec![]),
+            make_package("app_ios", false, vec![]),
+            make_package("app_web", false, vec![]),
+            make_package("core", false, vec![]),
+            make_package("demo_app", false, vec![]),
+        ];
+
+        let filters = PackageFilters {
+            scope: Some(vec!["app_{ios,android}".to_string()]),
+            ..Default::default()
+        };
+        let result = apply_filters(&packages, &filters, None).unwrap();
+        let names: Vec<&str> = result.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app_android", "app_ios"]);
+
+        let filters = PackageFilters {
+            scope: Some(vec!["{app_*,*_app}".to_string()]),
+            ignore: Some(vec!["{*_web,demo_*}".to_string()]),
+            ..Default::default()
+        };
+        let result = apply_filters(&packages, &filters, None).unwrap();
+        let names: Vec<&str> = result.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app_android", "app_ios"]);
+    }
+
     #[test]
     fn test_ignore_glob_filter() {
         let packages = vec![
@@ -611,6 +814,7 @@ mod tests {
                 dependencies: vec!["core".to_string()],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -622,6 +826,7 @@ mod tests {
                 dependencies: vec!["utils".to_string()],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -633,6 +838,7 @@ mod tests {
                 dependencies: vec![],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -644,6 +850,7 @@ mod tests {
                 dependencies: vec![],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
         ];
@@ -676,6 +883,7 @@ mod tests {
                 dependencies: vec!["core".to_string()],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -687,6 +895,7 @@ mod tests {
                 dependencies: vec!["utils".to_string()],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -698,6 +907,7 @@ mod tests {
                 dependencies: vec![],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
             Package {
@@ -709,6 +919,7 @@ mod tests {
                 dependencies: vec![],
                 dev_dependencies: vec![],
                 dependency_versions: HashMap::new(),
+                dependency_kinds: HashMap::new(),
                 resolution: None,
             },
         ];
@@ -728,6 +939,117 @@ mod tests {
         assert!(!names.contains(&"unrelated"));
     }
 
+    /// Diamond: `app` -> (`left`, `right`) -> `base`, plus `other` -> `base`.
+    fn diamond_packages() -> Vec<Package> {
+        vec![
+            make_package("app", false, vec!["left", "right"]),
+            make_package("base", false, vec![]),
+            make_package("left", false, vec!["base"]),
+            make_package("other", false, vec!["base"]),
+            make_package("right", false, vec!["base"]),
+        ]
+    }
+
+    fn filtered_names(packages: &[Package], filters: &PackageFilters) -> Vec<String> {
+        apply_filters(packages, filters, None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_include_dependencies_diamond_no_duplicates() {
+        let filters = PackageFilters {
+            scope: Some(vec!["app".to_string()]),
+            include_dependencies: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_names(&diamond_packages(), &filters),
+            vec!["app", "base", "left", "right"]
+        );
+    }
+
+    #[test]
+    fn test_include_dependents_diamond_no_duplicates() {
+        let filters = PackageFilters {
+            scope: Some(vec!["base".to_string()]),
+            include_dependents: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_names(&diamond_packages(), &filters),
+            vec!["app", "base", "left", "other", "right"]
+        );
+    }
+
+    #[test]
+    fn test_exclude_dependencies_keeps_only_direct_dependencies() {
+        // app -> left/right -> base: base is only reached transitively
+        let filters = PackageFilters {
+            scope: Some(vec!["app".to_string()]),
+            include_dependencies: true,
+            exclude_dependencies: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_names(&diamond_packages(), &filters),
+            vec!["app", "left", "right"]
+        );
+
+        // Matched packages stay even when another matched package depends on
+        // them, and dependents are still added in full
+        let filters = PackageFilters {
+            scope: Some(vec!["left".to_string(), "base".to_string()]),
+            include_dependencies: true,
+            exclude_dependencies: true,
+            include_dependents: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_names(&diamond_packages(), &filters),
+            vec!["app", "base", "left", "other", "right"]
+        );
+
+        // Without include_dependencies there is nothing to prune
+        let filters = PackageFilters {
+            scope: Some(vec!["app".to_string()]),
+            exclude_dependencies: true,
+            ..Default::default()
+        };
+        assert_eq!(filtered_names(&diamond_packages(), &filters), vec!["app"]);
+    }
+
+    #[test]
+    fn test_include_dependencies_and_dependents_from_selection_only() {
+        // Dependents are those of `left` itself, not of its dependency `base`,
+        // so `right` and `other` stay out.
+        let filters = PackageFilters {
+            scope: Some(vec!["left".to_string()]),
+            include_dependencies: true,
+            include_dependents: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_names(&diamond_packages(), &filters),
+            vec!["app", "base", "left"]
+        );
+    }
+
+    #[test]
+    fn test_retain_affected() {
+        let changed: HashSet<String> = ["left".to_string(), "missing".to_string()].into();
+
+        let only = retain_affected(diamond_packages(), &changed, false);
+        let names: Vec<&str> = only.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["left"]);
+
+        let with_dependents = retain_affected(diamond_packages(), &changed, true);
+        let names: Vec<&str> = with_dependents.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "left"]);
+    }
+
     #[test]
     fn test_category_filter() {
         let packages = vec![
@@ -765,13 +1087,27 @@ mod tests {
         assert_eq!(result[0].name, "core_lib");
         assert_eq!(result[1].name, "utils");
 
-        // Filter to nonexistent category -> empty result
+        // Multiple categories union their packages
         let filters = PackageFilters {
-            category: Some(vec!["nonexistent".to_string()]),
+            category: Some(vec!["apps".to_string(), "libraries".to_string()]),
             ..Default::default()
         };
         let result = apply_filters_with_categories(&packages, &filters, None, &categories).unwrap();
-        assert_eq!(result.len(), 0);
+        assert_eq!(result.len(), packages.len());
+
+        // Filter to nonexistent category -> error listing the defined ones
+        let filters = PackageFilters {
+            category: Some(vec!["nonexistent".to_string()]),
+            ..Default::default()
+        };
+        let err = apply_filters_with_categories(&packages, &filters, None, &categories)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Category 'nonexistent' is not defined in `categories`. \
+             Available categories: apps, libraries"
+        );
     }
 
     #[test]
@@ -822,6 +1158,35 @@ mod tests {
         assert!(cyclic.contains(&"b"));
     }
 
+    #[test]
+    fn test_find_dependency_cycle_none() {
+        let packages = vec![
+            make_package("app", false, vec!["core", "http"]),
+            make_package("core", false, vec!["utils"]),
+            make_package("utils", false, vec![]),
+        ];
+        assert_eq!(find_dependency_cycle(&packages), None);
+    }
+
+    #[test]
+    fn test_find_dependency_cycle_names_cycle() {
+        let packages = vec![
+            make_package("a", false, vec!["b"]),
+            make_package("b", false, vec!["c"]),
+            make_package("c", false, vec!["a"]),
+            make_package("d", false, vec!["a"]),
+        ];
+        assert_eq!(
+            find_dependency_cycle(&packages),
+            Some(vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "a".to_string()
+            ])
+        );
+    }
+
     #[test]
     fn test_published_filter_only_published() {
         let packages = vec![
@@ -946,4 +1311,245 @@ mod tests {
         // Empty MELOS_PACKAGES should not affect filtering
         assert_eq!(result.len(), 2);
     }
+
+    // -----------------------------------------------------------------------
+    // since_latest_tag
+    // -----------------------------------------------------------------------
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@test.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@test.com")
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Create a repo with packages `a` and `b` committed, returning the packages.
+    fn git_fixture(root: &Path) -> Vec<Package> {
+        git(root, &["init", "-q"]);
+        let packages: Vec<Package> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let dir = root.join("packages").join(name);
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join("pubspec.yaml"), format!("name: {name}\n")).unwrap();
+                Package {
+                    path: dir,
+                    ..make_package(name, false, vec![])
+                }
+            })
+            .collect();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "init"]);
+        packages
+    }
+
+    #[test]
+    fn test_since_latest_tag_selects_packages_changed_since_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+        git(root, &["tag", "v1.0.0"]);
+        std::fs::write(root.join("packages/b/lib.dart"), "void main() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "change b"]);
+
+        let filters = PackageFilters {
+            since_latest_tag: true,
+            ..Default::default()
+        };
+        let result = apply_filters(&packages, &filters, Some(root)).unwrap();
+        let names: Vec<&str> = result.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["b"]);
+    }
+
+    #[test]
+    fn test_since_latest_tag_errors_without_tags() {
+        let tmp = tempfile::tempdir().unwrap();
+        let packages = git_fixture(tmp.path());
+
+        let filters = PackageFilters {
+            since_latest_tag: true,
+            ..Default::default()
+        };
+        let err = apply_filters(&packages, &filters, Some(tmp.path()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("No git tags found"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_since_latest_tag_falls_back_to_diff() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+        std::fs::write(root.join("packages/a/lib.dart"), "void main() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "change a"]);
+
+        let filters = PackageFilters {
+            since_latest_tag: true,
+            diff: Some("HEAD~1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_diff_ref(&filters, root).unwrap().as_deref(),
+            Some("HEAD~1")
+        );
+        let result = apply_filters(&packages, &filters, Some(root)).unwrap();
+        let names: Vec<&str> = result.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["a"]);
+    }
+
+    fn diff_names(packages: &[Package], root: &Path, filters: PackageFilters) -> Vec<String> {
+        apply_filters(packages, &filters, Some(root))
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    }
+
+    fn diff_filter(git_ref: &str) -> PackageFilters {
+        PackageFilters {
+            diff: Some(git_ref.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_uses_longest_package_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut packages = git_fixture(root);
+        // `packages/a_ext` shares a string prefix with `packages/a`
+        let ext = root.join("packages/a_ext");
+        std::fs::create_dir_all(&ext).unwrap();
+        std::fs::write(ext.join("pubspec.yaml"), "name: a_ext\n").unwrap();
+        packages.push(Package {
+            path: ext.clone(),
+            ..make_package("a_ext", false, vec![])
+        });
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "add a_ext"]);
+
+        std::fs::write(ext.join("lib.dart"), "void main() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "change a_ext"]);
+
+        assert_eq!(
+            diff_names(&packages, root, diff_filter("HEAD~1")),
+            vec!["a_ext"]
+        );
+    }
+
+    #[test]
+    fn test_diff_ignores_files_outside_packages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+        std::fs::write(root.join("README.md"), "# ws\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "docs"]);
+
+        assert!(diff_names(&packages, root, diff_filter("HEAD~1")).is_empty());
+    }
+
+    #[test]
+    fn test_diff_head_includes_uncommitted_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+        // Modified tracked file in `a`, new untracked file in `b`
+        std::fs::write(
+            root.join("packages/a/pubspec.yaml"),
+            "name: a\nversion: 2.0.0\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("packages/b/new.dart"), "void main() {}\n").unwrap();
+
+        assert_eq!(
+            diff_names(&packages, root, diff_filter("HEAD")),
+            vec!["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_diff_composes_with_scope() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+        std::fs::write(root.join("packages/a/lib.dart"), "\n").unwrap();
+        std::fs::write(root.join("packages/b/lib.dart"), "\n").unwrap();
+
+        let filters = PackageFilters {
+            scope: Some(vec!["b".to_string()]),
+            ..diff_filter("HEAD")
+        };
+        assert_eq!(diff_names(&packages, root, filters), vec!["b"]);
+    }
+
+    #[test]
+    fn test_diff_invalid_ref_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let packages = git_fixture(root);
+
+        let err = apply_filters(&packages, &diff_filter("no-such-ref"), Some(root))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Invalid --diff ref 'no-such-ref'"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_dir_and_file_exists_filters_and_together() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut packages = Vec::new();
+        for (name, dirs, files) in [
+            ("app", vec!["ios", "test"], vec!["Podfile"]),
+            ("ios_only", vec!["ios"], vec![]),
+            ("tested", vec!["test"], vec!["Podfile"]),
+        ] {
+            let mut pkg = make_package(name, true, vec![]);
+            pkg.path = tmp.path().join(name);
+            for dir in dirs {
+                std::fs::create_dir_all(pkg.path.join(dir)).unwrap();
+            }
+            for file in files {
+                std::fs::write(pkg.path.join(file), "").unwrap();
+            }
+            packages.push(pkg);
+        }
+
+        let filters = PackageFilters {
+            dir_exists: Some(vec!["ios".to_string(), "test".to_string()]),
+            ..Default::default()
+        };
+        let names: Vec<String> = apply_filters(&packages, &filters, None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["app"]);
+
+        let filters = PackageFilters {
+            dir_exists: Some(vec!["test".to_string()]),
+            file_exists: Some(vec!["Podfile".to_string()]),
+            ..Default::default()
+        };
+        let names: Vec<String> = apply_filters(&packages, &filters, None)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["app", "tested"]);
+    }
 }
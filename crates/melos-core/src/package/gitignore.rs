@@ -0,0 +1,189 @@
+//! Minimal `.gitignore` matching for package discovery.
+//!
+//! Only directories are ever tested (package candidates and their ancestors),
+//! so every rule applies regardless of a trailing `/`. Rules from deeper
+//! `.gitignore` files are evaluated after shallower ones, and the last
+//! matching rule wins, as in git.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+/// A single parsed `.gitignore` line.
+#[derive(Debug)]
+struct Rule {
+    pattern: Pattern,
+    negated: bool,
+    /// Pattern contains a `/`, so it matches relative to the file's directory
+    /// rather than against any single path component.
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let line = line.strip_suffix('/').unwrap_or(line);
+        let anchored = line.contains('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        if line.is_empty() {
+            return None;
+        }
+        let pattern = Pattern::new(line).ok()?;
+        Some(Self {
+            pattern,
+            negated,
+            anchored,
+        })
+    }
+
+    /// Whether the rule matches `components`, a directory path relative to
+    /// the `.gitignore` that declared the rule.
+    fn matches(&self, components: &[&str]) -> bool {
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        if self.anchored {
+            self.pattern.matches_with(&components.join("/"), options)
+        } else {
+            components
+                .last()
+                .is_some_and(|name| self.pattern.matches_with(name, options))
+        }
+    }
+}
+
+/// `.gitignore` rules for a workspace, loaded lazily per directory.
+#[derive(Debug)]
+pub struct GitIgnore {
+    root: PathBuf,
+    /// Rules keyed by directory relative to `root` (`""` for the root itself).
+    files: HashMap<String, Vec<Rule>>,
+}
+
+impl GitIgnore {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Whether `dir` (absolute, or relative to the root) or any of its
+    /// ancestors below the root is ignored.
+    ///
+    /// Paths outside the root are never ignored.
+    pub fn is_ignored(&mut self, dir: &Path) -> bool {
+        let relative = match dir.strip_prefix(&self.root) {
+            Ok(rel) => rel,
+            Err(_) if dir.is_absolute() => return false,
+            Err(_) => dir,
+        };
+        let components: Vec<&str> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => s.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        // Git never re-includes anything below an excluded directory, so the
+        // first ignored ancestor decides.
+        (1..=components.len()).any(|depth| self.is_dir_ignored(&components[..depth]))
+    }
+
+    /// Evaluate every `.gitignore` from the root down to the parent of
+    /// `components` against that directory.
+    fn is_dir_ignored(&mut self, components: &[&str]) -> bool {
+        let mut ignored = false;
+        for base in 0..components.len() {
+            let rules = self.rules_for(&components[..base]);
+            for rule in rules {
+                if rule.matches(&components[base..]) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+        ignored
+    }
+
+    fn rules_for(&mut self, dir: &[&str]) -> &[Rule] {
+        let key = dir.join("/");
+        let root = &self.root;
+        self.files.entry(key).or_insert_with(|| {
+            let path = dir
+                .iter()
+                .fold(root.clone(), |path, c| path.join(c))
+                .join(".gitignore");
+            std::fs::read_to_string(path)
+                .map(|content| content.lines().filter_map(Rule::parse).collect())
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn ignore_with(root_rules: &str) -> (TempDir, GitIgnore) {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(".gitignore"), root_rules).unwrap();
+        let gitignore = GitIgnore::new(dir.path());
+        (dir, gitignore)
+    }
+
+    #[test]
+    fn test_unanchored_name_matches_at_any_depth() {
+        let (_dir, mut gi) = ignore_with("# generated\nexamples_generated/\n");
+        assert!(gi.is_ignored(Path::new("packages/examples_generated")));
+        assert!(gi.is_ignored(Path::new("packages/examples_generated/sample_a")));
+        assert!(gi.is_ignored(Path::new("examples_generated")));
+        assert!(!gi.is_ignored(Path::new("packages/examples")));
+    }
+
+    #[test]
+    fn test_anchored_pattern_matches_from_root_only() {
+        let (_dir, mut gi) = ignore_with("/packages/tmp_*\n");
+        assert!(gi.is_ignored(Path::new("packages/tmp_one")));
+        assert!(!gi.is_ignored(Path::new("other/packages/tmp_one")));
+    }
+
+    #[test]
+    fn test_negation_last_rule_wins() {
+        let (_dir, mut gi) = ignore_with("samples_*\n!samples_keep\n");
+        assert!(gi.is_ignored(Path::new("packages/samples_drop")));
+        assert!(!gi.is_ignored(Path::new("packages/samples_keep")));
+    }
+
+    #[test]
+    fn test_nested_gitignore_applies_to_its_subtree() {
+        let (dir, mut gi) = ignore_with("");
+        fs::create_dir_all(dir.path().join("packages/app")).unwrap();
+        fs::write(dir.path().join("packages/app/.gitignore"), "fixtures/\n").unwrap();
+
+        assert!(gi.is_ignored(&dir.path().join("packages/app/fixtures")));
+        assert!(gi.is_ignored(&dir.path().join("packages/app/fixtures/pkg")));
+        assert!(!gi.is_ignored(&dir.path().join("packages/fixtures")));
+    }
+
+    #[test]
+    fn test_double_star_and_outside_root() {
+        let (dir, mut gi) = ignore_with("**/gen/out\n");
+        assert!(gi.is_ignored(Path::new("gen/out")));
+        assert!(gi.is_ignored(Path::new("a/b/gen/out")));
+        assert!(!gi.is_ignored(Path::new("a/gen")));
+        assert!(!gi.is_ignored(&dir.path().parent().unwrap().join("gen/out")));
+    }
+}
@@ -0,0 +1,270 @@
+//! Intra-workspace dependency graph.
+//!
+//! Built once from the discovered packages so ordering, filtering and graph
+//! output share the same edges. Only dependencies on packages present in the
+//! workspace become edges; SDK and hosted pub dependencies are ignored.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::package::Package;
+
+/// Forward (depends-on) and reverse (depended-by) adjacency for workspace
+/// packages.
+///
+/// Edges cover both `dependencies` and `dev_dependencies`. Neighbours are kept
+/// sorted by name so every traversal is deterministic.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// Package names in workspace order
+    names: Vec<String>,
+    /// Package name -> workspace packages it depends on
+    dependencies: HashMap<String, BTreeSet<String>>,
+    /// Package name -> workspace packages that depend on it
+    dependents: HashMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph from `packages`, keeping only edges between them.
+    pub fn new(packages: &[Package]) -> Self {
+        let known: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        let mut graph = Self {
+            names: packages.iter().map(|p| p.name.clone()).collect(),
+            ..Default::default()
+        };
+
+        for pkg in packages {
+            graph.dependencies.entry(pkg.name.clone()).or_default();
+            graph.dependents.entry(pkg.name.clone()).or_default();
+            for dep in pkg.dependencies.iter().chain(pkg.dev_dependencies.iter()) {
+                if !known.contains(dep.as_str()) {
+                    continue;
+                }
+                graph
+                    .dependencies
+                    .entry(pkg.name.clone())
+                    .or_default()
+                    .insert(dep.clone());
+                graph
+                    .dependents
+                    .entry(dep.clone())
+                    .or_default()
+                    .insert(pkg.name.clone());
+            }
+        }
+
+        graph
+    }
+
+    /// Package names in workspace order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Whether `name` is a package in the graph.
+    pub fn contains(&self, name: &str) -> bool {
+        self.dependencies.contains_key(name)
+    }
+
+    /// Workspace packages that `name` depends on directly, sorted by name.
+    pub fn dependencies_of(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.dependencies
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Workspace packages that depend on `name` directly, sorted by name.
+    pub fn dependents_of(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.dependents
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// `seeds` plus everything they transitively depend on.
+    ///
+    /// Names not in the graph are ignored.
+    pub fn transitive_dependencies<'a>(
+        &self,
+        seeds: impl IntoIterator<Item = &'a str>,
+    ) -> HashSet<String> {
+        self.closure(seeds, &self.dependencies)
+    }
+
+    /// `seeds` plus every package that transitively depends on one of them.
+    ///
+    /// Names not in the graph are ignored.
+    pub fn transitive_dependents<'a>(
+        &self,
+        seeds: impl IntoIterator<Item = &'a str>,
+    ) -> HashSet<String> {
+        self.closure(seeds, &self.dependents)
+    }
+
+    /// Breadth-first closure over `edges`; each package is visited once, so
+    /// diamonds and cycles terminate.
+    fn closure<'a>(
+        &self,
+        seeds: impl IntoIterator<Item = &'a str>,
+        edges: &HashMap<String, BTreeSet<String>>,
+    ) -> HashSet<String> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        for seed in seeds {
+            if let Some((name, _)) = edges.get_key_value(seed)
+                && visited.insert(name.clone())
+            {
+                queue.push_back(name);
+            }
+        }
+
+        while let Some(name) = queue.pop_front() {
+            for next in edges.get(name).into_iter().flatten() {
+                if visited.insert(next.clone()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Package names in dependency order: packages without workspace
+    /// dependencies first, then the packages that depend on them, and so on.
+    ///
+    /// Uses Kahn's algorithm, breaking ties by name. Packages on a cycle are
+    /// appended at the end in workspace order rather than dropped.
+    pub fn topo_order(&self) -> Vec<&str> {
+        let mut in_degree: HashMap<&str, usize> = self
+            .dependencies
+            .iter()
+            .map(|(name, deps)| (name.as_str(), deps.len()))
+            .collect();
+
+        let mut initial: Vec<&str> = in_degree
+            .iter()
+            .filter(|&(_, &deg)| deg == 0)
+            .map(|(&name, _)| name)
+            .collect();
+        initial.sort();
+        let mut queue: VecDeque<&str> = initial.into();
+
+        let mut order: Vec<&str> = Vec::with_capacity(self.names.len());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            // Neighbours iterate in name order, so ready nodes are already sorted
+            for dependent in self.dependents_of(node) {
+                if let Some(deg) = in_degree.get_mut(dependent) {
+                    *deg -= 1;
+                    if *deg == 0 {
+                        queue.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        if order.len() < self.names.len() {
+            let placed: HashSet<&str> = order.iter().copied().collect();
+            let remaining: Vec<&str> = self
+                .names
+                .iter()
+                .map(String::as_str)
+                .filter(|name| !placed.contains(name))
+                .collect();
+            order.extend(remaining);
+        }
+
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_pkg(name: &str, deps: &[&str], dev_deps: &[&str]) -> Package {
+        Package {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/workspace/packages/{}", name)),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            dev_dependencies: dev_deps.iter().map(|d| d.to_string()).collect(),
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        }
+    }
+
+    /// app -> {feature, http}, feature -> core, core (dev) -> test_utils
+    fn sample_graph() -> DependencyGraph {
+        DependencyGraph::new(&[
+            make_pkg("app", &["feature", "http", "flutter"], &[]),
+            make_pkg("feature", &["core"], &[]),
+            make_pkg("core", &["collection"], &["test_utils"]),
+            make_pkg("test_utils", &[], &[]),
+        ])
+    }
+
+    #[test]
+    fn test_edges_only_include_workspace_packages() {
+        let graph = sample_graph();
+        assert_eq!(
+            graph.dependencies_of("app").collect::<Vec<_>>(),
+            ["feature"]
+        );
+        assert_eq!(
+            graph.dependencies_of("core").collect::<Vec<_>>(),
+            ["test_utils"]
+        );
+        assert_eq!(graph.dependents_of("core").collect::<Vec<_>>(), ["feature"]);
+        assert_eq!(
+            graph.dependents_of("test_utils").collect::<Vec<_>>(),
+            ["core"]
+        );
+        assert!(!graph.contains("http"));
+        assert_eq!(graph.dependents_of("http").count(), 0);
+    }
+
+    #[test]
+    fn test_transitive_closures() {
+        let graph = sample_graph();
+
+        let mut deps: Vec<String> = graph
+            .transitive_dependencies(["feature"])
+            .into_iter()
+            .collect();
+        deps.sort();
+        assert_eq!(deps, vec!["core", "feature", "test_utils"]);
+
+        let mut dependents: Vec<String> = graph
+            .transitive_dependents(["core", "unknown"])
+            .into_iter()
+            .collect();
+        dependents.sort();
+        assert_eq!(dependents, vec!["app", "core", "feature"]);
+    }
+
+    #[test]
+    fn test_topo_order_dependencies_first() {
+        assert_eq!(
+            sample_graph().topo_order(),
+            vec!["test_utils", "core", "feature", "app"]
+        );
+    }
+
+    #[test]
+    fn test_topo_order_appends_cycles_in_workspace_order() {
+        let graph = DependencyGraph::new(&[
+            make_pkg("b", &["a"], &[]),
+            make_pkg("a", &["b"], &[]),
+            make_pkg("leaf", &[], &[]),
+        ]);
+        assert_eq!(graph.topo_order(), vec!["leaf", "b", "a"]);
+        assert_eq!(graph.names(), ["b", "a", "leaf"]);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod filter;
+pub mod gitignore;
+pub mod graph;
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -7,6 +9,8 @@ use anyhow::{Context, Result};
 use rayon::prelude::*;
 use serde::Deserialize;
 
+use gitignore::GitIgnore;
+
 /// Represents a Dart/Flutter package found in the workspace
 #[derive(Debug, Clone)]
 pub struct Package {
@@ -38,6 +42,13 @@ pub struct Package {
     /// are excluded.
     pub dependency_versions: HashMap<String, String>,
 
+    /// How each dependency and dev dependency is sourced (dep name -> kind).
+    ///
+    /// Filled from the pubspec by [`Package::from_path`]; siblings of a
+    /// `resolution: workspace` package are marked [`DepKind::Workspace`] once
+    /// the whole workspace is known (see [`mark_workspace_dependencies`]).
+    pub dependency_kinds: HashMap<String, DepKind>,
+
     /// The `resolution` field from pubspec.yaml (e.g. "workspace").
     ///
     /// Dart 3.5+ workspaces require each member package to declare
@@ -46,6 +57,35 @@ pub struct Package {
     pub resolution: Option<String>,
 }
 
+/// How a pubspec dependency is sourced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepKind {
+    /// Resolved from a package server: a constraint string, `any`/empty, or a
+    /// mapping with `version`/`hosted` keys
+    Version,
+    /// A `path:` dependency
+    Path,
+    /// A `git:` dependency
+    Git,
+    /// An SDK dependency, e.g. `sdk: flutter`
+    Sdk,
+    /// A sibling resolved implicitly by a Dart workspace (`resolution: workspace`)
+    Workspace,
+}
+
+/// File next to `pubspec.yaml` holding the package version when the pubspec
+/// omits it (e.g. generated packages). Only read by `version` when
+/// `command.version.versionFile` is enabled.
+pub const VERSION_FILE: &str = "version.txt";
+
+/// Read the version from the package's [`VERSION_FILE`], if present and
+/// non-empty.
+pub fn read_version_file(package_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(package_dir.join(VERSION_FILE)).ok()?;
+    let version = content.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
 /// Minimal pubspec.yaml structure for parsing
 #[derive(Debug, Deserialize)]
 pub struct PubspecYaml {
@@ -92,8 +132,9 @@ impl Package {
             .map(|deps| deps.keys().cloned().collect())
             .unwrap_or_default();
 
-        // Extract version constraints from both deps and dev_deps
+        // Extract version constraints and sources from both deps and dev_deps
         let mut dependency_versions = HashMap::new();
+        let mut dependency_kinds = HashMap::new();
         for deps_map in [&pubspec.dependencies, &pubspec.dev_dependencies]
             .iter()
             .copied()
@@ -103,6 +144,7 @@ impl Package {
                 if let Some(constraint) = extract_version_constraint(value) {
                     dependency_versions.insert(name.clone(), constraint);
                 }
+                dependency_kinds.insert(name.clone(), dependency_kind(value));
             }
         }
 
@@ -130,6 +172,7 @@ impl Package {
             dependencies,
             dev_dependencies,
             dependency_versions,
+            dependency_kinds,
             resolution: pubspec.resolution,
         })
     }
@@ -151,6 +194,11 @@ impl Package {
             .is_some_and(|p| p.eq_ignore_ascii_case("none"))
     }
 
+    /// How `dep` is sourced, if it is a dependency or dev dependency.
+    pub fn dependency_kind(&self, dep: &str) -> Option<DepKind> {
+        self.dependency_kinds.get(dep).copied()
+    }
+
     /// Check if this package has a given dependency (in deps or dev_deps)
     pub fn has_dependency(&self, dep: &str) -> bool {
         self.dependencies.contains(&dep.to_string())
@@ -199,6 +247,46 @@ fn extract_version_constraint(value: &yaml_serde::Value) -> Option<String> {
     }
 }
 
+/// Classify a YAML dependency value by its source.
+///
+/// Mappings are checked for `sdk`, then `git`, then `path`; anything else
+/// (strings, null, `version`/`hosted` mappings) is [`DepKind::Version`].
+fn dependency_kind(value: &yaml_serde::Value) -> DepKind {
+    let yaml_serde::Value::Mapping(map) = value else {
+        return DepKind::Version;
+    };
+    let has = |key: &str| map.contains_key(yaml_serde::Value::String(key.to_string()));
+    if has("sdk") {
+        DepKind::Sdk
+    } else if has("git") {
+        DepKind::Git
+    } else if has("path") {
+        DepKind::Path
+    } else {
+        DepKind::Version
+    }
+}
+
+/// Mark dependencies on sibling packages as [`DepKind::Workspace`] in
+/// packages that use `resolution: workspace`.
+///
+/// Only plain version dependencies are marked: an explicit `path`, `git` or
+/// `sdk` source is kept.
+pub fn mark_workspace_dependencies(packages: &mut [Package]) {
+    let names: std::collections::HashSet<String> =
+        packages.iter().map(|p| p.name.clone()).collect();
+    for pkg in packages.iter_mut() {
+        if !pkg.uses_workspace_resolution() {
+            continue;
+        }
+        for (dep, kind) in pkg.dependency_kinds.iter_mut() {
+            if *kind == DepKind::Version && names.contains(dep) {
+                *kind = DepKind::Workspace;
+            }
+        }
+    }
+}
+
 /// Directories excluded during package discovery.
 ///
 /// These contain cached dependencies, build artifacts, or IDE files whose
@@ -236,11 +324,42 @@ fn is_in_excluded_dir(path: &Path, root: &Path) -> bool {
 /// Directories listed in [`EXCLUDED_PACKAGE_DIRS`] (e.g. `.dart_tool`,
 /// `.symlinks`, `build`) are automatically skipped so that cached
 /// dependencies and build artifacts are never treated as workspace packages.
-pub fn discover_packages(root: &Path, patterns: &[String]) -> Result<Vec<Package>> {
+///
+/// When `respect_gitignore` is set, directories ignored by the root
+/// `.gitignore` (or one nested below it) are skipped as well.
+///
+/// Patterns starting with `!` are exclusions: every positive pattern is
+/// matched first, then directories matching any negation are removed,
+/// regardless of the order they appear in.
+///
+/// Directories whose `pubspec.yaml` cannot be parsed are left out and a
+/// warning is pushed onto `warnings`.
+pub fn discover_packages(
+    root: &Path,
+    patterns: &[String],
+    respect_gitignore: bool,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Package>> {
     // Phase 1: collect candidate directories sequentially (glob is fast)
     let mut candidate_dirs: Vec<PathBuf> = Vec::new();
+    let mut gitignore = respect_gitignore.then(|| GitIgnore::new(root));
+
+    let (negations, positives): (Vec<&String>, Vec<&String>) =
+        patterns.iter().partition(|p| p.starts_with('!'));
+    let exclusions: Vec<glob::Pattern> = negations
+        .iter()
+        .map(|p| {
+            let full_pattern = root.join(&p[1..]).display().to_string();
+            glob::Pattern::new(&full_pattern)
+                .with_context(|| format!("Invalid glob pattern: {}", p))
+        })
+        .collect::<Result<_>>()?;
+    let exclude_options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
 
-    for pattern in patterns {
+    for pattern in positives {
         let full_pattern = root.join(pattern).display().to_string();
 
         for entry in glob::glob(&full_pattern)
@@ -253,27 +372,40 @@ pub fn discover_packages(root: &Path, patterns: &[String]) -> Result<Vec<Package
                 continue;
             }
 
-            if entry_path.is_dir() && entry_path.join("pubspec.yaml").exists() {
+            if exclusions
+                .iter()
+                .any(|ex| ex.matches_path_with(&entry_path, exclude_options))
+            {
+                continue;
+            }
+
+            if entry_path.is_dir()
+                && entry_path.join("pubspec.yaml").exists()
+                && !gitignore
+                    .as_mut()
+                    .is_some_and(|gi| gi.is_ignored(&entry_path))
+            {
                 candidate_dirs.push(entry_path);
             }
         }
     }
 
     // Phase 2: parse pubspec.yaml files in parallel
-    let mut packages: Vec<Package> = candidate_dirs
+    let parsed: Vec<(&PathBuf, Result<Package>)> = candidate_dirs
         .par_iter()
-        .filter_map(|dir| match Package::from_path(dir) {
-            Ok(pkg) => Some(pkg),
-            Err(e) => {
-                eprintln!(
-                    "Warning: Failed to parse package at {}: {}",
-                    dir.display(),
-                    e
-                );
-                None
-            }
-        })
+        .map(|dir| (dir, Package::from_path(dir)))
         .collect();
+    let mut packages = Vec::with_capacity(parsed.len());
+    for (dir, result) in parsed {
+        match result {
+            Ok(pkg) => packages.push(pkg),
+            Err(e) => warnings.push(format!(
+                "Failed to parse package at {}: {}",
+                dir.display(),
+                e
+            )),
+        }
+    }
 
     // Sort by name for deterministic ordering
     packages.sort_by(|a, b| a.name.cmp(&b.name));
@@ -306,6 +438,36 @@ mod tests {
         assert!(pkg.dependencies.contains(&"http".to_string()));
     }
 
+    #[test]
+    fn test_from_path_ignores_version_file() {
+        let dir = TempDir::new().unwrap();
+        let pkg_dir = dir.path().join("generated");
+        fs::create_dir_all(&pkg_dir).unwrap();
+        fs::write(
+            pkg_dir.join("pubspec.yaml"),
+            "name: generated
+",
+        )
+        .unwrap();
+        assert_eq!(Package::from_path(&pkg_dir).unwrap().version, None);
+
+        // version.txt is an opt-in fallback of the version command
+        fs::write(pkg_dir.join(VERSION_FILE), "0.4.1\n").unwrap();
+        assert_eq!(Package::from_path(&pkg_dir).unwrap().version, None);
+        assert_eq!(read_version_file(&pkg_dir).as_deref(), Some("0.4.1"));
+
+        // The pubspec wins when it declares a version
+        fs::write(
+            pkg_dir.join("pubspec.yaml"),
+            "name: generated\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            Package::from_path(&pkg_dir).unwrap().version.as_deref(),
+            Some("1.0.0")
+        );
+    }
+
     #[test]
     fn test_from_path_flutter_package() {
         let dir = TempDir::new().unwrap();
@@ -349,6 +511,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         assert!(pkg.is_private());
@@ -365,6 +528,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         assert!(!pkg.is_private());
@@ -381,6 +545,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         assert!(!pkg.is_private());
@@ -397,6 +562,7 @@ mod tests {
             dependencies: vec!["http".to_string()],
             dev_dependencies: vec!["test".to_string()],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         assert!(pkg.has_dependency("http"));
@@ -457,6 +623,59 @@ mod tests {
         assert!(!pkg.dependency_versions.contains_key("flutter"));
     }
 
+    #[test]
+    fn test_dependency_kinds_parsed() {
+        let dir = TempDir::new().unwrap();
+        let pkg_dir = dir.path().join("pkg");
+        fs::create_dir_all(&pkg_dir).unwrap();
+        fs::write(
+            pkg_dir.join("pubspec.yaml"),
+            "name: pkg\nversion: 1.0.0\ndependencies:\n  http: ^0.13.0\n  core:\n    version: ^2.0.0\n    path: ../core\n  flutter:\n    sdk: flutter\n  forked:\n    git:\n      url: https://example.com/forked.git\n  hosted_dep:\n    hosted: https://pub.example.com\n    version: ^1.0.0\ndev_dependencies:\n  lints:\n",
+        )
+        .unwrap();
+
+        let pkg = Package::from_path(&pkg_dir).unwrap();
+        assert_eq!(pkg.dependency_kind("http"), Some(DepKind::Version));
+        assert_eq!(pkg.dependency_kind("core"), Some(DepKind::Path));
+        assert_eq!(pkg.dependency_kind("flutter"), Some(DepKind::Sdk));
+        assert_eq!(pkg.dependency_kind("forked"), Some(DepKind::Git));
+        assert_eq!(pkg.dependency_kind("hosted_dep"), Some(DepKind::Version));
+        assert_eq!(pkg.dependency_kind("lints"), Some(DepKind::Version));
+        assert_eq!(pkg.dependency_kind("missing"), None);
+    }
+
+    #[test]
+    fn test_mark_workspace_dependencies() {
+        let dir = TempDir::new().unwrap();
+        let write = |name: &str, pubspec: &str| {
+            let pkg_dir = dir.path().join(name);
+            fs::create_dir_all(&pkg_dir).unwrap();
+            fs::write(pkg_dir.join("pubspec.yaml"), pubspec).unwrap();
+            Package::from_path(&pkg_dir).unwrap()
+        };
+        let mut packages = vec![
+            write(
+                "core",
+                "name: core\nversion: 1.0.0\nresolution: workspace\n",
+            ),
+            write(
+                "app",
+                "name: app\nresolution: workspace\ndependencies:\n  core: ^1.0.0\n  ui:\n    path: ../ui\n  http: ^1.0.0\n",
+            ),
+            write("ui", "name: ui\ndependencies:\n  core: ^1.0.0\n"),
+        ];
+        mark_workspace_dependencies(&mut packages);
+
+        assert_eq!(
+            packages[1].dependency_kind("core"),
+            Some(DepKind::Workspace)
+        );
+        assert_eq!(packages[1].dependency_kind("ui"), Some(DepKind::Path));
+        assert_eq!(packages[1].dependency_kind("http"), Some(DepKind::Version));
+        // Not a workspace-resolution package
+        assert_eq!(packages[2].dependency_kind("core"), Some(DepKind::Version));
+    }
+
     #[test]
     fn test_extract_version_constraint_string() {
         let val = yaml_serde::Value::String("^1.0.0".to_string());
@@ -522,6 +741,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: Some("workspace".to_string()),
         };
         assert!(pkg.uses_workspace_resolution());
@@ -538,6 +758,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: Some("Workspace".to_string()),
         };
         assert!(pkg.uses_workspace_resolution());
@@ -554,6 +775,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: Some("local".to_string()),
         };
         assert!(!pkg.uses_workspace_resolution());
@@ -570,6 +792,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
         assert!(!pkg.uses_workspace_resolution());
@@ -684,7 +907,8 @@ mod tests {
         )
         .unwrap();
 
-        let packages = discover_packages(root, &["packages/**".to_string()]).unwrap();
+        let packages =
+            discover_packages(root, &["packages/**".to_string()], true, &mut Vec::new()).unwrap();
         let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
         assert!(names.contains(&"core"), "real package should be found");
         assert!(
@@ -717,7 +941,8 @@ mod tests {
         )
         .unwrap();
 
-        let packages = discover_packages(root, &["packages/**".to_string()]).unwrap();
+        let packages =
+            discover_packages(root, &["packages/**".to_string()], true, &mut Vec::new()).unwrap();
         let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
         assert!(names.contains(&"app"));
         assert!(
@@ -749,7 +974,8 @@ mod tests {
         )
         .unwrap();
 
-        let packages = discover_packages(root, &["packages/**".to_string()]).unwrap();
+        let packages =
+            discover_packages(root, &["packages/**".to_string()], true, &mut Vec::new()).unwrap();
         let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
         assert!(names.contains(&"ui"));
         assert!(
@@ -790,7 +1016,8 @@ mod tests {
             .unwrap();
         }
 
-        let packages = discover_packages(root, &["packages/**".to_string()]).unwrap();
+        let packages =
+            discover_packages(root, &["packages/**".to_string()], true, &mut Vec::new()).unwrap();
         let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
 
         assert_eq!(
@@ -802,4 +1029,120 @@ mod tests {
         assert!(names.contains(&"pkg_a"));
         assert!(names.contains(&"pkg_b"));
     }
+
+    #[test]
+    fn test_discover_respects_gitignore() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        for (rel, name) in [
+            ("packages/core", "core"),
+            ("packages/examples_generated/sample", "sample"),
+            ("packages/core/fixtures/fixture_pkg", "fixture_pkg"),
+        ] {
+            fs::create_dir_all(root.join(rel)).unwrap();
+            fs::write(
+                root.join(rel).join("pubspec.yaml"),
+                format!("name: {}\nversion: 1.0.0\n", name),
+            )
+            .unwrap();
+        }
+        fs::write(root.join(".gitignore"), "examples_generated/\n").unwrap();
+        fs::write(root.join("packages/core/.gitignore"), "/fixtures\n").unwrap();
+
+        let patterns = ["packages/**".to_string()];
+        let names =
+            |pkgs: Vec<Package>| -> Vec<String> { pkgs.into_iter().map(|p| p.name).collect() };
+
+        assert_eq!(
+            names(discover_packages(root, &patterns, true, &mut Vec::new()).unwrap()),
+            vec!["core"]
+        );
+        assert_eq!(
+            names(discover_packages(root, &patterns, false, &mut Vec::new()).unwrap()),
+            vec!["core", "fixture_pkg", "sample"]
+        );
+    }
+
+    #[test]
+    fn test_discover_negated_patterns_exclude_matches() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for (rel, name) in [
+            ("packages/core", "core"),
+            ("packages/core/example", "core_example"),
+            ("packages/ui/widgets", "widgets"),
+            ("packages/ui/widgets/example", "widgets_example"),
+        ] {
+            fs::create_dir_all(root.join(rel)).unwrap();
+            fs::write(
+                root.join(rel).join("pubspec.yaml"),
+                format!("name: {}\nversion: 1.0.0\n", name),
+            )
+            .unwrap();
+        }
+        let names =
+            |pkgs: Vec<Package>| -> Vec<String> { pkgs.into_iter().map(|p| p.name).collect() };
+
+        // Positive-only behaviour is unchanged
+        let positive = ["packages/**".to_string()];
+        assert_eq!(
+            names(discover_packages(root, &positive, false, &mut Vec::new()).unwrap()),
+            vec!["core", "core_example", "widgets", "widgets_example"]
+        );
+
+        // Negations subtract from positives regardless of their position
+        let negation_last = [
+            "packages/**".to_string(),
+            "!packages/**/example".to_string(),
+        ];
+        let negation_first = [
+            "!packages/**/example".to_string(),
+            "packages/**".to_string(),
+        ];
+        for patterns in [&negation_last, &negation_first] {
+            assert_eq!(
+                names(discover_packages(root, patterns, false, &mut Vec::new()).unwrap()),
+                vec!["core", "widgets"]
+            );
+        }
+
+        // A single-segment wildcard does not cross directories
+        let shallow = ["packages/**".to_string(), "!packages/*/example".to_string()];
+        assert_eq!(
+            names(discover_packages(root, &shallow, false, &mut Vec::new()).unwrap()),
+            vec!["core", "widgets", "widgets_example"]
+        );
+    }
+
+    #[test]
+    fn test_discover_invalid_negated_pattern_errors() {
+        let dir = TempDir::new().unwrap();
+        let patterns = ["packages/*".to_string(), "![".to_string()];
+        let err = discover_packages(dir.path(), &patterns, false, &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("Invalid glob pattern"));
+    }
+
+    #[test]
+    fn test_discover_reports_unparsable_pubspec_as_warning() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for (name, pubspec) in [("core", "name: core\n"), ("broken", "name: [\n")] {
+            fs::create_dir_all(root.join("packages").join(name)).unwrap();
+            fs::write(
+                root.join("packages").join(name).join("pubspec.yaml"),
+                pubspec,
+            )
+            .unwrap();
+        }
+
+        let mut warnings = Vec::new();
+        let packages =
+            discover_packages(root, &["packages/*".to_string()], false, &mut warnings).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "core");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Failed to parse package at"));
+        assert!(warnings[0].contains("broken"));
+    }
 }
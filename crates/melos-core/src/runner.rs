@@ -1,13 +1,18 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::Semaphore;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
 
+use crate::commands::PackageResults;
 use crate::events::Event;
 use crate::package::Package;
+use crate::package::filter::{find_dependency_cycle, topological_sort};
 
 /// Return the platform-appropriate shell executable and flag for running commands.
 ///
@@ -21,13 +26,308 @@ pub fn shell_command() -> (&'static str, &'static str) {
     }
 }
 
+/// Retry policy for package commands that exit unsuccessfully.
+///
+/// Retries use exponential backoff: the delay before retry `n` (1-based) is
+/// `backoff * 2^(n-1)`. With `jitter` enabled, each delay is randomized into
+/// the upper half of that value (`[delay/2, delay]`) so that many packages
+/// retrying at once don't hit a flaky server in lockstep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of additional attempts after the first failure (0 = no retries)
+    pub max_retries: usize,
+    /// Base delay before the first retry
+    pub backoff: Duration,
+    /// Randomize each delay to spread out concurrent retries
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Exponential backoff delay before retry `attempt` (1-based), without jitter.
+    pub fn backoff_delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        self.backoff.saturating_mul(2u32.pow(exponent))
+    }
+
+    /// Delay before retry `attempt` (1-based), with jitter applied if enabled.
+    pub fn delay_for(&self, attempt: usize) -> Duration {
+        let delay = self.backoff_delay(attempt);
+        if self.jitter {
+            apply_jitter(delay, random_u64())
+        } else {
+            delay
+        }
+    }
+}
+
+/// Scale `delay` into `[delay/2, delay]` using `seed` as the random source.
+fn apply_jitter(delay: Duration, seed: u64) -> Duration {
+    let half = delay / 2;
+    let spread = (delay - half).as_millis() as u64;
+    if spread == 0 {
+        return delay;
+    }
+    half + Duration::from_millis(seed % (spread + 1))
+}
+
+/// A cheap random number from the std hasher's per-instance random keys.
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+/// Outcome of a single attempt at running a package command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttemptOutcome {
+    Success,
+    Failed,
+    /// The command-wide deadline elapsed while the process was running
+    DeadlineExceeded,
+    /// The process was killed before it finished
+    Aborted(AbortReason),
+    /// The command was not found: the spawn failed with `NotFound`, or the
+    /// shell exited with [`COMMAND_NOT_FOUND_EXIT`]
+    NotFound,
+}
+
+/// Why the commands still running are being killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AbortReason {
+    /// Another package failed under fail-fast
+    FailFast,
+    /// The user pressed Ctrl-C
+    Interrupted,
+}
+
+impl AbortReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            AbortReason::FailFast => "fail-fast",
+            AbortReason::Interrupted => "interrupted",
+        }
+    }
+}
+
+/// Signal running jobs to abort, keeping the first reason if one was set.
+fn request_abort(abort: &watch::Sender<Option<AbortReason>>, reason: AbortReason) {
+    abort.send_if_modified(|current| {
+        let unset = current.is_none();
+        if unset {
+            *current = Some(reason);
+        }
+        unset
+    });
+}
+
+/// Sender that tells a run's jobs to abort.
+type AbortSender = Arc<watch::Sender<Option<AbortReason>>>;
+
+/// Abort senders of the runs in progress, keyed by registration id.
+static ACTIVE_RUNS: LazyLock<Mutex<HashMap<u64, AbortSender>>> = LazyLock::new(Default::default);
+
+/// Registers a run with the process-wide Ctrl-C handler until dropped.
+///
+/// Each command runs in its own process group (see [`run_attempt`]), so the
+/// terminal's SIGINT no longer reaches it; on Ctrl-C the handler aborts every
+/// registered run and the runner kills the groups itself. With no run
+/// registered, Ctrl-C ends the process as it would without a handler.
+struct InterruptRegistration(u64);
+
+impl InterruptRegistration {
+    fn new(abort: AbortSender) -> Self {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        install_interrupt_handler();
+        let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        active_runs().insert(id, abort);
+        Self(id)
+    }
+}
+
+impl Drop for InterruptRegistration {
+    fn drop(&mut self) {
+        active_runs().remove(&self.0);
+    }
+}
+
+fn active_runs() -> std::sync::MutexGuard<'static, HashMap<u64, AbortSender>> {
+    ACTIVE_RUNS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Install the process-wide Ctrl-C handler on first use.
+///
+/// Listening for Ctrl-C disables the default SIGINT termination for the whole
+/// process, so one handler serves every run (and every tokio runtime) from
+/// its own thread. Returns once the handler is listening, so no Ctrl-C during
+/// a run is missed.
+fn install_interrupt_handler() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("melos-interrupt".to_string())
+            .spawn(move || {
+                let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                else {
+                    return;
+                };
+                runtime.block_on(async move {
+                    #[cfg(unix)]
+                    let signal =
+                        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt());
+                    #[cfg(windows)]
+                    let signal = tokio::signal::windows::ctrl_c();
+                    let Ok(mut signal) = signal else {
+                        return;
+                    };
+                    let _ = ready_tx.send(());
+                    while signal.recv().await.is_some() {
+                        handle_interrupt();
+                    }
+                });
+            });
+        // If the handler could not start, Ctrl-C keeps its default behavior
+        if spawned.is_ok() {
+            let _ = ready_rx.recv();
+        }
+    });
+}
+
+/// Abort every active run, or end the process if none is active.
+fn handle_interrupt() {
+    let runs = active_runs();
+    if runs.is_empty() {
+        drop(runs);
+        exit_interrupted();
+    }
+    for abort in runs.values() {
+        request_abort(abort, AbortReason::Interrupted);
+    }
+}
+
+/// End the process the way an unhandled SIGINT would.
+fn exit_interrupted() -> ! {
+    #[cfg(unix)]
+    // safety: restoring the default disposition and re-raising only affects
+    // this process, which is meant to terminate here
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+        libc::raise(libc::SIGINT);
+    }
+    std::process::exit(130);
+}
+
+/// How a package's job ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JobOutcome {
+    /// The command ran; whether it eventually succeeded
+    Finished(bool),
+    /// The package was skipped, with the reason
+    Skipped(String),
+    /// The command was killed before it finished
+    Aborted,
+}
+
+impl JobOutcome {
+    /// Add the outcome for package `name` to `results`.
+    fn record(self, name: String, results: &mut PackageResults) {
+        match self {
+            JobOutcome::Finished(success) => results.results.push((name, success)),
+            JobOutcome::Skipped(reason) => results.skipped.push((name, reason)),
+            JobOutcome::Aborted => results.aborted.push(name),
+        }
+    }
+}
+
+/// Exit code a shell uses when the command it was asked to run is not found.
+const COMMAND_NOT_FOUND_EXIT: i32 = if cfg!(windows) { 9009 } else { 127 };
+
+/// Resolve the effective concurrency for a command: the CLI flag wins, then
+/// the per-command `concurrency` config, then the command's `default`.
+pub fn resolve_concurrency(cli: Option<usize>, config: Option<usize>, default: usize) -> usize {
+    cli.or(config).unwrap_or(default)
+}
+
+/// Upper bound for `auto` concurrency, so machines reporting very large core
+/// counts don't spawn hundreds of `pub`/`flutter` processes at once.
+pub const MAX_AUTO_CONCURRENCY: usize = 32;
+
+/// Parse a concurrency value: a positive number, `auto` (the available CPU
+/// count) or `auto/N` (the CPU count divided by `N`).
+///
+/// `auto` values are clamped to `1..=MAX_AUTO_CONCURRENCY`.
+pub fn parse_concurrency(value: &str) -> Result<usize, String> {
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    parse_concurrency_with_cpus(value, cpus)
+}
+
+/// [`parse_concurrency`] with an explicit CPU count.
+fn parse_concurrency_with_cpus(value: &str, cpus: usize) -> Result<usize, String> {
+    let value = value.trim();
+    if let Some(rest) = value.strip_prefix("auto") {
+        let divisor = match rest.strip_prefix('/') {
+            Some(d) => d.parse::<usize>().ok().filter(|&d| d > 0).ok_or_else(|| {
+                format!(
+                    "invalid divisor in '{}': expected auto/N with N >= 1",
+                    value
+                )
+            })?,
+            None if rest.is_empty() => 1,
+            None => return Err(format!("invalid concurrency '{}'", value)),
+        };
+        return Ok((cpus / divisor).clamp(1, MAX_AUTO_CONCURRENCY));
+    }
+    match value.parse::<usize>() {
+        Ok(0) => Err("concurrency must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!(
+            "invalid concurrency '{}': expected a number, auto or auto/N",
+            value
+        )),
+    }
+}
+
+/// Semaphores of the named concurrency groups, shared by every runner in the
+/// process so a group is serialized across the whole run.
+static CONCURRENCY_GROUPS: LazyLock<Mutex<HashMap<String, Arc<Semaphore>>>> =
+    LazyLock::new(Default::default);
+
+/// The single-permit semaphore of concurrency group `name`.
+fn concurrency_group_semaphore(name: &str) -> Arc<Semaphore> {
+    let mut groups = CONCURRENCY_GROUPS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    groups
+        .entry(name.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(1)))
+        .clone()
+}
+
 /// Process runner that executes shell commands in package directories
 /// with configurable concurrency and fail-fast behavior.
+///
+/// With fail-fast, the first failure stops new packages from starting and
+/// kills the commands still running; those packages are reported with
+/// [`Event::PackageAborted`]. Ctrl-C does the same for any run, which then
+/// returns an error.
 pub struct ProcessRunner {
     /// Maximum concurrent processes
     concurrency: usize,
     /// Whether to stop on first failure
     fail_fast: bool,
+    /// Retry policy applied to each package independently
+    retry: RetryPolicy,
+    /// Wall-time budget for the whole run (all packages)
+    deadline: Option<Duration>,
+    /// Directory relative to each package root to run the command in
+    working_directory: Option<PathBuf>,
+    /// Concurrency group semaphore: one package at a time across the run
+    group: Option<Arc<Semaphore>>,
+    /// Report packages whose command is not found as skipped, not failed
+    skip_missing: bool,
 }
 
 impl ProcessRunner {
@@ -35,6 +335,70 @@ impl ProcessRunner {
         Self {
             concurrency: concurrency.max(1),
             fail_fast,
+            retry: RetryPolicy::default(),
+            deadline: None,
+            working_directory: None,
+            group: None,
+            skip_missing: false,
+        }
+    }
+
+    /// Retry failed package commands according to `retry`.
+    ///
+    /// Only the final failed attempt counts towards fail-fast.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Bound the wall time of the entire run.
+    ///
+    /// Once the deadline passes, running processes are killed and packages
+    /// that have not started yet are reported as skipped.
+    pub fn with_deadline(mut self, deadline: Option<Duration>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Run each package's command in `dir`, relative to the package root,
+    /// instead of the root itself.
+    ///
+    /// `dir` must already be validated as relative and non-escaping (see
+    /// [`normalize_working_directory`](crate::config::script::normalize_working_directory)).
+    /// Packages without the directory are reported as skipped.
+    pub fn with_working_directory(mut self, dir: Option<PathBuf>) -> Self {
+        self.working_directory = dir;
+        self
+    }
+
+    /// Serialize package commands with every other runner in the same named
+    /// concurrency group.
+    ///
+    /// A group holds one permit for the whole process, so at most one package
+    /// of the group runs at a time. Each command still needs a slot under this
+    /// runner's `concurrency`. Blank names are ignored.
+    pub fn with_concurrency_group(mut self, group: Option<&str>) -> Self {
+        self.group = group
+            .filter(|name| !name.trim().is_empty())
+            .map(concurrency_group_semaphore);
+        self
+    }
+
+    /// Report packages whose command is not found (exit code 127, or a
+    /// spawn error) as skipped with reason `not found` instead of failed.
+    ///
+    /// Such packages are neither passed nor failed and never trigger fail-fast, which suits
+    /// optional tooling installed in only some packages.
+    pub fn with_skip_missing(mut self, skip_missing: bool) -> Self {
+        self.skip_missing = skip_missing;
+        self
+    }
+
+    /// Directory a package's command runs in.
+    fn package_dir(&self, pkg: &Package) -> PathBuf {
+        match self.working_directory {
+            Some(ref dir) => pkg.path.join(dir),
+            None => pkg.path.clone(),
         }
     }
 
@@ -49,7 +413,7 @@ impl ProcessRunner {
         env_vars: &HashMap<String, String>,
         timeout: Option<Duration>,
         all_packages: &[Package],
-    ) -> Result<Vec<(String, bool)>> {
+    ) -> Result<PackageResults> {
         self.run_in_packages_with_events(packages, command, env_vars, timeout, None, all_packages)
             .await
     }
@@ -65,7 +429,10 @@ impl ProcessRunner {
     /// `all_packages` is the full workspace package list, used for parent package detection.
     /// If empty, parent package env vars are not set.
     ///
-    /// Returns a vec of (package_name, success) results.
+    /// Returns the per-package results; packages that never ran (fail-fast,
+    /// deadline, missing working directory, command not found) are listed as
+    /// skipped, and packages killed by fail-fast as aborted. Errors if the run
+    /// was interrupted.
     pub async fn run_in_packages_with_events(
         &self,
         packages: &[Package],
@@ -74,184 +441,229 @@ impl ProcessRunner {
         timeout: Option<Duration>,
         events: Option<&UnboundedSender<Event>>,
         all_packages: &[Package],
-    ) -> Result<Vec<(String, bool)>> {
+    ) -> Result<PackageResults> {
         let semaphore = std::sync::Arc::new(Semaphore::new(self.concurrency));
-        let results = std::sync::Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let results = std::sync::Arc::new(tokio::sync::Mutex::new(PackageResults::default()));
         let failed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let deadline_at = self.deadline.map(|d| Instant::now() + d);
+        let (abort_tx, abort_rx) = watch::channel(None);
+        let abort_tx = std::sync::Arc::new(abort_tx);
+        let _interrupt = InterruptRegistration::new(abort_tx.clone());
 
         let mut handles = Vec::new();
 
-        for pkg in packages {
+        for (i, pkg) in packages.iter().enumerate() {
             if self.fail_fast && failed.load(std::sync::atomic::Ordering::Relaxed) {
+                let tx = events.cloned();
+                let mut results = results.lock().await;
+                for rest in &packages[i..] {
+                    emit(
+                        &tx,
+                        Event::PackageSkipped {
+                            name: rest.name.clone(),
+                            reason: "fail-fast".to_string(),
+                        },
+                    );
+                    results
+                        .skipped
+                        .push((rest.name.clone(), "fail-fast".to_string()));
+                }
                 break;
             }
 
             let sem = semaphore.clone();
             let results = results.clone();
             let failed = failed.clone();
+            let abort_tx = abort_tx.clone();
             let fail_fast = self.fail_fast;
-            let command = command.to_string();
-            let pkg_name = pkg.name.clone();
-            let pkg_path = pkg.path.clone();
-            let tx = events.cloned();
-
-            let env = build_package_env(env_vars, pkg, all_packages);
-
+            let job = PackageJob {
+                command: command.to_string(),
+                name: pkg.name.clone(),
+                path: self.package_dir(pkg),
+                working_directory: self.working_directory.clone(),
+                env: build_package_env(env_vars, pkg, all_packages),
+                timeout,
+                deadline_at,
+                retry: self.retry,
+                abort: abort_rx.clone(),
+                skip_missing: self.skip_missing,
+                tx: events.cloned(),
+            };
+
+            let group = self.group.clone();
             let handle = tokio::spawn(async move {
+                // Take the group permit first, so packages waiting on their
+                // group don't hold concurrency slots others could use.
+                let _group_permit = acquire_group(group).await;
                 // safety: the semaphore is never closed, so acquire always succeeds
                 let _permit = sem.acquire().await.expect("semaphore closed unexpectedly");
 
-                // Skip if already failed and fail-fast is enabled
-                if fail_fast && failed.load(std::sync::atomic::Ordering::Relaxed) {
-                    results.lock().await.push((pkg_name.clone(), false));
-                    return;
-                }
+                let outcome = if fail_fast && failed.load(std::sync::atomic::Ordering::Relaxed) {
+                    // Skip if already failed and fail-fast is enabled
+                    job.skip("fail-fast")
+                } else if job.interrupted() {
+                    job.skip("interrupted")
+                } else if job.deadline_passed() {
+                    // Skip if the command-wide deadline has already passed
+                    job.skip("deadline")
+                } else if let Some(reason) = job.missing_working_directory() {
+                    job.skip(&reason)
+                } else {
+                    let outcome = job.run().await;
+                    if outcome == JobOutcome::Finished(false) {
+                        failed.store(true, std::sync::atomic::Ordering::Relaxed);
+                        if fail_fast {
+                            request_abort(&abort_tx, AbortReason::FailFast);
+                        }
+                    }
+                    outcome
+                };
 
-                emit(
-                    &tx,
-                    Event::PackageStarted {
-                        name: pkg_name.clone(),
-                    },
-                );
-
-                let start = std::time::Instant::now();
-                let (shell, shell_flag) = shell_command();
-                let child = tokio::process::Command::new(shell)
-                    .arg(shell_flag)
-                    .arg(&command)
-                    .current_dir(&pkg_path)
-                    .envs(&env)
-                    .stdin(std::process::Stdio::null())
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-                    .spawn();
-
-                let success = match child {
-                    Ok(mut child) => {
-                        // Take stdout/stderr handles for streaming.
-                        // safety: we set Stdio::piped() above so these are always Some
-                        let stdout = child.stdout.take().expect("stdout piped");
-                        let stderr = child.stderr.take().expect("stderr piped");
-
-                        let stdout_tx = tx.clone();
-                        let stderr_tx = tx.clone();
-                        let stdout_name = pkg_name.clone();
-                        let stderr_name = pkg_name.clone();
-
-                        // Stream stdout lines as they arrive.
-                        let stdout_task = tokio::spawn(async move {
-                            let reader = BufReader::new(stdout);
-                            let mut lines = reader.lines();
-                            while let Ok(Some(line)) = lines.next_line().await {
-                                emit(
-                                    &stdout_tx,
-                                    Event::PackageOutput {
-                                        name: stdout_name.clone(),
-                                        line,
-                                        is_stderr: false,
-                                    },
-                                );
-                            }
-                        });
-
-                        // Stream stderr lines as they arrive.
-                        let stderr_task = tokio::spawn(async move {
-                            let reader = BufReader::new(stderr);
-                            let mut lines = reader.lines();
-                            while let Ok(Some(line)) = lines.next_line().await {
-                                emit(
-                                    &stderr_tx,
-                                    Event::PackageOutput {
-                                        name: stderr_name.clone(),
-                                        line,
-                                        is_stderr: true,
-                                    },
-                                );
-                            }
-                        });
-
-                        // Wait for the process to exit, optionally with a timeout.
-                        let status = if let Some(dur) = timeout {
-                            match tokio::time::timeout(dur, child.wait()).await {
-                                Ok(Ok(s)) => Some(s),
-                                Ok(Err(e)) => {
-                                    emit(
-                                        &tx,
-                                        Event::PackageOutput {
-                                            name: pkg_name.clone(),
-                                            line: format!("ERROR: {}", e),
-                                            is_stderr: true,
-                                        },
-                                    );
-                                    None
-                                }
-                                Err(_) => {
-                                    emit(
-                                        &tx,
-                                        Event::PackageOutput {
-                                            name: pkg_name.clone(),
-                                            line: format!(
-                                                "TIMEOUT: timed out after {}s",
-                                                dur.as_secs()
-                                            ),
-                                            is_stderr: true,
-                                        },
-                                    );
-                                    None
-                                }
-                            }
-                        } else {
-                            match child.wait().await {
-                                Ok(s) => Some(s),
-                                Err(e) => {
-                                    emit(
-                                        &tx,
-                                        Event::PackageOutput {
-                                            name: pkg_name.clone(),
-                                            line: format!("ERROR: {}", e),
-                                            is_stderr: true,
-                                        },
-                                    );
-                                    None
-                                }
-                            }
-                        };
+                outcome.record(job.name, &mut *results.lock().await);
+            });
 
-                        // Ensure streaming tasks finish before we emit PackageFinished.
-                        let _ = stdout_task.await;
-                        let _ = stderr_task.await;
+            handles.push(handle);
+        }
 
-                        status.is_some_and(|s| s.success())
-                    }
-                    Err(e) => {
-                        emit(
-                            &tx,
-                            Event::PackageOutput {
-                                name: pkg_name.clone(),
-                                line: format!("ERROR: {}", e),
-                                is_stderr: true,
-                            },
-                        );
-                        false
-                    }
-                };
+        // Wait for all tasks to complete
+        for handle in handles {
+            handle.await.context("Package task panicked")?;
+        }
+
+        if *abort_rx.borrow() == Some(AbortReason::Interrupted) {
+            bail!("Interrupted");
+        }
+
+        let results = results.lock().await;
+        Ok(results.clone())
+    }
+
+    /// Run a command in each package in dependency order, emitting events.
+    ///
+    /// A package is only started once every package it depends on (through
+    /// `dependencies` or `dev_dependencies`, among `packages`) has finished
+    /// successfully; independent packages still run concurrently up to the
+    /// concurrency limit. Dependents of a failed or skipped package are
+    /// reported as skipped. Errors up front, naming the packages involved, if the
+    /// dependency graph has a cycle.
+    ///
+    /// Other parameters behave as in [`run_in_packages_with_events`].
+    pub async fn run_in_packages_ordered(
+        &self,
+        packages: &[Package],
+        command: &str,
+        env_vars: &HashMap<String, String>,
+        timeout: Option<Duration>,
+        events: Option<&UnboundedSender<Event>>,
+        all_packages: &[Package],
+    ) -> Result<PackageResults> {
+        if let Some(cycle) = find_dependency_cycle(packages) {
+            bail!("Dependency cycle detected: {}", cycle.join(" -> "));
+        }
+
+        let semaphore = std::sync::Arc::new(Semaphore::new(self.concurrency));
+        let results = std::sync::Arc::new(tokio::sync::Mutex::new(PackageResults::default()));
+        let failed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let deadline_at = self.deadline.map(|d| Instant::now() + d);
+        let (abort_tx, abort_rx) = watch::channel(None);
+        let abort_tx = std::sync::Arc::new(abort_tx);
+        let _interrupt = InterruptRegistration::new(abort_tx.clone());
+
+        // One completion channel per package: `None` while pending, then
+        // `Some(success)` once it finished or was skipped.
+        let mut senders = HashMap::new();
+        let mut receivers = HashMap::new();
+        for pkg in packages {
+            let (tx, rx) = watch::channel(None::<bool>);
+            senders.insert(pkg.name.as_str(), tx);
+            receivers.insert(pkg.name.as_str(), rx);
+        }
 
-                let duration = start.elapsed();
+        let mut handles = Vec::new();
 
-                emit(
-                    &tx,
-                    Event::PackageFinished {
-                        name: pkg_name.clone(),
-                        success,
-                        duration,
-                    },
-                );
+        for pkg in topological_sort(packages) {
+            let Some(done) = senders.remove(pkg.name.as_str()) else {
+                continue;
+            };
+            let deps: Vec<(String, watch::Receiver<Option<bool>>)> = pkg
+                .dependencies
+                .iter()
+                .chain(pkg.dev_dependencies.iter())
+                .filter_map(|dep| {
+                    receivers
+                        .get(dep.as_str())
+                        .map(|rx| (dep.clone(), rx.clone()))
+                })
+                .collect();
 
-                if !success {
-                    failed.store(true, std::sync::atomic::Ordering::Relaxed);
+            let sem = semaphore.clone();
+            let results = results.clone();
+            let failed = failed.clone();
+            let abort_tx = abort_tx.clone();
+            let fail_fast = self.fail_fast;
+            let job = PackageJob {
+                command: command.to_string(),
+                name: pkg.name.clone(),
+                path: self.package_dir(&pkg),
+                working_directory: self.working_directory.clone(),
+                env: build_package_env(env_vars, &pkg, all_packages),
+                timeout,
+                deadline_at,
+                retry: self.retry,
+                abort: abort_rx.clone(),
+                skip_missing: self.skip_missing,
+                tx: events.cloned(),
+            };
+
+            let group = self.group.clone();
+            let handle = tokio::spawn(async move {
+                // Wait for dependencies before taking a permit, so waiting
+                // packages never starve the ones they are waiting on.
+                let mut failed_dep = None;
+                for (dep, mut rx) in deps {
+                    let dep_ok = matches!(
+                        rx.wait_for(Option::is_some).await.map(|s| *s),
+                        Ok(Some(true))
+                    );
+                    if !dep_ok {
+                        failed_dep = Some(dep);
+                        break;
+                    }
                 }
 
-                results.lock().await.push((pkg_name, success));
+                // Dependents may start only if the command ran and did not
+                // fail; a command skipped as not found counts as passing.
+                let (outcome, unblocks) = if let Some(dep) = failed_dep {
+                    (job.skip(&format!("dependency {} failed", dep)), false)
+                } else {
+                    let _group_permit = acquire_group(group).await;
+                    // safety: the semaphore is never closed, so acquire always succeeds
+                    let _permit = sem.acquire().await.expect("semaphore closed unexpectedly");
+
+                    if fail_fast && failed.load(std::sync::atomic::Ordering::Relaxed) {
+                        (job.skip("fail-fast"), false)
+                    } else if job.interrupted() {
+                        (job.skip("interrupted"), false)
+                    } else if job.deadline_passed() {
+                        (job.skip("deadline"), false)
+                    } else if let Some(reason) = job.missing_working_directory() {
+                        (job.skip(&reason), false)
+                    } else {
+                        let outcome = job.run().await;
+                        if outcome == JobOutcome::Finished(false) {
+                            failed.store(true, std::sync::atomic::Ordering::Relaxed);
+                            if fail_fast {
+                                request_abort(&abort_tx, AbortReason::FailFast);
+                            }
+                        }
+                        let unblocks =
+                            matches!(outcome, JobOutcome::Finished(true) | JobOutcome::Skipped(_));
+                        (outcome, unblocks)
+                    }
+                };
+
+                let _ = done.send(Some(unblocks));
+                outcome.record(job.name, &mut *results.lock().await);
             });
 
             handles.push(handle);
@@ -262,14 +674,369 @@ impl ProcessRunner {
             handle.await.context("Package task panicked")?;
         }
 
+        if *abort_rx.borrow() == Some(AbortReason::Interrupted) {
+            bail!("Interrupted");
+        }
+
         let results = results.lock().await;
         Ok(results.clone())
     }
 }
 
+/// Wait for the concurrency group's permit, if the runner has a group.
+async fn acquire_group(group: Option<Arc<Semaphore>>) -> Option<tokio::sync::OwnedSemaphorePermit> {
+    match group {
+        // safety: group semaphores are never closed, so acquire always succeeds
+        Some(sem) => Some(
+            sem.acquire_owned()
+                .await
+                .expect("semaphore closed unexpectedly"),
+        ),
+        None => None,
+    }
+}
+
+/// A single package's command invocation, shared by the scheduling modes of
+/// [`ProcessRunner`].
+struct PackageJob {
+    command: String,
+    name: String,
+    /// Directory the command runs in (package root joined with `working_directory`)
+    path: PathBuf,
+    working_directory: Option<PathBuf>,
+    env: HashMap<String, String>,
+    timeout: Option<Duration>,
+    deadline_at: Option<Instant>,
+    retry: RetryPolicy,
+    /// Abort signal; set on Ctrl-C, or when another package fails under fail-fast
+    abort: watch::Receiver<Option<AbortReason>>,
+    skip_missing: bool,
+    tx: Option<UnboundedSender<Event>>,
+}
+
+impl PackageJob {
+    /// Report the package as skipped without running it.
+    fn skip(&self, reason: &str) -> JobOutcome {
+        emit(
+            &self.tx,
+            Event::PackageSkipped {
+                name: self.name.clone(),
+                reason: reason.to_string(),
+            },
+        );
+        JobOutcome::Skipped(reason.to_string())
+    }
+
+    /// Whether the user pressed Ctrl-C during the run.
+    fn interrupted(&self) -> bool {
+        *self.abort.borrow() == Some(AbortReason::Interrupted)
+    }
+
+    /// Whether the command-wide deadline has already passed.
+    fn deadline_passed(&self) -> bool {
+        self.deadline_at.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// Skip reason when the package lacks the configured working directory.
+    fn missing_working_directory(&self) -> Option<String> {
+        let dir = self.working_directory.as_ref()?;
+        (!self.path.is_dir()).then(|| format!("no {}/", dir.display()))
+    }
+
+    /// Run the command (with retries), emitting started/finished events, or
+    /// an aborted event if fail-fast or Ctrl-C killed it.
+    ///
+    /// With `skip_missing`, a command that is not found ends the package with
+    /// a skipped event instead, and the package is skipped.
+    async fn run(&self) -> JobOutcome {
+        let tx = &self.tx;
+        let pkg_name = &self.name;
+        let retry = self.retry;
+        let deadline_at = self.deadline_at;
+
+        emit(
+            tx,
+            Event::PackageStarted {
+                name: pkg_name.clone(),
+            },
+        );
+
+        let start = Instant::now();
+        let mut attempt = 0;
+        let success = loop {
+            let outcome = run_attempt(self).await;
+
+            match outcome {
+                AttemptOutcome::Success => break true,
+                AttemptOutcome::NotFound if self.skip_missing => {
+                    return self.skip("not found");
+                }
+                AttemptOutcome::DeadlineExceeded => break false,
+                AttemptOutcome::Aborted(reason) => {
+                    emit(
+                        tx,
+                        Event::PackageAborted {
+                            name: pkg_name.clone(),
+                            reason: reason.as_str().to_string(),
+                            duration: start.elapsed(),
+                        },
+                    );
+                    return JobOutcome::Aborted;
+                }
+                AttemptOutcome::Failed | AttemptOutcome::NotFound
+                    if attempt < retry.max_retries =>
+                {
+                    attempt += 1;
+                    let delay = retry.delay_for(attempt);
+                    if deadline_at.is_some_and(|d| Instant::now() + delay >= d) {
+                        break false;
+                    }
+                    emit(
+                        tx,
+                        Event::PackageRetry {
+                            name: pkg_name.clone(),
+                            attempt: attempt + 1,
+                            max_attempts: retry.max_retries + 1,
+                            delay,
+                        },
+                    );
+                    tokio::time::sleep(delay).await;
+                    if self.abort.borrow().is_some() {
+                        break false;
+                    }
+                }
+                AttemptOutcome::Failed | AttemptOutcome::NotFound => break false,
+            }
+        };
+
+        emit(
+            tx,
+            Event::PackageFinished {
+                name: pkg_name.clone(),
+                success,
+                duration: start.elapsed(),
+            },
+        );
+
+        JobOutcome::Finished(success)
+    }
+}
+
+/// Which limit bounds a single attempt's wait on the child process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaitLimit {
+    Timeout(Duration),
+    Deadline(Duration),
+}
+
+/// Pick the tighter of the per-package timeout and the remaining deadline.
+fn wait_limit(timeout: Option<Duration>, deadline_at: Option<Instant>) -> Option<WaitLimit> {
+    let remaining = deadline_at.map(|d| d.saturating_duration_since(Instant::now()));
+    match (timeout, remaining) {
+        (Some(t), Some(r)) if r < t => Some(WaitLimit::Deadline(r)),
+        (Some(t), _) => Some(WaitLimit::Timeout(t)),
+        (None, Some(r)) => Some(WaitLimit::Deadline(r)),
+        (None, None) => None,
+    }
+}
+
+/// Resolve with the reason once `abort` is set; never resolves without a signal.
+async fn abort_requested(mut abort: watch::Receiver<Option<AbortReason>>) -> AbortReason {
+    if let Ok(reason) = abort.wait_for(Option::is_some).await
+        && let Some(reason) = *reason
+    {
+        return reason;
+    }
+    std::future::pending().await
+}
+
+/// Kill the command's whole process tree and reap the shell.
+///
+/// On Unix the shell leads its own process group, so signalling the group
+/// also kills the grandchildren it spawned (a plain kill would orphan them).
+async fn kill_process_tree(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // safety: killpg only sends a signal; the group is the one the shell
+        // leads (see `process_group(0)` in `run_attempt`), and the shell is
+        // not reaped yet, so its id cannot have been reused
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    // kill() also waits for the process, so it is reaped
+    let _ = child.kill().await;
+}
+
+/// Spawn the job's command once, stream its output, and wait for it to exit
+/// (bounded by the per-package timeout and the run deadline).
+///
+/// The command runs in its own process group on Unix. If it times out or an
+/// abort is signalled first, the whole group is killed and the shell reaped,
+/// so neither orphans nor zombies are left behind.
+async fn run_attempt(job: &PackageJob) -> AttemptOutcome {
+    let pkg_name = job.name.as_str();
+    let timeout = job.timeout;
+    let tx = &job.tx;
+    let (shell, shell_flag) = shell_command();
+    let mut command = tokio::process::Command::new(shell);
+    command
+        .arg(shell_flag)
+        .arg(&job.command)
+        .current_dir(&job.path)
+        .envs(&job.env)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    #[cfg(unix)]
+    command.process_group(0);
+    let child = command.spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            emit_line(tx, pkg_name, format!("ERROR: {}", e));
+            return if e.kind() == std::io::ErrorKind::NotFound {
+                AttemptOutcome::NotFound
+            } else {
+                AttemptOutcome::Failed
+            };
+        }
+    };
+
+    // Take stdout/stderr handles for streaming.
+    // safety: we set Stdio::piped() above so these are always Some
+    let stdout = child.stdout.take().expect("stdout piped");
+    let stderr = child.stderr.take().expect("stderr piped");
+
+    // Stream stdout and stderr lines as they arrive.
+    let stdout_task = spawn_line_reader(stdout, tx.clone(), pkg_name.to_string(), false);
+    let stderr_task = spawn_line_reader(stderr, tx.clone(), pkg_name.to_string(), true);
+
+    // Wait for the process to exit, optionally bounded by a timeout or deadline.
+    let limit = wait_limit(timeout, job.deadline_at);
+    let wait = async {
+        match limit {
+            Some(WaitLimit::Timeout(dur)) | Some(WaitLimit::Deadline(dur)) => {
+                tokio::time::timeout(dur, child.wait()).await.ok()
+            }
+            None => Some(child.wait().await),
+        }
+    };
+    let waited = tokio::select! {
+        waited = wait => Ok(waited),
+        reason = abort_requested(job.abort.clone()) => Err(reason),
+    };
+    let waited = match waited {
+        Ok(waited) => waited,
+        Err(reason) => {
+            kill_process_tree(&mut child).await;
+            stdout_task.abort();
+            stderr_task.abort();
+            let message = match reason {
+                AbortReason::FailFast => "ABORTED: killed after another package failed",
+                AbortReason::Interrupted => "ABORTED: interrupted",
+            };
+            emit_line(tx, pkg_name, message.to_string());
+            return AttemptOutcome::Aborted(reason);
+        }
+    };
+
+    let outcome = match waited {
+        Some(Ok(status)) if status.success() => AttemptOutcome::Success,
+        Some(Ok(status)) if status.code() == Some(COMMAND_NOT_FOUND_EXIT) => {
+            AttemptOutcome::NotFound
+        }
+        Some(Ok(_)) => AttemptOutcome::Failed,
+        Some(Err(e)) => {
+            emit_line(tx, pkg_name, format!("ERROR: {}", e));
+            AttemptOutcome::Failed
+        }
+        None => {
+            kill_process_tree(&mut child).await;
+            // Stop reading rather than waiting for EOF, in case a process
+            // outside the group still holds the pipes open.
+            stdout_task.abort();
+            stderr_task.abort();
+            return match limit {
+                Some(WaitLimit::Deadline(_)) => {
+                    emit_line(
+                        tx,
+                        pkg_name,
+                        "DEADLINE: command deadline exceeded".to_string(),
+                    );
+                    AttemptOutcome::DeadlineExceeded
+                }
+                _ => {
+                    let secs = timeout.map(|t| t.as_secs()).unwrap_or_default();
+                    emit_line(tx, pkg_name, format!("TIMEOUT: timed out after {}s", secs));
+                    AttemptOutcome::Failed
+                }
+            };
+        }
+    };
+
+    // Ensure streaming tasks finish before we emit PackageFinished.
+    let _ = stdout_task.await;
+    let _ = stderr_task.await;
+
+    outcome
+}
+
+/// Spawn a task that forwards each line from `reader` as a `PackageOutput` event.
+fn spawn_line_reader<R>(
+    reader: R,
+    tx: Option<UnboundedSender<Event>>,
+    name: String,
+    is_stderr: bool,
+) -> tokio::task::JoinHandle<()>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            emit(
+                &tx,
+                Event::PackageOutput {
+                    name: name.clone(),
+                    line,
+                    is_stderr,
+                },
+            );
+        }
+    })
+}
+
+/// Emit a runner-generated diagnostic line (on stderr) for a package.
+fn emit_line(tx: &Option<UnboundedSender<Event>>, name: &str, line: String) {
+    emit(
+        tx,
+        Event::PackageOutput {
+            name: name.to_string(),
+            line,
+            is_stderr: true,
+        },
+    );
+}
+
 /// Send an event if the transmitter is present, ignoring send errors
 /// (the receiver may have been dropped).
+///
+/// Output lines pass through the installed redactor first.
 fn emit(tx: &Option<UnboundedSender<Event>>, event: Event) {
+    let event = match event {
+        Event::PackageOutput {
+            name,
+            line,
+            is_stderr,
+        } => Event::PackageOutput {
+            name,
+            line: crate::redact::redact_output(line),
+            is_stderr,
+        },
+        event => event,
+    };
     if let Some(tx) = tx {
         let _ = tx.send(event);
     }
@@ -278,6 +1045,9 @@ fn emit(tx: &Option<UnboundedSender<Event>>, event: Event) {
 /// Build environment variables for a specific package, merging workspace-level
 /// vars with per-package Melos env vars.
 ///
+/// `MELOS_PACKAGE_IS_FLUTTER` is `true`/`false`, and `MELOS_PACKAGE_DEPENDENCIES`
+/// lists the package's in-workspace dependencies (regular and dev), comma-separated.
+///
 /// Also sets `MELOS_PARENT_PACKAGE_*` vars when the package is an "example"
 /// child of another workspace package (name ends with `example` and its path
 /// is a subdirectory of the parent's path).
@@ -295,6 +1065,20 @@ fn build_package_env(
     if let Some(ref version) = pkg.version {
         env.insert("MELOS_PACKAGE_VERSION".to_string(), version.clone());
     }
+    env.insert(
+        "MELOS_PACKAGE_IS_FLUTTER".to_string(),
+        pkg.is_flutter.to_string(),
+    );
+    let mut workspace_deps: Vec<&str> = Vec::new();
+    for dep in pkg.dependencies.iter().chain(&pkg.dev_dependencies) {
+        if all_packages.iter().any(|p| &p.name == dep) && !workspace_deps.contains(&dep.as_str()) {
+            workspace_deps.push(dep);
+        }
+    }
+    env.insert(
+        "MELOS_PACKAGE_DEPENDENCIES".to_string(),
+        workspace_deps.join(","),
+    );
 
     // Detect parent package: if the current package name ends with "example"
     // and its directory is a child of another package's directory, set parent env vars.
@@ -352,7 +1136,7 @@ fn find_parent_package<'a>(pkg: &Package, all_packages: &'a [Package]) -> Option
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     fn make_pkg(name: &str, path: &str) -> Package {
         Package {
@@ -364,10 +1148,44 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         }
     }
 
+    // -- resolve_concurrency tests --
+
+    #[test]
+    fn test_resolve_concurrency_precedence() {
+        assert_eq!(resolve_concurrency(Some(2), Some(8), 5), 2);
+        assert_eq!(resolve_concurrency(None, Some(8), 5), 8);
+        assert_eq!(resolve_concurrency(None, None, 5), 5);
+    }
+
+    #[test]
+    fn test_parse_concurrency_numbers_and_auto() {
+        assert_eq!(parse_concurrency_with_cpus("4", 8), Ok(4));
+        assert_eq!(parse_concurrency_with_cpus("auto", 8), Ok(8));
+        assert_eq!(parse_concurrency_with_cpus("auto/2", 8), Ok(4));
+        assert_eq!(parse_concurrency_with_cpus("auto/3", 8), Ok(2));
+        // Never below 1, never above the cap
+        assert_eq!(parse_concurrency_with_cpus("auto/4", 2), Ok(1));
+        assert_eq!(
+            parse_concurrency_with_cpus("auto", 256),
+            Ok(MAX_AUTO_CONCURRENCY)
+        );
+        assert!(parse_concurrency("auto").is_ok_and(|n| n >= 1));
+    }
+
+    #[test]
+    fn test_parse_concurrency_rejects_invalid_values() {
+        assert!(parse_concurrency_with_cpus("0", 8).is_err());
+        assert!(parse_concurrency_with_cpus("auto/0", 8).is_err());
+        assert!(parse_concurrency_with_cpus("auto/x", 8).is_err());
+        assert!(parse_concurrency_with_cpus("automatic", 8).is_err());
+        assert!(parse_concurrency_with_cpus("many", 8).is_err());
+    }
+
     // -- shell_command tests --
 
     #[test]
@@ -382,6 +1200,587 @@ mod tests {
         }
     }
 
+    // -- RetryPolicy tests --
+
+    #[test]
+    fn test_retry_backoff_delay_increases_per_attempt() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            backoff: Duration::from_millis(100),
+            jitter: false,
+        };
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_delay(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay_for(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retry_backoff_delay_zero_base() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay_for(2), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_apply_jitter_stays_within_upper_half() {
+        let delay = Duration::from_millis(400);
+        for seed in [0, 1, 57, 199, 200, 201, u64::MAX] {
+            let jittered = apply_jitter(delay, seed);
+            assert!(jittered >= Duration::from_millis(200), "{jittered:?}");
+            assert!(jittered <= delay, "{jittered:?}");
+        }
+    }
+
+    #[test]
+    fn test_wait_limit_prefers_tighter_bound() {
+        let far = Some(Instant::now() + Duration::from_secs(60));
+        assert_eq!(
+            wait_limit(Some(Duration::from_secs(1)), far),
+            Some(WaitLimit::Timeout(Duration::from_secs(1)))
+        );
+        assert!(matches!(
+            wait_limit(Some(Duration::from_secs(120)), far),
+            Some(WaitLimit::Deadline(_))
+        ));
+        assert_eq!(wait_limit(None, None), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_retry_reruns_failed_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = make_pkg("flaky", dir.path().to_str().unwrap());
+        let runner = ProcessRunner::new(1, false).with_retry(RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(10),
+            jitter: false,
+        });
+
+        // Fails on the first attempt, succeeds once the marker exists.
+        let results = runner
+            .run_in_packages(
+                &[pkg],
+                "test -f marker || { touch marker; exit 1; }",
+                &HashMap::new(),
+                None,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.results, vec![("flaky".to_string(), true)]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_retry_emits_retry_events_until_final_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = make_pkg("broken", dir.path().to_str().unwrap());
+        let runner = ProcessRunner::new(1, true).with_retry(RetryPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(5),
+            jitter: false,
+        });
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let results = runner
+            .run_in_packages_with_events(&[pkg], "exit 1", &HashMap::new(), None, Some(&tx), &[])
+            .await
+            .unwrap();
+        drop(tx);
+        assert_eq!(results.results, vec![("broken".to_string(), false)]);
+
+        let mut retries = Vec::new();
+        let mut finished = 0;
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageRetry {
+                    attempt,
+                    max_attempts,
+                    delay,
+                    ..
+                } => retries.push((attempt, max_attempts, delay)),
+                Event::PackageFinished { success, .. } => {
+                    assert!(!success);
+                    finished += 1;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            retries,
+            vec![
+                (2, 3, Duration::from_millis(5)),
+                (3, 3, Duration::from_millis(10))
+            ]
+        );
+        assert_eq!(finished, 1, "only the final failure is reported");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_output_lines_are_redacted_before_emitting() {
+        // The redactor is process-global; this pattern matches nothing else.
+        crate::redact::install(
+            crate::redact::Redactor::new(&["runner-secret-[0-9]+".to_string()], &[]).unwrap(),
+        );
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = make_pkg("leaky", dir.path().to_str().unwrap());
+        let runner = ProcessRunner::new(1, false);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        runner
+            .run_in_packages_with_events(
+                &[pkg],
+                "echo token runner-secret-42; echo runner-secret-7 >&2",
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+
+        let mut lines = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let Event::PackageOutput { line, .. } = event {
+                lines.push(line);
+            }
+        }
+        lines.sort();
+        assert_eq!(lines, vec!["***".to_string(), "token ***".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_deadline_stops_further_dispatch() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages: Vec<Package> = ["a", "b", "c"]
+            .iter()
+            .map(|name| make_pkg(name, dir.path().to_str().unwrap()))
+            .collect();
+        let runner = ProcessRunner::new(1, false).with_deadline(Some(Duration::from_millis(200)));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let start = Instant::now();
+        let results = runner
+            .run_in_packages_with_events(
+                &packages,
+                "sleep 5",
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+
+        assert!(start.elapsed() < Duration::from_secs(4));
+        assert_eq!(results.results, vec![("a".to_string(), false)]);
+        assert_eq!(
+            results.skipped,
+            vec![
+                ("b".to_string(), "deadline".to_string()),
+                ("c".to_string(), "deadline".to_string()),
+            ]
+        );
+
+        let mut started = 0;
+        let mut skipped = 0;
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageStarted { .. } => started += 1,
+                Event::PackageSkipped { reason, .. } if reason == "deadline" => skipped += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(started, 1, "only the first package should be dispatched");
+        assert_eq!(skipped, 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_skip_missing_reports_not_found_without_failing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages: Vec<Package> = ["a", "b", "c"]
+            .iter()
+            .map(|name| make_pkg(name, dir.path().to_str().unwrap()))
+            .collect();
+        let command =
+            r#"if [ "$MELOS_PACKAGE_NAME" = a ]; then melos_rs_missing_tool; else true; fi"#;
+
+        // Without the option, a missing command is an ordinary failure
+        let results = ProcessRunner::new(1, false)
+            .run_in_packages(&packages, command, &HashMap::new(), None, &[])
+            .await
+            .unwrap();
+        assert!(results.results.contains(&("a".to_string(), false)));
+
+        let runner = ProcessRunner::new(1, true).with_skip_missing(true);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let results = runner
+            .run_in_packages_with_events(&packages, command, &HashMap::new(), None, Some(&tx), &[])
+            .await
+            .unwrap();
+        drop(tx);
+
+        // Fail-fast is not triggered: the other packages still run
+        assert_eq!(results.passed(), 2);
+        assert_eq!(results.failed(), 0);
+        assert_eq!(
+            results.skipped,
+            vec![("a".to_string(), "not found".to_string())]
+        );
+        let mut finished = Vec::new();
+        let mut skipped = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageFinished { name, .. } => finished.push(name),
+                Event::PackageSkipped { name, reason } => skipped.push((name, reason)),
+                _ => {}
+            }
+        }
+        assert_eq!(skipped, vec![("a".to_string(), "not found".to_string())]);
+        finished.sort();
+        assert_eq!(finished, vec!["b", "c"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fail_fast_emits_skipped_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages: Vec<Package> = ["a", "b", "c"]
+            .iter()
+            .map(|name| make_pkg(name, dir.path().to_str().unwrap()))
+            .collect();
+        let runner = ProcessRunner::new(1, true);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let results = runner
+            .run_in_packages_with_events(&packages, "exit 1", &HashMap::new(), None, Some(&tx), &[])
+            .await
+            .unwrap();
+        drop(tx);
+
+        // Skipped packages are not counted as failures
+        assert_eq!(results.failed(), 1);
+        assert_eq!(results.skipped(), 2);
+
+        let mut finished = 0;
+        let mut skipped = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageFinished { .. } => finished += 1,
+                Event::PackageSkipped { name, reason } => {
+                    assert_eq!(reason, "fail-fast");
+                    skipped.push(name);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(finished, 1);
+        skipped.sort();
+        assert_eq!(skipped, vec!["b", "c"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fail_fast_aborts_running_packages() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages: Vec<Package> = ["a", "b"]
+            .iter()
+            .map(|name| make_pkg(name, dir.path().to_str().unwrap()))
+            .collect();
+        let runner = ProcessRunner::new(2, true);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // `b` backgrounds a subshell, which only dies if its group is killed
+        let start = Instant::now();
+        let results = runner
+            .run_in_packages_with_events(
+                &packages,
+                r#"if [ "$MELOS_PACKAGE_NAME" = a ]; then sleep 0.2; exit 1; else (sleep 1; touch marker) & wait; fi"#,
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(results.results, vec![("a".to_string(), false)]);
+        assert_eq!(results.aborted, vec!["b".to_string()]);
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(
+            !dir.path().join("marker").exists(),
+            "grandchild outlived the aborted package"
+        );
+
+        let mut finished = Vec::new();
+        let mut aborted = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageFinished { name, .. } => finished.push(name),
+                Event::PackageAborted { name, reason, .. } => {
+                    assert_eq!(reason, "fail-fast");
+                    aborted.push(name);
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(finished, vec!["a"]);
+        assert_eq!(aborted, vec!["b"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_grandchildren() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages = vec![make_pkg("a", dir.path().to_str().unwrap())];
+
+        let results = ProcessRunner::new(1, false)
+            .run_in_packages(
+                &packages,
+                "(sleep 1; touch marker) & wait",
+                &HashMap::new(),
+                Some(Duration::from_millis(200)),
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.results, vec![("a".to_string(), false)]);
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(
+            !dir.path().join("marker").exists(),
+            "grandchild outlived the timed-out package"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_deadline_kills_grandchildren() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages = vec![make_pkg("a", dir.path().to_str().unwrap())];
+
+        let results = ProcessRunner::new(1, false)
+            .with_deadline(Some(Duration::from_millis(200)))
+            .run_in_packages(
+                &packages,
+                "(sleep 1; touch marker) & wait",
+                &HashMap::new(),
+                None,
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.results, vec![("a".to_string(), false)]);
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(
+            !dir.path().join("marker").exists(),
+            "grandchild outlived the command deadline"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_concurrency_group_serializes_across_runners() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let first: Vec<Package> = ["a", "b"].iter().map(|n| make_pkg(n, path)).collect();
+        let second = vec![make_pkg("c", path)];
+        // `mkdir` fails if another package holds the lock directory
+        let cmd = "mkdir held || exit 1; sleep 0.2; rmdir held";
+        let env = HashMap::new();
+
+        let runner_a = ProcessRunner::new(4, false).with_concurrency_group(Some("emulator-test"));
+        let runner_b = ProcessRunner::new(4, false).with_concurrency_group(Some("emulator-test"));
+        let (a, b) = tokio::join!(
+            runner_a.run_in_packages(&first, cmd, &env, None, &[]),
+            runner_b.run_in_packages(&second, cmd, &env, None, &[]),
+        );
+
+        let results: Vec<(String, bool)> = a
+            .unwrap()
+            .results
+            .into_iter()
+            .chain(b.unwrap().results)
+            .collect();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|(_, ok)| *ok), "{:?}", results);
+    }
+
+    // -- run_in_packages_ordered tests --
+
+    fn make_dep_pkg(name: &str, path: &Path, deps: &[&str]) -> Package {
+        let mut pkg = make_pkg(name, path.to_str().unwrap());
+        pkg.dependencies = deps.iter().map(|d| d.to_string()).collect();
+        pkg
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ordered_starts_dependents_after_dependencies() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // `app` is listed first, but must wait for `core`, which waits for `utils`
+        let packages = vec![
+            make_dep_pkg("app", dir.path(), &["core"]),
+            make_dep_pkg("core", dir.path(), &["utils"]),
+            make_dep_pkg("utils", dir.path(), &[]),
+        ];
+        let runner = ProcessRunner::new(5, false);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let results = runner
+            .run_in_packages_ordered(
+                &packages,
+                "sleep 0.1",
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+        assert!(results.results.iter().all(|(_, ok)| *ok));
+
+        let mut timeline = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                Event::PackageStarted { name } => timeline.push(format!("start {name}")),
+                Event::PackageFinished { name, .. } => timeline.push(format!("end {name}")),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            timeline,
+            vec![
+                "start utils",
+                "end utils",
+                "start core",
+                "end core",
+                "start app",
+                "end app"
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_ordered_skips_dependents_of_failed_package() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages = vec![
+            make_dep_pkg("app", dir.path(), &["core"]),
+            make_dep_pkg("core", dir.path(), &[]),
+            make_dep_pkg("other", dir.path(), &[]),
+        ];
+        let runner = ProcessRunner::new(5, false);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let mut results = runner
+            .run_in_packages_ordered(
+                &packages,
+                r#"test "$MELOS_PACKAGE_NAME" != core"#,
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+        results.results.sort();
+        assert_eq!(
+            results.results,
+            vec![("core".to_string(), false), ("other".to_string(), true)]
+        );
+        assert_eq!(
+            results.skipped,
+            vec![("app".to_string(), "dependency core failed".to_string())]
+        );
+
+        let mut skipped = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let Event::PackageSkipped { name, reason } = event {
+                skipped.push((name, reason));
+            }
+        }
+        assert_eq!(
+            skipped,
+            vec![("app".to_string(), "dependency core failed".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ordered_rejects_dependency_cycle() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let packages = vec![
+            make_dep_pkg("a", dir.path(), &["b"]),
+            make_dep_pkg("b", dir.path(), &["a"]),
+        ];
+        let runner = ProcessRunner::new(1, false);
+
+        let err = runner
+            .run_in_packages_ordered(&packages, "true", &HashMap::new(), None, None, &[])
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Dependency cycle detected: a -> b -> a");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_working_directory_runs_in_subdir_and_skips_missing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let with_android = dir.path().join("app");
+        std::fs::create_dir_all(with_android.join("android")).unwrap();
+        let without_android = dir.path().join("core");
+        std::fs::create_dir_all(&without_android).unwrap();
+        let packages = vec![
+            make_pkg("app", with_android.to_str().unwrap()),
+            make_pkg("core", without_android.to_str().unwrap()),
+        ];
+        let runner =
+            ProcessRunner::new(2, false).with_working_directory(Some(PathBuf::from("android")));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let results = runner
+            .run_in_packages_with_events(
+                &packages,
+                r#"test "$(basename "$PWD")" = android && test "$MELOS_PACKAGE_PATH" != "$PWD""#,
+                &HashMap::new(),
+                None,
+                Some(&tx),
+                &[],
+            )
+            .await
+            .unwrap();
+        drop(tx);
+        assert_eq!(results.results, vec![("app".to_string(), true)]);
+        assert_eq!(
+            results.skipped,
+            vec![("core".to_string(), "no android/".to_string())]
+        );
+
+        let mut skipped = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let Event::PackageSkipped { name, reason } = event {
+                skipped.push((name, reason));
+            }
+        }
+        assert_eq!(
+            skipped,
+            vec![("core".to_string(), "no android/".to_string())]
+        );
+    }
+
     // -- find_parent_package tests --
 
     #[test]
@@ -479,10 +1878,32 @@ mod tests {
                 .expect("should have MELOS_ROOT_PATH"),
             "/workspace"
         );
+        assert_eq!(env["MELOS_PACKAGE_IS_FLUTTER"], "false");
+        assert_eq!(env["MELOS_PACKAGE_DEPENDENCIES"], "");
         // No parent env vars
         assert!(!env.contains_key("MELOS_PARENT_PACKAGE_NAME"));
     }
 
+    #[test]
+    fn test_build_package_env_flutter_and_workspace_dependencies() {
+        let ws_env = HashMap::new();
+        let mut app = make_pkg("app", "/workspace/packages/app");
+        app.is_flutter = true;
+        app.dependencies = vec!["core".to_string(), "http".to_string(), "ui".to_string()];
+        app.dev_dependencies = vec!["test_utils".to_string(), "core".to_string()];
+        let all = vec![
+            app.clone(),
+            make_pkg("core", "/workspace/packages/core"),
+            make_pkg("ui", "/workspace/packages/ui"),
+            make_pkg("test_utils", "/workspace/packages/test_utils"),
+        ];
+
+        let env = build_package_env(&ws_env, &app, &all);
+        assert_eq!(env["MELOS_PACKAGE_IS_FLUTTER"], "true");
+        // External deps are dropped; duplicates across sections appear once
+        assert_eq!(env["MELOS_PACKAGE_DEPENDENCIES"], "core,ui,test_utils");
+    }
+
     #[test]
     fn test_build_package_env_with_parent() {
         let ws_env = HashMap::new();
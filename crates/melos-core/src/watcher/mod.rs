@@ -3,9 +3,13 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use notify_debouncer_mini::{DebouncedEventKind, new_debouncer};
+use notify::{PollWatcher, RecommendedWatcher, Watcher};
+use notify_debouncer_mini::{
+    DebounceEventResult, DebouncedEventKind, Debouncer, new_debouncer_opt,
+};
 use tokio::sync::mpsc;
 
+use crate::events::Event;
 use crate::package::Package;
 
 /// Default debounce duration for file change events.
@@ -26,11 +30,77 @@ const IGNORED_DIRS: &[&str] = &[
     ".vscode",
 ];
 
+/// Category of a changed file, so consumers can decide what to re-run
+/// (e.g. only regenerate localizations when just `.arb` files changed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Dart source (`.dart`, including generated `.g.dart`)
+    Source,
+    /// The package's `pubspec.yaml`
+    Pubspec,
+    /// Flutter localization resource (`.arb`)
+    Arb,
+    /// Any other watched file (other YAML, JSON)
+    Other,
+}
+
+impl ChangeKind {
+    /// Classify a changed file by its name and extension.
+    pub fn from_path(path: &Path) -> Self {
+        if path.file_name().is_some_and(|name| name == "pubspec.yaml") {
+            return Self::Pubspec;
+        }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("dart") => Self::Source,
+            Some("arb") => Self::Arb,
+            _ => Self::Other,
+        }
+    }
+}
+
 /// A file change event identifying which package was affected.
+///
+/// A debounced batch touching several kinds of file in one package emits one
+/// event per kind.
 #[derive(Debug, Clone)]
 pub struct PackageChangeEvent {
     /// Name of the package where the change was detected
     pub package_name: String,
+    /// Category of the file(s) that changed
+    pub change_kind: ChangeKind,
+}
+
+/// The debounced watcher backend, kept alive while watching.
+enum Backend {
+    /// Native OS file events (inotify, FSEvents, ...)
+    Native(Debouncer<RecommendedWatcher>),
+    /// Periodic scans, for mounts where native events don't fire (NFS,
+    /// Docker volumes)
+    Polling(Debouncer<PollWatcher>),
+}
+
+impl Backend {
+    fn new(
+        debounce: Duration,
+        poll_interval: Option<Duration>,
+        tx: std::sync::mpsc::Sender<DebounceEventResult>,
+    ) -> notify::Result<Self> {
+        let config = notify_debouncer_mini::Config::default().with_timeout(debounce);
+        Ok(match poll_interval {
+            Some(interval) => Self::Polling(new_debouncer_opt(
+                config.with_notify_config(notify::Config::default().with_poll_interval(interval)),
+                tx,
+            )?),
+            None => Self::Native(new_debouncer_opt(config, tx)?),
+        })
+    }
+
+    fn watcher(&mut self) -> &mut dyn Watcher {
+        match self {
+            Self::Native(debouncer) => debouncer.watcher(),
+            Self::Polling(debouncer) => debouncer.watcher(),
+        }
+    }
 }
 
 /// Watch package directories for file changes, emitting debounced events.
@@ -41,16 +111,21 @@ pub struct PackageChangeEvent {
 /// # Arguments
 /// * `packages` - Packages to watch (their `path` directories are monitored recursively)
 /// * `debounce_ms` - Debounce duration in milliseconds (0 uses the default 500ms)
+/// * `poll_interval` - Scan for changes at this interval instead of relying on
+///   native file events, which don't fire on network or container mounts
 /// * `event_tx` - Channel sender for emitting change events
 /// * `shutdown_rx` - Receiver that signals the watcher to stop
 /// * `ready_tx` - Optional oneshot sender signaled after all watchers are registered.
 ///   Used by tests to avoid race conditions between watcher setup and file writes.
+/// * `events` - Optional channel for [`Event::Warning`]s about watch errors
 pub fn start_watching(
     packages: &[Package],
     debounce_ms: u64,
+    poll_interval: Option<Duration>,
     event_tx: mpsc::UnboundedSender<PackageChangeEvent>,
     mut shutdown_rx: mpsc::Receiver<()>,
     ready_tx: Option<std::sync::mpsc::Sender<()>>,
+    events: Option<mpsc::UnboundedSender<Event>>,
 ) -> Result<()> {
     let debounce_duration = if debounce_ms == 0 {
         Duration::from_millis(DEFAULT_DEBOUNCE_MS)
@@ -69,12 +144,12 @@ pub fn start_watching(
 
     // Create the debounced file watcher
     let (tx, rx) = std::sync::mpsc::channel();
-    let mut debouncer =
-        new_debouncer(debounce_duration, tx).context("Failed to create file watcher")?;
+    let mut backend = Backend::new(debounce_duration, poll_interval, tx)
+        .context("Failed to create file watcher")?;
 
     // Watch each package directory recursively
     for pkg in packages {
-        debouncer
+        backend
             .watcher()
             .watch(&pkg.path, notify::RecursiveMode::Recursive)
             .with_context(|| format!("Failed to watch directory: {}", pkg.path.display()))?;
@@ -99,7 +174,7 @@ pub fn start_watching(
         match rx.recv_timeout(Duration::from_millis(200)) {
             Ok(Ok(events)) => {
                 // Collect affected packages, filtering out ignored paths and extensions
-                let mut affected_packages: HashSet<String> = HashSet::new();
+                let mut affected_packages: HashSet<(String, ChangeKind)> = HashSet::new();
 
                 for event in events {
                     if event.kind != DebouncedEventKind::Any {
@@ -120,13 +195,16 @@ pub fn start_watching(
 
                     // Find which package this file belongs to
                     if let Some(pkg_name) = find_owning_package(path, &package_paths) {
-                        affected_packages.insert(pkg_name);
+                        affected_packages.insert((pkg_name, ChangeKind::from_path(path)));
                     }
                 }
 
                 // Emit events for each affected package
-                for package_name in affected_packages {
-                    let event = PackageChangeEvent { package_name };
+                for (package_name, change_kind) in affected_packages {
+                    let event = PackageChangeEvent {
+                        package_name,
+                        change_kind,
+                    };
                     if event_tx.send(event).is_err() {
                         // Receiver dropped, stop watching
                         break;
@@ -134,7 +212,9 @@ pub fn start_watching(
                 }
             }
             Ok(Err(error)) => {
-                eprintln!("WARN: Watch error: {}", error);
+                if let Some(ref events) = events {
+                    let _ = events.send(Event::Warning(format!("Watch error: {}", error)));
+                }
             }
             Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                 // No events, continue loop
@@ -165,7 +245,10 @@ fn has_watched_extension(path: &Path) -> bool {
 
 /// Find which package owns a given file path by checking if the file
 /// is under any of the watched package directories.
-fn find_owning_package(file_path: &Path, package_paths: &[(PathBuf, String)]) -> Option<String> {
+pub(crate) fn find_owning_package(
+    file_path: &Path,
+    package_paths: &[(PathBuf, String)],
+) -> Option<String> {
     let canonical = file_path
         .canonicalize()
         .unwrap_or_else(|_| file_path.to_path_buf());
@@ -256,6 +339,34 @@ mod tests {
         assert!(!has_watched_extension(Path::new("Dockerfile")));
     }
 
+    #[test]
+    fn test_change_kind_from_path() {
+        assert_eq!(
+            ChangeKind::from_path(Path::new("lib/main.dart")),
+            ChangeKind::Source
+        );
+        assert_eq!(
+            ChangeKind::from_path(Path::new("lib/models/user.g.dart")),
+            ChangeKind::Source
+        );
+        assert_eq!(
+            ChangeKind::from_path(Path::new("/ws/packages/foo/pubspec.yaml")),
+            ChangeKind::Pubspec
+        );
+        assert_eq!(
+            ChangeKind::from_path(Path::new("lib/l10n/app_de.arb")),
+            ChangeKind::Arb
+        );
+        assert_eq!(
+            ChangeKind::from_path(Path::new("analysis_options.yaml")),
+            ChangeKind::Other
+        );
+        assert_eq!(
+            ChangeKind::from_path(Path::new("assets/config.json")),
+            ChangeKind::Other
+        );
+    }
+
     #[test]
     fn test_find_owning_package_basic() {
         let packages = vec![
@@ -380,6 +491,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -391,7 +503,15 @@ mod tests {
         let packages_clone = packages.clone();
 
         let watcher_handle = tokio::task::spawn_blocking(move || {
-            start_watching(&packages_clone, 100, event_tx, shutdown_rx, Some(ready_tx))
+            start_watching(
+                &packages_clone,
+                100,
+                None,
+                event_tx,
+                shutdown_rx,
+                Some(ready_tx),
+                None,
+            )
         });
 
         // Wait until watchers are fully registered before writing
@@ -412,12 +532,78 @@ mod tests {
         assert!(event.is_ok(), "Should receive an event within 5 seconds");
         let event = event.unwrap().expect("Should have an event");
         assert_eq!(event.package_name, "my_package");
+        assert_eq!(event.change_kind, ChangeKind::Source);
 
         // Signal shutdown and wait for the watcher to exit
         drop(shutdown_tx);
         let _ = tokio::time::timeout(std::time::Duration::from_secs(2), watcher_handle).await;
     }
 
+    #[tokio::test]
+    async fn test_watcher_polling_detects_changes() {
+        use std::collections::HashMap;
+        use std::fs;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let pkg_dir = tmp.path().join("my_package");
+        let lib_dir = pkg_dir.join("lib");
+        fs::create_dir_all(&lib_dir).unwrap();
+
+        let package = Package {
+            name: "my_package".to_string(),
+            path: pkg_dir.clone(),
+            version: Some("1.0.0".to_string()),
+            is_flutter: false,
+            publish_to: None,
+            dependencies: vec![],
+            dev_dependencies: vec![],
+            dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
+            resolution: None,
+        };
+
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel::<()>();
+
+        let packages = vec![package];
+        let poll_interval = Some(Duration::from_millis(50));
+        let watcher_handle = tokio::task::spawn_blocking(move || {
+            start_watching(
+                &packages,
+                100,
+                poll_interval,
+                event_tx,
+                shutdown_rx,
+                Some(ready_tx),
+                None,
+            )
+        });
+
+        tokio::task::spawn_blocking(move || {
+            ready_rx
+                .recv_timeout(Duration::from_secs(5))
+                .expect("Watcher should signal ready within 5 seconds");
+        })
+        .await
+        .unwrap();
+
+        // A build/ output is ignored, a pubspec change is not
+        fs::create_dir_all(pkg_dir.join("build")).unwrap();
+        fs::write(pkg_dir.join("build/out.dart"), "// generated").unwrap();
+        fs::write(pkg_dir.join("pubspec.yaml"), "name: my_package").unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .expect("Should receive an event within 5 seconds")
+            .expect("Should have an event");
+        assert_eq!(event.package_name, "my_package");
+        assert_eq!(event.change_kind, ChangeKind::Pubspec);
+
+        drop(shutdown_tx);
+        let _ = tokio::time::timeout(Duration::from_secs(2), watcher_handle).await;
+    }
+
     #[tokio::test]
     async fn test_watcher_ignores_non_dart_files() {
         use std::collections::HashMap;
@@ -437,6 +623,7 @@ mod tests {
             dependencies: vec![],
             dev_dependencies: vec![],
             dependency_versions: HashMap::new(),
+            dependency_kinds: HashMap::new(),
             resolution: None,
         };
 
@@ -448,7 +635,15 @@ mod tests {
         let packages_clone = packages.clone();
 
         let watcher_handle = tokio::task::spawn_blocking(move || {
-            start_watching(&packages_clone, 100, event_tx, shutdown_rx, Some(ready_tx))
+            start_watching(
+                &packages_clone,
+                100,
+                None,
+                event_tx,
+                shutdown_rx,
+                Some(ready_tx),
+                None,
+            )
         });
 
         // Wait until watchers are fully registered before writing
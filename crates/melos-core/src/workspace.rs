@@ -3,7 +3,9 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 
+use crate::commands::run::expand_env_vars;
 use crate::config::{self, ConfigSource, MelosConfig};
+use crate::package::graph::DependencyGraph;
 use crate::package::{self, Package};
 
 /// Represents a Melos workspace with its config and discovered packages
@@ -27,8 +29,54 @@ pub struct Workspace {
     /// workspace discovery, useRootAsPackage issues). The caller is responsible
     /// for presenting these to the user.
     pub warnings: Vec<String>,
+
+    /// Config fields overridden by a local `.melos_overrides.yaml`, as dotted
+    /// camelCase paths (e.g. `command.exec.concurrency`)
+    pub overridden_fields: Vec<String>,
+
+    /// Whether child processes are told to emit ANSI colors
+    pub color: ColorMode,
+}
+
+/// Color behaviour requested for child processes whose output is captured.
+///
+/// Captured stdout is not a TTY, so many tools drop their colors. `Always`
+/// sets the conventional force-color variables so the output keeps them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Leave the decision to each tool
+    #[default]
+    Auto,
+    /// Set `FORCE_COLOR=1` and `CLICOLOR_FORCE=1`
+    Always,
+    /// Set `NO_COLOR=1`
+    Never,
 }
 
+impl ColorMode {
+    /// Environment variables passed to child processes for this mode.
+    pub fn env_vars(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ColorMode::Auto => &[],
+            ColorMode::Always => &[("FORCE_COLOR", "1"), ("CLICOLOR_FORCE", "1")],
+            ColorMode::Never => &[("NO_COLOR", "1")],
+        }
+    }
+
+    /// The `--color` / `--no-color` flag understood by `dart test` and
+    /// `flutter test`, if this mode overrides their TTY detection.
+    pub fn test_flag(self) -> Option<&'static str> {
+        match self {
+            ColorMode::Auto => None,
+            ColorMode::Always => Some("--color"),
+            ColorMode::Never => Some("--no-color"),
+        }
+    }
+}
+
+/// Prefix applied to config validation warnings in [`Workspace::warnings`].
+const CONFIG_WARNING_PREFIX: &str = "config: ";
+
 impl Workspace {
     /// Find melos.yaml or pubspec.yaml (with melos: key) by walking up from the
     /// current directory, then load the workspace.
@@ -38,19 +86,74 @@ impl Workspace {
     ///
     /// `sdk_path_override` is the CLI `--sdk-path` value, which takes highest priority.
     pub fn find_and_load(sdk_path_override: Option<&str>) -> Result<Self> {
-        let config_source = find_config()?;
+        let cwd = std::env::current_dir().context("Failed to get current directory")?;
+        Self::load(find_config(&cwd)?, sdk_path_override)
+    }
+
+    /// Like [`Workspace::find_and_load`], but start the config search from
+    /// `start` instead of the current directory.
+    ///
+    /// `start` is canonicalized so a relative directory can be walked up.
+    pub fn find_and_load_from(start: &Path, sdk_path_override: Option<&str>) -> Result<Self> {
+        let start = start
+            .canonicalize()
+            .with_context(|| format!("Workspace directory '{}' not found", start.display()))?;
+        Self::load(find_config(&start)?, sdk_path_override)
+    }
+
+    /// Load the workspace described by the config file at `path`, skipping
+    /// discovery. The workspace root is the file's parent directory.
+    ///
+    /// A file named `pubspec.yaml` must contain a `melos:` key (7.x mode);
+    /// any other `.yaml`/`.yml` file is read as a `melos.yaml` (6.x mode).
+    pub fn load_from_config(path: &Path, sdk_path_override: Option<&str>) -> Result<Self> {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Config file '{}' not found", path.display()))?;
+        Self::load(config_source_for(&path)?, sdk_path_override)
+    }
+
+    /// Load a workspace from an already-located config source.
+    ///
+    /// Config validation warnings are merged into `warnings` with a
+    /// `"config: "` prefix, ahead of any discovery warnings.
+    pub fn load(config_source: ConfigSource, sdk_path_override: Option<&str>) -> Result<Self> {
         let root_path = config_source
             .path()
             .parent()
             .context("Config file has no parent directory")?
             .to_path_buf();
 
-        let config = config::parse_config(&config_source)?;
+        let mut config = config::parse_config(&config_source)?;
+        let applied = config::overrides::load_overrides(&root_path, &mut config)?;
 
         // Run post-parse validation and collect warnings
-        let mut warnings = config.validate();
+        let mut warnings: Vec<String> = config
+            .validate()
+            .into_iter()
+            .map(|w| format!("{CONFIG_WARNING_PREFIX}{w}"))
+            .collect();
+
+        let overridden_fields = match applied {
+            Some(applied) => {
+                warnings.extend(applied.ignored.iter().map(|key| {
+                    format!(
+                        "{}: `{}` cannot be overridden and was ignored",
+                        config::overrides::OVERRIDES_FILE,
+                        key
+                    )
+                }));
+                applied.fields
+            }
+            None => Vec::new(),
+        };
 
-        let mut packages = package::discover_packages(&root_path, &config.packages)?;
+        let mut packages = package::discover_packages(
+            &root_path,
+            &config.packages,
+            config.should_respect_gitignore(),
+            &mut warnings,
+        )?;
 
         // Discover packages from nested workspaces if enabled
         if config.discover_nested_workspaces == Some(true) {
@@ -97,6 +200,8 @@ impl Workspace {
             }
         }
 
+        package::mark_workspace_dependencies(&mut packages);
+
         // Apply top-level ignore patterns (global exclusion before any command-level filters)
         if let Some(ref ignore_patterns) = config.ignore {
             packages.retain(|pkg| {
@@ -121,9 +226,42 @@ impl Workspace {
             packages,
             sdk_path,
             warnings,
+            overridden_fields,
+            color: ColorMode::default(),
         })
     }
 
+    /// Re-read the config and rediscover packages in place.
+    ///
+    /// Reloads from the stored [`Workspace::config_source`], replacing
+    /// `config`, `packages`, `warnings` and `overridden_fields`. The resolved
+    /// `sdk_path` and `color` are kept. On error the workspace is left unchanged.
+    pub fn reload(&mut self) -> Result<()> {
+        let fresh = Self::load(self.config_source.clone(), self.sdk_path.as_deref())
+            .with_context(|| format!("Failed to reload {}", self.config_source.path().display()))?;
+        self.config = fresh.config;
+        self.packages = fresh.packages;
+        self.warnings = fresh.warnings;
+        self.overridden_fields = fresh.overridden_fields;
+        Ok(())
+    }
+
+    /// Dependency graph over all workspace packages.
+    ///
+    /// Built from [`Workspace::packages`] on each call; hold on to the result
+    /// when querying it repeatedly.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        DependencyGraph::new(&self.packages)
+    }
+
+    /// Warnings produced by config validation, without the `"config: "` prefix.
+    pub fn config_warnings(&self) -> Vec<&str> {
+        self.warnings
+            .iter()
+            .filter_map(|w| w.strip_prefix(CONFIG_WARNING_PREFIX))
+            .collect()
+    }
+
     /// Extract a lifecycle hook command for a given command and phase.
     ///
     /// `command` is one of `"bootstrap"`, `"build"`, `"clean"`, `"test"`, `"publish"`.
@@ -190,17 +328,46 @@ impl Workspace {
     ///   MELOS_PACKAGE_PATH - (set per-package during exec)
     ///   MELOS_PACKAGE_VERSION - (set per-package during exec)
     ///
+    /// Entries of the workspace-level `env` config are added first, with
+    /// `${VAR}` references expanded against the variables above and the
+    /// process environment. The built-in `MELOS_*` variables cannot be
+    /// overridden.
+    ///
     /// When `sdk_path` is set, `{sdk_path}/bin` is prepended to `PATH` so that
     /// `dart` and `flutter` executables from that SDK are found by child processes.
     pub fn env_vars(&self) -> HashMap<String, String> {
-        let mut env = HashMap::new();
-        env.insert(
+        self.env_vars_with(&HashMap::new())
+    }
+
+    /// [`Workspace::env_vars`] with `extra` (e.g. from `--env-file`) layered
+    /// over the workspace `env` config and the color variables. The built-in
+    /// `MELOS_*` variables still win, and the SDK bin dir is still prepended
+    /// to an overridden `PATH`.
+    pub fn env_vars_with(&self, extra: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut builtins = HashMap::new();
+        builtins.insert(
             "MELOS_ROOT_PATH".to_string(),
             self.root_path.display().to_string(),
         );
         if let Some(ref sdk_path) = self.sdk_path {
-            env.insert("MELOS_SDK_PATH".to_string(), sdk_path.clone());
+            builtins.insert("MELOS_SDK_PATH".to_string(), sdk_path.clone());
+        }
 
+        let mut env = HashMap::new();
+        if !self.config.env.is_empty() {
+            let mut lookup: HashMap<String, String> = std::env::vars().collect();
+            lookup.extend(builtins.clone());
+            for (key, value) in &self.config.env {
+                env.insert(key.clone(), expand_env_vars(value, &lookup));
+            }
+        }
+        for (key, value) in self.color.env_vars() {
+            env.insert(key.to_string(), value.to_string());
+        }
+        env.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env.extend(builtins);
+
+        if let Some(ref sdk_path) = self.sdk_path {
             // Prepend sdk_path/bin to PATH so child processes find dart/flutter
             let sdk_bin = std::path::Path::new(sdk_path).join("bin");
             let separator = if cfg!(target_os = "windows") {
@@ -208,9 +375,13 @@ impl Workspace {
             } else {
                 ":"
             };
-            let new_path = match std::env::var("PATH") {
-                Ok(current) => format!("{}{}{}", sdk_bin.display(), separator, current),
-                Err(_) => sdk_bin.display().to_string(),
+            let current = env
+                .get("PATH")
+                .cloned()
+                .or_else(|| std::env::var("PATH").ok());
+            let new_path = match current {
+                Some(current) => format!("{}{}{}", sdk_bin.display(), separator, current),
+                None => sdk_bin.display().to_string(),
             };
             env.insert("PATH".to_string(), new_path);
         }
@@ -218,16 +389,15 @@ impl Workspace {
     }
 }
 
-/// Search for workspace config starting from the current directory and walking up.
+/// Search for workspace config starting from `start` and walking up.
 ///
 /// For each directory we check:
 /// 1. `melos.yaml` — if found, use 6.x mode (preferred)
 /// 2. `pubspec.yaml` containing a top-level `melos:` key — use 7.x mode
 ///
 /// If both exist in the same directory, `melos.yaml` wins (user hasn't migrated).
-fn find_config() -> Result<ConfigSource> {
-    let cwd = std::env::current_dir().context("Failed to get current directory")?;
-    let mut dir: &Path = &cwd;
+fn find_config(start: &Path) -> Result<ConfigSource> {
+    let mut dir: &Path = start;
 
     loop {
         // Prefer melos.yaml (6.x)
@@ -252,10 +422,33 @@ fn find_config() -> Result<ConfigSource> {
         "Could not find melos.yaml or pubspec.yaml (with melos: key) in '{}' or any parent directory.\n\
          \n\
          Hint: Create a melos.yaml (Melos 6.x) or add a `melos:` section to your root pubspec.yaml (Melos 7.x).",
-        cwd.display()
+        start.display()
     )
 }
 
+/// Classify an explicitly given config file by its name.
+fn config_source_for(path: &Path) -> Result<ConfigSource> {
+    if !path.is_file() {
+        anyhow::bail!("Config path '{}' is not a file", path.display());
+    }
+    if path.file_name().is_some_and(|n| n == "pubspec.yaml") {
+        if !pubspec_has_melos_key(path) {
+            anyhow::bail!(
+                "Config file '{}' has no top-level `melos:` key",
+                path.display()
+            );
+        }
+        return Ok(ConfigSource::PubspecYaml(path.to_path_buf()));
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => Ok(ConfigSource::MelosYaml(path.to_path_buf())),
+        _ => anyhow::bail!(
+            "Unrecognized config file '{}': expected melos.yaml, a pubspec.yaml with a `melos:` key, or another .yaml/.yml file",
+            path.display()
+        ),
+    }
+}
+
 /// Check whether a pubspec.yaml file contains a top-level `melos:` key.
 ///
 /// We do a quick YAML parse to a generic Value rather than a full MelosConfig
@@ -382,10 +575,14 @@ mod tests {
                 categories: HashMap::new(),
                 use_root_as_package: None,
                 discover_nested_workspaces: None,
+                respect_gitignore: None,
+                env: HashMap::new(),
             },
             packages: vec![],
             sdk_path: None,
             warnings: vec![],
+            overridden_fields: vec![],
+            color: Default::default(),
         }
     }
 
@@ -512,19 +709,23 @@ mod tests {
             version: None,
             bootstrap: Some(BootstrapCommandConfig {
                 run_pub_get_in_parallel: None,
+                concurrency: None,
                 enforce_versions_for_dependency_resolution: None,
                 enforce_lockfile: None,
                 run_pub_get_offline: None,
                 dependency_override_paths: None,
+                dependency_overrides: None,
                 environment: None,
                 dependencies: None,
                 dev_dependencies: None,
+                verify_links: None,
                 hooks: Some(BootstrapHooks {
                     pre: Some("echo pre-bootstrap".to_string()),
                     post: None,
                 }),
             }),
             build: None,
+            exec: None,
             clean: None,
             publish: None,
             test: None,
@@ -539,6 +740,7 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: Some(CleanCommandConfig {
                 hooks: Some(CleanHooks {
                     pre: None,
@@ -558,9 +760,12 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: None,
             publish: None,
             test: Some(TestCommandConfig {
+                concurrency: None,
+                timeout: None,
                 hooks: Some(TestHooks {
                     pre: Some("echo pre-test".to_string()),
                     post: Some("echo post-test".to_string()),
@@ -577,8 +782,10 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: None,
             publish: Some(PublishCommandConfig {
+                concurrency: None,
                 hooks: Some(PublishHooks {
                     pre: Some("echo pre-publish".to_string()),
                     post: None,
@@ -596,6 +803,7 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: None,
             publish: None,
             test: None,
@@ -609,6 +817,7 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: Some(CleanCommandConfig {
                 hooks: Some(CleanHooks {
                     pre: Some("echo pre".to_string()),
@@ -627,6 +836,7 @@ mod tests {
             version: None,
             bootstrap: None,
             build: None,
+            exec: None,
             clean: Some(CleanCommandConfig { hooks: None }),
             publish: None,
             test: None,
@@ -664,4 +874,279 @@ mod tests {
             "PATH should start with sdk_path/bin, got: {path}"
         );
     }
+
+    #[test]
+    fn test_env_vars_color_mode() {
+        let mut ws = make_workspace_with_commands(None);
+        ws.config.env = HashMap::from([("FORCE_COLOR".to_string(), "0".to_string())]);
+        let env = ws.env_vars();
+        assert_eq!(env.get("FORCE_COLOR").unwrap(), "0");
+        assert!(!env.contains_key("CLICOLOR_FORCE"));
+        assert!(!env.contains_key("NO_COLOR"));
+
+        ws.color = ColorMode::Always;
+        let env = ws.env_vars();
+        assert_eq!(env.get("FORCE_COLOR").unwrap(), "1");
+        assert_eq!(env.get("CLICOLOR_FORCE").unwrap(), "1");
+
+        ws.color = ColorMode::Never;
+        let env = ws.env_vars();
+        assert_eq!(env.get("NO_COLOR").unwrap(), "1");
+        assert_eq!(ColorMode::Never.test_flag(), Some("--no-color"));
+        assert_eq!(ColorMode::Auto.test_flag(), None);
+    }
+
+    #[test]
+    fn test_env_vars_with_layers_extra_below_builtins() {
+        let mut ws = make_workspace_with_commands(None);
+        ws.sdk_path = Some("/opt/flutter".to_string());
+        ws.config.env = HashMap::from([
+            ("API_URL".to_string(), "https://config".to_string()),
+            ("KEEP".to_string(), "config".to_string()),
+        ]);
+        let extra = HashMap::from([
+            ("API_URL".to_string(), "https://dotenv".to_string()),
+            ("MELOS_ROOT_PATH".to_string(), "/elsewhere".to_string()),
+            ("PATH".to_string(), "/custom/bin".to_string()),
+        ]);
+
+        let env = ws.env_vars_with(&extra);
+        assert_eq!(env.get("API_URL").unwrap(), "https://dotenv");
+        assert_eq!(env.get("KEEP").unwrap(), "config");
+        assert_eq!(env.get("MELOS_ROOT_PATH").unwrap(), "/workspace");
+        let path = env.get("PATH").unwrap();
+        assert!(path.starts_with("/opt/flutter/bin") && path.ends_with("/custom/bin"));
+    }
+
+    #[test]
+    fn test_env_vars_merges_workspace_env_with_expansion() {
+        let mut ws = make_workspace_with_commands(None);
+        ws.sdk_path = Some("/opt/flutter".to_string());
+        ws.config.env = HashMap::from([
+            (
+                "PUB_CACHE".to_string(),
+                "${MELOS_ROOT_PATH}/.pub-cache".to_string(),
+            ),
+            ("FLUTTER_ROOT".to_string(), "$MELOS_SDK_PATH".to_string()),
+            ("MELOS_ROOT_PATH".to_string(), "/elsewhere".to_string()),
+            ("PATH".to_string(), "/custom/bin".to_string()),
+        ]);
+
+        let env = ws.env_vars();
+        assert_eq!(env.get("PUB_CACHE").unwrap(), "/workspace/.pub-cache");
+        assert_eq!(env.get("FLUTTER_ROOT").unwrap(), "/opt/flutter");
+        // Built-ins win over the config
+        assert_eq!(env.get("MELOS_ROOT_PATH").unwrap(), "/workspace");
+        // The SDK bin dir is prepended to a configured PATH
+        let path = env.get("PATH").unwrap();
+        assert!(path.starts_with("/opt/flutter/bin") && path.ends_with("/custom/bin"));
+    }
+
+    #[test]
+    fn test_load_merges_config_warnings() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("melos.yaml");
+        fs::write(
+            &config_path,
+            "name: test\npackages:\n  - packages/**\nscripts:\n  lint:\n    run: dart analyze\n    packageFilters:\n      category: [missing]\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(ConfigSource::MelosYaml(config_path), None).unwrap();
+
+        assert!(
+            workspace
+                .warnings
+                .iter()
+                .any(|w| w.starts_with("config: ") && w.contains("missing")),
+            "expected prefixed category warning, got {:?}",
+            workspace.warnings
+        );
+        let config_warnings = workspace.config_warnings();
+        assert_eq!(config_warnings.len(), 1);
+        assert!(config_warnings[0].contains("'missing'"));
+    }
+
+    #[test]
+    fn test_load_applies_local_overrides() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("melos.yaml");
+        fs::write(
+            &config_path,
+            "name: test\npackages:\n  - packages/**\nsdkPath: /opt/shared\nscripts:\n  lint: dart analyze\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".melos_overrides.yaml"),
+            "sdkPath: /opt/local\ncommand:\n  exec:\n    concurrency: 2\nscripts:\n  lint: echo\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(ConfigSource::MelosYaml(config_path), None).unwrap();
+
+        assert_eq!(workspace.config.sdk_path.as_deref(), Some("/opt/local"));
+        assert_eq!(
+            workspace.overridden_fields,
+            vec!["sdkPath", "command.exec.concurrency"]
+        );
+        assert_eq!(
+            workspace.config.scripts["lint"].run_command(),
+            Some("dart analyze")
+        );
+        assert!(
+            workspace
+                .warnings
+                .iter()
+                .any(|w| w.contains("`scripts` cannot be overridden")),
+            "expected ignored-key warning, got {:?}",
+            workspace.warnings
+        );
+        assert!(workspace.config_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_find_and_load_from_walks_up_from_start() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("melos.yaml"),
+            "name: remote\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        let pkg_dir = dir.path().join("packages").join("core");
+        fs::create_dir_all(&pkg_dir).unwrap();
+        fs::write(pkg_dir.join("pubspec.yaml"), "name: core\nversion: 1.0.0\n").unwrap();
+
+        let workspace = Workspace::find_and_load_from(&pkg_dir, None).unwrap();
+
+        assert_eq!(workspace.config.name, "remote");
+        assert_eq!(workspace.root_path, dir.path().canonicalize().unwrap());
+        assert_eq!(workspace.packages.len(), 1);
+        assert_eq!(workspace.packages[0].name, "core");
+    }
+
+    #[test]
+    fn test_find_and_load_from_missing_dir() {
+        let Err(err) = Workspace::find_and_load_from(Path::new("/nonexistent/melos-ws"), None)
+        else {
+            panic!("expected missing workspace dir to fail");
+        };
+        let err = err.to_string();
+        assert!(err.contains("not found"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_load_from_config_uses_parent_as_root() {
+        let dir = TempDir::new().unwrap();
+        let config_dir = dir.path().join("tooling");
+        fs::create_dir_all(config_dir.join("packages").join("core")).unwrap();
+        fs::write(
+            config_dir.join("melos.ci.yaml"),
+            "name: ci\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        fs::write(
+            config_dir.join("packages/core/pubspec.yaml"),
+            "name: core\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        // A melos.yaml in the cwd-style location must not be picked up
+        fs::write(dir.path().join("melos.yaml"), "name: other\npackages: []\n").unwrap();
+
+        let workspace =
+            Workspace::load_from_config(&config_dir.join("melos.ci.yaml"), None).unwrap();
+
+        assert_eq!(workspace.config.name, "ci");
+        assert!(workspace.config_source.is_legacy());
+        assert_eq!(workspace.root_path, config_dir.canonicalize().unwrap());
+        assert_eq!(workspace.packages.len(), 1);
+    }
+
+    #[test]
+    fn test_load_from_config_pubspec_requires_melos_key() {
+        let dir = TempDir::new().unwrap();
+        let pubspec = dir.path().join("pubspec.yaml");
+        fs::write(&pubspec, "name: ws\n").unwrap();
+
+        let Err(err) = Workspace::load_from_config(&pubspec, None) else {
+            panic!("expected pubspec without melos key to fail");
+        };
+        assert!(err.to_string().contains("no top-level `melos:` key"));
+
+        fs::write(
+            &pubspec,
+            "name: ws\nmelos:\n  packages:\n    - packages/**\n",
+        )
+        .unwrap();
+        let workspace = Workspace::load_from_config(&pubspec, None).unwrap();
+        assert!(!workspace.config_source.is_legacy());
+    }
+
+    #[test]
+    fn test_load_from_config_errors() {
+        let Err(err) = Workspace::load_from_config(Path::new("/nonexistent/melos.yaml"), None)
+        else {
+            panic!("expected missing config file to fail");
+        };
+        assert!(
+            err.to_string()
+                .contains("Config file '/nonexistent/melos.yaml' not found")
+        );
+
+        let dir = TempDir::new().unwrap();
+        let toml = dir.path().join("melos.toml");
+        fs::write(&toml, "name = 'x'\n").unwrap();
+        let Err(err) = Workspace::load_from_config(&toml, None) else {
+            panic!("expected unrecognized format to fail");
+        };
+        assert!(err.to_string().contains("Unrecognized config file"));
+    }
+
+    #[test]
+    fn test_reload_picks_up_config_and_package_changes() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("melos.yaml"),
+            "name: ws\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        let core = dir.path().join("packages/core");
+        fs::create_dir_all(&core).unwrap();
+        fs::write(core.join("pubspec.yaml"), "name: core\nversion: 1.0.0\n").unwrap();
+        let mut workspace = Workspace::find_and_load_from(dir.path(), None).unwrap();
+        assert_eq!(workspace.packages.len(), 1);
+
+        let app = dir.path().join("packages/app");
+        fs::create_dir_all(&app).unwrap();
+        fs::write(app.join("pubspec.yaml"), "name: app\nversion: 1.0.0\n").unwrap();
+        fs::write(
+            dir.path().join("melos.yaml"),
+            "name: renamed\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+
+        workspace.reload().unwrap();
+        assert_eq!(workspace.config.name, "renamed");
+        let names: Vec<_> = workspace.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "core"]);
+    }
+
+    #[test]
+    fn test_reload_error_keeps_previous_state() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("melos.yaml"),
+            "name: ws\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        let core = dir.path().join("packages/core");
+        fs::create_dir_all(&core).unwrap();
+        fs::write(core.join("pubspec.yaml"), "name: core\nversion: 1.0.0\n").unwrap();
+        let mut workspace = Workspace::find_and_load_from(dir.path(), None).unwrap();
+
+        fs::write(dir.path().join("melos.yaml"), "name: [broken\n").unwrap();
+        let err = workspace.reload().unwrap_err();
+        assert!(err.to_string().contains("Failed to reload"));
+        assert_eq!(workspace.config.name, "ws");
+        assert_eq!(workspace.packages.len(), 1);
+    }
 }
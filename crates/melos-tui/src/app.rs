@@ -140,6 +140,7 @@ pub enum CommandOpts {
         version_drift: bool,
         missing_fields: bool,
         sdk_consistency: bool,
+        analysis_options: bool,
     },
     Pub {
         subcommand: usize,
@@ -187,6 +188,7 @@ impl CommandOpts {
                 version_drift: true,
                 missing_fields: true,
                 sdk_consistency: true,
+                analysis_options: true,
             }),
             "pub" => Some(Self::Pub {
                 subcommand: 1,
@@ -258,10 +260,12 @@ impl CommandOpts {
                 version_drift,
                 missing_fields,
                 sdk_consistency,
+                analysis_options,
             } => vec![
                 OptionRow::Bool("version-drift", *version_drift),
                 OptionRow::Bool("missing-fields", *missing_fields),
                 OptionRow::Bool("sdk-consistency", *sdk_consistency),
+                OptionRow::Bool("analysis-options", *analysis_options),
             ],
             Self::Pub {
                 subcommand,
@@ -363,7 +367,13 @@ impl CommandOpts {
                 version_drift,
                 missing_fields,
                 sdk_consistency,
-            } => vec![version_drift, missing_fields, sdk_consistency],
+                analysis_options,
+            } => vec![
+                version_drift,
+                missing_fields,
+                sdk_consistency,
+                analysis_options,
+            ],
             Self::Pub { major_versions, .. } => vec![major_versions],
         }
     }
@@ -490,6 +500,10 @@ pub struct App {
     pub running_packages: Vec<String>,
     /// Finished package results: (name, success, duration).
     pub finished_packages: Vec<(String, bool, Duration)>,
+    /// Packages skipped without running: (name, reason).
+    pub skipped_packages: Vec<(String, String)>,
+    /// Packages killed while running (fail-fast): (name, reason).
+    pub aborted_packages: Vec<(String, String)>,
     /// Progress state: (completed, total, message).
     pub progress: Option<(usize, usize, String)>,
     /// Output log lines: (package_name, line, is_stderr).
@@ -563,6 +577,8 @@ impl App {
             running_command: None,
             running_packages: Vec::new(),
             finished_packages: Vec::new(),
+            skipped_packages: Vec::new(),
+            aborted_packages: Vec::new(),
             progress: None,
             output_log: Vec::new(),
             exec_messages: Vec::new(),
@@ -748,11 +764,11 @@ impl App {
                 (KeyCode::Char('c'), true) => self.quit = true,
                 // Health dashboard tab cycling.
                 (KeyCode::Tab, _) if self.health_report.is_some() => {
-                    self.health_tab = (self.health_tab + 1) % 3;
+                    self.health_tab = (self.health_tab + 1) % 4;
                 }
                 (KeyCode::BackTab, _) if self.health_report.is_some() => {
                     self.health_tab = if self.health_tab == 0 {
-                        2
+                        3
                     } else {
                         self.health_tab - 1
                     };
@@ -974,6 +990,8 @@ impl App {
         self.running_command = Some(name.to_string());
         self.running_packages.clear();
         self.finished_packages.clear();
+        self.skipped_packages.clear();
+        self.aborted_packages.clear();
         self.progress = None;
         self.output_log.clear();
         self.exec_messages.clear();
@@ -1008,6 +1026,29 @@ impl App {
                     *completed += 1;
                 }
             }
+            CoreEvent::PackageRetry {
+                name,
+                attempt,
+                max_attempts,
+                ..
+            } => {
+                self.exec_messages
+                    .push(format!("retry {name} {attempt}/{max_attempts}"));
+            }
+            CoreEvent::PackageSkipped { name, reason } => {
+                self.running_packages.retain(|n| n != &name);
+                self.skipped_packages.push((name, reason));
+                if let Some((completed, _, _)) = &mut self.progress {
+                    *completed += 1;
+                }
+            }
+            CoreEvent::PackageAborted { name, reason, .. } => {
+                self.running_packages.retain(|n| n != &name);
+                self.aborted_packages.push((name, reason));
+                if let Some((completed, _, _)) = &mut self.progress {
+                    *completed += 1;
+                }
+            }
             CoreEvent::PackageOutput {
                 name,
                 line,
@@ -1256,6 +1297,8 @@ impl App {
 
 #[cfg(test)]
 mod tests {
+    use melos_core::commands::health::HEALTH_SCHEMA_VERSION;
+
     use super::*;
 
     /// Helper: press a key with no modifiers.
@@ -1890,6 +1933,77 @@ mod tests {
         assert_eq!(app.progress, Some((1, 3, String::new())));
     }
 
+    #[test]
+    fn test_handle_package_skipped_populates_skipped_list() {
+        let mut app = App::new(Theme::default());
+        app.state = AppState::Running;
+        app.progress = Some((0, 2, String::new()));
+
+        app.handle_core_event(CoreEvent::PackageSkipped {
+            name: "pkg_b".to_string(),
+            reason: "deadline".to_string(),
+        });
+
+        assert_eq!(
+            app.skipped_packages,
+            vec![("pkg_b".to_string(), "deadline".to_string())]
+        );
+        assert!(app.finished_packages.is_empty());
+        assert_eq!(app.progress, Some((1, 2, String::new())));
+    }
+
+    #[test]
+    fn test_handle_package_skipped_not_counted_as_pass_or_fail() {
+        let mut app = App::new(Theme::default());
+        app.state = AppState::Running;
+        app.handle_core_event(CoreEvent::PackageFinished {
+            name: "pkg_a".to_string(),
+            success: true,
+            duration: Duration::from_millis(10),
+        });
+        app.handle_core_event(CoreEvent::PackageSkipped {
+            name: "pkg_b".to_string(),
+            reason: "fail-fast".to_string(),
+        });
+
+        let passed = app.finished_packages.iter().filter(|(_, s, _)| *s).count();
+        let failed = app.finished_packages.iter().filter(|(_, s, _)| !*s).count();
+        assert_eq!((passed, failed), (1, 0));
+        assert_eq!(app.skipped_packages.len(), 1);
+    }
+
+    #[test]
+    fn test_handle_package_aborted_leaves_running_list() {
+        let mut app = App::new(Theme::default());
+        app.state = AppState::Running;
+        app.progress = Some((0, 2, String::new()));
+        app.handle_core_event(CoreEvent::PackageStarted {
+            name: "pkg_a".to_string(),
+        });
+        app.handle_core_event(CoreEvent::PackageAborted {
+            name: "pkg_a".to_string(),
+            reason: "fail-fast".to_string(),
+            duration: Duration::from_millis(10),
+        });
+
+        assert!(app.running_packages.is_empty());
+        assert!(app.finished_packages.is_empty());
+        assert_eq!(
+            app.aborted_packages,
+            vec![("pkg_a".to_string(), "fail-fast".to_string())]
+        );
+        assert_eq!(app.progress, Some((1, 2, String::new())));
+    }
+
+    #[test]
+    fn test_start_command_clears_skipped_packages() {
+        let mut app = App::new(Theme::default());
+        app.skipped_packages
+            .push(("old".to_string(), "deadline".to_string()));
+        app.start_command("test");
+        assert!(app.skipped_packages.is_empty());
+    }
+
     #[test]
     fn test_handle_package_output_appends_to_log() {
         let mut app = App::new(Theme::default());
@@ -1933,6 +2047,19 @@ mod tests {
         assert_eq!(app.exec_messages, vec!["info msg"]);
     }
 
+    #[test]
+    fn test_handle_retry_appends_message() {
+        let mut app = App::new(Theme::default());
+        app.state = AppState::Running;
+        app.handle_core_event(CoreEvent::PackageRetry {
+            name: "pkg_a".to_string(),
+            attempt: 2,
+            max_attempts: 3,
+            delay: Duration::from_millis(500),
+        });
+        assert_eq!(app.exec_messages, vec!["retry pkg_a 2/3"]);
+    }
+
     // --- on_command_finished tests ---
 
     #[test]
@@ -2290,9 +2417,10 @@ mod tests {
                 version_drift: true,
                 missing_fields: true,
                 sdk_consistency: true,
+                analysis_options: true,
             }
         ));
-        assert_eq!(opts.option_count(), 3);
+        assert_eq!(opts.option_count(), 4);
     }
 
     #[test]
@@ -2443,19 +2571,22 @@ mod tests {
     #[test]
     fn test_health_all_bools_toggle() {
         let mut opts = CommandOpts::build_default("health").unwrap();
-        // Health has 3 bool rows at indices 0, 1, 2.
+        // Health has 4 bool rows at indices 0, 1, 2, 3.
         opts.toggle_bool(0);
         opts.toggle_bool(1);
         opts.toggle_bool(2);
+        opts.toggle_bool(3);
         match &opts {
             CommandOpts::Health {
                 version_drift,
                 missing_fields,
                 sdk_consistency,
+                analysis_options,
             } => {
                 assert!(!*version_drift);
                 assert!(!*missing_fields);
                 assert!(!*sdk_consistency);
+                assert!(!*analysis_options);
             }
             _ => panic!("wrong variant"),
         }
@@ -2969,9 +3100,12 @@ mod tests {
 
     fn make_health_report() -> HealthReport {
         HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: Some(vec![]),
             missing_fields: Some(vec![]),
             sdk_consistency: Some(melos_core::commands::health::SdkConsistencyResult::default()),
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 0,
         }
     }
@@ -3011,6 +3145,8 @@ mod tests {
         press(&mut app, KeyCode::Tab);
         assert_eq!(app.health_tab, 2);
         press(&mut app, KeyCode::Tab);
+        assert_eq!(app.health_tab, 3);
+        press(&mut app, KeyCode::Tab);
         assert_eq!(app.health_tab, 0);
     }
 
@@ -3021,6 +3157,8 @@ mod tests {
         app.health_report = Some(make_health_report());
         assert_eq!(app.health_tab, 0);
         press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.health_tab, 3);
+        press(&mut app, KeyCode::BackTab);
         assert_eq!(app.health_tab, 2);
         press(&mut app, KeyCode::BackTab);
         assert_eq!(app.health_tab, 1);
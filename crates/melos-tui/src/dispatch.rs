@@ -11,6 +11,7 @@ use melos_core::commands::pub_cmds::{PubOpts, PubSubcommand};
 use melos_core::commands::publish::PublishOpts;
 use melos_core::commands::test::TestOpts;
 use melos_core::events::Event;
+use melos_core::lock::WorkspaceLock;
 use melos_core::workspace::Workspace;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -32,6 +33,9 @@ pub struct DispatchResult {
 ///
 /// If `opts` is provided, the user-configured options from the overlay
 /// are used. Otherwise, sensible defaults are applied.
+///
+/// Commands that rewrite workspace files hold the workspace lock while they
+/// run, like their CLI counterparts.
 pub async fn dispatch_command(
     name: &str,
     workspace: &Arc<Workspace>,
@@ -39,6 +43,12 @@ pub async fn dispatch_command(
     opts: Option<CommandOpts>,
 ) -> Result<DispatchResult> {
     let packages = &workspace.packages;
+    let _lock = match name {
+        "bootstrap" | "clean" | "publish" => {
+            WorkspaceLock::acquire_unless_inherited(&workspace.root_path)?
+        }
+        _ => None,
+    };
 
     match name {
         "analyze" => {
@@ -53,12 +63,14 @@ pub async fn dispatch_command(
                     fatal_warnings,
                     fatal_infos,
                     no_fatal,
+                    json_output: false,
                 },
                 _ => AnalyzeOpts {
                     concurrency: 1,
                     fatal_warnings: false,
                     fatal_infos: false,
                     no_fatal: false,
+                    json_output: false,
                 },
             };
             let r = melos_core::commands::analyze::run(packages, workspace, &core_opts, Some(&tx))
@@ -135,6 +147,12 @@ pub async fn dispatch_command(
             })
         }
         "test" => {
+            let timeout = workspace
+                .config
+                .command
+                .as_ref()
+                .and_then(|c| c.test.as_ref())
+                .and_then(|t| t.timeout_duration());
             let core_opts = match opts {
                 Some(CommandOpts::Test {
                     concurrency,
@@ -145,6 +163,7 @@ pub async fn dispatch_command(
                 }) => TestOpts {
                     concurrency,
                     fail_fast,
+                    timeout,
                     coverage,
                     test_randomize_ordering_seed: None,
                     update_goldens,
@@ -154,6 +173,7 @@ pub async fn dispatch_command(
                 _ => TestOpts {
                     concurrency: 1,
                     fail_fast: false,
+                    timeout,
                     coverage: false,
                     test_randomize_ordering_seed: None,
                     update_goldens: false,
@@ -176,10 +196,12 @@ pub async fn dispatch_command(
                 }) => PublishOpts {
                     concurrency,
                     dry_run,
+                    tag: None,
                 },
                 _ => PublishOpts {
                     dry_run: true,
                     concurrency: 1,
+                    tag: None,
                 },
             };
             let r = melos_core::commands::publish::run(packages, workspace, &core_opts, Some(&tx))
@@ -195,19 +217,26 @@ pub async fn dispatch_command(
                     version_drift,
                     missing_fields,
                     sdk_consistency,
+                    analysis_options,
                 }) => HealthOpts {
                     version_drift,
                     missing_fields,
                     sdk_consistency,
+                    analysis_options,
                     all: false,
                     json: false,
+                    root_path: workspace.root_path.clone(),
+                    sdk_path: workspace.sdk_path.clone(),
                 },
                 _ => HealthOpts {
                     version_drift: true,
                     missing_fields: true,
                     sdk_consistency: true,
+                    analysis_options: true,
                     all: false,
                     json: false,
+                    root_path: workspace.root_path.clone(),
+                    sdk_path: workspace.sdk_path.clone(),
                 },
             };
             dispatch_health(packages, &health_opts, &tx)
@@ -306,6 +335,33 @@ fn dispatch_health(
                 sdk.missing_sdk.join(", ")
             )));
         }
+        if let Some(ref issues) = report.analysis_options {
+            for issue in issues {
+                let message = if issue.conflicts.is_empty() {
+                    format!(
+                        "analysis options of {} don't include the workspace lints",
+                        issue.package
+                    )
+                } else {
+                    let conflicts: Vec<String> =
+                        issue.conflicts.iter().map(ToString::to_string).collect();
+                    format!(
+                        "analysis options of {} override the workspace lints: {}",
+                        issue.package,
+                        conflicts.join(", ")
+                    )
+                };
+                let _ = tx.send(Event::Warning(message));
+            }
+        }
+        if let Some(ref sdk) = report.sdk_version {
+            for issue in &sdk.incompatible {
+                let _ = tx.send(Event::Warning(format!(
+                    "{} {} constraint {} excludes the resolved sdk",
+                    issue.package, issue.sdk, issue.constraint
+                )));
+            }
+        }
     }
 
     let duration = start.elapsed();
@@ -315,9 +371,40 @@ fn dispatch_health(
     });
 
     Ok(DispatchResult {
-        package_results: PackageResults {
-            results: Vec::new(),
-        },
+        package_results: PackageResults::default(),
         health_report: Some(report),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mutating_commands_take_the_workspace_lock() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("melos.yaml"),
+            "name: tui_ws\npackages:\n  - packages/**\n",
+        )
+        .unwrap();
+        let workspace = Arc::new(Workspace::find_and_load_from(dir.path(), None).unwrap());
+
+        let held = WorkspaceLock::acquire(&workspace.root_path).unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let Err(err) = dispatch_command("clean", &workspace, tx, None).await else {
+            panic!("clean should fail while the lock is held");
+        };
+        assert!(err.to_string().contains("Another melos-rs process"));
+
+        drop(held);
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        assert!(
+            dispatch_command("clean", &workspace, tx, None)
+                .await
+                .is_ok()
+        );
+        // The lock is released once the command finishes
+        assert!(WorkspaceLock::acquire(&workspace.root_path).is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use anyhow::{Context, Result};
 use clap::Parser;
@@ -36,6 +37,11 @@ struct Cli {
     #[arg(long, value_name = "DIR")]
     workspace: Option<PathBuf>,
 
+    /// Load this config file instead of searching for one; its directory is
+    /// the workspace root.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
     /// Color theme to use.
     ///
     /// Available themes: dark, light, catppuccin-mocha, catppuccin-latte,
@@ -44,6 +50,19 @@ struct Cli {
     /// solarized-dark, solarized-light, tokyo-night, tokyo-night-light.
     #[arg(long, value_name = "NAME", default_value = "dark")]
     theme: String,
+
+    /// Load colors from a JSON theme file instead; takes precedence over
+    /// `--theme`.
+    #[arg(long, value_name = "PATH")]
+    theme_file: Option<PathBuf>,
+
+    /// Replace matches of this regex in command output with `***` (can be repeated).
+    #[arg(long, value_name = "REGEX")]
+    redact: Vec<String>,
+
+    /// Redact common token shapes by preset name (comma-separated: aws, github).
+    #[arg(long, value_delimiter = ',', value_name = "PRESETS")]
+    redact_preset: Vec<String>,
 }
 
 #[tokio::main]
@@ -53,7 +72,7 @@ async fn main() -> Result<()> {
 
     info!("melos-tui starting");
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
 
     // Respect NO_COLOR / TERM=dumb: TUI requires a capable terminal.
     if std::env::var_os("NO_COLOR").is_some() {
@@ -69,6 +88,21 @@ async fn main() -> Result<()> {
         );
     }
 
+    // Bad redaction patterns fail before anything runs.
+    let redactor = melos_core::redact::Redactor::new(&cli.redact, &cli.redact_preset)?;
+    if !redactor.is_empty() {
+        melos_core::redact::install(redactor);
+    }
+
+    // Resolve --config against the original directory before --workspace
+    // changes it.
+    if let Some(ref path) = cli.config {
+        cli.config = Some(
+            std::path::absolute(path)
+                .with_context(|| format!("Failed to resolve config path: {}", path.display()))?,
+        );
+    }
+
     // If --workspace is provided, change to that directory first.
     if let Some(ref ws_path) = cli.workspace {
         std::env::set_current_dir(ws_path).with_context(|| {
@@ -88,22 +122,26 @@ async fn main() -> Result<()> {
     }));
 
     // Try to load the workspace before entering raw mode so errors print normally.
-    let workspace = load_workspace();
+    let workspace = load_workspace(cli.config.as_deref());
 
-    // Resolve theme by name (fall back to default dark if unknown).
+    // Resolve theme by file or name (fall back to default dark if unknown).
+    // A bad theme file fails here, before raw mode.
     let theme_index = Theme::available_names()
         .iter()
         .position(|&n| n == cli.theme)
         .unwrap_or(0);
-    let theme = Theme::by_name(&cli.theme).unwrap_or_else(|| {
-        eprintln!(
-            "Unknown theme '{}'. Available: {}",
-            cli.theme,
-            Theme::available_names().join(", ")
-        );
-        eprintln!("Falling back to 'dark' theme.");
-        Theme::default()
-    });
+    let theme = match cli.theme_file {
+        Some(ref path) => Theme::from_file(path)?,
+        None => Theme::by_name(&cli.theme).unwrap_or_else(|| {
+            eprintln!(
+                "Unknown theme '{}'. Available: {}",
+                cli.theme,
+                Theme::available_names().join(", ")
+            );
+            eprintln!("Falling back to 'dark' theme.");
+            Theme::default()
+        }),
+    };
 
     // Set up terminal.
     enable_raw_mode()?;
@@ -129,8 +167,12 @@ async fn main() -> Result<()> {
 }
 
 /// Load the workspace, returning Ok(Workspace) or an error message.
-fn load_workspace() -> Result<Workspace> {
-    let result = Workspace::find_and_load(None).context("Failed to load workspace");
+fn load_workspace(config: Option<&Path>) -> Result<Workspace> {
+    let result = match config {
+        Some(path) => Workspace::load_from_config(path, None),
+        None => Workspace::find_and_load(None),
+    }
+    .context("Failed to load workspace");
     match &result {
         Ok(ws) => info!(
             name = ws.config.name,
@@ -155,7 +197,7 @@ async fn run(
     app.theme_index = theme_index;
 
     // Wrap workspace in Arc for sharing with spawned command tasks.
-    let workspace = match workspace {
+    let mut workspace = match workspace {
         Ok(ws) => {
             app.load_workspace(&ws);
             Some(Arc::new(ws))
@@ -166,6 +208,9 @@ async fn run(
         }
     };
 
+    // Config file timestamps, polled on tick to reload the workspace on edits.
+    let mut last_stamp = workspace.as_deref().map(config_stamp);
+
     // Set page size from terminal height (body area minus header, footer, table border, header row).
     let term_height = terminal.size()?.height;
     app.update_page_size(term_height);
@@ -273,7 +318,28 @@ async fn run(
             }
 
             _ = tick.tick() => {
-                // Periodic redraw (handled by loop top).
+                // Periodic redraw (handled by loop top). While no command
+                // holds the workspace, reload it when its config changed.
+                if app.state != app::AppState::Running
+                    && let Some(ws) = workspace.as_mut()
+                {
+                    let stamp = config_stamp(ws);
+                    if last_stamp.as_ref() != Some(&stamp)
+                        && let Some(ws) = Arc::get_mut(ws)
+                    {
+                        last_stamp = Some(stamp);
+                        match ws.reload() {
+                            Ok(()) => {
+                                info!(packages = ws.packages.len(), "workspace reloaded");
+                                app.load_workspace(ws);
+                            }
+                            Err(e) => {
+                                warn!("workspace reload failed: {e:#}");
+                                app.warnings.push(format!("Workspace reload failed: {e:#}"));
+                            }
+                        }
+                    }
+                }
             }
         }
 
@@ -330,6 +396,15 @@ async fn run(
     Ok(())
 }
 
+/// Modification times of the workspace config and root `pubspec.yaml`.
+fn config_stamp(workspace: &Workspace) -> Vec<Option<SystemTime>> {
+    let mtime = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    vec![
+        mtime(workspace.config_source.path()),
+        mtime(&workspace.root_path.join("pubspec.yaml")),
+    ]
+}
+
 /// Restore terminal to normal mode.
 fn restore_terminal() -> Result<()> {
     disable_raw_mode()?;
@@ -1,6 +1,8 @@
+use anyhow::{Context, Result, bail};
 use ratatui::style::Color;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::Path;
 
 // Bundled theme JSON files, embedded at compile time.
 const THEME_DEFAULT_DARK: &str = include_str!("../themes/default-dark.json");
@@ -24,6 +26,29 @@ struct ThemeFile {
     themes: Vec<ThemeVariant>,
 }
 
+/// A user theme file for `--theme-file`: either the bundled format above
+/// (the first variant is used) or a flat map of role names to colors.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum UserThemeFile {
+    Bundled(ThemeFile),
+    Flat(HashMap<String, String>),
+}
+
+/// Roles a user theme file must define; `pkg_color_0`..`pkg_color_9` are
+/// optional and fall back to the default palette.
+const REQUIRED_ROLES: &[&str] = &[
+    "accent",
+    "text",
+    "text_muted",
+    "text_secondary",
+    "header",
+    "success",
+    "error",
+    "highlight_bg",
+    "highlight_fg",
+];
+
 /// A single theme variant within a theme file.
 #[derive(Debug, Deserialize)]
 struct ThemeVariant {
@@ -142,6 +167,61 @@ impl Theme {
         Some(Self::from_variant(variant))
     }
 
+    /// Load a user-defined theme from a JSON file.
+    ///
+    /// The file maps semantic roles (see [`REQUIRED_ROLES`]) to `#RRGGBB` or
+    /// `#RRGGBBAA` colors, either at the top level or in the `colors` of the
+    /// first variant of a bundled-format file. Unlike the bundled themes,
+    /// missing roles and malformed colors are errors.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme file {}", path.display()))?;
+        let file: UserThemeFile = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse theme file {}", path.display()))?;
+        let colors = match file {
+            UserThemeFile::Bundled(file) => match file.themes.into_iter().next() {
+                Some(variant) => variant.colors,
+                None => bail!("Theme file {} has no themes", path.display()),
+            },
+            UserThemeFile::Flat(colors) => colors,
+        };
+        Self::from_colors(colors).with_context(|| format!("Invalid theme file {}", path.display()))
+    }
+
+    /// Build a `Theme` from a role-to-color map, validating every color.
+    fn from_colors(colors: HashMap<String, String>) -> Result<Self> {
+        let missing: Vec<&str> = REQUIRED_ROLES
+            .iter()
+            .copied()
+            .filter(|role| !colors.contains_key(*role))
+            .collect();
+        if !missing.is_empty() {
+            bail!("missing required role(s): {}", missing.join(", "));
+        }
+        for (role, value) in &colors {
+            if parse_hex_color(value).is_none() {
+                bail!("invalid hex color `{}` for `{}`", value, role);
+            }
+        }
+
+        let defaults = Self::default();
+        let mut pkg_colors = defaults.pkg_colors;
+        for (i, slot) in pkg_colors.iter_mut().enumerate() {
+            if let Some(value) = colors.get(&format!("pkg_color_{i}")) {
+                *slot = parse_color(value);
+            }
+        }
+        let variant = ThemeVariant {
+            name: String::new(),
+            mode: String::new(),
+            colors,
+        };
+        Ok(Self {
+            pkg_colors,
+            ..Self::from_variant(&variant)
+        })
+    }
+
     /// List all available built-in theme names.
     pub fn available_names() -> &'static [&'static str] {
         &[
@@ -205,6 +285,15 @@ impl Theme {
     }
 }
 
+/// Parse a strict `#RRGGBB` or `#RRGGBBAA` color (alpha is ignored).
+fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(parse_color(value))
+}
+
 /// Parse a hex color string into a ratatui `Color`.
 ///
 /// Supports `#RRGGBB` and `#RRGGBBAA` formats (alpha is ignored).
@@ -482,6 +571,66 @@ mod tests {
         assert_eq!(t.text, Color::Rgb(170, 187, 204));
     }
 
+    // --- Theme::from_file tests ---
+
+    const FLAT_THEME: &str = r##"{
+        "accent": "#112233", "text": "#FFFFFF", "text_muted": "#666666",
+        "text_secondary": "#808080", "header": "#FFFF00", "success": "#00FF00",
+        "error": "#FF0000", "highlight_bg": "#3A3A3A", "highlight_fg": "#FFFFFFFF",
+        "pkg_color_0": "#ABCDEF"
+    }"##;
+
+    #[test]
+    fn test_from_file_flat_roles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mine.json");
+        std::fs::write(&path, FLAT_THEME).unwrap();
+
+        let t = Theme::from_file(&path).unwrap();
+        assert_eq!(t.accent, Color::Rgb(0x11, 0x22, 0x33));
+        assert_eq!(t.highlight_fg, Color::Rgb(255, 255, 255));
+        assert_eq!(t.pkg_colors[0], Color::Rgb(0xAB, 0xCD, 0xEF));
+        // Unset package colors keep the default palette
+        assert_eq!(t.pkg_colors[1], Theme::default().pkg_colors[1]);
+    }
+
+    #[test]
+    fn test_from_file_bundled_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundled.json");
+        std::fs::write(&path, THEME_FFE).unwrap();
+
+        let t = Theme::from_file(&path).unwrap();
+        assert_eq!(t.accent, Color::Rgb(79, 214, 190));
+    }
+
+    #[test]
+    fn test_from_file_rejects_invalid_hex_and_missing_roles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.json");
+
+        std::fs::write(&path, FLAT_THEME.replace("#112233", "#11223")).unwrap();
+        let err = format!("{:#}", Theme::from_file(&path).unwrap_err());
+        assert!(
+            err.contains("invalid hex color `#11223` for `accent`"),
+            "{err}"
+        );
+
+        std::fs::write(&path, FLAT_THEME.replace("#FF0000", "red")).unwrap();
+        let err = format!("{:#}", Theme::from_file(&path).unwrap_err());
+        assert!(err.contains("invalid hex color `red` for `error`"), "{err}");
+
+        std::fs::write(&path, r##"{"accent": "#112233"}"##).unwrap();
+        let err = format!("{:#}", Theme::from_file(&path).unwrap_err());
+        assert!(
+            err.contains("missing required role(s): text, text_muted"),
+            "{err}"
+        );
+
+        let err = Theme::from_file(&dir.path().join("missing.json")).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read theme file"));
+    }
+
     // --- pkg_colors tests ---
 
     #[test]
@@ -6,15 +6,20 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use melos_core::commands::health::HealthReport;
+use melos_core::commands::health::{AnalysisOptionsProblem, HealthReport, SdkVersionResult};
 
 use crate::app::App;
 use crate::theme::Theme;
 
 /// Tab labels for the health dashboard.
-const TAB_LABELS: &[&str] = &["Version Drift", "Missing Fields", "SDK Consistency"];
-
-/// Draw the health dashboard with three tabs.
+const TAB_LABELS: &[&str] = &[
+    "Version Drift",
+    "Missing Fields",
+    "SDK Consistency",
+    "Analysis Options",
+];
+
+/// Draw the health dashboard with four tabs.
 ///
 /// The active tab is determined by `app.health_tab`. Tab/BackTab cycle through
 /// the tabs in the Done state key handler.
@@ -38,11 +43,12 @@ pub fn draw_health(frame: &mut Frame, area: Rect, app: &App) {
     match app.health_tab {
         0 => draw_version_drift(frame, content_area, report, theme),
         1 => draw_missing_fields(frame, content_area, report, theme),
-        _ => draw_sdk_consistency(frame, content_area, report, theme),
+        2 => draw_sdk_consistency(frame, content_area, report, theme),
+        _ => draw_analysis_options(frame, content_area, report, theme),
     }
 }
 
-/// Render the tab bar with three tabs, highlighting the active one.
+/// Render the tab bar, highlighting the active tab.
 fn draw_tab_bar(frame: &mut Frame, area: Rect, active: usize, theme: &Theme) {
     let mut spans = Vec::new();
     for (i, label) in TAB_LABELS.iter().enumerate() {
@@ -248,6 +254,10 @@ fn draw_sdk_consistency(frame: &mut Frame, area: Rect, report: &HealthReport, th
         }
     }
 
+    if let Some(version) = &report.sdk_version {
+        draw_sdk_version_lines(&mut lines, version, theme);
+    }
+
     let block = Block::default()
         .borders(Borders::TOP)
         .title(" SDK Consistency ");
@@ -257,10 +267,136 @@ fn draw_sdk_consistency(frame: &mut Frame, area: Rect, report: &HealthReport, th
     frame.render_widget(paragraph, area);
 }
 
+/// Render the Analysis Options tab content.
+fn draw_analysis_options(frame: &mut Frame, area: Rect, report: &HealthReport, theme: &Theme) {
+    let mut lines: Vec<Line<'_>> = Vec::new();
+
+    match &report.analysis_options {
+        None => {
+            lines.push(Line::from(Span::styled(
+                "Analysis options check was not enabled.",
+                Style::default().fg(theme.text_muted),
+            )));
+        }
+        Some(issues) if issues.is_empty() => {
+            lines.push(Line::from(Span::styled(
+                "All packages build on the workspace lints.",
+                Style::default().fg(theme.success),
+            )));
+        }
+        Some(issues) => {
+            lines.push(Line::from(Span::styled(
+                format!("{} packages not using the workspace lints:", issues.len()),
+                Style::default()
+                    .fg(theme.header)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(""));
+
+            for issue in issues {
+                let problem = match issue.problem {
+                    AnalysisOptionsProblem::Missing => "no analysis_options.yaml".to_string(),
+                    AnalysisOptionsProblem::NoInclude => "no include".to_string(),
+                    AnalysisOptionsProblem::ForeignInclude => {
+                        format!("includes {}", issue.include.join(", "))
+                    }
+                    AnalysisOptionsProblem::ConflictingRules => {
+                        "overrides workspace lints".to_string()
+                    }
+                };
+                let mut spans = vec![
+                    Span::styled(
+                        format!("  {}: ", issue.package),
+                        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(problem, Style::default().fg(theme.error)),
+                ];
+                if let Some(ref file) = issue.file {
+                    spans.push(Span::styled(
+                        format!("  ({file})"),
+                        Style::default().fg(theme.text_muted),
+                    ));
+                }
+                lines.push(Line::from(spans));
+                for conflict in &issue.conflicts {
+                    lines.push(Line::from(Span::styled(
+                        format!("    {conflict}"),
+                        Style::default().fg(theme.text_muted),
+                    )));
+                }
+            }
+        }
+    }
+
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .title(" Analysis Options ");
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// Append the resolved SDK versions and incompatible constraints.
+fn draw_sdk_version_lines<'a>(
+    lines: &mut Vec<Line<'a>>,
+    version: &'a SdkVersionResult,
+    theme: &Theme,
+) {
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+
+    let mut resolved = format!("Dart {}", version.dart.as_deref().unwrap_or("unknown"));
+    if let Some(ref flutter) = version.flutter {
+        resolved.push_str(&format!(", Flutter {flutter}"));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            "Resolved SDK: ",
+            Style::default()
+                .fg(theme.header)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(resolved, Style::default().fg(theme.accent)),
+    ]));
+
+    if version.incompatible.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "All SDK constraints allow the resolved SDK.",
+            Style::default().fg(theme.success),
+        )));
+        return;
+    }
+
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} constraints exclude the resolved SDK:",
+            version.incompatible.len()
+        ),
+        Style::default()
+            .fg(theme.header)
+            .add_modifier(Modifier::BOLD),
+    )));
+    for issue in &version.incompatible {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {} ", issue.package),
+                Style::default().fg(theme.error),
+            ),
+            Span::styled(
+                format!("{}: {}", issue.sdk, issue.constraint),
+                Style::default().fg(theme.text_muted),
+            ),
+        ]));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use melos_core::commands::health::{
-        ConstraintUsage, MissingFieldsIssue, SdkConsistencyResult, VersionDriftIssue,
+        AnalysisOptionsIssue, ConstraintUsage, HEALTH_SCHEMA_VERSION, LintConflict,
+        MissingFieldsIssue, SdkConsistencyResult, SdkIncompatibility, VersionDriftIssue,
     };
     use ratatui::{Terminal, backend::TestBackend};
 
@@ -299,9 +435,12 @@ mod tests {
 
     fn make_clean_report() -> HealthReport {
         HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: Some(vec![]),
             missing_fields: Some(vec![]),
             sdk_consistency: Some(SdkConsistencyResult::default()),
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 0,
         }
     }
@@ -362,6 +501,7 @@ mod tests {
     #[test]
     fn test_health_version_drift_with_issues() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: Some(vec![VersionDriftIssue {
                 dependency: "http".to_string(),
                 constraints: vec![
@@ -377,6 +517,8 @@ mod tests {
             }]),
             missing_fields: None,
             sdk_consistency: None,
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 1,
         };
         let app = app_with_health(report, 0);
@@ -410,12 +552,15 @@ mod tests {
     #[test]
     fn test_health_missing_fields_with_issues() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: None,
             missing_fields: Some(vec![MissingFieldsIssue {
                 package: "my_pkg".to_string(),
                 missing: vec!["description".to_string(), "homepage".to_string()],
             }]),
             sdk_consistency: None,
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 1,
         };
         let app = app_with_health(report, 1);
@@ -449,6 +594,7 @@ mod tests {
     #[test]
     fn test_health_sdk_missing_packages() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: None,
             missing_fields: None,
             sdk_consistency: Some(SdkConsistencyResult {
@@ -456,6 +602,8 @@ mod tests {
                 dart_sdk_drift: vec![],
                 flutter_sdk_drift: vec![],
             }),
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 1,
         };
         let app = app_with_health(report, 2);
@@ -470,6 +618,7 @@ mod tests {
     #[test]
     fn test_health_sdk_dart_drift() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: None,
             missing_fields: None,
             sdk_consistency: Some(SdkConsistencyResult {
@@ -480,6 +629,8 @@ mod tests {
                 }],
                 flutter_sdk_drift: vec![],
             }),
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 1,
         };
         let app = app_with_health(report, 2);
@@ -495,12 +646,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_health_sdk_version_and_incompatible_constraints() {
+        let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
+            version_drift: None,
+            missing_fields: None,
+            sdk_consistency: Some(SdkConsistencyResult::default()),
+            sdk_version: Some(SdkVersionResult {
+                dart: Some("3.5.0".to_string()),
+                flutter: Some("3.24.0".to_string()),
+                incompatible: vec![SdkIncompatibility {
+                    package: "pkg_new".to_string(),
+                    sdk: "dart".to_string(),
+                    constraint: "^3.6.0".to_string(),
+                }],
+            }),
+            analysis_options: None,
+            total_issues: 1,
+        };
+        let app = app_with_health(report, 2);
+        let buf = render_frame(draw_health, &app, 80, 20);
+        let text = buffer_text(&buf, 80, 20);
+        assert!(
+            text.contains("Resolved SDK: Dart 3.5.0, Flutter 3.24.0"),
+            "Expected resolved SDK versions, got:\n{text}"
+        );
+        assert!(
+            text.contains("pkg_new dart: ^3.6.0"),
+            "Expected incompatible constraint, got:\n{text}"
+        );
+    }
+
+    #[test]
+    fn test_health_analysis_options_with_issues() {
+        let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
+            version_drift: None,
+            missing_fields: None,
+            sdk_consistency: None,
+            sdk_version: None,
+            analysis_options: Some(vec![
+                AnalysisOptionsIssue {
+                    package: "pkg_own".to_string(),
+                    problem: AnalysisOptionsProblem::ForeignInclude,
+                    file: Some("packages/pkg_own/analysis_options.yaml".to_string()),
+                    include: vec!["package:lints/core.yaml".to_string()],
+                    conflicts: vec![],
+                },
+                AnalysisOptionsIssue {
+                    package: "pkg_bare".to_string(),
+                    problem: AnalysisOptionsProblem::NoInclude,
+                    file: Some("packages/pkg_bare/analysis_options.yaml".to_string()),
+                    include: vec![],
+                    conflicts: vec![],
+                },
+                AnalysisOptionsIssue {
+                    package: "pkg_loose".to_string(),
+                    problem: AnalysisOptionsProblem::ConflictingRules,
+                    file: Some("packages/pkg_loose/analysis_options.yaml".to_string()),
+                    include: vec!["../../analysis_options.yaml".to_string()],
+                    conflicts: vec![LintConflict {
+                        setting: "linter.rules.avoid_print".to_string(),
+                        value: "false".to_string(),
+                        baseline: None,
+                    }],
+                },
+            ]),
+            total_issues: 3,
+        };
+        let app = app_with_health(report, 3);
+        let buf = render_frame(draw_health, &app, 100, 20);
+        let text = buffer_text(&buf, 100, 20);
+        assert!(
+            text.contains("pkg_own: includes package:lints/core.yaml"),
+            "Expected foreign include, got:\n{text}"
+        );
+        assert!(
+            text.contains("pkg_bare: no include"),
+            "Expected missing include, got:\n{text}"
+        );
+        assert!(
+            text.contains("linter.rules.avoid_print: false (workspace: unset)"),
+            "Expected conflicting rule, got:\n{text}"
+        );
+
+        let app = app_with_health(make_clean_report(), 3);
+        let buf = render_frame(draw_health, &app, 80, 20);
+        let text = buffer_text(&buf, 80, 20);
+        assert!(
+            text.contains("not enabled"),
+            "Expected disabled message, got:\n{text}"
+        );
+    }
+
     #[test]
     fn test_health_disabled_check_shows_message() {
         let report = HealthReport {
+            schema_version: HEALTH_SCHEMA_VERSION,
             version_drift: None,
             missing_fields: None,
             sdk_consistency: None,
+            sdk_version: None,
+            analysis_options: None,
             total_issues: 0,
         };
         let app = app_with_health(report, 0);
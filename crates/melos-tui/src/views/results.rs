@@ -18,7 +18,7 @@ pub fn draw_results(frame: &mut Frame, area: Rect, app: &App) {
     let failed = app.finished_packages.iter().filter(|(_, s, _)| !*s).count();
 
     let cmd_name = app.running_command.as_deref().unwrap_or("command");
-    let has_error = failed > 0 || app.command_error.is_some();
+    let has_error = failed > 0 || !app.aborted_packages.is_empty() || app.command_error.is_some();
 
     // Outer border matching the two-pane panels.
     let border_color = if has_error {
@@ -43,8 +43,18 @@ pub fn draw_results(frame: &mut Frame, area: Rect, app: &App) {
         theme.success
     };
 
+    let skipped = if app.skipped_packages.is_empty() {
+        String::new()
+    } else {
+        format!(", {} skipped", app.skipped_packages.len())
+    };
+    let aborted = if app.aborted_packages.is_empty() {
+        String::new()
+    } else {
+        format!(", {} aborted", app.aborted_packages.len())
+    };
     summary_lines.push(Line::from(Span::styled(
-        format!("{cmd_name}: {passed} passed, {failed} failed"),
+        format!("{cmd_name}: {passed} passed, {failed} failed{aborted}{skipped}"),
         Style::default()
             .fg(header_color)
             .add_modifier(Modifier::BOLD),
@@ -83,6 +93,45 @@ pub fn draw_results(frame: &mut Frame, area: Rect, app: &App) {
         }
     }
 
+    // Aborted packages: "!" + name + reason.
+    if !app.aborted_packages.is_empty() {
+        if app.finished_packages.is_empty() {
+            summary_lines.push(Line::from(""));
+        }
+        for (name, reason) in &app.aborted_packages {
+            summary_lines.push(Line::from(vec![
+                Span::styled(
+                    " ! ",
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(name.as_str()),
+                Span::styled(
+                    format!("  (aborted: {reason})"),
+                    Style::default().fg(theme.text_muted),
+                ),
+            ]));
+        }
+    }
+
+    // Skipped packages: dimmed "-" + name + reason.
+    if !app.skipped_packages.is_empty() {
+        if app.finished_packages.is_empty() && app.aborted_packages.is_empty() {
+            summary_lines.push(Line::from(""));
+        }
+        for (name, reason) in &app.skipped_packages {
+            summary_lines.push(Line::from(vec![
+                Span::styled(" - ", Style::default().fg(theme.text_muted)),
+                Span::styled(name.as_str(), Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    format!("  (skipped: {reason})"),
+                    Style::default().fg(theme.text_muted),
+                ),
+            ]));
+        }
+    }
+
     // Blank separator before output log.
     summary_lines.push(Line::from(""));
 
@@ -220,6 +269,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_results_shows_skipped_packages() {
+        let mut app = App::new(Theme::default());
+        app.state = AppState::Done;
+        app.running_command = Some("test".to_string());
+        app.finished_packages = vec![("pkg_a".to_string(), true, Duration::from_millis(100))];
+        app.skipped_packages = vec![("pkg_b".to_string(), "no test/".to_string())];
+
+        let buf = render_frame(draw_results, &app, 80, 20);
+        let text = buffer_text(&buf, 80, 20);
+        assert!(
+            text.contains("1 passed, 0 failed, 1 skipped"),
+            "Expected skipped count in summary, got:\n{text}"
+        );
+        assert!(
+            text.contains("pkg_b") && text.contains("skipped: no test/"),
+            "Expected skipped package with reason, got:\n{text}"
+        );
+    }
+
     #[test]
     fn test_results_shows_output_log() {
         let mut app = App::new(Theme::default());